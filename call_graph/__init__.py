@@ -1,6 +1,33 @@
 """
 Call Graph Analyzer - 多语言函数调用关系分析工具
+
+库接口::
+
+    import call_graph
+
+    graph = call_graph.analyze("/path/to/project")
+    for node in graph.lookup("db::save"):
+        callers = [graph.node(i) for i in graph.predecessors(node.id)]
 """
 
+from .algorithms import condensation, dominator_tree
+from .api import AnalysisBuilder, AnalysisConfig, analyze, load
+from .graph import CallGraph, CompactGraph, Edge, EdgeKind, Node
+from .pruning import prune
+
 __version__ = "0.1.0"
 
+__all__ = [
+    "AnalysisBuilder",
+    "AnalysisConfig",
+    "CallGraph",
+    "CompactGraph",
+    "Edge",
+    "EdgeKind",
+    "Node",
+    "analyze",
+    "condensation",
+    "dominator_tree",
+    "load",
+    "prune",
+]
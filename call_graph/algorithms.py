@@ -0,0 +1,637 @@
+"""
+调用图算法
+强连通分量、递归环检测、缩点、可达性分析、调用路径枚举、支配树等
+"""
+
+import fnmatch
+import os
+from collections import deque
+from typing import Dict, Iterable, List, Optional, Set, Union
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph, attribute_name
+except ImportError:
+    from graph import CallGraph, attribute_name
+
+# 缩点后分量节点名称中列出的成员数量
+CONDENSED_NAMES = 3
+
+
+def strongly_connected_components(graph: CallGraph) -> List[List[str]]:
+    """
+    Tarjan 算法计算强连通分量（迭代实现，避免大图递归过深；
+    在 CSR 表示的整数编号上计算）
+
+    Returns:
+        强连通分量列表，每个分量是节点 ID 列表；分量按逆拓扑序排列
+        （被调用者所在的分量在前）
+    """
+    compact = graph.compact()
+    count = len(compact)
+    index_of = [-1] * count
+    lowlink = [0] * count
+    on_stack = bytearray(count)
+    stack: List[int] = []
+    components: List[List[str]] = []
+    counter = 0
+
+    for start in range(count):
+        if index_of[start] >= 0:
+            continue
+
+        # 每个栈帧: (节点, 后继迭代器)
+        work = [(start, iter(compact.successors(start)))]
+        index_of[start] = lowlink[start] = counter
+        counter += 1
+        stack.append(start)
+        on_stack[start] = 1
+
+        while work:
+            node, successors = work[-1]
+            advanced = False
+            for succ in successors:
+                if index_of[succ] < 0:
+                    index_of[succ] = lowlink[succ] = counter
+                    counter += 1
+                    stack.append(succ)
+                    on_stack[succ] = 1
+                    work.append((succ, iter(compact.successors(succ))))
+                    advanced = True
+                    break
+                if on_stack[succ]:
+                    lowlink[node] = min(lowlink[node], index_of[succ])
+            if advanced:
+                continue
+
+            work.pop()
+            if work:
+                parent = work[-1][0]
+                lowlink[parent] = min(lowlink[parent], lowlink[node])
+
+            if lowlink[node] == index_of[node]:
+                component = []
+                while True:
+                    member = stack.pop()
+                    on_stack[member] = 0
+                    component.append(compact.ids[member])
+                    if member == node:
+                        break
+                components.append(component)
+
+    return components
+
+
+def condensation(graph: CallGraph) -> CallGraph:
+    """
+    把每个强连通分量（相互递归的函数组）缩成一个节点，得到无环图
+
+    单个函数的分量保留原节点（去掉自调用边）；多个函数的分量变为 kind 为
+    component 的节点，ID 为 scc:<第一个成员ID>，名称列出前几个成员，
+    extras.members 为成员 ID，extras.functions 为包含的函数数量（模块级
+    视图中为各模块函数数量之和）。分量之间的每个调用点都保留
+    """
+    condensed = CallGraph()
+    condensed.root = graph.root
+    component_of: Dict[str, str] = {}
+    # Tarjan 算法按逆拓扑序给出分量，反转后调用者在前
+    for component in reversed(strongly_connected_components(graph)):
+        if len(component) == 1:
+            node_id = component[0]
+            component_of[node_id] = node_id
+            condensed.add_node(graph.nodes[node_id])
+            continue
+
+        members = sorted(
+            component,
+            key=lambda n: (
+                graph.nodes[n]["name"],
+                graph.nodes[n]["file"] or "",
+                graph.nodes[n].get("start_line") or 0,
+            ),
+        )
+        names = [graph.nodes[n]["name"] for n in members]
+        shown = ", ".join(names[:CONDENSED_NAMES])
+        if len(names) > CONDENSED_NAMES:
+            shown += ", …"
+        first = graph.nodes[members[0]]
+        component_id = f"scc:{members[0]}"
+        condensed.add_node(
+            {
+                "id": component_id,
+                "name": f"{{{shown}}}",
+                "kind": "component",
+                "file": first["file"],
+                "start_line": first.get("start_line"),
+                "end_line": None,
+                "language": first.get("language"),
+                "is_exported": int(
+                    any(graph.nodes[n].get("is_exported") for n in members)
+                ),
+                "extras": {
+                    "members": members,
+                    "functions": sum(
+                        (graph.nodes[n].get("extras") or {}).get("functions", 1)
+                        for n in members
+                    ),
+                    "crate": (first.get("extras") or {}).get("crate"),
+                },
+            }
+        )
+        for node_id in members:
+            component_of[node_id] = component_id
+
+    for relation in graph.relations:
+        caller = component_of.get(relation["caller_id"])
+        callee = component_of.get(relation["callee_id"])
+        if caller and callee and caller != callee:
+            condensed.add_relation(
+                {**relation, "caller_id": caller, "callee_id": callee}
+            )
+    return condensed
+
+
+def find_cycle_path(graph: CallGraph, members: List[str]) -> Optional[List[str]]:
+    """
+    在强连通分量内找一条从第一个成员出发回到自身的最短环路
+
+    Returns:
+        环路节点列表（首尾相同），不存在时返回 None
+    """
+    start = members[0]
+    allowed = set(members)
+    parent: Dict[str, str] = {}
+    queue = deque([start])
+    visited = set()
+
+    while queue:
+        node = queue.popleft()
+        for succ in graph.successors(node):
+            if succ not in allowed:
+                continue
+            if succ == start:
+                path = [node]
+                while path[-1] != start:
+                    path.append(parent[path[-1]])
+                path.reverse()
+                return path + [start]
+            if succ not in visited:
+                visited.add(succ)
+                parent[succ] = node
+                queue.append(succ)
+    return None
+
+
+def find_recursion_cycles(graph: CallGraph) -> List[Dict[str, object]]:
+    """
+    查找所有递归环：直接递归（自调用）和相互递归的函数组
+
+    Returns:
+        每项包含 members（按名称排序的节点 ID）、kind（direct/mutual）
+        和 cycle（一条示例环路）；按函数数量降序排列
+    """
+    cycles = []
+    for component in strongly_connected_components(graph):
+        if len(component) == 1:
+            node_id = component[0]
+            if node_id not in graph.successors(node_id):
+                continue
+            kind = "direct"
+        else:
+            kind = "mutual"
+
+        members = sorted(
+            component,
+            key=lambda n: (graph.nodes[n]["name"], graph.nodes[n]["file"]),
+        )
+        cycles.append(
+            {
+                "members": members,
+                "kind": kind,
+                "cycle": find_cycle_path(graph, members),
+            }
+        )
+
+    cycles.sort(
+        key=lambda c: (-len(c["members"]), graph.nodes[c["members"][0]]["name"])
+    )
+    return cycles
+
+
+# 视为入口的函数属性（Rust 属性 / Java 注解 / Python 装饰器，去掉参数后比较）
+ENTRY_ATTRIBUTES = {
+    "test": "test",
+    "bench": "test",
+    "Test": "test",
+    "ParameterizedTest": "test",
+    "no_mangle": "no_mangle",
+    "export_name": "no_mangle",
+    "proc_macro": "proc_macro",
+    "proc_macro_derive": "proc_macro",
+    "proc_macro_attribute": "proc_macro",
+}
+
+
+def entry_point_reason(graph: CallGraph, node_id: str) -> Optional[str]:
+    """
+    判断函数是否为默认入口点，返回原因（见 ENTRY_REASONS），否则返回 None
+
+    - main 函数（包括 #[tokio::main] 等异步运行时入口、Cargo 二进制目标）和
+      Python 模块顶层代码
+    - 测试函数：#[test]/#[bench]、Java @Test、Python 的 test_*、
+      Go 的 TestXxx/BenchmarkXxx（位于 *_test.go）
+    - 导出给外部调用的函数：#[no_mangle]、#[export_name]，以及按外部 ABI
+      定义的 extern "C" fn（ffi）；WebAssembly 模块的导出函数（ffi）和 start
+      函数（main）
+    - Cargo 构建脚本（build.rs）的 main 函数（build）
+    - 过程宏（#[proc_macro]、#[proc_macro_derive]、#[proc_macro_attribute]），
+      由编译器在展开宏时调用（proc_macro）
+    """
+    node = graph.nodes[node_id]
+    name = node["name"]
+    extras = node.get("extras") or {}
+
+    # 构建脚本的 main 和其调用的函数只在编译期运行
+    if name == "main" and not extras.get("owner"):
+        file_name = os.path.basename(node.get("file") or "")
+        if extras.get("build_script") or (
+            file_name == "build.rs" and node.get("language") == "rust"
+        ):
+            return "build"
+
+    for attribute in extras.get("attributes") or []:
+        base = attribute_name(attribute)
+        if base in ENTRY_ATTRIBUTES:
+            return ENTRY_ATTRIBUTES[base]
+        # #[tokio::test]、#[rstest::rstest] 等
+        if base.endswith("::test"):
+            return "test"
+        if base.endswith("::main"):
+            return "main"
+
+    # Python 模块顶层代码在导入或直接运行时执行
+    if extras.get("module_body"):
+        return "main"
+    if extras.get("abi") and extras["abi"] != "Rust":
+        return "ffi"
+    if extras.get("wasm_start"):
+        return "main"
+    if extras.get("wasm_exports") and "host_module" not in extras:
+        return "ffi"
+
+    language = node.get("language")
+    # Java 的 main 是类的静态方法，其他语言只认自由函数
+    if name == "main" and (language == "java" or not extras.get("owner")):
+        return "main"
+    if language == "python" and name.startswith("test_"):
+        return "test"
+    if language == "go" and (node.get("file") or "").endswith("_test.go"):
+        for prefix in ("Test", "Benchmark", "Example", "Fuzz"):
+            if name.startswith(prefix):
+                return "test"
+    if language == "go" and name == "init":
+        return "main"
+    return None
+
+
+# 测试代码所在的目录，以及测试文件名的模式
+TEST_DIRECTORIES = ("tests", "test", "__tests__")
+TEST_FILE_PATTERNS = (
+    "test_*.py",
+    "*_test.py",
+    "*_test.go",
+    "*.test.js",
+    "*.test.ts",
+    "*.spec.js",
+    "*.spec.ts",
+    "*Test.java",
+)
+
+
+def is_test_code(graph: CallGraph, node_id: str) -> bool:
+    """
+    判断函数是否属于测试代码
+
+    测试函数本身（见 entry_point_reason）、Rust 的 tests 模块中的函数，
+    以及 tests/ 等测试目录或 *_test.go、test_*.py 等测试文件中的函数
+    """
+    if entry_point_reason(graph, node_id) == "test":
+        return True
+    qualified = graph.qualified_name(node_id)
+    if "tests" in qualified.replace("::", ".").split(".")[:-1]:
+        return True
+    # 目录只看项目根目录以下的部分
+    parts = graph.module_of(node_id).split("/")
+    if any(part in TEST_DIRECTORIES for part in parts[:-1]):
+        return True
+    file_name = os.path.basename(graph.nodes[node_id].get("file") or "")
+    return any(
+        fnmatch.fnmatchcase(file_name, pattern) for pattern in TEST_FILE_PATTERNS
+    )
+
+
+# 入口点的原因 -> 说明（按显示顺序）
+ENTRY_REASONS = {
+    "main": "main 函数和二进制目标",
+    "test": "测试和基准测试",
+    "no_mangle": "#[no_mangle] / #[export_name] 导出",
+    "ffi": 'extern "C" 等外部 ABI 函数和 WebAssembly 导出',
+    "build": "构建脚本（build.rs）",
+    "proc_macro": "过程宏",
+    "user": "--root 指定",
+}
+
+
+def find_entry_points(graph: CallGraph) -> Dict[str, str]:
+    """查找所有默认入口点，返回 {节点ID: 原因}"""
+    entries = {}
+    for node_id in graph.nodes:
+        reason = entry_point_reason(graph, node_id)
+        if reason:
+            entries[node_id] = reason
+    return entries
+
+
+def tests_reaching(graph: CallGraph) -> Dict[str, List[str]]:
+    """
+    静态测试覆盖的近似：以每个测试函数为根做可达性分析
+
+    Returns:
+        {生产代码函数ID: 能到达它的测试函数ID 列表（按限定名排序）}，
+        包含所有不属于测试代码（见 is_test_code）的函数，没有测试能到达时为空列表
+    """
+    tests = sorted(
+        (
+            node_id
+            for node_id in graph.nodes
+            if entry_point_reason(graph, node_id) == "test"
+        ),
+        key=graph.qualified_name,
+    )
+    reaching: Dict[str, List[str]] = {
+        node_id: [] for node_id in graph.nodes if not is_test_code(graph, node_id)
+    }
+    for test_id in tests:
+        for node_id in reachable_from(graph, [test_id]):
+            if node_id in reaching:
+                reaching[node_id].append(test_id)
+    return reaching
+
+
+def const_violations(graph: CallGraph) -> List[Dict[str, object]]:
+    """
+    const fn 中对非 const 函数的调用（编译期求值时会报错的调用）
+
+    只检查调用图中的函数，对标准库和依赖的调用没有 const 信息，不计入
+
+    Returns:
+        [{"caller_id", "callee_id", "sites"}]，按调用者和被调用者的限定名排序
+    """
+    violations = []
+    for edge in graph.edges():
+        caller_id, callee_id = edge["caller_id"], edge["callee_id"]
+        if graph.is_const(caller_id) and not graph.is_const(callee_id):
+            violations.append(
+                {
+                    "caller_id": caller_id,
+                    "callee_id": callee_id,
+                    "sites": graph.call_sites(caller_id, callee_id),
+                }
+            )
+    violations.sort(
+        key=lambda v: (
+            graph.qualified_name(v["caller_id"]),
+            graph.qualified_name(v["callee_id"]),
+        )
+    )
+    return violations
+
+
+def const_subgraph(graph: CallGraph) -> CallGraph:
+    """
+    可以在编译期执行的子图：所有 const fn 和它们之间的调用，以及 const fn
+    调用的非 const 函数（见 const_violations）
+    """
+    const = {node_id for node_id in graph.nodes if graph.is_const(node_id)}
+    called = {
+        callee_id for node_id in const for callee_id in graph.successors(node_id)
+    }
+    return graph.subgraph(const | called)
+
+
+def reachable_from(graph: CallGraph, roots: Iterable[str]) -> Set[str]:
+    """从给定根节点出发可达的所有节点（包括根节点本身）"""
+    return graph.compact().reachable(roots)
+
+
+def neighborhood(
+    graph: CallGraph,
+    roots: Iterable[str],
+    depth: int,
+    callers: bool = True,
+    callees: bool = True,
+) -> Set[str]:
+    """
+    焦点函数的邻域：向上 depth 跳以内的调用者和向下 depth 跳以内的被调用者
+
+    两个方向分别搜索，不包括“调用者的其他被调用者”这类旁支
+    """
+    roots = [root for root in roots if root in graph.nodes]
+    result = set(roots)
+    directions = []
+    if callers:
+        directions.append(graph.predecessors)
+    if callees:
+        directions.append(graph.successors)
+    for neighbors in directions:
+        visited = set(roots)
+        frontier = list(roots)
+        for _ in range(depth):
+            next_frontier = []
+            for node in frontier:
+                for neighbor in neighbors(node):
+                    if neighbor not in visited:
+                        visited.add(neighbor)
+                        next_frontier.append(neighbor)
+            frontier = next_frontier
+        result |= visited
+    return result
+
+
+def find_unreachable(graph: CallGraph, roots: Iterable[str]) -> List[str]:
+    """
+    查找从任何根节点都不可达的函数（疑似死代码）
+
+    Returns:
+        节点 ID 列表，按文件和行号排序
+    """
+    reachable = reachable_from(graph, roots)
+    unreachable = [node_id for node_id in graph.nodes if node_id not in reachable]
+    unreachable.sort(
+        key=lambda n: (graph.nodes[n]["file"], graph.nodes[n].get("start_line") or 0)
+    )
+    return unreachable
+
+
+def _distances_to(graph: CallGraph, targets: Iterable[str]) -> Dict[str, int]:
+    """反向 BFS：每个能到达目标的节点到最近目标的跳数"""
+    distance = {target: 0 for target in targets if target in graph.nodes}
+    queue = deque(distance)
+    while queue:
+        node = queue.popleft()
+        for pred in graph.predecessors(node):
+            if pred not in distance:
+                distance[pred] = distance[node] + 1
+                queue.append(pred)
+    return distance
+
+
+def find_call_paths(
+    graph: CallGraph,
+    sources: Iterable[str],
+    targets: Iterable[str],
+    limit: Optional[int] = 20,
+    max_depth: Optional[int] = None,
+) -> List[List[str]]:
+    """
+    枚举从任一源函数到任一目标函数的简单路径（路径上没有重复节点）
+
+    按路径长度从短到长枚举，只沿能到达目标的节点扩展，因此不会在
+    与目标无关的子图上浪费时间。
+
+    Args:
+        limit: 最多返回的路径条数，None 表示不限制
+        max_depth: 路径的最大跳数（边数），None 表示不限制
+
+    Returns:
+        路径列表，每条路径是从源到目标的节点 ID 列表
+    """
+    target_set = {target for target in targets if target in graph.nodes}
+    distance = _distances_to(graph, target_set)
+
+    def fits(path_length: int, node: str) -> bool:
+        return node in distance and (
+            max_depth is None or path_length + distance[node] <= max_depth
+        )
+
+    queue = deque([source] for source in sorted(set(sources)) if fits(0, source))
+    paths: List[List[str]] = []
+    while queue and (limit is None or len(paths) < limit):
+        path = queue.popleft()
+        node = path[-1]
+        if node in target_set:
+            paths.append(path)
+            # 到达目标后不再经过它继续延伸
+            continue
+        on_path = set(path)
+        for succ in sorted(
+            graph.successors(node), key=lambda n: (distance.get(n, 0), n)
+        ):
+            if succ not in on_path and fits(len(path), succ):
+                queue.append(path + [succ])
+    return paths
+
+
+def dominator_tree(
+    graph: CallGraph, roots: Union[str, Iterable[str]]
+) -> Dict[str, Optional[str]]:
+    """
+    计算从入口函数出发的支配树（Cooper-Harvey-Kennedy 迭代算法）
+
+    函数 d 支配 n 表示从入口到 n 的每条调用路径都经过 d；直接支配者是
+    除 n 本身外离 n 最近的支配者。有多个入口时视为从一个虚拟根出发，
+    只被虚拟根支配的函数没有直接支配者
+
+    Args:
+        roots: 入口函数 ID（一个或多个）
+
+    Returns:
+        {节点ID: 直接支配者ID}，只包含从入口可达的函数，入口的值为 None
+    """
+    if isinstance(roots, str):
+        roots = [roots]
+    roots = sorted({root for root in roots if root in graph.nodes})
+    if not roots:
+        return {}
+    root_set = set(roots)
+
+    # None 表示虚拟根；迭代 DFS 求后序
+    postorder: List[Optional[str]] = []
+    visited: Set[Optional[str]] = {None}
+    work = [(None, iter(roots))]
+    while work:
+        node, successors = work[-1]
+        for succ in successors:
+            if succ not in visited:
+                visited.add(succ)
+                work.append((succ, iter(graph.successors(succ))))
+                break
+        else:
+            work.pop()
+            postorder.append(node)
+    number = {node: index for index, node in enumerate(postorder)}
+
+    def predecessors(node: str) -> List[Optional[str]]:
+        preds: List[Optional[str]] = [
+            pred for pred in graph.predecessors(node) if pred in number
+        ]
+        if node in root_set:
+            preds.append(None)
+        return preds
+
+    idom: Dict[Optional[str], Optional[str]] = {None: None}
+
+    def intersect(a: Optional[str], b: Optional[str]) -> Optional[str]:
+        while a != b:
+            while number[a] < number[b]:
+                a = idom[a]
+            while number[b] < number[a]:
+                b = idom[b]
+        return a
+
+    # 按逆后序迭代到不动点（虚拟根在后序的最后）
+    order = postorder[-2::-1]
+    changed = True
+    while changed:
+        changed = False
+        for node in order:
+            candidates = [pred for pred in predecessors(node) if pred in idom]
+            dominator = candidates[0]
+            for pred in candidates[1:]:
+                dominator = intersect(pred, dominator)
+            if node not in idom or idom[node] != dominator:
+                idom[node] = dominator
+                changed = True
+
+    return {node: idom[node] for node in order}
+
+
+def shortest_paths_to(
+    graph: CallGraph, sources: Iterable[str], targets: Iterable[str]
+) -> Dict[str, List[str]]:
+    """
+    每个源函数到最近目标函数的一条最短调用路径
+
+    只做一次反向 BFS，沿到目标距离递减的被调用者前进；距离相同时取
+    ID 最小的节点，使结果稳定。源函数本身是目标时路径只包含它自己
+
+    Returns:
+        {源函数ID: 路径节点 ID 列表}，不包含到达不了任何目标的源函数
+    """
+    distance = _distances_to(graph, targets)
+    paths = {}
+    for source in sources:
+        if source not in distance:
+            continue
+        path = [source]
+        while distance[path[-1]] > 0:
+            step = distance[path[-1]] - 1
+            path.append(
+                min(
+                    succ
+                    for succ in graph.successors(path[-1])
+                    if distance.get(succ) == step
+                )
+            )
+        paths[source] = path
+    return paths
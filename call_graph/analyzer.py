@@ -3,17 +3,149 @@
 协调代码解析和调用关系提取
 """
 
+import logging
 import os
+import time
 from pathlib import Path
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, List, Optional, Set, Tuple
 
 # 支持相对导入和直接运行
 try:
-    from .database import CallGraphDB
-    from .parsers import LANGUAGE_CONFIG, detect_language, get_parser
+    from .algorithms import condensation, neighborhood
+    from .cache import DEFAULT_CACHE_DIR, AnalysisCache, file_content_hash
+    from .cargo import CargoWorkspace
+    from .cfg import CfgSet
+    from .database import CallGraphDB, symbols_changed
+    from .coverage_report import apply_coverage, coverage_summary
+    from .dependencies import external_refs, include_dependencies
+    from .directives import apply_directives, drop_ignored_refs, ignore_relations
+    from .exporters import export_graph
+    from .filters import SymbolFilter
+    from .finder import suggestion
+    from .graph import CallGraph, assign_stable_ids
+    from .ignore import IgnoreRules
+    from .macros import expand_macro_calls
+    from .mir import mir_call_edges
+    from .parsers import (
+        LANGUAGE_CONFIG,
+        AddressTakenMarker,
+        detect_language,
+        drop_implicit_refs,
+        get_parser,
+        mark_address_taken,
+    )
+    from .profiling import apply_profile, profile_summary
+    from .progress import ProgressBar, log_event
+    from .pruning import DEFAULT_PRUNE_CENTRALITY, prune
+    from .streaming import StreamingStore, intern_symbols, load_call_refs
 except ImportError:
-    from database import CallGraphDB
-    from parsers import LANGUAGE_CONFIG, detect_language, get_parser
+    from algorithms import condensation, neighborhood
+    from cache import DEFAULT_CACHE_DIR, AnalysisCache, file_content_hash
+    from cargo import CargoWorkspace
+    from cfg import CfgSet
+    from database import CallGraphDB, symbols_changed
+    from coverage_report import apply_coverage, coverage_summary
+    from dependencies import external_refs, include_dependencies
+    from directives import apply_directives, drop_ignored_refs, ignore_relations
+    from exporters import export_graph
+    from filters import SymbolFilter
+    from finder import suggestion
+    from graph import CallGraph, assign_stable_ids
+    from ignore import IgnoreRules
+    from macros import expand_macro_calls
+    from mir import mir_call_edges
+    from parsers import (
+        LANGUAGE_CONFIG,
+        AddressTakenMarker,
+        detect_language,
+        drop_implicit_refs,
+        get_parser,
+        mark_address_taken,
+    )
+    from profiling import apply_profile, profile_summary
+    from progress import ProgressBar, log_event
+    from pruning import DEFAULT_PRUNE_CENTRALITY, prune
+    from streaming import StreamingStore, intern_symbols, load_call_refs
+
+# 未指定 exclude_dirs 时排除的目录（以 . 开头的目录总是被排除）
+DEFAULT_EXCLUDE_DIRS = [
+    "node_modules",
+    ".git",
+    "__pycache__",
+    "venv",
+    "env",
+    "build",
+    "dist",
+    "target",
+    ".idea",
+    ".vscode",
+    "bin",
+    "obj",
+]
+
+
+def annotate_spilled_refs(
+    store: StreamingStore,
+    file_results: Dict[str, Dict[str, Any]],
+    workspace: Optional[CargoWorkspace],
+    cfg: Optional[List[str]] = None,
+    cfg_mode: str = "filter",
+    frontier: Optional[Dict[str, Dict[str, Any]]] = None,
+) -> Tuple[int, int]:
+    """
+    低内存模式下补做 workspace.annotate 和 cfg 对调用引用的处理：逐个文件
+    读回调用引用，规范化路径并按 cfg 过滤后重新写入
+
+    指定 frontier 时在其中收集可能调用依赖 crate 的调用引用（{文件: 解析结果}，
+    见 external_refs），作为加入依赖 crate 的起点
+
+    Returns:
+        (条件不成立的函数数量, 条件不成立的调用引用数量)
+    """
+    cfg_set = CfgSet(cfg) if cfg else None
+    inactive_functions = inactive_refs = 0
+
+    def transform(file_path: str, result: Dict[str, Any]):
+        nonlocal inactive_functions, inactive_refs
+        if workspace:
+            workspace.annotate_call_refs(file_path, result)
+            if frontier is not None and result.get("language") == "rust":
+                refs = external_refs(workspace, result["call_refs"])
+                if refs:
+                    frontier[file_path] = {"language": "rust", "call_refs": refs}
+        if cfg_set:
+            functions, refs = cfg_set.apply({file_path: result}, cfg_mode)
+            inactive_functions += functions
+            inactive_refs += refs
+
+    store.rewrite_call_refs(file_results, transform)
+    return inactive_functions, inactive_refs
+
+
+def finish_spilled_refs(
+    store: StreamingStore,
+    file_results: Dict[str, Dict[str, Any]],
+    functions: List[Dict[str, Any]],
+    implicit: Set[str],
+) -> Tuple[int, int]:
+    """
+    低内存模式下的 mark_address_taken、drop_implicit_refs 和 ignore 指令：
+    逐个文件读回调用引用处理后重新写入
+
+    Returns:
+        (可能被间接调用的函数数量, 按注释指令忽略的调用引用数量)
+    """
+    marker = AddressTakenMarker(functions)
+    ignored = 0
+
+    def transform(file_path: str, result: Dict[str, Any]):
+        nonlocal ignored
+        marker.mark(result)
+        drop_implicit_refs({file_path: result}, implicit)
+        ignored += drop_ignored_refs(result)
+
+    store.rewrite_call_refs(file_results, transform)
+    return marker.count(), ignored
 
 
 class CallGraphAnalyzer:
@@ -24,52 +156,246 @@ class CallGraphAnalyzer:
         self.all_functions: List[Dict[str, Any]] = []
 
     def analyze_project(
-        self, project_path: str, exclude_dirs: Optional[List[str]] = None
+        self,
+        project_path: str,
+        exclude_dirs: Optional[List[str]] = None,
+        use_cache: bool = False,
+        cache_dir: Optional[str] = None,
+        expand_macros: bool = False,
+        macro_call_sites: bool = False,
+        mir: bool = False,
+        operator_calls: bool = False,
+        drop_glue: bool = False,
+        languages: Optional[List[str]] = None,
+        workspace: Optional[CargoWorkspace] = None,
+        dependency_depth: int = 0,
+        low_memory: bool = False,
+        spill_dir: Optional[str] = None,
+        show_progress: bool = True,
+        ignore_files: bool = True,
+        cfg: Optional[List[str]] = None,
+        cfg_mode: str = "filter",
     ) -> Dict[str, Any]:
-        """分析整个项目"""
+        """
+        分析整个项目
+
+        Args:
+            project_path: 项目路径
+            exclude_dirs: 排除的目录列表
+            use_cache: 启用增量缓存，只重新解析内容变化的文件，
+                并只更新数据库中变化文件的符号
+            cache_dir: 缓存目录（默认：项目根目录下的 .call_graph_cache）
+            expand_macros: 通过 cargo expand 展开 Rust 宏，补充宏生成的调用
+            macro_call_sites: 将宏生成的调用定位到宏调用位置
+            mir: 编译 Rust crate 获取 MIR，用编译器解析的调用替换语法前端的
+                调用关系（需要能编译项目）
+            operator_calls: 加入运算符重载和解引用对应的 trait 方法调用（Rust）
+            drop_glue: 加入值离开作用域时调用的 Drop::drop 实现（Rust）
+            languages: 只分析这些语言的源文件（默认：所有支持的语言）
+            workspace: 预先构建的 Cargo 工作区（如来自 cargo metadata）；指定时
+                只分析其中选中的 crate，不再在项目目录中查找 Cargo.toml
+            dependency_depth: 加入本地代码调用到的依赖 crate 中的函数，
+                沿调用链最多深入的层数（0 表示不加入）
+            low_memory: 低内存模式，调用引用和调用关系写入临时文件（见 streaming）
+            spill_dir: 低内存模式的临时文件目录（默认：系统临时目录）
+            show_progress: 在终端中显示各阶段的进度条（写到标准错误）
+            ignore_files: 遵循 .gitignore、.ignore 和 .callgraphignore
+            cfg: 生效的 Rust cfg（如 unix、feature="serde"），指定时按它们求值
+                #[cfg(...)] 条件
+            cfg_mode: filter 排除条件不成立的函数和调用，tag 只标记（cfg_active）
+        """
+        start_time = time.time()
         if exclude_dirs is None:
-            exclude_dirs = [
-                "node_modules",
-                ".git",
-                "__pycache__",
-                "venv",
-                "env",
-                "build",
-                "dist",
-                "target",
-                ".idea",
-                ".vscode",
-                "bin",
-                "obj",
-            ]
+            exclude_dirs = list(DEFAULT_EXCLUDE_DIRS)
 
         project_path = Path(project_path).resolve()
 
         print(f"开始分析项目: {project_path}")
+        log_event(
+            "analysis.start",
+            "开始分析项目",
+            project=str(project_path),
+            workers=1,
+            low_memory=low_memory,
+        )
 
         # 收集所有源代码文件
-        source_files = self._collect_source_files(project_path, exclude_dirs)
+        phase_start = time.time()
+        source_files = self._collect_source_files(
+            project_path, exclude_dirs, show_progress, ignore_files
+        )
+
+        # Cargo 工作区：成员 crate 可能位于项目目录之外
+        workspace = self._discover_workspace(
+            project_path, exclude_dirs, source_files, workspace, ignore_files
+        )
+        if languages:
+            source_files = [
+                path for path in source_files if detect_language(path) in languages
+            ]
 
         print(f"找到 {len(source_files)} 个源代码文件")
+        log_event(
+            "walk.done",
+            "收集源文件完成",
+            files=len(source_files),
+            seconds=round(time.time() - phase_start, 3),
+        )
 
-        # 第一遍：提取所有函数定义
-        print("第一遍扫描：提取函数定义...")
-        for file_path in source_files:
-            self._extract_functions_from_file(file_path)
+        cache = None
+        if use_cache:
+            cache = AnalysisCache(cache_dir or str(project_path / DEFAULT_CACHE_DIR))
+
+        # 低内存模式：每个文件解析后立即把调用引用写入临时文件，内存中只保留
+        # 函数定义和条目
+        store = StreamingStore(spill_dir) if low_memory else None
+
+        # 第一遍：提取所有函数定义和调用引用（每个文件只解析一次）
+        print("第一遍扫描：提取函数定义和调用引用...")
+        phase_start = time.time()
+        file_results = {}
+        total = len(source_files)
+        with ProgressBar("提取函数", total, show_progress, every=20) as progress:
+            for file_path in source_files:
+                result = self._analyze_file_symbols(file_path, cache)
+                progress.update()
+                if result:
+                    file_results[file_path] = result
+                    log_event(
+                        "parse.file",
+                        "解析文件",
+                        logging.DEBUG,
+                        file=file_path,
+                        functions=len(result["functions"]),
+                        call_refs=len(result["call_refs"]),
+                    )
+                    if store:
+                        store.spill_file(file_path, result)
+
+        include_deps = workspace is not None and dependency_depth > 0
+        if workspace:
+            workspace.annotate(file_results)
+        # 低内存模式：annotate 时调用引用还在临时文件中，逐个文件读回处理；
+        # 依赖分析只从可能调用依赖 crate 的调用引用出发
+        frontier = file_results
+        cfg_counts = (0, 0)
+        if store:
+            frontier = {}
+            cfg_counts = annotate_spilled_refs(
+                store,
+                file_results,
+                workspace,
+                cfg,
+                cfg_mode,
+                frontier if include_deps else None,
+            )
+        included = {}
+        if include_deps:
+            included = self._include_dependencies(
+                workspace, frontier, dependency_depth, exclude_dirs, cache
+            )
+            file_results.update(included)
+
+        if cfg:
+            # 低内存模式下本地文件已逐个处理，只剩新加入的依赖文件
+            self._apply_cfg(
+                included if store else file_results, cfg, cfg_mode, cfg_counts
+            )
+        if store:
+            for file_path, result in included.items():
+                store.spill_file(file_path, result)
+
+        self.all_functions = []
+        for result in file_results.values():
+            self.all_functions.extend(result["functions"])
+        assign_stable_ids(self.all_functions, str(project_path))
 
         print(f"共提取 {len(self.all_functions)} 个函数定义")
+        log_event(
+            "parse.done",
+            "第一遍扫描完成",
+            files=len(file_results),
+            functions=len(self.all_functions),
+            cache_hits=cache.hits if cache else 0,
+            seconds=round(time.time() - phase_start, 3),
+        )
+        implicit = {
+            kind
+            for kind, enabled in (("operator", operator_calls), ("drop", drop_glue))
+            if enabled
+        }
+        # 全程序收集作为值引用的函数，作为间接调用的候选目标；
+        # 源码注释指令：去掉 ignore 的调用引用，edge 声明的调用在第二遍之后加入
+        if store:
+            address_taken, spilled_ignored = finish_spilled_refs(
+                store, file_results, self.all_functions, implicit
+            )
+        else:
+            address_taken = mark_address_taken(file_results, self.all_functions)
+            drop_implicit_refs(file_results, implicit)
+            spilled_ignored = 0
+        if address_taken:
+            print(f"{address_taken} 个函数作为值被引用（可能被间接调用）")
+        ignored, annotated = apply_directives(file_results, self.all_functions)
+        ignored += spilled_ignored
+        if ignored or annotated:
+            print(
+                f"按注释指令忽略 {ignored} 个调用引用，声明 {len(annotated)} 个调用关系"
+            )
+        if cache:
+            cache.prune(file_results.keys())
+            print(f"缓存命中 {cache.hits} 个文件，重新解析 {cache.misses} 个文件")
 
         # 保存函数定义到数据库
         print("保存函数定义到数据库...")
-        for func in self.all_functions:
-            self.db.insert_symbol(func)
+        self._save_symbols(file_results, incremental=use_cache)
+
+        # 第二遍：解析调用关系
+        print("第二遍扫描：解析调用关系...")
+        phase_start = time.time()
+        if store:
+            total_calls = self._stream_calls(file_results, store, show_progress)
+        else:
+            total_calls = 0
+            with ProgressBar(
+                "提取调用", len(file_results), show_progress, every=20
+            ) as progress:
+                for file_path, result in file_results.items():
+                    calls = self._resolve_calls(file_path, result)
+                    total_calls += len(calls)
+                    progress.update()
+        for relation in annotated:
+            self.db.insert_call_relation(relation)
+        total_calls += len(annotated)
+        log_event(
+            "resolve.done",
+            "第二遍扫描完成",
+            relations=total_calls,
+            seconds=round(time.time() - phase_start, 3),
+        )
 
-        # 第二遍：提取调用关系
-        print("第二遍扫描：提取调用关系...")
-        total_calls = 0
-        for file_path in source_files:
-            calls = self._extract_calls_from_file(file_path)
-            total_calls += len(calls)
+        # 宏展开：补充宏生成的调用
+        if expand_macros:
+            print("宏展开：提取宏生成的调用...")
+            expanded = self._expand_macros(
+                project_path, file_results, exclude_dirs, macro_call_sites
+            )
+            for func in expanded["functions"]:
+                self.db.insert_symbol(func)
+            for call in expanded["calls"]:
+                self.db.insert_call_relation(call)
+            total_calls += len(expanded["calls"])
+
+        # MIR：用编译器解析的调用替换语法前端推断的 Rust 调用关系
+        if mir:
+            print("MIR 后端：从编译器的 MIR 提取 Rust 调用关系...")
+            extracted = self._mir_calls(project_path, file_results, exclude_dirs)
+            total_calls -= self.db.delete_relations_from(extracted["callers"])
+            # 注释指令声明的调用关系不来自 MIR，保留
+            kept = [r for r in annotated if r["caller_id"] in extracted["callers"]]
+            for call in extracted["calls"] + kept:
+                self.db.insert_call_relation(call)
+            total_calls += len(extracted["calls"]) + len(kept)
 
         print(f"共提取 {total_calls} 个调用关系")
 
@@ -82,14 +408,311 @@ class CallGraphAnalyzer:
         print("\n按语言统计:")
         for lang, count in stats["by_language"].items():
             print(f"  {lang}: {count}")
+        log_event(
+            "analysis.done",
+            "分析完成",
+            files=len(source_files),
+            symbols=stats["total_symbols"],
+            relations=stats["total_relations"],
+            seconds=round(time.time() - start_time, 3),
+        )
 
         return stats
 
+    def _analyze_file_symbols(
+        self, file_path: str, cache: Optional[AnalysisCache] = None
+    ) -> Optional[Dict[str, Any]]:
+        """解析单个文件（优先使用缓存），返回函数定义、调用引用及内容哈希"""
+        language = detect_language(file_path)
+        if not language:
+            return None
+
+        try:
+            content_hash = file_content_hash(file_path)
+            result = cache.load(file_path, content_hash) if cache else None
+            if result is None:
+                parser = get_parser(language)
+                result = parser.parse_file_symbols(file_path)
+                if cache:
+                    cache.store(file_path, content_hash, result)
+            result["hash"] = content_hash
+            result["language"] = language
+            return result
+        except Exception as e:
+            print(f"警告: 提取函数失败 {file_path}: {e}")
+            log_event(
+                "parse.failed",
+                "提取函数失败",
+                logging.WARNING,
+                file=file_path,
+                error=str(e),
+            )
+            return None
+
+    def _save_symbols(
+        self, file_results: Dict[str, Dict[str, Any]], incremental: bool
+    ):
+        """
+        保存函数定义
+
+        增量模式下只替换内容哈希或函数标注与数据库记录不一致的文件（公开 API
+        等标注依赖其他文件中的 mod 声明和 pub use），删除已不存在的文件，
+        并清空调用关系（调用解析依赖全局符号表，需要整体重建）。
+        """
+        if incremental:
+            db_hashes = self.db.get_file_hashes()
+            for file_path in set(db_hashes) - set(file_results):
+                self.db.delete_file(file_path)
+            stored = self.db.get_symbol_extras()
+            changed = [
+                path
+                for path, result in file_results.items()
+                if db_hashes.get(path) != result["hash"]
+                or symbols_changed(result["functions"], stored)
+            ]
+            for file_path in changed:
+                self.db.delete_file(file_path)
+            self.db.clear_relations()
+            print(f"变化的文件: {len(changed)} 个")
+        else:
+            changed = list(file_results)
+
+        for file_path in changed:
+            for func in file_results[file_path]["functions"]:
+                self.db.insert_symbol(func)
+        # 未变化的文件也重新记录：旧数据库中没有 trait 信息
+        for file_path, result in file_results.items():
+            self.db.upsert_file(
+                file_path, result["hash"], result["language"], result.get("items")
+            )
+
+    def _resolve_calls(
+        self,
+        file_path: str,
+        result: Dict[str, Any],
+        store: Optional[StreamingStore] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        解析文件的调用引用并保存到数据库
+
+        指定 store 时（低内存模式）从临时文件读取调用引用，调用关系写入 store
+        """
+        try:
+            parser = get_parser(result["language"])
+            call_refs = result["call_refs"]
+            if store:
+                call_refs = load_call_refs(store.call_refs_path(file_path))
+            calls = parser.resolve_call_refs(call_refs, self.all_functions)
+
+            if store:
+                store.add_relations(calls)
+                return calls
+
+            # 保存到数据库
+            for call in calls:
+                self.db.insert_call_relation(call)
+
+            return calls
+        except Exception as e:
+            print(f"警告: 提取调用关系失败 {file_path}: {e}")
+            log_event(
+                "resolve.failed",
+                "提取调用关系失败",
+                logging.WARNING,
+                file=file_path,
+                error=str(e),
+            )
+            return []
+
+    def _stream_calls(
+        self,
+        file_results: Dict[str, Dict[str, Any]],
+        store: StreamingStore,
+        show_progress: bool = False,
+    ) -> int:
+        """
+        低内存模式的第二遍扫描：从临时文件逐个文件读回调用引用解析，
+        调用关系分段排序写入磁盘，最后归并入库；返回调用关系数量（完成后
+        关闭 store）
+        """
+        with store:
+            intern_symbols(self.all_functions)
+            store.order_files(file_results)
+            with ProgressBar(
+                "提取调用", len(file_results), show_progress, every=20
+            ) as progress:
+                for file_path, result in file_results.items():
+                    self._resolve_calls(file_path, result, store)
+                    progress.update()
+            print(f"归并 {max(store.run_count, 1)} 个有序段到数据库...")
+            for relation in store.relations():
+                self.db.insert_call_relation(relation)
+            return store.relation_count
+
+    def _apply_cfg(
+        self,
+        file_results: Dict[str, Dict[str, Any]],
+        cfg: List[str],
+        mode: str,
+        counts: Tuple[int, int] = (0, 0),
+    ):
+        """
+        按 --cfg 求值 Rust 的 #[cfg(...)] 条件（见 cfg 模块）
+
+        counts 为已经逐个文件处理过的部分的计数（见 annotate_spilled_refs），
+        与 file_results 的计数合计输出
+        """
+        functions, refs = CfgSet(cfg).apply(file_results, mode)
+        functions += counts[0]
+        refs += counts[1]
+        action = "排除" if mode == "filter" else "标记"
+        print(f"按 cfg {action}条件不成立的 {functions} 个函数、{refs} 个调用引用")
+        log_event("cfg.done", "求值 cfg 条件", mode=mode, functions=functions, refs=refs)
+
+    def _discover_workspace(
+        self,
+        project_path: Path,
+        exclude_dirs: List[str],
+        source_files: List[str],
+        workspace: Optional[CargoWorkspace] = None,
+        ignore_files: bool = True,
+    ) -> Optional[CargoWorkspace]:
+        """
+        识别 Cargo crate，并把项目目录之外的工作区成员加入源文件列表
+
+        指定了 workspace 时改为只收集其中选中的 crate 和目标的源文件
+        """
+        if workspace:
+            files = []
+            for directory in workspace.source_directories():
+                files.extend(
+                    self._collect_source_files(
+                        directory, exclude_dirs, ignore_files=ignore_files
+                    )
+                )
+            source_files[:] = [
+                path for path in dict.fromkeys(files) if workspace.includes_file(path)
+            ]
+        else:
+            workspace = CargoWorkspace.discover(project_path, exclude_dirs)
+            if not workspace:
+                return None
+            for directory in workspace.external_directories(project_path):
+                source_files.extend(
+                    self._collect_source_files(
+                        directory, exclude_dirs, ignore_files=ignore_files
+                    )
+                )
+        print(
+            f"检测到 {len(workspace.crates)} 个 Cargo crate: "
+            f"{', '.join(sorted(workspace.crates))}"
+        )
+        return workspace
+
+    def _include_dependencies(
+        self,
+        workspace: CargoWorkspace,
+        file_results: Dict[str, Dict[str, Any]],
+        depth: int,
+        exclude_dirs: List[str],
+        cache: Optional[AnalysisCache],
+    ) -> Dict[str, Dict[str, Any]]:
+        """解析本地代码调用到的依赖 crate，返回需要加入的依赖文件结果"""
+
+        def parse_files(files: List[str]) -> Dict[str, Dict[str, Any]]:
+            results = {}
+            for file_path in files:
+                result = self._analyze_file_symbols(file_path, cache)
+                if result:
+                    results[file_path] = result
+            return results
+
+        print(f"依赖 crate：沿调用链最多深入 {depth} 层...")
+        included = include_dependencies(
+            workspace, file_results, depth, parse_files, exclude_dirs
+        )
+        functions = [
+            func for result in included.values() for func in result["functions"]
+        ]
+        crates = {func["extras"]["crate"] for func in functions}
+        print(f"加入 {len(crates)} 个依赖 crate 中的 {len(functions)} 个函数")
+        return included
+
+    def _expand_macros(
+        self,
+        project_path: Path,
+        file_results: Dict[str, Dict[str, Any]],
+        exclude_dirs: List[str],
+        macro_call_sites: bool,
+    ) -> Dict[str, Any]:
+        """展开项目中的 Rust crate，返回宏生成的函数和调用关系"""
+        rust_files = [
+            path
+            for path, result in file_results.items()
+            if result["language"] == "rust"
+        ]
+        if not rust_files:
+            print("没有 Rust 源文件，跳过宏展开")
+            return {"functions": [], "calls": [], "crates": 0}
+
+        expanded = expand_macro_calls(
+            project_path,
+            rust_files,
+            self.all_functions,
+            exclude_dirs,
+            attribute_to_site=macro_call_sites,
+        )
+        print(
+            f"展开 {expanded['crates']} 个 crate 目标，"
+            f"宏生成的函数 {len(expanded['functions'])} 个，"
+            f"宏生成的调用 {len(expanded['calls'])} 个"
+        )
+        return expanded
+
+    def _mir_calls(
+        self,
+        project_path: Path,
+        file_results: Dict[str, Dict[str, Any]],
+        exclude_dirs: List[str],
+    ) -> Dict[str, Any]:
+        """编译项目中的 Rust crate，返回从 MIR 提取的调用关系（已应用 ignore 指令）"""
+        rust_files = [
+            path
+            for path, result in file_results.items()
+            if result["language"] == "rust"
+        ]
+        if not rust_files:
+            print("没有 Rust 源文件，跳过 MIR 分析")
+            return {"calls": [], "callers": set(), "crates": 0, "unmapped": 0}
+
+        foreign = {
+            item["name"]
+            for result in file_results.values()
+            for item in (result.get("items") or {}).get("foreign") or []
+        }
+        extracted = mir_call_edges(
+            project_path, rust_files, self.all_functions, exclude_dirs, foreign
+        )
+        extracted["calls"], ignored = ignore_relations(extracted["calls"], file_results)
+        print(
+            f"编译 {extracted['crates']} 个 crate 目标，"
+            f"替换 {len(extracted['callers'])} 个函数的调用关系，"
+            f"MIR 调用关系 {len(extracted['calls'])} 个"
+            + (f"（按注释指令忽略 {ignored} 个）" if ignored else "")
+        )
+        return extracted
+
     def _collect_source_files(
-        self, project_path: Path, exclude_dirs: List[str]
+        self,
+        project_path: Path,
+        exclude_dirs: List[str],
+        show_progress: bool = False,
+        ignore_files: bool = True,
     ) -> List[str]:
-        """收集所有源代码文件"""
+        """收集所有源代码文件（ignore_files 为 True 时遵循忽略文件，见 ignore 模块）"""
         source_files = []
+        progress = ProgressBar("扫描文件", None, show_progress, every=200)
+        ignore = IgnoreRules(project_path) if ignore_files else None
 
         # 收集所有支持的扩展名
         supported_extensions = set()
@@ -99,48 +722,24 @@ class CallGraphAnalyzer:
         for root, dirs, files in os.walk(project_path):
             # 排除指定目录
             dirs[:] = [
-                d for d in dirs if d not in exclude_dirs and not d.startswith(".")
+                d
+                for d in dirs
+                if d not in exclude_dirs
+                and not d.startswith(".")
+                and not (ignore and ignore.is_ignored(os.path.join(root, d), True))
             ]
 
             for file in files:
                 if any(file.endswith(ext) for ext in supported_extensions):
                     file_path = os.path.join(root, file)
+                    if ignore and ignore.is_ignored(file_path, False):
+                        continue
                     source_files.append(file_path)
+                    progress.update(len(source_files))
 
+        progress.close()
         return source_files
 
-    def _extract_functions_from_file(self, file_path: str):
-        """从文件中提取函数定义"""
-        language = detect_language(file_path)
-        if not language:
-            return
-
-        try:
-            parser = get_parser(language)
-            functions = parser.extract_functions(file_path)
-            self.all_functions.extend(functions)
-        except Exception as e:
-            print(f"警告: 提取函数失败 {file_path}: {e}")
-
-    def _extract_calls_from_file(self, file_path: str) -> List[Dict[str, Any]]:
-        """从文件中提取调用关系"""
-        language = detect_language(file_path)
-        if not language:
-            return []
-
-        try:
-            parser = get_parser(language)
-            calls = parser.extract_calls(file_path, self.all_functions)
-
-            # 保存到数据库
-            for call in calls:
-                self.db.insert_call_relation(call)
-
-            return calls
-        except Exception as e:
-            print(f"警告: 提取调用关系失败 {file_path}: {e}")
-            return []
-
     def analyze_file(self, file_path: str) -> Dict[str, Any]:
         """分析单个文件"""
         language = detect_language(file_path)
@@ -207,59 +806,115 @@ class CallGraphAnalyzer:
         """获取统计信息"""
         return self.db.get_statistics()
 
-    def export_graph(self, output_format: str = "dot") -> str:
-        """导出调用图"""
-        if output_format == "dot":
-            return self._export_dot()
-        else:
-            raise ValueError(f"不支持的导出格式: {output_format}")
-
-    def _export_dot(self) -> str:
-        """导出为Graphviz DOT格式"""
-        lines = ["digraph CallGraph {"]
-        lines.append("  rankdir=LR;")
-        lines.append("  node [shape=box];")
-        lines.append('  graph [fontname="Arial", fontsize=10];')
-        lines.append('  node [fontname="Arial", fontsize=9];')
-        lines.append('  edge [fontname="Arial", fontsize=8];')
-
-        # 添加所有函数节点
-        symbols = self.db.get_symbols_by_kind("function")
-        valid_node_ids = set()
-        for symbol in symbols:
-            node_id = symbol["id"]
-            valid_node_ids.add(node_id)
-            # 转义特殊字符
-            name = symbol["name"].replace('"', '\\"')
-            file_path = symbol["file"].replace('"', '\\"')
-            label = f"{name}\\n({file_path}:{symbol.get('start_line', '?')})"
-            lines.append(f'  "{node_id}" [label="{label}"];')
-
-        # 添加调用边（只添加有效的边）
-        cursor = self.db.conn.cursor()
-        cursor.execute(
-            """
-            SELECT caller_id, callee_id, caller_name, callee_name 
-            FROM call_relations 
-            WHERE caller_id IS NOT NULL AND callee_id IS NOT NULL
+    def export_view(
+        self,
+        focus: Optional[List[str]] = None,
+        depth: int = 2,
+        granularity: str = "function",
+        symbol_filter: Optional[SymbolFilter] = None,
+        monomorphize: bool = False,
+        condense: bool = False,
+        profile: Optional[List[Tuple[List[str], int]]] = None,
+        coverage: Optional[Dict[str, Any]] = None,
+        max_nodes: Optional[int] = None,
+        prune_by: str = DEFAULT_PRUNE_CENTRALITY,
+        stable_ids: bool = False,
+        public_only: bool = False,
+    ) -> CallGraph:
+        """
+        按导出选项处理后的调用图
+
+        Args:
+            focus: 焦点函数名或限定名；指定时只导出它们 depth 跳以内的
+                调用者和被调用者
+            granularity: function（函数级）、module（每个模块一个节点，
+                模块间的调用合并为边）或 type（每个类型一个节点，见
+                CallGraph.type_graph）
+            symbol_filter: 按文件路径和限定名包含/排除函数，在其他处理之前应用
+            monomorphize: 按调用点的类型实参把泛型函数拆分为单态化节点
+            condense: 把每个强连通分量（递归环）缩成一个节点，在最后应用，
+                与 module 粒度一起使用时缩并模块间的循环依赖
+            profile: 剖析数据的调用栈（见 profiling.load_profile），在过滤之后、
+                选取焦点之前叠加样本数；只能用于函数级视图
+            coverage: 覆盖率报告（见 coverage_report.load_coverage），同 profile
+            max_nodes: 节点数超过该值时只保留按 prune_by 中心度排名靠前的函数，
+                被裁掉的函数合并为占位节点（见 pruning.prune），在缩点之后应用
+            stable_ids: 把节点 ID 换成稳定 ID（见 CallGraph.stable_id），在最后应用
+            public_only: 只保留公开函数，经过私有函数的调用链合并为边（见
+                CallGraph.public_only），在叠加数据之后、选取焦点之前应用
+        """
+        overlay = profile is not None or coverage is not None
+        if overlay and (granularity != "function" or condense):
+            raise ValueError(
+                "剖析数据和覆盖率数据只能叠加到函数级视图，不能与 module、type 粒度或缩点同时使用"
+            )
+        graph = CallGraph.from_db(self.db)
+        if symbol_filter:
+            graph = symbol_filter.apply(graph)
+        if profile is not None:
+            print(profile_summary(graph, apply_profile(graph, profile)))
+        if coverage is not None:
+            print(coverage_summary(apply_coverage(graph, coverage)))
+        if public_only:
+            graph = graph.public_only()
+        if focus:
+            roots = []
+            for name in focus:
+                matches = graph.resolve(name)
+                if not matches:
+                    raise ValueError(f"没有找到函数: {name}{suggestion(graph, name)}")
+                roots.extend(matches)
+            graph = graph.subgraph(neighborhood(graph, roots, depth))
+        if monomorphize:
+            graph = graph.monomorphize()
+        if granularity == "module":
+            graph = graph.module_graph()
+        elif granularity == "type":
+            graph = graph.type_graph()
+        if condense:
+            graph = condensation(graph)
+        if max_nodes is not None:
+            graph = prune(graph, max_nodes, prune_by)
+        if stable_ids:
+            graph = graph.with_stable_ids()
+        return graph
+
+    def export_graph(
+        self,
+        output_format: str = "dot",
+        focus: Optional[List[str]] = None,
+        depth: int = 2,
+        granularity: str = "function",
+        symbol_filter: Optional[SymbolFilter] = None,
+        monomorphize: bool = False,
+        condense: bool = False,
+        profile: Optional[List[Tuple[List[str], int]]] = None,
+        coverage: Optional[Dict[str, Any]] = None,
+        max_nodes: Optional[int] = None,
+        prune_by: str = DEFAULT_PRUNE_CENTRALITY,
+        stable_ids: bool = False,
+        public_only: bool = False,
+        **options,
+    ) -> str:
+        """
+        导出调用图（选项见 exporters 模块中对应格式的导出函数，其余参数
+        同 export_view）
         """
+        graph = self.export_view(
+            focus=focus,
+            depth=depth,
+            granularity=granularity,
+            symbol_filter=symbol_filter,
+            monomorphize=monomorphize,
+            condense=condense,
+            profile=profile,
+            coverage=coverage,
+            max_nodes=max_nodes,
+            prune_by=prune_by,
+            stable_ids=stable_ids,
+            public_only=public_only,
         )
-        edge_count = 0
-        for row in cursor.fetchall():
-            caller_id = row["caller_id"]
-            callee_id = row["callee_id"]
-            # 只添加两端节点都存在的边
-            if caller_id in valid_node_ids and callee_id in valid_node_ids:
-                lines.append(f'  "{caller_id}" -> "{callee_id}";')
-                edge_count += 1
-
-        lines.append("}")
-
-        # 返回结果，包含统计信息作为注释
-        result = "\n".join(lines)
-        print(f"导出成功: {len(valid_node_ids)} 个节点, {edge_count} 条边")
-
-        return result
+        return export_graph(graph, output_format, **options)
 
     def close(self):
         """关闭分析器"""
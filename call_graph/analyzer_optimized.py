@@ -3,57 +3,157 @@
 支持多进程并行处理和批量数据库操作
 """
 
+import logging
 import os
 import time
+from itertools import islice
 from multiprocessing import Pool, cpu_count
 from pathlib import Path
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, Iterable, List, Optional, Tuple
 
 # 支持相对导入和直接运行
 try:
-    from .database import CallGraphDB
-    from .parsers import LANGUAGE_CONFIG, detect_language, get_parser
+    from .algorithms import condensation, neighborhood
+    from .analyzer import (
+        DEFAULT_EXCLUDE_DIRS,
+        annotate_spilled_refs,
+        finish_spilled_refs,
+    )
+    from .cache import DEFAULT_CACHE_DIR, AnalysisCache, file_content_hash
+    from .cargo import CargoWorkspace
+    from .cfg import CfgSet
+    from .database import CallGraphDB, symbols_changed
+    from .coverage_report import apply_coverage, coverage_summary
+    from .dependencies import include_dependencies
+    from .directives import apply_directives, ignore_relations
+    from .exporters import export_graph
+    from .filters import SymbolFilter
+    from .finder import suggestion
+    from .graph import CallGraph, assign_stable_ids
+    from .ignore import IgnoreRules
+    from .macros import expand_macro_calls
+    from .mir import mir_call_edges
+    from .parsers import (
+        LANGUAGE_CONFIG,
+        detect_language,
+        drop_implicit_refs,
+        get_parser,
+        mark_address_taken,
+    )
+    from .plugins import load_plugins, loaded_plugins
+    from .profiling import apply_profile, profile_summary
+    from .progress import ProgressBar, log_event
+    from .pruning import DEFAULT_PRUNE_CENTRALITY, prune
+    from .streaming import StreamingStore, intern_symbols, load_call_refs
 except ImportError:
-    from database import CallGraphDB
-    from parsers import LANGUAGE_CONFIG, detect_language, get_parser
-
-
-def _process_file_functions(file_path: str) -> List[Dict[str, Any]]:
+    from algorithms import condensation, neighborhood
+    from analyzer import (
+        DEFAULT_EXCLUDE_DIRS,
+        annotate_spilled_refs,
+        finish_spilled_refs,
+    )
+    from cache import DEFAULT_CACHE_DIR, AnalysisCache, file_content_hash
+    from cargo import CargoWorkspace
+    from cfg import CfgSet
+    from database import CallGraphDB, symbols_changed
+    from coverage_report import apply_coverage, coverage_summary
+    from dependencies import include_dependencies
+    from directives import apply_directives, ignore_relations
+    from exporters import export_graph
+    from filters import SymbolFilter
+    from finder import suggestion
+    from graph import CallGraph, assign_stable_ids
+    from ignore import IgnoreRules
+    from macros import expand_macro_calls
+    from mir import mir_call_edges
+    from parsers import (
+        LANGUAGE_CONFIG,
+        detect_language,
+        drop_implicit_refs,
+        get_parser,
+        mark_address_taken,
+    )
+    from plugins import load_plugins, loaded_plugins
+    from profiling import apply_profile, profile_summary
+    from progress import ProgressBar, log_event
+    from pruning import DEFAULT_PRUNE_CENTRALITY, prune
+    from streaming import StreamingStore, intern_symbols, load_call_refs
+
+
+def _process_file_symbols(args) -> Optional[Dict[str, Any]]:
     """
-    工作进程：从单个文件中提取函数定义
+    工作进程：解析单个文件，同时提取函数定义和调用引用（优先使用缓存）
     这个函数必须在模块级别，才能被 multiprocessing pickle
+    args: (file_path, cache_dir)，cache_dir 为 None 时不使用缓存
     """
+    file_path, cache_dir = args
     language = detect_language(file_path)
     if not language:
-        return []
+        return None
 
     try:
-        parser = get_parser(language)
-        functions = parser.extract_functions(file_path)
-        return functions
+        content_hash = file_content_hash(file_path)
+        cache = AnalysisCache(cache_dir) if cache_dir else None
+        result = cache.load(file_path, content_hash) if cache else None
+        cached = result is not None
+        if result is None:
+            parser = get_parser(language)
+            result = parser.parse_file_symbols(file_path)
+            if cache:
+                cache.store(file_path, content_hash, result)
+        result.update(
+            file=file_path, hash=content_hash, language=language, cached=cached
+        )
+        return result
     except Exception as e:
         print(f"警告: 提取函数失败 {file_path}: {e}")
-        return []
+        log_event(
+            "parse.failed", "提取函数失败", logging.WARNING, file=file_path, error=str(e)
+        )
+        return None
+
+
+# 工作进程中的全局符号表，由 Pool 初始化函数在每个进程中设置一次，
+# 避免随每个任务重复 pickle 整个符号表
+_worker_functions: List[Dict[str, Any]] = []
+
+
+def _init_symbols_worker(plugins: List[str]):
+    """工作进程初始化：加载语言插件（以 spawn 方式启动的进程不继承注册表）"""
+    if plugins:
+        load_plugins(plugins)
+
+
+def _init_calls_worker(all_functions: List[Dict[str, Any]], plugins: List[str]):
+    """工作进程初始化：加载语言插件并保存全局符号表"""
+    global _worker_functions
+    _init_symbols_worker(plugins)
+    _worker_functions = all_functions
 
 
 def _process_file_calls(args) -> List[Dict[str, Any]]:
     """
-    工作进程：从单个文件中提取调用关系
-    args: (file_path, all_functions_dict)
+    工作进程：解析单个文件的调用引用
+    args: (file_path, language, call_refs)，低内存模式下 call_refs 为
+    写有调用引用的临时文件路径
     """
-    file_path, all_functions_dict = args
-    language = detect_language(file_path)
-    if not language:
-        return []
+    file_path, language, call_refs = args
 
     try:
+        if isinstance(call_refs, str):
+            call_refs = load_call_refs(call_refs)
         parser = get_parser(language)
-        # 将 dict 转换回 list
-        all_functions = list(all_functions_dict.values())
-        calls = parser.extract_calls(file_path, all_functions)
+        calls = parser.resolve_call_refs(call_refs, _worker_functions)
         return calls
     except Exception as e:
         print(f"警告: 提取调用关系失败 {file_path}: {e}")
+        log_event(
+            "resolve.failed",
+            "提取调用关系失败",
+            logging.WARNING,
+            file=file_path,
+            error=str(e),
+        )
         return []
 
 
@@ -62,10 +162,12 @@ class CallGraphAnalyzerOptimized:
     性能优化版本的调用关系分析器
 
     优化特性：
-    1. 多进程并行处理文件
-    2. 批量数据库操作
-    3. 进度显示
-    4. 事务优化
+    1. 多进程并行处理文件（每个文件只解析一次，结果按文件合并）
+    2. 全局符号表通过进程初始化函数只传输一次
+    3. 批量数据库操作
+    4. 进度显示
+    5. 事务优化
+    6. 低内存模式：调用引用和调用关系写入临时文件，有序段归并后入库
     """
 
     def __init__(
@@ -82,6 +184,21 @@ class CallGraphAnalyzerOptimized:
         exclude_dirs: Optional[List[str]] = None,
         batch_size: int = 100,
         show_progress: bool = True,
+        use_cache: bool = False,
+        cache_dir: Optional[str] = None,
+        expand_macros: bool = False,
+        macro_call_sites: bool = False,
+        mir: bool = False,
+        operator_calls: bool = False,
+        drop_glue: bool = False,
+        workspace: Optional[CargoWorkspace] = None,
+        dependency_depth: int = 0,
+        low_memory: bool = False,
+        spill_dir: Optional[str] = None,
+        languages: Optional[List[str]] = None,
+        ignore_files: bool = True,
+        cfg: Optional[List[str]] = None,
+        cfg_mode: str = "filter",
     ) -> Dict[str, Any]:
         """
         分析整个项目（性能优化版本）
@@ -91,65 +208,224 @@ class CallGraphAnalyzerOptimized:
             exclude_dirs: 排除的目录列表
             batch_size: 批量插入数据库的大小
             show_progress: 是否显示进度
+            use_cache: 启用增量缓存，只重新解析内容变化的文件
+            cache_dir: 缓存目录（默认：项目根目录下的 .call_graph_cache）
+            expand_macros: 通过 cargo expand 展开 Rust 宏，补充宏生成的调用
+            macro_call_sites: 将宏生成的调用定位到宏调用位置
+            mir: 编译 Rust crate 获取 MIR，用编译器解析的调用替换语法前端的
+                调用关系（需要能编译项目）
+            operator_calls: 加入运算符重载和解引用对应的 trait 方法调用（Rust）
+            drop_glue: 加入值离开作用域时调用的 Drop::drop 实现（Rust）
+            workspace: 预先构建的 Cargo 工作区（如来自 cargo metadata）；指定时
+                只分析其中选中的 crate，不再在项目目录中查找 Cargo.toml
+            dependency_depth: 加入本地代码调用到的依赖 crate 中的函数，
+                沿调用链最多深入的层数（0 表示不加入）
+            low_memory: 低内存模式，见 streaming 模块
+            spill_dir: 低内存模式的临时文件目录（默认：系统临时目录）
+            languages: 只分析这些语言的源文件（默认：所有支持的语言）
+            ignore_files: 遵循 .gitignore、.ignore 和 .callgraphignore
+            cfg: 生效的 Rust cfg，指定时按它们求值 #[cfg(...)] 条件
+            cfg_mode: filter 排除条件不成立的函数和调用，tag 只标记
         """
         start_time = time.time()
 
         if exclude_dirs is None:
-            exclude_dirs = [
-                "node_modules",
-                ".git",
-                "__pycache__",
-                "venv",
-                "env",
-                "build",
-                "dist",
-                "target",
-                ".idea",
-                ".vscode",
-                "bin",
-                "obj",
-            ]
+            exclude_dirs = list(DEFAULT_EXCLUDE_DIRS)
 
         project_path = Path(project_path).resolve()
 
         print(f"开始分析项目: {project_path}")
         print(f"使用 {self.num_workers} 个工作进程")
+        log_event(
+            "analysis.start",
+            "开始分析项目",
+            project=str(project_path),
+            workers=self.num_workers,
+            low_memory=low_memory,
+        )
 
         # 收集所有源代码文件
-        source_files = self._collect_source_files(project_path, exclude_dirs)
+        phase_start = time.time()
+        source_files = self._collect_source_files(
+            project_path, exclude_dirs, show_progress, ignore_files
+        )
+
+        # Cargo 工作区：成员 crate 可能位于项目目录之外
+        workspace = self._discover_workspace(
+            project_path, exclude_dirs, source_files, workspace, ignore_files
+        )
+        if languages:
+            source_files = [
+                path for path in source_files if detect_language(path) in languages
+            ]
         total_files = len(source_files)
 
         print(f"找到 {total_files} 个源代码文件")
+        log_event(
+            "walk.done",
+            "收集源文件完成",
+            files=total_files,
+            seconds=round(time.time() - phase_start, 3),
+        )
+
+        if use_cache:
+            cache_dir = cache_dir or str(project_path / DEFAULT_CACHE_DIR)
+        else:
+            cache_dir = None
+
+        # 低内存模式：每个工作进程的结果到达后立即把调用引用写入临时文件，
+        # 内存中只保留函数定义和条目
+        store = StreamingStore(spill_dir) if low_memory else None
+
+        # 第一遍：并行提取所有函数定义和调用引用（每个文件只解析一次）
+        print(f"\n第一遍扫描：提取函数定义和调用引用（并行处理）...")
+        phase_start = time.time()
+        results = self._parallel_extract_symbols(
+            source_files, cache_dir, show_progress, store
+        )
 
-        # 第一遍：并行提取所有函数定义
-        print(f"\n第一遍扫描：提取函数定义（并行处理）...")
-        functions_list = self._parallel_extract_functions(source_files, show_progress)
+        # 合并结果（按文件收集顺序，保证结果稳定）
+        by_file = {result["file"]: result for result in results if result}
+        file_results = {path: by_file[path] for path in source_files if path in by_file}
+
+        include_deps = workspace is not None and dependency_depth > 0
+        if workspace:
+            workspace.annotate(file_results)
+        # 低内存模式：annotate 时调用引用还在临时文件中，逐个文件读回处理；
+        # 依赖分析只从可能调用依赖 crate 的调用引用出发
+        frontier = file_results
+        cfg_counts = (0, 0)
+        if store:
+            frontier = {}
+            cfg_counts = annotate_spilled_refs(
+                store,
+                file_results,
+                workspace,
+                cfg,
+                cfg_mode,
+                frontier if include_deps else None,
+            )
+        included = {}
+        if include_deps:
+            included = self._include_dependencies(
+                workspace, frontier, dependency_depth, exclude_dirs, cache_dir
+            )
+            file_results.update(included)
+
+        if cfg:
+            # 低内存模式下本地文件已逐个处理，只剩新加入的依赖文件
+            self._apply_cfg(
+                included if store else file_results, cfg, cfg_mode, cfg_counts
+            )
+        if store:
+            for file_path, result in included.items():
+                store.spill_file(file_path, result)
 
-        # 合并结果
         self.all_functions = []
-        for functions in functions_list:
-            self.all_functions.extend(functions)
+        for result in file_results.values():
+            self.all_functions.extend(result["functions"])
+        assign_stable_ids(self.all_functions, str(project_path))
 
         print(f"共提取 {len(self.all_functions)} 个函数定义")
+        log_event(
+            "parse.done",
+            "第一遍扫描完成",
+            files=len(file_results),
+            functions=len(self.all_functions),
+            cache_hits=sum(1 for result in file_results.values() if result["cached"]),
+            seconds=round(time.time() - phase_start, 3),
+        )
+        implicit = {
+            kind
+            for kind, enabled in (("operator", operator_calls), ("drop", drop_glue))
+            if enabled
+        }
+        # 全程序收集作为值引用的函数，作为间接调用的候选目标；
+        # 源码注释指令：去掉 ignore 的调用引用，edge 声明的调用在第二遍之后加入
+        if store:
+            address_taken, spilled_ignored = finish_spilled_refs(
+                store, file_results, self.all_functions, implicit
+            )
+        else:
+            address_taken = mark_address_taken(file_results, self.all_functions)
+            drop_implicit_refs(file_results, implicit)
+            spilled_ignored = 0
+        if address_taken:
+            print(f"{address_taken} 个函数作为值被引用（可能被间接调用）")
+        ignored, annotated = apply_directives(file_results, self.all_functions)
+        ignored += spilled_ignored
+        if ignored or annotated:
+            print(
+                f"按注释指令忽略 {ignored} 个调用引用，声明 {len(annotated)} 个调用关系"
+            )
+        if cache_dir:
+            AnalysisCache(cache_dir).prune(file_results.keys())
+            hits = sum(1 for result in file_results.values() if result["cached"])
+            print(
+                f"缓存命中 {hits} 个文件，重新解析 {len(file_results) - hits} 个文件"
+            )
 
         # 批量保存函数定义到数据库
         print(f"\n保存函数定义到数据库（批量操作，批次大小：{batch_size}）...")
-        self._batch_insert_symbols(self.all_functions, batch_size, show_progress)
-
-        # 第二遍：并行提取调用关系
-        print(f"\n第二遍扫描：提取调用关系（并行处理）...")
-        calls_list = self._parallel_extract_calls(source_files, show_progress)
-
-        # 合并结果
-        all_calls = []
-        for calls in calls_list:
-            all_calls.extend(calls)
-
-        print(f"共提取 {len(all_calls)} 个调用关系")
+        changed = self._prepare_symbol_changes(file_results, incremental=use_cache)
+        symbols = []
+        for file_path in changed:
+            symbols.extend(file_results[file_path]["functions"])
+        self._batch_insert_symbols(symbols, batch_size, show_progress)
+        # 未变化的文件也重新记录：旧数据库中没有 trait 信息
+        for file_path, result in file_results.items():
+            self.db.upsert_file(
+                file_path, result["hash"], result["language"], result.get("items")
+            )
+
+        # 第二遍：并行解析调用关系
+        phase_start = time.time()
+        if store:
+            total_calls = self._stream_calls(
+                file_results, batch_size, show_progress, store
+            )
+        else:
+            print(f"\n第二遍扫描：解析调用关系（并行处理）...")
+            calls_list = self._parallel_extract_calls(file_results, show_progress)
+
+            # 合并结果
+            all_calls = []
+            for calls in calls_list:
+                all_calls.extend(calls)
+
+            print(f"共提取 {len(all_calls)} 个调用关系")
+
+            # 批量保存调用关系
+            print(f"\n保存调用关系到数据库（批量操作，批次大小：{batch_size}）...")
+            self._batch_insert_calls(all_calls, batch_size, show_progress)
+            total_calls = len(all_calls)
+        if annotated:
+            self._batch_insert_calls(annotated, batch_size, False)
+            total_calls += len(annotated)
+        log_event(
+            "resolve.done",
+            "第二遍扫描完成",
+            relations=total_calls,
+            seconds=round(time.time() - phase_start, 3),
+        )
 
-        # 批量保存调用关系
-        print(f"\n保存调用关系到数据库（批量操作，批次大小：{batch_size}）...")
-        self._batch_insert_calls(all_calls, batch_size, show_progress)
+        # 宏展开：补充宏生成的调用
+        if expand_macros:
+            print(f"\n宏展开：提取宏生成的调用...")
+            expanded = self._expand_macros(
+                project_path, file_results, exclude_dirs, macro_call_sites
+            )
+            self._batch_insert_symbols(expanded["functions"], batch_size, False)
+            self._batch_insert_calls(expanded["calls"], batch_size, False)
+
+        # MIR：用编译器解析的调用替换语法前端推断的 Rust 调用关系
+        if mir:
+            print(f"\nMIR 后端：从编译器的 MIR 提取 Rust 调用关系...")
+            extracted = self._mir_calls(project_path, file_results, exclude_dirs)
+            self.db.delete_relations_from(extracted["callers"])
+            # 注释指令声明的调用关系不来自 MIR，保留
+            kept = [r for r in annotated if r["caller_id"] in extracted["callers"]]
+            self._batch_insert_calls(extracted["calls"] + kept, batch_size, False)
 
         # 生成统计报告
         stats = self.db.get_statistics()
@@ -167,17 +443,236 @@ class CallGraphAnalyzerOptimized:
         for lang, count in stats["by_language"].items():
             print(f"  {lang:15s}: {count:6d} 个符号")
         print("=" * 60)
+        log_event(
+            "analysis.done",
+            "分析完成",
+            files=total_files,
+            symbols=stats["total_symbols"],
+            relations=stats["total_relations"],
+            seconds=round(elapsed_time, 3),
+        )
 
         stats["elapsed_time"] = elapsed_time
         stats["files_per_second"] = total_files / elapsed_time
 
         return stats
 
+    def _stream_calls(
+        self,
+        file_results: Dict[str, Dict[str, Any]],
+        batch_size: int,
+        show_progress: bool,
+        store: StreamingStore,
+    ) -> int:
+        """
+        低内存模式的第二遍扫描：工作进程从临时文件读取调用引用，调用关系
+        分段排序后归并入库；返回调用关系数量（完成后关闭 store）
+        """
+        with store:
+            intern_symbols(self.all_functions)
+            store.order_files(file_results)
+            print(f"\n第二遍扫描：解析调用关系（并行处理，低内存模式）...")
+            self._parallel_extract_calls(file_results, show_progress, store)
+            print(
+                f"共提取 {store.relation_count} 个调用关系"
+                f"（{max(store.run_count, 1)} 个有序段）"
+            )
+            print(f"\n归并调用关系到数据库（批量操作，批次大小：{batch_size}）...")
+            self._batch_insert_calls(
+                store.relations(), batch_size, show_progress, store.relation_count
+            )
+            return store.relation_count
+
+    def _prepare_symbol_changes(
+        self, file_results: Dict[str, Dict[str, Any]], incremental: bool
+    ) -> List[str]:
+        """
+        返回需要（重新）写入符号的文件列表
+
+        增量模式下删除已不存在、内容变化或函数标注变化的文件的旧符号（公开 API
+        等标注依赖其他文件中的 mod 声明和 pub use），并清空调用关系
+        （调用解析依赖全局符号表，需要整体重建）。
+        """
+        if not incremental:
+            return list(file_results)
+
+        db_hashes = self.db.get_file_hashes()
+        for file_path in set(db_hashes) - set(file_results):
+            self.db.delete_file(file_path)
+        stored = self.db.get_symbol_extras()
+        changed = [
+            path
+            for path, result in file_results.items()
+            if db_hashes.get(path) != result["hash"]
+            or symbols_changed(result["functions"], stored)
+        ]
+        for file_path in changed:
+            self.db.delete_file(file_path)
+        self.db.clear_relations()
+        print(f"变化的文件: {len(changed)} 个")
+        return changed
+
+    def _apply_cfg(
+        self,
+        file_results: Dict[str, Dict[str, Any]],
+        cfg: List[str],
+        mode: str,
+        counts: Tuple[int, int] = (0, 0),
+    ):
+        """
+        按 --cfg 求值 Rust 的 #[cfg(...)] 条件（见 cfg 模块）
+
+        counts 为已经逐个文件处理过的部分的计数（见 annotate_spilled_refs），
+        与 file_results 的计数合计输出
+        """
+        functions, refs = CfgSet(cfg).apply(file_results, mode)
+        functions += counts[0]
+        refs += counts[1]
+        action = "排除" if mode == "filter" else "标记"
+        print(f"按 cfg {action}条件不成立的 {functions} 个函数、{refs} 个调用引用")
+        log_event("cfg.done", "求值 cfg 条件", mode=mode, functions=functions, refs=refs)
+
+    def _discover_workspace(
+        self,
+        project_path: Path,
+        exclude_dirs: List[str],
+        source_files: List[str],
+        workspace: Optional[CargoWorkspace] = None,
+        ignore_files: bool = True,
+    ) -> Optional[CargoWorkspace]:
+        """
+        识别 Cargo crate，并把项目目录之外的工作区成员加入源文件列表
+
+        指定了 workspace 时改为只收集其中选中的 crate 和目标的源文件
+        """
+        if workspace:
+            files = []
+            for directory in workspace.source_directories():
+                files.extend(
+                    self._collect_source_files(
+                        directory, exclude_dirs, ignore_files=ignore_files
+                    )
+                )
+            source_files[:] = [
+                path for path in dict.fromkeys(files) if workspace.includes_file(path)
+            ]
+        else:
+            workspace = CargoWorkspace.discover(project_path, exclude_dirs)
+            if not workspace:
+                return None
+            for directory in workspace.external_directories(project_path):
+                source_files.extend(
+                    self._collect_source_files(
+                        directory, exclude_dirs, ignore_files=ignore_files
+                    )
+                )
+        print(
+            f"检测到 {len(workspace.crates)} 个 Cargo crate: "
+            f"{', '.join(sorted(workspace.crates))}"
+        )
+        return workspace
+
+    def _include_dependencies(
+        self,
+        workspace: CargoWorkspace,
+        file_results: Dict[str, Dict[str, Any]],
+        depth: int,
+        exclude_dirs: List[str],
+        cache_dir: Optional[str],
+    ) -> Dict[str, Dict[str, Any]]:
+        """解析本地代码调用到的依赖 crate，返回需要加入的依赖文件结果"""
+
+        def parse_files(files: List[str]) -> Dict[str, Dict[str, Any]]:
+            results = self._parallel_extract_symbols(files, cache_dir, False)
+            by_file = {result["file"]: result for result in results if result}
+            return {path: by_file[path] for path in files if path in by_file}
+
+        print(f"\n依赖 crate：沿调用链最多深入 {depth} 层...")
+        included = include_dependencies(
+            workspace, file_results, depth, parse_files, exclude_dirs
+        )
+        functions = [
+            func for result in included.values() for func in result["functions"]
+        ]
+        crates = {func["extras"]["crate"] for func in functions}
+        print(f"加入 {len(crates)} 个依赖 crate 中的 {len(functions)} 个函数")
+        return included
+
+    def _expand_macros(
+        self,
+        project_path: Path,
+        file_results: Dict[str, Dict[str, Any]],
+        exclude_dirs: List[str],
+        macro_call_sites: bool,
+    ) -> Dict[str, Any]:
+        """展开项目中的 Rust crate，返回宏生成的函数和调用关系"""
+        rust_files = [
+            path
+            for path, result in file_results.items()
+            if result["language"] == "rust"
+        ]
+        if not rust_files:
+            print("没有 Rust 源文件，跳过宏展开")
+            return {"functions": [], "calls": [], "crates": 0}
+
+        expanded = expand_macro_calls(
+            project_path,
+            rust_files,
+            self.all_functions,
+            exclude_dirs,
+            attribute_to_site=macro_call_sites,
+        )
+        print(
+            f"展开 {expanded['crates']} 个 crate 目标，"
+            f"宏生成的函数 {len(expanded['functions'])} 个，"
+            f"宏生成的调用 {len(expanded['calls'])} 个"
+        )
+        return expanded
+
+    def _mir_calls(
+        self,
+        project_path: Path,
+        file_results: Dict[str, Dict[str, Any]],
+        exclude_dirs: List[str],
+    ) -> Dict[str, Any]:
+        """编译项目中的 Rust crate，返回从 MIR 提取的调用关系（已应用 ignore 指令）"""
+        rust_files = [
+            path
+            for path, result in file_results.items()
+            if result["language"] == "rust"
+        ]
+        if not rust_files:
+            print("没有 Rust 源文件，跳过 MIR 分析")
+            return {"calls": [], "callers": set(), "crates": 0, "unmapped": 0}
+
+        foreign = {
+            item["name"]
+            for result in file_results.values()
+            for item in (result.get("items") or {}).get("foreign") or []
+        }
+        extracted = mir_call_edges(
+            project_path, rust_files, self.all_functions, exclude_dirs, foreign
+        )
+        extracted["calls"], ignored = ignore_relations(extracted["calls"], file_results)
+        print(
+            f"编译 {extracted['crates']} 个 crate 目标，"
+            f"替换 {len(extracted['callers'])} 个函数的调用关系，"
+            f"MIR 调用关系 {len(extracted['calls'])} 个"
+            + (f"（按注释指令忽略 {ignored} 个）" if ignored else "")
+        )
+        return extracted
+
     def _collect_source_files(
-        self, project_path: Path, exclude_dirs: List[str]
+        self,
+        project_path: Path,
+        exclude_dirs: List[str],
+        show_progress: bool = False,
+        ignore_files: bool = True,
     ) -> List[str]:
-        """收集所有源代码文件"""
+        """收集所有源代码文件（ignore_files 为 True 时遵循忽略文件，见 ignore 模块）"""
         source_files = []
+        progress = ProgressBar("扫描文件", None, show_progress, every=200)
+        ignore = IgnoreRules(project_path) if ignore_files else None
 
         # 收集所有支持的扩展名
         supported_extensions = set()
@@ -187,76 +682,120 @@ class CallGraphAnalyzerOptimized:
         for root, dirs, files in os.walk(project_path):
             # 排除指定目录
             dirs[:] = [
-                d for d in dirs if d not in exclude_dirs and not d.startswith(".")
+                d
+                for d in dirs
+                if d not in exclude_dirs
+                and not d.startswith(".")
+                and not (ignore and ignore.is_ignored(os.path.join(root, d), True))
             ]
 
             for file in files:
                 if any(file.endswith(ext) for ext in supported_extensions):
                     file_path = os.path.join(root, file)
+                    if ignore and ignore.is_ignored(file_path, False):
+                        continue
                     source_files.append(file_path)
+                    progress.update(len(source_files))
 
+        progress.close()
         return source_files
 
-    def _parallel_extract_functions(
-        self, source_files: List[str], show_progress: bool = True
-    ) -> List[List[Dict]]:
+    def _parallel_extract_symbols(
+        self,
+        source_files: List[str],
+        cache_dir: Optional[str] = None,
+        show_progress: bool = True,
+        store: Optional[StreamingStore] = None,
+    ) -> List[Optional[Dict]]:
         """
-        并行提取函数定义
+        并行解析文件，提取函数定义和调用引用
+
+        指定 store 时（低内存模式）每个结果到达后立即把调用引用写入 store
         """
         total = len(source_files)
         results = []
-
-        with Pool(processes=self.num_workers) as pool:
-            if show_progress:
-                # 使用 imap 可以显示进度
-                processed = 0
+        args_list = [(file_path, cache_dir) for file_path in source_files]
+        chunksize = self._chunksize(total)
+
+        with Pool(
+            processes=self.num_workers,
+            initializer=_init_symbols_worker,
+            initargs=(loaded_plugins(),),
+        ) as pool:
+            # 使用 imap 可以显示进度
+            with ProgressBar("提取函数", total, show_progress, every=50) as progress:
                 for result in pool.imap_unordered(
-                    _process_file_functions, source_files, chunksize=10
+                    _process_file_symbols, args_list, chunksize=chunksize
                 ):
                     results.append(result)
-                    processed += 1
-                    if processed % 50 == 0 or processed == total:
-                        self._print_progress(processed, total, "提取函数")
-                print()  # 换行
-            else:
-                results = pool.map(_process_file_functions, source_files, chunksize=10)
+                    progress.update()
+                    if result:
+                        log_event(
+                            "parse.file",
+                            "解析文件",
+                            logging.DEBUG,
+                            file=result["file"],
+                            functions=len(result["functions"]),
+                            call_refs=len(result["call_refs"]),
+                            cached=result["cached"],
+                        )
+                        if store:
+                            store.spill_file(result["file"], result)
 
         return results
 
     def _parallel_extract_calls(
-        self, source_files: List[str], show_progress: bool = True
+        self,
+        file_results: Dict[str, Dict[str, Any]],
+        show_progress: bool = True,
+        store: Optional[StreamingStore] = None,
     ) -> List[List[Dict]]:
         """
-        并行提取调用关系
+        并行解析调用关系
+
+        指定 store 时（低内存模式）工作进程从临时文件读取调用引用，解析出的
+        调用关系随完成随写入 store，返回空列表
         """
-        total = len(source_files)
+        total = len(file_results)
         results = []
 
-        # 创建函数字典（用于传递给工作进程）
-        # 使用 dict 减少数据传输量
-        functions_dict = {
-            f"{func['name']}:{func['file']}": func for func in self.all_functions
-        }
-
-        # 准备参数
-        args_list = [(file_path, functions_dict) for file_path in source_files]
-
-        with Pool(processes=self.num_workers) as pool:
-            if show_progress:
-                processed = 0
+        # 准备参数（符号表通过进程初始化函数只传输一次）
+        args_list = [
+            (
+                file_path,
+                result["language"],
+                store.call_refs_path(file_path) if store else result["call_refs"],
+            )
+            for file_path, result in file_results.items()
+        ]
+        chunksize = self._chunksize(total)
+
+        with Pool(
+            processes=self.num_workers,
+            initializer=_init_calls_worker,
+            initargs=(self.all_functions, loaded_plugins()),
+        ) as pool:
+            with ProgressBar("提取调用", total, show_progress, every=50) as progress:
                 for result in pool.imap_unordered(
-                    _process_file_calls, args_list, chunksize=10
+                    _process_file_calls, args_list, chunksize=chunksize
                 ):
-                    results.append(result)
-                    processed += 1
-                    if processed % 50 == 0 or processed == total:
-                        self._print_progress(processed, total, "提取调用")
-                print()  # 换行
-            else:
-                results = pool.map(_process_file_calls, args_list, chunksize=10)
+                    if store:
+                        store.add_relations(result)
+                    else:
+                        results.append(result)
+                    progress.update()
 
         return results
 
+    def _chunksize(self, total: int) -> int:
+        """
+        计算任务分块大小
+
+        每个进程大约分到 4 块：块太小时进程间通信开销大，
+        块太大时文件大小不均会导致负载不均衡
+        """
+        return max(1, min(100, total // (self.num_workers * 4)))
+
     def _batch_insert_symbols(
         self, symbols: List[Dict], batch_size: int, show_progress: bool = True
     ):
@@ -265,6 +804,7 @@ class CallGraphAnalyzerOptimized:
         """
         total = len(symbols)
         inserted = 0
+        progress = ProgressBar("保存符号", total, show_progress, every=500)
 
         # 使用事务批量插入
         self.db.conn.execute("BEGIN TRANSACTION")
@@ -276,62 +816,52 @@ class CallGraphAnalyzerOptimized:
                     self.db.insert_symbol(symbol)
 
                 inserted += len(batch)
-
-                if show_progress and (inserted % 500 == 0 or inserted == total):
-                    self._print_progress(inserted, total, "保存符号")
+                progress.update(inserted)
 
             self.db.conn.commit()
-
-            if show_progress:
-                print()  # 换行
+            progress.close()
         except Exception as e:
             self.db.conn.rollback()
             raise e
 
     def _batch_insert_calls(
-        self, calls: List[Dict], batch_size: int, show_progress: bool = True
+        self,
+        calls: Iterable[Dict],
+        batch_size: int,
+        show_progress: bool = True,
+        total: Optional[int] = None,
     ):
         """
         批量插入调用关系到数据库
+
+        calls 可以是迭代器（如低内存模式的归并结果），此时由 total 给出总数
         """
-        total = len(calls)
+        if total is None:
+            total = len(calls)
+        calls = iter(calls)
         inserted = 0
+        progress = ProgressBar("保存调用", total, show_progress, every=1000)
 
         # 使用事务批量插入
         self.db.conn.execute("BEGIN TRANSACTION")
 
         try:
-            for i in range(0, total, batch_size):
-                batch = calls[i : i + batch_size]
+            while True:
+                batch = list(islice(calls, batch_size))
+                if not batch:
+                    break
                 for call in batch:
                     self.db.insert_call_relation(call)
 
                 inserted += len(batch)
-
-                if show_progress and (inserted % 1000 == 0 or inserted == total):
-                    self._print_progress(inserted, total, "保存调用")
+                progress.update(inserted)
 
             self.db.conn.commit()
-
-            if show_progress:
-                print()  # 换行
+            progress.close()
         except Exception as e:
             self.db.conn.rollback()
             raise e
 
-    def _print_progress(self, current: int, total: int, task: str):
-        """
-        打印进度条
-        """
-        percent = current / total * 100
-        bar_length = 40
-        filled = int(bar_length * current / total)
-        bar = "█" * filled + "░" * (bar_length - filled)
-
-        print(
-            f"\r{task}: [{bar}] {current}/{total} ({percent:.1f}%)", end="", flush=True
-        )
-
     # 保留原有的查询方法
     def query_callers(self, function_name: str) -> List[Dict[str, Any]]:
         """查询调用指定函数的所有函数"""
@@ -359,59 +889,115 @@ class CallGraphAnalyzerOptimized:
         """获取统计信息"""
         return self.db.get_statistics()
 
-    def export_graph(self, output_format: str = "dot") -> str:
-        """导出调用图"""
-        if output_format == "dot":
-            return self._export_dot()
-        else:
-            raise ValueError(f"不支持的导出格式: {output_format}")
-
-    def _export_dot(self) -> str:
-        """导出为Graphviz DOT格式"""
-        lines = ["digraph CallGraph {"]
-        lines.append("  rankdir=LR;")
-        lines.append("  node [shape=box];")
-        lines.append('  graph [fontname="Arial", fontsize=10];')
-        lines.append('  node [fontname="Arial", fontsize=9];')
-        lines.append('  edge [fontname="Arial", fontsize=8];')
-
-        # 添加所有函数节点
-        symbols = self.db.get_symbols_by_kind("function")
-        valid_node_ids = set()
-        for symbol in symbols:
-            node_id = symbol["id"]
-            valid_node_ids.add(node_id)
-            # 转义特殊字符
-            name = symbol["name"].replace('"', '\\"')
-            file_path = symbol["file"].replace('"', '\\"')
-            label = f"{name}\\n({file_path}:{symbol.get('start_line', '?')})"
-            lines.append(f'  "{node_id}" [label="{label}"];')
-
-        # 添加调用边（只添加有效的边）
-        cursor = self.db.conn.cursor()
-        cursor.execute(
-            """
-            SELECT caller_id, callee_id, caller_name, callee_name 
-            FROM call_relations 
-            WHERE caller_id IS NOT NULL AND callee_id IS NOT NULL
+    def export_view(
+        self,
+        focus: Optional[List[str]] = None,
+        depth: int = 2,
+        granularity: str = "function",
+        symbol_filter: Optional[SymbolFilter] = None,
+        monomorphize: bool = False,
+        condense: bool = False,
+        profile: Optional[List[Tuple[List[str], int]]] = None,
+        coverage: Optional[Dict[str, Any]] = None,
+        max_nodes: Optional[int] = None,
+        prune_by: str = DEFAULT_PRUNE_CENTRALITY,
+        stable_ids: bool = False,
+        public_only: bool = False,
+    ) -> CallGraph:
         """
-        )
-        edge_count = 0
-        for row in cursor.fetchall():
-            caller_id = row["caller_id"]
-            callee_id = row["callee_id"]
-            # 只添加两端节点都存在的边
-            if caller_id in valid_node_ids and callee_id in valid_node_ids:
-                lines.append(f'  "{caller_id}" -> "{callee_id}";')
-                edge_count += 1
-
-        lines.append("}")
-
-        # 返回结果，包含统计信息作为注释
-        result = "\n".join(lines)
-        print(f"导出成功: {len(valid_node_ids)} 个节点, {edge_count} 条边")
+        按导出选项处理后的调用图
 
-        return result
+        Args:
+            focus: 焦点函数名或限定名；指定时只导出它们 depth 跳以内的
+                调用者和被调用者
+            granularity: function（函数级）、module（每个模块一个节点，
+                模块间的调用合并为边）或 type（每个类型一个节点，见
+                CallGraph.type_graph）
+            symbol_filter: 按文件路径和限定名包含/排除函数，在其他处理之前应用
+            monomorphize: 按调用点的类型实参把泛型函数拆分为单态化节点
+            condense: 把每个强连通分量（递归环）缩成一个节点，在最后应用，
+                与 module 粒度一起使用时缩并模块间的循环依赖
+            profile: 剖析数据的调用栈（见 profiling.load_profile），在过滤之后、
+                选取焦点之前叠加样本数；只能用于函数级视图
+            coverage: 覆盖率报告（见 coverage_report.load_coverage），同 profile
+            max_nodes: 节点数超过该值时只保留按 prune_by 中心度排名靠前的函数，
+                被裁掉的函数合并为占位节点（见 pruning.prune），在缩点之后应用
+            stable_ids: 把节点 ID 换成稳定 ID（见 CallGraph.stable_id），在最后应用
+            public_only: 只保留公开函数，经过私有函数的调用链合并为边（见
+                CallGraph.public_only），在叠加数据之后、选取焦点之前应用
+        """
+        overlay = profile is not None or coverage is not None
+        if overlay and (granularity != "function" or condense):
+            raise ValueError(
+                "剖析数据和覆盖率数据只能叠加到函数级视图，不能与 module、type 粒度或缩点同时使用"
+            )
+        graph = CallGraph.from_db(self.db)
+        if symbol_filter:
+            graph = symbol_filter.apply(graph)
+        if profile is not None:
+            print(profile_summary(graph, apply_profile(graph, profile)))
+        if coverage is not None:
+            print(coverage_summary(apply_coverage(graph, coverage)))
+        if public_only:
+            graph = graph.public_only()
+        if focus:
+            roots = []
+            for name in focus:
+                matches = graph.resolve(name)
+                if not matches:
+                    raise ValueError(f"没有找到函数: {name}{suggestion(graph, name)}")
+                roots.extend(matches)
+            graph = graph.subgraph(neighborhood(graph, roots, depth))
+        if monomorphize:
+            graph = graph.monomorphize()
+        if granularity == "module":
+            graph = graph.module_graph()
+        elif granularity == "type":
+            graph = graph.type_graph()
+        if condense:
+            graph = condensation(graph)
+        if max_nodes is not None:
+            graph = prune(graph, max_nodes, prune_by)
+        if stable_ids:
+            graph = graph.with_stable_ids()
+        return graph
+
+    def export_graph(
+        self,
+        output_format: str = "dot",
+        focus: Optional[List[str]] = None,
+        depth: int = 2,
+        granularity: str = "function",
+        symbol_filter: Optional[SymbolFilter] = None,
+        monomorphize: bool = False,
+        condense: bool = False,
+        profile: Optional[List[Tuple[List[str], int]]] = None,
+        coverage: Optional[Dict[str, Any]] = None,
+        max_nodes: Optional[int] = None,
+        prune_by: str = DEFAULT_PRUNE_CENTRALITY,
+        stable_ids: bool = False,
+        public_only: bool = False,
+        **options,
+    ) -> str:
+        """
+        导出调用图（选项见 exporters 模块中对应格式的导出函数，其余参数
+        同 export_view）
+        """
+        graph = self.export_view(
+            focus=focus,
+            depth=depth,
+            granularity=granularity,
+            symbol_filter=symbol_filter,
+            monomorphize=monomorphize,
+            condense=condense,
+            profile=profile,
+            coverage=coverage,
+            max_nodes=max_nodes,
+            prune_by=prune_by,
+            stable_ids=stable_ids,
+            public_only=public_only,
+        )
+        return export_graph(graph, output_format, **options)
 
     def close(self):
         """关闭分析器"""
@@ -0,0 +1,279 @@
+"""
+库接口
+在 Python 代码中直接分析项目并得到内存调用图，无需经过 CLI 和数据库文件
+"""
+
+import contextlib
+import io
+import os
+from dataclasses import dataclass, field, replace
+from typing import List, Optional, Union
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import is_test_code
+    from .analyzer import CallGraphAnalyzer
+    from .database import CallGraphDB
+    from .dependencies import DEFAULT_DEPENDENCY_DEPTH
+    from .filters import SymbolFilter
+    from .graph import CallGraph
+    from .parsers import LANGUAGE_CONFIG
+    from .plugins import load_plugins
+except ImportError:
+    from algorithms import is_test_code
+    from analyzer import CallGraphAnalyzer
+    from database import CallGraphDB
+    from dependencies import DEFAULT_DEPENDENCY_DEPTH
+    from filters import SymbolFilter
+    from graph import CallGraph
+    from parsers import LANGUAGE_CONFIG
+    from plugins import load_plugins
+
+
+@dataclass
+class AnalysisConfig:
+    """
+    分析配置
+
+    Attributes:
+        root: 项目路径
+        languages: 只分析这些语言（如 rust、python；默认所有支持的语言）
+        exclude_dirs: 排除的目录列表（默认同 analyze 命令）
+        ignore_files: 遵循 .gitignore、.ignore 和 .callgraphignore
+        exclude_tests: 从结果中去掉测试代码（见 algorithms.is_test_code）
+        follow_macros: 通过 cargo expand 展开 Rust 宏，补充宏生成的调用
+        macro_call_sites: 将宏生成的调用定位到宏调用位置
+        mir: 编译 Rust crate，用 MIR 中的调用替换语法分析推断的调用关系
+        operator_calls: 加入 Rust 运算符重载和解引用对应的 trait 方法调用
+        drop_glue: 加入 Rust 值离开作用域时调用的 Drop::drop 实现
+        include_deps: 加入 Rust 代码调用到的依赖 crate 中的函数
+        deps_depth: 沿调用链最多深入依赖的层数
+        use_cache: 启用增量分析缓存
+        cache_dir: 缓存目录（默认：项目根目录下的 .call_graph_cache）
+        include / exclude: 函数包含/排除模式，规则同 --include/--exclude
+        phase: 只保留运行时（runtime）或编译期（compile-time）的函数
+        edge_kinds: 只保留这些类型的调用边（见 graph.EdgeKind）
+        plugins: 额外加载的语言插件模块（entry point 插件总是自动加载）
+        database: 同时把结果写入该数据库文件；默认只使用内存数据库
+        quiet: 不输出分析进度
+    """
+
+    root: Optional[str] = None
+    languages: List[str] = field(default_factory=list)
+    exclude_dirs: Optional[List[str]] = None
+    ignore_files: bool = True
+    exclude_tests: bool = False
+    follow_macros: bool = False
+    macro_call_sites: bool = False
+    mir: bool = False
+    operator_calls: bool = False
+    drop_glue: bool = False
+    include_deps: bool = False
+    deps_depth: int = DEFAULT_DEPENDENCY_DEPTH
+    use_cache: bool = False
+    cache_dir: Optional[str] = None
+    include: List[str] = field(default_factory=list)
+    exclude: List[str] = field(default_factory=list)
+    phase: Optional[str] = None
+    edge_kinds: List[str] = field(default_factory=list)
+    plugins: List[str] = field(default_factory=list)
+    database: Optional[str] = None
+    quiet: bool = True
+
+    def validate(self):
+        """检查配置；插件提供的语言需在加载插件之后才能识别"""
+        if not self.root:
+            raise ValueError("没有指定项目路径")
+        if not os.path.isdir(self.root):
+            raise ValueError(f"项目路径不存在: {self.root}")
+        unknown = [name for name in self.languages if name not in LANGUAGE_CONFIG]
+        if unknown:
+            raise ValueError(f"不支持的语言: {', '.join(unknown)}")
+        if self.include_deps and self.deps_depth < 1:
+            raise ValueError("依赖深度至少为 1")
+        SymbolFilter(self.include, self.exclude, self.phase, self.edge_kinds)
+
+
+class AnalysisBuilder:
+    """
+    链式构建分析配置::
+
+        graph = (
+            AnalysisBuilder()
+            .root("/path/to/project")
+            .language("rust")
+            .exclude_tests()
+            .follow_macros()
+            .analyze()
+        )
+    """
+
+    def __init__(self, config: Optional[AnalysisConfig] = None):
+        self._config = AnalysisConfig()
+        if config:
+            self._config = _copy_config(config)
+
+    def root(self, path: str) -> "AnalysisBuilder":
+        self._config.root = path
+        return self
+
+    def language(self, *names: str) -> "AnalysisBuilder":
+        """只分析指定语言，可多次调用"""
+        self._config.languages.extend(name.lower() for name in names)
+        return self
+
+    def exclude_dirs(self, *dirs: str) -> "AnalysisBuilder":
+        """替换默认的排除目录列表"""
+        self._config.exclude_dirs = list(dirs)
+        return self
+
+    def ignore_files(self, enabled: bool = True) -> "AnalysisBuilder":
+        """是否跳过忽略文件（.gitignore 等）中的路径（默认跳过）"""
+        self._config.ignore_files = enabled
+        return self
+
+    def exclude_tests(self, enabled: bool = True) -> "AnalysisBuilder":
+        self._config.exclude_tests = enabled
+        return self
+
+    def follow_macros(
+        self, enabled: bool = True, call_sites: bool = False
+    ) -> "AnalysisBuilder":
+        """展开 Rust 宏；call_sites 为 True 时把宏生成的调用定位到宏调用位置"""
+        self._config.follow_macros = enabled
+        self._config.macro_call_sites = call_sites
+        return self
+
+    def mir(self, enabled: bool = True) -> "AnalysisBuilder":
+        """编译 Rust crate，使用 MIR 中由编译器解析的调用（需要能编译项目）"""
+        self._config.mir = enabled
+        return self
+
+    def operator_calls(self, enabled: bool = True) -> "AnalysisBuilder":
+        """加入运算符重载（a + b）和解引用（*a、自动解引用）的隐式调用"""
+        self._config.operator_calls = enabled
+        return self
+
+    def drop_glue(self, enabled: bool = True) -> "AnalysisBuilder":
+        """加入值离开作用域（或 drop(x)）时调用的 Drop::drop 实现"""
+        self._config.drop_glue = enabled
+        return self
+
+    def include_deps(
+        self, enabled: bool = True, depth: int = DEFAULT_DEPENDENCY_DEPTH
+    ) -> "AnalysisBuilder":
+        """加入调用到的依赖 crate 中的函数，沿调用链最多深入 depth 层"""
+        self._config.include_deps = enabled
+        self._config.deps_depth = depth
+        return self
+
+    def cache(
+        self, enabled: bool = True, cache_dir: Optional[str] = None
+    ) -> "AnalysisBuilder":
+        self._config.use_cache = enabled
+        self._config.cache_dir = cache_dir
+        return self
+
+    def include(self, *patterns: str) -> "AnalysisBuilder":
+        self._config.include.extend(patterns)
+        return self
+
+    def exclude(self, *patterns: str) -> "AnalysisBuilder":
+        self._config.exclude.extend(patterns)
+        return self
+
+    def phase(self, phase: str) -> "AnalysisBuilder":
+        """只保留运行时（runtime）或编译期（compile-time）的函数"""
+        self._config.phase = phase
+        return self
+
+    def edge_kinds(self, *kinds: str) -> "AnalysisBuilder":
+        """只保留这些类型（见 graph.EdgeKind）的调用边，可多次调用"""
+        self._config.edge_kinds.extend(kinds)
+        return self
+
+    def plugin(self, *modules: str) -> "AnalysisBuilder":
+        self._config.plugins.extend(modules)
+        return self
+
+    def database(self, db_path: str) -> "AnalysisBuilder":
+        self._config.database = db_path
+        return self
+
+    def quiet(self, enabled: bool = True) -> "AnalysisBuilder":
+        self._config.quiet = enabled
+        return self
+
+    def build(self) -> AnalysisConfig:
+        """返回配置的副本，之后对构建器的修改不影响它"""
+        return _copy_config(self._config)
+
+    def analyze(self) -> CallGraph:
+        return analyze(self.build())
+
+
+def _copy_config(config: AnalysisConfig, **changes) -> AnalysisConfig:
+    """复制配置（列表字段也复制）"""
+    copied = replace(config, **changes)
+    for name in ("languages", "include", "exclude", "edge_kinds", "plugins"):
+        setattr(copied, name, list(getattr(copied, name)))
+    return copied
+
+
+def analyze(path: Union[str, AnalysisConfig], **options) -> CallGraph:
+    """
+    分析项目并返回调用图
+
+    Args:
+        path: 项目路径，或完整的 AnalysisConfig
+        options: 覆盖 AnalysisConfig 的其他字段（如 exclude_dirs、database、
+            plugins、quiet）
+    """
+    if isinstance(path, AnalysisConfig):
+        config = _copy_config(path, **options)
+    else:
+        config = AnalysisConfig(root=path, **options)
+
+    load_plugins(config.plugins)
+    config.validate()
+
+    log = contextlib.nullcontext()
+    if config.quiet:
+        log = contextlib.redirect_stdout(io.StringIO())
+    with CallGraphAnalyzer(config.database or ":memory:") as analyzer, log:
+        analyzer.analyze_project(
+            config.root,
+            exclude_dirs=config.exclude_dirs,
+            use_cache=config.use_cache,
+            cache_dir=config.cache_dir,
+            expand_macros=config.follow_macros,
+            macro_call_sites=config.macro_call_sites,
+            mir=config.mir,
+            operator_calls=config.operator_calls,
+            drop_glue=config.drop_glue,
+            languages=config.languages or None,
+            dependency_depth=config.deps_depth if config.include_deps else 0,
+            show_progress=not config.quiet,
+            ignore_files=config.ignore_files,
+        )
+        graph = CallGraph.from_db(analyzer.db)
+    graph.root = os.path.realpath(config.root)
+
+    if config.exclude_tests:
+        graph = graph.subgraph(
+            node_id for node_id in graph.nodes if not is_test_code(graph, node_id)
+        )
+    return SymbolFilter(
+        config.include, config.exclude, config.phase, config.edge_kinds
+    ).apply(graph)
+
+
+def load(db_path: str) -> CallGraph:
+    """从已有的分析数据库加载调用图"""
+    if not os.path.exists(db_path):
+        raise FileNotFoundError(f"数据库不存在: {db_path}")
+    db = CallGraphDB(db_path)
+    try:
+        return CallGraph.from_db(db)
+    finally:
+        db.close()
@@ -0,0 +1,122 @@
+"""
+模块依赖基线
+把当前的模块间依赖记录为已批准的基线文件（JSON，适合提交到仓库并在代码审查
+中查看差异），之后在 CI 中检查是否出现了基线之外的新依赖：已有的依赖可以逐步
+清理，新的依赖需要显式更新基线
+"""
+
+import json
+from pathlib import Path
+from typing import Any, Dict, List, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .diff import module_dependencies
+    from .graph import CallGraph
+except ImportError:
+    from diff import module_dependencies
+    from graph import CallGraph
+
+# 基线文件格式版本（格式不兼容地变化时递增）
+BASELINE_VERSION = 1
+
+# 默认的基线文件名（位于当前目录）
+DEFAULT_BASELINE_FILE = "callgraph-baseline.json"
+
+# 每个新增依赖列出的示例调用数量
+BASELINE_EXAMPLES = 3
+
+
+def dependency_calls(graph: CallGraph) -> Dict[Tuple[str, str], List[Dict[str, Any]]]:
+    """
+    每个模块依赖 (调用方模块, 被调用方模块) 上的函数调用：
+    [{"caller", "callee", "file", "line"}]，按文件和行号排序
+    """
+    calls: Dict[Tuple[str, str], List[Dict[str, Any]]] = {}
+    for edge in graph.edges():
+        caller_id, callee_id = edge["caller_id"], edge["callee_id"]
+        key = (graph.module_name(caller_id), graph.module_name(callee_id))
+        if key[0] == key[1]:
+            continue
+        for site in graph.call_sites(caller_id, callee_id):
+            calls.setdefault(key, []).append(
+                {
+                    "caller": graph.qualified_name(caller_id),
+                    "callee": graph.qualified_name(callee_id),
+                    "file": site.get("caller_file"),
+                    "line": site.get("call_site_line"),
+                }
+            )
+    for sites in calls.values():
+        sites.sort(key=lambda s: (s["file"] or "", s["line"] or 0, s["callee"]))
+    return calls
+
+
+def record_baseline(graph: CallGraph) -> Dict[str, Any]:
+    """
+    当前调用图的基线：{"version", "dependencies": [{"from", "to", "calls"}]}，
+    calls 为依赖上的调用点数量（只供参考，检查时不比较），按模块名排序
+    """
+    calls = dependency_calls(graph)
+    return {
+        "version": BASELINE_VERSION,
+        "dependencies": [
+            {"from": caller, "to": callee, "calls": len(calls[(caller, callee)])}
+            for caller, callee in sorted(module_dependencies(graph))
+        ],
+    }
+
+
+def save_baseline(path: str, graph: CallGraph) -> Dict[str, Any]:
+    """记录基线并写入 path，返回写入的内容"""
+    baseline = record_baseline(graph)
+    text = json.dumps(baseline, ensure_ascii=False, indent=2)
+    Path(path).write_text(text + "\n", encoding="utf-8")
+    return baseline
+
+
+def load_baseline(path: str) -> Set[Tuple[str, str]]:
+    """读取基线文件中已批准的模块依赖"""
+    try:
+        data = json.loads(Path(path).read_text(encoding="utf-8"))
+    except FileNotFoundError as e:
+        raise ValueError(
+            f"基线文件不存在: {path}（先运行 baseline record 记录基线）"
+        ) from e
+    except ValueError as e:
+        raise ValueError(f"无法解析基线文件 {path}: {e}") from e
+    if not isinstance(data, dict) or data.get("version") != BASELINE_VERSION:
+        raise ValueError(
+            f"不支持的基线文件格式: {path}（需要 version {BASELINE_VERSION}）"
+        )
+    try:
+        return {(item["from"], item["to"]) for item in data["dependencies"]}
+    except (KeyError, TypeError) as e:
+        raise ValueError(f"基线文件 {path} 中的依赖格式错误: {e}") from e
+
+
+def check_baseline(
+    graph: CallGraph, approved: Set[Tuple[str, str]]
+) -> Dict[str, List[Any]]:
+    """
+    与基线比较：new 为不在基线中的新依赖 {"from", "to", "calls", "examples"}
+    （examples 为前 BASELINE_EXAMPLES 个调用），stale 为基线中已经不存在的依赖
+    （可以重新记录基线收紧），均按模块名排序
+    """
+    calls = dependency_calls(graph)
+    current = module_dependencies(graph)
+    return {
+        "new": [
+            {
+                "from": caller,
+                "to": callee,
+                "calls": len(calls[(caller, callee)]),
+                "examples": calls[(caller, callee)][:BASELINE_EXAMPLES],
+            }
+            for caller, callee in sorted(current - approved)
+        ],
+        "stale": [
+            {"from": caller, "to": callee}
+            for caller, callee in sorted(approved - current)
+        ],
+    }
@@ -0,0 +1,395 @@
+"""
+二进制后端
+从编译后的二进制文件提取调用图：objdump 反汇编得到函数符号和调用指令，
+-l 读取 DWARF 行号信息得到函数和调用点所在的源码位置
+
+得到的是单态化、内联之后的调用图：泛型函数的每个实例化是单独的节点，
+trait 对象和函数指针的调用为间接调用（没有目标，只计数），被内联的函数
+不出现（其中的调用归到内联到的函数）。需要带调试信息编译（debug 构建，
+或 release 构建加 debug = true / -C debuginfo=1）
+"""
+
+import hashlib
+import os
+import re
+import subprocess
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .database import CallGraphDB
+except ImportError:
+    from database import CallGraphDB
+
+OBJDUMP_TIMEOUT = 600
+
+# 函数开始：0000000000007b30 <my_app::main>:
+SYMBOL_LINE = re.compile(r"^(?P<address>[0-9a-fA-F]+) <(?P<symbol>.+)>:$")
+
+# -l 输出的源码位置：/path/to/src/main.rs:12（可能带 (discriminator N)）
+SOURCE_LINE = re.compile(
+    r"^(?P<file>/?[^\s:][^:]*):(?P<line>\d+)(?: \(discriminator \d+\))?$"
+)
+
+# 指令：    7b34:	call   7b60 <my_app::run>
+INSTRUCTION_LINE = re.compile(
+    r"^\s+(?P<address>[0-9a-fA-F]+):\s+(?P<mnemonic>[a-z][\w.]*)\s*(?P<operands>.*)$"
+)
+
+# 直接调用 / 跳转的目标：7b60 <my_app::run> 或 7b60 <my_app::run+0x10>
+# （RISC-V 等前面还有寄存器操作数：ra,10478 <foo>）
+DIRECT_TARGET = re.compile(
+    r"(?:^|[\s,])(?:0x)?(?P<address>[0-9a-fA-F]+) <(?P<symbol>.+)>\s*$"
+)
+
+SYMBOL_OFFSET = re.compile(r"\+0x[0-9a-fA-F]+$")
+
+# 调用指令（x86、AArch64、ARM、RISC-V、PowerPC）
+CALL_MNEMONICS = {"call", "callq", "calll", "bl", "blx", "jal", "jalr", "bctrl", "blr"}
+# 跳到另一个函数开头的跳转为尾调用
+JUMP_MNEMONICS = {"jmp", "jmpq", "b", "j", "tail"}
+
+RUST_HASH = re.compile(r"::h[0-9a-f]{16}$")
+
+LANGUAGE_EXTENSIONS = {
+    ".rs": "rust",
+    ".c": "c",
+    ".h": "c",
+    ".cc": "cpp",
+    ".cpp": "cpp",
+    ".cxx": "cpp",
+    ".hpp": "cpp",
+    ".go": "go",
+}
+
+
+def run_objdump(binary: str, objdump: str = "objdump") -> str:
+    """
+    反汇编二进制文件（-d 反汇编、-l 源码行号、-C 还原符号名）
+
+    Raises:
+        ValueError: 没有找到 objdump 或反汇编失败
+    """
+    command = [objdump, "-d", "-l", "-C", "--no-show-raw-insn", binary]
+    try:
+        result = subprocess.run(
+            command, capture_output=True, text=True, timeout=OBJDUMP_TIMEOUT
+        )
+    except FileNotFoundError as e:
+        raise ValueError(f"没有找到 {objdump}（需要 binutils 或 llvm-objdump）") from e
+    except subprocess.TimeoutExpired as e:
+        raise ValueError(f"{' '.join(command)} 超时") from e
+    if result.returncode != 0:
+        lines = result.stderr.strip().splitlines()
+        raise ValueError(f"反汇编失败: {lines[-1] if lines else binary}")
+    return result.stdout
+
+
+def _call_target(
+    mnemonic: str, operands: str
+) -> Optional[Tuple[Optional[int], str]]:
+    """
+    指令的调用目标：(目标地址, 目标符号)，间接调用为 (None, "")；不是调用
+    指令、或是函数内的跳转（目标带偏移）时返回 None
+    """
+    is_call = mnemonic in CALL_MNEMONICS
+    if not (is_call or mnemonic in JUMP_MNEMONICS):
+        return None
+    operands = operands.strip()
+    match = DIRECT_TARGET.search(operands)
+    if match:
+        if SYMBOL_OFFSET.search(match.group("symbol")):
+            return None
+        return (int(match.group("address"), 16), match.group("symbol"))
+    # call *%rax、blr x8、jalr a5：间接调用；间接跳转多为 switch 跳转表
+    if is_call and (operands.startswith("*") or mnemonic in ("blr", "jalr", "bctrl")):
+        return (None, "")
+    return None
+
+
+def parse_objdump(text: str) -> List[Dict[str, Any]]:
+    """
+    解析 objdump -d -l 的输出
+
+    Returns:
+        函数列表：address、symbol（还原后的符号名）、locations（[(文件, 行号)]，
+        按出现顺序）、calls（[(目标地址, 调用点文件, 行号, 指令, 是否尾调用)]）、
+        indirect（间接调用的数量）
+    """
+    functions = []
+    current: Optional[Dict[str, Any]] = None
+    location: Optional[Tuple[str, int]] = None
+    for line in text.splitlines():
+        if not line.strip():
+            continue
+        match = SYMBOL_LINE.match(line)
+        if match:
+            current = {
+                "address": int(match.group("address"), 16),
+                "symbol": match.group("symbol"),
+                "locations": [],
+                "calls": [],
+                "indirect": 0,
+            }
+            functions.append(current)
+            location = None
+            continue
+        if current is None:
+            continue
+        match = SOURCE_LINE.match(line.strip())
+        if match and not line[0].isspace():
+            location = (match.group("file"), int(match.group("line")))
+            current["locations"].append(location)
+            continue
+        match = INSTRUCTION_LINE.match(line)
+        if not match:
+            continue
+        target = _call_target(match.group("mnemonic"), match.group("operands"))
+        if target is None:
+            continue
+        address, symbol = target
+        if address is None:
+            current["indirect"] += 1
+            continue
+        instruction = f"{match.group('mnemonic')} {symbol}"
+        tail = match.group("mnemonic") in JUMP_MNEMONICS
+        file_path, line_number = location or (None, None)
+        current["calls"].append((address, file_path, line_number, instruction, tail))
+    return functions
+
+
+def _split_path(path: str) -> List[str]:
+    """按顶层的 :: 分割（不分割泛型参数和 <T as Trait> 中的 ::）"""
+    parts = []
+    depth = 0
+    current = ""
+    index = 0
+    while index < len(path):
+        char = path[index]
+        if char == "<":
+            depth += 1
+        elif char == ">":
+            depth -= 1
+        if depth == 0 and path.startswith("::", index):
+            parts.append(current)
+            current = ""
+            index += 2
+            continue
+        current += char
+        index += 1
+    parts.append(current)
+    return [part for part in parts if part]
+
+
+def _strip_generics(text: str) -> Tuple[str, Optional[str]]:
+    """去掉末尾的泛型参数：Vec<my_app::User> -> (Vec, my_app::User)"""
+    if text.endswith(">") and "<" in text:
+        start = text.index("<")
+        return text[:start], text[start + 1 : -1]
+    return text, None
+
+
+def symbol_path(symbol: str) -> Dict[str, Any]:
+    """
+    还原后的 Rust / C++ 符号名拆分为函数路径
+
+    Returns:
+        crate（第一段）、module（中间的模块路径）、owner（所属类型：首字母大写的
+        一段或 <Type as Trait> 中的类型）、trait、name、type_args（泛型实参）
+    """
+    # C++ 符号的参数列表
+    symbol = RUST_HASH.sub("", symbol.split("(", 1)[0])
+    parts = _split_path(symbol)
+    trait = None
+    type_args = []
+    if parts and parts[0].startswith("<") and parts[0].endswith(">"):
+        inner = parts[0][1:-1]
+        self_type, _, trait_path = inner.partition(" as ")
+        parts = _split_path(self_type) + parts[1:]
+        if trait_path:
+            trait = _strip_generics(_split_path(trait_path)[-1])[0]
+    cleaned = []
+    for part in parts:
+        # v0 符号的 turbofish：rapp::generic::<u8>
+        if part.startswith("<") and part.endswith(">") and cleaned:
+            type_args.append(part[1:-1])
+            continue
+        base, args = _strip_generics(part)
+        if args:
+            type_args.append(args)
+        cleaned.append(base)
+    if not cleaned:
+        return {"name": symbol}
+    name = cleaned[-1]
+    owner = None
+    scope = cleaned[:-1]
+    if scope and scope[-1][:1].isupper():
+        owner = scope.pop()
+    return {
+        "crate": scope[0] if scope else None,
+        "module": "::".join(scope[1:]) or None,
+        "owner": owner,
+        "trait": trait,
+        "name": name,
+        "type_args": ", ".join(type_args) or None,
+    }
+
+
+class BinaryCallGraph:
+    """
+    由反汇编结果生成符号和调用关系（格式同源码分析写入数据库的数据）
+
+    默认只保留源码位于 source_root 下的函数（去掉标准库、依赖和没有调试
+    信息的函数）；all_symbols 时保留所有有调试信息的函数
+    """
+
+    def __init__(
+        self,
+        functions: List[Dict[str, Any]],
+        binary: str,
+        source_root: Optional[str] = None,
+        all_symbols: bool = False,
+    ):
+        self.functions = functions
+        self.binary = binary
+        self.source_root = Path(source_root).resolve() if source_root else None
+        self.all_symbols = all_symbols
+
+    def _included(self, file_path: Optional[str]) -> bool:
+        if not file_path:
+            return False
+        if self.all_symbols or self.source_root is None:
+            return True
+        try:
+            Path(file_path).resolve().relative_to(self.source_root)
+        except ValueError:
+            return False
+        return True
+
+    def _function_symbol(self, func: Dict[str, Any]) -> Optional[Dict[str, Any]]:
+        locations = func["locations"]
+        if not locations or not self._included(locations[0][0]):
+            return None
+        file_path, start_line = locations[0]
+        own_lines = [line for path, line in locations if path == file_path]
+        path = symbol_path(func["symbol"])
+        language = LANGUAGE_EXTENSIONS.get(Path(file_path).suffix, "c")
+        extras: Dict[str, Any] = {
+            "binary": os.path.basename(self.binary),
+            "address": f"0x{func['address']:x}",
+        }
+        if func["indirect"]:
+            extras["indirect_calls"] = func["indirect"]
+        if language == "rust" and path.get("crate"):
+            extras["crate"] = path["crate"]
+            if path.get("module"):
+                extras["module"] = path["module"]
+        if path.get("owner"):
+            extras["owner"] = path["owner"]
+        if path.get("trait"):
+            extras["trait"] = path["trait"]
+        if path.get("type_args"):
+            extras["type_args"] = path["type_args"]
+        digest = hashlib.md5(
+            f"{self.binary}:{func['address']}".encode("utf-8")
+        ).hexdigest()[:16]
+        return {
+            "id": f"bin_{digest}",
+            "file": file_path,
+            "name": path["name"],
+            "kind": "function",
+            "start_line": start_line,
+            "end_line": max(own_lines),
+            "signature": RUST_HASH.sub("", func["symbol"]),
+            "language": language,
+            "extras": extras,
+        }
+
+    def build(self) -> Tuple[List[Dict[str, Any]], List[Dict[str, Any]], int]:
+        """
+        Returns:
+            (符号, 调用关系, 调用了调用图之外的函数（库函数、PLT）的调用点数量)
+        """
+        symbols = {}
+        for func in self.functions:
+            symbol = self._function_symbol(func)
+            if symbol is not None:
+                symbols[func["address"]] = (func, symbol)
+
+        relations = []
+        external = 0
+        for func, caller in symbols.values():
+            for target, file_path, line, instruction, tail in func["calls"]:
+                # 调用指令来自内联到本函数的代码时，行号属于其他文件
+                if file_path != caller["file"]:
+                    line = caller["start_line"]
+                if target not in symbols:
+                    external += 1
+                    continue
+                callee = symbols[target][1]
+                if tail and callee["id"] == caller["id"]:
+                    continue
+                relations.append(
+                    {
+                        "caller_id": caller["id"],
+                        "callee_id": callee["id"],
+                        "caller_name": caller["name"],
+                        "callee_name": callee["name"],
+                        "caller_file": caller["file"],
+                        "callee_file": callee["file"],
+                        "call_site_line": line,
+                        "call_site_column": None,
+                        "call_site_snippet": instruction + ("（尾调用）" if tail else ""),
+                        "language": caller["language"],
+                        "edge_kind": "static",
+                        "type_args": callee["extras"].get("type_args"),
+                        "confidence": "certain",
+                    }
+                )
+        return [symbol for _, symbol in symbols.values()], relations, external
+
+
+def default_source_root(binary: str) -> str:
+    """二进制文件位于 Cargo 的 target/ 目录中时为 target 的上级目录，否则为当前目录"""
+    for parent in Path(binary).resolve().parents:
+        if parent.name == "target":
+            return str(parent.parent)
+    return os.getcwd()
+
+
+def analyze_binary(
+    db: CallGraphDB,
+    binary: str,
+    source_root: Optional[str] = None,
+    all_symbols: bool = False,
+    objdump: str = "objdump",
+) -> Dict[str, Any]:
+    """
+    分析二进制文件并写入数据库
+
+    Raises:
+        ValueError: 反汇编失败
+    """
+    if not os.path.isfile(binary):
+        raise ValueError(f"二进制文件不存在: {binary}")
+    print(f"反汇编 {binary}...")
+    functions = parse_objdump(run_objdump(binary, objdump))
+    builder = BinaryCallGraph(functions, binary, source_root, all_symbols)
+    symbols, relations, external = builder.build()
+    if functions and not symbols:
+        print("警告: 没有找到带调试信息的项目函数，请检查编译时是否启用了调试信息")
+    for symbol in symbols:
+        db.insert_symbol(symbol)
+    for relation in relations:
+        db.insert_call_relation(relation)
+    return {
+        "binary": binary,
+        "symbols_in_binary": len(functions),
+        "functions": len(symbols),
+        "call_relations": len(relations),
+        "external_calls": external,
+        "indirect_calls": sum(
+            symbol["extras"].get("indirect_calls", 0) for symbol in symbols
+        ),
+    }
@@ -0,0 +1,101 @@
+"""
+增量分析缓存
+按文件内容哈希缓存每个文件的解析结果（函数定义 + 未解析的调用引用），
+未修改的文件无需重新解析
+"""
+
+import hashlib
+import json
+import os
+from pathlib import Path
+from typing import Any, Dict, Iterable, Optional
+
+# 缓存格式版本，解析器输出结构变化时递增，使旧缓存失效
+CACHE_VERSION = 32
+
+# 默认缓存目录（位于被分析项目的根目录下）
+DEFAULT_CACHE_DIR = ".call_graph_cache"
+
+
+def file_content_hash(file_path: str) -> str:
+    """计算文件内容的 SHA-256 哈希"""
+    digest = hashlib.sha256()
+    with open(file_path, "rb") as f:
+        for chunk in iter(lambda: f.read(65536), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+class AnalysisCache:
+    """
+    每个源文件对应缓存目录中的一个 JSON 文件：
+
+        {"version": 1, "file": 路径, "hash": 内容哈希,
+         "functions": [...], "call_refs": [...], "items": {...}, "directives": [...]}
+
+    items 为文件级的模块项（如 Rust 的 mod 声明），directives 为注释指令，
+    没有时省略
+    """
+
+    def __init__(self, cache_dir: str):
+        self.cache_dir = Path(cache_dir)
+        self.cache_dir.mkdir(parents=True, exist_ok=True)
+        self.hits = 0
+        self.misses = 0
+
+    def _entry_path(self, file_path: str) -> Path:
+        name = hashlib.md5(file_path.encode()).hexdigest()
+        return self.cache_dir / f"{name}.json"
+
+    def load(self, file_path: str, content_hash: str) -> Optional[Dict[str, Any]]:
+        """读取缓存；文件内容或缓存版本变化时返回 None"""
+        entry_path = self._entry_path(file_path)
+        try:
+            with open(entry_path, "r", encoding="utf-8") as f:
+                entry = json.load(f)
+        except (OSError, ValueError):
+            self.misses += 1
+            return None
+
+        if (
+            entry.get("version") != CACHE_VERSION
+            or entry.get("file") != file_path
+            or entry.get("hash") != content_hash
+        ):
+            self.misses += 1
+            return None
+
+        self.hits += 1
+        result = {"functions": entry["functions"], "call_refs": entry["call_refs"]}
+        for key in ("items", "directives"):
+            if entry.get(key):
+                result[key] = entry[key]
+        return result
+
+    def store(self, file_path: str, content_hash: str, result: Dict[str, Any]):
+        """写入缓存（先写临时文件再替换，避免中断时留下损坏的缓存）"""
+        entry = {
+            "version": CACHE_VERSION,
+            "file": file_path,
+            "hash": content_hash,
+            "functions": result["functions"],
+            "call_refs": result["call_refs"],
+        }
+        for key in ("items", "directives"):
+            if result.get(key):
+                entry[key] = result[key]
+        entry_path = self._entry_path(file_path)
+        tmp_path = entry_path.with_suffix(".tmp")
+        with open(tmp_path, "w", encoding="utf-8") as f:
+            json.dump(entry, f, ensure_ascii=False)
+        os.replace(tmp_path, entry_path)
+
+    def prune(self, valid_files: Iterable[str]) -> int:
+        """删除已不存在的源文件的缓存，返回删除数量"""
+        keep = {self._entry_path(path).name for path in valid_files}
+        removed = 0
+        for entry_path in self.cache_dir.glob("*.json"):
+            if entry_path.name not in keep:
+                entry_path.unlink()
+                removed += 1
+        return removed
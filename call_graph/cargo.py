@@ -0,0 +1,983 @@
+"""
+Cargo 工作区
+解析 Cargo.toml，识别工作区中的所有 crate，为 Rust 函数标注所属 crate 和模块路径，
+并将调用引用中的 use 路径规范化为以 crate 名开头的绝对路径，用于跨 crate 解析；
+也可以直接使用 cargo metadata 的输出（cargo callgraph 子命令）
+"""
+
+import glob
+import json
+import os
+import subprocess
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional, Set, Tuple
+
+try:
+    import tomllib
+except ImportError:  # Python 3.10
+    tomllib = None
+
+
+def load_toml(toml_path: Path) -> Dict[str, Any]:
+    """读取 TOML 文件（Cargo.toml、配置文件），解析失败时返回空字典"""
+    try:
+        text = toml_path.read_text(encoding="utf-8", errors="ignore")
+    except OSError:
+        return {}
+    if tomllib:
+        try:
+            return tomllib.loads(text)
+        except tomllib.TOMLDecodeError as e:
+            print(f"警告: 解析 {toml_path} 失败: {e}")
+            return {}
+    return _parse_manifest_minimal(text)
+
+
+def _strip_comment(line: str) -> str:
+    """去掉不在字符串中的 # 注释"""
+    quote = None
+    for i, ch in enumerate(line):
+        if quote:
+            if ch == quote:
+                quote = None
+        elif ch in "\"'":
+            quote = ch
+        elif ch == "#":
+            return line[:i]
+    return line
+
+
+def _split_top_level(text: str) -> List[str]:
+    """按不在字符串、数组、内联表中的逗号分割"""
+    parts, depth, quote, current = [], 0, None, ""
+    for ch in text:
+        if quote:
+            if ch == quote:
+                quote = None
+        elif ch in "\"'":
+            quote = ch
+        elif ch in "[{":
+            depth += 1
+        elif ch in "]}":
+            depth -= 1
+        elif ch == "," and depth == 0:
+            parts.append(current)
+            current = ""
+            continue
+        current += ch
+    if current.strip():
+        parts.append(current)
+    return [part.strip() for part in parts if part.strip()]
+
+
+def _parse_value(value: str) -> Any:
+    value = value.strip()
+    if value[:1] in "\"'" and value[-1:] == value[:1]:
+        return value[1:-1]
+    if value in ("true", "false"):
+        return value == "true"
+    if value.startswith("[") and value.endswith("]"):
+        return [_parse_value(item) for item in _split_top_level(value[1:-1])]
+    if value.startswith("{") and value.endswith("}"):
+        table: Dict[str, Any] = {}
+        for item in _split_top_level(value[1:-1]):
+            if "=" in item:
+                key, item_value = item.split("=", 1)
+                _set_key(table, key, _parse_value(item_value))
+        return table
+    return value
+
+
+def _set_key(table: Dict[str, Any], key: str, value: Any):
+    """设置（可能带点号的）键"""
+    parts = [part.strip().strip("\"'") for part in key.split(".")]
+    for part in parts[:-1]:
+        table = table.setdefault(part, {})
+    table[parts[-1]] = value
+
+
+def _parse_manifest_minimal(text: str) -> Dict[str, Any]:
+    """
+    没有 tomllib（Python 3.10）时使用的简化解析器
+
+    只支持 Cargo.toml 中常用的写法：表、数组表、字符串、布尔、数组和内联表
+    """
+    data: Dict[str, Any] = {}
+    table = data
+    lines = iter(text.splitlines())
+    for raw in lines:
+        line = _strip_comment(raw).strip()
+        if not line:
+            continue
+        if line.startswith("[["):
+            parent = data
+            parts = [p.strip().strip("\"'") for p in line[2:-2].split(".")]
+            for part in parts[:-1]:
+                parent = parent.setdefault(part, {})
+            table = {}
+            parent.setdefault(parts[-1], []).append(table)
+            continue
+        if line.startswith("["):
+            table = data
+            for part in line[1:-1].split("."):
+                table = table.setdefault(part.strip().strip("\"'"), {})
+            continue
+        if "=" not in line:
+            continue
+        key, value = line.split("=", 1)
+        value = value.strip()
+        # 跨行的数组
+        while value.startswith("[") and value.count("[") > value.count("]"):
+            next_line = next(lines, None)
+            if next_line is None:
+                break
+            value += " " + _strip_comment(next_line).strip()
+        _set_key(table, key, _parse_value(value))
+    return data
+
+
+def crate_name(package_name: str) -> str:
+    """包名对应的 crate 名（代码中使用的名字，- 替换为 _）"""
+    return package_name.replace("-", "_")
+
+
+# cargo metadata 中表示库的目标类型
+LIB_TARGET_KINDS = ("lib", "rlib", "dylib", "cdylib", "staticlib", "proc-macro")
+
+# 沿 use 别名和重新导出展开路径的最大层数
+ALIAS_DEPTH = 8
+
+
+def run_cargo_metadata(
+    manifest_path: Optional[str] = None,
+    features: Optional[List[str]] = None,
+    all_features: bool = False,
+    no_default_features: bool = False,
+    cwd: Optional[str] = None,
+) -> Dict[str, Any]:
+    """
+    运行 cargo metadata，返回解析后的 JSON
+
+    未指定 manifest_path 时由 cargo 从 cwd 向上查找工作区；
+    features 影响依赖解析结果（可选依赖是否启用）
+    """
+    command = ["cargo", "metadata", "--format-version", "1"]
+    if manifest_path:
+        command += ["--manifest-path", manifest_path]
+    if features:
+        command += ["--features", ",".join(features)]
+    if all_features:
+        command.append("--all-features")
+    if no_default_features:
+        command.append("--no-default-features")
+    try:
+        result = subprocess.run(command, cwd=cwd, capture_output=True, text=True)
+    except FileNotFoundError as e:
+        raise ValueError("没有找到 cargo 命令") from e
+    if result.returncode != 0:
+        raise ValueError(f"cargo metadata 失败: {result.stderr.strip()}")
+    return json.loads(result.stdout)
+
+
+def _lib_name(package: Dict[str, Any]) -> str:
+    """package 的库目标名（代码中引用它时使用的 crate 名）"""
+    for target in package.get("targets") or []:
+        if any(kind in LIB_TARGET_KINDS for kind in target.get("kind") or []):
+            return crate_name(target["name"])
+    return crate_name(package["name"])
+
+
+def _target_paths(target: Dict[str, Any]) -> List[Path]:
+    """
+    目标专属的源文件路径
+
+    src/bin/x/main.rs 等以 main.rs 为根、位于 bin/examples/tests/benches 下的目标
+    占有整个目录，其他目标只占有根文件
+    """
+    src_path = Path(target["src_path"]).resolve()
+    if src_path.name == "main.rs" and src_path.parent.parent.name in (
+        "bin",
+        "examples",
+        "tests",
+        "benches",
+    ):
+        return [src_path.parent]
+    return [src_path]
+
+
+class Crate:
+    """工作区中的一个 crate（package）"""
+
+    def __init__(self, name: str, directory: Path):
+        self.name = name
+        self.directory = directory
+        # Cargo.toml 中的 package 名（可能包含 -，与库目标名不同）
+        self.package = name
+        # 依赖别名（代码中 use 的名字）-> crate 名（工作区内的 crate，
+        # 或来自 cargo metadata 的依赖 crate）
+        self.dependencies: Dict[str, str] = {}
+        # 库目标和二进制目标的根源文件（目标名），以及构建脚本（build.rs）的路径
+        self.lib_root: Optional[Path] = None
+        self.bin_targets: Dict[Path, str] = {}
+        self.build_script: Optional[Path] = None
+        # 过程宏 crate：其中的代码只在编译期由编译器调用
+        self.proc_macro = False
+
+    def add_metadata_targets(self, package: Dict[str, Any]):
+        """记录 cargo metadata 中 package 的二进制目标和构建脚本"""
+        for target in package.get("targets") or []:
+            kinds = target.get("kind") or []
+            src_path = Path(target["src_path"]).resolve()
+            if "bin" in kinds:
+                self.bin_targets[src_path] = target["name"]
+            elif any(kind in LIB_TARGET_KINDS for kind in kinds):
+                self.lib_root = src_path
+                self.proc_macro = "proc-macro" in kinds
+            elif "custom-build" in kinds:
+                self.build_script = src_path
+
+    def add_manifest_targets(self, manifest: Dict[str, Any]):
+        """
+        按 Cargo.toml 推断二进制目标和构建脚本（没有 cargo metadata 时）
+
+        与 cargo 的自动发现规则一致：src/main.rs 为与 package 同名的二进制，
+        src/bin/x.rs 和 src/bin/x/main.rs 为二进制 x（autobins = false 时不自动
+        发现），[[bin]] 表显式声明的目标；库目标为 [lib] path，默认为
+        src/lib.rs（[lib] proc-macro = true 时为过程宏 crate）；package.build
+        指定构建脚本，默认为 build.rs（build = false 时没有构建脚本）
+        """
+        package = manifest.get("package") or {}
+        src = self.directory / "src"
+        lib = manifest.get("lib") or {}
+        self.proc_macro = bool(lib.get("proc-macro") or lib.get("proc_macro"))
+        lib_path = lib.get("path")
+        if lib_path:
+            self.lib_root = (self.directory / lib_path).resolve()
+        elif (src / "lib.rs").is_file():
+            self.lib_root = (src / "lib.rs").resolve()
+        if package.get("autobins", True):
+            if (src / "main.rs").is_file():
+                self.bin_targets[(src / "main.rs").resolve()] = self.package
+            for path in sorted((src / "bin").glob("*.rs")):
+                self.bin_targets[path.resolve()] = path.stem
+            for path in sorted((src / "bin").glob("*/main.rs")):
+                self.bin_targets[path.resolve()] = path.parent.name
+        for target in manifest.get("bin") or []:
+            name = target.get("name")
+            if not name:
+                continue
+            path = target.get("path")
+            if path:
+                path = self.directory / path
+            elif (src / "bin" / f"{name}.rs").is_file():
+                path = src / "bin" / f"{name}.rs"
+            else:
+                path = src / "main.rs"
+            self.bin_targets[path.resolve()] = name
+
+        build = package.get("build", True)
+        if isinstance(build, str):
+            self.build_script = (self.directory / build).resolve()
+        elif build and (self.directory / "build.rs").is_file():
+            self.build_script = (self.directory / "build.rs").resolve()
+
+
+class CargoWorkspace:
+    """
+    项目中的 Cargo crate 集合
+
+    包括工作区成员（可能位于项目目录之外）和独立的 package
+    """
+
+    def __init__(self, crates: List[Crate]):
+        self.crates = {crate.name: crate for crate in crates}
+        # 按目录深度降序，嵌套 crate 优先匹配
+        self._by_directory = sorted(
+            crates, key=lambda crate: len(crate.directory.parts), reverse=True
+        )
+        # 只分析这些 crate（None 表示全部），以及其中被排除的目标源文件/目录
+        self.selected: Optional[Set[str]] = None
+        self.excluded_paths: List[Path] = []
+        # 工作区之外的依赖 crate（来自 cargo metadata，源码通常位于 registry 缓存中）
+        self.dependency_crates: Dict[str, Crate] = {}
+        self._dependencies_by_directory: List[Crate] = []
+        self.target_directory: Optional[Path] = None
+        # use 声明引入的名字：{(crate, *模块, 名字): [目标绝对路径]}，
+        # 以及通配导入：{(crate, *模块): [目标路径前缀]}
+        self.aliases: Dict[tuple, List[str]] = {}
+        self.glob_imports: Dict[tuple, List[str]] = {}
+        # struct/enum/union/trait/type 定义处的绝对路径
+        self.type_paths: Set[str] = set()
+        self._canonical: Dict[str, Tuple[List[str], List[str]]] = {}
+
+    @classmethod
+    def from_metadata(
+        cls,
+        metadata: Dict[str, Any],
+        packages: Optional[Iterable[str]] = None,
+        bins: Optional[Iterable[str]] = None,
+    ) -> "CargoWorkspace":
+        """
+        根据 cargo metadata 的输出创建工作区
+
+        crate 之间的依赖取自解析后的依赖图（包括重命名和按 feature 启用的
+        可选依赖），比直接读取 Cargo.toml 更准确
+
+        Args:
+            packages: 只分析这些 package（--package），默认为全部工作区成员
+            bins: 只分析这些二进制目标（--bin）；所属 package 的其他 bin、
+                example、test、bench 目标的源文件被排除
+        """
+        by_id = {package["id"]: package for package in metadata["packages"]}
+        members = set(metadata.get("workspace_members") or [])
+
+        crates = []
+        crate_of_id = {}
+        for package_id in sorted(members, key=lambda i: by_id[i]["name"]):
+            package = by_id[package_id]
+            crate = Crate(
+                _lib_name(package), Path(package["manifest_path"]).resolve().parent
+            )
+            crate.package = package["name"]
+            crate.add_metadata_targets(package)
+            crates.append(crate)
+            crate_of_id[package_id] = crate
+        workspace = cls(crates)
+        workspace.target_directory = Path(metadata["target_directory"])
+        workspace._add_resolved_dependencies(metadata, crate_of_id)
+
+        if packages:
+            names = {crate.package: crate.name for crate in crates}
+            unknown = [name for name in packages if name not in names]
+            if unknown:
+                raise ValueError(f"工作区中没有 package: {', '.join(unknown)}")
+            workspace.selected = {names[name] for name in packages}
+
+        if bins:
+            bins = list(bins)
+            owners = {}
+            for package_id, crate in crate_of_id.items():
+                for target in by_id[package_id].get("targets") or []:
+                    if "bin" in (target.get("kind") or []):
+                        owners.setdefault(target["name"], crate)
+            unknown = [name for name in bins if name not in owners]
+            if unknown:
+                raise ValueError(f"工作区中没有二进制目标: {', '.join(unknown)}")
+            selected_bins = {owners[name].name for name in bins}
+            workspace.selected = (workspace.selected or set()) | selected_bins
+            for package_id, crate in crate_of_id.items():
+                if crate.name not in selected_bins:
+                    continue
+                for target in by_id[package_id].get("targets") or []:
+                    kinds = target.get("kind") or []
+                    if any(kind in LIB_TARGET_KINDS for kind in kinds):
+                        continue
+                    if "bin" in kinds and target["name"] in bins:
+                        continue
+                    if "custom-build" in kinds:
+                        continue
+                    workspace.excluded_paths.extend(_target_paths(target))
+        return workspace
+
+    def attach_metadata(self, metadata: Dict[str, Any]) -> Set[str]:
+        """
+        用 cargo metadata 的依赖图补充由 Cargo.toml 识别的工作区：
+        记录外部依赖的别名和依赖 crate 的源码目录
+
+        Returns:
+            metadata 中包含的 crate 名
+        """
+        by_directory = {crate.directory: crate for crate in self.crates.values()}
+        crate_of_id = {}
+        for package in metadata["packages"]:
+            crate = by_directory.get(Path(package["manifest_path"]).resolve().parent)
+            if crate:
+                crate_of_id[package["id"]] = crate
+        self._add_resolved_dependencies(metadata, crate_of_id)
+        self.target_directory = Path(metadata["target_directory"])
+        return {crate.name for crate in crate_of_id.values()}
+
+    def _add_resolved_dependencies(
+        self, metadata: Dict[str, Any], crate_of_id: Dict[str, Crate]
+    ):
+        """根据解析后的依赖图设置各 crate 的依赖，并创建工作区之外的依赖 crate"""
+        by_id = {package["id"]: package for package in metadata["packages"]}
+        nodes = (metadata.get("resolve") or {}).get("nodes") or []
+        crate_of_id = dict(crate_of_id)
+        for node in nodes:
+            if node["id"] in crate_of_id or node["id"] not in by_id:
+                continue
+            package = by_id[node["id"]]
+            name = _lib_name(package)
+            # 同一 crate 的多个版本只保留第一个
+            if name in self.crates or name in self.dependency_crates:
+                continue
+            crate = Crate(name, Path(package["manifest_path"]).resolve().parent)
+            crate.package = package["name"]
+            self.dependency_crates[name] = crate
+            crate_of_id[node["id"]] = crate
+        self._dependencies_by_directory = sorted(
+            self.dependency_crates.values(),
+            key=lambda crate: len(crate.directory.parts),
+            reverse=True,
+        )
+
+        for node in nodes:
+            crate = crate_of_id.get(node["id"])
+            if not crate:
+                continue
+            for dep in node.get("deps") or []:
+                dep_package = by_id.get(dep["pkg"])
+                if dep_package:
+                    crate.dependencies[dep["name"]] = _lib_name(dep_package)
+
+    def source_directories(self) -> List[Path]:
+        """需要分析的 crate 目录"""
+        return [
+            crate.directory
+            for crate in self.crates.values()
+            if self.selected is None or crate.name in self.selected
+        ]
+
+    def includes_file(self, file_path: str) -> bool:
+        """文件是否属于选中的 crate 和目标"""
+        path = Path(file_path).resolve()
+        if any(
+            path == excluded or excluded in path.parents
+            for excluded in self.excluded_paths
+        ):
+            return False
+        if self.selected is None:
+            return True
+        crate = self.crate_for_file(file_path)
+        return bool(crate and crate.name in self.selected)
+
+    @classmethod
+    def discover(
+        cls, project_path: Path, exclude_dirs: List[str]
+    ) -> Optional["CargoWorkspace"]:
+        """查找项目中的所有 Cargo.toml，没有任何 crate 时返回 None"""
+        manifests = []
+        for root, dirs, files in os.walk(project_path):
+            dirs[:] = [
+                d for d in dirs if d not in exclude_dirs and not d.startswith(".")
+            ]
+            if "Cargo.toml" in files:
+                manifests.append(Path(root) / "Cargo.toml")
+
+        loaded: Dict[Path, Dict[str, Any]] = {}
+        pending = list(manifests)
+        workspace_dependencies: Dict[str, Any] = {}
+        while pending:
+            manifest_path = pending.pop().resolve()
+            if manifest_path in loaded:
+                continue
+            manifest = load_toml(manifest_path)
+            loaded[manifest_path] = manifest
+
+            # 工作区成员可能位于项目目录之外
+            workspace = manifest.get("workspace") or {}
+            workspace_dependencies.update(workspace.get("dependencies") or {})
+            excluded = {
+                (manifest_path.parent / path).resolve()
+                for path in workspace.get("exclude") or []
+            }
+            for pattern in workspace.get("members") or []:
+                for member in glob.glob(str(manifest_path.parent / pattern)):
+                    member_manifest = Path(member) / "Cargo.toml"
+                    if (
+                        member_manifest.exists()
+                        and Path(member).resolve() not in excluded
+                    ):
+                        pending.append(member_manifest)
+
+        crates = []
+        specs = {}
+        for manifest_path, manifest in sorted(loaded.items()):
+            package = manifest.get("package") or {}
+            if "name" not in package:
+                continue
+            crate = Crate(crate_name(str(package["name"])), manifest_path.parent)
+            crate.package = str(package["name"])
+            crate.add_manifest_targets(manifest)
+            crates.append(crate)
+            specs[crate.name] = manifest
+        if not crates:
+            return None
+
+        names = {crate.name for crate in crates}
+        for crate in crates:
+            manifest = specs[crate.name]
+            tables = [
+                manifest.get(key) or {}
+                for key in ("dependencies", "dev-dependencies", "build-dependencies")
+            ]
+            for table in tables:
+                for alias, spec in table.items():
+                    package_name = alias
+                    if isinstance(spec, dict):
+                        if spec.get("workspace") and isinstance(
+                            workspace_dependencies.get(alias), dict
+                        ):
+                            spec = {**workspace_dependencies[alias], **spec}
+                        package_name = spec.get("package", alias)
+                    target = crate_name(str(package_name))
+                    if target in names:
+                        crate.dependencies[crate_name(alias)] = target
+
+        return cls(crates)
+
+    def crate_for_file(self, file_path: str) -> Optional[Crate]:
+        """源文件所属的 crate（工作区 crate 优先，其次是依赖 crate）"""
+        path = Path(file_path).resolve()
+        for crate in self._by_directory + self._dependencies_by_directory:
+            if crate.directory in path.parents:
+                return crate
+        return None
+
+    def external_directories(self, project_path: Path) -> List[Path]:
+        """位于项目目录之外的成员 crate 目录"""
+        project_path = project_path.resolve()
+        return [
+            crate.directory
+            for crate in self.crates.values()
+            if crate.directory != project_path
+            and project_path not in crate.directory.parents
+        ]
+
+    @staticmethod
+    def module_for_file(crate: Crate, file_path: str) -> List[str]:
+        """
+        源文件在 crate 中的模块路径
+
+        src/lib.rs、src/main.rs、src/bin/*.rs 以及 tests/、examples/ 下的文件
+        都是各自目标的根模块；src/a/b.rs 和 src/a/b/mod.rs 是 a::b
+        """
+        rel = Path(file_path).resolve().relative_to(crate.directory)
+        parts = list(rel.with_suffix("").parts)
+        if not parts or parts[0] != "src":
+            return []
+        parts = parts[1:]
+        if not parts or parts[0] == "bin" or parts in (["lib"], ["main"]):
+            return []
+        if parts[-1] == "mod":
+            parts = parts[:-1]
+        return parts
+
+    def annotate(self, file_results: Dict[str, Dict[str, Any]]):
+        """
+        为 Rust 函数标注 crate 和模块路径（extras 中的 crate、module；
+        依赖 crate 中的函数另外标注 dependency；二进制目标和构建脚本的
+        main 函数另外标注 bin（目标名）或 build_script；构建脚本和过程宏 crate
+        中的函数另外标注 compile_time（build / proc-macro）；方法另外标注所属类型
+        定义处的路径 owner_type；库的公开 API 另外标注 api_paths，见
+        _annotate_public_paths；所在模块的 mod 声明带有 #[cfg(...)] 时另外标注
+        module_cfg），
+        并将调用引用中的 use 路径（hints.paths，以及函数值引用所在调用的
+        hints.passed_to.hints.paths）规范化为定义处的绝对路径（hints.qualified，
+        经过重新导出和 use 别名的路径按 canonicalize 展开），方法调用的接收者
+        类型（hints.receiver_type）解析为类型定义处的路径（hints.receiver_types）
+        """
+        self._collect_imports(file_results)
+        module_cfg = self._module_cfg(file_results)
+        for file_path, result in file_results.items():
+            if result.get("language") != "rust":
+                continue
+            crate = self.crate_for_file(file_path)
+            if not crate:
+                continue
+            module = self.module_for_file(crate, file_path)
+            dependency = crate is self.dependency_crates.get(crate.name)
+            resolved = Path(file_path).resolve()
+            # 只在编译期运行的代码：构建脚本，以及过程宏 crate
+            if resolved == crate.build_script:
+                compile_time = "build"
+            elif crate.proc_macro:
+                compile_time = "proc-macro"
+            else:
+                compile_time = None
+            # 各层 mod 声明上的条件（由外到内）
+            file_cfg = [
+                predicate
+                for i in range(1, len(module) + 1)
+                for predicate in module_cfg.get((crate.name, *module[:i]), [])
+            ]
+
+            for func in result["functions"]:
+                extras = func.setdefault("extras", {})
+                extras["crate"] = crate.name
+                extras["module"] = "::".join(module)
+                if file_cfg:
+                    extras["module_cfg"] = file_cfg
+                else:
+                    extras.pop("module_cfg", None)
+                if dependency:
+                    extras["dependency"] = True
+                if compile_time:
+                    extras["compile_time"] = compile_time
+                else:
+                    extras.pop("compile_time", None)
+                scope = module + list(extras.get("inline_modules") or [])
+                if extras.get("owner"):
+                    owner_type = self.resolve_type(
+                        crate, scope, extras.get("owner_path") or extras["owner"]
+                    )
+                    if owner_type:
+                        extras["owner_type"] = owner_type
+                    else:
+                        extras.pop("owner_type", None)
+                top_level = not extras.get("owner") and not extras.get("inline_modules")
+                if func["name"] == "main" and top_level:
+                    if resolved in crate.bin_targets:
+                        extras["bin"] = crate.bin_targets[resolved]
+                    elif resolved == crate.build_script:
+                        extras["build_script"] = True
+
+            self.annotate_call_refs(file_path, result)
+
+        self._annotate_public_paths(file_results)
+
+    def annotate_call_refs(self, file_path: str, result: Dict[str, Any]):
+        """
+        规范化一个文件的调用引用中的路径和接收者类型（见 annotate）
+
+        annotate 已经处理了同时传入的调用引用；低内存模式下调用引用在 annotate
+        时还在临时文件中，逐个文件读回后单独调用
+        """
+        if result.get("language") != "rust":
+            return
+        crate = self.crate_for_file(file_path)
+        if not crate:
+            return
+        module = self.module_for_file(crate, file_path)
+        by_id = {func["id"]: func for func in result["functions"]}
+        for ref in result["call_refs"]:
+            hints = ref.get("hints") or {}
+            caller = by_id.get(ref["caller_id"])
+            scope = module + list(
+                ((caller or {}).get("extras") or {}).get("inline_modules") or []
+            )
+            for target in (hints, (hints.get("passed_to") or {}).get("hints")):
+                if target and target.get("paths"):
+                    target["qualified"] = self._qualify_paths(
+                        crate, scope, target["paths"]
+                    )
+            if hints.get("receiver_type"):
+                receiver_types = self.type_candidates(
+                    crate, scope, hints["receiver_type"]
+                )
+                if receiver_types:
+                    hints["receiver_types"] = receiver_types
+
+    def _module_cfg(
+        self, file_results: Dict[str, Dict[str, Any]]
+    ) -> Dict[tuple, List[str]]:
+        """带 cfg 条件的 mod 声明：{(crate 名, 模块路径...): [条件]}"""
+        module_cfg = {}
+        for file_path, result in file_results.items():
+            if result.get("language") != "rust":
+                continue
+            crate = self.crate_for_file(file_path)
+            if not crate:
+                continue
+            module = self.module_for_file(crate, file_path)
+            for decl in (result.get("items") or {}).get("modules") or []:
+                if decl.get("cfg"):
+                    module_cfg[(crate.name, *module, *decl["path"])] = decl["cfg"]
+        return module_cfg
+
+    @staticmethod
+    def _library_module(crate: Crate, file_path: str) -> Optional[List[str]]:
+        """库目标中的源文件的模块路径；二进制目标、构建脚本等不属于库时为 None"""
+        if crate.lib_root is None:
+            return None
+        resolved = Path(file_path).resolve()
+        if resolved == crate.lib_root:
+            return []
+        module = CargoWorkspace.module_for_file(crate, file_path)
+        return module or None
+
+    def _annotate_public_paths(self, file_results: Dict[str, Dict[str, Any]]):
+        """
+        标注库的公开 API：从 crate 根可以访问的函数，在 extras 的 api_paths 中
+        记录它们的公开路径（如 mycrate::Parser::new，最短的在前）
+
+        - 函数本身为 pub，所在的每一层模块都是 pub mod，方法所属的类型是 pub
+          （类型定义在其他模块时不检查）；pub(crate) 等受限可见性不算公开
+        - pub use 重新导出的函数、模块和类型（包括通配导入和跨 crate 的 pub use）
+          在导出位置得到新的路径，可以多次转导出
+        - #[doc(hidden)] 的函数、模块、类型和 pub use 不算公开 API
+        - trait 实现方法、嵌套在函数体内的函数和闭包不单独列出
+        """
+        decls: Dict[tuple, Dict[str, Any]] = {}
+        types: Dict[tuple, Dict[str, Any]] = {}
+        reexports = []
+        members = []
+        for file_path, result in file_results.items():
+            if result.get("language") != "rust":
+                continue
+            crate = self.crate_for_file(file_path)
+            if not crate or crate is self.dependency_crates.get(crate.name):
+                continue
+            module = self._library_module(crate, file_path)
+            if module is None:
+                continue
+            items = result.get("items") or {}
+            for decl in items.get("modules") or []:
+                decls[(crate.name, *module, *decl["path"])] = decl
+            for item in items.get("types") or []:
+                types[(crate.name, *module, *item["scope"], item["name"])] = item
+            for item in items.get("uses") or []:
+                if item["visibility"] == "public":
+                    reexports.append((crate, module + item["scope"], item))
+            members.extend((module, func) for func in result["functions"])
+
+        def public_module(path: tuple) -> bool:
+            decl = decls.get(path)
+            return bool(decl) and decl["visibility"] == "public" and not decl["hidden"]
+
+        def exposed(path: tuple) -> bool:
+            return all(public_module(path[:i]) for i in range(2, len(path) + 1))
+
+        # {函数ID: {路径段: (各段对应的模块（非模块为 None）, 是否公开)}}
+        known: Dict[str, Dict[tuple, Any]] = {}
+        by_id = {}
+        for module, func in members:
+            extras = func.get("extras") or {}
+            extras.pop("api_paths", None)
+            attributes = ["".join(a.split()) for a in extras.get("attributes") or []]
+            if (
+                extras.get("visibility") != "public"
+                or extras.get("trait")
+                or extras.get("closure")
+                or func.get("container")
+                or "doc(hidden)" in attributes
+            ):
+                continue
+            scope = (extras["crate"], *module, *(extras.get("inline_modules") or []))
+            owner = extras.get("owner")
+            if owner:
+                owner_type = types.get((*scope, owner))
+                if owner_type and (
+                    owner_type["visibility"] != "public" or owner_type["hidden"]
+                ):
+                    continue
+            segments = scope + ((owner,) if owner else ()) + (func["name"],)
+            modules = tuple(scope[:i] for i in range(1, len(scope) + 1))
+            modules += (None,) * (len(segments) - len(scope))
+            known[func["id"]] = {segments: (modules, exposed(scope))}
+            by_id[func["id"]] = func
+
+        def accessible(modules: tuple, start: int) -> bool:
+            """路径中 start 之后经过的模块都是公开的"""
+            return all(m is None or public_module(m) for m in modules[start:])
+
+        # 重新导出可以链式传递，反复展开直到没有新的路径
+        changed = True
+        while changed:
+            changed = False
+            for crate, scope, item in reexports:
+                if item["hidden"]:
+                    continue
+                base = (crate.name, *scope)
+                base_modules = tuple(base[:i] for i in range(1, len(base) + 1))
+                public = exposed(base)
+                for candidate in self.qualify(crate, scope, item["path"]):
+                    target = tuple(candidate.split("::"))
+                    for paths in known.values():
+                        for segments, (modules, _) in list(paths.items()):
+                            if segments[: len(target)] != target:
+                                continue
+                            if item["alias"] is None:
+                                # pub use a::* 导出 a 的成员，不包括 a 本身
+                                if len(segments) == len(target):
+                                    continue
+                                new = base + segments[len(target) :]
+                                new_modules = base_modules + modules[len(target) :]
+                            else:
+                                rest = segments[len(target) :]
+                                new = base + (item["alias"],) + rest
+                                new_modules = base_modules + modules[len(target) - 1 :]
+                            if not accessible(modules, len(target)):
+                                continue
+                            # 经过同一个模块两次（如 pub use super::*）的路径没有意义
+                            visited = [m for m in new_modules if m is not None]
+                            if len(visited) != len(set(visited)):
+                                continue
+                            previous = paths.get(new)
+                            if previous is None or (public and not previous[1]):
+                                paths[new] = (new_modules, public)
+                                changed = True
+
+        for func_id, paths in known.items():
+            public = sorted(
+                ("::".join(s) for s, (_, shown) in paths.items() if shown),
+                key=lambda path: (path.count("::"), path),
+            )
+            if public:
+                by_id[func_id]["extras"]["api_paths"] = public
+
+    def _collect_imports(self, file_results: Dict[str, Dict[str, Any]]):
+        """
+        由各文件模块级的 use 声明（解析结果的 items.uses）建立别名表，
+        并记录类型定义处的路径（items.types）
+        """
+        aliases: Dict[tuple, List[str]] = {}
+        globs: Dict[tuple, List[str]] = {}
+        type_paths = set()
+        crates = set()
+        for file_path, result in file_results.items():
+            if result.get("language") != "rust":
+                continue
+            crate = self.crate_for_file(file_path)
+            if not crate:
+                continue
+            crates.add(crate.name)
+            module = self.module_for_file(crate, file_path)
+            for item in (result.get("items") or {}).get("types") or []:
+                path = [crate.name, *module, *item["scope"], item["name"]]
+                type_paths.add("::".join(path))
+            for item in (result.get("items") or {}).get("uses") or []:
+                scope = module + item["scope"]
+                key = (crate.name, *scope)
+                targets = self.qualify(crate, scope, item["path"])
+                if item["alias"] is None:
+                    table = globs.setdefault(key, [])
+                else:
+                    key += (item["alias"],)
+                    # use foo; 引入外部 crate 时目标就是自身
+                    targets = [t for t in targets if tuple(t.split("::")) != key]
+                    if not targets:
+                        continue
+                    table = aliases.setdefault(key, [])
+                table.extend(t for t in targets if t not in table)
+        for table, collected in ((self.aliases, aliases), (self.glob_imports, globs)):
+            for key in [key for key in table if key[0] in crates]:
+                del table[key]
+            table.update(collected)
+        self.type_paths = {
+            path for path in self.type_paths if path.split("::")[0] not in crates
+        } | type_paths
+        self._canonical = {}
+
+    def type_candidates(self, crate: Crate, scope: List[str], path: str) -> List[str]:
+        """类型路径（如 Foo、model::Foo）在 scope 中可能指向的项目中的类型定义"""
+        return [
+            candidate
+            for candidate in self._qualify_paths(crate, scope, [path])
+            if candidate in self.type_paths
+        ]
+
+    def resolve_type(self, crate: Crate, scope: List[str], path: str) -> Optional[str]:
+        """类型在 scope 中指向的定义处路径，不是项目中定义的类型时返回 None"""
+        candidates = self.type_candidates(crate, scope, path)
+        return candidates[0] if candidates else None
+
+    def canonicalize(self, path: str) -> Tuple[List[str], List[str]]:
+        """
+        沿 use 别名和重新导出把路径展开为定义处路径的候选，可以多层转导出
+        （如 mycrate::prelude::Foo::bar -> mycrate::model::Foo::bar）
+
+        Returns:
+            (经过 use 别名得到的路径, 经过通配导入可能得到的路径)；前者所在的
+            名字已被 use 占用，不会是定义处，后者只是可能的候选
+        """
+        if path in self._canonical:
+            return self._canonical[path]
+        aliased: List[str] = []
+        globbed: List[str] = []
+        seen = {path}
+        # 本身又经过 use 别名的中间路径不是定义处
+        intermediate = set()
+        frontier = [(tuple(path.split("::")), False)]
+        for _ in range(ALIAS_DEPTH):
+            expanded = []
+            for segments, via_glob in frontier:
+                for i in range(len(segments), 1, -1):
+                    targets = self.aliases.get(segments[:i])
+                    if targets:
+                        intermediate.add("::".join(segments))
+                        for target in targets:
+                            expanded.append((target, segments[i:], via_glob))
+                        break
+                else:
+                    # 名字没有被 use 占用时才可能来自通配导入
+                    for i in range(len(segments) - 1, 0, -1):
+                        for target in self.glob_imports.get(segments[:i]) or []:
+                            expanded.append((target, segments[i:], True))
+            frontier = []
+            for target, rest, via_glob in expanded:
+                candidate = "::".join((target,) + rest)
+                if candidate in seen:
+                    continue
+                seen.add(candidate)
+                (globbed if via_glob else aliased).append(candidate)
+                frontier.append((tuple(candidate.split("::")), via_glob))
+            if not frontier:
+                break
+        aliased = [p for p in aliased if p not in intermediate]
+        globbed = [p for p in globbed if p not in intermediate]
+        self._canonical[path] = (aliased, globbed)
+        return aliased, globbed
+
+    def _qualify_paths(
+        self, crate: Crate, scope: List[str], paths: List[str]
+    ) -> List[str]:
+        qualified = []
+        for path in paths:
+            for candidate in self.qualify(crate, scope, path):
+                aliased, globbed = self.canonicalize(candidate)
+                for resolved in aliased + [candidate] + globbed:
+                    if resolved not in qualified:
+                        qualified.append(resolved)
+        return qualified
+
+    def qualify(self, crate: Crate, scope: List[str], path: str) -> List[str]:
+        """
+        将 use 路径规范化为以 crate 名开头的绝对路径候选
+
+        crate::a -> 当前crate::a；self::a、super::a 相对当前模块；
+        依赖别名和工作区 crate 名开头的路径指向对应 crate；
+        其他路径先按相对当前模块、再按相对 crate 根解析
+        """
+        segments = [segment for segment in path.split("::") if segment]
+        if not segments:
+            return []
+        first = segments[0]
+        if first == "crate":
+            return ["::".join([crate.name] + segments[1:])]
+        if first in ("self", "super"):
+            base = list(scope)
+            while segments and segments[0] in ("self", "super"):
+                if segments[0] == "super" and base:
+                    base.pop()
+                segments = segments[1:]
+            return ["::".join([crate.name] + base + segments)]
+        if first in crate.dependencies:
+            return ["::".join([crate.dependencies[first]] + segments[1:])]
+        if first in self.crates:
+            return ["::".join(segments)]
+
+        candidates = ["::".join([crate.name] + scope + segments)]
+        if scope:
+            candidates.append("::".join([crate.name] + segments))
+        return candidates
+
+
+def function_path(func: Dict[str, Any]) -> Optional[str]:
+    """
+    Rust 函数的绝对路径（crate::module::Type::name），未标注 crate 时返回 None
+
+    trait 实现方法使用实现类型作为路径（Type::method）
+    """
+    extras = func.get("extras") or {}
+    if not extras.get("crate") or extras.get("closure"):
+        return None
+    parts = [extras["crate"]]
+    if extras.get("module"):
+        parts.extend(extras["module"].split("::"))
+    parts.extend(extras.get("inline_modules") or [])
+    if extras.get("owner"):
+        parts.append(extras["owner"])
+    parts.append(func["name"])
+    return "::".join(parts)
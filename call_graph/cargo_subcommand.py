@@ -0,0 +1,208 @@
+"""
+Cargo 子命令
+安装后作为 cargo callgraph 运行：通过 cargo metadata 识别当前工作区，
+分析选中的 package/bin，再执行 call-graph 的查询或导出命令
+"""
+
+import argparse
+import contextlib
+import io
+import sys
+from typing import List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .analyzer import DEFAULT_EXCLUDE_DIRS, CallGraphAnalyzer
+    from .analyzer_optimized import CallGraphAnalyzerOptimized
+    from .cargo import CargoWorkspace, run_cargo_metadata
+    from .dependencies import DEFAULT_DEPENDENCY_DEPTH
+    from .main import main as call_graph_main
+    from .plugins import load_plugins
+except ImportError:
+    from analyzer import DEFAULT_EXCLUDE_DIRS, CallGraphAnalyzer
+    from analyzer_optimized import CallGraphAnalyzerOptimized
+    from cargo import CargoWorkspace, run_cargo_metadata
+    from dependencies import DEFAULT_DEPENDENCY_DEPTH
+    from main import main as call_graph_main
+    from plugins import load_plugins
+
+# 数据库和缓存位于 cargo 目标目录下的该子目录中
+TARGET_SUBDIR = "callgraph"
+
+
+def _parser() -> argparse.ArgumentParser:
+    parser = argparse.ArgumentParser(
+        prog="cargo callgraph",
+        description="分析当前 Cargo 工作区的调用关系，然后执行 call-graph 命令",
+        formatter_class=argparse.RawDescriptionHelpFormatter,
+        epilog="""
+示例:
+  # 分析整个工作区并显示统计信息
+  cargo callgraph
+
+  # 只分析 core package，导出交互式 HTML
+  cargo callgraph -p core export --format html -o graph.html
+
+  # 只分析 server 二进制（及所属 package 的库代码），查询调用者树
+  cargo callgraph --bin server query handle_request --callers --tree
+
+  # 启用 feature（影响可选依赖的解析）
+  cargo callgraph --features tls,metrics deadcode
+        """,
+    )
+    parser.add_argument("--manifest-path", help="Cargo.toml 路径（默认：向上查找）")
+    parser.add_argument(
+        "--package",
+        "-p",
+        action="append",
+        default=[],
+        metavar="SPEC",
+        help="只分析指定的 package，可多次指定（默认：所有工作区成员）",
+    )
+    parser.add_argument(
+        "--bin",
+        action="append",
+        default=[],
+        metavar="NAME",
+        help="只分析指定的二进制目标（以及所属 package 的库代码），可多次指定",
+    )
+    parser.add_argument(
+        "--features",
+        "-F",
+        action="append",
+        default=[],
+        help="启用的 feature（逗号或空格分隔，可多次指定）",
+    )
+    parser.add_argument("--all-features", action="store_true", help="启用所有 feature")
+    parser.add_argument(
+        "--no-default-features", action="store_true", help="不启用 default feature"
+    )
+    parser.add_argument(
+        "--include-deps",
+        action="store_true",
+        help="加入调用到的依赖 crate 中的函数（源码来自 cargo 缓存）",
+    )
+    parser.add_argument(
+        "--deps-depth",
+        type=int,
+        default=DEFAULT_DEPENDENCY_DEPTH,
+        metavar="N",
+        help=f"沿调用链最多深入依赖的层数（默认：{DEFAULT_DEPENDENCY_DEPTH}）",
+    )
+    parser.add_argument(
+        "--database",
+        "-d",
+        help="数据库文件路径（默认：<目标目录>/callgraph/call_graph.db）",
+    )
+    parser.add_argument(
+        "--config", help="call-graph 配置文件路径（TOML，[filters]、[rules] 和 [effects] 表）"
+    )
+    parser.add_argument(
+        "--fast", action="store_true", help="使用性能优化模式（多进程+批量操作）"
+    )
+    parser.add_argument(
+        "--plugin",
+        action="append",
+        default=[],
+        metavar="MODULE",
+        help="加载语言前端插件模块（可多次指定）",
+    )
+    parser.add_argument(
+        "--verbose", "-v", action="store_true", help="显示完整的分析进度"
+    )
+    parser.add_argument(
+        "command",
+        nargs=argparse.REMAINDER,
+        help="分析完成后执行的 call-graph 命令及参数（默认：stats）",
+    )
+    return parser
+
+
+def main(argv: Optional[List[str]] = None):
+    """cargo-callgraph 入口"""
+    argv = list(sys.argv[1:] if argv is None else argv)
+    # cargo 调用子命令时把子命令名作为第一个参数传入
+    if argv[:1] == ["callgraph"]:
+        argv = argv[1:]
+    args = _parser().parse_args(argv)
+
+    command = args.command or ["stats"]
+    if command[0] == "analyze":
+        print(
+            "错误: cargo callgraph 会自动分析工作区，请直接指定查询或导出命令",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+    if args.include_deps and args.deps_depth < 1:
+        print("错误: --deps-depth 至少为 1", file=sys.stderr)
+        sys.exit(1)
+
+    features = [
+        feature
+        for value in args.features
+        for feature in value.replace(",", " ").split()
+    ]
+    try:
+        load_plugins(args.plugin)
+        metadata = run_cargo_metadata(
+            manifest_path=args.manifest_path,
+            features=features,
+            all_features=args.all_features,
+            no_default_features=args.no_default_features,
+        )
+        workspace = CargoWorkspace.from_metadata(metadata, args.package, args.bin)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
+    # 目标目录已经考虑了 CARGO_TARGET_DIR 和 .cargo/config.toml 中的 build.target-dir
+    output_dir = workspace.target_directory / TARGET_SUBDIR
+    output_dir.mkdir(parents=True, exist_ok=True)
+    database = args.database or str(output_dir / "call_graph.db")
+
+    selected = sorted(
+        crate.package
+        for crate in workspace.crates.values()
+        if workspace.selected is None or crate.name in workspace.selected
+    )
+    print(f"分析 Cargo 工作区: {metadata['workspace_root']}", file=sys.stderr)
+    print(f"package: {', '.join(selected)}", file=sys.stderr)
+
+    # 源文件范围由 cargo metadata 的目标决定，src/bin 下的二进制目标也要分析
+    exclude_dirs = [name for name in DEFAULT_EXCLUDE_DIRS if name != "bin"]
+
+    if args.fast:
+        analyzer = CallGraphAnalyzerOptimized(database)
+    else:
+        analyzer = CallGraphAnalyzer(database)
+    log = contextlib.nullcontext()
+    if not args.verbose:
+        log = contextlib.redirect_stdout(io.StringIO())
+    try:
+        with log:
+            # 增量缓存：再次运行时只重新解析变化的文件
+            stats = analyzer.analyze_project(
+                metadata["workspace_root"],
+                exclude_dirs=exclude_dirs,
+                use_cache=True,
+                cache_dir=str(output_dir / "cache"),
+                workspace=workspace,
+                dependency_depth=args.deps_depth if args.include_deps else 0,
+                show_progress=args.verbose,
+            )
+    finally:
+        analyzer.close()
+    print(
+        f"共 {stats['total_symbols']} 个符号, {stats['total_relations']} 个调用关系"
+        f"（数据库: {database}）",
+        file=sys.stderr,
+    )
+
+    global_args = ["--database", database]
+    if args.config:
+        global_args += ["--config", args.config]
+    call_graph_main(global_args + command)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,245 @@
+"""
+条件编译
+Rust 的 #[cfg(...)] 条件：解析器在函数（extras 的 cfg，及所在模块的
+module_cfg）和调用点（调用关系的 cfg 列）上记录生效的条件，analyze --cfg
+按指定的配置求值，排除（或只标记）不参与编译的函数和调用
+"""
+
+import re
+from typing import Any, Dict, Iterable, List, Optional, Set, Tuple
+
+# --cfg-mode：filter 排除条件不成立的函数和调用；tag 全部保留，只标记
+CFG_MODES = ("filter", "tag")
+
+# 指定 target_os 时隐含的 target_family（以及同名的 unix / windows）
+UNIX_TARGETS = {
+    "linux",
+    "macos",
+    "ios",
+    "android",
+    "freebsd",
+    "netbsd",
+    "openbsd",
+    "dragonfly",
+    "solaris",
+    "illumos",
+}
+
+TOKEN_PATTERN = re.compile(
+    r'\s*(?:(?P<string>"[^"]*")|(?P<op>[(),=])|(?P<name>[\w:]+))'
+)
+
+# 语法树：("name", 名称) / ("key", 名称, 值) / ("all" | "any", [子树]) / ("not", 子树)
+CfgExpr = Tuple
+
+
+class CfgError(ValueError):
+    """cfg 条件或 --cfg 选项的语法错误"""
+
+
+def _tokenize(text: str) -> List[Tuple[str, str]]:
+    tokens = []
+    position = 0
+    while position < len(text):
+        if text[position:].isspace():
+            break
+        match = TOKEN_PATTERN.match(text, position)
+        if not match or match.end() == position:
+            raise CfgError(f"无法解析的 cfg 条件: {text}")
+        kind = match.lastgroup
+        value = match.group(kind)
+        tokens.append((kind, value[1:-1] if kind == "string" else value))
+        position = match.end()
+    return tokens
+
+
+def parse_cfg(text: str) -> CfgExpr:
+    """
+    解析 cfg(...) 括号中的条件，如 all(unix, feature = "serde")
+
+    Raises:
+        CfgError: 语法错误
+    """
+    tokens = _tokenize(text)
+    index = 0
+
+    def take(kind: Optional[str] = None, value: Optional[str] = None):
+        nonlocal index
+        if index >= len(tokens):
+            raise CfgError(f"cfg 条件不完整: {text}")
+        token = tokens[index]
+        if (kind and token[0] != kind) or (value and token[1] != value):
+            raise CfgError(f"无法解析的 cfg 条件: {text}")
+        index += 1
+        return token
+
+    def peek(value: str) -> bool:
+        return index < len(tokens) and tokens[index] == ("op", value)
+
+    def predicate() -> CfgExpr:
+        name = take("name")[1]
+        if name in ("all", "any", "not") and peek("("):
+            take("op", "(")
+            items = []
+            while not peek(")"):
+                items.append(predicate())
+                if not peek(")"):
+                    take("op", ",")
+            take("op", ")")
+            if name == "not":
+                if len(items) != 1:
+                    raise CfgError(f"not 只能有一个条件: {text}")
+                return ("not", items[0])
+            return (name, items)
+        if peek("="):
+            take("op", "=")
+            return ("key", name, take("string")[1])
+        return ("name", name)
+
+    expr = predicate()
+    if index != len(tokens):
+        raise CfgError(f"无法解析的 cfg 条件: {text}")
+    return expr
+
+
+def cfg_predicates(attributes: Iterable[str]) -> List[str]:
+    """属性（不含 #[]）中 cfg(...) 的条件，合并空白"""
+    predicates = []
+    for attribute in attributes:
+        text = attribute.strip()
+        if text.startswith("cfg") and text[3:].lstrip().startswith("("):
+            inner = text[3:].strip()[1:-1]
+            predicates.append(" ".join(inner.split()))
+    return predicates
+
+
+def combine_cfg(predicates: Iterable[str]) -> Optional[str]:
+    """多个同时成立的条件合并为一个：没有条件时为 None，多个时为 all(...)"""
+    unique = list(dict.fromkeys(predicate for predicate in predicates if predicate))
+    if not unique:
+        return None
+    if len(unique) == 1:
+        return unique[0]
+    return f"all({', '.join(unique)})"
+
+
+def function_cfg(func: Dict[str, Any]) -> List[str]:
+    """函数生效的全部条件：所在模块的 mod 声明上的条件和函数所在的项上的条件"""
+    extras = func.get("extras") or {}
+    return list(extras.get("module_cfg") or []) + list(extras.get("cfg") or [])
+
+
+class CfgSet:
+    """
+    一组生效的 cfg（同 rustc --cfg）：名称（unix、test）和键值（feature="serde"、
+    target_os="linux"），没有指定的名称和键值都不成立
+
+    指定 target_os 而没有指定 target_family 时按 target_os 补上 target_family
+    和 unix / windows
+    """
+
+    def __init__(self, specs: Iterable[str] = ()):
+        self.names: Set[str] = set()
+        self.values: Dict[str, Set[str]] = {}
+        for spec in specs:
+            self.add(spec)
+        if "target_os" in self.values and "target_family" not in self.values:
+            for target_os in self.values["target_os"]:
+                family = (
+                    "windows"
+                    if target_os == "windows"
+                    else "unix" if target_os in UNIX_TARGETS else None
+                )
+                if family:
+                    self.values.setdefault("target_family", set()).add(family)
+                    self.names.add(family)
+
+    def add(self, spec: str):
+        """
+        Raises:
+            CfgError: 不是 name 或 name="value" 形式
+        """
+        name, separator, value = spec.partition("=")
+        name = name.strip()
+        if not re.fullmatch(r"[A-Za-z_]\w*", name):
+            raise CfgError(f"无效的 --cfg: {spec}（应为 name 或 name=\"value\"）")
+        if not separator:
+            self.names.add(name)
+            return
+        value = value.strip()
+        if len(value) >= 2 and value[0] == value[-1] == '"':
+            value = value[1:-1]
+        self.values.setdefault(name, set()).add(value)
+
+    def __bool__(self) -> bool:
+        return bool(self.names or self.values)
+
+    def _eval(self, expr: CfgExpr) -> bool:
+        kind = expr[0]
+        if kind == "name":
+            return expr[1] in self.names
+        if kind == "key":
+            return expr[2] in self.values.get(expr[1], ())
+        if kind == "not":
+            return not self._eval(expr[1])
+        if kind == "all":
+            return all(self._eval(item) for item in expr[1])
+        return any(self._eval(item) for item in expr[1])
+
+    def evaluate(self, predicate: str) -> bool:
+        """条件是否成立；无法解析的条件（如宏生成的）视为成立"""
+        try:
+            return self._eval(parse_cfg(predicate))
+        except CfgError:
+            return True
+
+    def active(self, predicates: Iterable[str]) -> bool:
+        return all(self.evaluate(predicate) for predicate in predicates)
+
+    def apply(
+        self, file_results: Dict[str, Dict[str, Any]], mode: str = "filter"
+    ) -> Tuple[int, int]:
+        """
+        按配置处理解析结果
+
+        - filter：去掉条件不成立的函数、它们发出的调用引用，以及条件不成立的
+          调用点；数据库中记录的文件哈希带上 cfg，切换配置后重新保存受影响的文件
+        - tag：全部保留，在条件不成立的函数的 extras 上设置 cfg_active 为 False
+
+        Returns:
+            (条件不成立的函数数量, 条件不成立的调用引用数量)
+        """
+        inactive_functions = 0
+        inactive_refs = 0
+        signature = ",".join(
+            sorted(self.names)
+            + sorted(f"{k}={v}" for k, vs in self.values.items() for v in vs)
+        )
+        for result in file_results.values():
+            inactive = set()
+            for func in result["functions"]:
+                extras = func.get("extras") or {}
+                extras.pop("cfg_active", None)
+                if not self.active(function_cfg(func)):
+                    inactive.add(func["id"])
+                    if mode == "tag":
+                        extras["cfg_active"] = False
+            refs = result["call_refs"]
+            dropped = [
+                ref
+                for ref in refs
+                if ref.get("caller_id") in inactive
+                or (ref.get("cfg") and not self.evaluate(ref["cfg"]))
+            ]
+            inactive_functions += len(inactive)
+            inactive_refs += len(dropped)
+            if mode != "filter" or not (inactive or dropped):
+                continue
+            dropped_ids = {id(ref) for ref in dropped}
+            result["functions"] = [
+                func for func in result["functions"] if func["id"] not in inactive
+            ]
+            result["call_refs"] = [ref for ref in refs if id(ref) not in dropped_ids]
+            if result.get("hash"):
+                result["hash"] = f"{result['hash']}+cfg:{signature}"
+        return inactive_functions, inactive_refs
@@ -0,0 +1,183 @@
+"""
+项目配置文件
+从被分析的目录（其他命令为当前目录）向上查找 call_graph.toml，把其中的选项作为
+命令行参数的默认值，团队可以提交共享的配置，而不必维护很长的命令行::
+
+    database = "target/call_graph.db"   # 全局选项
+
+    [analyze]                            # 与子命令同名的表：该命令的选项默认值
+    fast = true
+    languages = ["rust"]
+    exclude = ["target", "vendor"]
+
+    [export]
+    format = "html"
+    output = "target/call_graph.html"
+
+    [roots]                              # deadcode、roots 等可达性分析的入口点
+    functions = ["my_crate::server::run"]
+    default = true
+
+    [filters] / [rules] / [effects]      # 见 SymbolFilter、RuleSet、EffectSet
+
+命令行中显式指定的选项优先；可多次指定的选项（如 --root、--include）与
+配置文件中的值合并
+"""
+
+import argparse
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .cargo import load_toml
+except ImportError:
+    from cargo import load_toml
+
+CONFIG_FILE_NAME = "call_graph.toml"
+
+# 由其他模块读取的表，不作为命令的选项默认值
+SHARED_TABLES = {"filters", "rules", "effects", "roots"}
+
+# 取值为路径的选项：相对路径按配置文件所在目录解析
+PATH_OPTIONS = {"database", "output", "cache_dir", "spill_dir", "export"}
+
+# 不能在配置文件中设置的选项
+IGNORED_OPTIONS = {"help", "config", "no_config"}
+
+
+def find_config(start: str) -> Optional[Path]:
+    """
+    从 start 向上查找 call_graph.toml
+
+    到达仓库根目录（包含 .git 的目录）或文件系统根目录时停止
+    """
+    directory = Path(start).resolve()
+    if not directory.is_dir():
+        directory = directory.parent
+    for candidate in (directory, *directory.parents):
+        path = candidate / CONFIG_FILE_NAME
+        if path.is_file():
+            return path
+        if (candidate / ".git").exists():
+            return None
+    return None
+
+
+def load_config(config_path: str) -> Dict[str, Any]:
+    """
+    Raises:
+        ValueError: 配置文件不存在
+    """
+    path = Path(config_path)
+    if not path.exists():
+        raise ValueError(f"配置文件不存在: {config_path}")
+    return load_toml(path)
+
+
+def _actions(parser: argparse.ArgumentParser) -> Dict[str, argparse.Action]:
+    """{选项名: action}，按 dest 和长选项名（- 换成 _）索引，不包括位置参数"""
+    actions = {}
+    for action in parser._actions:
+        if not action.option_strings or action.dest in IGNORED_OPTIONS:
+            continue
+        actions[action.dest] = action
+        for option in action.option_strings:
+            if option.startswith("--"):
+                actions[option[2:].replace("-", "_")] = action
+    return actions
+
+
+def _option_value(
+    table: str, key: str, value: Any, action: argparse.Action, base_dir: Path
+) -> Any:
+    """把配置文件中的值转换为 argparse 选项的值"""
+    where = f"配置文件 [{table}] 中的 {key}" if table else f"配置文件中的 {key}"
+    if isinstance(action, (argparse._StoreTrueAction, argparse._StoreFalseAction)):
+        if not isinstance(value, bool):
+            raise ValueError(f"{where} 应为 true 或 false")
+        # store_false 的选项（如 --no-xxx）写作 xxx = false 时语义相反
+        return value if isinstance(action, argparse._StoreTrueAction) else not value
+    if isinstance(action, argparse._AppendAction):
+        values = value if isinstance(value, list) else [value]
+        return [str(item) for item in values]
+    if isinstance(value, list):
+        # 逗号分隔的选项（如 analyze --exclude）也可以写成数组
+        value = ",".join(str(item) for item in value)
+    if isinstance(value, bool) or isinstance(value, dict):
+        raise ValueError(f"{where} 的类型不正确")
+    if callable(action.type) and not (
+        isinstance(action.type, type) and isinstance(value, action.type)
+    ):
+        try:
+            value = action.type(value)
+        except (TypeError, ValueError):
+            raise ValueError(f"{where} 的值无效: {value}")
+    if action.choices is not None and value not in action.choices:
+        choices = ", ".join(str(choice) for choice in action.choices)
+        raise ValueError(f"{where} 应为以下之一: {choices}")
+    if action.dest in PATH_OPTIONS and isinstance(value, str):
+        value = str((base_dir / value).resolve())
+    return value
+
+
+def _defaults(
+    table: str,
+    values: Dict[str, Any],
+    parser: argparse.ArgumentParser,
+    base_dir: Path,
+) -> Dict[str, Any]:
+    actions = _actions(parser)
+    defaults = {}
+    for key, value in values.items():
+        action = actions.get(key.replace("-", "_"))
+        if action is None:
+            where = f"配置文件 [{table}] 中" if table else "配置文件中"
+            raise ValueError(f"{where}的未知选项: {key}")
+        defaults[action.dest] = _option_value(table, key, value, action, base_dir)
+    return defaults
+
+
+def apply_config(
+    config: Dict[str, Any],
+    config_path: str,
+    parser: argparse.ArgumentParser,
+    command: Optional[str],
+    command_parser: Optional[argparse.ArgumentParser],
+):
+    """
+    把配置文件中的选项设为 parser（全局选项）和 command_parser（子命令选项）
+    的默认值；之后需要重新解析命令行
+
+    [roots] 表设置支持 --root / --no-default-roots 的命令的入口点
+
+    Raises:
+        ValueError: 未知的选项或取值无效
+    """
+    base_dir = Path(config_path).resolve().parent
+    global_options = {
+        key: value for key, value in config.items() if not isinstance(value, dict)
+    }
+    parser.set_defaults(**_defaults("", global_options, parser, base_dir))
+    if command_parser is None:
+        return
+
+    actions = _actions(command_parser)
+    defaults: Dict[str, Any] = {}
+    roots = config.get("roots") or {}
+    unknown = set(roots) - {"functions", "default"}
+    if unknown:
+        raise ValueError(f"配置文件 [roots] 中的未知选项: {', '.join(sorted(unknown))}")
+    if "root" in actions and roots.get("functions"):
+        defaults["root"] = _option_value(
+            "roots", "functions", roots["functions"], actions["root"], base_dir
+        )
+    if "no_default_roots" in actions and "default" in roots:
+        if not isinstance(roots["default"], bool):
+            raise ValueError("配置文件 [roots] 中的 default 应为 true 或 false")
+        defaults["no_default_roots"] = not roots["default"]
+
+    table = config.get(command) if command not in SHARED_TABLES else None
+    if isinstance(table, dict):
+        defaults.update(_defaults(command, table, command_parser, base_dir))
+    command_parser.set_defaults(**defaults)
@@ -0,0 +1,227 @@
+"""
+测试覆盖率数据
+读取 lcov（cargo llvm-cov --lcov、grcov、gcov、coverage.py 的 lcov 输出）或
+Cobertura XML（cargo llvm-cov --cobertura、coverage.py 的 xml 输出）格式的
+覆盖率报告，按函数的定义范围判断每个函数是否被执行过
+"""
+
+import os
+import xml.etree.ElementTree as ElementTree
+from pathlib import PurePosixPath
+from typing import Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+COVERAGE_FORMATS = ("auto", "lcov", "cobertura")
+
+# 每个源文件的覆盖率：lines 为 {行号: 执行次数}，functions 为
+# {函数起始行: 执行次数}（报告中有函数记录时）
+FileCoverage = Dict[str, Dict[int, int]]
+
+
+def _file_entry(report: Dict[str, FileCoverage], path: str) -> FileCoverage:
+    return report.setdefault(path, {"lines": {}, "functions": {}})
+
+
+def parse_lcov(text: str) -> Dict[str, FileCoverage]:
+    """
+    lcov 的 tracefile：SF 开始一个源文件，DA:行号,次数 为行覆盖率，
+    FN:行号,函数名 和 FNDA:次数,函数名 为函数覆盖率
+
+    Raises:
+        ValueError: 不是 lcov 格式
+    """
+    report: Dict[str, FileCoverage] = {}
+    entry: Optional[FileCoverage] = None
+    function_lines: Dict[str, int] = {}
+    for number, line in enumerate(text.splitlines(), 1):
+        line = line.strip()
+        if not line or line.startswith("TN:"):
+            continue
+        tag, _, value = line.partition(":")
+        if tag == "SF":
+            entry = _file_entry(report, value)
+            function_lines = {}
+            continue
+        if tag == "end_of_record":
+            entry = None
+            continue
+        if entry is None:
+            raise ValueError(f"第 {number} 行不在 SF 记录中: {line[:80]}")
+        try:
+            if tag == "DA":
+                line_number, hits = (int(item) for item in value.split(",")[:2])
+                lines = entry["lines"]
+                lines[line_number] = lines.get(line_number, 0) + hits
+            elif tag == "FN":
+                line_number, name = value.split(",", 1)
+                function_lines[name] = int(line_number)
+                entry["functions"].setdefault(int(line_number), 0)
+            elif tag == "FNDA":
+                hits, name = value.split(",", 1)
+                if name in function_lines:
+                    start = function_lines[name]
+                    # 泛型函数的各个实例化记录在同一行
+                    entry["functions"][start] += int(hits)
+        except ValueError as e:
+            raise ValueError(f"第 {number} 行不是 lcov 格式: {line[:80]}") from e
+    return report
+
+
+def parse_cobertura(text: str) -> Dict[str, FileCoverage]:
+    """
+    Cobertura XML：class 元素的 filename（相对于 sources 中的第一个目录）和
+    lines 中每一行的 hits
+
+    Raises:
+        ValueError: XML 格式错误
+    """
+    try:
+        root = ElementTree.fromstring(text)
+    except ElementTree.ParseError as e:
+        raise ValueError(f"无法解析 Cobertura XML: {e}") from e
+    sources = [
+        (source.text or "").strip() for source in root.iter("source") if source.text
+    ]
+    report: Dict[str, FileCoverage] = {}
+    for element in root.iter("class"):
+        filename = element.get("filename") or ""
+        if sources and not os.path.isabs(filename):
+            filename = os.path.join(sources[0], filename)
+        entry = _file_entry(report, filename)
+        for line in element.iter("line"):
+            try:
+                line_number = int(line.get("number") or 0)
+                hits = int(line.get("hits") or 0)
+            except ValueError:
+                continue
+            entry["lines"][line_number] = max(entry["lines"].get(line_number, 0), hits)
+    return report
+
+
+def load_coverage(path: str, coverage_format: str = "auto") -> Dict[str, FileCoverage]:
+    """
+    读取覆盖率报告；auto 时以 < 开头的为 Cobertura XML，否则为 lcov
+
+    Raises:
+        ValueError: 文件无法读取或格式错误
+    """
+    try:
+        with open(path, "r", encoding="utf-8", errors="replace") as f:
+            text = f.read()
+    except OSError as e:
+        raise ValueError(f"无法读取覆盖率报告 {path}: {e}") from e
+    if coverage_format == "auto":
+        coverage_format = "cobertura" if text.lstrip().startswith("<") else "lcov"
+    if coverage_format == "cobertura":
+        return parse_cobertura(text)
+    return parse_lcov(text)
+
+
+class CoverageOverlay:
+    """
+    把覆盖率报告对应到调用图中的函数
+
+    - 报告中的文件按路径后缀对应到函数所在的文件（报告中的路径可以是绝对
+      路径，也可以相对于项目根目录）
+    - 报告中有起始行上的函数记录时按函数记录判断；否则看函数定义范围内
+      （去掉其中嵌套的函数和闭包）可执行的行中是否有执行过的行
+    - 文件不在报告中、或范围内没有可执行行的函数没有覆盖率数据
+    """
+
+    def __init__(self, graph: CallGraph, report: Dict[str, FileCoverage]):
+        self.graph = graph
+        self.report = report
+        self._suffixes: Dict[Tuple[str, ...], List[str]] = {}
+        for path in report:
+            parts = PurePosixPath(path.replace("\\", "/")).parts
+            for start in range(len(parts)):
+                self._suffixes.setdefault(tuple(parts[start:]), []).append(path)
+
+    def file_coverage(self, file_path: str) -> Optional[FileCoverage]:
+        """函数所在文件的覆盖率：路径相同，或报告中的路径是它最长的唯一后缀"""
+        if file_path in self.report:
+            return self.report[file_path]
+        parts = PurePosixPath(file_path.replace("\\", "/")).parts
+        for start in range(len(parts)):
+            matches = self._suffixes.get(tuple(parts[start:]))
+            if matches:
+                return self.report[matches[0]] if len(matches) == 1 else None
+        return None
+
+    def apply(self) -> Dict[str, int]:
+        """
+        在节点 extras 上设置 covered（True / False，没有覆盖率数据时不设置）和
+        line_coverage（[执行过的行数, 可执行的行数]）
+
+        Returns:
+            {"covered", "uncovered", "unknown"}：各类函数的数量
+        """
+        by_file: Dict[str, List[str]] = {}
+        for node_id, node in self.graph.nodes.items():
+            extras = node.setdefault("extras", {})
+            extras.pop("covered", None)
+            extras.pop("line_coverage", None)
+            if node.get("file") and node.get("start_line"):
+                by_file.setdefault(node["file"], []).append(node_id)
+
+        counts = {"covered": 0, "uncovered": 0, "unknown": 0}
+        for file_path, node_ids in by_file.items():
+            coverage = self.file_coverage(file_path)
+            for node_id in node_ids:
+                status = None
+                if coverage is not None:
+                    status = self._status(node_id, node_ids, coverage)
+                if status is None:
+                    counts["unknown"] += 1
+                else:
+                    counts["covered" if status else "uncovered"] += 1
+        return counts
+
+    def _status(
+        self, node_id: str, siblings: List[str], coverage: FileCoverage
+    ) -> Optional[bool]:
+        node = self.graph.nodes[node_id]
+        start, end = node["start_line"], node.get("end_line") or node["start_line"]
+        nested = set()
+        for other_id in siblings:
+            other = self.graph.nodes[other_id]
+            other_start = other["start_line"]
+            other_end = other.get("end_line") or other_start
+            if other_id != node_id and start < other_start and other_end <= end:
+                nested.update(range(other_start, other_end + 1))
+        lines = [
+            hits
+            for line, hits in coverage["lines"].items()
+            if start <= line <= end and line not in nested
+        ]
+        extras = node["extras"]
+        if lines:
+            extras["line_coverage"] = [sum(1 for hits in lines if hits), len(lines)]
+        if start in coverage["functions"]:
+            extras["covered"] = coverage["functions"][start] > 0
+        elif lines:
+            extras["covered"] = any(lines)
+        return extras.get("covered")
+
+
+def apply_coverage(graph: CallGraph, report: Dict[str, FileCoverage]) -> Dict[str, int]:
+    """把覆盖率报告叠加到调用图上（见 CoverageOverlay.apply）"""
+    return CoverageOverlay(graph, report).apply()
+
+
+def coverage_summary(counts: Dict[str, int]) -> str:
+    """叠加结果的一行摘要"""
+    known = counts["covered"] + counts["uncovered"]
+    percent = counts["covered"] / known * 100 if known else 0.0
+    text = (
+        f"覆盖率数据: {known} 个函数中 {counts['covered']} 个（{percent:.1f}%）"
+        f"被执行过，{counts['uncovered']} 个没有被执行"
+    )
+    if counts["unknown"]:
+        text += f"，{counts['unknown']} 个没有覆盖率数据"
+    return text
@@ -7,6 +7,32 @@ import sqlite3
 from pathlib import Path
 from typing import Any, Dict, List, Optional
 
+# 在初始 schema 之后新增的列：{表名: {列名: 列定义}}
+SCHEMA_MIGRATIONS = {
+    "call_relations": {
+        "edge_kind": "TEXT DEFAULT 'static'",
+        "type_args": "TEXT",
+        "call_site_snippet": "TEXT",
+        "callee_path": "TEXT",
+        "confidence": "TEXT DEFAULT 'certain'",
+        "cfg": "TEXT",
+        "error_handling": "TEXT",
+    },
+    "files": {
+        "traits_json": "TEXT",
+    },
+}
+
+
+def symbols_changed(
+    functions: List[Dict[str, Any]], stored: Dict[str, Dict[str, Any]]
+) -> bool:
+    """函数的 extras 与数据库中的记录（get_symbol_extras）是否不一致"""
+    return any(
+        stored.get(func["id"]) != json.loads(json.dumps(func.get("extras", {})))
+        for func in functions
+    )
+
 
 class CallGraphDB:
     """调用关系数据库管理"""
@@ -25,8 +51,21 @@ class CallGraphDB:
         schema_path = Path(__file__).parent.parent / "init_db.sql"
         with open(schema_path, "r", encoding="utf-8") as f:
             self.conn.executescript(f.read())
+        self._migrate_schema()
         self.conn.commit()
 
+    def _migrate_schema(self):
+        """为旧版本数据库补充新增的列"""
+        cursor = self.conn.cursor()
+        for table, columns in SCHEMA_MIGRATIONS.items():
+            cursor.execute(f"PRAGMA table_info({table})")
+            existing = {row["name"] for row in cursor.fetchall()}
+            for column, definition in columns.items():
+                if column not in existing:
+                    cursor.execute(
+                        f"ALTER TABLE {table} ADD COLUMN {column} {definition}"
+                    )
+
     def insert_symbol(self, symbol: Dict[str, Any]):
         """插入符号信息"""
         cursor = self.conn.cursor()
@@ -63,8 +102,10 @@ class CallGraphDB:
             """
             INSERT INTO call_relations 
             (caller_id, callee_id, caller_name, callee_name, caller_file, 
-             callee_file, call_site_line, call_site_column, language)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             callee_file, call_site_line, call_site_column, call_site_snippet,
+             language, edge_kind, type_args, callee_path, confidence, cfg,
+             error_handling)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         """,
             (
                 relation["caller_id"],
@@ -75,7 +116,14 @@ class CallGraphDB:
                 relation.get("callee_file"),
                 relation.get("call_site_line"),
                 relation.get("call_site_column"),
+                relation.get("call_site_snippet"),
                 relation["language"],
+                relation.get("edge_kind", "static"),
+                relation.get("type_args"),
+                relation.get("callee_path"),
+                relation.get("confidence", "certain"),
+                relation.get("cfg"),
+                relation.get("error_handling"),
             ),
         )
         self.conn.commit()
@@ -130,6 +178,13 @@ class CallGraphDB:
         dfs(function_name, [], 0)
         return chains
 
+    def get_symbol(self, symbol_id: str) -> Optional[Dict[str, Any]]:
+        """按 ID 获取符号"""
+        cursor = self.conn.cursor()
+        cursor.execute("SELECT * FROM symbols WHERE id = ?", (symbol_id,))
+        row = cursor.fetchone()
+        return dict(row) if row else None
+
     def get_function_info(self, func_name: str) -> Optional[Dict[str, Any]]:
         """获取函数的详细信息（文件和行号）"""
         cursor = self.conn.cursor()
@@ -458,11 +513,96 @@ class CallGraphDB:
             "by_kind": by_kind,
         }
 
+    def get_file_hashes(self) -> Dict[str, str]:
+        """获取已分析文件的内容哈希：{路径: 哈希}"""
+        cursor = self.conn.cursor()
+        cursor.execute("SELECT path, hash FROM files")
+        return {row["path"]: row["hash"] for row in cursor.fetchall()}
+
+    def get_symbol_extras(self) -> Dict[str, Dict[str, Any]]:
+        """获取所有符号的附加信息：{符号ID: extras}"""
+        cursor = self.conn.cursor()
+        cursor.execute("SELECT id, extras_json FROM symbols")
+        return {
+            row["id"]: json.loads(row["extras_json"] or "{}")
+            for row in cursor.fetchall()
+        }
+
+    def upsert_file(
+        self,
+        file_path: str,
+        content_hash: str,
+        language: str,
+        items: Optional[Dict[str, Any]] = None,
+    ):
+        """
+        记录文件的内容哈希
+
+        items 为解析结果中的模块项，其中的 trait 定义和 impl 块（traits、impls）
+        一并保存，供 get_trait_items 使用
+        """
+        traits = {key: (items or {}).get(key) or [] for key in ("traits", "impls")}
+        traits_json = (
+            json.dumps(traits, ensure_ascii=False) if any(traits.values()) else None
+        )
+        cursor = self.conn.cursor()
+        cursor.execute(
+            "INSERT OR REPLACE INTO files (path, hash, language, traits_json)"
+            " VALUES (?, ?, ?, ?)",
+            (file_path, content_hash, language, traits_json),
+        )
+        self.conn.commit()
+
+    def get_trait_items(self) -> Dict[str, List[Dict[str, Any]]]:
+        """
+        所有文件中的 trait 定义和 impl 块：{"traits": [...], "impls": [...]}，
+        每一项加上所在文件 file（字段见 RustParser.collect_module_items）
+        """
+        cursor = self.conn.cursor()
+        cursor.execute(
+            "SELECT path, traits_json FROM files WHERE traits_json IS NOT NULL"
+            " ORDER BY path"
+        )
+        result: Dict[str, List[Dict[str, Any]]] = {"traits": [], "impls": []}
+        for row in cursor.fetchall():
+            stored = json.loads(row["traits_json"])
+            for key, entries in result.items():
+                entries.extend(
+                    {**entry, "file": row["path"]} for entry in stored.get(key) or []
+                )
+        return result
+
+    def delete_file(self, file_path: str):
+        """删除文件的符号、从该文件发出的调用关系以及文件记录"""
+        cursor = self.conn.cursor()
+        cursor.execute("DELETE FROM call_relations WHERE caller_file = ?", (file_path,))
+        cursor.execute("DELETE FROM symbols WHERE file = ?", (file_path,))
+        cursor.execute("DELETE FROM files WHERE path = ?", (file_path,))
+        self.conn.commit()
+
+    def delete_relations_from(self, caller_ids) -> int:
+        """删除这些函数发出的调用关系，返回删除的数量"""
+        cursor = self.conn.cursor()
+        before = self.conn.total_changes
+        cursor.executemany(
+            "DELETE FROM call_relations WHERE caller_id = ?",
+            [(caller_id,) for caller_id in caller_ids],
+        )
+        self.conn.commit()
+        return self.conn.total_changes - before
+
+    def clear_relations(self):
+        """清空调用关系（符号保留）"""
+        cursor = self.conn.cursor()
+        cursor.execute("DELETE FROM call_relations")
+        self.conn.commit()
+
     def clear_all(self):
         """清空所有数据"""
         cursor = self.conn.cursor()
         cursor.execute("DELETE FROM call_relations")
         cursor.execute("DELETE FROM symbols")
+        cursor.execute("DELETE FROM files")
         self.conn.commit()
 
     def close(self):
@@ -0,0 +1,190 @@
+"""
+依赖 crate
+把本地代码调用到的外部依赖（如 serde_json::to_string、tokio::spawn）中的函数
+加入调用图：源码取自 cargo metadata 给出的目录（通常是 cargo registry 缓存），
+只保留沿调用链从本地代码出发、在深度限制之内可达的函数
+"""
+
+import os
+from typing import Any, Callable, Dict, List, Set
+
+# 支持相对导入和直接运行
+try:
+    from .cargo import CargoWorkspace, Crate, function_path, run_cargo_metadata
+except ImportError:
+    from cargo import CargoWorkspace, Crate, function_path, run_cargo_metadata
+
+# --include-deps 默认沿调用链深入依赖的层数
+DEFAULT_DEPENDENCY_DEPTH = 1
+
+
+def dependency_source_files(crate: Crate, exclude_dirs: List[str]) -> List[str]:
+    """依赖 crate 的库源文件（src 下的 .rs 文件，不含 tests、examples 等目标）"""
+    source_files = []
+    for root, dirs, files in os.walk(crate.directory / "src"):
+        dirs[:] = [d for d in dirs if d not in exclude_dirs and not d.startswith(".")]
+        for file in sorted(files):
+            if file.endswith(".rs"):
+                source_files.append(os.path.join(root, file))
+    return source_files
+
+
+def referenced_crates(
+    workspace: CargoWorkspace, call_refs: List[Dict[str, Any]]
+) -> Set[str]:
+    """调用引用中按路径指向的依赖 crate"""
+    crates = set()
+    for ref in call_refs:
+        for path in (ref.get("hints") or {}).get("qualified") or []:
+            name = path.split("::", 1)[0]
+            if name in workspace.dependency_crates:
+                crates.add(name)
+    return crates
+
+
+def external_refs(
+    workspace: CargoWorkspace, call_refs: List[Dict[str, Any]]
+) -> List[Dict[str, Any]]:
+    """
+    可能调用依赖 crate 的调用引用：按路径指向工作区之外的 crate
+
+    依赖 crate 可能在 cargo metadata 之后才识别，这里只排除工作区中的 crate
+    """
+    return [
+        ref
+        for ref in call_refs
+        if any(
+            path.split("::", 1)[0] not in workspace.crates
+            for path in (ref.get("hints") or {}).get("qualified") or []
+        )
+    ]
+
+
+def _rust_refs(file_results: Dict[str, Dict[str, Any]]) -> List[Dict[str, Any]]:
+    return [
+        ref
+        for result in file_results.values()
+        if result.get("language") == "rust"
+        for ref in result["call_refs"]
+    ]
+
+
+def _attach_metadata(workspace: CargoWorkspace):
+    """
+    为由 Cargo.toml 识别的工作区运行 cargo metadata
+
+    项目中可能有多个互不相关的工作区，对还没有覆盖到的 crate 逐个运行
+    """
+    covered: Set[str] = set()
+    for name, crate in sorted(workspace.crates.items()):
+        if name in covered:
+            continue
+        try:
+            metadata = run_cargo_metadata(
+                manifest_path=str(crate.directory / "Cargo.toml")
+            )
+        except ValueError as e:
+            print(f"警告: 无法获取 {crate.package} 的依赖信息: {e}")
+            continue
+        covered.update(workspace.attach_metadata(metadata))
+
+
+def _with_closures(ids: Set[str], closures: Dict[str, List[str]]) -> Set[str]:
+    """函数及其中定义的（嵌套）闭包"""
+    result = set(ids)
+    pending = list(ids)
+    while pending:
+        for closure_id in closures.get(pending.pop(), []):
+            if closure_id not in result:
+                result.add(closure_id)
+                pending.append(closure_id)
+    return result
+
+
+def include_dependencies(
+    workspace: CargoWorkspace,
+    file_results: Dict[str, Dict[str, Any]],
+    depth: int,
+    parse_files: Callable[[List[str]], Dict[str, Dict[str, Any]]],
+    exclude_dirs: List[str],
+) -> Dict[str, Dict[str, Any]]:
+    """
+    解析被调用到的依赖 crate，返回需要加入调用图的依赖文件结果
+
+    第 1 层是本地代码按路径直接调用的依赖函数，第 n 层是第 n-1 层函数调用的
+    依赖函数；依赖 crate 只在被引用到时才解析，函数中定义的闭包随函数一起加入。
+    超出深度的依赖函数被丢弃，对它们的调用与其他外部调用一样显示为 external
+
+    由 Cargo.toml 识别的工作区没有依赖源码的位置，先运行 cargo metadata 获取
+
+    Args:
+        file_results: 本地代码的解析结果（已由 workspace.annotate 标注；
+            只用到其中的调用引用，可以只包含 external_refs）
+        depth: 最多深入的层数
+        parse_files: 解析文件列表，返回 {文件: 解析结果}
+    """
+    if workspace.target_directory is None:
+        _attach_metadata(workspace)
+        if workspace.target_directory is None:
+            return {}
+
+    loaded: Set[str] = set()
+    dependency_results: Dict[str, Dict[str, Any]] = {}
+    by_path: Dict[str, Dict[str, Any]] = {}
+    closures: Dict[str, List[str]] = {}
+    kept: Set[str] = set()
+    frontier = _rust_refs(file_results)
+
+    for _ in range(depth):
+        crates = sorted(referenced_crates(workspace, frontier) - loaded)
+        if crates:
+            loaded.update(crates)
+            files = []
+            for name in crates:
+                files.extend(
+                    dependency_source_files(
+                        workspace.dependency_crates[name], exclude_dirs
+                    )
+                )
+            results = parse_files(files)
+            workspace.annotate(results)
+            dependency_results.update(results)
+            for result in results.values():
+                for func in result["functions"]:
+                    extras = func.get("extras") or {}
+                    if extras.get("closure"):
+                        closures.setdefault(extras["parent"], []).append(func["id"])
+                    path = function_path(func)
+                    if path:
+                        by_path.setdefault(path, func)
+
+        reached = set()
+        for ref in frontier:
+            for path in (ref.get("hints") or {}).get("qualified") or []:
+                func = by_path.get(path)
+                if func:
+                    if func["id"] not in kept:
+                        reached.add(func["id"])
+                    break
+        if not reached:
+            break
+        # 闭包随定义它的函数一起加入
+        reached = _with_closures(reached, closures)
+        kept.update(reached)
+        frontier = [
+            ref
+            for ref in _rust_refs(dependency_results)
+            if ref["caller_id"] in reached
+        ]
+
+    included = {}
+    for file_path, result in dependency_results.items():
+        functions = [func for func in result["functions"] if func["id"] in kept]
+        if not functions:
+            continue
+        result["functions"] = functions
+        result["call_refs"] = [
+            ref for ref in result["call_refs"] if ref["caller_id"] in kept
+        ]
+        included[file_path] = result
+    return included
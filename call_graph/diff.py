@@ -0,0 +1,102 @@
+"""
+调用图差异
+比较两个版本（git 修订版本或目录）的调用图：新增/删除的函数、调用边和模块依赖
+"""
+
+import os
+import subprocess
+import tarfile
+from typing import Any, Dict, List, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+
+def _git(repo: str, *args: str, binary: bool = False):
+    """在 repo 目录中执行 git 命令，返回标准输出"""
+    try:
+        result = subprocess.run(
+            ["git", *args], cwd=repo, capture_output=True, check=False
+        )
+    except FileNotFoundError as e:
+        raise ValueError("没有找到 git 命令") from e
+    if result.returncode != 0:
+        message = result.stderr.decode("utf-8", "replace").strip()
+        raise ValueError(f"git {' '.join(args)} 失败: {message}")
+    return result.stdout if binary else result.stdout.decode("utf-8").strip()
+
+
+def export_revision(repo: str, revision: str, destination: str) -> str:
+    """
+    把 git 修订版本中 repo 目录的内容导出到 destination
+
+    repo 可以是仓库中的子目录，此时只导出该子目录
+
+    Returns:
+        导出后与 repo 对应的目录
+    """
+    top = _git(repo, "rev-parse", "--show-toplevel")
+    prefix = _git(repo, "rev-parse", "--show-prefix")
+    content = _git(top, "archive", "--format=tar", revision, prefix or ".", binary=True)
+    archive = os.path.join(destination, "revision.tar")
+    with open(archive, "wb") as f:
+        f.write(content)
+    with tarfile.open(archive) as tar:
+        # Python 3.12 起可以用 data 过滤器拒绝绝对路径和指向目录外的成员
+        if hasattr(tarfile, "data_filter"):
+            tar.extractall(destination, filter="data")
+        else:
+            tar.extractall(destination)
+    os.remove(archive)
+    return os.path.join(destination, prefix)
+
+
+def _functions(graph: CallGraph) -> Set[str]:
+    """所有函数的限定名（同名重载视为同一个函数）"""
+    return {graph.qualified_name(node_id) for node_id in graph.nodes}
+
+
+def _edges(graph: CallGraph) -> Set[Tuple[str, str]]:
+    """按限定名表示的调用边"""
+    return {
+        (graph.qualified_name(e["caller_id"]), graph.qualified_name(e["callee_id"]))
+        for e in graph.edges()
+    }
+
+
+def module_dependencies(graph: CallGraph) -> Set[Tuple[str, str]]:
+    """模块之间的依赖：(调用方模块, 被调用方模块)"""
+    dependencies = set()
+    for edge in graph.edges():
+        caller = graph.module_name(edge["caller_id"])
+        callee = graph.module_name(edge["callee_id"])
+        if caller != callee:
+            dependencies.add((caller, callee))
+    return dependencies
+
+
+def diff_graphs(base: CallGraph, head: CallGraph) -> Dict[str, List[Any]]:
+    """
+    比较两个调用图
+
+    函数按限定名对应（行号变化不算差异）。结果包含：
+    added_functions / removed_functions（限定名）、added_edges / removed_edges、
+    added_dependencies / removed_dependencies（模块依赖），均已排序
+    """
+    base_functions = _functions(base)
+    head_functions = _functions(head)
+    base_edges = _edges(base)
+    head_edges = _edges(head)
+    base_dependencies = module_dependencies(base)
+    head_dependencies = module_dependencies(head)
+    return {
+        "added_functions": sorted(head_functions - base_functions),
+        "removed_functions": sorted(base_functions - head_functions),
+        "added_edges": sorted(head_edges - base_edges),
+        "removed_edges": sorted(base_edges - head_edges),
+        "added_dependencies": sorted(head_dependencies - base_dependencies),
+        "removed_dependencies": sorted(base_dependencies - head_dependencies),
+    }
@@ -0,0 +1,223 @@
+"""
+源码注释指令
+在注释中标注静态分析无法正确处理的调用（支持所有内置语言的注释）::
+
+    handler.run()  // callgraph: ignore            忽略这一行的所有调用
+    // callgraph: ignore log, trace                忽略下一行中对 log、trace 的调用
+    // callgraph: edge -> plugins::load            所在函数调用 plugins::load
+    # callgraph: edge app.main -> app.jobs.run     声明任意两个函数之间的调用
+
+ignore 去掉误报的调用；edge 加入通过配置驱动的分发、反射等方式发生、解析器
+看不到的调用，边类型为 annotated
+"""
+
+import re
+from typing import Any, Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph, EdgeKind
+except ImportError:
+    from graph import CallGraph, EdgeKind
+
+DIRECTIVE_PATTERN = re.compile(r"\bcallgraph:\s*(?P<action>[\w-]+)(?P<args>.*)")
+
+EDGE_KIND = EdgeKind.ANNOTATED.value
+
+
+def _names(text: str) -> List[str]:
+    return [name for name in re.split(r"[\s,]+", text.strip()) if name]
+
+
+def parse_directive(text: str) -> Optional[Dict[str, Any]]:
+    """
+    解析注释中的指令；没有 callgraph: 标记时返回 None
+
+    Returns:
+        {"kind": "ignore", "names": [...]}、
+        {"kind": "edge", "caller": 调用者或 None, "callees": [...]}，
+        无法解析时为 {"kind": "invalid", "text": 指令}
+    """
+    match = DIRECTIVE_PATTERN.search(text)
+    if not match:
+        return None
+    # 去掉块注释的结尾
+    args = match.group("args").split("*/")[0].strip()
+    action = match.group("action")
+    if action == "ignore":
+        return {"kind": "ignore", "names": _names(args)}
+    if action == "edge" and "->" in args:
+        caller, _, callees = args.partition("->")
+        callees = _names(callees)
+        if callees:
+            caller = caller.strip() or None
+            return {"kind": "edge", "caller": caller, "callees": callees}
+    return {"kind": "invalid", "text": match.group(0).split("*/")[0].strip()}
+
+
+def collect_directives(
+    comments: List[Tuple[str, int, int, bool]], functions: List[Dict[str, Any]]
+) -> List[Dict[str, Any]]:
+    """
+    从注释中收集指令
+
+    Args:
+        comments: (注释文本, 起始行, 结束行, 是否在代码之后)
+        functions: 本文件的函数定义（确定 edge 指令所在的函数）
+
+    Returns:
+        指令列表，line 为注释所在的行；ignore 指令的 target_line 为作用的行
+        （代码之后的注释为同一行，单独一行的注释为下一行）；edge 指令的
+        caller_id 为所在的函数（最内层）
+    """
+    directives = []
+    for text, start_line, end_line, trailing in comments:
+        directive = parse_directive(text)
+        if directive is None:
+            continue
+        directive["line"] = start_line
+        if directive["kind"] == "ignore":
+            directive["target_line"] = start_line if trailing else end_line + 1
+        elif directive["kind"] == "edge":
+            enclosing = [
+                func
+                for func in functions
+                if func["start_line"] <= start_line <= func["end_line"]
+            ]
+            if enclosing:
+                innermost = min(
+                    enclosing, key=lambda func: func["end_line"] - func["start_line"]
+                )
+                directive["caller_id"] = innermost["id"]
+        directives.append(directive)
+    return directives
+
+
+def _ignored(ref: Dict[str, Any], directive: Dict[str, Any]) -> bool:
+    if ref.get("call_site_line") != directive["target_line"]:
+        return False
+    if not directive["names"]:
+        return True
+    name = ref.get("call_name") or ""
+    short_name = name.replace("::", ".").rsplit(".", 1)[-1]
+    return any(
+        target in (name, short_name) or name.endswith(f"::{target}")
+        for target in directive["names"]
+    )
+
+
+def drop_ignored_refs(result: Dict[str, Any]) -> int:
+    """去掉一个文件中 ignore 指令作用的调用引用，返回去掉的数量"""
+    ignores = [d for d in result.get("directives") or [] if d["kind"] == "ignore"]
+    if not ignores:
+        return 0
+    refs = result["call_refs"]
+    result["call_refs"] = [
+        ref for ref in refs if not any(_ignored(ref, d) for d in ignores)
+    ]
+    return len(refs) - len(result["call_refs"])
+
+
+def apply_directives(
+    file_results: Dict[str, Dict[str, Any]], functions: List[Dict[str, Any]]
+) -> Tuple[int, List[Dict[str, Any]]]:
+    """
+    应用解析结果中的注释指令：去掉 ignore 指令作用的调用引用，把 edge 指令
+    解析为调用关系（函数名规则同 query，按名称或限定名后缀匹配）
+
+    需要在解析调用关系之前、对完整的函数表执行一次
+
+    Returns:
+        (忽略的调用引用数量, edge 指令声明的调用关系)
+    """
+    ignored = 0
+    edges = []
+    for file_path, result in file_results.items():
+        ignored += drop_ignored_refs(result)
+        for directive in result.get("directives") or []:
+            if directive["kind"] == "invalid":
+                print(
+                    f"警告: 无法解析的注释指令 {file_path}:{directive['line']}: "
+                    f"{directive['text']}"
+                )
+            elif directive["kind"] == "edge":
+                edges.append((file_path, result.get("language"), directive))
+    if not edges:
+        return ignored, []
+
+    graph = CallGraph()
+    for func in functions:
+        graph.add_node(func)
+
+    def lookup(name: str, location: str) -> List[str]:
+        matches = graph.resolve(name)
+        if not matches:
+            print(f"警告: 注释指令中的函数 '{name}' 不存在 ({location})")
+        return matches
+
+    relations = []
+    for file_path, language, directive in edges:
+        location = f"{file_path}:{directive['line']}"
+        if directive["caller"]:
+            callers = lookup(directive["caller"], location)
+        elif directive.get("caller_id") in graph.nodes:
+            callers = [directive["caller_id"]]
+        else:
+            print(f"警告: 注释指令不在函数中，需要指定调用者 ({location})")
+            continue
+        callees = [
+            callee_id
+            for name in directive["callees"]
+            for callee_id in lookup(name, location)
+        ]
+        for caller_id in callers:
+            caller = graph.nodes[caller_id]
+            for callee_id in callees:
+                callee = graph.nodes[callee_id]
+                relations.append(
+                    {
+                        "caller_id": caller_id,
+                        "callee_id": callee_id,
+                        "caller_name": caller["name"],
+                        "callee_name": callee["name"],
+                        "caller_file": file_path,
+                        "callee_file": callee.get("file"),
+                        "call_site_line": directive["line"],
+                        "call_site_column": None,
+                        "language": language or caller.get("language"),
+                        "edge_kind": EDGE_KIND,
+                        "confidence": "certain",
+                    }
+                )
+    return ignored, relations
+
+
+def ignore_relations(
+    relations: List[Dict[str, Any]], file_results: Dict[str, Dict[str, Any]]
+) -> Tuple[List[Dict[str, Any]], int]:
+    """
+    对不经过调用引用生成的调用关系（如 MIR 后端）应用 ignore 指令
+
+    Returns:
+        (保留的调用关系, 忽略的数量)
+    """
+    ignores: Dict[str, List[Dict[str, Any]]] = {}
+    for file_path, result in file_results.items():
+        for directive in result.get("directives") or []:
+            if directive["kind"] == "ignore":
+                ignores.setdefault(file_path, []).append(directive)
+    kept = [
+        relation
+        for relation in relations
+        if not any(
+            _ignored(
+                {
+                    "call_site_line": relation["call_site_line"],
+                    "call_name": relation["callee_name"],
+                },
+                directive,
+            )
+            for directive in ignores.get(relation["caller_file"], [])
+        )
+    ]
+    return kept, len(relations) - len(kept)
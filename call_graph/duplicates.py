@@ -0,0 +1,175 @@
+"""
+重复函数检测
+用两种廉价的指纹找出可疑的相似函数：函数体的规范化词法单元哈希（见
+parsers 中的 body_fingerprint，只重命名了变量或修改了常量的函数指纹相同），
+以及被调用函数的多重集合（调用了几乎相同的一组函数）。不是完整的克隆检测，
+用于在重构前快速找出候选
+"""
+
+from collections import Counter
+from typing import Any, Dict, Iterable, List
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import is_test_code
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import is_test_code
+    from graph import CallGraph
+
+# 只比较至少有这么多词法单元的函数（跳过 getter 等很短的函数）
+DEFAULT_MIN_TOKENS = 30
+
+# 按调用比较时，函数至少要有这么多调用点
+DEFAULT_MIN_CALLS = 3
+
+# 被调用函数多重集合的默认相似度阈值（加权 Jaccard）
+DEFAULT_CALLEE_SIMILARITY = 0.8
+
+# 被这么多函数调用的函数（日志、格式化等）不用于查找候选，但仍参与相似度计算
+COMMON_CALLEE_LIMIT = 50
+
+
+def callee_fingerprints(
+    graph: CallGraph, node_ids: Iterable[str]
+) -> Dict[str, Counter]:
+    """
+    被调用函数的多重集合：{函数ID: {被调用函数: 调用点数量}}
+
+    项目内的函数为限定名，项目外的调用为 use 展开后的路径或调用名
+    """
+    fingerprints: Dict[str, Counter] = {node_id: Counter() for node_id in node_ids}
+    for relation in graph.relations:
+        callees = fingerprints.get(relation["caller_id"])
+        if callees is None:
+            continue
+        callee_id = relation["callee_id"]
+        if callee_id in graph.nodes:
+            callees[graph.qualified_name(callee_id)] += 1
+        else:
+            callees[relation.get("callee_path") or relation["callee_name"]] += 1
+    return fingerprints
+
+
+def _similarity(a: Counter, b: Counter) -> float:
+    """多重集合的加权 Jaccard 相似度"""
+    union = sum((a | b).values())
+    return sum((a & b).values()) / union if union else 0.0
+
+
+def _common(fingerprints: Iterable[Counter]) -> Counter:
+    fingerprints = list(fingerprints)
+    common = fingerprints[0]
+    for fingerprint in fingerprints[1:]:
+        common = common & fingerprint
+    return common
+
+
+def find_duplicates(
+    graph: CallGraph,
+    min_tokens: int = DEFAULT_MIN_TOKENS,
+    min_calls: int = DEFAULT_MIN_CALLS,
+    similarity: float = DEFAULT_CALLEE_SIMILARITY,
+    include_tests: bool = False,
+) -> List[Dict[str, Any]]:
+    """
+    相似函数的分组
+
+    函数体哈希相同，或被调用函数的相似度不低于 similarity（两者都至少有
+    min_calls 个调用点）的函数连成一组
+
+    Returns:
+        [{"members", "same_body", "tokens", "similarity", "shared_callees"}]：
+        members 为函数ID（按限定名排序）；same_body 为所有成员的函数体哈希
+        相同；tokens 为成员的最大词法单元数；similarity 为所有成员被调用函数
+        的相似度（交集 / 并集）；shared_callees 为所有成员都调用的函数。
+        函数体相同的分组在前，其余按成员数和相似度降序
+    """
+    candidates = []
+    for node_id, node in graph.nodes.items():
+        extras = node.get("extras") or {}
+        if graph.node_kind(node_id) not in ("function", "method", "closure"):
+            continue
+        if (extras.get("tokens") or 0) < min_tokens:
+            continue
+        if not include_tests and is_test_code(graph, node_id):
+            continue
+        candidates.append(node_id)
+
+    parent = {node_id: node_id for node_id in candidates}
+
+    def find(node_id: str) -> str:
+        while parent[node_id] != node_id:
+            parent[node_id] = parent[parent[node_id]]
+            node_id = parent[node_id]
+        return node_id
+
+    def union(a: str, b: str):
+        parent[find(a)] = find(b)
+
+    # 函数体哈希相同
+    by_hash: Dict[str, List[str]] = {}
+    for node_id in candidates:
+        body_hash = graph.nodes[node_id]["extras"].get("body_hash")
+        if body_hash:
+            by_hash.setdefault(body_hash, []).append(node_id)
+    for members in by_hash.values():
+        for other in members[1:]:
+            union(members[0], other)
+
+    # 被调用函数相似：通过共同调用的（不太常见的）函数查找候选对
+    fingerprints = callee_fingerprints(graph, candidates)
+    callers_of: Dict[str, List[str]] = {}
+    for node_id, fingerprint in fingerprints.items():
+        if sum(fingerprint.values()) >= min_calls:
+            for callee in fingerprint:
+                callers_of.setdefault(callee, []).append(node_id)
+    compared = set()
+    for callers in callers_of.values():
+        if len(callers) > COMMON_CALLEE_LIMIT:
+            continue
+        for i, a in enumerate(callers):
+            for b in callers[i + 1 :]:
+                if (a, b) in compared:
+                    continue
+                compared.add((a, b))
+                if _similarity(fingerprints[a], fingerprints[b]) >= similarity:
+                    union(a, b)
+
+    groups: Dict[str, List[str]] = {}
+    for node_id in candidates:
+        groups.setdefault(find(node_id), []).append(node_id)
+
+    clusters = []
+    for members in groups.values():
+        if len(members) < 2:
+            continue
+        members.sort(key=graph.qualified_name)
+        hashes = {graph.nodes[n]["extras"].get("body_hash") for n in members}
+        member_fingerprints = [fingerprints[n] for n in members]
+        common = _common(member_fingerprints)
+        union_all: Counter = Counter()
+        for fingerprint in member_fingerprints:
+            union_all |= fingerprint
+        total = sum(union_all.values())
+        clusters.append(
+            {
+                "members": members,
+                "same_body": len(hashes) == 1 and None not in hashes,
+                "tokens": max(graph.nodes[n]["extras"]["tokens"] for n in members),
+                "similarity": (
+                    round(sum(common.values()) / total, 2) if total else 0.0
+                ),
+                "shared_callees": sorted(common),
+            }
+        )
+    clusters.sort(
+        key=lambda c: (
+            not c["same_body"],
+            -len(c["members"]),
+            -c["similarity"],
+            -c["tokens"],
+            graph.qualified_name(c["members"][0]),
+        )
+    )
+    return clusters
@@ -0,0 +1,143 @@
+"""
+副作用标记
+在配置文件中按模式声明副作用标签（如 std::fs::* 为 io、alloc::* 为 alloc），
+标记直接调用匹配函数的函数，再沿调用图传播到所有可能间接触发这些副作用的函数，
+用于在嵌入式代码中划定不分配内存、不做 IO 的区域
+"""
+
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import shortest_paths_to
+    from .cargo import load_toml
+    from .filters import Pattern, node_match_text
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import shortest_paths_to
+    from cargo import load_toml
+    from filters import Pattern, node_match_text
+    from graph import CallGraph
+
+# 命令行中分隔标签和模式的符号：<标签>=<模式>
+EFFECT_SEPARATOR = "="
+
+
+class EffectSet:
+    """
+    副作用标签及其模式
+
+    模式规则同 --include/--exclude（glob 或 re:正则）：项目外的被调用函数按
+    调用路径（如 std::fs::read；无法确定路径时为函数名）匹配，项目内的函数
+    按限定名和文件路径匹配
+    """
+
+    def __init__(self, effects: Optional[Dict[str, List[str]]] = None):
+        self.patterns: Dict[str, List[Pattern]] = {}
+        for name, patterns in (effects or {}).items():
+            if isinstance(patterns, str):
+                patterns = [patterns]
+            self.patterns.setdefault(name, []).extend(Pattern(p) for p in patterns)
+
+    def __bool__(self) -> bool:
+        return any(self.patterns.values())
+
+    @classmethod
+    def from_config(cls, config_path: str) -> "EffectSet":
+        """
+        从 TOML 配置文件的 [effects] 表读取标签::
+
+            [effects]
+            io = ["std::fs::*", "std::net::*", "std::io::*"]
+            alloc = ["alloc::*", "Box::new", "Vec::*", "String::from"]
+        """
+        path = Path(config_path)
+        if not path.exists():
+            raise ValueError(f"配置文件不存在: {config_path}")
+        return cls(load_toml(path).get("effects") or {})
+
+    @classmethod
+    def from_specs(cls, specs: List[str]) -> "EffectSet":
+        """命令行中的 <标签>=<模式> 列表"""
+        effects: Dict[str, List[str]] = {}
+        for spec in specs:
+            name, separator, pattern = spec.partition(EFFECT_SEPARATOR)
+            if not separator or not name.strip() or not pattern.strip():
+                raise ValueError(f"无效的副作用定义 '{spec}'：格式应为 <标签>=<模式>")
+            effects.setdefault(name.strip(), []).append(pattern.strip())
+        return cls(effects)
+
+    def merge(self, other: "EffectSet") -> "EffectSet":
+        """合并两组标签（如配置文件和命令行）"""
+        merged = EffectSet()
+        for effects in (self, other):
+            for name, patterns in effects.patterns.items():
+                merged.patterns.setdefault(name, []).extend(patterns)
+        return merged
+
+    def names(self) -> List[str]:
+        return sorted(name for name, patterns in self.patterns.items() if patterns)
+
+    def _matching(self, qualified_name: str, file_path: str) -> List[str]:
+        return [
+            name
+            for name in self.names()
+            if any(p.matches(qualified_name, file_path) for p in self.patterns[name])
+        ]
+
+    def direct_effects(self, graph: CallGraph) -> Dict[str, Dict[str, Dict[str, Any]]]:
+        """
+        直接触发副作用的函数：调用了匹配的项目外函数，或自身匹配
+
+        Returns:
+            {函数ID: {标签: {"callee", "line", "count"}}}：callee 为第一个匹配的
+            被调用函数（自身匹配时为 None），line 为其调用点行号（自身匹配时为
+            定义行号），count 为匹配的调用点数量
+        """
+        effects: Dict[str, Dict[str, Dict[str, Any]]] = {}
+        for node_id in graph.nodes:
+            for name in self._matching(*node_match_text(graph, node_id)):
+                effects.setdefault(node_id, {})[name] = {
+                    "callee": None,
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "count": 1,
+                }
+        for relation in graph.relations:
+            caller_id = relation["caller_id"]
+            if caller_id not in graph.nodes or relation["callee_id"] in graph.nodes:
+                continue
+            callee = relation.get("callee_path") or relation["callee_name"]
+            for name in self._matching(callee, ""):
+                site = effects.setdefault(caller_id, {}).get(name)
+                if site is None:
+                    effects[caller_id][name] = {
+                        "callee": callee,
+                        "line": relation.get("call_site_line"),
+                        "count": 1,
+                    }
+                elif site["callee"] is not None:
+                    site["count"] += 1
+        return effects
+
+    def propagate(
+        self, graph: CallGraph, direct: Optional[Dict[str, Dict[str, Any]]] = None
+    ) -> Dict[str, Dict[str, List[str]]]:
+        """
+        每个函数可能直接或间接触发的副作用
+
+        Args:
+            direct: direct_effects 的结果，省略时重新计算
+
+        Returns:
+            {函数ID: {标签: 到最近的直接触发函数的最短调用路径}}，
+            不包含没有任何副作用的函数
+        """
+        if direct is None:
+            direct = self.direct_effects(graph)
+        effects: Dict[str, Dict[str, List[str]]] = {}
+        for name in self.names():
+            targets = [node_id for node_id, tags in direct.items() if name in tags]
+            for node_id, path in shortest_paths_to(graph, graph.nodes, targets).items():
+                effects.setdefault(node_id, {})[name] = path
+        return effects
@@ -0,0 +1,122 @@
+"""
+错误传播
+根据函数的返回类型（Result / Option）和调用点对返回结果的处理方式（? 转发、
+match 处理、unwrap 等，见 parsers.ERROR_HANDLING），从可能失败的底层函数出发，
+沿调用者向上跟踪错误的传播链：哪些调用者继续转发错误，哪些在本地处理
+"""
+
+from typing import Any, Dict, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+    from .parsers import ERROR_HANDLING
+except ImportError:
+    from graph import CallGraph
+    from parsers import ERROR_HANDLING
+
+# 没有记录处理方式的调用点（存入变量、作为参数传递等）
+UNTRACKED = "untracked"
+
+# 处理方式的说明（用于文本输出）
+HANDLING_LABELS = {
+    "propagate": "转发",
+    "match": "match 处理",
+    "unwrap": "unwrap（失败时 panic）",
+    "convert": "转换为默认值",
+    "ignore": "丢弃",
+    UNTRACKED: "未跟踪",
+}
+
+# 错误传播树默认展开的层数
+DEFAULT_ERROR_DEPTH = 5
+
+
+def fallible_type(graph: CallGraph, node_id: str) -> Optional[str]:
+    """函数返回的可能失败的类型：Result / Option，其他为 None"""
+    return (graph.nodes[node_id].get("extras") or {}).get("fallible")
+
+
+def site_handling(site: Dict[str, Any]) -> str:
+    """调用点的处理方式（见 ERROR_HANDLING），没有记录时为 UNTRACKED"""
+    handling = site.get("error_handling")
+    return handling if handling in ERROR_HANDLING else UNTRACKED
+
+
+def error_callers(graph: CallGraph, node_id: str) -> List[Dict[str, Any]]:
+    """
+    函数的每个调用者如何处理它返回的错误
+
+    Returns:
+        [{"caller_id", "handling", "sites"}]：handling 为各调用点的处理方式
+        （按 ERROR_HANDLING 的顺序去重，未跟踪的排在最后），有任一调用点
+        转发时调用者算作转发；按转发在前、限定名排序
+    """
+    order = ERROR_HANDLING + (UNTRACKED,)
+    callers = []
+    for caller_id in graph.predecessors(node_id):
+        sites = graph.call_sites(caller_id, node_id)
+        kinds = sorted({site_handling(site) for site in sites}, key=order.index)
+        callers.append({"caller_id": caller_id, "handling": kinds, "sites": sites})
+    callers.sort(
+        key=lambda c: (
+            "propagate" not in c["handling"],
+            graph.qualified_name(c["caller_id"]),
+        )
+    )
+    return callers
+
+
+def propagation_tree(
+    graph: CallGraph, node_id: str, max_depth: int = DEFAULT_ERROR_DEPTH
+) -> Dict[str, Any]:
+    """
+    错误传播树：根为 node_id，子节点为调用者；只有转发错误的调用者继续展开
+    它自己的调用者，直到 max_depth 层
+
+    Returns:
+        {"id", "callers": [{"caller_id", "handling", "sites", "callers",
+        "recursive", "truncated"}]}：recursive 为调用者已在当前路径上（递归），
+        truncated 为转发的调用者超过 max_depth 没有展开
+    """
+
+    def walk(current: str, depth: int, path: set) -> List[Dict[str, Any]]:
+        entries = []
+        for entry in error_callers(graph, current):
+            caller_id = entry["caller_id"]
+            entry = {**entry, "callers": [], "recursive": False, "truncated": False}
+            if "propagate" in entry["handling"]:
+                if caller_id in path:
+                    entry["recursive"] = True
+                elif depth >= max_depth:
+                    entry["truncated"] = bool(graph.predecessors(caller_id))
+                else:
+                    entry["callers"] = walk(caller_id, depth + 1, path | {caller_id})
+            entries.append(entry)
+        return entries
+
+    return {"id": node_id, "callers": walk(node_id, 1, {node_id})}
+
+
+def error_summary(graph: CallGraph) -> List[Dict[str, Any]]:
+    """
+    每个可能失败的函数的调用点按处理方式的统计
+
+    Returns:
+        [{"id", "fallible", "counts": {处理方式: 调用点数量}}]，按调用点总数
+        降序、限定名升序排列；counts 包含 ERROR_HANDLING 和 UNTRACKED 的所有键
+    """
+    summary = []
+    for node_id in graph.nodes:
+        fallible = fallible_type(graph, node_id)
+        if not fallible:
+            continue
+        counts = {kind: 0 for kind in ERROR_HANDLING + (UNTRACKED,)}
+        for caller_id in graph.predecessors(node_id):
+            for site in graph.call_sites(caller_id, node_id):
+                counts[site_handling(site)] += 1
+        summary.append({"id": node_id, "fallible": fallible, "counts": counts})
+    summary.sort(
+        key=lambda s: (-sum(s["counts"].values()), graph.qualified_name(s["id"]))
+    )
+    return summary
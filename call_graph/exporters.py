@@ -0,0 +1,1627 @@
+"""
+调用图导出模块
+将内存调用图导出为各种可视化/交换格式
+"""
+
+import csv
+import html
+import json
+import math
+import os
+import sqlite3
+import unicodedata
+from typing import Any, Dict, Iterable, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from . import raster
+    from .cfg import combine_cfg, function_cfg
+    from .graph import GROUP_KINDS, CallGraph, site_confidence
+    from .html_template import HTML_TEMPLATE
+    from .layout import NODE_PADDING, edge_label_position, layered_layout
+except ImportError:
+    import raster
+    from cfg import combine_cfg, function_cfg
+    from graph import GROUP_KINDS, CallGraph, site_confidence
+    from html_template import HTML_TEMPLATE
+    from layout import NODE_PADDING, edge_label_position, layered_layout
+
+
+# 按模块着色时使用的调色板（浅色，保证标签可读）
+MODULE_COLORS = [
+    "#a6cee3",
+    "#b2df8a",
+    "#fb9a99",
+    "#fdbf6f",
+    "#cab2d6",
+    "#ffff99",
+    "#8dd3c7",
+    "#bebada",
+    "#fb8072",
+    "#80b1d3",
+    "#fccde5",
+    "#d9d9d9",
+]
+
+# 按类别区分节点形状
+KIND_SHAPES = {
+    "function": "box",
+    "method": "ellipse",
+    "closure": "diamond",
+    "module": "folder",
+    "type": "component",
+    "component": "box3d",
+    "elided": "note",
+}
+
+
+# DOT 导出未指定 cluster_depth 时，节点数超过该值的图按 crate / 模块 / 类型
+# 嵌套成子图
+DOT_CLUSTER_THRESHOLD = 200
+
+# 叠加覆盖率数据时被执行过 / 没有被执行的函数的颜色
+COVERAGE_COLORS = {True: "#c7e9c0", False: "#fcbba1"}
+
+
+def _heat_color(fraction: float) -> str:
+    """剖析数据的热度颜色：从浅黄（样本少）到深红（样本最多）"""
+    low, high = (255, 237, 160), (189, 0, 38)
+    fraction = min(max(fraction, 0.0), 1.0)
+    return "#" + "".join(
+        f"{round(a + (b - a) * fraction):02x}" for a, b in zip(low, high)
+    )
+
+
+def _dot_escape(text: str) -> str:
+    """转义 DOT 字符串中的特殊字符"""
+    return str(text).replace("\\", "\\\\").replace('"', '\\"')
+
+
+def _edge_label(edge: Dict[str, Any], edge_labels: bool) -> str:
+    """
+    边标签：调用次数（edge_labels 时）、实例化泛型函数的类型实参（如 <User>）
+    和合并的调用链经过的私有函数数量（--public-only）
+    """
+    parts = [str(edge["count"])] if edge_labels else []
+    parts.extend(f"<{args}>" for args in edge.get("type_args") or [])
+    if edge.get("via"):
+        parts.append(f"经 {len(edge['via'])} 个私有函数")
+    return " ".join(parts)
+
+
+def export_dot(
+    graph: CallGraph,
+    color_by_module: bool = False,
+    shape_by_kind: bool = False,
+    edge_labels: bool = False,
+    rankdir: str = "LR",
+    cluster_depth: Optional[int] = None,
+) -> str:
+    """
+    导出为 Graphviz DOT 格式
+
+    Args:
+        graph: 调用图
+        color_by_module: 按模块为节点着色
+        shape_by_kind: 按类别（普通函数/方法/闭包）设置节点形状
+        edge_labels: 在边上标注调用次数
+        rankdir: 布局方向（LR/TB/RL/BT）
+        cluster_depth: 按 crate → 模块 → 类型（impl、类）嵌套 subgraph cluster
+            的层数，0 表示不分组；为 None 时节点数超过 DOT_CLUSTER_THRESHOLD
+            的图完整嵌套，其余不分组
+
+    叠加了剖析数据（见 profiling 模块）时，有样本的函数按包含样本数着色并在
+    标签中标注样本数，有样本的边按样本数加粗；叠加了覆盖率数据（见
+    coverage_report 模块）时，被执行过的函数为绿色、没有被执行的为红色
+    （有样本时热度优先）
+
+    编译期运行的函数（build.rs、过程宏 crate）放在单独的虚线框子图中，与运行时
+    的调用图分开
+    """
+    lines = ["digraph CallGraph {"]
+    lines.append(f"  rankdir={rankdir};")
+    lines.append("  node [shape=box];")
+    lines.append('  graph [fontname="Arial", fontsize=10];')
+    lines.append('  node [fontname="Arial", fontsize=9];')
+    lines.append('  edge [fontname="Arial", fontsize=8];')
+
+    module_colors: Dict[str, str] = {}
+    node_lines: Dict[str, str] = {}
+    edges = graph.edges()
+    if cluster_depth is None:
+        cluster_depth = -1 if len(graph.nodes) > DOT_CLUSTER_THRESHOLD else 0
+    # 热度和边宽相对于样本数最多的函数
+    max_samples = max(
+        (
+            (node.get("extras") or {}).get("samples") or 0
+            for node in graph.nodes.values()
+        ),
+        default=0,
+    )
+
+    # 添加所有函数节点
+    for node_id, node in graph.nodes.items():
+        name = _dot_escape(node["name"])
+        if graph.node_kind(node_id) == "elided":
+            label = name
+        elif graph.node_kind(node_id) in GROUP_KINDS:
+            label = f"{name}\\n({node['extras']['functions']} 个函数)"
+        else:
+            file_path = _dot_escape(node["file"])
+            label = f"{name}\\n({file_path}:{node.get('start_line', '?')})"
+        samples = (node.get("extras") or {}).get("samples")
+        if samples:
+            self_samples = node["extras"].get("self_samples") or 0
+            label += f"\\n{samples} 样本（自身 {self_samples}）"
+        attrs = [f'label="{label}"']
+
+        if shape_by_kind:
+            attrs.append(f"shape={KIND_SHAPES[graph.node_kind(node_id)]}")
+
+        if color_by_module:
+            module = graph.module_of(node_id)
+            if module not in module_colors:
+                module_colors[module] = MODULE_COLORS[
+                    len(module_colors) % len(MODULE_COLORS)
+                ]
+            attrs.append("style=filled")
+            attrs.append(f'fillcolor="{module_colors[module]}"')
+
+        covered = (node.get("extras") or {}).get("covered")
+        line_coverage = (node.get("extras") or {}).get("line_coverage")
+        if line_coverage:
+            attrs.append(f'tooltip="覆盖 {line_coverage[0]}/{line_coverage[1]} 行"')
+
+        # 剖析数据的热度、覆盖率优先于模块颜色
+        if covered is not None and not samples:
+            if "style=filled" not in attrs:
+                attrs.append("style=filled")
+            attrs = [attr for attr in attrs if not attr.startswith("fillcolor=")]
+            attrs.append(f'fillcolor="{COVERAGE_COLORS[covered]}"')
+        if samples:
+            if "style=filled" not in attrs:
+                attrs.append("style=filled")
+            attrs = [attr for attr in attrs if not attr.startswith("fillcolor=")]
+            fraction = samples / max_samples
+            attrs.append(f'fillcolor="{_heat_color(fraction)}"')
+            if fraction > 0.6:
+                attrs.append('fontcolor="white"')
+
+        # 裁剪大图时的占位节点用灰色虚线框表示
+        if graph.node_kind(node_id) == "elided":
+            if "style=filled" in attrs:
+                attrs[attrs.index("style=filled")] = 'style="filled,dashed"'
+            else:
+                attrs.append("style=dashed")
+            attrs.extend(['color="gray50"', 'fontcolor="gray40"'])
+
+        node_lines[node_id] = f'"{node_id}" [{", ".join(attrs)}];'
+
+    runtime = [n for n in graph.nodes if not graph.compile_time(n)]
+    compile_time = [n for n in graph.nodes if graph.compile_time(n)]
+    counter = [0]
+    _dot_clusters(graph, runtime, node_lines, cluster_depth, "  ", lines, counter)
+    if compile_time:
+        lines.append("  subgraph cluster_compile_time {")
+        lines.append('    label="编译期（build.rs / 过程宏）";')
+        lines.append("    style=dashed;")
+        _dot_clusters(
+            graph, compile_time, node_lines, cluster_depth, "    ", lines, counter
+        )
+        lines.append("  }")
+
+    # 添加调用边（同一对函数的多个调用点合并为一条边）
+    for edge in edges:
+        attrs = []
+        # 动态分发（trait 对象 / 泛型约束）的边用虚线表示
+        if edge["edge_kind"] == "dynamic":
+            attrs.append("style=dashed")
+        # 函数到其内部闭包的定义边用点线表示
+        elif edge["edge_kind"] == "closure":
+            attrs.append("style=dotted")
+        # 宏展开后才出现的调用用灰色表示
+        elif edge["edge_kind"] == "macro":
+            attrs.append('color="gray50"')
+        # 通过函数指针/回调的间接调用用蓝色虚线表示
+        elif edge["edge_kind"] == "indirect":
+            attrs.extend(["style=dashed", 'color="steelblue"'])
+        # 运算符重载、解引用等隐式调用用橙色表示
+        elif edge["edge_kind"] == "operator":
+            attrs.append('color="darkorange"')
+        # 值被销毁时的 Drop::drop 调用用紫色点线表示
+        elif edge["edge_kind"] == "drop":
+            attrs.extend(["style=dotted", 'color="purple"'])
+        # 源码注释指令声明的调用用绿色粗线表示
+        elif edge["edge_kind"] == "annotated":
+            attrs.extend(['color="forestgreen"', "penwidth=2"])
+        # 跨语言（FFI）边界上的调用用红色粗线表示
+        elif edge["edge_kind"] == "ffi":
+            attrs.extend(['color="crimson"', "penwidth=2"])
+        # 交给新线程/异步任务运行的函数用青色粗虚线表示
+        elif edge["edge_kind"] == "spawn":
+            attrs.extend(["style=dashed", 'color="darkcyan"', "penwidth=2"])
+        # 类型级视图中的签名依赖（不是调用）用灰色虚线表示
+        elif edge["edge_kind"] == "signature":
+            attrs.extend(["style=dashed", 'color="gray50"'])
+        # 不确定的边（可能的目标之一、按名称猜测的目标）用虚线表示
+        if edge["confidence"] != "certain" and not any(
+            attr.startswith("style=") for attr in attrs
+        ):
+            attrs.append("style=dashed")
+        if edge.get("samples"):
+            attrs = [attr for attr in attrs if not attr.startswith("penwidth=")]
+            width = 1 + 5 * edge["samples"] / max_samples
+            attrs.append(f"penwidth={width:.1f}")
+            attrs.append(f'tooltip="{edge["samples"]} 样本"')
+        label = _edge_label(edge, edge_labels)
+        if label:
+            attrs.append(f'label="{_dot_escape(label)}"')
+        attr_str = f" [{', '.join(attrs)}]" if attrs else ""
+        lines.append(f'  "{edge["caller_id"]}" -> "{edge["callee_id"]}"{attr_str};')
+
+    # 按模块着色时附带图例
+    if color_by_module and module_colors:
+        lines.append("  subgraph cluster_legend {")
+        lines.append('    label="modules";')
+        for i, (module, color) in enumerate(sorted(module_colors.items())):
+            lines.append(
+                f'    "legend_{i}" [label="{_dot_escape(module)}", '
+                f'style=filled, fillcolor="{color}", shape=note];'
+            )
+        lines.append("  }")
+
+    lines.append("}")
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边")
+
+    return "\n".join(lines)
+
+
+def _dot_clusters(
+    graph: CallGraph,
+    node_ids: List[str],
+    node_lines: Dict[str, str],
+    depth: int,
+    indent: str,
+    lines: List[str],
+    counter: List[int],
+):
+    """
+    输出节点声明，按容器树（见 _container_tree）嵌套在 subgraph cluster 中；
+    类型（impl、类）的子图为圆角框；counter 保证子图名称在整个图中唯一
+    """
+
+    def emit(container: Dict[str, Any], indent: str):
+        for node_id in container["nodes"]:
+            lines.append(f"{indent}{node_lines[node_id]}")
+        for (kind, name), child in sorted(container["children"].items()):
+            lines.append(f"{indent}subgraph cluster_{counter[0]} {{")
+            counter[0] += 1
+            lines.append(f'{indent}  label="{_dot_escape(name)}";')
+            if kind == "type":
+                lines.append(f"{indent}  style=rounded;")
+            emit(child, indent + "  ")
+            lines.append(f"{indent}}}")
+
+    emit(_container_tree(graph, node_ids, depth, types=True), indent)
+
+
+def _mermaid_escape(text: str) -> str:
+    """
+    转义 Mermaid 标签
+
+    泛型参数中的尖括号（Vec<T>）会被 Mermaid 当作 HTML 标签，
+    因此与引号等符号一起替换为实体编码
+    """
+    replacements = {
+        "&": "#amp;",
+        '"': "#quot;",
+        "<": "#lt;",
+        ">": "#gt;",
+        "|": "#124;",
+        "[": "#91;",
+        "]": "#93;",
+        "{": "#123;",
+        "}": "#125;",
+    }
+    return "".join(replacements.get(ch, ch) for ch in str(text))
+
+
+def export_mermaid(
+    graph: CallGraph,
+    group_by_module: bool = False,
+    edge_labels: bool = False,
+    direction: str = "TD",
+) -> str:
+    """
+    导出为 Mermaid 流程图（可直接嵌入 GitHub README / MkDocs 的 Markdown）
+
+    Args:
+        graph: 调用图
+        group_by_module: 每个模块生成一个 subgraph
+        edge_labels: 在边上标注调用次数
+        direction: 布局方向（TD/LR/BT/RL）
+    """
+    lines = [f"graph {direction}"]
+
+    # Mermaid 节点 ID 只能使用简单字符，使用序号代替符号 ID
+    mermaid_ids = {node_id: f"n{i}" for i, node_id in enumerate(graph.nodes)}
+
+    def node_line(node_id: str, indent: str) -> str:
+        node = graph.nodes[node_id]
+        if graph.node_kind(node_id) in ("module", "type", "elided"):
+            label = _mermaid_escape(node["name"])
+        elif graph.node_kind(node_id) == "component":
+            label = _mermaid_escape(
+                f"{node['name']} ({node['extras']['functions']} 个函数)"
+            )
+        else:
+            label = _mermaid_escape(f"{node['name']} ({node.get('start_line', '?')})")
+        return f'{indent}{mermaid_ids[node_id]}["{label}"]'
+
+    if group_by_module:
+        modules: Dict[str, list] = {}
+        for node_id in graph.nodes:
+            modules.setdefault(graph.module_of(node_id), []).append(node_id)
+        for i, (module, node_ids) in enumerate(sorted(modules.items())):
+            lines.append(f'    subgraph m{i} ["{_mermaid_escape(module)}"]')
+            for node_id in node_ids:
+                lines.append(node_line(node_id, "        "))
+            lines.append("    end")
+    else:
+        for node_id in graph.nodes:
+            lines.append(node_line(node_id, "    "))
+
+    edges = graph.edges()
+    for edge in edges:
+        # 动态分发和间接调用的边用虚线表示，闭包定义边用圆头连线表示，
+        # 注释指令声明的调用和跨语言（FFI）调用用粗线表示，交给新线程/异步任务
+        # 运行的函数用叉头连线表示
+        arrow = {
+            "dynamic": "-.->",
+            "indirect": "-.->",
+            "closure": "--o",
+            "annotated": "==>",
+            "ffi": "==>",
+            "spawn": "--x",
+            "signature": "-.->",
+        }.get(edge["edge_kind"], "-->")
+        label = _edge_label(edge, edge_labels)
+        label = f"|{_mermaid_escape(label)}|" if label else ""
+        lines.append(
+            f"    {mermaid_ids[edge['caller_id']]} {arrow}{label} "
+            f"{mermaid_ids[edge['callee_id']]}"
+        )
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边")
+
+    return "\n".join(lines)
+
+
+# PlantUML 的箭头样式：边类型 -> 方括号中的线型和颜色，与 DOT 导出的样式对应
+PLANTUML_EDGE_STYLES = {
+    "dynamic": "dashed",
+    "closure": "dotted",
+    "macro": "#7f7f7f",
+    "indirect": "#4682b4,dashed",
+    "operator": "#ff8c00",
+    "drop": "#800080,dotted",
+    "annotated": "#228b22,bold",
+    "ffi": "#dc143c,bold",
+    "spawn": "#008b8b,dashed",
+    "signature": "#7f7f7f,dashed",
+}
+
+# PlantUML 类图中的可见性标记
+PLANTUML_VISIBILITY = {"public": "+", "crate": "~", "protected": "#", "private": "-"}
+
+PLANTUML_STYLES = ["component", "class"]
+
+
+def _plantuml_escape(text: str) -> str:
+    """PlantUML 引号字符串中不能出现双引号，换成单引号"""
+    return str(text).replace('"', "'")
+
+
+def _plantuml_arrow(edge_kind: str) -> str:
+    style = PLANTUML_EDGE_STYLES.get(edge_kind)
+    return f"-[{style}]->" if style else "-->"
+
+
+def export_plantuml(
+    graph: CallGraph,
+    style: str = "component",
+    edge_labels: bool = False,
+    rankdir: str = "LR",
+) -> str:
+    """
+    导出为 PlantUML 图
+
+    - component：每个函数一个组件，按模块放在 package 中，边为函数间的调用
+    - class：每个模块一个类，其中的函数列为方法（带可见性标记），
+      模块间的调用合并为一条边并标注调用次数
+
+    Args:
+        graph: 调用图
+        style: 图的类型（component / class）
+        edge_labels: component 图中在边上标注调用次数
+        rankdir: LR 时从左到右布局，其他方向为 PlantUML 默认的从上到下
+    """
+    if style not in PLANTUML_STYLES:
+        raise ValueError(f"不支持的 PlantUML 图类型: {style}")
+    lines = ["@startuml"]
+    if rankdir == "LR":
+        lines.append("left to right direction")
+
+    modules: Dict[str, List[str]] = {}
+    for node_id in graph.nodes:
+        modules.setdefault(graph.module_name(node_id), []).append(node_id)
+
+    edges = graph.edges()
+    if style == "component":
+        lines.append("skinparam componentStyle rectangle")
+        # PlantUML 别名只能使用简单字符，使用序号代替符号 ID
+        aliases = {node_id: f"n{i}" for i, node_id in enumerate(graph.nodes)}
+        for module, node_ids in sorted(modules.items()):
+            lines.append(f'package "{_plantuml_escape(module)}" {{')
+            for node_id in node_ids:
+                node = graph.nodes[node_id]
+                if graph.node_kind(node_id) == "elided":
+                    location = ""
+                elif graph.node_kind(node_id) in GROUP_KINDS:
+                    location = f"{node['extras']['functions']} 个函数"
+                else:
+                    location = (
+                        f"{os.path.basename(node['file'] or '')}:"
+                        f"{node.get('start_line', '?')}"
+                    )
+                label = _plantuml_escape(
+                    f"{node['name']}\\n{location}" if location else node["name"]
+                )
+                lines.append(f'  component "{label}" as {aliases[node_id]}')
+            lines.append("}")
+        for edge in edges:
+            label = _edge_label(edge, edge_labels)
+            lines.append(
+                f"{aliases[edge['caller_id']]} {_plantuml_arrow(edge['edge_kind'])} "
+                f"{aliases[edge['callee_id']]}"
+                + (f" : {_plantuml_escape(label)}" if label else "")
+            )
+    else:
+        class_of = {}
+        for i, (module, node_ids) in enumerate(sorted(modules.items())):
+            lines.append(f'class "{_plantuml_escape(module)}" as c{i} {{')
+            for node_id in sorted(node_ids, key=lambda n: graph.nodes[n]["name"]):
+                class_of[node_id] = f"c{i}"
+                marker = PLANTUML_VISIBILITY.get(graph.visibility(node_id), "-")
+                name = _plantuml_escape(graph.nodes[node_id]["name"])
+                lines.append(f"  {marker}{name}()")
+            lines.append("}")
+        counts: Dict[tuple, int] = {}
+        for edge in edges:
+            key = (class_of[edge["caller_id"]], class_of[edge["callee_id"]])
+            if key[0] != key[1]:
+                counts[key] = counts.get(key, 0) + edge["count"]
+        for (caller, callee), count in sorted(counts.items()):
+            lines.append(f"{caller} --> {callee} : {count}")
+    lines.append("@enduml")
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边")
+
+    return "\n".join(lines)
+
+
+# JSON 导出格式版本，字段含义变化或删除字段时递增（新增字段不递增）
+JSON_SCHEMA_VERSION = 1
+
+
+def export_json(graph: CallGraph, indent: int = 2) -> str:
+    """
+    导出为 JSON（格式说明见 README 的"JSON 格式"一节）
+
+    节点按文件和行号排序，边按调用位置排序，相同输入的输出保持稳定，
+    便于版本对比和下游工具处理
+
+    Args:
+        graph: 调用图
+        indent: 缩进空格数，0 表示紧凑输出
+    """
+    node_ids = sorted(
+        graph.nodes,
+        key=lambda n: (
+            graph.nodes[n]["file"],
+            graph.nodes[n].get("start_line") or 0,
+            graph.nodes[n]["name"],
+            n,
+        ),
+    )
+
+    nodes = []
+    for node_id in node_ids:
+        node = graph.nodes[node_id]
+        nodes.append(
+            {
+                "id": node_id,
+                "stable_id": graph.stable_id(node_id),
+                "name": node["name"],
+                "qualified_name": graph.qualified_name(node_id),
+                "kind": graph.node_kind(node_id),
+                "visibility": graph.visibility(node_id),
+                "language": node.get("language"),
+                "file": node["file"],
+                "span": {
+                    "start_line": node.get("start_line"),
+                    "end_line": node.get("end_line"),
+                    "start_byte": node.get("start_byte"),
+                    "end_byte": node.get("end_byte"),
+                },
+                "owner": node["extras"].get("owner"),
+                "crate": node["extras"].get("crate"),
+                "signature": node.get("signature"),
+                "loc": graph.line_count(node_id),
+                "doc": graph.doc(node_id),
+                "attributes": graph.attributes(node_id),
+                "cfg": combine_cfg(function_cfg(node)),
+            }
+        )
+        # 缩点后的强连通分量和裁剪大图的占位节点列出成员
+        if graph.node_kind(node_id) in ("component", "elided"):
+            nodes[-1]["members"] = node["extras"]["members"]
+        # 叠加了覆盖率数据时是否被执行过、执行过的行数和可执行的行数
+        if node["extras"].get("covered") is not None:
+            nodes[-1]["covered"] = node["extras"]["covered"]
+            nodes[-1]["line_coverage"] = node["extras"].get("line_coverage")
+        # 编译期运行的函数：build（构建脚本）/ proc-macro（过程宏 crate）
+        if graph.compile_time(node_id):
+            nodes[-1]["compile_time"] = graph.compile_time(node_id)
+        # const fn：可以在编译期求值
+        if graph.is_const(node_id):
+            nodes[-1]["const"] = True
+        # 叠加了剖析数据时的样本数
+        if node["extras"].get("samples"):
+            nodes[-1]["samples"] = node["extras"]["samples"]
+            nodes[-1]["self_samples"] = node["extras"].get("self_samples") or 0
+
+    # 每个调用点一条边
+    edges = []
+    for edge in graph.edges():
+        for site in graph.call_sites(edge["caller_id"], edge["callee_id"]):
+            edges.append(
+                {
+                    "caller": edge["caller_id"],
+                    "callee": edge["callee_id"],
+                    "kind": site.get("edge_kind") or "static",
+                    "confidence": site_confidence(site),
+                    "type_args": site.get("type_args"),
+                    "cfg": site.get("cfg"),
+                    "error_handling": site.get("error_handling"),
+                    "samples": site.get("samples"),
+                    "via": site.get("via") or [],
+                    "call_site": {
+                        "file": site["caller_file"],
+                        "line": site["call_site_line"],
+                        "column": site["call_site_column"],
+                        "snippet": site.get("call_site_snippet"),
+                    },
+                }
+            )
+    edges.sort(
+        key=lambda e: (
+            e["call_site"]["file"] or "",
+            e["call_site"]["line"] or 0,
+            e["call_site"]["column"] or 0,
+            e["callee"],
+        )
+    )
+
+    document = {
+        "schema_version": JSON_SCHEMA_VERSION,
+        "nodes": nodes,
+        "edges": edges,
+    }
+
+    print(f"导出成功: {len(nodes)} 个节点, {len(edges)} 条边")
+
+    return json.dumps(document, indent=indent or None, ensure_ascii=False)
+
+
+def export_html(graph: CallGraph, title: str = "调用图") -> str:
+    """
+    导出为单文件交互式 HTML 页面
+
+    页面内置布局和交互脚本，不依赖网络：支持缩放平移、函数搜索、
+    模块折叠/展开，以及单击函数高亮经过它的所有调用路径并显示签名、行数、
+    文档摘要和属性
+
+    Args:
+        graph: 调用图
+        title: 页面标题
+    """
+    nodes = []
+    for node_id, node in graph.nodes.items():
+        nodes.append(
+            {
+                "id": node_id,
+                "name": node["name"],
+                "qualified_name": graph.qualified_name(node_id),
+                "module": graph.module_of(node_id),
+                "kind": graph.node_kind(node_id),
+                "file": node["file"],
+                "line": node.get("start_line"),
+                "signature": node.get("signature"),
+                "loc": graph.line_count(node_id),
+                "doc": graph.doc(node_id),
+                "attributes": graph.attributes(node_id),
+            }
+        )
+    edges = [
+        {
+            "caller": edge["caller_id"],
+            "callee": edge["callee_id"],
+            "kind": edge["edge_kind"],
+            "count": edge["count"],
+        }
+        for edge in graph.edges()
+    ]
+
+    # 数据嵌入在 <script> 中，需要避免提前出现 </script>
+    data = json.dumps({"nodes": nodes, "edges": edges}, ensure_ascii=False)
+    data = data.replace("</", "<\\/")
+
+    print(f"导出成功: {len(nodes)} 个节点, {len(edges)} 条边")
+
+    return HTML_TEMPLATE.replace("__TITLE__", html.escape(title)).replace(
+        "__GRAPH_DATA__", data
+    )
+
+
+# GraphML / GEXF 的节点属性：(属性名, 类型)，类型使用两种格式共同的名称
+GRAPH_NODE_ATTRIBUTES = [
+    ("name", "string"),
+    ("qualified_name", "string"),
+    ("module", "string"),
+    ("kind", "string"),
+    ("language", "string"),
+    ("file", "string"),
+    ("line", "int"),
+    ("loc", "int"),
+    ("fan_in", "int"),
+    ("fan_out", "int"),
+]
+
+# 边属性：(属性名, 类型, 合并后边的字段)
+GRAPH_EDGE_ATTRIBUTES = [
+    ("kind", "string", "edge_kind"),
+    ("count", "int", "count"),
+]
+
+
+def _xml_escape(value: Any) -> str:
+    return html.escape(str(value), quote=True)
+
+
+def _graph_node_attributes(graph: CallGraph, node_id: str) -> Dict[str, Any]:
+    """
+    GraphML / GEXF 导出的节点属性
+
+    loc 为函数定义的行数，fan_in/fan_out 为不同调用者/被调用者的数量；
+    模块级视图中 loc 为空
+    """
+    node = graph.nodes[node_id]
+    return {
+        "name": node["name"],
+        "qualified_name": graph.qualified_name(node_id),
+        "module": graph.module_name(node_id),
+        "kind": graph.node_kind(node_id),
+        "language": node.get("language"),
+        "file": node.get("file"),
+        "line": node.get("start_line"),
+        "loc": graph.line_count(node_id),
+        "fan_in": len(graph.predecessors(node_id)),
+        "fan_out": len(graph.successors(node_id)),
+    }
+
+
+def _sorted_edges(graph: CallGraph) -> List[Dict[str, Any]]:
+    """合并后的边，按调用者和被调用者排序，保证输出稳定"""
+    return sorted(graph.edges(), key=lambda e: (e["caller_id"], e["callee_id"]))
+
+
+def export_graphml(graph: CallGraph) -> str:
+    """
+    导出为 GraphML（yEd、Gephi、NetworkX 等工具可直接打开）
+
+    节点属性见 GRAPH_NODE_ATTRIBUTES，边属性为 kind 和 count（调用点数量）
+    """
+    lines = [
+        '<?xml version="1.0" encoding="UTF-8"?>',
+        '<graphml xmlns="http://graphml.graphdrawing.org/xmlns">',
+    ]
+    for name, attr_type in GRAPH_NODE_ATTRIBUTES:
+        lines.append(
+            f'  <key id="n_{name}" for="node" attr.name="{name}" '
+            f'attr.type="{attr_type}"/>'
+        )
+    for name, attr_type, _ in GRAPH_EDGE_ATTRIBUTES:
+        lines.append(
+            f'  <key id="e_{name}" for="edge" attr.name="{name}" '
+            f'attr.type="{attr_type}"/>'
+        )
+    lines.append('  <graph id="CallGraph" edgedefault="directed">')
+
+    for node_id in sorted(graph.nodes):
+        lines.append(f'    <node id="{_xml_escape(node_id)}">')
+        for name, value in _graph_node_attributes(graph, node_id).items():
+            if value is not None:
+                lines.append(
+                    f'      <data key="n_{name}">{_xml_escape(value)}</data>'
+                )
+        lines.append("    </node>")
+
+    edges = _sorted_edges(graph)
+    for index, edge in enumerate(edges):
+        lines.append(
+            f'    <edge id="e{index}" source="{_xml_escape(edge["caller_id"])}" '
+            f'target="{_xml_escape(edge["callee_id"])}">'
+        )
+        for name, _, field in GRAPH_EDGE_ATTRIBUTES:
+            lines.append(
+                f'      <data key="e_{name}">{_xml_escape(edge[field])}</data>'
+            )
+        lines.append("    </edge>")
+
+    lines.append("  </graph>")
+    lines.append("</graphml>")
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边")
+
+    return "\n".join(lines)
+
+
+def export_gexf(graph: CallGraph) -> str:
+    """
+    导出为 GEXF 1.3（Gephi 的原生格式）
+
+    节点标签为函数名，其余属性同 GraphML；边的 weight 为调用点数量
+    """
+    lines = [
+        '<?xml version="1.0" encoding="UTF-8"?>',
+        '<gexf xmlns="http://gexf.net/1.3" version="1.3">',
+        '  <graph mode="static" defaultedgetype="directed">',
+        '    <attributes class="node">',
+    ]
+    for index, (name, attr_type) in enumerate(GRAPH_NODE_ATTRIBUTES):
+        gexf_type = "integer" if attr_type == "int" else attr_type
+        lines.append(
+            f'      <attribute id="{index}" title="{name}" type="{gexf_type}"/>'
+        )
+    lines.append("    </attributes>")
+    lines.append('    <attributes class="edge">')
+    lines.append('      <attribute id="0" title="kind" type="string"/>')
+    lines.append("    </attributes>")
+
+    lines.append("    <nodes>")
+    for node_id in sorted(graph.nodes):
+        attributes = _graph_node_attributes(graph, node_id)
+        lines.append(
+            f'      <node id="{_xml_escape(node_id)}" '
+            f'label="{_xml_escape(attributes["name"])}">'
+        )
+        lines.append("        <attvalues>")
+        for index, (name, _) in enumerate(GRAPH_NODE_ATTRIBUTES):
+            value = attributes[name]
+            if value is not None:
+                lines.append(
+                    f'          <attvalue for="{index}" '
+                    f'value="{_xml_escape(value)}"/>'
+                )
+        lines.append("        </attvalues>")
+        lines.append("      </node>")
+    lines.append("    </nodes>")
+
+    edges = _sorted_edges(graph)
+    lines.append("    <edges>")
+    for index, edge in enumerate(edges):
+        lines.append(
+            f'      <edge id="{index}" source="{_xml_escape(edge["caller_id"])}" '
+            f'target="{_xml_escape(edge["callee_id"])}" weight="{edge["count"]}">'
+        )
+        lines.append(
+            f'        <attvalues><attvalue for="0" '
+            f'value="{_xml_escape(edge["edge_kind"])}"/></attvalues>'
+        )
+        lines.append("      </edge>")
+    lines.append("    </edges>")
+    lines.append("  </graph>")
+    lines.append("</gexf>")
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边")
+
+    return "\n".join(lines)
+
+
+# SQLite 导出的 schema 版本，表结构不兼容地变化时递增
+SQLITE_SCHEMA_VERSION = 1
+
+SQLITE_SCHEMA = """
+CREATE TABLE metadata(key TEXT PRIMARY KEY, value TEXT);
+
+-- 模块：识别出 Cargo crate 的 Rust 代码为 crate::模块路径，其他为源文件路径
+CREATE TABLE modules(
+    id INTEGER PRIMARY KEY,
+    name TEXT UNIQUE NOT NULL,
+    crate TEXT
+);
+
+CREATE TABLE files(
+    id INTEGER PRIMARY KEY,
+    path TEXT UNIQUE NOT NULL,
+    language TEXT
+);
+
+-- 函数节点；loc 为定义的行数，fan_in/fan_out 为不同调用者/被调用者的数量
+CREATE TABLE nodes(
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    qualified_name TEXT NOT NULL,
+    kind TEXT,
+    visibility TEXT,
+    language TEXT,
+    owner TEXT,
+    signature TEXT,
+    module_id INTEGER REFERENCES modules(id),
+    file_id INTEGER REFERENCES files(id),
+    start_line INTEGER,
+    end_line INTEGER,
+    start_byte INTEGER,
+    end_byte INTEGER,
+    loc INTEGER,
+    fan_in INTEGER,
+    fan_out INTEGER
+);
+
+-- 合并后的边：同一对函数一条，count 为调用点数量
+CREATE TABLE edges(
+    caller_id TEXT NOT NULL REFERENCES nodes(id),
+    callee_id TEXT NOT NULL REFERENCES nodes(id),
+    kind TEXT,
+    count INTEGER,
+    PRIMARY KEY (caller_id, callee_id)
+);
+
+-- 每个调用点一行
+CREATE TABLE call_sites(
+    id INTEGER PRIMARY KEY,
+    caller_id TEXT NOT NULL REFERENCES nodes(id),
+    callee_id TEXT NOT NULL REFERENCES nodes(id),
+    kind TEXT,
+    file_id INTEGER REFERENCES files(id),
+    line INTEGER,
+    column INTEGER,
+    snippet TEXT
+);
+
+CREATE INDEX idx_nodes_name ON nodes(name);
+CREATE INDEX idx_nodes_qualified_name ON nodes(qualified_name);
+CREATE INDEX idx_nodes_module ON nodes(module_id);
+CREATE INDEX idx_nodes_file ON nodes(file_id);
+CREATE INDEX idx_nodes_fan_in ON nodes(fan_in);
+CREATE INDEX idx_edges_callee ON edges(callee_id);
+CREATE INDEX idx_call_sites_edge ON call_sites(caller_id, callee_id);
+CREATE INDEX idx_call_sites_file ON call_sites(file_id, line);
+"""
+
+
+def export_sqlite(graph: CallGraph, output: str) -> str:
+    """
+    导出为独立的 SQLite 数据库（表结构见 SQLITE_SCHEMA），便于直接用 SQL 查询
+
+    与分析数据库不同，节点的限定名、模块、行数和扇入/扇出都已计算好。
+    已存在的输出文件会被覆盖
+
+    Args:
+        graph: 调用图
+        output: 输出数据库文件路径
+    """
+    if os.path.exists(output):
+        os.remove(output)
+    conn = sqlite3.connect(output)
+    try:
+        conn.executescript(SQLITE_SCHEMA)
+        conn.executemany(
+            "INSERT INTO metadata VALUES (?, ?)",
+            [
+                ("schema_version", str(SQLITE_SCHEMA_VERSION)),
+                ("project_root", graph.project_root()),
+            ],
+        )
+
+        module_ids: Dict[str, int] = {}
+        file_ids: Dict[str, int] = {}
+
+        def file_id(path, language=None):
+            if not path:
+                return None
+            if path not in file_ids:
+                file_ids[path] = len(file_ids) + 1
+                conn.execute(
+                    "INSERT INTO files VALUES (?, ?, ?)",
+                    (file_ids[path], path, language),
+                )
+            return file_ids[path]
+
+        for node_id in sorted(graph.nodes):
+            node = graph.nodes[node_id]
+            attributes = _graph_node_attributes(graph, node_id)
+            module = attributes["module"]
+            if module not in module_ids:
+                module_ids[module] = len(module_ids) + 1
+                conn.execute(
+                    "INSERT INTO modules VALUES (?, ?, ?)",
+                    (module_ids[module], module, node["extras"].get("crate")),
+                )
+            conn.execute(
+                "INSERT INTO nodes VALUES "
+                "(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                (
+                    node_id,
+                    node["name"],
+                    attributes["qualified_name"],
+                    attributes["kind"],
+                    graph.visibility(node_id),
+                    node.get("language"),
+                    node["extras"].get("owner"),
+                    node.get("signature"),
+                    module_ids[module],
+                    file_id(node.get("file"), node.get("language")),
+                    node.get("start_line"),
+                    node.get("end_line"),
+                    node.get("start_byte"),
+                    node.get("end_byte"),
+                    attributes["loc"],
+                    attributes["fan_in"],
+                    attributes["fan_out"],
+                ),
+            )
+
+        edges = _sorted_edges(graph)
+        sites = 0
+        for edge in edges:
+            caller_id, callee_id = edge["caller_id"], edge["callee_id"]
+            conn.execute(
+                "INSERT INTO edges VALUES (?, ?, ?, ?)",
+                (caller_id, callee_id, edge["edge_kind"], edge["count"]),
+            )
+            for site in graph.call_sites(caller_id, callee_id):
+                conn.execute(
+                    "INSERT INTO call_sites (caller_id, callee_id, kind, file_id, "
+                    "line, column, snippet) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    (
+                        caller_id,
+                        callee_id,
+                        site.get("edge_kind") or "static",
+                        file_id(site.get("caller_file")),
+                        site.get("call_site_line"),
+                        site.get("call_site_column"),
+                        site.get("call_site_snippet"),
+                    ),
+                )
+                sites += 1
+        conn.commit()
+    finally:
+        conn.close()
+
+    print(
+        f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边, {sites} 个调用点"
+    )
+    return output
+
+
+# 内置渲染（SVG / PNG）的边样式：边类型 -> (颜色, 虚线的实线和空白长度)，
+# 与 DOT 导出的样式对应
+RENDER_EDGE_STYLES = {
+    "static": ("#333333", None),
+    "dynamic": ("#333333", (6, 4)),
+    "closure": ("#333333", (2, 3)),
+    "macro": ("#7f7f7f", None),
+    "indirect": ("#4682b4", (6, 4)),
+    "operator": ("#ff8c00", None),
+    "drop": ("#800080", (2, 3)),
+    "annotated": ("#228b22", None),
+    "ffi": ("#dc143c", None),
+    "spawn": ("#008b8b", (6, 4)),
+    "signature": ("#7f7f7f", (6, 4)),
+}
+
+# SVG 标签的字号和行高（像素）
+SVG_FONT_SIZE = 12
+SVG_LINE_HEIGHT = 16
+
+# PNG 点阵字体的放大倍数和标签行高（像素）
+PNG_FONT_SCALE = 2
+PNG_LINE_HEIGHT = raster.GLYPH_HEIGHT * PNG_FONT_SCALE + 6
+# PNG 图像的最大像素数，超过时应先缩小导出范围
+PNG_MAX_PIXELS = 40_000_000
+
+
+# Cypher 导出中所有调用图节点共有的标签（id 唯一约束建立在该标签上）
+CYPHER_NODE_LABEL = "Symbol"
+
+
+def _cypher_label(text: str) -> str:
+    """标签名：首字母大写，含非标识符字符时用反引号括起"""
+    label = text[:1].upper() + text[1:]
+    if label.isidentifier():
+        return label
+    return "`" + label.replace("`", "``") + "`"
+
+
+def _cypher_map(properties: Dict[str, Any]) -> str:
+    """属性映射字面量，省略没有值的属性（JSON 字符串即合法的 Cypher 字符串）"""
+    items = [
+        f"{key}: {json.dumps(value, ensure_ascii=False)}"
+        for key, value in properties.items()
+        if value is not None
+    ]
+    return "{" + ", ".join(items) + "}"
+
+
+def cypher_graph_data(graph: CallGraph) -> Dict[str, List[Dict[str, Any]]]:
+    """
+    Cypher 导出和直接导入 Neo4j 共用的数据
+
+    Returns:
+        {"nodes", "modules", "crates", "edges"}：nodes 的每项带 labels（类别和
+        语言标签）和 properties（id 与 GraphML 的节点属性，再加上 crate）；
+        modules 为函数所在的模块，模块级视图中节点本身就是模块，不再单独列出
+    """
+    nodes = []
+    modules: Dict[str, Dict[str, Any]] = {}
+    crates = set()
+    for node_id in sorted(graph.nodes):
+        attributes = _graph_node_attributes(graph, node_id)
+        crate = graph.nodes[node_id]["extras"].get("crate")
+        labels = [CYPHER_NODE_LABEL, _cypher_label(attributes["kind"])]
+        if attributes["language"]:
+            labels.append(_cypher_label(attributes["language"]))
+        nodes.append(
+            {
+                "labels": labels,
+                "properties": {"id": node_id, **attributes, "crate": crate},
+            }
+        )
+        if crate:
+            crates.add(crate)
+        if attributes["kind"] not in GROUP_KINDS:
+            module = attributes["module"]
+            modules.setdefault(module, {"name": module, "crate": crate})
+    edges = [
+        {
+            "caller_id": edge["caller_id"],
+            "callee_id": edge["callee_id"],
+            "properties": {
+                name: edge[field] for name, _, field in GRAPH_EDGE_ATTRIBUTES
+            },
+        }
+        for edge in _sorted_edges(graph)
+    ]
+    return {
+        "nodes": nodes,
+        "modules": [modules[name] for name in sorted(modules)],
+        "crates": [{"name": name} for name in sorted(crates)],
+        "edges": edges,
+    }
+
+
+def export_cypher(graph: CallGraph) -> str:
+    """
+    导出为 Cypher 语句（可用 cypher-shell 执行，导入 Neo4j / Memgraph 等图数据库）
+
+    - 调用图节点带 Symbol 标签、类别标签（Function/Method/Closure/Module）和
+      语言标签（如 Rust），属性见 cypher_graph_data
+    - 模块和 crate 各建一个节点：(函数)-[:IN_MODULE]->(:Module)-[:IN_CRATE]->(:Crate)，
+      模块级视图中 (:Module 节点)-[:IN_CRATE]->(:Crate)
+    - 调用关系为 (调用者)-[:CALLS {kind, count}]->(被调用者)
+
+    语句以分号结尾，先建立 Symbol.id 的唯一约束，使按 id 匹配节点建边时走索引
+    """
+    data = cypher_graph_data(graph)
+    label = CYPHER_NODE_LABEL
+    lines = [
+        "// 调用图（call-graph 导出）",
+        f"CREATE CONSTRAINT symbol_id IF NOT EXISTS FOR (n:{label}) "
+        "REQUIRE n.id IS UNIQUE;",
+    ]
+
+    for crate in data["crates"]:
+        lines.append(f"CREATE (:Crate {_cypher_map(crate)});")
+    for module in data["modules"]:
+        lines.append(f"CREATE (:Module {_cypher_map(module)});")
+    for node in data["nodes"]:
+        lines.append(
+            f"CREATE (:{':'.join(node['labels'])} {_cypher_map(node['properties'])});"
+        )
+
+    for node in data["nodes"]:
+        properties = node["properties"]
+        node_id = json.dumps(properties["id"], ensure_ascii=False)
+        if properties["kind"] not in GROUP_KINDS:
+            module = json.dumps(properties["module"], ensure_ascii=False)
+            lines.append(
+                f"MATCH (n:{label} {{id: {node_id}}}), (m:Module {{name: {module}}}) "
+                "CREATE (n)-[:IN_MODULE]->(m);"
+            )
+        elif properties["crate"]:
+            crate = json.dumps(properties["crate"], ensure_ascii=False)
+            lines.append(
+                f"MATCH (n:{label} {{id: {node_id}}}), (c:Crate {{name: {crate}}}) "
+                "CREATE (n)-[:IN_CRATE]->(c);"
+            )
+    for module in data["modules"]:
+        if module["crate"]:
+            name = json.dumps(module["name"], ensure_ascii=False)
+            crate = json.dumps(module["crate"], ensure_ascii=False)
+            lines.append(
+                f"MATCH (m:Module {{name: {name}}}), (c:Crate {{name: {crate}}}) "
+                "CREATE (m)-[:IN_CRATE]->(c);"
+            )
+
+    for edge in data["edges"]:
+        caller = json.dumps(edge["caller_id"], ensure_ascii=False)
+        callee = json.dumps(edge["callee_id"], ensure_ascii=False)
+        lines.append(
+            f"MATCH (a:{label} {{id: {caller}}}), (b:{label} {{id: {callee}}}) "
+            f"CREATE (a)-[:CALLS {_cypher_map(edge['properties'])}]->(b);"
+        )
+
+    print(f"导出成功: {len(data['nodes'])} 个节点, {len(data['edges'])} 条边")
+    return "\n".join(lines)
+
+
+def _module_color_map(graph: CallGraph) -> Dict[str, str]:
+    """按首次出现的顺序为每个模块分配 MODULE_COLORS 中的颜色"""
+    colors: Dict[str, str] = {}
+    for node_id in graph.nodes:
+        module = graph.module_of(node_id)
+        if module not in colors:
+            colors[module] = MODULE_COLORS[len(colors) % len(MODULE_COLORS)]
+    return colors
+
+
+def _svg_text_width(text: str) -> float:
+    """估算 SVG 文本宽度：全角字符按一个字号，其他字符按 0.6 个字号"""
+    return sum(
+        SVG_FONT_SIZE * (1 if unicodedata.east_asian_width(c) in "WF" else 0.6)
+        for c in text
+    )
+
+
+def export_svg(
+    graph: CallGraph,
+    color_by_module: bool = False,
+    edge_labels: bool = False,
+    rankdir: str = "LR",
+) -> str:
+    """
+    导出为 SVG 图像（使用内置的分层布局，不需要安装 Graphviz）
+
+    Args:
+        graph: 调用图
+        color_by_module: 按模块为节点着色
+        edge_labels: 在边上标注调用次数
+        rankdir: 布局方向（LR/TB/RL/BT）
+    """
+    layout = layered_layout(graph, _svg_text_width, SVG_LINE_HEIGHT, rankdir)
+    module_colors = _module_color_map(graph) if color_by_module else {}
+    width, height = math.ceil(layout.width), math.ceil(layout.height)
+
+    lines = [
+        f'<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" '
+        f'viewBox="0 0 {width} {height}" font-family="Arial, sans-serif" '
+        f'font-size="{SVG_FONT_SIZE}">',
+        f'<rect width="{width}" height="{height}" fill="#ffffff"/>',
+        "<defs>",
+    ]
+    # SVG 1.1 的箭头不继承线条颜色，每种颜色各定义一个
+    arrow_ids: Dict[str, str] = {}
+    for color, _ in RENDER_EDGE_STYLES.values():
+        if color not in arrow_ids:
+            arrow_ids[color] = f"arrow{len(arrow_ids)}"
+            lines.append(
+                f'<marker id="{arrow_ids[color]}" viewBox="0 0 10 10" refX="10" '
+                f'refY="5" markerWidth="8" markerHeight="8" orient="auto">'
+                f'<path d="M0,0 L10,5 L0,10 z" fill="{color}"/></marker>'
+            )
+    lines.append("</defs>")
+
+    for edge, points in layout.edges:
+        color, dash = RENDER_EDGE_STYLES.get(
+            edge["edge_kind"], RENDER_EDGE_STYLES["static"]
+        )
+        attrs = [
+            f'points="{" ".join(f"{x:.1f},{y:.1f}" for x, y in points)}"',
+            'fill="none"',
+            f'stroke="{color}"',
+            f'marker-end="url(#{arrow_ids[color]})"',
+        ]
+        if dash:
+            attrs.append(f'stroke-dasharray="{dash[0]},{dash[1]}"')
+        title = (
+            f"{graph.qualified_name(edge['caller_id'])} -> "
+            f"{graph.qualified_name(edge['callee_id'])}"
+        )
+        lines.append(
+            f"<polyline {' '.join(attrs)}><title>{_xml_escape(title)}</title>"
+            "</polyline>"
+        )
+        label = _edge_label(edge, edge_labels)
+        position = edge_label_position(points)
+        if label and position:
+            lines.append(
+                f'<text x="{position[0]:.1f}" y="{position[1] - 3:.1f}" '
+                f'text-anchor="middle" font-size="{SVG_FONT_SIZE - 2}" '
+                f'fill="{color}">{_xml_escape(label)}</text>'
+            )
+
+    for node_id, box in layout.boxes.items():
+        fill = module_colors.get(graph.module_of(node_id), "#ffffff")
+        lines.append(f"<g><title>{_xml_escape(graph.qualified_name(node_id))}</title>")
+        lines.append(
+            f'<rect x="{box.left:.1f}" y="{box.top:.1f}" width="{box.width:.1f}" '
+            f'height="{box.height:.1f}" rx="4" fill="{fill}" stroke="#333333"/>'
+        )
+        for i, text in enumerate(layout.labels[node_id]):
+            # 第一行为函数名，其余行（位置、函数数量）使用灰色小字
+            style = "" if i == 0 else f' font-size="{SVG_FONT_SIZE - 2}" fill="#555555"'
+            baseline = box.top + NODE_PADDING / 2 + (i + 0.75) * SVG_LINE_HEIGHT
+            lines.append(
+                f'<text x="{box.x:.1f}" y="{baseline:.1f}" text-anchor="middle"'
+                f"{style}>{_xml_escape(text)}</text>"
+            )
+        lines.append("</g>")
+    lines.append("</svg>")
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(layout.edges)} 条边")
+
+    return "\n".join(lines)
+
+
+def export_png(
+    graph: CallGraph,
+    output: str,
+    color_by_module: bool = False,
+    edge_labels: bool = False,
+    rankdir: str = "LR",
+) -> str:
+    """
+    导出为 PNG 图像（内置分层布局和光栅绘制，不需要安装 Graphviz 或图像库）
+
+    内置点阵字体只覆盖 ASCII，其他字符绘制为方框，需要显示中文时使用 SVG
+
+    Args:
+        graph: 调用图
+        output: 输出图像文件路径
+        color_by_module: 按模块为节点着色
+        edge_labels: 在边上标注调用次数
+        rankdir: 布局方向（LR/TB/RL/BT）
+    """
+    layout = layered_layout(
+        graph,
+        lambda text: raster.text_width(text, PNG_FONT_SCALE),
+        PNG_LINE_HEIGHT,
+        rankdir,
+    )
+    width, height = math.ceil(layout.width), math.ceil(layout.height)
+    if width * height > PNG_MAX_PIXELS:
+        raise ValueError(
+            f"图像过大（{width}x{height} 像素），请使用 --focus、--include 或 "
+            "--granularity module 缩小导出范围，或导出为 SVG"
+        )
+    module_colors = _module_color_map(graph) if color_by_module else {}
+    canvas = raster.Canvas(width, height)
+    border = raster.parse_color("#333333")
+    secondary = raster.parse_color("#555555")
+
+    for edge, points in layout.edges:
+        color, dash = RENDER_EDGE_STYLES.get(
+            edge["edge_kind"], RENDER_EDGE_STYLES["static"]
+        )
+        rgb = raster.parse_color(color)
+        # 折线的最后一段缩短到箭头底部
+        (x0, y0), (x1, y1) = points[-2], points[-1]
+        length = math.hypot(x1 - x0, y1 - y0) or 1.0
+        ux, uy = (x1 - x0) / length, (y1 - y0) / length
+        base = (x1 - ux * 10, y1 - uy * 10)
+        segments = list(zip(points, points[1:]))
+        for i, (start, end) in enumerate(segments):
+            if i == len(segments) - 1:
+                end = base
+            x_start, y_start = round(start[0]), round(start[1])
+            canvas.line(x_start, y_start, round(end[0]), round(end[1]), rgb, dash)
+        canvas.triangle(
+            [
+                (x1, y1),
+                (base[0] - uy * 4, base[1] + ux * 4),
+                (base[0] + uy * 4, base[1] - ux * 4),
+            ],
+            rgb,
+        )
+        label = _edge_label(edge, edge_labels)
+        position = edge_label_position(points)
+        if label and position:
+            canvas.text(
+                round(position[0] - raster.text_width(label, 1) / 2),
+                round(position[1]) - raster.GLYPH_HEIGHT - 2,
+                label,
+                rgb,
+            )
+
+    for node_id, box in layout.boxes.items():
+        fill = raster.parse_color(
+            module_colors.get(graph.module_of(node_id), "#ffffff")
+        )
+        left, top = round(box.left), round(box.top)
+        right, bottom = round(box.left + box.width), round(box.top + box.height)
+        canvas.fill_rect(left, top, right, bottom, fill)
+        canvas.rect(left, top, right, bottom, border)
+        for i, text in enumerate(layout.labels[node_id]):
+            text_top = (
+                box.top
+                + NODE_PADDING / 2
+                + i * PNG_LINE_HEIGHT
+                + (PNG_LINE_HEIGHT - raster.GLYPH_HEIGHT * PNG_FONT_SCALE) / 2
+            )
+            canvas.text(
+                round(box.x - raster.text_width(text, PNG_FONT_SCALE) / 2),
+                round(text_top),
+                text,
+                border if i == 0 else secondary,
+                PNG_FONT_SCALE,
+            )
+
+    with open(output, "wb") as f:
+        f.write(canvas.to_png())
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(layout.edges)} 条边")
+    return output
+
+
+# CSV 导出的文件名
+CSV_NODES_FILE = "nodes.csv"
+CSV_EDGES_FILE = "edges.csv"
+
+# Neo4j 批量导入（neo4j-admin database import）的关系类型
+NEO4J_RELATIONSHIP_TYPE = "CALLS"
+
+
+def _csv_value(value: Any) -> Any:
+    """没有值的单元格为空"""
+    return "" if value is None else value
+
+
+def export_csv(graph: CallGraph, output: str, neo4j_headers: bool = False) -> str:
+    """
+    导出为节点表和边表（output 目录下的 nodes.csv 和 edges.csv），
+    可直接在电子表格、pandas 中打开
+
+    节点列为 id 加上 GRAPH_NODE_ATTRIBUTES，边列为 caller_id、callee_id、
+    kind 和 count。neo4j_headers 为 True 时表头改用 neo4j-admin 批量导入的
+    格式（id:ID、:START_ID、:END_ID、:TYPE 和 :int 类型标注），节点类别同时
+    作为标签（:LABEL），边的类型为 CALLS。目录不存在时自动创建，已存在的
+    同名文件会被覆盖
+
+    Args:
+        graph: 调用图
+        output: 输出目录
+    """
+    os.makedirs(output, exist_ok=True)
+
+    def column(name: str, attr_type: str) -> str:
+        return f"{name}:{attr_type}" if neo4j_headers and attr_type == "int" else name
+
+    node_header = ["id:ID" if neo4j_headers else "id"]
+    node_header += [column(name, t) for name, t in GRAPH_NODE_ATTRIBUTES]
+    if neo4j_headers:
+        node_header.append(":LABEL")
+    with open(
+        os.path.join(output, CSV_NODES_FILE), "w", encoding="utf-8", newline=""
+    ) as f:
+        writer = csv.writer(f, lineterminator="\n")
+        writer.writerow(node_header)
+        for node_id in sorted(graph.nodes):
+            attributes = _graph_node_attributes(graph, node_id)
+            row = [node_id] + [
+                _csv_value(attributes[name]) for name, _ in GRAPH_NODE_ATTRIBUTES
+            ]
+            if neo4j_headers:
+                row.append(attributes["kind"].capitalize())
+            writer.writerow(row)
+
+    if neo4j_headers:
+        edge_header = [":START_ID", ":END_ID", ":TYPE"]
+    else:
+        edge_header = ["caller_id", "callee_id"]
+    edge_header += [column(name, t) for name, t, _ in GRAPH_EDGE_ATTRIBUTES]
+    edges = _sorted_edges(graph)
+    with open(
+        os.path.join(output, CSV_EDGES_FILE), "w", encoding="utf-8", newline=""
+    ) as f:
+        writer = csv.writer(f, lineterminator="\n")
+        writer.writerow(edge_header)
+        for edge in edges:
+            row = [edge["caller_id"], edge["callee_id"]]
+            if neo4j_headers:
+                row.append(NEO4J_RELATIONSHIP_TYPE)
+            row += [edge[field] for _, _, field in GRAPH_EDGE_ATTRIBUTES]
+            writer.writerow(row)
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边")
+    return output
+
+
+# D2 中按类别设置的节点形状（普通函数使用默认的矩形）
+D2_SHAPES = {"method": "oval", "closure": "diamond", "module": "package"}
+
+# rankdir -> D2 的 direction
+D2_DIRECTIONS = {"LR": "right", "TB": "down", "RL": "left", "BT": "up"}
+
+
+def _d2_string(text: str) -> str:
+    """D2 的双引号字符串（转义方式与 JSON 相同）"""
+    return json.dumps(str(text), ensure_ascii=False)
+
+
+def _container_path(graph: CallGraph, node_id: str) -> List[str]:
+    """
+    节点所在的嵌套容器：Rust 按 crate::模块路径，其他语言按目录和文件；
+    模块级视图中模块节点放在与它同名的容器中，与子模块的容器并列
+    """
+    if graph.node_kind(node_id) == "module":
+        name = graph.nodes[node_id]["name"]
+    else:
+        name = graph.module_name(node_id)
+    return [part for part in name.split("::" if "::" in name else "/") if part]
+
+
+def _container_tree(
+    graph: CallGraph, node_ids: Iterable[str], depth: int = -1, types: bool = False
+) -> Dict[str, Any]:
+    """
+    按 _container_path 把节点组织成容器树 {"children": {(类别, 名称): 子树},
+    "nodes": [节点ID]}，类别为 module / type
+
+    types 时方法再放在所属类型（impl 类型、类）的容器中；depth 限制嵌套的层数，
+    负数表示不限制，0 表示所有节点都在根上
+    """
+    tree: Dict[str, Any] = {"children": {}, "nodes": []}
+    for node_id in node_ids:
+        path = [("module", part) for part in _container_path(graph, node_id)]
+        owner = (graph.nodes[node_id].get("extras") or {}).get("owner")
+        if types and owner and graph.node_kind(node_id) == "method":
+            path.append(("type", owner))
+        if depth >= 0:
+            path = path[:depth]
+        container = tree
+        for key in path:
+            container = container["children"].setdefault(
+                key, {"children": {}, "nodes": []}
+            )
+        container["nodes"].append(node_id)
+    return tree
+
+
+def export_d2(
+    graph: CallGraph,
+    shape_by_kind: bool = False,
+    edge_labels: bool = False,
+    rankdir: str = "LR",
+) -> str:
+    """
+    导出为 D2 图，每个 crate / 模块是一个嵌套容器
+
+    Args:
+        graph: 调用图
+        shape_by_kind: 按类别设置节点形状（方法=椭圆，闭包=菱形）
+        edge_labels: 在边上标注调用次数
+        rankdir: 布局方向（LR/TB/RL/BT）
+    """
+    lines = [f"direction: {D2_DIRECTIONS.get(rankdir, 'right')}"]
+
+    # D2 的键使用序号，显示的名称放在 label 中；容器按路径组织成树
+    tree = _container_tree(graph, graph.nodes)
+
+    keys: Dict[str, str] = {}
+    counter = {"container": 0, "node": 0}
+
+    def emit(container: Dict[str, Any], prefix: str, indent: str):
+        for node_id in container["nodes"]:
+            key = f"n{counter['node']}"
+            counter["node"] += 1
+            keys[node_id] = prefix + key
+            node = graph.nodes[node_id]
+            if graph.node_kind(node_id) == "elided":
+                location = ""
+            elif graph.node_kind(node_id) in GROUP_KINDS:
+                location = f"{node['extras']['functions']} 个函数"
+            else:
+                location = (
+                    f"{os.path.basename(node['file'] or '')}:"
+                    f"{node.get('start_line', '?')}"
+                )
+            label = _d2_string(
+                f"{node['name']}\n{location}" if location else node["name"]
+            )
+            shape = D2_SHAPES.get(graph.node_kind(node_id)) if shape_by_kind else None
+            if shape:
+                lines.append(f"{indent}{key}: {label} {{shape: {shape}}}")
+            else:
+                lines.append(f"{indent}{key}: {label}")
+        for (_, name), child in sorted(container["children"].items()):
+            key = f"m{counter['container']}"
+            counter["container"] += 1
+            lines.append(f"{indent}{key}: {_d2_string(name)} {{")
+            emit(child, f"{prefix}{key}.", indent + "  ")
+            lines.append(f"{indent}}}")
+
+    emit(tree, "", "")
+
+    edges = graph.edges()
+    for edge in edges:
+        color, dash = RENDER_EDGE_STYLES.get(
+            edge["edge_kind"], RENDER_EDGE_STYLES["static"]
+        )
+        label = _edge_label(edge, edge_labels)
+        line = f"{keys[edge['caller_id']]} -> {keys[edge['callee_id']]}"
+        if label:
+            line += f": {_d2_string(label)}"
+        styles = []
+        if color != RENDER_EDGE_STYLES["static"][0]:
+            styles.append(f'style.stroke: "{color}"')
+        if dash:
+            styles.append(f"style.stroke-dash: {dash[0] // 2 + 1}")
+        if styles:
+            line += " {" + "; ".join(styles) + "}"
+        lines.append(line)
+
+    print(f"导出成功: {len(graph.nodes)} 个节点, {len(edges)} 条边")
+
+    return "\n".join(lines)
+
+
+# 导出格式 -> 导出函数
+EXPORTERS = {
+    "dot": export_dot,
+    "mermaid": export_mermaid,
+    "json": export_json,
+    "html": export_html,
+    "graphml": export_graphml,
+    "gexf": export_gexf,
+    "plantuml": export_plantuml,
+    "d2": export_d2,
+    "svg": export_svg,
+    "cypher": export_cypher,
+}
+
+# 直接写入 output 文件（而不是返回文本内容）的导出格式
+FILE_EXPORTERS = {
+    "sqlite": export_sqlite,
+    "png": export_png,
+    "csv": export_csv,
+}
+
+
+def export_graph(graph: CallGraph, output_format: str = "dot", **options) -> str:
+    """
+    按格式导出调用图
+
+    FILE_EXPORTERS 中的格式需要 output 选项（输出文件路径，csv 为输出目录），
+    返回该路径
+    """
+    if output_format in FILE_EXPORTERS:
+        if not options.get("output"):
+            raise ValueError(f"{output_format} 格式需要指定输出文件")
+        return FILE_EXPORTERS[output_format](graph, **options)
+    exporter = EXPORTERS.get(output_format)
+    if not exporter:
+        raise ValueError(f"不支持的导出格式: {output_format}")
+    return exporter(graph, **options)
@@ -0,0 +1,272 @@
+"""
+Feature 组合分析
+按 Cargo.toml 中声明的 feature（或指定的组合）逐一求值函数和调用点上记录的
+#[cfg(feature = "...")] 条件（见 cfg 模块），找出只在部分组合下存在的函数和
+调用，以及只在某些 feature 下编译、在这些组合下却都没有调用者的函数
+"""
+
+from itertools import combinations
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import find_entry_points
+    from .cargo import load_toml
+    from .cfg import CfgSet, function_cfg
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import find_entry_points
+    from cargo import load_toml
+    from cfg import CfgSet, function_cfg
+    from graph import CallGraph
+
+# feature 数量不超过此值时分析所有组合，否则只分析默认、无 feature、
+# 每个 feature 单独启用和全部启用
+DEFAULT_MAX_FEATURES = 6
+
+
+def manifest_features(manifest: Dict[str, Any]) -> Dict[str, List[str]]:
+    """
+    Cargo.toml 中的 feature：[features] 表，加上可选依赖隐含的同名 feature
+    （没有任何 feature 用 dep:name 引用该依赖时）
+    """
+    table = {
+        str(name): [str(item) for item in (items or [])]
+        for name, items in (manifest.get("features") or {}).items()
+    }
+    explicit = {
+        item[4:]
+        for items in table.values()
+        for item in items
+        if item.startswith("dep:")
+    }
+    for key in ("dependencies", "build-dependencies"):
+        for name, spec in (manifest.get(key) or {}).items():
+            optional = isinstance(spec, dict) and spec.get("optional")
+            if optional and name not in explicit and name not in table:
+                table[name] = [f"dep:{name}"]
+    return table
+
+
+def feature_closure(requested: Iterable[str], table: Dict[str, List[str]]) -> Set[str]:
+    """
+    启用 requested 后实际启用的 feature（沿 [features] 表展开）
+
+    dep:x 只启用依赖，不启用 feature；x/y 同时启用 feature x（存在时），
+    x?/y 不启用 x
+    """
+    enabled: Set[str] = set()
+    pending = list(requested)
+    while pending:
+        name = pending.pop()
+        if name in enabled:
+            continue
+        enabled.add(name)
+        for item in table.get(name, []):
+            if item.startswith("dep:"):
+                continue
+            dependency, separator, _ = item.partition("/")
+            if not separator:
+                pending.append(item)
+            elif not dependency.endswith("?") and dependency in table:
+                pending.append(dependency)
+    return enabled
+
+
+def parse_feature_set(text: str) -> List[str]:
+    """--set 的值：逗号或空白分隔的 feature 名，空字符串表示不启用任何 feature"""
+    return [name for name in text.replace(",", " ").split() if name]
+
+
+def feature_matrix(
+    table: Dict[str, List[str]], max_features: int = DEFAULT_MAX_FEATURES
+) -> List[List[str]]:
+    """
+    要分析的 feature 组合（每个组合为请求启用的 feature）
+
+    不超过 max_features 个 feature 时为所有组合（default 也作为一个
+    feature），否则为无 feature、默认、每个 feature 单独启用和全部启用
+    """
+    names = sorted(table)
+    if len(names) <= max_features:
+        return [
+            list(subset)
+            for size in range(len(names) + 1)
+            for subset in combinations(names, size)
+        ]
+    matrix = [[]]
+    if "default" in table:
+        matrix.append(["default"])
+    matrix.extend([name] for name in names if name != "default")
+    matrix.append(names)
+    return matrix
+
+
+def combination_label(requested: List[str]) -> str:
+    return ",".join(requested) if requested else "（无）"
+
+
+def crate_manifests(graph: CallGraph) -> Dict[Optional[str], Path]:
+    """
+    Rust 函数所属 crate 的 Cargo.toml：{crate 名: 路径}
+
+    从函数所在的文件向上查找包含 [package] 的 Cargo.toml；没有标注 crate 的
+    函数（未识别出 Cargo 工作区）的键为 None
+    """
+    manifests: Dict[Optional[str], Path] = {}
+    for node in graph.nodes.values():
+        if node.get("language") != "rust" or not node.get("file"):
+            continue
+        extras = node.get("extras") or {}
+        crate = extras.get("crate")
+        if crate in manifests or extras.get("dependency"):
+            continue
+        for directory in Path(node["file"]).resolve().parents:
+            path = directory / "Cargo.toml"
+            if path.is_file() and "package" in load_toml(path):
+                manifests[crate] = path
+                break
+    return manifests
+
+
+class FeatureMatrix:
+    """
+    在一个 crate 的各个 feature 组合下求值 cfg 条件
+
+    数据库需要在不带 --cfg 或带 --cfg-mode tag 的情况下分析（保留所有函数和
+    调用点）；只求值该 crate 中的函数和从该 crate 发出的调用，其他 crate 中的
+    函数视为始终存在；名称解析的结果不随组合变化
+    """
+
+    def __init__(
+        self,
+        graph: CallGraph,
+        crate: Optional[str],
+        table: Dict[str, List[str]],
+        cfg: Iterable[str] = (),
+    ):
+        self.graph = graph
+        self.crate = crate
+        self.table = table
+        self.cfg = list(cfg)
+        self.members = {
+            node_id
+            for node_id, node in graph.nodes.items()
+            if node.get("language") == "rust"
+            and (node.get("extras") or {}).get("crate") == crate
+        }
+
+    def _active_nodes(self, cfg_set: CfgSet) -> Set[str]:
+        return {
+            node_id
+            for node_id in self.members
+            if cfg_set.active(function_cfg(self.graph.nodes[node_id]))
+        }
+
+    def _active_edges(
+        self, cfg_set: CfgSet, active: Set[str]
+    ) -> Set[Tuple[str, str]]:
+        edges = set()
+        for caller_id in self.members:
+            for callee_id in self.graph.successors(caller_id):
+                if caller_id not in active or (
+                    callee_id in self.members and callee_id not in active
+                ):
+                    continue
+                sites = self.graph.call_sites(caller_id, callee_id)
+                if any(
+                    not site.get("cfg") or cfg_set.evaluate(site["cfg"])
+                    for site in sites
+                ):
+                    edges.add((caller_id, callee_id))
+        return edges
+
+    def analyze(self, matrix: List[List[str]]) -> Dict[str, Any]:
+        """
+        Returns:
+            - combinations: 每个组合请求的 feature、实际启用的 feature 和
+              存在的函数、调用数量
+            - functions / edges: 只在部分组合下存在的函数和调用，及存在于哪些
+              组合（组合的下标）
+            - absent: 在所有组合下都不存在的函数（如 cfg(test)、其他平台的代码）
+            - uncalled: 只在部分组合下存在、在这些组合下都没有调用者的函数
+              （入口点除外）
+        """
+        all_edges = {
+            (caller_id, callee_id)
+            for caller_id in self.members
+            for callee_id in self.graph.successors(caller_id)
+        }
+        results = []
+        node_presence: Dict[str, List[int]] = {node_id: [] for node_id in self.members}
+        edge_presence: Dict[Tuple[str, str], List[int]] = {
+            edge: [] for edge in all_edges
+        }
+        called: Set[str] = set()
+        for index, requested in enumerate(matrix):
+            enabled = feature_closure(requested, self.table)
+            cfg_set = CfgSet(
+                self.cfg + [f'feature="{name}"' for name in sorted(enabled)]
+            )
+            active = self._active_nodes(cfg_set)
+            edges = self._active_edges(cfg_set, active)
+            for node_id in active:
+                node_presence[node_id].append(index)
+            for edge in edges:
+                edge_presence[edge].append(index)
+            # 其他 crate 中的调用者视为始终存在
+            for node_id in active:
+                if any(
+                    caller_id not in self.members
+                    and caller_id != node_id
+                    for caller_id in self.graph.predecessors(node_id)
+                ):
+                    called.add(node_id)
+            called.update(
+                callee_id for caller_id, callee_id in edges if caller_id != callee_id
+            )
+            results.append(
+                {
+                    "features": list(requested),
+                    "enabled": sorted(enabled),
+                    "functions": len(active),
+                    "edges": len(edges),
+                }
+            )
+
+        total = len(matrix)
+        functions = {
+            node_id: present
+            for node_id, present in node_presence.items()
+            if 0 < len(present) < total
+        }
+        edges = {
+            edge: present
+            for edge, present in edge_presence.items()
+            if 0 < len(present) < total
+        }
+        absent = [node_id for node_id, present in node_presence.items() if not present]
+        entry_points = find_entry_points(self.graph)
+        uncalled = sorted(
+            (
+                node_id
+                for node_id, present in functions.items()
+                if node_id not in called and node_id not in entry_points
+            ),
+            key=self._order,
+        )
+        return {
+            "crate": self.crate,
+            "combinations": results,
+            "functions": dict(sorted(functions.items(), key=lambda i: self._order(i[0]))),
+            "edges": dict(
+                sorted(edges.items(), key=lambda i: (self._order(i[0][0]), i[0][1]))
+            ),
+            "absent": sorted(absent, key=self._order),
+            "uncalled": uncalled,
+        }
+
+    def _order(self, node_id: str) -> Tuple[str, int]:
+        node = self.graph.nodes[node_id]
+        return (node.get("file") or "", node.get("start_line") or 0)
@@ -0,0 +1,181 @@
+"""
+符号过滤
+按文件路径和完全限定名的 glob / 正则模式包含或排除函数
+"""
+
+import fnmatch
+import os
+import re
+from pathlib import Path
+from typing import Iterable, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .cargo import load_toml
+    from .graph import EDGE_KINDS, PHASES, CallGraph, attribute_name
+except ImportError:
+    from cargo import load_toml
+    from graph import EDGE_KINDS, PHASES, CallGraph, attribute_name
+
+# 以该前缀开头的模式按正则表达式匹配，其他按 glob 匹配
+REGEX_PREFIX = "re:"
+# 以该前缀开头的模式匹配函数上的属性/注解/装饰器名（不区分大小写）
+ATTRIBUTE_PREFIX = "attr:"
+
+
+def _suffixes(text: str, separator: str) -> List[str]:
+    """从每个路径段开始的后缀：a::b::c -> [a::b::c, b::c, c]"""
+    result = [text]
+    for match in re.finditer(separator, text):
+        result.append(text[match.end() :])
+    return [suffix for suffix in result if suffix]
+
+
+def node_match_text(graph: CallGraph, node_id: str) -> Tuple[str, str]:
+    """模式匹配的对象：节点的限定名和（相对项目根目录的）文件路径"""
+    qualified_name = graph.qualified_name(node_id)
+    file_path = graph.nodes[node_id].get("file") or ""
+    root = graph.project_root()
+    if root and file_path.startswith(root):
+        file_path = os.path.relpath(file_path, root)
+    return qualified_name, file_path.replace(os.sep, "/")
+
+
+def node_attributes(graph: CallGraph, node_id: str) -> List[str]:
+    """attr: 模式匹配的对象：函数上所有属性的名称（去掉参数，小写）"""
+    extras = graph.nodes[node_id].get("extras") or {}
+    return [attribute_name(a).lower() for a in extras.get("attributes") or []]
+
+
+class Pattern:
+    """
+    单个过滤模式
+
+    - re:<正则>：在限定名和文件路径中搜索
+    - attr:<glob>：匹配函数上的属性名（不含参数，不区分大小写），
+      attr:deprecated 匹配 Rust 的 #[deprecated]、Java 的 @Deprecated 和
+      Python 的 @deprecated，attr:tokio::* 匹配 #[tokio::main] 等
+    - 其他为 glob（* 可以跨越 :: 和 /），从任意路径段开始匹配完整后缀：
+      tests::* 匹配 my_crate::net::tests::roundtrip，*::fmt 匹配所有 fmt 方法，
+      tests/* 匹配任意 tests 目录下的文件
+    """
+
+    def __init__(self, text: str):
+        self.text = text
+        self.regex = None
+        self.attribute = None
+        if text.startswith(ATTRIBUTE_PREFIX):
+            self.attribute = text[len(ATTRIBUTE_PREFIX) :].lower()
+        elif text.startswith(REGEX_PREFIX):
+            try:
+                self.regex = re.compile(text[len(REGEX_PREFIX) :])
+            except re.error as e:
+                raise ValueError(f"无效的正则表达式 '{text}': {e}") from e
+
+    def matches(
+        self, qualified_name: str, file_path: str, attributes: Iterable[str] = ()
+    ) -> bool:
+        if self.attribute is not None:
+            return any(
+                fnmatch.fnmatchcase(attribute, self.attribute)
+                for attribute in attributes
+            )
+        if self.regex:
+            return bool(
+                self.regex.search(qualified_name) or self.regex.search(file_path)
+            )
+        candidates = _suffixes(qualified_name, r"::|\.") + _suffixes(file_path, "/")
+        return any(
+            fnmatch.fnmatchcase(candidate, self.text) for candidate in candidates
+        )
+
+    def matches_node(self, graph: CallGraph, node_id: str) -> bool:
+        """按节点的限定名、文件路径和属性匹配"""
+        return self.matches(
+            *node_match_text(graph, node_id), node_attributes(graph, node_id)
+        )
+
+
+class SymbolFilter:
+    """
+    包含/排除过滤器
+
+    指定了包含模式时只保留至少匹配一个包含模式的函数；
+    然后去掉匹配任一排除模式的函数；指定了 phase（见 graph.PHASES）时只保留
+    运行时或编译期（build.rs、过程宏 crate）的函数；指定了 edge_kinds（见
+    graph.EdgeKind）时只保留这些类型的调用边
+    """
+
+    def __init__(
+        self,
+        include: Optional[Iterable[str]] = None,
+        exclude: Optional[Iterable[str]] = None,
+        phase: Optional[str] = None,
+        edge_kinds: Optional[Iterable[str]] = None,
+    ):
+        if phase is not None and phase not in PHASES:
+            raise ValueError(f"未知的阶段: {phase}（可选: {', '.join(PHASES)}）")
+        unknown = [kind for kind in edge_kinds or [] if kind not in EDGE_KINDS]
+        if unknown:
+            raise ValueError(
+                f"未知的边类型: {', '.join(unknown)}（可选: {', '.join(EDGE_KINDS)}）"
+            )
+        self.include = [Pattern(text) for text in include or []]
+        self.exclude = [Pattern(text) for text in exclude or []]
+        self.phase = phase
+        self.edge_kinds = list(edge_kinds or [])
+
+    def __bool__(self) -> bool:
+        return bool(self.include or self.exclude or self.phase or self.edge_kinds)
+
+    @classmethod
+    def from_config(cls, config_path: str) -> "SymbolFilter":
+        """
+        从 TOML 配置文件的 [filters] 表读取过滤模式::
+
+            [filters]
+            include = ["my_crate::*"]
+            exclude = ["tests::*", "*::fmt"]
+            phase = "runtime"
+            edge_kinds = ["static", "dynamic"]
+        """
+        path = Path(config_path)
+        if not path.exists():
+            raise ValueError(f"配置文件不存在: {config_path}")
+        filters = load_toml(path).get("filters") or {}
+        return cls(
+            filters.get("include"),
+            filters.get("exclude"),
+            filters.get("phase"),
+            filters.get("edge_kinds"),
+        )
+
+    def merge(self, other: "SymbolFilter") -> "SymbolFilter":
+        """
+        合并两个过滤器的模式（如配置文件和命令行，阶段和边类型以 other 为准）
+        """
+        merged = SymbolFilter()
+        merged.include = self.include + other.include
+        merged.exclude = self.exclude + other.exclude
+        merged.phase = other.phase or self.phase
+        merged.edge_kinds = other.edge_kinds or self.edge_kinds
+        return merged
+
+    def accepts(self, graph: CallGraph, node_id: str) -> bool:
+        if self.phase and graph.phase(node_id) != self.phase:
+            return False
+        if self.include and not any(
+            pattern.matches_node(graph, node_id) for pattern in self.include
+        ):
+            return False
+        return not any(pattern.matches_node(graph, node_id) for pattern in self.exclude)
+
+    def apply(self, graph: CallGraph) -> CallGraph:
+        """返回只包含通过过滤的函数（和指定类型的调用边）的子图"""
+        if self.include or self.exclude or self.phase:
+            graph = graph.subgraph(
+                node_id for node_id in graph.nodes if self.accepts(graph, node_id)
+            )
+        if self.edge_kinds:
+            graph = graph.with_edge_kinds(self.edge_kinds)
+        return graph
@@ -0,0 +1,142 @@
+"""
+函数模糊查找
+按完全限定名的子串、单词和子序列匹配函数，用于记不清完整限定名时查找
+--focus、paths 等命令需要的函数
+"""
+
+import re
+from typing import List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+# 匹配方式的基础分数（越高越靠前）：
+# - exact: 函数名或限定名与查找文本相同
+# - suffix: 限定名以查找文本结尾（按路径段，如 db::save 匹配 app::db::save）
+# - prefix: 函数名以查找文本开头
+# - name: 函数名包含查找文本
+# - qualified: 限定名（或文件路径）包含查找文本
+# - words: 查找文本的每个单词（按 _、::、.、/ 和空格拆分）都出现在限定名中
+# - fuzzy: 查找文本的字符按顺序出现在限定名中
+MATCH_SCORES = {
+    "exact": 100,
+    "suffix": 90,
+    "prefix": 80,
+    "name": 70,
+    "qualified": 60,
+    "words": 50,
+    "fuzzy": 0,
+}
+
+# 子序列匹配的最高分数（低于 words）
+FUZZY_MAX_SCORE = 40
+
+WORD_SEPARATORS = re.compile(r"[._/\s]+")
+# 限定名中的单词边界：分隔符之后、驼峰的大写字母
+BOUNDARY_CHARACTERS = ":._/ "
+
+
+def _normalize(text: str) -> str:
+    """统一路径分隔符（Python、Java 的 . 和 Rust 的 ::）并转为小写"""
+    return text.replace("::", ".").lower()
+
+
+def _fuzzy_score(pattern: str, text: str, original: str) -> Optional[float]:
+    """
+    pattern 的字符按顺序出现在 text 中时的分数（0 ~ FUZZY_MAX_SCORE），
+    否则为 None
+
+    贪心地从左到右匹配，连续匹配的字符和位于单词边界（分隔符之后、驼峰的
+    大写字母）的字符加分，匹配范围越分散分数越低
+    """
+    position = 0
+    first = None
+    bonus = 0
+    previous = -2
+    for char in pattern:
+        index = text.find(char, position)
+        if index < 0:
+            return None
+        if first is None:
+            first = index
+        if index == previous + 1:
+            bonus += 2
+        if index == 0 or original[index - 1] in BOUNDARY_CHARACTERS:
+            bonus += 3
+        elif original[index].isupper() and original[index - 1].islower():
+            bonus += 3
+        previous = index
+        position = index + 1
+    span = previous - first + 1
+    density = len(pattern) / span
+    quality = (density + min(bonus / (5 * len(pattern)), 1.0)) / 2
+    return round(FUZZY_MAX_SCORE * quality, 2)
+
+
+def match_score(graph: CallGraph, node_id: str, pattern: str) -> Optional[float]:
+    """函数与查找文本的匹配分数（见 MATCH_SCORES），不匹配时为 None"""
+    needle = _normalize(pattern.strip())
+    if not needle:
+        return None
+    node = graph.nodes[node_id]
+    name = node["name"].lower()
+    original = graph.qualified_name(node_id).replace("::", ".")
+    qualified = original.lower()
+
+    if needle in (name, qualified):
+        return MATCH_SCORES["exact"]
+    if qualified.endswith(f".{needle}"):
+        return MATCH_SCORES["suffix"]
+    if name.startswith(needle):
+        return MATCH_SCORES["prefix"]
+    if needle in name:
+        return MATCH_SCORES["name"]
+    if needle in qualified or needle in (node.get("file") or "").lower():
+        return MATCH_SCORES["qualified"]
+    words = [word for word in WORD_SEPARATORS.split(needle) if word]
+    if len(words) > 1 and all(word in qualified for word in words):
+        return MATCH_SCORES["words"]
+    compact = needle.replace(" ", "")
+    score = _fuzzy_score(compact, qualified, original)
+    if score is None:
+        return None
+    return MATCH_SCORES["fuzzy"] + score
+
+
+def fuzzy_find(
+    graph: CallGraph, pattern: str, limit: Optional[int] = 20
+) -> List[Tuple[str, float]]:
+    """
+    按匹配分数从高到低返回 [(节点ID, 分数)]
+
+    分数相同时限定名较短的在前；limit 为 None 或 0 时返回所有匹配
+    """
+    scored = []
+    for node_id in graph.nodes:
+        score = match_score(graph, node_id, pattern)
+        if score is not None:
+            scored.append((node_id, score))
+    scored.sort(
+        key=lambda item: (
+            -item[1],
+            len(graph.qualified_name(item[0])),
+            graph.qualified_name(item[0]),
+            graph.nodes[item[0]].get("file") or "",
+        )
+    )
+    return scored[:limit] if limit else scored
+
+
+def suggestion(graph: CallGraph, name: str, limit: int = 3) -> str:
+    """
+    找不到函数时的提示：相近的函数的限定名（没有时为空字符串），
+    如 "（相近的函数: app::auth::validate_user、...，可用 find 命令查找）"
+    """
+    matches = fuzzy_find(graph, name, limit)
+    if not matches:
+        return ""
+    names = "、".join(graph.qualified_name(node_id) for node_id, _ in matches)
+    return f"（相近的函数: {names}，可用 find 命令查找）"
@@ -0,0 +1,1119 @@
+"""
+内存调用图
+从数据库加载函数节点和调用边，供导出器和图分析使用
+"""
+
+import hashlib
+import json
+import os
+import re
+import sys
+from array import array
+from collections import defaultdict, deque
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import Any, Dict, Iterable, Iterator, List, Optional, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .cargo import function_path
+    from .cfg import combine_cfg, function_cfg
+    from .database import CallGraphDB
+except ImportError:
+    from cargo import function_path
+    from cfg import combine_cfg, function_cfg
+    from database import CallGraphDB
+
+# 单态化时沿泛型函数内部的调用继续实例化的最大层数
+MONOMORPHIZE_DEPTH = 8
+
+# 调用边的置信度，从高到低：
+# - certain: 确定的调用（直接的静态调用，按路径、导入或已知的接收者类型解析）
+# - probable: 可能的目标之一（trait 对象/泛型约束的实现、函数指针的候选等）
+# - heuristic: 按名称猜测的目标（如接收者类型未知的 obj.m()）
+CONFIDENCE_LEVELS = ("certain", "probable", "heuristic")
+
+
+class EdgeKind(str, Enum):
+    """
+    调用边的类型（调用关系的 edge_kind，JSON 导出中的 edges[].kind）
+
+    - static: 目标确定的直接调用
+    - dynamic: trait 对象、泛型约束、接口上的动态分发，目标是实现之一
+    - closure: 函数到其内部定义的闭包
+    - macro: 宏展开后生成的调用
+    - indirect: 通过函数指针、回调、存入字段的函数值的间接调用
+    - operator: 运算符重载和解引用对应的 trait 方法（--operator-calls）
+    - drop: 值被销毁时的 Drop::drop（--drop-glue）
+    - annotated: 源码注释指令声明的调用
+    - ffi: 跨语言边界上的调用（Rust 与 C/C++ 之间）
+    - spawn: 交给新线程、异步任务运行的函数和闭包（thread::spawn(f)、
+      Thread(target=f)、pthread_create、Go 的 go f()），与调用者并发执行
+
+    继承 str：成员与对应的字符串相等，可以直接与调用关系中的 edge_kind 比较
+    """
+
+    STATIC = "static"
+    DYNAMIC = "dynamic"
+    CLOSURE = "closure"
+    MACRO = "macro"
+    INDIRECT = "indirect"
+    OPERATOR = "operator"
+    DROP = "drop"
+    ANNOTATED = "annotated"
+    FFI = "ffi"
+    SPAWN = "spawn"
+
+
+EDGE_KINDS = tuple(kind.value for kind in EdgeKind)
+
+# 稳定 ID 的长度（十六进制字符数），与数据库中的符号 ID（32 位 MD5）区分
+STABLE_ID_LENGTH = 16
+STABLE_ID_PATTERN = re.compile(rf"[0-9a-f]{{{STABLE_ID_LENGTH}}}")
+# 签名中不参与稳定 ID 的部分：Rust 的可见性（pub、pub(crate) 等）
+SIGNATURE_VISIBILITY = re.compile(r"^pub(\([^)]*\))?\s+")
+
+# 代表多个函数的聚合节点：模块级视图中的模块（module）、类型级视图中的类型
+# （type）、缩点后的强连通分量（component）和裁剪大图时合并被裁掉的函数的占位
+# 节点（elided，见 pruning 模块）
+GROUP_KINDS = ("module", "type", "component", "elided")
+
+# 类型级视图中签名依赖的边类型：方法的参数或返回值中使用了另一个类型（不是调用，
+# 不属于 EdgeKind）
+SIGNATURE_EDGE_KIND = "signature"
+
+# 签名中的标识符（按名称与已知的类型对应）
+SIGNATURE_IDENTIFIER = re.compile(r"[A-Za-z_][A-Za-z0-9_]*")
+
+# 函数运行的阶段：runtime（程序运行时）/ compile-time（构建脚本 build.rs 和过程宏
+# crate 中的代码，只在编译期运行，生成代码而不在程序的调用路径上）
+PHASES = ("runtime", "compile-time")
+
+# 节点元数据中不列出的属性：文档注释（#[doc]）、条件编译（已单独记录在 cfg 中）
+# 和 lint 控制，其余属性（#[inline]、#[deprecated]、#[tokio::main]、Java 注解、
+# Python 装饰器等）见 CallGraph.attributes
+METADATA_IGNORED_ATTRIBUTES = frozenset(
+    ("doc", "cfg", "cfg_attr", "allow", "warn", "deny", "forbid", "expect")
+)
+
+# 弃用属性中的字符串参数："..." 或 '...'（Python），前面可以有参数名
+DEPRECATION_ARGUMENT = re.compile(
+    r"""(?:(\w+)\s*=\s*)?(?:"((?:[^"\\]|\\.)*)"|'((?:[^'\\]|\\.)*)')"""
+)
+# 弃用说明和起始版本的参数名：Rust 的 note/since、Java 的 since、Python
+# deprecated / Deprecated 包的 reason、details、version 等
+DEPRECATION_NOTE_KEYS = ("note", "reason", "details", "message", "msg")
+DEPRECATION_SINCE_KEYS = ("since", "version", "deprecated_in")
+
+# 加载时驻留的字符串字段：同一函数 ID、文件路径等在节点和各调用点中共享一个对象
+NODE_STRING_FIELDS = ("id", "name", "file", "language", "kind")
+RELATION_STRING_FIELDS = (
+    "caller_id",
+    "callee_id",
+    "caller_name",
+    "callee_name",
+    "caller_file",
+    "callee_file",
+    "language",
+    "edge_kind",
+    "confidence",
+)
+
+
+def _intern_fields(record: Dict[str, Any], fields: Tuple[str, ...]):
+    for name in fields:
+        value = record.get(name)
+        if type(value) is str:
+            record[name] = sys.intern(value)
+
+
+def site_confidence(relation: Dict[str, Any]) -> str:
+    """调用点的置信度（旧数据库中没有记录的为 certain）"""
+    confidence = relation.get("confidence")
+    return confidence if confidence in CONFIDENCE_LEVELS else "certain"
+
+
+def edge_kind(relation: Dict[str, Any]) -> str:
+    """调用点的边类型（见 EdgeKind，没有记录的为 static）"""
+    return relation.get("edge_kind") or EdgeKind.STATIC.value
+
+
+def best_confidence(sites: Iterable[Dict[str, Any]]) -> str:
+    """合并后的边的置信度：各调用点中最高的"""
+    return min(
+        (site_confidence(site) for site in sites),
+        key=CONFIDENCE_LEVELS.index,
+        default="certain",
+    )
+
+
+@dataclass(frozen=True)
+class Node:
+    """函数节点的只读视图（由 CallGraph.node 创建）"""
+
+    id: str
+    name: str
+    qualified_name: str
+    kind: str
+    visibility: str
+    language: Optional[str]
+    file: Optional[str]
+    start_line: Optional[int]
+    end_line: Optional[int]
+    extras: Dict[str, Any] = field(default_factory=dict, compare=False)
+
+
+@dataclass(frozen=True)
+class Edge:
+    """
+    合并后调用边的只读视图（由 CallGraph.edge 创建）
+
+    kind 为边的类型（见 EdgeKind），sites 为所有调用点的 (文件, 行号, 列号)，
+    confidence 为各调用点中最高的置信度
+    """
+
+    caller: str
+    callee: str
+    kind: str
+    count: int
+    confidence: str = "certain"
+    sites: Tuple[Tuple[Optional[str], Optional[int], Optional[int]], ...] = field(
+        default=(), compare=False
+    )
+
+
+class CompactGraph:
+    """
+    调用图邻接关系的紧凑表示（CSR），由 CallGraph.compact 创建
+
+    节点编号为 0..n-1（ids[i] 为符号 ID，index 为反向映射），节点 i 的后继为
+    out_targets[out_offsets[i]:out_offsets[i + 1]]，前驱同理；编号用 32 位整数
+    数组保存，遍历时不再查找字符串键，适合可达性、强连通分量等需要遍历全图的分析
+    """
+
+    def __init__(self, graph: "CallGraph"):
+        self.ids: List[str] = list(graph.nodes)
+        self.index: Dict[str, int] = {node_id: i for i, node_id in enumerate(self.ids)}
+        self.out_offsets, self.out_targets = self._build(graph._out)
+        self.in_offsets, self.in_targets = self._build(graph._in)
+
+    def _build(self, adjacency: Dict[str, Dict[str, Any]]) -> Tuple[array, array]:
+        offsets = array("I", [0])
+        targets = array("I")
+        index = self.index
+        for node_id in self.ids:
+            targets.extend(index[n] for n in adjacency.get(node_id, ()))
+            offsets.append(len(targets))
+        return offsets, targets
+
+    def __len__(self) -> int:
+        return len(self.ids)
+
+    def successors(self, i: int) -> array:
+        return self.out_targets[self.out_offsets[i] : self.out_offsets[i + 1]]
+
+    def predecessors(self, i: int) -> array:
+        return self.in_targets[self.in_offsets[i] : self.in_offsets[i + 1]]
+
+    def reachable(self, roots: Iterable[str], reverse: bool = False) -> Set[str]:
+        """从 roots 出发（reverse 时沿调用者方向）可达的节点 ID，包括 roots 本身"""
+        if reverse:
+            offsets, targets = self.in_offsets, self.in_targets
+        else:
+            offsets, targets = self.out_offsets, self.out_targets
+        visited = bytearray(len(self.ids))
+        queue = deque()
+        for root in roots:
+            i = self.index.get(root)
+            if i is not None and not visited[i]:
+                visited[i] = 1
+                queue.append(i)
+        while queue:
+            i = queue.popleft()
+            for j in targets[offsets[i] : offsets[i + 1]]:
+                if not visited[j]:
+                    visited[j] = 1
+                    queue.append(j)
+        return {self.ids[i] for i, seen in enumerate(visited) if seen}
+
+
+class CallGraph:
+    """
+    调用图
+
+    - nodes: {符号ID: 符号信息}，extras_json 已解析为 extras
+    - relations: 所有调用点（包括指向外部函数的调用）
+    - 邻接表只包含两端都是已知函数的边，同一对函数的多个调用点合并为一条边
+    - 节点和调用点中的函数 ID、名称、文件路径等字符串在加载时驻留；
+      遍历全图的分析使用 compact 返回的 CSR 表示
+    """
+
+    def __init__(self):
+        self.nodes: Dict[str, Dict[str, Any]] = {}
+        self.relations: List[Dict[str, Any]] = []
+        self._out: Dict[str, Dict[str, List[Dict[str, Any]]]] = defaultdict(dict)
+        self._in: Dict[str, Dict[str, List[Dict[str, Any]]]] = defaultdict(dict)
+        self._by_name: Dict[str, List[str]] = defaultdict(list)
+        self._root: Optional[str] = None
+        self._compact: Optional[CompactGraph] = None
+        self._stable_ids: Optional[Dict[str, str]] = None
+        # 显式指定的项目根目录；未指定时取所有源文件的公共目录
+        self.root: Optional[str] = None
+
+    @classmethod
+    def from_db(cls, db: CallGraphDB) -> "CallGraph":
+        """从数据库加载调用图"""
+        graph = cls()
+        for symbol in db.get_symbols_by_kind("function"):
+            graph.add_node(symbol)
+
+        cursor = db.conn.cursor()
+        cursor.execute(
+            """
+            SELECT * FROM call_relations
+            WHERE caller_id IS NOT NULL AND callee_id IS NOT NULL
+            ORDER BY caller_file, call_site_line
+        """
+        )
+        for row in cursor:
+            graph.add_relation(dict(row))
+        return graph
+
+    def add_node(self, symbol: Dict[str, Any]):
+        """添加函数节点"""
+        node = dict(symbol)
+        if "extras" not in node:
+            try:
+                node["extras"] = json.loads(node.get("extras_json") or "{}")
+            except ValueError:
+                node["extras"] = {}
+        _intern_fields(node, NODE_STRING_FIELDS)
+        self.nodes[node["id"]] = node
+        self._by_name[node["name"]].append(node["id"])
+        self._root = None
+        self._stable_ids = None
+        self._compact = None
+
+    def add_relation(self, relation: Dict[str, Any]):
+        """添加调用点；两端都是已知函数时同时加入邻接表"""
+        _intern_fields(relation, RELATION_STRING_FIELDS)
+        self.relations.append(relation)
+        caller_id = relation["caller_id"]
+        callee_id = relation["callee_id"]
+        if caller_id in self.nodes and callee_id in self.nodes:
+            self._out[caller_id].setdefault(callee_id, []).append(relation)
+            self._in[callee_id].setdefault(caller_id, []).append(relation)
+            self._compact = None
+
+    def compact(self) -> CompactGraph:
+        """邻接关系的 CSR 表示（缓存到下一次添加节点或边为止）"""
+        if self._compact is None:
+            self._compact = CompactGraph(self)
+        return self._compact
+
+    def successors(self, node_id: str) -> List[str]:
+        """被该函数调用的函数 ID 列表"""
+        return list(self._out.get(node_id, {}).keys())
+
+    def predecessors(self, node_id: str) -> List[str]:
+        """调用该函数的函数 ID 列表"""
+        return list(self._in.get(node_id, {}).keys())
+
+    def call_sites(self, caller_id: str, callee_id: str) -> List[Dict[str, Any]]:
+        """两个函数之间的所有调用点"""
+        return self._out.get(caller_id, {}).get(callee_id, [])
+
+    def edges(self) -> List[Dict[str, Any]]:
+        """
+        合并后的边列表
+
+        每条边包含 caller_id, callee_id, count（调用点数量）, edge_kind,
+        confidence（各调用点中最高的置信度）、type_args（各调用点实例化泛型
+        函数使用的不同类型实参）、samples（叠加的剖析数据中这对函数之间的
+        调用样本数，没有时为 None）和 via（public_only 视图中合并的调用链经过
+        的私有函数，直接调用为空列表）
+        """
+        result = []
+        for caller_id, targets in self._out.items():
+            for callee_id, sites in targets.items():
+                result.append(
+                    {
+                        "caller_id": caller_id,
+                        "callee_id": callee_id,
+                        "count": len(sites),
+                        "edge_kind": edge_kind(sites[0]),
+                        "confidence": best_confidence(sites),
+                        "samples": sites[0].get("samples"),
+                        "via": sites[0].get("via") or [],
+                        "type_args": sorted(
+                            {
+                                site["type_args"]
+                                for site in sites
+                                if site.get("type_args")
+                            }
+                        ),
+                    }
+                )
+        return result
+
+    def node(self, node_id: str) -> Node:
+        """节点的 Node 视图"""
+        node = self.nodes[node_id]
+        return Node(
+            id=node_id,
+            name=node["name"],
+            qualified_name=self.qualified_name(node_id),
+            kind=self.node_kind(node_id),
+            visibility=self.visibility(node_id),
+            language=node.get("language"),
+            file=node.get("file"),
+            start_line=node.get("start_line"),
+            end_line=node.get("end_line"),
+            extras=dict(node.get("extras") or {}),
+        )
+
+    def edge(self, caller_id: str, callee_id: str) -> Optional[Edge]:
+        """两个函数之间合并后的边；没有调用时返回 None"""
+        sites = self.call_sites(caller_id, callee_id)
+        if not sites:
+            return None
+        return Edge(
+            caller=caller_id,
+            callee=callee_id,
+            kind=edge_kind(sites[0]),
+            count=len(sites),
+            confidence=best_confidence(sites),
+            sites=tuple(
+                (
+                    site.get("caller_file"),
+                    site.get("call_site_line"),
+                    site.get("call_site_column"),
+                )
+                for site in sites
+            ),
+        )
+
+    def iter_nodes(self) -> Iterator[Node]:
+        """遍历所有节点"""
+        for node_id in self.nodes:
+            yield self.node(node_id)
+
+    def iter_edges(self) -> Iterator[Edge]:
+        """遍历所有合并后的边"""
+        for caller_id, targets in self._out.items():
+            for callee_id in targets:
+                yield self.edge(caller_id, callee_id)
+
+    def lookup(self, name: str) -> List[Node]:
+        """按函数名或（部分）限定名查找节点，规则同 resolve"""
+        return [self.node(node_id) for node_id in self.resolve(name)]
+
+    def __contains__(self, node_id: object) -> bool:
+        return node_id in self.nodes
+
+    def find(self, name: str) -> List[str]:
+        """按函数名查找节点 ID"""
+        return list(self._by_name.get(name, []))
+
+    def resolve(self, name: str) -> List[str]:
+        """
+        按函数名、（部分）限定名或稳定 ID 查找节点 ID
+
+        process 匹配所有同名函数；my_crate::db::process、db::process、
+        app.handlers.process 按限定名的后缀匹配
+        """
+        if STABLE_ID_PATTERN.fullmatch(name):
+            matches = [n for n in self.nodes if self.stable_id(n) == name]
+            if matches:
+                return matches
+        if "::" not in name and "." not in name:
+            return self.find(name)
+        short_name = name.replace("::", ".").rsplit(".", 1)[-1]
+        matches = []
+        for node_id in self.nodes:
+            if not self.nodes[node_id]["name"].endswith(short_name):
+                continue
+            qualified = self.qualified_name(node_id)
+            for separator in ("::", "."):
+                if qualified == name or qualified.endswith(f"{separator}{name}"):
+                    matches.append(node_id)
+                    break
+        return matches
+
+    def subgraph(self, node_ids: Iterable[str]) -> "CallGraph":
+        """只包含给定节点及它们之间调用的子图（保留这些节点对外部函数的调用）"""
+        graph = CallGraph()
+        graph.root = self.root
+        keep = set(node_ids)
+        for node_id, node in self.nodes.items():
+            if node_id in keep:
+                graph.add_node(node)
+        for relation in self.relations:
+            callee_id = relation["callee_id"]
+            if relation["caller_id"] in keep and (
+                callee_id in keep or callee_id not in self.nodes
+            ):
+                graph.add_relation(relation)
+        return graph
+
+    def with_edge_kinds(self, kinds: Iterable[str]) -> "CallGraph":
+        """只保留给定类型（见 EdgeKind）的调用点，节点不变"""
+        allowed = set(kinds)
+        graph = CallGraph()
+        graph.root = self.root
+        for node in self.nodes.values():
+            graph.add_node(node)
+        for relation in self.relations:
+            if edge_kind(relation) in allowed:
+                graph.add_relation(relation)
+        return graph
+
+    def with_min_confidence(self, level: str) -> "CallGraph":
+        """去掉置信度低于 level 的调用点，节点不变"""
+        if level == CONFIDENCE_LEVELS[-1]:
+            return self
+        allowed = CONFIDENCE_LEVELS[: CONFIDENCE_LEVELS.index(level) + 1]
+        graph = CallGraph()
+        graph.root = self.root
+        for node in self.nodes.values():
+            graph.add_node(node)
+        for relation in self.relations:
+            if site_confidence(relation) in allowed:
+                graph.add_relation(relation)
+        return graph
+
+    def public_only(self) -> "CallGraph":
+        """
+        只保留 public 可见性函数的视图
+
+        公开函数之间的直接调用不变；经过私有函数（private、crate 等非 public
+        可见性）的调用链合并为一条公开函数之间的边，调用点为链上第一个调用点，
+        via 为链上的私有函数 ID（最短的一条链），置信度为链上最低的置信度。
+        已有直接调用的两个函数不再添加合并的边；私有函数对项目外函数的调用不保留
+        """
+        public = {
+            node_id for node_id in self.nodes if self.visibility(node_id) == "public"
+        }
+        graph = self.subgraph(public)
+        for caller_id in public:
+            direct = set(self.successors(caller_id))
+            # 只经过私有函数的广度优先搜索：{私有函数: 链上的前一个私有函数}
+            parents: Dict[str, Optional[str]] = {}
+            queue = deque()
+            for callee_id in self.successors(caller_id):
+                if callee_id not in public:
+                    parents[callee_id] = None
+                    queue.append(callee_id)
+            reached = set()
+            while queue:
+                node_id = queue.popleft()
+                for callee_id in self.successors(node_id):
+                    if callee_id in public:
+                        if callee_id in direct or callee_id in reached:
+                            continue
+                        reached.add(callee_id)
+                        via = [node_id]
+                        while parents[via[-1]] is not None:
+                            via.append(parents[via[-1]])
+                        via.reverse()
+                        chain = [caller_id, *via, callee_id]
+                        confidence = max(
+                            (
+                                best_confidence(self.call_sites(a, b))
+                                for a, b in zip(chain, chain[1:])
+                            ),
+                            key=CONFIDENCE_LEVELS.index,
+                        )
+                        callee = self.nodes[callee_id]
+                        graph.add_relation(
+                            {
+                                **self.call_sites(caller_id, via[0])[0],
+                                "callee_id": callee_id,
+                                "callee_name": callee["name"],
+                                "callee_file": callee.get("file"),
+                                "confidence": confidence,
+                                "via": via,
+                            }
+                        )
+                    elif callee_id not in parents:
+                        parents[callee_id] = node_id
+                        queue.append(callee_id)
+        return graph
+
+    def project_root(self) -> str:
+        """项目根目录：显式指定的 root，否则为所有源文件的公共目录"""
+        if self.root:
+            return self.root
+        if self._root is None:
+            files = {node["file"] for node in self.nodes.values() if node.get("file")}
+            if not files:
+                self._root = ""
+            elif len(files) == 1:
+                self._root = os.path.dirname(next(iter(files)))
+            else:
+                self._root = os.path.commonpath(list(files))
+        return self._root
+
+    def module_of(self, node_id: str) -> str:
+        """节点所属模块：相对项目根目录、去掉扩展名的文件路径"""
+        file_path = self.nodes[node_id].get("file") or ""
+        root = self.project_root()
+        rel = os.path.relpath(file_path, root) if root else file_path
+        return os.path.splitext(rel)[0].replace(os.sep, "/")
+
+    def module_name(self, node_id: str) -> str:
+        """
+        节点所属模块的显示名称
+
+        识别出 Cargo crate 的 Rust 函数为 crate::模块路径，其他为 module_of 的结果
+        """
+        extras = self.nodes[node_id].get("extras") or {}
+        if extras.get("crate"):
+            parts = [extras["crate"]]
+            if extras.get("module"):
+                parts.append(extras["module"])
+            return "::".join(parts)
+        return self.module_of(node_id)
+
+    def module_graph(self) -> "CallGraph":
+        """
+        按模块聚合的调用图
+
+        每个模块（源文件）一个节点（kind 为 module，extras.functions 为函数数量），
+        模块之间的每个调用点都保留，因此合并后的边计数就是模块间的调用次数；
+        模块内部的调用被忽略
+        """
+        graph = CallGraph()
+        graph.root = self.root
+        module_of_node = {}
+        for node_id, node in self.nodes.items():
+            name = self.module_name(node_id)
+            module_id = f"module:{name}"
+            module_of_node[node_id] = module_id
+            if module_id in graph.nodes:
+                graph.nodes[module_id]["extras"]["functions"] += 1
+                continue
+            graph.add_node(
+                {
+                    "id": module_id,
+                    "name": name,
+                    "kind": "module",
+                    "file": node["file"],
+                    "start_line": None,
+                    "end_line": None,
+                    "language": node.get("language"),
+                    "is_exported": 1,
+                    "extras": {"functions": 1, "crate": node["extras"].get("crate")},
+                }
+            )
+
+        for relation in self.relations:
+            caller = module_of_node.get(relation["caller_id"])
+            callee = module_of_node.get(relation["callee_id"])
+            if caller and callee and caller != callee:
+                graph.add_relation(
+                    {**relation, "caller_id": caller, "callee_id": callee}
+                )
+        return graph
+
+    def type_graph(self) -> "CallGraph":
+        """
+        按类型聚合的调用图
+
+        每个有方法的类型（impl 块、类、trait）一个节点（kind 为 type，name 为
+        限定名，extras.functions 为方法数量），不同类型的方法之间的每个调用点都
+        保留，合并后的边计数就是类型间的调用次数；同一类型内部的调用和普通函数
+        被忽略。方法签名中使用了另一个类型（按名称对应，同名时优先同一模块中的
+        类型）且两个类型之间没有调用时，添加 SIGNATURE_EDGE_KIND 类型的边，每个
+        使用它的方法一个调用点
+        """
+        graph = CallGraph()
+        graph.root = self.root
+        type_of_node = {}
+        by_name: Dict[Tuple[Optional[str], str], List[str]] = defaultdict(list)
+        for node_id, node in self.nodes.items():
+            extras = node.get("extras") or {}
+            owner = extras.get("owner")
+            if not owner or self.node_kind(node_id) != "method":
+                continue
+            qualified = self.qualified_name(node_id)
+            separator = "::" if node.get("language") in ("rust", "c", "cpp") else "."
+            suffix = separator + node["name"]
+            name = qualified[: -len(suffix)] if qualified.endswith(suffix) else owner
+            type_id = f"type:{name}"
+            type_of_node[node_id] = type_id
+            if type_id in graph.nodes:
+                graph.nodes[type_id]["extras"]["functions"] += 1
+                continue
+            by_name[(node.get("language"), owner.rsplit(separator, 1)[-1])].append(
+                type_id
+            )
+            graph.add_node(
+                {
+                    "id": type_id,
+                    "name": name,
+                    "kind": "type",
+                    "file": node["file"],
+                    "start_line": None,
+                    "end_line": None,
+                    "language": node.get("language"),
+                    "is_exported": 1,
+                    "extras": {
+                        "functions": 1,
+                        "owner": owner,
+                        "crate": extras.get("crate"),
+                        "module": extras.get("module"),
+                    },
+                }
+            )
+
+        calls = set()
+        for relation in self.relations:
+            caller = type_of_node.get(relation["caller_id"])
+            callee = type_of_node.get(relation["callee_id"])
+            if caller and callee and caller != callee:
+                calls.add((caller, callee))
+                graph.add_relation(
+                    {**relation, "caller_id": caller, "callee_id": callee}
+                )
+
+        for node_id, caller in type_of_node.items():
+            node = self.nodes[node_id]
+            used = set()
+            for word in SIGNATURE_IDENTIFIER.findall(node.get("signature") or ""):
+                candidates = by_name.get((node.get("language"), word)) or []
+                local = [
+                    type_id
+                    for type_id in candidates
+                    if graph.module_name(type_id) == self.module_name(node_id)
+                ]
+                if len(local) == 1 or len(candidates) == 1:
+                    used.add((local or candidates)[0])
+            for callee in sorted(used - {caller}):
+                if (caller, callee) in calls:
+                    continue
+                graph.add_relation(
+                    {
+                        "caller_id": caller,
+                        "callee_id": callee,
+                        "caller_name": node["name"],
+                        "callee_name": graph.nodes[callee]["name"],
+                        "caller_file": node["file"],
+                        "call_site_line": node.get("start_line"),
+                        "call_site_column": None,
+                        "language": node.get("language"),
+                        "edge_kind": SIGNATURE_EDGE_KIND,
+                    }
+                )
+        return graph
+
+    def monomorphize(self) -> "CallGraph":
+        """
+        按类型实参拆分泛型函数的调用图
+
+        调用点记录了类型实参的泛型函数（extras.type_params）按实参拆成单态化节点
+        name<实参>（extras.instance_of 为泛型函数 ID），调用点改为指向对应的实例。
+        实例复制泛型函数中的调用，并把其中的类型参数替换为实参，从而沿调用链继续
+        实例化（最多 MONOMORPHIZE_DEPTH 层）；所有调用都已实例化的泛型函数不再保留
+        """
+        outgoing: Dict[str, List[Dict[str, Any]]] = defaultdict(list)
+        for relation in self.relations:
+            outgoing[relation["caller_id"]].append(relation)
+
+        instances: Dict[Tuple[str, str], str] = {}
+        instance_nodes: Dict[str, Dict[str, Any]] = {}
+        relations: List[Dict[str, Any]] = []
+        # 待复制调用的实例：(实例 ID, 泛型函数 ID, 实参, 层数)
+        pending: List[Tuple[str, str, str, int]] = []
+        # 存在未实例化调用的泛型函数
+        uninstantiated = set()
+
+        def instantiate(relation: Dict[str, Any], depth: int) -> Dict[str, Any]:
+            callee_id = relation["callee_id"]
+            if not self._type_params(callee_id):
+                return relation
+            args = relation.get("type_args")
+            key = (callee_id, args)
+            if key not in instances:
+                if not args or depth > MONOMORPHIZE_DEPTH:
+                    uninstantiated.add(callee_id)
+                    return relation
+                node = self.nodes[callee_id]
+                instance_id = f"{callee_id}<{args}>"
+                instances[key] = instance_id
+                instance_nodes[instance_id] = {
+                    **node,
+                    "id": instance_id,
+                    "name": f"{node['name']}<{args}>",
+                    "extras": {
+                        **(node.get("extras") or {}),
+                        "instance_of": callee_id,
+                        "type_args": args,
+                    },
+                }
+                # 实例的稳定 ID 按实例名 name<实参> 重新计算
+                instance_nodes[instance_id]["extras"].pop("stable_id", None)
+                pending.append((instance_id, callee_id, args, depth))
+            instance_id = instances[key]
+            return {
+                **relation,
+                "callee_id": instance_id,
+                "callee_name": instance_nodes[instance_id]["name"],
+                "type_args": None,
+            }
+
+        def expand():
+            """为新建的实例复制泛型函数中的调用"""
+            while pending:
+                instance_id, generic_id, args, depth = pending.pop()
+                mapping = dict(
+                    zip(self._type_params(generic_id), _split_type_args(args))
+                )
+                for relation in outgoing.get(generic_id, []):
+                    relation = {
+                        **relation,
+                        "caller_id": instance_id,
+                        "caller_name": instance_nodes[instance_id]["name"],
+                    }
+                    if relation.get("type_args"):
+                        relation["type_args"] = _substitute(
+                            relation["type_args"], mapping
+                        )
+                    relations.append(instantiate(relation, depth + 1))
+
+        for caller_id, caller_relations in outgoing.items():
+            if self._type_params(caller_id):
+                continue
+            for relation in caller_relations:
+                relations.append(instantiate(relation, 1))
+            expand()
+
+        # 保留的泛型函数中的调用可能再使其他泛型函数被保留，直到不再变化
+        generated = {generic_id for generic_id, _ in instances}
+        processed = set()
+        while True:
+            kept = {
+                node_id
+                for node_id in self.nodes
+                if self._type_params(node_id)
+                and (node_id in uninstantiated or node_id not in generated)
+            } - processed
+            if not kept:
+                break
+            for generic_id in sorted(kept):
+                processed.add(generic_id)
+                params = self._type_params(generic_id)
+                for relation in outgoing.get(generic_id, []):
+                    # 实参中含有所在泛型函数的类型参数时无法实例化
+                    args = relation.get("type_args") or ""
+                    if any(
+                        re.search(rf"\b{re.escape(param)}\b", args) for param in params
+                    ):
+                        if self._type_params(relation["callee_id"]):
+                            uninstantiated.add(relation["callee_id"])
+                        relations.append(relation)
+                    else:
+                        relations.append(instantiate(relation, 1))
+                expand()
+
+        graph = CallGraph()
+        graph.root = self.root
+        for node_id, node in self.nodes.items():
+            if node_id in processed or node_id not in generated:
+                graph.add_node(node)
+        for node in instance_nodes.values():
+            graph.add_node(node)
+        for relation in relations:
+            graph.add_relation(relation)
+        return graph
+
+    def _type_params(self, node_id: str) -> List[str]:
+        node = self.nodes.get(node_id)
+        return ((node or {}).get("extras") or {}).get("type_params") or []
+
+    def node_kind(self, node_id: str) -> str:
+        """
+        节点类别：closure（闭包）/ method（方法）/ function（普通函数），
+        聚合节点为 module / component / elided（见 GROUP_KINDS）
+        """
+        node = self.nodes[node_id]
+        if node.get("kind") in GROUP_KINDS:
+            return node["kind"]
+        extras = node.get("extras") or {}
+        if node.get("kind") == "closure" or extras.get("closure"):
+            return "closure"
+        if extras.get("owner"):
+            return "method"
+        return "function"
+
+    def qualified_name(self, node_id: str) -> str:
+        """
+        完全限定名：模块路径 + 所属类型 + 函数名
+
+        Rust/C/C++ 使用 :: 分隔（Rust 去掉 src/ 前缀以及 lib/main/mod 文件名），
+        其他语言使用 . 分隔。识别出 Cargo crate 的 Rust 函数以 crate 名开头；
+        Java 方法使用包名代替模块路径（包名.外部类.类.方法）；
+        闭包为所在函数的限定名加 ::{closure#N}
+        """
+        node = self.nodes[node_id]
+        if node.get("kind") in GROUP_KINDS:
+            return node["name"]
+        language = node.get("language")
+        separator = "::" if language in ("rust", "c", "cpp") else "."
+        extras = node.get("extras") or {}
+
+        parent_id = extras.get("parent")
+        if extras.get("closure") and parent_id in self.nodes:
+            parent_name = self.nodes[parent_id]["name"]
+            suffix = node["name"][len(parent_name) :].lstrip(":.")
+            return f"{self.qualified_name(parent_id)}{separator}{suffix}"
+
+        path = function_path(node) if language == "rust" else None
+        if path:
+            return path
+
+        if language == "java" and "package" in extras:
+            package = extras["package"]
+            parts = package.split(".") if package else []
+            parts.extend(extras.get("enclosing") or [])
+        else:
+            parts = [part for part in self.module_of(node_id).split("/") if part]
+        if language == "rust":
+            if parts and parts[0] == "src":
+                parts = parts[1:]
+            if parts and parts[-1] in ("lib", "main", "mod"):
+                parts = parts[:-1]
+
+        name = node["name"]
+        owner = extras.get("owner")
+        # C++ 类外定义（A::b）和 Go 方法（T.m）的名称中已包含所属类型
+        if owner and not name.startswith(f"{owner}::") and not name.startswith(
+            f"{owner}."
+        ):
+            parts.append(owner)
+        parts.append(name)
+        return separator.join(parts)
+
+    def stable_id(self, node_id: str) -> str:
+        """
+        跨分析保持不变的节点 ID：完全限定名、签名和条件编译条件的哈希
+
+        与位置无关：函数在文件中移动、其他代码增删时不变，重命名、移动到其他
+        模块或修改签名时改变。分析时计算并保存在 extras 的 stable_id 中；旧
+        数据库中没有保存时在整个图上计算（见 assign_stable_ids）
+        """
+        stable_id = (self.nodes[node_id].get("extras") or {}).get("stable_id")
+        if stable_id:
+            return stable_id
+        if self._stable_ids is None:
+            self._stable_ids = _stable_ids(self)
+        return self._stable_ids[node_id]
+
+    def with_stable_ids(self) -> "CallGraph":
+        """把节点 ID 换成稳定 ID 的调用图（导出给外部工具时使用）"""
+        mapping = {node_id: self.stable_id(node_id) for node_id in self.nodes}
+        graph = CallGraph()
+        graph.root = self.root or self.project_root()
+        for node_id, node in self.nodes.items():
+            renamed = dict(node, id=mapping[node_id])
+            extras = dict(node.get("extras") or {})
+            for key in ("parent", "members"):
+                if isinstance(extras.get(key), str):
+                    extras[key] = mapping.get(extras[key], extras[key])
+                elif isinstance(extras.get(key), list):
+                    extras[key] = [mapping.get(m, m) for m in extras[key]]
+            renamed["extras"] = extras
+            graph.add_node(renamed)
+        for relation in self.relations:
+            graph.add_relation(
+                dict(
+                    relation,
+                    caller_id=mapping.get(relation["caller_id"], relation["caller_id"]),
+                    callee_id=mapping.get(relation["callee_id"], relation["callee_id"]),
+                )
+            )
+        return graph
+
+    def compile_time(self, node_id: str) -> Optional[str]:
+        """
+        编译期运行的函数的来源：build（构建脚本）/ proc-macro（过程宏 crate），
+        运行时的函数为 None；没有 Cargo 信息时按文件名识别 build.rs
+        """
+        node = self.nodes[node_id]
+        compile_time = (node.get("extras") or {}).get("compile_time")
+        if compile_time:
+            return compile_time
+        if (
+            node.get("language") == "rust"
+            and os.path.basename(node.get("file") or "") == "build.rs"
+        ):
+            return "build"
+        return None
+
+    def phase(self, node_id: str) -> str:
+        """函数运行的阶段（见 PHASES）"""
+        return "compile-time" if self.compile_time(node_id) else "runtime"
+
+    def is_const(self, node_id: str) -> bool:
+        """是否为 const fn（可以在编译期求值，也可以在运行时调用）"""
+        return bool((self.nodes[node_id].get("extras") or {}).get("const"))
+
+    def visibility(self, node_id: str) -> str:
+        """节点可见性；旧数据库没有记录时根据 is_exported 推断"""
+        node = self.nodes[node_id]
+        visibility = (node.get("extras") or {}).get("visibility")
+        if visibility:
+            return visibility
+        return "public" if node.get("is_exported") else "private"
+
+    def line_count(self, node_id: str) -> Optional[int]:
+        """函数定义的行数（含签名和结尾的括号），没有行号时（如模块级视图）为 None"""
+        node = self.nodes[node_id]
+        start_line = node.get("start_line")
+        end_line = node.get("end_line")
+        if start_line is None or end_line is None:
+            return None
+        return end_line - start_line + 1
+
+    def doc(self, node_id: str) -> Optional[str]:
+        """文档摘要：文档注释或 docstring 的第一行，没有时为 None"""
+        return (self.nodes[node_id].get("extras") or {}).get("doc")
+
+    def attributes(self, node_id: str) -> List[str]:
+        """
+        函数上值得注意的属性/注解/装饰器（原样保留参数，不含 #[] 或 @），
+        如 inline、deprecated(since = "1.2")、tokio::main；
+        不含 METADATA_IGNORED_ATTRIBUTES 中的属性
+        """
+        return [
+            attribute
+            for attribute in (self.nodes[node_id].get("extras") or {}).get(
+                "attributes"
+            )
+            or []
+            if attribute_name(attribute) not in METADATA_IGNORED_ATTRIBUTES
+        ]
+
+    def deprecation(self, node_id: str) -> Optional[Dict[str, Optional[str]]]:
+        """函数的弃用信息 {"since", "note"}（见模块函数 deprecation），未弃用时为 None"""
+        for attribute in (self.nodes[node_id].get("extras") or {}).get(
+            "attributes"
+        ) or []:
+            info = deprecation(attribute)
+            if info is not None:
+                return info
+        return None
+
+
+def attribute_name(attribute: str) -> str:
+    """属性名去掉参数部分：tokio::main(flavor = "..") -> tokio::main"""
+    return attribute.split("(", 1)[0].split("=", 1)[0].strip()
+
+
+def deprecation(attribute: str) -> Optional[Dict[str, Optional[str]]]:
+    """
+    弃用属性的起始版本和说明 {"since", "note"}（没有时为 None），不是弃用属性时
+    返回 None
+
+    支持 Rust 的 deprecated、deprecated = "..."、deprecated(since = "..",
+    note = "..")，Java 的 @Deprecated(since = "..")，以及 Python 的
+    @deprecated("...")（warnings、typing_extensions、deprecated 等包）
+    """
+    name = attribute_name(attribute)
+    if re.split(r"::|\.", name)[-1].lower() != "deprecated":
+        return None
+    info: Dict[str, Optional[str]] = {"since": None, "note": None}
+    for match in DEPRECATION_ARGUMENT.finditer(attribute[len(name) :]):
+        key = (match.group(1) or "").lower()
+        value = match.group(2) if match.group(2) is not None else match.group(3)
+        value = re.sub(r"\\(.)", r"\1", value)
+        if key in DEPRECATION_SINCE_KEYS:
+            info["since"] = info["since"] or value
+        elif key in DEPRECATION_NOTE_KEYS or not key:
+            info["note"] = info["note"] or value
+    return info
+
+
+def _stable_key(graph: CallGraph, node_id: str) -> str:
+    """
+    稳定 ID 的哈希内容：语言、完全限定名、规范化的签名（合并空白，去掉结尾的
+    { 和 Rust 的可见性）与条件编译条件；闭包的签名包含函数体，不参与
+    """
+    node = graph.nodes[node_id]
+    signature = ""
+    if graph.node_kind(node_id) != "closure":
+        signature = " ".join((node.get("signature") or "").split()).rstrip("{ ")
+        signature = SIGNATURE_VISIBILITY.sub("", signature)
+    cfg = combine_cfg(function_cfg(node)) or ""
+    return "\n".join(
+        [node.get("language") or "", graph.qualified_name(node_id), signature, cfg]
+    )
+
+
+def _stable_ids(graph: CallGraph) -> Dict[str, str]:
+    """
+    图中所有节点的稳定 ID
+
+    内容完全相同的函数（如同一文件中以不同方式定义的同名函数）按文件和行号
+    的顺序加上序号区分
+    """
+    groups: Dict[str, List[str]] = defaultdict(list)
+    for node_id in graph.nodes:
+        groups[_stable_key(graph, node_id)].append(node_id)
+    root = graph.project_root()
+    result = {}
+    for key, node_ids in groups.items():
+        node_ids.sort(
+            key=lambda n: (
+                os.path.relpath(graph.nodes[n]["file"] or "", root),
+                graph.nodes[n].get("start_line") or 0,
+            )
+        )
+        for index, node_id in enumerate(node_ids):
+            content = key if index == 0 else f"{key}\n#{index}"
+            digest = hashlib.sha1(content.encode("utf-8")).hexdigest()
+            result[node_id] = digest[:STABLE_ID_LENGTH]
+    return result
+
+
+def assign_stable_ids(functions: List[Dict[str, Any]], root: str):
+    """
+    为分析得到的函数计算稳定 ID，保存到 extras 的 stable_id 中
+
+    需要在标注 crate 和模块路径（CargoWorkspace.annotate）之后调用；
+    root 为项目根目录（非 Rust 语言的模块路径相对于它）
+    """
+    graph = CallGraph()
+    graph.root = root
+    for func in functions:
+        func.setdefault("extras", {}).pop("stable_id", None)
+        graph.add_node(func)
+    for node_id, stable_id in _stable_ids(graph).items():
+        graph.nodes[node_id]["extras"]["stable_id"] = stable_id
+
+
+def _split_type_args(args: str) -> List[str]:
+    """按顶层逗号拆分类型实参（"HashMap<K, V>, u8" -> ["HashMap<K, V>", "u8"]）"""
+    parts = []
+    depth = 0
+    current = ""
+    for char in args:
+        if char in "<([":
+            depth += 1
+        elif char in ">)]":
+            depth -= 1
+        if char == "," and depth == 0:
+            parts.append(current.strip())
+            current = ""
+        else:
+            current += char
+    if current.strip():
+        parts.append(current.strip())
+    return parts
+
+
+def _substitute(text: str, mapping: Dict[str, str]) -> str:
+    """把类型表达式中的类型参数替换为实参"""
+    if not mapping:
+        return text
+    return re.sub(r"\b\w+\b", lambda m: mapping.get(m.group(0), m.group(0)), text)
@@ -0,0 +1,147 @@
+"""
+调用图历史趋势
+按时间间隔从 git 历史中取出项目的快照并逐个分析，计算每个快照的规模和结构
+指标（函数数、调用边数、最大扇入、递归环数、模块依赖数），输出为趋势 CSV，
+用于观察架构随时间的劣化
+"""
+
+import csv
+import datetime
+import io
+import shutil
+import tempfile
+from typing import Any, Callable, Dict, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import find_recursion_cycles
+    from .diff import _git, export_revision, module_dependencies
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import find_recursion_cycles
+    from diff import _git, export_revision, module_dependencies
+    from graph import CallGraph
+
+# 快照间隔：每个提交、每天、每周（ISO 周）、每月的最后一个提交
+HISTORY_STEPS = ("commit", "daily", "weekly", "monthly")
+
+# 趋势 CSV 的列
+HISTORY_FIELDS = [
+    "revision",
+    "date",
+    "functions",
+    "edges",
+    "max_fan_in",
+    "max_fan_in_function",
+    "cycles",
+    "module_dependencies",
+]
+
+
+def _period(revision: str, date: str, step: str) -> str:
+    """提交所属的时间段（同一时间段中只保留最后一个提交）"""
+    if step == "commit":
+        return revision
+    if step == "daily":
+        return date[:10]
+    if step == "weekly":
+        year, week, _ = datetime.date.fromisoformat(date[:10]).isocalendar()
+        return f"{year}-W{week:02d}"
+    return date[:7]
+
+
+def history_revisions(
+    repo: str, since: str, until: str = "HEAD", step: str = "monthly"
+) -> List[Dict[str, str]]:
+    """
+    要分析的快照 [{"revision", "date"}]，从旧到新
+
+    第一个快照为 since 本身，之后沿 until 的第一父提交链，每个时间段
+    （见 HISTORY_STEPS）取最后一个提交；日期为提交日期（ISO 8601）
+    """
+    if step not in HISTORY_STEPS:
+        raise ValueError(
+            f"未知的快照间隔: {step}（可选: {', '.join(HISTORY_STEPS)}）"
+        )
+    first = _git(repo, "log", "-1", "--format=%H %cI", since, "--")
+    lines = _git(
+        repo,
+        "log",
+        "--first-parent",
+        "--reverse",
+        "--format=%H %cI",
+        f"{since}..{until}",
+        "--",
+    ).splitlines()
+
+    periods: Dict[str, Dict[str, str]] = {}
+    for line in lines:
+        revision, date = line.split(" ", 1)
+        periods[_period(revision, date, step)] = {"revision": revision, "date": date}
+    revision, date = first.split(" ", 1)
+    snapshots = [{"revision": revision, "date": date}]
+    snapshots.extend(periods.values())
+    return snapshots
+
+
+def snapshot_metrics(graph: CallGraph) -> Dict[str, Any]:
+    """一个快照的指标（HISTORY_FIELDS 中除 revision 和 date 之外的列）"""
+    max_fan_in, busiest = 0, None
+    for node_id in graph.nodes:
+        fan_in = len(graph.predecessors(node_id))
+        if fan_in > max_fan_in or (
+            fan_in == max_fan_in
+            and busiest is not None
+            and graph.qualified_name(node_id) < graph.qualified_name(busiest)
+        ):
+            max_fan_in, busiest = fan_in, node_id
+    return {
+        "functions": len(graph.nodes),
+        "edges": sum(1 for _ in graph.edges()),
+        "max_fan_in": max_fan_in,
+        "max_fan_in_function": graph.qualified_name(busiest) if busiest else "",
+        "cycles": len(find_recursion_cycles(graph)),
+        "module_dependencies": len(module_dependencies(graph)),
+    }
+
+
+def analyze_history(
+    repo: str,
+    snapshots: List[Dict[str, str]],
+    analyze_project: Callable[[str], CallGraph],
+    progress: Optional[Callable[[int, Dict[str, str]], None]] = None,
+) -> List[Dict[str, Any]]:
+    """
+    逐个导出并分析快照，返回趋势表的行（列见 HISTORY_FIELDS）
+
+    analyze_project 分析导出后的项目目录并返回调用图；progress 在分析每个
+    快照之前调用（参数为序号和快照）
+    """
+    rows = []
+    for index, snapshot in enumerate(snapshots, 1):
+        if progress:
+            progress(index, snapshot)
+        workdir = tempfile.mkdtemp(prefix="callgraph-history-")
+        try:
+            project = export_revision(repo, snapshot["revision"], workdir)
+            graph = analyze_project(project)
+        finally:
+            shutil.rmtree(workdir, ignore_errors=True)
+        rows.append(
+            {
+                "revision": snapshot["revision"][:12],
+                "date": snapshot["date"],
+                **snapshot_metrics(graph),
+            }
+        )
+    return rows
+
+
+def history_to_csv(rows: List[Dict[str, Any]]) -> str:
+    """趋势 CSV（带表头）"""
+    output = io.StringIO()
+    writer = csv.DictWriter(output, fieldnames=HISTORY_FIELDS, lineterminator="\n")
+    writer.writeheader()
+    for row in rows:
+        writer.writerow(row)
+    return output.getvalue()
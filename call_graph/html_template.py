@@ -0,0 +1,510 @@
+"""
+交互式 HTML 导出模板
+单文件页面，不依赖任何外部脚本或样式，可离线打开
+
+页面中的 __TITLE__ 和 __GRAPH_DATA__ 由 exporters.export_html 替换
+"""
+
+HTML_TEMPLATE = """<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<title>__TITLE__</title>
+<style>
+  html, body {
+    margin: 0; height: 100%; font-family: Arial, sans-serif; font-size: 12px;
+  }
+  body { display: flex; }
+  #sidebar {
+    width: 280px; padding: 10px; box-sizing: border-box; overflow: auto;
+    border-right: 1px solid #ccc; background: #fafafa;
+  }
+  #sidebar h1 { font-size: 15px; margin: 0 0 8px; }
+  #search { width: 100%; box-sizing: border-box; padding: 4px; }
+  #info { margin: 8px 0; word-break: break-all; }
+  #info .label { color: #777; }
+  #modules label { display: block; white-space: nowrap; }
+  .swatch { display: inline-block; width: 10px; height: 10px; margin-right: 4px; }
+  .help { color: #777; line-height: 1.5; }
+  #canvas { flex: 1; position: relative; }
+  svg { width: 100%; height: 100%; cursor: grab; display: block; }
+  svg.panning { cursor: grabbing; }
+  .node { cursor: pointer; }
+  .node rect { stroke: #555; stroke-width: 1; }
+  .node text { pointer-events: none; font-size: 11px; }
+  .node.module rect { stroke-width: 2; stroke-dasharray: 5 2; }
+  .node.match rect { stroke: #e6550d; stroke-width: 3; }
+  .node.selected rect { stroke: #000; stroke-width: 3; }
+  .edge { fill: none; stroke: #999; stroke-width: 1; marker-end: url(#arrow); }
+  .edge.dynamic { stroke-dasharray: 6 3; }
+  .edge.closure { stroke-dasharray: 2 2; }
+  .edge.macro { stroke: #bbb; }
+  .edge.indirect { stroke: steelblue; stroke-dasharray: 6 3; }
+  .edge.operator { stroke: darkorange; }
+  .edge.drop { stroke: purple; stroke-dasharray: 2 2; }
+  .edge.annotated { stroke: forestgreen; stroke-width: 2.5; }
+  .edge.ffi { stroke: crimson; stroke-width: 2.5; }
+  .edge.spawn { stroke: darkcyan; stroke-width: 2.5; stroke-dasharray: 6 3; }
+  .edge.signature { stroke: #bbb; stroke-dasharray: 6 3; }
+  .edge.active { stroke: #3182bd; stroke-width: 2; }
+  .dim { opacity: 0.12; }
+</style>
+</head>
+<body>
+<div id="sidebar">
+  <h1>__TITLE__</h1>
+  <input id="search" placeholder="搜索函数（回车定位）">
+  <div id="info"></div>
+  <p>
+    <button id="collapse-all">全部折叠</button>
+    <button id="expand-all">全部展开</button>
+    <button id="fit">适应窗口</button>
+  </p>
+  <div id="modules"></div>
+  <p class="help">
+    滚轮缩放，拖动空白处平移。<br>
+    单击函数高亮经过它的所有调用路径，单击空白处取消。<br>
+    双击函数折叠所在模块，双击模块节点展开。<br>
+    虚线：动态分发；点线：闭包；灰色：宏展开。
+  </p>
+</div>
+<div id="canvas">
+  <svg id="svg">
+    <defs>
+      <marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5"
+              markerWidth="6" markerHeight="6" orient="auto">
+        <path d="M0,0L10,5L0,10z" fill="#999"></path>
+      </marker>
+    </defs>
+    <g id="viewport"></g>
+  </svg>
+</div>
+<script id="graph-data" type="application/json">__GRAPH_DATA__</script>
+<script>
+(function () {
+  "use strict";
+
+  var data = JSON.parse(document.getElementById("graph-data").textContent);
+  var SVG_NS = "http://www.w3.org/2000/svg";
+  var COLUMN_GAP = 80, ROW_HEIGHT = 34, NODE_HEIGHT = 24, CHAR_WIDTH = 7;
+  // 节点数量超过该值时初始折叠所有模块
+  var COLLAPSE_THRESHOLD = 500;
+  var COLORS = [
+    "#a6cee3", "#b2df8a", "#fb9a99", "#fdbf6f", "#cab2d6", "#ffff99",
+    "#8dd3c7", "#bebada", "#fb8072", "#80b1d3", "#fccde5", "#d9d9d9"
+  ];
+
+  var svg = document.getElementById("svg");
+  var viewport = document.getElementById("viewport");
+  var searchInput = document.getElementById("search");
+  var info = document.getElementById("info");
+
+  var nodesById = {};
+  var modules = {};
+  data.nodes.forEach(function (node) {
+    nodesById[node.id] = node;
+    (modules[node.module] = modules[node.module] || []).push(node.id);
+  });
+  var moduleNames = Object.keys(modules).sort();
+  var moduleColor = {};
+  moduleNames.forEach(function (name, i) {
+    moduleColor[name] = COLORS[i % COLORS.length];
+  });
+
+  var collapsed = {};
+  if (data.nodes.length > COLLAPSE_THRESHOLD) {
+    moduleNames.forEach(function (name) { collapsed[name] = true; });
+  }
+  var selected = null;
+  var view = null;
+  var transform = { x: 20, y: 20, k: 1 };
+
+  function element(tag, attrs, parent) {
+    var el = document.createElementNS(SVG_NS, tag);
+    Object.keys(attrs || {}).forEach(function (key) {
+      el.setAttribute(key, attrs[key]);
+    });
+    if (parent) { parent.appendChild(el); }
+    return el;
+  }
+
+  function compare(a, b) { return a < b ? -1 : a > b ? 1 : 0; }
+
+  function viewKey(nodeId) {
+    var module = nodesById[nodeId].module;
+    return collapsed[module] ? "module:" + module : nodeId;
+  }
+
+  // 当前显示的图：折叠的模块合并为一个节点，边按端点合并并累计调用次数
+  function buildView() {
+    var nodes = {}, order = [];
+    data.nodes.forEach(function (node) {
+      var key = viewKey(node.id);
+      if (nodes[key]) { return; }
+      if (collapsed[node.module]) {
+        nodes[key] = {
+          key: key, module: node.module, isModule: true,
+          label: node.module + " (" + modules[node.module].length + ")",
+          title: "模块 " + node.module + "\\n" + modules[node.module].length + " 个函数",
+          search: node.module.toLowerCase()
+        };
+      } else {
+        nodes[key] = {
+          key: key, module: node.module, isModule: false, label: node.name,
+          title: node.qualified_name + "\\n" + node.file +
+            (node.line ? ":" + node.line : ""),
+          search: (node.name + " " + node.qualified_name).toLowerCase(),
+          source: node
+        };
+      }
+      order.push(key);
+    });
+
+    var edgeMap = {}, edges = [];
+    data.edges.forEach(function (edge) {
+      var source = viewKey(edge.caller), target = viewKey(edge.callee);
+      if (source === target) { return; }
+      var key = source + " -> " + target;
+      var merged = edgeMap[key];
+      if (!merged) {
+        merged = edgeMap[key] = {
+          source: source, target: target, kind: edge.kind, count: 0
+        };
+        edges.push(merged);
+      } else if (merged.kind !== edge.kind) {
+        merged.kind = "static";
+      }
+      merged.count += edge.count;
+    });
+
+    var out = {}, incoming = {};
+    order.forEach(function (key) { out[key] = []; incoming[key] = []; });
+    edges.forEach(function (edge) {
+      out[edge.source].push(edge.target);
+      incoming[edge.target].push(edge.source);
+    });
+    return { nodes: nodes, order: order, edges: edges, out: out, incoming: incoming };
+  }
+
+  // 分层布局：DFS 去掉回边后按最长路径分层，层内按邻居的平均位置排序减少交叉
+  function layout(v) {
+    var state = {}, postorder = [], backEdges = {};
+    v.order.forEach(function (start) {
+      if (state[start]) { return; }
+      state[start] = 1;
+      var stack = [[start, 0]];
+      while (stack.length) {
+        var top = stack[stack.length - 1], node = top[0];
+        if (top[1] < v.out[node].length) {
+          var next = v.out[node][top[1]++];
+          if (!state[next]) {
+            state[next] = 1;
+            stack.push([next, 0]);
+          } else if (state[next] === 1) {
+            backEdges[node + " -> " + next] = true;
+          }
+        } else {
+          state[node] = 2;
+          postorder.push(node);
+          stack.pop();
+        }
+      }
+    });
+
+    var layer = {};
+    postorder.reverse().forEach(function (key) {
+      layer[key] = layer[key] || 0;
+      v.out[key].forEach(function (target) {
+        if (!backEdges[key + " -> " + target]) {
+          layer[target] = Math.max(layer[target] || 0, layer[key] + 1);
+        }
+      });
+    });
+
+    var columns = [], position = {};
+    v.order.forEach(function (key) {
+      (columns[layer[key]] = columns[layer[key]] || []).push(key);
+    });
+    columns.forEach(function (column) {
+      column.sort(function (a, b) {
+        return compare(v.nodes[a].module, v.nodes[b].module) ||
+          compare(v.nodes[a].label, v.nodes[b].label);
+      });
+      column.forEach(function (key, i) { position[key] = i; });
+    });
+    for (var sweep = 0; sweep < 4; sweep++) {
+      columns.forEach(function (column) {
+        var barycenter = {};
+        column.forEach(function (key) {
+          var neighbors = sweep % 2 ? v.out[key] : v.incoming[key];
+          barycenter[key] = neighbors.length
+            ? neighbors.reduce(function (sum, n) { return sum + position[n]; }, 0) /
+              neighbors.length
+            : position[key];
+        });
+        column.sort(function (a, b) { return barycenter[a] - barycenter[b]; });
+        column.forEach(function (key, i) { position[key] = i; });
+      });
+    }
+
+    var x = 0;
+    columns.forEach(function (column) {
+      var width = 0;
+      column.forEach(function (key, i) {
+        var node = v.nodes[key];
+        node.width = node.label.length * CHAR_WIDTH + 16;
+        node.x = x;
+        node.y = i * ROW_HEIGHT;
+        width = Math.max(width, node.width);
+      });
+      x += width + COLUMN_GAP;
+    });
+  }
+
+  function render() {
+    view = buildView();
+    layout(view);
+    while (viewport.firstChild) { viewport.removeChild(viewport.firstChild); }
+    var edgeLayer = element("g", {}, viewport);
+    var nodeLayer = element("g", {}, viewport);
+
+    view.edges.forEach(function (edge) {
+      var s = view.nodes[edge.source], t = view.nodes[edge.target];
+      var x1 = s.x + s.width, y1 = s.y + NODE_HEIGHT / 2;
+      var x2 = t.x, y2 = t.y + NODE_HEIGHT / 2;
+      var dx = Math.max(40, Math.abs(x2 - x1) / 2);
+      edge.element = element("path", {
+        "class": "edge " + edge.kind,
+        d: "M" + x1 + "," + y1 + " C" + (x1 + dx) + "," + y1 + " " +
+          (x2 - dx) + "," + y2 + " " + x2 + "," + y2
+      }, edgeLayer);
+      element("title", {}, edge.element).textContent =
+        s.label + " -> " + t.label + "（" + edge.count + " 处调用）";
+    });
+
+    view.order.forEach(function (key) {
+      var node = view.nodes[key];
+      var group = element("g", {
+        "class": "node" + (node.isModule ? " module" : ""),
+        transform: "translate(" + node.x + "," + node.y + ")"
+      }, nodeLayer);
+      element("rect", {
+        width: node.width, height: NODE_HEIGHT, rx: 4,
+        fill: moduleColor[node.module]
+      }, group);
+      element("text", { x: 8, y: 16 }, group).textContent = node.label;
+      element("title", {}, group).textContent = node.title;
+      group.addEventListener("click", function (event) {
+        event.stopPropagation();
+        select(key);
+      });
+      group.addEventListener("dblclick", function (event) {
+        event.stopPropagation();
+        collapsed[node.module] = !node.isModule;
+        selected = node.isModule ? null : "module:" + node.module;
+        render();
+      });
+      node.element = group;
+    });
+
+    updateHighlight();
+    renderModuleList();
+  }
+
+  function reach(start, neighbors) {
+    var visited = {}, queue = [start];
+    visited[start] = true;
+    while (queue.length) {
+      neighbors[queue.shift()].forEach(function (next) {
+        if (!visited[next]) {
+          visited[next] = true;
+          queue.push(next);
+        }
+      });
+    }
+    return visited;
+  }
+
+  // 选中节点时只保留经过它的调用路径：它的所有上游调用者和下游被调用者
+  function updateHighlight() {
+    var query = searchInput.value.trim().toLowerCase();
+    var upstream = null, downstream = null;
+    if (selected && view.nodes[selected]) {
+      upstream = reach(selected, view.incoming);
+      downstream = reach(selected, view.out);
+    }
+    view.order.forEach(function (key) {
+      var node = view.nodes[key], classes = node.element.classList;
+      classes.toggle("selected", key === selected);
+      classes.toggle("match", !!query && node.search.indexOf(query) >= 0);
+      classes.toggle("dim", !!upstream && !upstream[key] && !downstream[key]);
+    });
+    view.edges.forEach(function (edge) {
+      var active = !!upstream && (
+        (upstream[edge.source] && upstream[edge.target]) ||
+        (downstream[edge.source] && downstream[edge.target]));
+      edge.element.classList.toggle("active", active);
+      edge.element.classList.toggle("dim", !!upstream && !active);
+    });
+    renderInfo(upstream, downstream);
+  }
+
+  function renderInfo(upstream, downstream) {
+    if (!upstream) {
+      info.textContent = data.nodes.length + " 个函数，" + data.edges.length +
+        " 条调用边，" + moduleNames.length + " 个模块";
+      return;
+    }
+    var node = view.nodes[selected];
+    info.innerHTML = "";
+    var lines = [["名称", node.title.split("\\n")[0]]];
+    if (!node.isModule) {
+      lines.push(["位置", node.title.split("\\n")[1]]);
+      // 函数元数据：签名、行数、文档摘要和属性
+      var source = node.source;
+      if (source.signature) { lines.push(["签名", source.signature]); }
+      if (source.loc) { lines.push(["行数", source.loc]); }
+      if (source.doc) { lines.push(["说明", source.doc]); }
+      if (source.attributes.length) {
+        lines.push(["属性", source.attributes.join(", ")]);
+      }
+    }
+    lines.push(["直接调用者", view.incoming[selected].length]);
+    lines.push(["直接被调用者", view.out[selected].length]);
+    lines.push(["上游（传递）", Object.keys(upstream).length - 1]);
+    lines.push(["下游（传递）", Object.keys(downstream).length - 1]);
+    lines.forEach(function (line) {
+      var div = document.createElement("div");
+      var label = document.createElement("span");
+      label.className = "label";
+      label.textContent = line[0] + ": ";
+      div.appendChild(label);
+      div.appendChild(document.createTextNode(String(line[1])));
+      info.appendChild(div);
+    });
+  }
+
+  function renderModuleList() {
+    var container = document.getElementById("modules");
+    container.innerHTML = "";
+    moduleNames.forEach(function (name) {
+      var label = document.createElement("label");
+      var checkbox = document.createElement("input");
+      checkbox.type = "checkbox";
+      checkbox.checked = !collapsed[name];
+      checkbox.addEventListener("change", function () {
+        collapsed[name] = !checkbox.checked;
+        render();
+      });
+      var swatch = document.createElement("span");
+      swatch.className = "swatch";
+      swatch.style.background = moduleColor[name];
+      label.appendChild(checkbox);
+      label.appendChild(swatch);
+      label.appendChild(document.createTextNode(
+        name + " (" + modules[name].length + ")"));
+      container.appendChild(label);
+    });
+  }
+
+  function select(key) {
+    selected = key;
+    updateHighlight();
+  }
+
+  function applyTransform() {
+    viewport.setAttribute("transform", "translate(" + transform.x + "," +
+      transform.y + ") scale(" + transform.k + ")");
+  }
+
+  function fit() {
+    var box = viewport.getBBox(), rect = svg.getBoundingClientRect();
+    if (!box.width || !box.height) { return; }
+    transform.k = Math.min(2, Math.min(rect.width / (box.width + 40),
+      rect.height / (box.height + 40)));
+    transform.x = 20 - box.x * transform.k;
+    transform.y = 20 - box.y * transform.k;
+    applyTransform();
+  }
+
+  function centerOn(key) {
+    var node = view.nodes[key], rect = svg.getBoundingClientRect();
+    transform.k = Math.max(transform.k, 1);
+    transform.x = rect.width / 2 - (node.x + node.width / 2) * transform.k;
+    transform.y = rect.height / 2 - (node.y + NODE_HEIGHT / 2) * transform.k;
+    applyTransform();
+  }
+
+  svg.addEventListener("wheel", function (event) {
+    event.preventDefault();
+    var rect = svg.getBoundingClientRect();
+    var mx = event.clientX - rect.left, my = event.clientY - rect.top;
+    var factor = event.deltaY < 0 ? 1.15 : 1 / 1.15;
+    var k = Math.min(8, Math.max(0.05, transform.k * factor));
+    transform.x = mx - (mx - transform.x) * (k / transform.k);
+    transform.y = my - (my - transform.y) * (k / transform.k);
+    transform.k = k;
+    applyTransform();
+  }, { passive: false });
+
+  var drag = null;
+  svg.addEventListener("mousedown", function (event) {
+    drag = { x: event.clientX, y: event.clientY, tx: transform.x, ty: transform.y,
+      moved: false };
+    svg.classList.add("panning");
+  });
+  window.addEventListener("mousemove", function (event) {
+    if (!drag) { return; }
+    var dx = event.clientX - drag.x, dy = event.clientY - drag.y;
+    drag.moved = drag.moved || Math.abs(dx) + Math.abs(dy) > 3;
+    transform.x = drag.tx + dx;
+    transform.y = drag.ty + dy;
+    applyTransform();
+  });
+  window.addEventListener("mouseup", function () {
+    svg.classList.remove("panning");
+    setTimeout(function () { drag = null; }, 0);
+  });
+  svg.addEventListener("click", function () {
+    if (drag && drag.moved) { return; }
+    select(null);
+  });
+
+  searchInput.addEventListener("input", updateHighlight);
+  searchInput.addEventListener("keydown", function (event) {
+    if (event.key !== "Enter") { return; }
+    var query = searchInput.value.trim().toLowerCase();
+    if (!query) { return; }
+    var match = data.nodes.filter(function (node) {
+      return (node.name + " " + node.qualified_name).toLowerCase().indexOf(query) >= 0;
+    })[0];
+    if (!match) { return; }
+    if (collapsed[match.module]) {
+      collapsed[match.module] = false;
+      render();
+    }
+    select(match.id);
+    centerOn(match.id);
+  });
+
+  document.getElementById("collapse-all").addEventListener("click", function () {
+    moduleNames.forEach(function (name) { collapsed[name] = true; });
+    selected = null;
+    render();
+    fit();
+  });
+  document.getElementById("expand-all").addEventListener("click", function () {
+    collapsed = {};
+    selected = null;
+    render();
+    fit();
+  });
+  document.getElementById("fit").addEventListener("click", fit);
+
+  render();
+  fit();
+})();
+</script>
+</body>
+</html>
+"""
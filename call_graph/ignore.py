@@ -0,0 +1,211 @@
+"""
+忽略文件
+扫描源文件时遵循 .gitignore、.ignore 和 .callgraphignore 中的规则（语义同
+ripgrep 使用的 ignore crate），不把 target/、node_modules/、生成的代码和
+vendored 的代码计入分析
+
+- 每个目录中的忽略文件作用于该目录及其子目录，子目录中的规则优先于上层目录
+- 同一目录中优先级从低到高为 .gitignore、.ignore、.callgraphignore；
+  同一文件中靠后的规则优先
+- .gitignore 和 .git/info/exclude 只在 git 仓库中生效；在仓库中时也读取
+  被分析目录之上、直到仓库根目录的各级忽略文件
+- 被忽略的目录不再进入，其中的文件不能再用 ! 规则重新包含
+"""
+
+import os
+import re
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+# 按优先级从低到高排列
+IGNORE_FILES = (".gitignore", ".ignore", ".callgraphignore")
+
+# 只在 git 仓库中生效的忽略文件
+GIT_IGNORE_FILES = {".gitignore"}
+
+
+def _glob_to_regex(segment: str) -> str:
+    """把路径中的一段（不含 /）的通配符转换为正则表达式"""
+    out = []
+    i = 0
+    while i < len(segment):
+        char = segment[i]
+        if char == "*":
+            while i + 1 < len(segment) and segment[i + 1] == "*":
+                i += 1
+            out.append("[^/]*")
+        elif char == "?":
+            out.append("[^/]")
+        elif char == "[":
+            end = segment.find("]", i + 2 if segment[i + 1 : i + 2] in "!^" else i + 1)
+            if end < 0:
+                out.append(re.escape(char))
+            else:
+                body = segment[i + 1 : end]
+                if body[:1] in ("!", "^"):
+                    body = "^" + body[1:]
+                out.append(f"[{body.replace(chr(92), chr(92) * 2)}]")
+                i = end
+        elif char == "\\" and i + 1 < len(segment):
+            i += 1
+            out.append(re.escape(segment[i]))
+        else:
+            out.append(re.escape(char))
+        i += 1
+    return "".join(out)
+
+
+class IgnorePattern:
+    """
+    忽略文件中的一条规则
+
+    - 以 ! 开头的规则重新包含之前被忽略的路径
+    - 以 / 结尾的规则只匹配目录
+    - 开头或中间含 / 的规则相对于忽略文件所在的目录，否则匹配任意层级的名称
+    - ** 匹配任意层目录
+    """
+
+    def __init__(self, line: str):
+        text = line.rstrip("\n").rstrip("\r")
+        # 末尾未转义的空格被忽略
+        while text.endswith(" ") and not text.endswith("\\ "):
+            text = text[:-1]
+        self.negated = text.startswith("!")
+        if self.negated:
+            text = text[1:]
+        elif text.startswith("\\!") or text.startswith("\\#"):
+            text = text[1:]
+        self.directory_only = text.endswith("/")
+        text = text.rstrip("/")
+        anchored = "/" in text
+        text = text.lstrip("/")
+        if not text:
+            raise ValueError(f"无效的忽略规则: {line!r}")
+
+        segments = text.split("/")
+        parts = []
+        for index, segment in enumerate(segments):
+            last = index == len(segments) - 1
+            if segment == "**":
+                parts.append(".*" if last else "(?:[^/]+/)*")
+                continue
+            parts.append(_glob_to_regex(segment))
+            if not last:
+                parts.append("/")
+        prefix = "" if anchored else "(?:.*/)?"
+        self.regex = re.compile(f"^{prefix}{''.join(parts)}$", re.DOTALL)
+
+    def matches(self, relative_path: str, is_dir: bool) -> bool:
+        if self.directory_only and not is_dir:
+            return False
+        return self.regex.match(relative_path) is not None
+
+
+class IgnoreFile:
+    """一个忽略文件中的规则（按文件中的顺序）"""
+
+    def __init__(self, path: Path):
+        self.path = path
+        self.patterns: List[IgnorePattern] = []
+        try:
+            lines = path.read_text(encoding="utf-8", errors="replace").splitlines()
+        except OSError:
+            lines = []
+        for line in lines:
+            if not line.strip() or line.startswith("#"):
+                continue
+            try:
+                self.patterns.append(IgnorePattern(line))
+            except ValueError:
+                continue
+
+    def match(self, relative_path: str, is_dir: bool) -> Optional[bool]:
+        """True 为忽略，False 为（用 ! 规则）重新包含，None 为没有匹配的规则"""
+        for pattern in reversed(self.patterns):
+            if pattern.matches(relative_path, is_dir):
+                return not pattern.negated
+        return None
+
+
+def _git_root(directory: Path) -> Optional[Path]:
+    """directory 所在的 git 仓库根目录（不在仓库中时为 None）"""
+    for candidate in (directory, *directory.parents):
+        if (candidate / ".git").exists():
+            return candidate
+    return None
+
+
+class IgnoreRules:
+    """
+    扫描 root 下的源文件时适用的忽略规则
+
+    各目录中的忽略文件在第一次用到时读取并缓存
+    """
+
+    def __init__(self, root: str):
+        self.root = Path(root).resolve()
+        self.git_root = _git_root(self.root)
+        self._files: Dict[Path, List[IgnoreFile]] = {}
+        self._chains: Dict[Path, List[Tuple[Path, IgnoreFile]]] = {}
+        # 规则相对于此目录之下的各级目录：在 git 仓库中为仓库根目录
+        self.top = self.git_root or self.root
+        self._extra: List[Tuple[Path, IgnoreFile]] = []
+        if self.git_root:
+            exclude = self.git_root / ".git" / "info" / "exclude"
+            if exclude.is_file():
+                self._extra.append((self.git_root, IgnoreFile(exclude)))
+
+    def _directory_rules(self, directory: Path) -> List[IgnoreFile]:
+        rules = self._files.get(directory)
+        if rules is None:
+            rules = []
+            for name in IGNORE_FILES:
+                if name in GIT_IGNORE_FILES and not self.git_root:
+                    continue
+                path = directory / name
+                if path.is_file():
+                    rules.append(IgnoreFile(path))
+            self._files[directory] = rules
+        return rules
+
+    def _chain(self, directory: Path) -> List[Tuple[Path, IgnoreFile]]:
+        """作用于 directory 中条目的忽略文件，按优先级从低到高"""
+        chain = self._chains.get(directory)
+        if chain is not None:
+            return chain
+        if directory == self.top:
+            chain = list(self._extra)
+        elif self.top in directory.parents:
+            chain = list(self._chain(directory.parent))
+        else:
+            return []
+        for ignore_file in self._directory_rules(directory):
+            chain.append((directory, ignore_file))
+        self._chains[directory] = chain
+        return chain
+
+    def is_ignored(self, path: str, is_dir: bool) -> bool:
+        """
+        path 本身是否被忽略（不检查上层目录，适合逐层扫描时使用）
+        """
+        path = Path(os.path.abspath(path))
+        for base, ignore_file in reversed(self._chain(path.parent)):
+            relative = path.relative_to(base).as_posix()
+            result = ignore_file.match(relative, is_dir)
+            if result is not None:
+                return result
+        return False
+
+    def excludes(self, path: str) -> bool:
+        """文件 path 或 root 与它之间的任一目录是否被忽略"""
+        path = Path(os.path.abspath(path))
+        try:
+            parts = path.relative_to(self.root).parts
+        except ValueError:
+            return False
+        current = self.root
+        for index, part in enumerate(parts):
+            current = current / part
+            if self.is_ignored(str(current), index < len(parts) - 1):
+                return True
+        return False
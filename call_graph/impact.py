@@ -0,0 +1,159 @@
+"""
+变更影响分析
+把 git diff 中修改的行对应到包含它们的函数，找出这些函数的所有（传递）调用者
+和能到达它们的测试，CI 可以据此只运行受影响的测试
+"""
+
+import os
+import re
+from typing import Dict, List, Set
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import entry_point_reason
+    from .diff import _git
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import entry_point_reason
+    from diff import _git
+    from graph import CallGraph
+
+# git diff 的文件头和块头：+++ b/<路径>、@@ -a,b +c,d @@
+DIFF_FILE = re.compile(r"^\+\+\+ (.*)$")
+DIFF_HUNK = re.compile(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,(\d+))? @@")
+
+# git 对带特殊字符的路径使用的 C 风格转义
+QUOTED_ESCAPES = {"a": 7, "b": 8, "t": 9, "n": 10, "v": 11, "f": 12, "r": 13}
+
+
+def _diff_path(header: str) -> str:
+    """
+    文件头中的路径（相对仓库根目录）；被删除的文件为 /dev/null
+
+    路径含空格时 git 在末尾加一个 TAB；含引号、反斜杠、控制字符等时整个
+    路径加双引号并按 C 风格转义（八进制转义的是 UTF-8 字节）
+    """
+    if header.endswith("\t"):
+        header = header[:-1]
+    if len(header) >= 2 and header[0] == header[-1] == '"':
+        data = bytearray()
+        text = header[1:-1]
+        i = 0
+        while i < len(text):
+            char = text[i]
+            if char != "\\" or i + 1 == len(text):
+                data.extend(char.encode("utf-8"))
+                i += 1
+            elif text[i + 1] in "01234567":
+                data.append(int(text[i + 1 : i + 4], 8))
+                i += 4
+            else:
+                escape = text[i + 1]
+                data.append(QUOTED_ESCAPES.get(escape, ord(escape)))
+                i += 2
+        header = data.decode("utf-8", "replace")
+    return header[2:] if header.startswith("b/") else header
+
+
+def changed_lines(repo: str, revision: str) -> Dict[str, Set[int]]:
+    """
+    revision 之后修改的行 {文件绝对路径: 新版本中的行号}
+
+    revision 为 git diff 接受的修订版本：单个版本（如 HEAD~1）与当前工作区
+    比较，A..B 比较两个版本。删除的行记为删除位置的前一行，使删除代码的
+    函数也算作修改；被删除的文件不计入
+    """
+    top = _git(repo, "rev-parse", "--show-toplevel")
+    # 非 ASCII 的路径不转义；固定前缀，不受 diff.noprefix 等配置影响
+    output = _git(
+        repo,
+        "-c",
+        "core.quotePath=false",
+        "diff",
+        "--unified=0",
+        "--no-color",
+        "--no-ext-diff",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+        revision,
+        "--",
+    )
+    changes: Dict[str, Set[int]] = {}
+    current = None
+    for line in output.splitlines():
+        match = DIFF_FILE.match(line)
+        if match:
+            path = _diff_path(match.group(1))
+            current = None if path == "/dev/null" else os.path.join(top, path)
+            continue
+        match = DIFF_HUNK.match(line)
+        if match and current:
+            start = int(match.group(1))
+            count = int(match.group(2)) if match.group(2) is not None else 1
+            lines = changes.setdefault(os.path.realpath(current), set())
+            if count:
+                lines.update(range(start, start + count))
+            elif start:
+                lines.add(start)
+    return changes
+
+
+def changed_functions(graph: CallGraph, changes: Dict[str, Set[int]]) -> List[str]:
+    """
+    包含修改行的函数（按文件和行号排序）
+
+    嵌套的函数（闭包、内部函数）中的修改只算作最内层的函数
+    """
+    by_file: Dict[str, List[str]] = {}
+    for node_id, node in graph.nodes.items():
+        if node.get("file") and node.get("start_line") and node.get("end_line"):
+            path = os.path.realpath(node["file"])
+            if path in changes:
+                by_file.setdefault(path, []).append(node_id)
+
+    changed = set()
+    for path, node_ids in by_file.items():
+        for line in changes[path]:
+            containing = [
+                node_id
+                for node_id in node_ids
+                if graph.nodes[node_id]["start_line"]
+                <= line
+                <= graph.nodes[node_id]["end_line"]
+            ]
+            if containing:
+                changed.add(
+                    min(
+                        containing,
+                        key=lambda n: (
+                            graph.nodes[n]["end_line"] - graph.nodes[n]["start_line"]
+                        ),
+                    )
+                )
+    return sorted(
+        changed,
+        key=lambda n: (graph.nodes[n]["file"], graph.nodes[n]["start_line"]),
+    )
+
+
+def impact(graph: CallGraph, changed: List[str]) -> Dict[str, List[str]]:
+    """
+    修改的函数的影响范围
+
+    Returns:
+        changed: 修改的函数；callers: 所有传递调用者（不含 changed）；
+        tests: 能到达修改的函数的测试函数（包括被修改的测试本身）；
+        test_files: 这些测试所在的文件。callers 和 tests 按限定名排序
+    """
+    affected = graph.compact().reachable(changed, reverse=True)
+    callers = sorted(affected - set(changed), key=graph.qualified_name)
+    tests = sorted(
+        (n for n in affected if entry_point_reason(graph, n) == "test"),
+        key=graph.qualified_name,
+    )
+    return {
+        "changed": list(changed),
+        "callers": callers,
+        "tests": tests,
+        "test_files": sorted({graph.nodes[n]["file"] for n in tests}),
+    }
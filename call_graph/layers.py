@@ -0,0 +1,206 @@
+"""
+分层架构推断
+把模块依赖图（见 CallGraph.module_graph）按调用方向排成层：底层模块不依赖
+其他模块，每个模块位于它依赖的最高层模块之上一层。循环依赖中的模块按贪心的
+最小反馈边集排序，逆着层次方向的依赖（反向依赖）即为违反分层的调用
+"""
+
+from typing import Any, Dict, List, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import strongly_connected_components
+    from .exporters import _dot_escape, _mermaid_escape
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import strongly_connected_components
+    from exporters import _dot_escape, _mermaid_escape
+    from graph import CallGraph
+
+
+def _component_order(
+    members: List[str], weights: Dict[Tuple[str, str], int]
+) -> List[str]:
+    """
+    循环依赖中模块的顺序（调用方在前），使逆序的依赖的调用次数尽量少
+
+    Eades 等人的贪心算法：反复把没有出边的模块放到末尾、没有入边的模块放到
+    开头，都没有时把（出边调用次数 - 入边调用次数）最大的模块放到开头
+    """
+    remaining = set(members)
+    start: List[str] = []
+    end: List[str] = []
+    internal = [
+        (a, b, count)
+        for (a, b), count in weights.items()
+        if a in remaining and b in remaining
+    ]
+
+    def degree(node: str, outgoing: bool) -> int:
+        total = 0
+        for a, b, count in internal:
+            source, target = (a, b) if outgoing else (b, a)
+            if source == node and target in remaining:
+                total += count
+        return total
+
+    while remaining:
+        changed = True
+        while changed:
+            changed = False
+            for node in sorted(remaining):
+                if not degree(node, True):
+                    end.append(node)
+                    remaining.discard(node)
+                    changed = True
+            for node in sorted(remaining):
+                if not degree(node, False):
+                    start.append(node)
+                    remaining.discard(node)
+                    changed = True
+        if remaining:
+            node = max(
+                sorted(remaining),
+                key=lambda n: degree(n, True) - degree(n, False),
+            )
+            start.append(node)
+            remaining.discard(node)
+    return start + end[::-1]
+
+
+def infer_layers(graph: CallGraph) -> Dict[str, Any]:
+    """
+    推断模块的分层
+
+    Returns:
+        layers: 每层的模块名 [[模块]]，下标为层号（0 为底层），层内按名称排序；
+        modules: {模块: 层号}；
+        dependencies: 模块依赖 [{"from", "to", "calls"}]（calls 为调用点数量）；
+        back_edges: 反向依赖 [{"from", "to", "calls", "from_layer",
+        "to_layer", "sites": [{"caller", "callee", "file", "line"}]}]，按调用
+        次数升序（最容易消除的在前）；
+        cycles: 包含多个模块的循环依赖 [[模块]]
+    """
+    modules = graph.module_graph()
+    name_of = {node_id: node["name"] for node_id, node in modules.nodes.items()}
+    weights: Dict[Tuple[str, str], int] = {}
+    sites: Dict[Tuple[str, str], List[Dict[str, Any]]] = {}
+    for relation in modules.relations:
+        key = (name_of[relation["caller_id"]], name_of[relation["callee_id"]])
+        weights[key] = weights.get(key, 0) + 1
+        sites.setdefault(key, []).append(
+            {
+                "caller": relation.get("caller_name"),
+                "callee": relation.get("callee_name"),
+                "file": relation.get("caller_file"),
+                "line": relation.get("call_site_line"),
+            }
+        )
+
+    # 分量按逆拓扑序给出，反转后调用方在前
+    order: List[str] = []
+    cycles: List[List[str]] = []
+    for component in reversed(strongly_connected_components(modules)):
+        members = sorted(name_of[node_id] for node_id in component)
+        if len(members) > 1:
+            cycles.append(members)
+            order.extend(_component_order(members, weights))
+        else:
+            order.extend(members)
+    position = {name: i for i, name in enumerate(order)}
+
+    backward: Set[Tuple[str, str]] = {
+        (a, b) for a, b in weights if position[a] > position[b]
+    }
+    layer_of: Dict[str, int] = {}
+    for name in reversed(order):
+        layer_of[name] = max(
+            (
+                layer_of[b] + 1
+                for (a, b) in weights
+                if a == name and (a, b) not in backward
+            ),
+            default=0,
+        )
+
+    depth = max(layer_of.values(), default=-1) + 1
+    layers: List[List[str]] = [[] for _ in range(depth)]
+    for name, layer in layer_of.items():
+        layers[layer].append(name)
+    for names in layers:
+        names.sort()
+
+    back_edges = [
+        {
+            "from": a,
+            "to": b,
+            "calls": weights[(a, b)],
+            "from_layer": layer_of[a],
+            "to_layer": layer_of[b],
+            "sites": sorted(
+                sites[(a, b)], key=lambda s: (s["file"] or "", s["line"] or 0)
+            ),
+        }
+        for a, b in backward
+    ]
+    back_edges.sort(key=lambda e: (e["calls"], e["from"], e["to"]))
+    return {
+        "layers": layers,
+        "modules": dict(sorted(layer_of.items())),
+        "dependencies": [
+            {"from": a, "to": b, "calls": count}
+            for (a, b), count in sorted(weights.items())
+        ],
+        "back_edges": back_edges,
+        "cycles": sorted(cycles),
+    }
+
+
+def layers_to_dot(result: Dict[str, Any]) -> str:
+    """分层图的 Graphviz DOT：每层一个子图，反向依赖为红色虚线"""
+    lines = ["digraph Layers {"]
+    lines.append("  rankdir=TB;")
+    lines.append('  node [shape=box, fontname="Arial", fontsize=9];')
+    lines.append('  graph [fontname="Arial", fontsize=10];')
+    lines.append('  edge [fontname="Arial", fontsize=8];')
+    for layer in range(len(result["layers"]) - 1, -1, -1):
+        lines.append(f"  subgraph cluster_layer{layer} {{")
+        lines.append(f'    label="第 {layer} 层";')
+        lines.append("    rank=same;")
+        for name in result["layers"][layer]:
+            lines.append(f'    "{_dot_escape(name)}";')
+        lines.append("  }")
+    backward = {(e["from"], e["to"]) for e in result["back_edges"]}
+    for edge in result["dependencies"]:
+        attrs = [f'label="{edge["calls"]}"']
+        if (edge["from"], edge["to"]) in backward:
+            attrs.extend(['color="red"', "style=dashed", "constraint=false"])
+        lines.append(
+            f'  "{_dot_escape(edge["from"])}" -> "{_dot_escape(edge["to"])}" '
+            f"[{', '.join(attrs)}];"
+        )
+    lines.append("}")
+    return "\n".join(lines) + "\n"
+
+
+def layers_to_mermaid(result: Dict[str, Any]) -> str:
+    """分层图的 Mermaid 流程图：每层一个 subgraph，反向依赖为红色虚线"""
+    lines = ["graph TD"]
+    ids = {name: f"n{i}" for i, name in enumerate(result["modules"])}
+    for layer in range(len(result["layers"]) - 1, -1, -1):
+        lines.append(f'    subgraph layer{layer} ["第 {layer} 层"]')
+        for name in result["layers"][layer]:
+            lines.append(f'        {ids[name]}["{_mermaid_escape(name)}"]')
+        lines.append("    end")
+    backward = {(e["from"], e["to"]) for e in result["back_edges"]}
+    styled = []
+    for i, edge in enumerate(result["dependencies"]):
+        arrow = "-.->" if (edge["from"], edge["to"]) in backward else "-->"
+        if arrow == "-.->":
+            styled.append(str(i))
+        lines.append(
+            f"    {ids[edge['from']]} {arrow}|{edge['calls']}| {ids[edge['to']]}"
+        )
+    if styled:
+        lines.append(f"    linkStyle {','.join(styled)} stroke:red")
+    return "\n".join(lines) + "\n"
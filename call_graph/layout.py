@@ -0,0 +1,355 @@
+"""
+分层布局
+不依赖 Graphviz 的简化 Sugiyama 布局，供内置的 SVG / PNG 渲染使用：
+反转回边去掉环，按最长路径分层，跨越多层的边插入虚拟节点，
+按重心排序减少交叉，最后计算节点坐标和边的折线
+"""
+
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import GROUP_KINDS, CallGraph
+except ImportError:
+    from graph import GROUP_KINDS, CallGraph
+
+# 节点内边距、同一层相邻节点的间距和相邻两层的间距（像素）
+NODE_PADDING = 8
+NODE_SPACING = 24
+LAYER_SPACING = 56
+# 图像边距（像素）
+MARGIN = 16
+# 自调用的环伸出节点边框的距离（像素），小于同一层相邻节点的间距
+LOOP_SIZE = NODE_SPACING / 2
+
+# 重心排序和坐标调整的迭代次数
+ORDER_SWEEPS = 8
+POSITION_SWEEPS = 4
+
+
+class Box:
+    """节点的矩形区域（中心坐标和尺寸）"""
+
+    def __init__(self, x: float, y: float, width: float, height: float):
+        self.x = x
+        self.y = y
+        self.width = width
+        self.height = height
+
+    @property
+    def left(self) -> float:
+        return self.x - self.width / 2
+
+    @property
+    def top(self) -> float:
+        return self.y - self.height / 2
+
+
+class Layout:
+    """
+    布局结果
+
+    - boxes: {节点ID: Box}
+    - labels: {节点ID: 标签行列表}
+    - edges: [(合并后的边, 折线顶点列表)]，折线从调用者边框指向被调用者边框
+    """
+
+    def __init__(self):
+        self.boxes: Dict[str, Box] = {}
+        self.labels: Dict[str, List[str]] = {}
+        self.edges: List[Tuple[Dict[str, Any], List[Tuple[float, float]]]] = []
+        self.width = 0.0
+        self.height = 0.0
+
+
+def node_label(graph: CallGraph, node_id: str) -> List[str]:
+    """
+    节点标签：函数名和相对项目根目录的位置；模块和强连通分量为函数数量，
+    裁剪大图的占位节点只有名称
+    """
+    node = graph.nodes[node_id]
+    if graph.node_kind(node_id) == "elided":
+        return [node["name"]]
+    if graph.node_kind(node_id) in GROUP_KINDS:
+        return [node["name"], f"({node['extras']['functions']} 个函数)"]
+    file_path = node.get("file") or ""
+    root = graph.project_root()
+    if root and file_path.startswith(root):
+        file_path = file_path[len(root) :].lstrip("/\\")
+    return [node["name"], f"{file_path}:{node.get('start_line', '?')}"]
+
+
+def _break_cycles(nodes: List[str], succ: Dict[str, List[str]]) -> set:
+    """深度优先搜索找出回边（指向搜索栈上节点的边），反转它们后图中没有环"""
+    back_edges = set()
+    state: Dict[str, int] = {}
+    for start in nodes:
+        if start in state:
+            continue
+        state[start] = 1
+        stack = [(start, iter(succ[start]))]
+        while stack:
+            node, children = stack[-1]
+            child = next(children, None)
+            if child is None:
+                state[node] = 2
+                stack.pop()
+            elif state.get(child) == 1:
+                back_edges.add((node, child))
+            elif child not in state:
+                state[child] = 1
+                stack.append((child, iter(succ[child])))
+    return back_edges
+
+
+def _assign_layers(nodes: List[str], succ: Dict[str, List[str]]) -> Dict[str, int]:
+    """最长路径分层：没有调用者的节点在第 0 层，其他节点在所有调用者的下一层"""
+    indegree = dict.fromkeys(nodes, 0)
+    for node in nodes:
+        for child in succ[node]:
+            indegree[child] += 1
+    layer = dict.fromkeys(nodes, 0)
+    ready = [node for node in nodes if not indegree[node]]
+    while ready:
+        node = ready.pop()
+        for child in succ[node]:
+            layer[child] = max(layer[child], layer[node] + 1)
+            indegree[child] -= 1
+            if not indegree[child]:
+                ready.append(child)
+    return layer
+
+
+def _order_layers(
+    layers: List[List[str]], up: Dict[str, List[str]], down: Dict[str, List[str]]
+):
+    """交替自上而下、自下而上按相邻层邻居位置的重心重新排序每一层"""
+    position = {node: i for layer in layers for i, node in enumerate(layer)}
+    for sweep in range(ORDER_SWEEPS):
+        downward = sweep % 2 == 0
+        indices = range(1, len(layers)) if downward else range(len(layers) - 2, -1, -1)
+        neighbors = up if downward else down
+        for index in indices:
+            layer = layers[index]
+            layer.sort(key=lambda node: _barycenter(neighbors[node], position, node))
+            for i, node in enumerate(layer):
+                position[node] = i
+
+
+def _barycenter(adjacent: List[str], position: Dict[str, float], node: str) -> float:
+    """相邻层邻居坐标的平均值；没有邻居时保持原位置"""
+    if not adjacent:
+        return position[node]
+    return sum(position[n] for n in adjacent) / len(adjacent)
+
+
+def _place_layer(
+    layer: List[str], breadth: Dict[str, float], desired: Dict[str, float]
+):
+    """
+    在保持顺序和最小间距的前提下让节点尽量靠近期望坐标：先从左到右推开
+    重叠的节点，再从右到左推回，取两次结果的平均
+    """
+    forward = []
+    for i, node in enumerate(layer):
+        x = desired[node]
+        if i:
+            prev = layer[i - 1]
+            x = max(x, forward[-1] + (breadth[prev] + breadth[node]) / 2 + NODE_SPACING)
+        forward.append(x)
+    backward = [0.0] * len(layer)
+    for i in range(len(layer) - 1, -1, -1):
+        node = layer[i]
+        x = desired[node]
+        if i < len(layer) - 1:
+            nxt = layer[i + 1]
+            limit = backward[i + 1] - (breadth[nxt] + breadth[node]) / 2 - NODE_SPACING
+            x = min(x, limit)
+        backward[i] = x
+    placed = [(f + b) / 2 for f, b in zip(forward, backward)]
+    # 平均后仍可能重叠，再从左到右推开一次
+    for i in range(1, len(layer)):
+        gap = (breadth[layer[i - 1]] + breadth[layer[i]]) / 2 + NODE_SPACING
+        placed[i] = max(placed[i], placed[i - 1] + gap)
+    for node, x in zip(layer, placed):
+        desired[node] = x
+
+
+def layered_layout(
+    graph: CallGraph,
+    text_width: Callable[[str], float],
+    line_height: float,
+    rankdir: str = "TB",
+) -> Layout:
+    """
+    计算调用图的分层布局
+
+    Args:
+        text_width: 标签文本的像素宽度
+        line_height: 标签每行的像素高度
+        rankdir: 布局方向（TB/BT/LR/RL），调用者在被调用者的上/下/左/右侧
+
+    自调用不参与分层，画成从节点层内方向的一侧（TB/BT 为右侧，LR/RL 为下侧）
+    伸出再折回的环
+    """
+    layout = Layout()
+    nodes = list(graph.nodes)
+    edges = [e for e in graph.edges() if e["caller_id"] != e["callee_id"]]
+    loops = [e for e in graph.edges() if e["caller_id"] == e["callee_id"]]
+    looped = {e["caller_id"] for e in loops}
+    horizontal = rankdir in ("LR", "RL")
+
+    width: Dict[str, float] = {}
+    height: Dict[str, float] = {}
+    for node_id in nodes:
+        layout.labels[node_id] = node_label(graph, node_id)
+        width[node_id] = (
+            max(text_width(line) for line in layout.labels[node_id]) + 2 * NODE_PADDING
+        )
+        height[node_id] = len(layout.labels[node_id]) * line_height + NODE_PADDING
+
+    # 反转回边后分层
+    succ: Dict[str, List[str]] = {node_id: [] for node_id in nodes}
+    for edge in edges:
+        succ[edge["caller_id"]].append(edge["callee_id"])
+    back_edges = _break_cycles(nodes, succ)
+    acyclic: Dict[str, List[str]] = {node_id: [] for node_id in nodes}
+    oriented = []
+    for edge in edges:
+        source, target = edge["caller_id"], edge["callee_id"]
+        if (source, target) in back_edges:
+            source, target = target, source
+        acyclic[source].append(target)
+        oriented.append((edge, source, target))
+    layer_of = _assign_layers(nodes, acyclic)
+
+    # 跨越多层的边拆成经过虚拟节点的链
+    up: Dict[str, List[str]] = {node_id: [] for node_id in nodes}
+    down: Dict[str, List[str]] = {node_id: [] for node_id in nodes}
+    chains = []
+    for index, (edge, source, target) in enumerate(oriented):
+        chain = [source]
+        for layer in range(layer_of[source] + 1, layer_of[target]):
+            dummy = f"\0{index}:{layer}"
+            layer_of[dummy] = layer
+            up[dummy], down[dummy] = [], []
+            width[dummy] = height[dummy] = 0.0
+            chain.append(dummy)
+        chain.append(target)
+        for a, b in zip(chain, chain[1:]):
+            down[a].append(b)
+            up[b].append(a)
+        chains.append((edge, chain, source != edge["caller_id"]))
+
+    count = max(layer_of.values(), default=-1) + 1
+    layers: List[List[str]] = [[] for _ in range(count)]
+    for node in layer_of:
+        layers[layer_of[node]].append(node)
+    _order_layers(layers, up, down)
+
+    # breadth 为节点在层内方向上的尺寸，depth 为沿分层方向的尺寸
+    breadth = height if horizontal else width
+    depth = width if horizontal else height
+    across: Dict[str, float] = {}
+    for layer in layers:
+        offset = 0.0
+        for node in layer:
+            across[node] = offset + breadth[node] / 2
+            offset += breadth[node] + NODE_SPACING
+    for sweep in range(POSITION_SWEEPS):
+        downward = sweep % 2 == 0
+        neighbors = up if downward else down
+        ordered = layers if downward else list(reversed(layers))
+        for layer in ordered:
+            desired = {
+                node: _barycenter(neighbors[node], across, node) for node in layer
+            }
+            _place_layer(layer, breadth, desired)
+            across.update(desired)
+    shift = min((across[n] - breadth[n] / 2 for n in across), default=0.0)
+
+    along: List[float] = []
+    offset = 0.0
+    for layer in layers:
+        size = max((depth[node] for node in layer), default=0.0)
+        along.append(offset + size / 2)
+        offset += size + LAYER_SPACING
+
+    # 有自调用的节点在层内方向上多占 LOOP_SIZE
+    extent_across = max(
+        (
+            across[n] + breadth[n] / 2 + (LOOP_SIZE if n in looped else 0.0)
+            for n in across
+        ),
+        default=0.0,
+    )
+    extent_across -= shift
+    extent_along = max(offset - LAYER_SPACING, 0.0)
+
+    def point(node: str) -> Tuple[float, float]:
+        a = across[node] - shift
+        b = along[layer_of[node]]
+        if rankdir in ("BT", "RL"):
+            b = extent_along - b
+        x, y = (b, a) if horizontal else (a, b)
+        return x + MARGIN, y + MARGIN
+
+    for node_id in nodes:
+        x, y = point(node_id)
+        layout.boxes[node_id] = Box(x, y, width[node_id], height[node_id])
+
+    for edge, chain, reversed_edge in chains:
+        points = [point(node) for node in chain]
+        points[0] = _border_point(layout.boxes[chain[0]], points[1])
+        points[-1] = _border_point(layout.boxes[chain[-1]], points[-2])
+        if reversed_edge:
+            points.reverse()
+        layout.edges.append((edge, points))
+    for edge in loops:
+        box = layout.boxes[edge["caller_id"]]
+        layout.edges.append((edge, _loop_points(box, horizontal)))
+
+    if horizontal:
+        layout.width, layout.height = extent_along, extent_across
+    else:
+        layout.width, layout.height = extent_across, extent_along
+    layout.width += 2 * MARGIN
+    layout.height += 2 * MARGIN
+    return layout
+
+
+def _loop_points(box: Box, horizontal: bool) -> List[Tuple[float, float]]:
+    """自调用的折线：从节点一侧的边框伸出 LOOP_SIZE 后折回同一侧"""
+    if horizontal:
+        bottom = box.top + box.height
+        a, b = box.x - box.width / 4, box.x + box.width / 4
+        outside = bottom + LOOP_SIZE
+        return [(a, bottom), (a, outside), (b, outside), (b, bottom)]
+    right = box.left + box.width
+    a, b = box.y - box.height / 4, box.y + box.height / 4
+    outside = right + LOOP_SIZE
+    return [(right, a), (outside, a), (outside, b), (right, b)]
+
+
+def _border_point(box: Box, toward: Tuple[float, float]) -> Tuple[float, float]:
+    """从节点中心指向 toward 的射线与节点边框的交点"""
+    dx = toward[0] - box.x
+    dy = toward[1] - box.y
+    if not dx and not dy:
+        return box.x, box.y
+    scale = min(
+        box.width / 2 / abs(dx) if dx else float("inf"),
+        box.height / 2 / abs(dy) if dy else float("inf"),
+    )
+    return box.x + dx * scale, box.y + dy * scale
+
+
+def edge_label_position(
+    points: List[Tuple[float, float]],
+) -> Optional[Tuple[float, float]]:
+    """边标签的位置：折线中间一段的中点"""
+    if len(points) < 2:
+        return None
+    middle = (len(points) - 1) // 2
+    (x1, y1), (x2, y2) = points[middle], points[middle + 1]
+    return (x1 + x2) / 2, (y1 + y2) / 2
@@ -0,0 +1,334 @@
+"""
+语言服务器
+最小的 LSP 服务器（标准输入/输出上的 JSON-RPC），基于分析数据库提供调用层次：
+textDocument/prepareCallHierarchy、callHierarchy/incomingCalls 和
+callHierarchy/outgoingCalls，让没有 rust-analyzer 的编辑器（或其他支持的语言）
+也能浏览调用者和被调用者
+"""
+
+import json
+import os
+import sys
+from typing import Any, BinaryIO, Callable, Dict, List, Optional, Tuple
+from urllib.parse import quote, unquote, urlparse
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+# LSP 的 SymbolKind
+SYMBOL_KINDS = {"function": 12, "method": 6, "closure": 12}
+
+# JSON-RPC 错误码
+METHOD_NOT_FOUND = -32601
+INVALID_PARAMS = -32602
+SERVER_NOT_INITIALIZED = -32002
+
+
+def path_to_uri(path: str) -> str:
+    """文件路径 -> file:// URI"""
+    path = os.path.abspath(path).replace("\\", "/")
+    if not path.startswith("/"):
+        path = "/" + path  # Windows 盘符
+    return "file://" + quote(path)
+
+
+def uri_to_path(uri: str) -> str:
+    """file:// URI -> 规范化的文件路径"""
+    parsed = urlparse(uri)
+    path = unquote(parsed.path)
+    if os.name == "nt" and path.startswith("/") and path[2:3] == ":":
+        path = path[1:]
+    return os.path.normcase(os.path.abspath(path))
+
+
+def _identifier_at(text: str, character: int) -> Tuple[int, int]:
+    """行内 character 处的标识符范围 [start, end)，不在标识符上时为空范围"""
+    start = end = min(character, len(text))
+    while start > 0 and (text[start - 1].isalnum() or text[start - 1] == "_"):
+        start -= 1
+    while end < len(text) and (text[end].isalnum() or text[end] == "_"):
+        end += 1
+    return start, end
+
+
+class CallHierarchyServer:
+    """
+    调用层次服务
+
+    图由 load_graph 加载，version 返回的值变化时（如分析数据库被 analyze --watch
+    更新）在下一个请求前重新加载
+    """
+
+    def __init__(
+        self,
+        load_graph: Callable[[], CallGraph],
+        version: Callable[[], Any] = lambda: None,
+    ):
+        self._load_graph = load_graph
+        self._version = version
+        self._loaded_version: Any = None
+        self.graph: Optional[CallGraph] = None
+        # {规范化的文件路径: [函数ID]}
+        self._by_file: Dict[str, List[str]] = {}
+        self._lines: Dict[str, List[str]] = {}
+        self.initialized = False
+        self.shutdown_requested = False
+
+    def _refresh(self):
+        version = self._version()
+        if self.graph is not None and version == self._loaded_version:
+            return
+        self.graph = self._load_graph()
+        self._loaded_version = version
+        self._lines = {}
+        self._by_file = {}
+        for node_id, node in self.graph.nodes.items():
+            if node.get("file"):
+                key = os.path.normcase(os.path.abspath(node["file"]))
+                self._by_file.setdefault(key, []).append(node_id)
+
+    def _line_text(self, path: str, line: int) -> str:
+        """源文件第 line 行（从 1 开始）的文本，文件不可读时为空"""
+        if path not in self._lines:
+            try:
+                with open(path, "r", encoding="utf-8", errors="replace") as f:
+                    self._lines[path] = f.read().splitlines()
+            except OSError:
+                self._lines[path] = []
+        lines = self._lines[path]
+        return lines[line - 1] if 0 < line <= len(lines) else ""
+
+    def _range(self, line: Optional[int], start: int = 0, end: int = 0):
+        row = max((line or 1) - 1, 0)
+        return {
+            "start": {"line": row, "character": start},
+            "end": {"line": row, "character": end},
+        }
+
+    def item(self, node_id: str) -> Dict[str, Any]:
+        """函数的 CallHierarchyItem（data 中保存函数 ID）"""
+        graph = self.graph
+        node = graph.nodes[node_id]
+        path = os.path.abspath(node["file"])
+        start_line = node.get("start_line") or 1
+        end_line = node.get("end_line") or start_line
+        definition = self._line_text(path, start_line)
+        column = definition.find(node["name"])
+        if column < 0:
+            selection = self._range(start_line)
+        else:
+            selection = self._range(start_line, column, column + len(node["name"]))
+        end_text = self._line_text(path, end_line)
+        full = {
+            "start": {"line": start_line - 1, "character": 0},
+            "end": {"line": end_line - 1, "character": len(end_text)},
+        }
+        return {
+            "name": node["name"],
+            "kind": SYMBOL_KINDS.get(graph.node_kind(node_id), 12),
+            "detail": graph.qualified_name(node_id),
+            "uri": path_to_uri(path),
+            "range": full,
+            "selectionRange": selection,
+            "data": {"id": node_id},
+        }
+
+    def _site_range(self, relation: Dict[str, Any]) -> Dict[str, Any]:
+        """调用点的范围：被调用函数名在调用表达式中的位置"""
+        line = relation.get("call_site_line")
+        column = relation.get("call_site_column") or 0
+        path = os.path.abspath(relation["caller_file"] or "")
+        text = self._line_text(path, line or 0)
+        name = relation.get("callee_name") or ""
+        found = text.find(name, column) if name else -1
+        if found >= 0:
+            return self._range(line, found, found + len(name))
+        return self._range(line, column, column)
+
+    def function_at(self, path: str, line: int, character: int) -> Optional[str]:
+        """
+        光标处的函数：光标在已知函数的调用上时为被调用函数，否则为
+        包含光标的最内层函数（line 从 1 开始）
+        """
+        candidates = self._by_file.get(path, [])
+        text = self._line_text(path, line)
+        start, end = _identifier_at(text, character)
+        word = text[start:end]
+        enclosing = [
+            node_id
+            for node_id in candidates
+            if (self.graph.nodes[node_id].get("start_line") or 0)
+            <= line
+            <= (self.graph.nodes[node_id].get("end_line") or 0)
+        ]
+        if word:
+            for caller_id in enclosing:
+                for callee_id in self.graph.successors(caller_id):
+                    for site in self.graph.call_sites(caller_id, callee_id):
+                        if site.get("call_site_line") == line and (
+                            site.get("callee_name") == word
+                            or self.graph.nodes[callee_id]["name"] == word
+                        ):
+                            return callee_id
+        if not enclosing:
+            return None
+        return min(
+            enclosing,
+            key=lambda n: (self.graph.nodes[n].get("end_line") or 0)
+            - (self.graph.nodes[n].get("start_line") or 0),
+        )
+
+    def _item_id(self, params: Dict[str, Any]) -> Optional[str]:
+        item = params.get("item") or {}
+        node_id = (item.get("data") or {}).get("id")
+        if node_id in self.graph.nodes:
+            return node_id
+        # 客户端没有回传 data 时按位置重新定位
+        selection = item.get("selectionRange") or item.get("range")
+        if item.get("uri") and selection:
+            start = selection["start"]
+            return self.function_at(
+                uri_to_path(item["uri"]), start["line"] + 1, start["character"]
+            )
+        return None
+
+    def prepare(self, params: Dict[str, Any]) -> Optional[List[Dict[str, Any]]]:
+        position = params["position"]
+        path = uri_to_path(params["textDocument"]["uri"])
+        node_id = self.function_at(path, position["line"] + 1, position["character"])
+        return [self.item(node_id)] if node_id else None
+
+    def incoming(self, params: Dict[str, Any]) -> Optional[List[Dict[str, Any]]]:
+        node_id = self._item_id(params)
+        if node_id is None:
+            return None
+        calls = []
+        for caller_id in self.graph.predecessors(node_id):
+            sites = self.graph.call_sites(caller_id, node_id)
+            calls.append(
+                {
+                    "from": self.item(caller_id),
+                    "fromRanges": [self._site_range(site) for site in sites],
+                }
+            )
+        return calls
+
+    def outgoing(self, params: Dict[str, Any]) -> Optional[List[Dict[str, Any]]]:
+        node_id = self._item_id(params)
+        if node_id is None:
+            return None
+        calls = []
+        for callee_id in self.graph.successors(node_id):
+            sites = self.graph.call_sites(node_id, callee_id)
+            calls.append(
+                {
+                    "to": self.item(callee_id),
+                    "fromRanges": [self._site_range(site) for site in sites],
+                }
+            )
+        return calls
+
+    def handle(self, message: Dict[str, Any]) -> Optional[Dict[str, Any]]:
+        """
+        处理一条消息，返回需要发送的响应（通知没有响应）
+
+        Raises:
+            SystemExit: 收到 exit 通知时，在 shutdown 之后退出码为 0，否则为 1
+        """
+        method = message.get("method")
+        request_id = message.get("id")
+        is_request = "id" in message
+
+        if method == "exit":
+            raise SystemExit(0 if self.shutdown_requested else 1)
+        if method == "initialize":
+            self.initialized = True
+            result: Any = {
+                "capabilities": {
+                    "callHierarchyProvider": True,
+                    "textDocumentSync": {"openClose": False, "change": 0},
+                },
+                "serverInfo": {"name": "call-graph"},
+            }
+            return {"jsonrpc": "2.0", "id": request_id, "result": result}
+        if not is_request:
+            return None
+        if not self.initialized:
+            return self._error(request_id, SERVER_NOT_INITIALIZED, "服务器尚未初始化")
+        if method == "shutdown":
+            self.shutdown_requested = True
+            return {"jsonrpc": "2.0", "id": request_id, "result": None}
+
+        handlers = {
+            "textDocument/prepareCallHierarchy": self.prepare,
+            "callHierarchy/incomingCalls": self.incoming,
+            "callHierarchy/outgoingCalls": self.outgoing,
+        }
+        handler = handlers.get(method)
+        if handler is None:
+            return self._error(request_id, METHOD_NOT_FOUND, f"不支持的方法: {method}")
+        self._refresh()
+        try:
+            result = handler(message.get("params") or {})
+        except (KeyError, TypeError) as e:
+            return self._error(request_id, INVALID_PARAMS, f"无效的参数: {e}")
+        return {"jsonrpc": "2.0", "id": request_id, "result": result}
+
+    @staticmethod
+    def _error(request_id: Any, code: int, message: str) -> Dict[str, Any]:
+        return {
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "error": {"code": code, "message": message},
+        }
+
+
+def read_message(stream: BinaryIO) -> Optional[Dict[str, Any]]:
+    """读取一条带 Content-Length 头的消息，输入结束时返回 None"""
+    length = None
+    while True:
+        line = stream.readline()
+        if not line:
+            return None
+        line = line.strip()
+        if not line:
+            break
+        name, _, value = line.decode("ascii").partition(":")
+        if name.strip().lower() == "content-length":
+            length = int(value.strip())
+    if length is None:
+        return None
+    return json.loads(stream.read(length).decode("utf-8"))
+
+
+def write_message(stream: BinaryIO, message: Dict[str, Any]):
+    body = json.dumps(message, ensure_ascii=False).encode("utf-8")
+    stream.write(f"Content-Length: {len(body)}\r\n\r\n".encode("ascii") + body)
+    stream.flush()
+
+
+def serve(
+    server: CallHierarchyServer,
+    stdin: Optional[BinaryIO] = None,
+    stdout: Optional[BinaryIO] = None,
+) -> int:
+    """运行服务器直到收到 exit 通知或输入结束，返回退出码"""
+    stdin = stdin or sys.stdin.buffer
+    stdout = stdout or sys.stdout.buffer
+    while True:
+        try:
+            message = read_message(stdin)
+        except (ValueError, UnicodeDecodeError) as e:
+            print(f"警告: 无法解析消息: {e}", file=sys.stderr)
+            continue
+        if message is None:
+            return 0 if server.shutdown_requested else 1
+        try:
+            response = server.handle(message)
+        except SystemExit as e:
+            return e.code
+        if response is not None:
+            write_message(stdout, response)
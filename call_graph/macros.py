@@ -0,0 +1,438 @@
+"""
+Rust 宏展开
+调用 cargo expand（或 rustc -Zunpretty=expanded）获取 crate 展开后的源码，
+与原始源码逐函数对比，找出由宏生成、在原始源码中不可见的调用
+"""
+
+import difflib
+import os
+import re
+import subprocess
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import EdgeKind
+    from .parsers import get_parser
+except ImportError:
+    from graph import EdgeKind
+    from parsers import get_parser
+
+
+# 展开单个 crate 目标的超时时间（秒），展开需要编译依赖
+EXPAND_TIMEOUT = 600
+
+# 宏生成的调用的边类型
+MACRO_EDGE_KIND = EdgeKind.MACRO.value
+
+
+def find_crates(project_path: Path, exclude_dirs: List[str]) -> List[Path]:
+    """查找项目中所有包含 [package] 的 Cargo.toml 所在目录"""
+    crates = []
+    for root, dirs, files in os.walk(project_path):
+        dirs[:] = [d for d in dirs if d not in exclude_dirs and not d.startswith(".")]
+        if "Cargo.toml" in files:
+            manifest = Path(root) / "Cargo.toml"
+            try:
+                content = manifest.read_text(encoding="utf-8", errors="ignore")
+            except OSError:
+                continue
+            if re.search(r"^\s*\[package\]", content, re.MULTILINE):
+                crates.append(Path(root))
+    return sorted(crates)
+
+
+def crate_targets(crate_dir: Path) -> List[Tuple[List[str], Path]]:
+    """
+    crate 的可展开目标
+
+    Returns:
+        [(cargo 目标参数, 目标根文件)]，如 (["--lib"], src/lib.rs)
+    """
+    targets = []
+    src = crate_dir / "src"
+    if (src / "lib.rs").exists():
+        targets.append((["--lib"], src / "lib.rs"))
+    if (src / "main.rs").exists():
+        manifest = crate_dir / "Cargo.toml"
+        content = manifest.read_text(encoding="utf-8", errors="ignore")
+        match = re.search(r'^\s*name\s*=\s*"([^"]+)"', content, re.MULTILINE)
+        if match:
+            targets.append((["--bin", match.group(1)], src / "main.rs"))
+    bin_dir = src / "bin"
+    if bin_dir.is_dir():
+        for path in sorted(bin_dir.glob("*.rs")):
+            targets.append((["--bin", path.stem], path))
+    return targets
+
+
+def run_expansion(crate_dir: Path, target_args: List[str]) -> Optional[str]:
+    """
+    展开 crate 目标，返回展开后的源码；失败时返回 None
+
+    优先使用 cargo expand；未安装时退回 cargo rustc -Zunpretty=expanded
+    （-Z 选项需要 nightly，通过 RUSTC_BOOTSTRAP=1 在稳定版上启用）
+    """
+    commands = [
+        (["cargo", "expand", *target_args], None),
+        (
+            ["cargo", "rustc", *target_args, "--profile=check", "--"]
+            + ["-Zunpretty=expanded"],
+            {**os.environ, "RUSTC_BOOTSTRAP": "1"},
+        ),
+    ]
+    last_error = ""
+    for command, env in commands:
+        try:
+            completed = subprocess.run(
+                command,
+                cwd=crate_dir,
+                env=env,
+                capture_output=True,
+                text=True,
+                timeout=EXPAND_TIMEOUT,
+            )
+        except FileNotFoundError:
+            print("警告: 未找到 cargo，跳过宏展开")
+            return None
+        except subprocess.TimeoutExpired:
+            last_error = f"{' '.join(command)} 超时"
+            continue
+        if completed.returncode == 0 and completed.stdout.strip():
+            return completed.stdout
+        lines = completed.stderr.strip().splitlines()
+        last_error = lines[-1] if lines else f"退出码 {completed.returncode}"
+
+    print(f"警告: 宏展开失败 {crate_dir} {' '.join(target_args)}: {last_error}")
+    return None
+
+
+def _module_ranges(root, source_code: bytes, parser) -> List[Tuple[int, int, str]]:
+    """文件内联模块（mod name { ... }）的字节范围"""
+    ranges = []
+    stack = [root]
+    while stack:
+        node = stack.pop()
+        if node.type == "mod_item":
+            name_node = node.child_by_field_name("name")
+            if name_node and node.child_by_field_name("body"):
+                ranges.append(
+                    (
+                        node.start_byte,
+                        node.end_byte,
+                        parser.get_node_text(name_node, source_code),
+                    )
+                )
+        stack.extend(node.children)
+    ranges.sort()
+    return ranges
+
+
+def _module_path(position: int, ranges: List[Tuple[int, int, str]]) -> Tuple[str, ...]:
+    """字节位置所在的内联模块路径"""
+    return tuple(name for start, end, name in ranges if start <= position < end)
+
+
+def _file_module_path(file_path: Path, target_root: Path) -> Optional[Tuple[str, ...]]:
+    """源文件对应的模块路径（相对目标根文件所在目录），不在目录下时返回 None"""
+    if file_path == target_root:
+        return ()
+    try:
+        rel = file_path.relative_to(target_root.parent)
+    except ValueError:
+        return None
+    parts = list(rel.with_suffix("").parts)
+    if parts and parts[-1] == "mod":
+        parts = parts[:-1]
+    return tuple(parts)
+
+
+def _function_key(func: Dict[str, Any], module_path: Tuple[str, ...]) -> Tuple:
+    """用于匹配原始函数和展开后函数的键：模块路径、所属类型、trait、函数名"""
+    extras = func.get("extras") or {}
+    return (module_path, extras.get("owner"), extras.get("trait"), func["name"])
+
+
+def _unique_by_key(
+    items: List[Tuple[Tuple, Dict[str, Any]]]
+) -> Dict[Tuple, Dict[str, Any]]:
+    """按键建立索引，丢弃有歧义（同一个键对应多个函数）的键"""
+    index: Dict[Tuple, Optional[Dict[str, Any]]] = {}
+    for key, func in items:
+        index[key] = None if key in index else func
+    return {key: func for key, func in index.items() if func is not None}
+
+
+def _is_reference(ref: Dict[str, Any]) -> bool:
+    """
+    闭包定义引用和函数值引用不是调用，不参与展开前后的对比；
+    运算符等隐式调用由分析选项控制，也不参与对比
+    """
+    hints = ref["hints"]
+    return bool(hints.get("target") or hints.get("value") or hints.get("implicit"))
+
+
+def _innermost(functions: List[Dict[str, Any]], position: int) -> Optional[str]:
+    """包含字节位置的最内层函数 ID"""
+    best = None
+    for func in functions:
+        if func["start_byte"] <= position < func["end_byte"]:
+            if best is None or (
+                func["end_byte"] - func["start_byte"]
+                < best["end_byte"] - best["start_byte"]
+            ):
+                best = func
+    return best["id"] if best else None
+
+
+class _OriginalFile:
+    """重新解析的原始源文件：函数、调用引用、宏调用位置、类型定义位置"""
+
+    def __init__(self, file_path: str, parser):
+        root, source_code = parser.read_and_parse(file_path)
+        self.file = file_path
+        self.functions: List[Dict[str, Any]] = []
+        self.refs: List[Dict[str, Any]] = []
+        self.module_ranges: List[Tuple[int, int, str]] = []
+        # 函数 ID -> [(行, 列)]
+        self.macro_sites: Dict[str, List[Tuple[int, int]]] = {}
+        # 类型名 -> (行, 列)
+        self.type_sites: Dict[str, Tuple[int, int]] = {}
+        if not root:
+            return
+
+        self.functions = parser.collect_functions(file_path, root, source_code)
+        self.refs = parser.collect_call_refs(
+            file_path, root, source_code, self.functions
+        )
+        self.module_ranges = _module_ranges(root, source_code, parser)
+
+        stack = [root]
+        while stack:
+            node = stack.pop()
+            if node.type == "macro_invocation":
+                owner = _innermost(self.functions, node.start_byte)
+                if owner:
+                    site = (node.start_point[0] + 1, node.start_point[1])
+                    self.macro_sites.setdefault(owner, []).append(site)
+            elif node.type in ("struct_item", "enum_item", "union_item"):
+                name_node = node.child_by_field_name("name")
+                if name_node:
+                    name = parser.get_node_text(name_node, source_code)
+                    self.type_sites.setdefault(
+                        name, (node.start_point[0] + 1, node.start_point[1])
+                    )
+            stack.extend(node.children)
+        for sites in self.macro_sites.values():
+            sites.sort()
+
+
+def _macro_site(
+    func: Dict[str, Any],
+    orig_refs: List[Dict[str, Any]],
+    anchor_start: int,
+    anchor_end: int,
+    macro_sites: List[Tuple[int, int]],
+) -> Tuple[int, int]:
+    """
+    推断宏生成的调用对应的宏调用位置
+
+    展开后多出的调用位于原始调用序列的 anchor_start 与 anchor_end 之间，
+    取这段区间内的第一个宏调用；区间内没有时取区间之前最近的宏调用，
+    都没有时使用函数定义位置
+    """
+    lower = (func["start_line"], 0)
+    upper = (func["end_line"] + 1, 0)
+    if anchor_start > 0:
+        prev = orig_refs[anchor_start - 1]
+        lower = (prev["call_site_line"], prev["call_site_column"])
+    if anchor_end < len(orig_refs):
+        nxt = orig_refs[anchor_end]
+        upper = (nxt["call_site_line"], nxt["call_site_column"])
+
+    inside = [site for site in macro_sites if lower <= site <= upper]
+    if inside:
+        return inside[0]
+    before = [site for site in macro_sites if site <= upper]
+    if before:
+        return before[-1]
+    return (func["start_line"], 0)
+
+
+def expand_macro_calls(
+    project_path: Path,
+    rust_files: List[str],
+    all_functions: List[Dict[str, Any]],
+    exclude_dirs: List[str],
+    attribute_to_site: bool = False,
+) -> Dict[str, Any]:
+    """
+    提取宏生成的调用
+
+    Args:
+        project_path: 项目路径
+        rust_files: 已分析的 Rust 源文件
+        all_functions: 全局函数表（用于解析被调用者）
+        exclude_dirs: 查找 crate 时排除的目录
+        attribute_to_site: 将调用点定位到推断出的宏调用位置；
+            否则使用调用者的定义位置（展开后的代码没有源码位置）
+
+    Returns:
+        {"functions": 由 derive 等宏生成的新函数, "calls": 调用关系,
+         "crates": 成功展开的目标数}
+    """
+    parser = get_parser("rust")
+    originals: Dict[str, _OriginalFile] = {}
+    processed = set()
+    new_functions: List[Dict[str, Any]] = []
+    new_refs: List[Dict[str, Any]] = []
+    expanded_targets = 0
+
+    for crate_dir in find_crates(project_path, exclude_dirs):
+        for target_args, target_root in crate_targets(crate_dir):
+            expanded = run_expansion(crate_dir, target_args)
+            if expanded is None:
+                continue
+            expanded_targets += 1
+
+            # 原始函数：按（模块路径、所属类型、trait、函数名）索引
+            candidates = []
+            types: Dict[str, Tuple[str, Tuple[int, int]]] = {}
+            for path in sorted(set(rust_files)):
+                module_path = _file_module_path(Path(path), target_root)
+                if module_path is None:
+                    continue
+                if path not in originals:
+                    originals[path] = _OriginalFile(path, parser)
+                original = originals[path]
+                for func in original.functions:
+                    inline = _module_path(func["start_byte"], original.module_ranges)
+                    candidates.append((_function_key(func, module_path + inline), func))
+                for name, site in original.type_sites.items():
+                    types.setdefault(name, (path, site))
+            orig_index = _unique_by_key(candidates)
+
+            # 展开后的函数和调用引用
+            source_code = expanded.encode("utf-8")
+            root = parser.parser.parse(source_code).root_node
+            exp_file = f"<expanded:{crate_dir.name}>"
+            exp_functions = parser.collect_functions(exp_file, root, source_code)
+            exp_refs = parser.collect_call_refs(
+                exp_file, root, source_code, exp_functions
+            )
+            exp_ranges = _module_ranges(root, source_code, parser)
+            exp_index = _unique_by_key(
+                [
+                    (
+                        _function_key(
+                            func, _module_path(func["start_byte"], exp_ranges)
+                        ),
+                        func,
+                    )
+                    for func in exp_functions
+                ]
+            )
+            refs_by_caller: Dict[str, List[Dict[str, Any]]] = {}
+            for ref in exp_refs:
+                refs_by_caller.setdefault(ref["caller_id"], []).append(ref)
+
+            for key, exp_func in exp_index.items():
+                exp_calls = [
+                    ref
+                    for ref in refs_by_caller.get(exp_func["id"], [])
+                    if not _is_reference(ref)
+                ]
+                orig_func = orig_index.get(key)
+
+                if orig_func is None:
+                    # 原始源码中不存在的方法（如 derive 生成的 impl），
+                    # 所属类型定义在项目中时作为新节点，定位到类型定义处
+                    owner = (exp_func.get("extras") or {}).get("owner")
+                    if not owner or owner not in types:
+                        continue
+                    file_path, (line, column) = types[owner]
+                    trait = exp_func["extras"].get("trait")
+                    path_name = (
+                        f"<{owner} as {trait}>::{exp_func['name']}"
+                        if trait
+                        else f"{owner}::{exp_func['name']}"
+                    )
+                    func = dict(exp_func)
+                    func.update(
+                        {
+                            "id": parser.generate_id(file_path, path_name, line),
+                            "file": file_path,
+                            "start_line": line,
+                            "end_line": line,
+                            "start_byte": None,
+                            "end_byte": None,
+                            "container": None,
+                            "is_exported": 1,
+                            "extras": {**exp_func["extras"], "generated": "macro"},
+                        }
+                    )
+                    if func["id"] in processed:
+                        continue
+                    processed.add(func["id"])
+                    new_functions.append(func)
+                    for ref in exp_calls:
+                        new_refs.append(_macro_ref(ref, func, (line, column)))
+                    continue
+
+                if orig_func["id"] in processed:
+                    continue
+                processed.add(orig_func["id"])
+
+                original = originals[orig_func["file"]]
+                orig_refs = [
+                    ref
+                    for ref in original.refs
+                    if ref["caller_id"] == orig_func["id"] and not _is_reference(ref)
+                ]
+                matcher = difflib.SequenceMatcher(
+                    None,
+                    [ref["call_name"] for ref in orig_refs],
+                    [ref["call_name"] for ref in exp_calls],
+                    autojunk=False,
+                )
+                for tag, i1, i2, j1, j2 in matcher.get_opcodes():
+                    if tag not in ("insert", "replace"):
+                        continue
+                    if attribute_to_site:
+                        site = _macro_site(
+                            orig_func,
+                            orig_refs,
+                            i1,
+                            i2,
+                            original.macro_sites.get(orig_func["id"], []),
+                        )
+                    else:
+                        site = (orig_func["start_line"], 0)
+                    for ref in exp_calls[j1:j2]:
+                        new_refs.append(_macro_ref(ref, orig_func, site))
+
+    calls = parser.resolve_call_refs(new_refs, all_functions + new_functions)
+    for call in calls:
+        if call["edge_kind"] == "static":
+            call["edge_kind"] = MACRO_EDGE_KIND
+    return {"functions": new_functions, "calls": calls, "crates": expanded_targets}
+
+
+def _macro_ref(
+    ref: Dict[str, Any], caller: Dict[str, Any], site: Tuple[int, int]
+) -> Dict[str, Any]:
+    """
+    将展开后代码中的调用引用改写为原始调用者、指定调用位置的引用
+
+    展开后的代码片段与源码不对应，不保留
+    """
+    return {
+        **ref,
+        "caller_id": caller["id"],
+        "caller_name": caller["name"],
+        "caller_file": caller["file"],
+        "call_site_line": site[0],
+        "call_site_column": site[1],
+        "call_site_snippet": None,
+    }
@@ -3,22 +3,403 @@
 """
 
 import argparse
+import contextlib
+import io
 import json
+import os
 import sys
+import tempfile
+import time
+from typing import Dict, List, Optional, Tuple
 
 # 支持相对导入和直接运行
 try:
-    from .analyzer import CallGraphAnalyzer
+    from .algorithms import (
+        const_subgraph,
+        const_violations,
+        dominator_tree,
+        ENTRY_REASONS,
+        entry_point_reason,
+        find_call_paths,
+        find_entry_points,
+        find_recursion_cycles,
+        find_unreachable,
+        is_test_code,
+        reachable_from,
+        shortest_paths_to,
+        tests_reaching,
+    )
+    from .analyzer import DEFAULT_EXCLUDE_DIRS, CallGraphAnalyzer
     from .analyzer_optimized import CallGraphAnalyzerOptimized
+    from .api import analyze
+    from .baseline import (
+        DEFAULT_BASELINE_FILE,
+        check_baseline,
+        load_baseline,
+        save_baseline,
+    )
+    from .binary import analyze_binary, default_source_root
+    from .cargo import crate_name, load_toml
+    from .cfg import CFG_MODES, CfgError, CfgSet, combine_cfg, function_cfg
+    from .config import CONFIG_FILE_NAME, apply_config, find_config, load_config
+    from .coverage_report import (
+        COVERAGE_FORMATS,
+        apply_coverage,
+        coverage_summary,
+        load_coverage,
+    )
     from .database import CallGraphDB
+    from .dependencies import DEFAULT_DEPENDENCY_DEPTH
+    from .diff import diff_graphs, export_revision
+    from .duplicates import (
+        DEFAULT_CALLEE_SIMILARITY,
+        DEFAULT_MIN_CALLS,
+        DEFAULT_MIN_TOKENS,
+        find_duplicates,
+    )
+    from .effects import EffectSet
+    from .errorflow import (
+        DEFAULT_ERROR_DEPTH,
+        HANDLING_LABELS,
+        error_summary,
+        fallible_type,
+        propagation_tree,
+        site_handling,
+    )
+    from .exporters import FILE_EXPORTERS, PLANTUML_STYLES, export_graph
+    from .features import (
+        DEFAULT_MAX_FEATURES,
+        FeatureMatrix,
+        combination_label,
+        crate_manifests,
+        feature_matrix,
+        manifest_features,
+        parse_feature_set,
+    )
+    from .filters import Pattern, SymbolFilter
+    from .finder import fuzzy_find, suggestion
+    from .graph import CONFIDENCE_LEVELS, EDGE_KINDS, PHASES, CallGraph
+    from .history import (
+        HISTORY_STEPS,
+        analyze_history,
+        history_revisions,
+        history_to_csv,
+    )
+    from .impact import changed_functions, changed_lines, impact
+    from .layers import infer_layers, layers_to_dot, layers_to_mermaid
+    from .lsp import CallHierarchyServer, serve
+    from .metrics import (
+        CENTRALITY_METHODS,
+        SORT_KEYS,
+        compute_metrics,
+        metrics_to_csv,
+        metrics_to_json,
+        sort_metrics,
+    )
+    from .neo4j_loader import PASSWORD_ENV, load_into_neo4j
+    from .nostd import DEFAULT_ALLOWED, STD_CRATES, std_calls
+    from .owners import CODEOWNERS_LOCATIONS, CodeOwners, team_dependencies
+    from .parsers import PANIC_KINDS, PARSER_CLASSES
+    from .plugins import load_plugins
+    from .profiling import (
+        PROFILE_FORMATS,
+        apply_profile,
+        hot_functions,
+        load_profile,
+        profile_summary,
+    )
+    from .progress import LOG_FORMATS, configure_logging
+    from .pruning import DEFAULT_PRUNE_CENTRALITY
+    from .rules import RuleSet
+    from .query import QueryError, evaluate_query
+    from .reach import compare_reach
+    from .recursion import analyze_recursion, frame_sizes, node_frame_sizes
+    from .sarif import (
+        FINDING_FORMATS,
+        cycle_results,
+        dead_code_results,
+        github_annotations,
+        panic_results,
+        sarif_log,
+        violation_results,
+    )
+    from .segregation import (
+        DEFAULT_MIN_METHODS,
+        DEFAULT_SIMILARITY,
+        segregation_suggestions,
+    )
+    from .snapshot import load_snapshot, save_snapshot
+    from .touches import (
+        touches_rows,
+        touches_summary,
+        touches_to_csv,
+        touches_to_markdown,
+    )
+    from .traits import trait_coverage
+    from .tui import run_tui
+    from .wasm import analyze_wasm, is_wasm
+    from .watch import ChangeWatcher, export_format_for
 except ImportError:
-    from analyzer import CallGraphAnalyzer
+    from algorithms import (
+        const_subgraph,
+        const_violations,
+        dominator_tree,
+        ENTRY_REASONS,
+        entry_point_reason,
+        find_call_paths,
+        find_entry_points,
+        find_recursion_cycles,
+        find_unreachable,
+        is_test_code,
+        reachable_from,
+        shortest_paths_to,
+        tests_reaching,
+    )
+    from analyzer import DEFAULT_EXCLUDE_DIRS, CallGraphAnalyzer
     from analyzer_optimized import CallGraphAnalyzerOptimized
+    from api import analyze
+    from baseline import (
+        DEFAULT_BASELINE_FILE,
+        check_baseline,
+        load_baseline,
+        save_baseline,
+    )
+    from binary import analyze_binary, default_source_root
+    from cargo import crate_name, load_toml
+    from cfg import CFG_MODES, CfgError, CfgSet, combine_cfg, function_cfg
+    from config import CONFIG_FILE_NAME, apply_config, find_config, load_config
+    from coverage_report import (
+        COVERAGE_FORMATS,
+        apply_coverage,
+        coverage_summary,
+        load_coverage,
+    )
     from database import CallGraphDB
+    from dependencies import DEFAULT_DEPENDENCY_DEPTH
+    from diff import diff_graphs, export_revision
+    from duplicates import (
+        DEFAULT_CALLEE_SIMILARITY,
+        DEFAULT_MIN_CALLS,
+        DEFAULT_MIN_TOKENS,
+        find_duplicates,
+    )
+    from effects import EffectSet
+    from errorflow import (
+        DEFAULT_ERROR_DEPTH,
+        HANDLING_LABELS,
+        error_summary,
+        fallible_type,
+        propagation_tree,
+        site_handling,
+    )
+    from exporters import FILE_EXPORTERS, PLANTUML_STYLES, export_graph
+    from features import (
+        DEFAULT_MAX_FEATURES,
+        FeatureMatrix,
+        combination_label,
+        crate_manifests,
+        feature_matrix,
+        manifest_features,
+        parse_feature_set,
+    )
+    from filters import Pattern, SymbolFilter
+    from finder import fuzzy_find, suggestion
+    from graph import CONFIDENCE_LEVELS, EDGE_KINDS, PHASES, CallGraph
+    from history import (
+        HISTORY_STEPS,
+        analyze_history,
+        history_revisions,
+        history_to_csv,
+    )
+    from impact import changed_functions, changed_lines, impact
+    from layers import infer_layers, layers_to_dot, layers_to_mermaid
+    from lsp import CallHierarchyServer, serve
+    from metrics import (
+        CENTRALITY_METHODS,
+        SORT_KEYS,
+        compute_metrics,
+        metrics_to_csv,
+        metrics_to_json,
+        sort_metrics,
+    )
+    from neo4j_loader import PASSWORD_ENV, load_into_neo4j
+    from nostd import DEFAULT_ALLOWED, STD_CRATES, std_calls
+    from owners import CODEOWNERS_LOCATIONS, CodeOwners, team_dependencies
+    from parsers import PANIC_KINDS, PARSER_CLASSES
+    from plugins import load_plugins
+    from profiling import (
+        PROFILE_FORMATS,
+        apply_profile,
+        hot_functions,
+        load_profile,
+        profile_summary,
+    )
+    from progress import LOG_FORMATS, configure_logging
+    from pruning import DEFAULT_PRUNE_CENTRALITY
+    from rules import RuleSet
+    from query import QueryError, evaluate_query
+    from reach import compare_reach
+    from recursion import analyze_recursion, frame_sizes, node_frame_sizes
+    from sarif import (
+        FINDING_FORMATS,
+        cycle_results,
+        dead_code_results,
+        github_annotations,
+        panic_results,
+        sarif_log,
+        violation_results,
+    )
+    from segregation import (
+        DEFAULT_MIN_METHODS,
+        DEFAULT_SIMILARITY,
+        segregation_suggestions,
+    )
+    from snapshot import load_snapshot, save_snapshot
+    from touches import (
+        touches_rows,
+        touches_summary,
+        touches_to_csv,
+        touches_to_markdown,
+    )
+    from traits import trait_coverage
+    from tui import run_tui
+    from wasm import analyze_wasm, is_wasm
+    from watch import ChangeWatcher, export_format_for
+
+# test-reach --verbose 中每个函数列出的测试数量
+TEST_NAMES_SHOWN = 3
+
+
+def _run_analysis(analyzer, args, use_cache: bool):
+    """按命令行参数分析一次项目，返回统计信息"""
+    exclude_dirs = args.exclude.split(",") if args.exclude else None
+    languages = _analysis_languages(args)
+    dependency_depth = args.deps_depth if args.include_deps else 0
+    if hasattr(args, "fast") and args.fast:
+        batch_size = args.batch_size if hasattr(args, "batch_size") else 100
+        return analyzer.analyze_project(
+            args.project_path,
+            exclude_dirs=exclude_dirs,
+            batch_size=batch_size,
+            show_progress=True,
+            use_cache=use_cache,
+            cache_dir=args.cache_dir,
+            expand_macros=args.expand_macros,
+            macro_call_sites=args.macro_call_sites,
+            mir=args.mir,
+            operator_calls=args.operator_calls,
+            drop_glue=args.drop_glue,
+            dependency_depth=dependency_depth,
+            low_memory=args.low_memory,
+            spill_dir=args.spill_dir,
+            languages=languages,
+            ignore_files=not args.no_ignore,
+            cfg=args.cfg,
+            cfg_mode=args.cfg_mode,
+        )
+    return analyzer.analyze_project(
+        args.project_path,
+        exclude_dirs=exclude_dirs,
+        use_cache=use_cache,
+        cache_dir=args.cache_dir,
+        expand_macros=args.expand_macros,
+        macro_call_sites=args.macro_call_sites,
+        mir=args.mir,
+        operator_calls=args.operator_calls,
+        drop_glue=args.drop_glue,
+        dependency_depth=dependency_depth,
+        low_memory=args.low_memory,
+        spill_dir=args.spill_dir,
+        languages=languages,
+        ignore_files=not args.no_ignore,
+        cfg=args.cfg,
+        cfg_mode=args.cfg_mode,
+    )
+
+
+def _analysis_languages(args) -> Optional[List[str]]:
+    """analyze --languages 中的语言列表（未指定时为 None，分析所有语言）"""
+    if not args.languages:
+        return None
+    return [name.strip().lower() for name in args.languages.split(",") if name.strip()]
+
+
+def _export_after_analysis(analyzer, args):
+    """analyze --export：把分析结果导出到文件（格式按扩展名或 --export-format）"""
+    output_format = args.export_format or export_format_for(args.export)
+    options = {"output": args.export} if output_format in FILE_EXPORTERS else {}
+    try:
+        # analyze 的 --exclude 是排除目录，这里只应用配置文件中的过滤模式
+        symbol_filter = SymbolFilter.from_config(args.config) if args.config else None
+        content = analyzer.export_graph(
+            output_format, symbol_filter=symbol_filter, **options
+        )
+    except ValueError as e:
+        print(f"错误: 导出失败: {e}", file=sys.stderr)
+        return
+    if output_format not in FILE_EXPORTERS:
+        with open(args.export, "w", encoding="utf-8") as f:
+            f.write(content)
+    print(f"已导出 {output_format} 格式到: {args.export}")
+
+
+def _watch(analyzer, args):
+    """监视模式：源文件变化时增量重新分析，并重新导出"""
+    exclude_dirs = args.exclude.split(",") if args.exclude else DEFAULT_EXCLUDE_DIRS
+    watcher = ChangeWatcher(
+        args.project_path, exclude_dirs, ignore_files=not args.no_ignore
+    )
+    print(f"\n监视 {args.project_path} 中的源文件变化（{watcher.mode}），按 Ctrl+C 退出")
+    try:
+        while True:
+            changed = watcher.wait()
+            print(f"\n[{time.strftime('%H:%M:%S')}] {len(changed)} 个文件变化:")
+            for path in changed[:10]:
+                print(f"  {path}")
+            if len(changed) > 10:
+                print(f"  ... 等 {len(changed)} 个")
+            start = time.time()
+            with contextlib.redirect_stdout(io.StringIO()):
+                stats = _run_analysis(analyzer, args, use_cache=True)
+            print(
+                f"重新分析完成（{time.time() - start:.2f} 秒）: "
+                f"{stats['total_symbols']} 个符号, {stats['total_relations']} 个调用关系"
+            )
+            if args.export:
+                _export_after_analysis(analyzer, args)
+    except KeyboardInterrupt:
+        print("\n停止监视")
+    finally:
+        watcher.close()
 
 
 def cmd_analyze(args):
     """分析项目命令"""
+    if args.export and not (args.export_format or export_format_for(args.export)):
+        print(
+            f"错误: 无法根据扩展名推断导出格式: {args.export}，请使用 --export-format",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+    if args.include_deps and args.deps_depth < 1:
+        print("错误: --deps-depth 至少为 1", file=sys.stderr)
+        sys.exit(1)
+    unknown = [
+        name for name in _analysis_languages(args) or [] if name not in PARSER_CLASSES
+    ]
+    if unknown:
+        print(
+            f"错误: 不支持的语言: {', '.join(unknown)}"
+            f"（支持: {', '.join(sorted(PARSER_CLASSES))}）",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+    try:
+        CfgSet(args.cfg or [])
+    except CfgError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
     # 根据参数选择分析器
     if hasattr(args, "fast") and args.fast:
         workers = args.workers if hasattr(args, "workers") else None
@@ -32,19 +413,8 @@ def cmd_analyze(args):
             print("清空现有数据...")
             analyzer.db.clear_all()
 
-        if hasattr(args, "fast") and args.fast:
-            batch_size = args.batch_size if hasattr(args, "batch_size") else 100
-            stats = analyzer.analyze_project(
-                args.project_path,
-                exclude_dirs=args.exclude.split(",") if args.exclude else None,
-                batch_size=batch_size,
-                show_progress=True,
-            )
-        else:
-            stats = analyzer.analyze_project(
-                args.project_path,
-                exclude_dirs=args.exclude.split(",") if args.exclude else None,
-            )
+        # 监视模式依赖增量缓存，重新分析时只解析变化的文件
+        stats = _run_analysis(analyzer, args, use_cache=args.cache or args.watch)
 
         if not (hasattr(args, "fast") and args.fast):
             # 优化版本已经打印了详细统计，这里只打印普通版本的
@@ -53,16 +423,144 @@ def cmd_analyze(args):
             print("=" * 50)
             print(json.dumps(stats, indent=2, ensure_ascii=False))
 
+        if args.export:
+            _export_after_analysis(analyzer, args)
+        if args.watch:
+            _watch(analyzer, args)
+
     finally:
         analyzer.close()
 
 
+def cmd_analyze_binary(args):
+    """
+    二进制分析命令：从带调试信息的二进制文件提取单态化、内联之后的调用图；
+    WebAssembly 模块直接解析指令流（包括导入的宿主函数）
+    """
+    source_root = args.source_root or default_source_root(args.binary)
+    db = CallGraphDB(args.database)
+    try:
+        if args.clear:
+            print("清空现有数据...")
+            db.clear_all()
+        if is_wasm(args.binary):
+            stats = analyze_wasm(db, args.binary)
+        else:
+            if not args.all_symbols:
+                print(f"只保留源码位于 {source_root} 下的函数")
+            stats = analyze_binary(
+                db,
+                args.binary,
+                source_root=source_root,
+                all_symbols=args.all_symbols,
+                objdump=args.objdump,
+            )
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    finally:
+        db.close()
+    print("\n" + "=" * 50)
+    print("分析统计:")
+    print("=" * 50)
+    print(json.dumps(stats, indent=2, ensure_ascii=False))
+
+
+def _open_query_db(args) -> CallGraphDB:
+    """query 使用的数据库：指定 --snapshot 时从快照加载到内存数据库"""
+    if not args.snapshot:
+        return CallGraphDB(args.database)
+    db = CallGraphDB(":memory:")
+    try:
+        with open(args.snapshot, "rb") as f:
+            load_snapshot(db, f)
+    except (OSError, ValueError) as e:
+        db.close()
+        print(f"错误: 无法加载快照: {e}", file=sys.stderr)
+        sys.exit(1)
+    return db
+
+
+def _query_expression(db: CallGraphDB, args):
+    """query --expr：求值查询表达式，输出匹配的函数列表"""
+    graph = CallGraph.from_db(db)
+    try:
+        matches = evaluate_query(graph, args.function)
+    except QueryError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    ordered = sorted(
+        matches,
+        key=lambda n: (graph.qualified_name(n), graph.nodes[n].get("file") or ""),
+    )
+    if args.format == "json":
+        document = {
+            "expression": args.function,
+            "functions": [
+                {
+                    "id": node_id,
+                    "name": graph.nodes[node_id]["name"],
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id].get("file"),
+                    "line": graph.nodes[node_id].get("start_line"),
+                }
+                for node_id in ordered
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+    print(f"\n查询: {args.function}\n")
+    if not ordered:
+        print("没有匹配的函数")
+        return
+    print(f"找到 {len(ordered)} 个函数:")
+    for i, node_id in enumerate(ordered, 1):
+        node = graph.nodes[node_id]
+        print(
+            f"{i}. {graph.qualified_name(node_id)}"
+            f"({node.get('file')}:{node.get('start_line')})"
+        )
+
+
 def cmd_query(args):
     """查询命令"""
-    db = CallGraphDB(args.database)
+    db = _open_query_db(args)
 
     try:
-        if args.callers:
+        if args.expr:
+            _query_expression(db, args)
+
+        elif args.format == "json" and args.dominators:
+            graph = CallGraph.from_db(db)
+            idom = dominator_tree(graph, graph.find(args.function))
+            document = {
+                "function": args.function,
+                "dominators": _dominator_entries(graph, idom),
+            }
+            print(json.dumps(document, ensure_ascii=False, indent=2))
+
+        elif args.format == "json":
+            if not (args.callers or args.callees):
+                print(
+                    "错误: --format json 只支持 --expr、--callers、--callees "
+                    "和 --dominators",
+                    file=sys.stderr,
+                )
+                sys.exit(1)
+            direction = "callers" if args.callers else "callees"
+            if args.callers:
+                results = db.get_callers(args.function)
+            else:
+                results = db.get_callees(args.function)
+            document = {
+                "function": args.function,
+                direction: _query_entries(
+                    db, results, direction, args.show_call_sites
+                ),
+            }
+            print(json.dumps(document, ensure_ascii=False, indent=2))
+
+        elif args.callers:
             # 查询调用者
             print(f"\n查询调用 '{args.function}' 的所有函数:\n")
             results = db.get_callers(args.function)
@@ -94,14 +592,24 @@ def cmd_query(args):
                         func_display = f"{caller_name}({info['file']}:{info['line']})"
                     else:
                         func_display = f"{caller_name}({rels[0]['caller_file'] or 'unknown'})"
+                    for kind in (
+                        "dynamic",
+                        "indirect",
+                        "operator",
+                        "drop",
+                        "annotated",
+                        "ffi",
+                        "spawn",
+                    ):
+                        if any(rel.get("edge_kind") == kind for rel in rels):
+                            func_display += f" [{kind}]"
 
                     # 在 verbose 模式下显示所有调用点
-                    if args.verbose and len(rels) > 0:
+                    if (args.verbose or args.show_call_sites) and len(rels) > 0:
                         print(f"{i}. {func_display}")
                         for j, rel in enumerate(rels, 1):
-                            print(
-                                f"   调用点 {j}: {rel['caller_file']}:{rel['call_site_line']}"
-                            )
+                            site = _call_site_text(rel, args.show_call_sites)
+                            print(f"   调用点 {j}: {site}")
                     else:
                         # 非 verbose 模式，只显示函数定义位置
                         if len(rels) > 1:
@@ -118,26 +626,44 @@ def cmd_query(args):
                 print(f"'{args.function}' 没有调用其他函数")
             else:
                 # 按被调用者分组（去重）
+                # 动态分发的多个实现、间接调用的多个候选、不同类型的运算符和 Drop
+                # 实现可能同名，按符号 ID 区分
                 callee_groups = {}
                 for rel in results:
                     callee_name = rel["callee_name"]
-                    if callee_name not in callee_groups:
-                        callee_groups[callee_name] = []
-                    callee_groups[callee_name].append(rel)
+                    if rel.get("edge_kind") in (
+                        "dynamic",
+                        "indirect",
+                        "operator",
+                        "drop",
+                    ):
+                        key = (callee_name, rel["callee_id"])
+                    else:
+                        key = (callee_name, "")
+                    if key not in callee_groups:
+                        callee_groups[key] = []
+                    callee_groups[key].append(rel)
 
                 # 批量获取所有被调用者的函数信息
                 callee_info_map = {}
-                for callee_name in callee_groups.keys():
-                    info = db.get_function_info(callee_name)
+                for callee_name, callee_id in callee_groups.keys():
+                    if callee_id:
+                        symbol = db.get_symbol(callee_id)
+                        info = (
+                            {"file": symbol["file"], "line": symbol["start_line"]}
+                            if symbol
+                            else None
+                        )
+                    else:
+                        info = db.get_function_info(callee_name)
                     if info:
-                        callee_info_map[callee_name] = info
+                        callee_info_map[(callee_name, callee_id)] = info
 
                 # 显示结果（已去重）
-                for i, (callee_name, rels) in enumerate(
-                    sorted(callee_groups.items()), 1
-                ):
-                    if callee_name in callee_info_map:
-                        info = callee_info_map[callee_name]
+                for i, (key, rels) in enumerate(sorted(callee_groups.items()), 1):
+                    callee_name = key[0]
+                    if key in callee_info_map:
+                        info = callee_info_map[key]
                         func_display = f"{callee_name}({info['file']}:{info['line']})"
                     else:
                         # 外部函数或未找到定义
@@ -146,14 +672,25 @@ def cmd_query(args):
                             if rels[0]["callee_file"]
                             else f"{callee_name}(external)"
                         )
+                    if key[1]:
+                        func_display += f" [{rels[0]['edge_kind']}]"
+                    else:
+                        for kind in ("closure", "ffi", "spawn"):
+                            if any(rel.get("edge_kind") == kind for rel in rels):
+                                func_display += f" [{kind}]"
+                    # 泛型函数各调用点使用的类型实参
+                    type_args = sorted(
+                        {rel["type_args"] for rel in rels if rel.get("type_args")}
+                    )
+                    if type_args:
+                        func_display += " " + " ".join(f"<{a}>" for a in type_args)
 
                     # 在 verbose 模式下显示所有调用点
-                    if args.verbose and len(rels) > 0:
+                    if (args.verbose or args.show_call_sites) and len(rels) > 0:
                         print(f"{i}. {func_display}")
                         for j, rel in enumerate(rels, 1):
-                            print(
-                                f"   调用点 {j}: {rel['caller_file']}:{rel['call_site_line']}"
-                            )
+                            site = _call_site_text(rel, args.show_call_sites)
+                            print(f"   调用点 {j}: {site}")
                     else:
                         # 非 verbose 模式，只显示函数定义位置
                         if len(rels) > 1:
@@ -161,6 +698,31 @@ def cmd_query(args):
                         else:
                             print(f"{i}. {func_display}")
 
+        elif args.reverse:
+            # 查询调用者树（向上，传递闭包）
+            print(f"\n'{args.function}' 的调用者树 (深度={args.depth}):\n")
+            graph = CallGraph.from_db(db)
+            targets = graph.find(args.function)
+
+            if not targets:
+                print(f"没有找到函数 '{args.function}'")
+            for target_id in targets:
+                count = _print_call_tree(graph, target_id, args.depth, callers=True)
+                print(f"\n共 {count} 个直接或间接调用者\n")
+
+        elif args.dominators:
+            # 查询支配树（向下）
+            print(f"\n从 '{args.function}' 出发的支配树 (深度={args.depth}):\n")
+            graph = CallGraph.from_db(db)
+            roots = graph.find(args.function)
+
+            if not roots:
+                print(f"没有找到函数 '{args.function}'")
+            else:
+                idom = dominator_tree(graph, roots)
+                _print_dominator_tree(graph, idom, args.depth)
+                print(f"\n共 {len(idom)} 个可达函数")
+
         elif args.chain:
             # 查询调用链
             print(f"\n'{args.function}' 的调用链 (深度={args.depth}):\n")
@@ -265,7 +827,10 @@ def cmd_query(args):
                             print(f"  已截断: 是（达到 {result['max_paths']} 条限制）")
 
         else:
-            print("请指定查询类型: --callers, --callees, --chain, 或 --fullpath")
+            print(
+                "请指定查询类型: --expr, --callers, --callees, --reverse, "
+                "--dominators, --chain, 或 --fullpath"
+            )
             sys.exit(1)
 
     finally:
@@ -295,6 +860,81 @@ def cmd_search(args):
         db.close()
 
 
+def _pick(count: int) -> Optional[int]:
+    """从标准输入读取选择的编号（1 ~ count），取消或输入无效时返回 None"""
+    while True:
+        sys.stderr.write(f"选择编号（1-{count}，回车取消）: ")
+        sys.stderr.flush()
+        line = sys.stdin.readline()
+        text = line.strip()
+        if not text:
+            return None
+        if text.isdigit() and 1 <= int(text) <= count:
+            return int(text) - 1
+        if not line.endswith("\n"):
+            return None
+        sys.stderr.write(f"无效的编号: {text}\n")
+
+
+def cmd_find(args):
+    """
+    模糊查找函数：按限定名的子串、单词和子序列匹配，列出候选函数及其位置；
+    --select 时交互选择一个，把它的限定名输出到标准输出（候选列表输出到
+    标准错误），可直接用于其他命令的参数
+    """
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+    matches = fuzzy_find(graph, args.pattern, args.limit)
+
+    if args.format == "json" and not args.select:
+        entries = []
+        for node_id, score in matches:
+            node = graph.nodes[node_id]
+            entries.append(
+                {
+                    "id": node_id,
+                    "name": node["name"],
+                    "qualified_name": graph.qualified_name(node_id),
+                    "kind": graph.node_kind(node_id),
+                    "file": node["file"],
+                    "line": node.get("start_line"),
+                    "score": score,
+                }
+            )
+        print(
+            json.dumps(
+                {"pattern": args.pattern, "matches": entries},
+                ensure_ascii=False,
+                indent=2,
+            )
+        )
+        return
+
+    if not matches:
+        print(f"没有找到匹配 '{args.pattern}' 的函数", file=sys.stderr)
+        sys.exit(1)
+    out = sys.stderr if args.select else sys.stdout
+    if not (args.select and len(matches) == 1):
+        print(f"\n匹配 '{args.pattern}' 的函数（{len(matches)} 个）:", file=out)
+        for i, (node_id, _) in enumerate(matches, 1):
+            node = graph.nodes[node_id]
+            location = f"{node['file']}:{node['start_line']}"
+            print(
+                f"  {i}. {graph.qualified_name(node_id)}  ({location})"
+                f"  [{graph.node_kind(node_id)}]",
+                file=out,
+            )
+    if not args.select:
+        return
+    choice = 0 if len(matches) == 1 else _pick(len(matches))
+    if choice is None:
+        sys.exit(1)
+    print(graph.qualified_name(matches[choice][0]))
+
+
 def cmd_stats(args):
     """统计命令"""
     db = CallGraphDB(args.database)
@@ -320,184 +960,5021 @@ def cmd_stats(args):
         db.close()
 
 
-def cmd_export(args):
-    """导出命令"""
-    analyzer = CallGraphAnalyzer(args.database)
+def cmd_save(args):
+    """把分析数据库保存为快照文件"""
+    db = CallGraphDB(args.database)
+    try:
+        with open(args.output, "wb") as f:
+            counts = save_snapshot(db, f)
+    except OSError as e:
+        print(f"错误: 无法写入快照: {e}", file=sys.stderr)
+        sys.exit(1)
+    finally:
+        db.close()
+    size = os.path.getsize(args.output)
+    print(
+        f"已保存: {counts['symbols']} 个符号, {counts['call_relations']} 个调用关系 "
+        f"-> {args.output} ({size} 字节)"
+    )
 
+
+def cmd_load(args):
+    """从快照文件恢复分析数据库（替换数据库中已有的数据）"""
+    db = CallGraphDB(args.database)
     try:
-        print(f"导出调用图为 {args.format} 格式...")
+        with open(args.input, "rb") as f:
+            counts = load_snapshot(db, f)
+    except (OSError, ValueError) as e:
+        print(f"错误: 无法加载快照: {e}", file=sys.stderr)
+        sys.exit(1)
+    finally:
+        db.close()
+    print(
+        f"已加载: {counts['symbols']} 个符号, {counts['call_relations']} 个调用关系 "
+        f"-> {args.database}"
+    )
 
-        content = analyzer.export_graph(args.format)
 
-        if args.output:
-            with open(args.output, "w", encoding="utf-8") as f:
-                f.write(content)
-            print(f"已保存到: {args.output}")
-        else:
-            print(content)
+def _symbol_filter(args) -> SymbolFilter:
+    """合并配置文件和命令行中的 --include/--exclude 模式、--phase 和 --edge-kinds"""
+    edge_kinds = [
+        kind.strip()
+        for value in args.edge_kinds or []
+        for kind in value.split(",")
+        if kind.strip()
+    ]
+    try:
+        symbol_filter = SymbolFilter(args.include, args.exclude, args.phase, edge_kinds)
+        if args.config:
+            symbol_filter = SymbolFilter.from_config(args.config).merge(symbol_filter)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    return symbol_filter
 
-    finally:
-        analyzer.close()
 
+def _load_graph(db: CallGraphDB, args) -> CallGraph:
+    """从数据库加载调用图，并应用包含/排除过滤、最低置信度和 --public-only"""
+    graph = _symbol_filter(args).apply(CallGraph.from_db(db))
+    if args.min_confidence:
+        graph = graph.with_min_confidence(args.min_confidence)
+    if args.public_only:
+        graph = graph.public_only()
+    return graph
 
-def main():
-    """主函数"""
-    parser = argparse.ArgumentParser(
-        description="多语言函数调用关系分析工具",
-        formatter_class=argparse.RawDescriptionHelpFormatter,
-        epilog="""
-使用示例:
 
-  # 分析项目（清空旧数据）
-  python call-graph.py --database myproject.db analyze /path/to/project --clear
-  
-  # 分析项目（使用性能优化模式，适合大型项目）⚡
-  python call-graph.py --database myproject.db analyze /path/to/project --clear --fast
-  
-  # 性能优化模式（自定义参数）
-  python call-graph.py --database myproject.db analyze /path/to/project --clear --fast --workers 8 --batch-size 200
-  
-  # 分析项目（排除特定目录）
-  python call-graph.py --database myproject.db analyze /path/to/project --exclude "node_modules,build"
-  
-  # 查看统计信息
-  python call-graph.py --database myproject.db stats
-  
-  # 查询谁调用了某个函数
-  python call-graph.py --database myproject.db query main --callers
-  
-  # 查询某个函数调用了哪些函数
-  python call-graph.py --database myproject.db query process_data --callees
-  
-  # 查询调用链（向下）
-  python call-graph.py --database myproject.db query main --chain --depth 3
-  
-  # 查询完整调用路径（向上+向下）
-  python call-graph.py --database myproject.db query process_data --fullpath
-  
-  # 查询完整路径并显示详细信息
-  python call-graph.py --database myproject.db query validate_input --fullpath --verbose
-  
-  # 搜索函数（模糊匹配）
-  python call-graph.py --database myproject.db search "process"
-  
-  # 搜索函数（显示详细信息）
-  python call-graph.py --database myproject.db search "calculate" --verbose
-  
-  # 导出调用图为 DOT 格式
-  python call-graph.py --database myproject.db export --output graph.dot
-  
-  # 使用 Graphviz 生成可视化图片
-  dot -Tpng graph.dot -o graph.png
+def _node_display(graph: CallGraph, node_id: str) -> str:
+    """格式化节点：函数名(文件:行号)"""
+    node = graph.nodes[node_id]
+    return f"{node['name']}({node['file']}:{node['start_line']})"
 
-安装依赖:
-  pip install -e .
 
-注意：
-  1. --database 参数必须放在子命令之前
-  2. 首次使用需要先安装依赖（见上方）
-  3. 首次分析建议使用 --clear 清空旧数据
-  4. 默认排除 node_modules, .git, __pycache__ 等目录
-  
-其他运行方式：
-  # 使用 Python 模块方式
-  python -m call_graph --database myproject.db stats
-  
-  # 如果已安装到系统（推荐日常使用）
-  call-graph --database myproject.db stats
-        """,
-    )
+def _call_site_text(rel, detailed: bool) -> str:
+    """
+    调用点的显示文本：文件:行号；detailed 时为 文件:行号:列号（列号从 1 开始，
+    可直接在编辑器中跳转）加源码片段
+    """
+    text = f"{rel['caller_file']}:{rel['call_site_line']}"
+    if not detailed:
+        return text
+    if rel.get("call_site_column") is not None:
+        text += f":{rel['call_site_column'] + 1}"
+    if rel.get("call_site_snippet"):
+        text += f"  {rel['call_site_snippet']}"
+    return text
 
-    parser.add_argument(
-        "--database",
-        "-d",
-        default="call_graph.db",
-        help="数据库文件路径 (默认: call_graph.db)",
+
+def _query_entries(
+    db: CallGraphDB, results, direction: str, show_call_sites: bool
+) -> List[dict]:
+    """
+    query --format json 的结果：每个调用者/被调用者（按符号 ID 合并）一项，
+    show_call_sites 时附带每个调用点
+    """
+    id_key, name_key, file_key = (
+        ("caller_id", "caller_name", "caller_file")
+        if direction == "callers"
+        else ("callee_id", "callee_name", "callee_file")
     )
+    groups = {}
+    for rel in results:
+        groups.setdefault(rel[id_key], []).append(rel)
 
-    subparsers = parser.add_subparsers(dest="command", help="子命令")
+    entries = []
+    for symbol_id, rels in groups.items():
+        symbol = db.get_symbol(symbol_id)
+        entry = {
+            "id": symbol_id if symbol else None,
+            "name": rels[0][name_key],
+            "file": symbol["file"] if symbol else rels[0][file_key],
+            "line": symbol["start_line"] if symbol else None,
+            "kind": rels[0].get("edge_kind") or "static",
+            "count": len(rels),
+        }
+        type_args = sorted({rel["type_args"] for rel in rels if rel.get("type_args")})
+        if type_args:
+            entry["type_args"] = type_args
+        if show_call_sites:
+            entry["call_sites"] = [
+                {
+                    "file": rel["caller_file"],
+                    "line": rel["call_site_line"],
+                    "column": rel["call_site_column"],
+                    "snippet": rel.get("call_site_snippet"),
+                }
+                for rel in rels
+            ]
+        entries.append(entry)
+    entries.sort(key=lambda e: (e["name"], e["file"] or "", e["line"] or 0))
+    return entries
 
-    # analyze命令
-    analyze_parser = subparsers.add_parser("analyze", help="分析项目")
-    analyze_parser.add_argument("project_path", help="项目路径")
-    analyze_parser.add_argument("--exclude", "-e", help="要排除的目录，用逗号分隔")
-    analyze_parser.add_argument(
-        "--clear", "-c", action="store_true", help="清空现有数据"
-    )
-    analyze_parser.add_argument(
-        "--fast", "-f", action="store_true", help="使用性能优化模式（多进程+批量操作）"
-    )
-    analyze_parser.add_argument(
-        "--workers",
-        "-w",
-        type=int,
-        default=None,
-        help="工作进程数（默认：CPU核心数-1）",
-    )
-    analyze_parser.add_argument(
-        "--batch-size",
-        "-b",
-        type=int,
-        default=100,
-        help="批量插入数据库的大小（默认：100）",
-    )
 
-    # query命令
-    query_parser = subparsers.add_parser("query", help="查询调用关系")
-    query_parser.add_argument("function", help="函数名称")
-    query_parser.add_argument(
-        "--callers", action="store_true", help="查询谁调用了这个函数"
-    )
-    query_parser.add_argument(
-        "--callees", action="store_true", help="查询这个函数调用了谁"
-    )
-    query_parser.add_argument("--chain", action="store_true", help="查询调用链（向下）")
-    query_parser.add_argument(
-        "--fullpath",
-        action="store_true",
-        help="查询完整调用路径（向上追溯到入口，向下追溯到叶子）",
-    )
-    query_parser.add_argument(
-        "--depth", type=int, default=10, help="最大搜索深度 (默认: 10)"
-    )
-    query_parser.add_argument(
-        "--verbose", "-v", action="store_true", help="显示详细信息（包括完整路径）"
-    )
+# 树形输出的连接线：(中间分支, 最后一个分支, 竖线缩进, 空白缩进)
+TREE_CHARSETS = {
+    "utf8": ("├── ", "└── ", "│   ", "    "),
+    "ascii": ("|-- ", "`-- ", "|   ", "    "),
+}
 
-    # search命令
-    search_parser = subparsers.add_parser("search", help="搜索符号")
-    search_parser.add_argument("pattern", help="搜索模式")
-    search_parser.add_argument(
-        "--verbose", "-v", action="store_true", help="显示详细信息"
-    )
 
-    # stats命令
-    subparsers.add_parser("stats", help="显示统计信息")
+def _print_call_tree(
+    graph: CallGraph,
+    root_id: str,
+    max_depth: int,
+    callers: bool = False,
+    charset: str = "utf8",
+    dedupe: bool = True,
+) -> int:
+    """
+    以树形打印从 root_id 出发的传递被调用者（callers 为 True 时为传递调用者，
+    子节点是调用父节点的函数）
 
-    # export命令
-    export_parser = subparsers.add_parser("export", help="导出调用图")
-    export_parser.add_argument(
-        "--format", "-f", default="dot", choices=["dot"], help="导出格式 (默认: dot)"
-    )
-    export_parser.add_argument("--output", "-o", help="输出文件路径")
+    递归调用标注 (递归) 不再展开；dedupe 时已展开过的函数只标注 (见上文)，
+    避免输出随路径数量膨胀
 
-    args = parser.parse_args()
+    Returns:
+        树中出现的函数数量（不含 root_id 本身）
+    """
+    middle, last_branch, pipe, blank = TREE_CHARSETS[charset]
+    neighbors = graph.predecessors if callers else graph.successors
+    print(_node_display(graph, root_id))
+    expanded = {root_id}
+    shown = set()
 
-    if not args.command:
+    def walk(node_id: str, depth: int, prefix: str, path: set):
+        children = sorted(
+            neighbors(node_id),
+            key=lambda n: (graph.nodes[n]["name"], graph.nodes[n]["file"]),
+        )
+        for i, child_id in enumerate(children):
+            last = i == len(children) - 1
+            shown.add(child_id)
+            if callers:
+                sites = graph.call_sites(child_id, node_id)
+            else:
+                sites = graph.call_sites(node_id, child_id)
+            kind = sites[0].get("edge_kind")
+            marker = f" [{kind}]" if kind and kind != "static" else ""
+
+            recurse = False
+            if child_id in path:
+                marker += " (递归)"
+            elif dedupe and child_id in expanded:
+                marker += " (见上文)"
+            elif depth >= max_depth:
+                if neighbors(child_id):
+                    marker += " ..."
+            else:
+                recurse = True
+
+            branch = last_branch if last else middle
+            print(f"{prefix}{branch}{_node_display(graph, child_id)}{marker}")
+            if recurse:
+                expanded.add(child_id)
+                walk(
+                    child_id,
+                    depth + 1,
+                    prefix + (blank if last else pipe),
+                    path | {child_id},
+                )
+
+    walk(root_id, 1, "", {root_id})
+    shown.discard(root_id)
+    return len(shown)
+
+
+def _dominator_children(
+    graph: CallGraph, idom: Dict[str, Optional[str]]
+) -> Tuple[Dict[Optional[str], List[str]], Dict[str, int]]:
+    """
+    支配树的子节点表（None 下为没有直接支配者的入口）和每个函数支配的函数数量
+    （子树大小，不含自身）；子节点按支配数量降序、名称升序排列
+    """
+    children: Dict[Optional[str], List[str]] = {}
+    for node_id, dominator in idom.items():
+        children.setdefault(dominator, []).append(node_id)
+
+    dominated: Dict[str, int] = {}
+    # 后序累计子树大小（迭代实现，避免深树递归过深）
+    stack = [(node_id, False) for node_id in children.get(None, [])]
+    while stack:
+        node_id, done = stack.pop()
+        if done:
+            dominated[node_id] = sum(
+                dominated[child] + 1 for child in children.get(node_id, [])
+            )
+            continue
+        stack.append((node_id, True))
+        stack.extend((child, False) for child in children.get(node_id, []))
+
+    for members in children.values():
+        members.sort(
+            key=lambda n: (
+                -dominated[n],
+                graph.nodes[n]["name"],
+                graph.nodes[n]["file"],
+            )
+        )
+    return children, dominated
+
+
+def _print_dominator_tree(
+    graph: CallGraph, idom: Dict[str, Optional[str]], max_depth: int
+):
+    """以树形打印支配树：子节点的每条入口调用路径都经过父节点"""
+    children, dominated = _dominator_children(graph, idom)
+
+    def label(node_id: str) -> str:
+        count = dominated[node_id]
+        suffix = f"  [支配 {count} 个函数]" if count else ""
+        return f"{_node_display(graph, node_id)}{suffix}"
+
+    def walk(node_id: str, depth: int, prefix: str):
+        members = children.get(node_id, [])
+        for i, child in enumerate(members):
+            last = i == len(members) - 1
+            branch = "└── " if last else "├── "
+            more = " ..." if depth >= max_depth and children.get(child) else ""
+            print(f"{prefix}{branch}{label(child)}{more}")
+            if depth < max_depth:
+                walk(child, depth + 1, prefix + ("    " if last else "│   "))
+
+    for root_id in children.get(None, []):
+        print(label(root_id))
+        walk(root_id, 1, "")
+
+
+def _dominator_entries(
+    graph: CallGraph, idom: Dict[str, Optional[str]]
+) -> List[dict]:
+    """query --dominators --format json 的结果：完整的嵌套支配树"""
+    children, dominated = _dominator_children(graph, idom)
+
+    def entry(node_id: str) -> dict:
+        node = graph.nodes[node_id]
+        return {
+            "id": node_id,
+            "name": node["name"],
+            "file": node["file"],
+            "line": node.get("start_line"),
+            "dominated": dominated[node_id],
+            "children": [entry(child) for child in children.get(node_id, [])],
+        }
+
+    return [entry(root_id) for root_id in children.get(None, [])]
+
+
+def cmd_tree(args):
+    """调用树命令：类似 cargo tree，以树形打印函数的传递被调用者（或调用者）"""
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    roots = graph.resolve(args.function)
+    if not roots:
+        print(
+            f"错误: 没有找到函数: {args.function}"
+            f"{suggestion(graph, args.function)}",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+
+    noun = "调用者" if args.invert else "被调用者"
+    for i, root_id in enumerate(sorted(roots, key=lambda n: _node_display(graph, n))):
+        if i:
+            print()
+        count = _print_call_tree(
+            graph,
+            root_id,
+            args.depth,
+            callers=args.invert,
+            charset=args.charset,
+            dedupe=not args.no_dedupe,
+        )
+        print(f"\n共 {count} 个直接或间接{noun}")
+
+
+def cmd_api(args):
+    """
+    公开 API 命令：列出从 crate 根可以访问的 pub 函数（见 cargo 模块的
+    api_paths 标注）及其调用的内部函数
+    """
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    api = {}
+    for node_id, node in graph.nodes.items():
+        extras = node.get("extras") or {}
+        if not extras.get("api_paths"):
+            continue
+        if args.crate and extras.get("crate") not in args.crate:
+            continue
+        api[node_id] = extras["api_paths"]
+    if not api:
+        print("\n没有找到公开 API（只支持 Cargo 库 crate 中的 Rust 函数）")
+        return
+
+    # 内部函数：公开 API 直接或间接调用、但本身不是公开 API 的函数
+    internal = {}
+    for node_id in api:
+        internal[node_id] = sorted(
+            (n for n in reachable_from(graph, [node_id]) if n not in api),
+            key=lambda n: (graph.qualified_name(n), graph.nodes[n]["file"] or ""),
+        )
+    dragged = set().union(*internal.values())
+    ordered = sorted(api, key=lambda n: (api[n][0], graph.nodes[n]["file"] or ""))
+
+    if args.format == "json":
+        items = []
+        for node_id in ordered:
+            node = graph.nodes[node_id]
+            items.append(
+                {
+                    "id": node_id,
+                    "path": api[node_id][0],
+                    "paths": api[node_id],
+                    "crate": node["extras"].get("crate"),
+                    "file": node["file"],
+                    "line": node.get("start_line"),
+                    "internal": [graph.qualified_name(n) for n in internal[node_id]],
+                }
+            )
+        report = {"items": items, "internal_functions": len(dragged)}
+        print(json.dumps(report, ensure_ascii=False, indent=2))
+        return
+
+    by_crate = {}
+    for node_id in api:
+        crate = graph.nodes[node_id]["extras"].get("crate")
+        by_crate[crate] = by_crate.get(crate, 0) + 1
+    summary = ", ".join(f"{c} {n} 个" for c, n in sorted(by_crate.items()))
+    print(f"\n公开 API: {len(api)} 个函数（{summary}）")
+    for node_id in ordered:
+        print(f"\n{api[node_id][0]}  内部函数 {len(internal[node_id])} 个")
+        for alias in api[node_id][1:]:
+            print(f"  也可通过 {alias} 访问")
+        if args.depth > 0:
+            _print_call_tree(graph, node_id, args.depth, charset=args.charset)
+        else:
+            print(_node_display(graph, node_id))
+
+    percent = 100.0 * len(dragged) / len(graph.nodes)
+    print(
+        f"\n公开 API 共用到 {len(dragged)} 个内部函数"
+        f"（占全部 {len(graph.nodes)} 个函数的 {percent:.1f}%）"
+    )
+
+
+def _print_findings(results: List[dict], output_format: str):
+    """
+    输出分析结果：SARIF 日志（--format sarif）或 GitHub Actions 注释
+    （--format github）
+    """
+    if output_format == "github":
+        for line in github_annotations(results):
+            print(line)
+        return
+    print(json.dumps(sarif_log(results), ensure_ascii=False, indent=2))
+
+
+def _bound_text(analysis: dict) -> str:
+    """递归是否有界的说明：有界（n - 1，第 12 行）或 未发现递减参数（可能无界）"""
+    if not analysis["bounded"]:
+        return "未发现递减参数（可能无界）"
+    evidence = analysis["evidence"][0]
+    kind = "结构递归" if evidence["kind"] == "structural" else "递减参数"
+    return f"有界，{kind} {evidence['argument']}（第 {evidence['line']} 行）"
+
+
+def _stack_text(analysis: dict, stack_limit: Optional[int]) -> Optional[str]:
+    """每层栈使用的说明（没有栈帧估计时为 None）"""
+    stack = analysis["stack"]
+    if not stack:
+        return None
+    size = f"{stack['bytes']} 字节" if stack["exact"] else f"至少 {stack['bytes']} 字节"
+    text = f"每层 {size}"
+    if analysis["max_depth"] is not None:
+        text += f"，{stack_limit} 字节的栈最多约 {analysis['max_depth']} 层"
+    return text
+
+
+def cmd_cycles(args):
+    """递归环检测命令：列出递归环，判断递归是否有界，可选估计栈使用"""
+    if args.stack_limit is not None and not args.binary:
+        print("错误: --stack-limit 需要与 --binary 一起使用", file=sys.stderr)
+        sys.exit(1)
+    if args.stack_limit is not None and args.stack_limit < 1:
+        print(f"错误: 栈大小必须大于 0: {args.stack_limit}", file=sys.stderr)
+        sys.exit(1)
+
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+        cycles = find_recursion_cycles(graph)
+
+        if args.format in FINDING_FORMATS:
+            _print_findings(cycle_results(graph, cycles), args.format)
+            return
+
+        frames = None
+        if args.binary:
+            try:
+                frames = node_frame_sizes(graph, frame_sizes(args.binary))
+            except ValueError as e:
+                print(f"错误: {e}", file=sys.stderr)
+                sys.exit(1)
+        analyses = analyze_recursion(graph, cycles, frames, args.stack_limit)
+
+        if args.format == "json":
+            document = {
+                "cycles": [
+                    {
+                        "kind": cycle["kind"],
+                        "members": [graph.qualified_name(n) for n in cycle["members"]],
+                        "cycle": [graph.qualified_name(n) for n in cycle["cycle"]],
+                        "bounded": analysis["bounded"],
+                        "evidence": [
+                            {
+                                **evidence,
+                                "caller": graph.qualified_name(evidence["caller"]),
+                                "callee": graph.qualified_name(evidence["callee"]),
+                            }
+                            for evidence in analysis["evidence"]
+                        ],
+                        "stack": analysis["stack"],
+                        "max_depth": analysis["max_depth"],
+                        "overflow": analysis["overflow"],
+                    }
+                    for cycle, analysis in zip(cycles, analyses)
+                ]
+            }
+            print(json.dumps(document, ensure_ascii=False, indent=2))
+            return
+
+        if not cycles:
+            print("\n没有发现递归调用")
+            return
+
+        direct = sum(1 for c in cycles if c["kind"] == "direct")
+        print(
+            f"\n发现 {len(cycles)} 个递归环"
+            f"（直接递归 {direct} 个，相互递归 {len(cycles) - direct} 组）:\n"
+        )
+
+        for i, (cycle, analysis) in enumerate(zip(cycles, analyses), 1):
+            members = cycle["members"]
+            if cycle["kind"] == "direct":
+                print(f"{i}. 直接递归: {_node_display(graph, members[0])}")
+            else:
+                print(f"{i}. 相互递归（{len(members)} 个函数）:")
+                for node_id in members:
+                    print(f"   - {_node_display(graph, node_id)}")
+
+            if cycle["cycle"]:
+                names = [graph.nodes[n]["name"] for n in cycle["cycle"]]
+                print(f"   环: {' -> '.join(names)}")
+            print(f"   有界: {_bound_text(analysis)}")
+            stack = _stack_text(analysis, args.stack_limit)
+            if stack:
+                print(f"   栈: {stack}")
+            if analysis["overflow"]:
+                print("   警告: 没有找到递减参数，递归深度超过上述层数时栈溢出")
+
+            # verbose 模式下显示环上每条边的调用点
+            if args.verbose and cycle["cycle"]:
+                path = cycle["cycle"]
+                for caller_id, callee_id in zip(path, path[1:]):
+                    for site in graph.call_sites(caller_id, callee_id):
+                        print(
+                            f"   调用点: {graph.nodes[caller_id]['name']} -> "
+                            f"{graph.nodes[callee_id]['name']} "
+                            f"({site['caller_file']}:{site['call_site_line']})"
+                        )
+            print()
+
+        unbounded = sum(1 for a in analyses if not a["bounded"])
+        print(f"其中 {unbounded} 个递归环没有找到明显的递减参数")
+        if args.binary:
+            overflow = sum(1 for a in analyses if a["overflow"])
+            missing = sum(1 for a in analyses if not a["stack"])
+            if args.stack_limit:
+                print(
+                    f"可能栈溢出的递归环: {overflow} 个（栈大小 {args.stack_limit} 字节）"
+                )
+            if missing:
+                print(f"{missing} 个递归环中有函数在二进制文件中没有栈帧信息")
+
+    finally:
+        db.close()
+
+
+def _entry_roots(graph: CallGraph, args) -> dict:
+    """默认入口点（--no-default-roots 时不使用）加上 --root 指定的函数：{节点ID: 原因}"""
+    roots = {} if args.no_default_roots else find_entry_points(graph)
+    for name in args.root or []:
+        matches = graph.resolve(name)
+        if not matches:
+            print(f"警告: 没有找到入口函数 '{name}'", file=sys.stderr)
+        for node_id in matches:
+            roots[node_id] = "user"
+    return roots
+
+
+def _root_detail(graph: CallGraph, node_id: str) -> str:
+    """入口点的补充说明：二进制目标名、ABI 或过程宏的属性"""
+    extras = graph.nodes[node_id].get("extras") or {}
+    if extras.get("bin"):
+        return f"bin: {extras['bin']}"
+    if extras.get("build_script"):
+        return "build.rs"
+    if extras.get("abi"):
+        return f'extern "{extras["abi"]}"'
+    for attribute in extras.get("attributes") or []:
+        if attribute.startswith("proc_macro"):
+            return f"#[{attribute}]"
+    return ""
+
+
+def cmd_roots(args):
+    """入口点命令：列出自动发现的入口点（可达性分析的默认起点）"""
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+    roots = _entry_roots(graph, args)
+    if args.kind:
+        roots = {n: reason for n, reason in roots.items() if reason in args.kind}
+    reasons = list(ENTRY_REASONS)
+    ordered = sorted(
+        roots.items(),
+        key=lambda item: (
+            reasons.index(item[1]),
+            graph.qualified_name(item[0]),
+            graph.nodes[item[0]]["file"] or "",
+        ),
+    )
+
+    if args.format == "json":
+        entries = []
+        for node_id, reason in ordered:
+            node = graph.nodes[node_id]
+            extras = node.get("extras") or {}
+            entries.append(
+                {
+                    "id": node_id,
+                    "name": node["name"],
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": node["file"],
+                    "line": node.get("start_line"),
+                    "reason": reason,
+                    "bin": extras.get("bin"),
+                    "abi": extras.get("abi"),
+                }
+            )
+        print(json.dumps({"roots": entries}, ensure_ascii=False, indent=2))
+        return
+
+    if not ordered:
+        print("\n没有找到任何入口点")
+        return
+    print(f"\n入口点: {len(ordered)} 个（共 {len(graph.nodes)} 个函数）")
+    current = None
+    for node_id, reason in ordered:
+        if reason != current:
+            current = reason
+            count = sum(1 for _, r in ordered if r == reason)
+            print(f"\n{ENTRY_REASONS[reason]} [{reason}]（{count} 个）:")
+        detail = _root_detail(graph, node_id)
+        suffix = f"  [{detail}]" if detail else ""
+        node = graph.nodes[node_id]
+        location = f"{node['file']}:{node['start_line']}"
+        print(f"  {graph.qualified_name(node_id)}  ({location}){suffix}")
+
+
+def cmd_deadcode(args):
+    """死代码检测命令：列出从任何入口点都不可达的函数"""
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+        roots = _entry_roots(graph, args)
+
+        if not roots:
+            if args.format in FINDING_FORMATS:
+                print("错误: 没有找到任何入口点，请使用 --root 指定", file=sys.stderr)
+                sys.exit(1)
+            print("\n没有找到任何入口点，请使用 --root 指定")
+            return
+
+        unreachable = find_unreachable(graph, roots)
+        if args.base:
+            unreachable = _newly_unreachable(graph, unreachable, args)
+
+        if args.format in FINDING_FORMATS:
+            results = dead_code_results(graph, unreachable, new=bool(args.base))
+            _print_findings(results, args.format)
+            return
+
+        by_reason = {}
+        for reason in roots.values():
+            by_reason[reason] = by_reason.get(reason, 0) + 1
+        summary = ", ".join(f"{r} {n} 个" for r, n in sorted(by_reason.items()))
+        print(f"\n入口点: {len(roots)} 个（{summary}）")
+        if args.verbose:
+            for node_id, reason in sorted(
+                roots.items(), key=lambda item: graph.nodes[item[0]]["name"]
+            ):
+                print(f"  [{reason}] {_node_display(graph, node_id)}")
+
+        if not unreachable:
+            if args.base:
+                print(f"\n没有相对 {args.base} 新增的不可达函数")
+            else:
+                print("\n所有函数都可以从入口点到达")
+            return
+
+        since = f"相对 {args.base} 新增的" if args.base else ""
+        print(
+            f"\n发现 {len(unreachable)} 个{since}不可达函数"
+            f"（共 {len(graph.nodes)} 个函数）:\n"
+        )
+        current_file = None
+        for node_id in unreachable:
+            node = graph.nodes[node_id]
+            if node["file"] != current_file:
+                current_file = node["file"]
+                print(f"{current_file}:")
+            # trait 实现方法可能由标准库或外部代码调用，单独标注便于人工确认
+            marker = " [trait impl]" if node["extras"].get("trait") else ""
+            print(f"  {node['start_line']:5d}  {node['name']}{marker}")
+
+    finally:
+        db.close()
+
+
+def _newly_unreachable(graph: CallGraph, unreachable: List[str], args) -> List[str]:
+    """
+    去掉在基准版本（--base）中就已经不可达的函数，只保留新变为不可达或新增的
+    不可达函数；基准版本用与数据库相同的过滤和入口点选项分析，按限定名比较
+    """
+    with tempfile.TemporaryDirectory() as workdir:
+        try:
+            base = _analyze_version(args, args.base, None, workdir)
+        except ValueError as e:
+            print(f"错误: {e}", file=sys.stderr)
+            sys.exit(1)
+    if args.min_confidence:
+        base = base.with_min_confidence(args.min_confidence)
+    if args.public_only:
+        base = base.public_only()
+    with contextlib.redirect_stderr(io.StringIO()):
+        # 基准版本中找不到 --root 指定的函数是正常的，不重复输出警告
+        base_roots = _entry_roots(base, args)
+    dead = {base.qualified_name(n) for n in find_unreachable(base, base_roots)}
+    return [n for n in unreachable if graph.qualified_name(n) not in dead]
+
+
+def _features_crates(graph: CallGraph, args) -> List[tuple]:
+    """要分析的 crate：[(crate 名, feature 表)]，按 --package 过滤"""
+    manifests = crate_manifests(graph)
+    selected = [crate_name(name) for name in args.package or []]
+    for name in selected:
+        if name not in manifests:
+            print(f"警告: 没有找到 crate '{name}'", file=sys.stderr)
+    crates = []
+    for crate, path in sorted(manifests.items(), key=lambda item: item[0] or ""):
+        if selected and crate not in selected:
+            continue
+        table = manifest_features(load_toml(path))
+        if table or args.set:
+            crates.append((crate, table))
+    return crates
+
+
+def cmd_features(args):
+    """feature 组合命令：在各个 feature 组合下求值 cfg 条件，找出只在部分组合下存在的代码"""
+    try:
+        CfgSet(args.cfg or [])
+    except CfgError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    crates = _features_crates(graph, args)
+    reports = []
+    for crate, table in crates:
+        if args.set:
+            matrix = [parse_feature_set(text) for text in args.set]
+            unknown = sorted(
+                {name for names in matrix for name in names} - set(table)
+            )
+            if unknown:
+                print(
+                    f"警告: crate {crate or '?'} 没有声明 feature: {', '.join(unknown)}",
+                    file=sys.stderr,
+                )
+        else:
+            matrix = feature_matrix(table, args.max_features)
+        feature_matrix_ = FeatureMatrix(graph, crate, table, args.cfg or [])
+        reports.append(feature_matrix_.analyze(matrix))
+
+    if args.format == "json":
+
+        def function_entry(node_id: str, present: Optional[List[int]] = None) -> dict:
+            node = graph.nodes[node_id]
+            entry = {
+                "id": node_id,
+                "qualified_name": graph.qualified_name(node_id),
+                "file": node["file"],
+                "line": node.get("start_line"),
+                "cfg": combine_cfg(function_cfg(node)),
+            }
+            if present is not None:
+                entry["combinations"] = present
+            return entry
+
+        document = {
+            "crates": [
+                {
+                    "crate": report["crate"],
+                    "combinations": report["combinations"],
+                    "functions": [
+                        function_entry(node_id, present)
+                        for node_id, present in report["functions"].items()
+                    ],
+                    "edges": [
+                        {
+                            "caller_id": caller_id,
+                            "callee_id": callee_id,
+                            "caller": graph.qualified_name(caller_id),
+                            "callee": graph.qualified_name(callee_id),
+                            "combinations": present,
+                        }
+                        for (caller_id, callee_id), present in report["edges"].items()
+                    ],
+                    "absent": [function_entry(n) for n in report["absent"]],
+                    "uncalled": [function_entry(n) for n in report["uncalled"]],
+                }
+                for report in reports
+            ]
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    if not reports:
+        print("\n没有找到声明了 feature 的 crate，请使用 --set 指定组合")
+        return
+    for report in reports:
+        combos = report["combinations"]
+        total = len(combos)
+        print(f"\ncrate {report['crate'] or '?'}: 分析 {total} 个 feature 组合\n")
+        width = max(len(combination_label(c["features"])) for c in combos)
+        for index, combo in enumerate(combos):
+            label = combination_label(combo["features"]).ljust(width)
+            print(
+                f"  [{index}] {label}  {combo['functions']:6d} 个函数  "
+                f"{combo['edges']:6d} 个调用"
+            )
+
+        if report["absent"]:
+            print(
+                f"\n{len(report['absent'])} 个函数在所有组合下都不存在"
+                "（如 cfg(test)、其他平台的代码）"
+            )
+        functions = report["functions"]
+        if not functions and not report["edges"]:
+            print("\n其他函数和调用在各个组合下都存在")
+            continue
+        print(f"\n只在部分组合下存在的函数: {len(functions)} 个\n")
+        current_file = None
+        for node_id, present in functions.items():
+            node = graph.nodes[node_id]
+            if node["file"] != current_file:
+                current_file = node["file"]
+                print(f"{current_file}:")
+            condition = combine_cfg(function_cfg(node)) or "-"
+            print(
+                f"  {node['start_line']:5d}  {node['name']}  [{condition}]  "
+                f"{len(present)}/{total}"
+            )
+
+        edges = report["edges"]
+        print(f"\n只在部分组合下存在的调用: {len(edges)} 个")
+        if args.verbose:
+            for (caller_id, callee_id), present in edges.items():
+                print(
+                    f"  {graph.qualified_name(caller_id)} -> "
+                    f"{graph.qualified_name(callee_id)}  {len(present)}/{total}"
+                )
+
+        uncalled = report["uncalled"]
+        if uncalled:
+            print(f"\n只在部分组合下存在、在这些组合下都没有调用者的函数: {len(uncalled)} 个\n")
+            for node_id in uncalled:
+                node = graph.nodes[node_id]
+                marker = " [pub]" if graph.visibility(node_id) == "public" else ""
+                print(f"  {_node_display(graph, node_id)}{marker}")
+
+
+def _load_profile_arg(args) -> Optional[List[tuple]]:
+    """--profile 指定的剖析数据；出错时退出"""
+    if not args.profile:
+        return None
+    try:
+        return load_profile(args.profile, args.profile_format)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
+
+def _load_coverage_arg(args) -> Optional[dict]:
+    """--coverage 指定的覆盖率报告；出错时退出"""
+    if not args.coverage:
+        return None
+    try:
+        return load_coverage(args.coverage, args.coverage_format)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
+
+def cmd_coverage(args):
+    """覆盖率命令：把覆盖率报告对应到调用图，列出可以从 main 到达却没有被执行的函数"""
+    report = _load_coverage_arg(args)
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+    counts = apply_coverage(graph, report)
+
+    if args.source:
+        sources = _reach_sources(graph, args.source)
+    else:
+        sources = sorted(
+            node_id
+            for node_id, reason in find_entry_points(graph).items()
+            if reason == "main"
+        )
+    if not sources:
+        print("\n没有找到 main 函数，请使用 --from 指定入口函数", file=sys.stderr)
+        sys.exit(1)
+    reachable = reachable_from(graph, sources)
+    uncovered = sorted(
+        (
+            node_id
+            for node_id in reachable
+            if graph.nodes[node_id]["extras"].get("covered") is False
+            and not is_test_code(graph, node_id)
+        ),
+        key=lambda n: (graph.nodes[n]["file"], graph.nodes[n].get("start_line") or 0),
+    )
+    unknown = sum(
+        1
+        for node_id in reachable
+        if graph.nodes[node_id]["extras"].get("covered") is None
+    )
+    paths = {}
+    if args.verbose or args.format == "json":
+        paths = {
+            node_id: (find_call_paths(graph, sources, [node_id], 1) or [[]])[0]
+            for node_id in uncovered
+        }
+
+    if args.format == "json":
+        document = {
+            "sources": [graph.qualified_name(n) for n in sources],
+            "covered": counts["covered"],
+            "uncovered": counts["uncovered"],
+            "unknown": counts["unknown"],
+            "reachable": len(reachable),
+            "reachable_uncovered": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "line_coverage": graph.nodes[node_id]["extras"].get(
+                        "line_coverage"
+                    ),
+                    "path": [graph.qualified_name(n) for n in paths[node_id]],
+                }
+                for node_id in uncovered
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    print(f"\n{coverage_summary(counts)}")
+    if counts["covered"] + counts["uncovered"] == 0:
+        print("\n覆盖率报告中的文件没有对应到调用图中的函数，请检查报告中的路径")
+        return
+    print(
+        f"\n从 {len(sources)} 个入口函数可以到达 {len(reachable)} 个函数，"
+        f"其中 {len(uncovered)} 个没有被执行（不含测试代码）"
+        + (f"，{unknown} 个没有覆盖率数据" if unknown else "")
+    )
+    if not uncovered:
+        return
+    print()
+    current_file = None
+    for node_id in uncovered:
+        node = graph.nodes[node_id]
+        if node["file"] != current_file:
+            current_file = node["file"]
+            print(f"{current_file}:")
+        print(f"  {node['start_line']:5d}  {node['name']}")
+        if args.verbose and paths[node_id]:
+            names = [graph.nodes[n]["name"] for n in paths[node_id]]
+            print(f"         {' -> '.join(names)}")
+
+
+def cmd_profile(args):
+    """剖析数据命令：把运行时采样的调用栈对应到调用图，列出热点函数和热路径"""
+    stacks = _load_profile_arg(args)
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+    summary = apply_profile(graph, stacks)
+    total = summary["total"] or 1
+    functions = hot_functions(graph, args.top)
+    edges = sorted(
+        (edge for edge in graph.edges() if edge["samples"]),
+        key=lambda edge: (
+            -edge["samples"],
+            graph.qualified_name(edge["caller_id"]),
+            graph.qualified_name(edge["callee_id"]),
+        ),
+    )
+    cold_edges = len(graph.edges()) - len(edges)
+    if args.top:
+        edges = edges[: args.top]
+
+    if args.format == "json":
+        document = {
+            "samples": summary["total"],
+            "matched": summary["matched"],
+            "functions": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "samples": graph.nodes[node_id]["extras"]["samples"],
+                    "self_samples": graph.nodes[node_id]["extras"]["self_samples"],
+                }
+                for node_id in functions
+            ],
+            "edges": [
+                {
+                    "caller_id": edge["caller_id"],
+                    "callee_id": edge["callee_id"],
+                    "caller": graph.qualified_name(edge["caller_id"]),
+                    "callee": graph.qualified_name(edge["callee_id"]),
+                    "samples": edge["samples"],
+                }
+                for edge in edges
+            ],
+            "unseen_edges": [
+                {
+                    "caller_id": caller_id,
+                    "callee_id": callee_id,
+                    "caller": graph.qualified_name(caller_id),
+                    "callee": graph.qualified_name(callee_id),
+                    "samples": samples,
+                }
+                for (caller_id, callee_id), samples in summary["unseen_edges"].items()
+            ],
+            "unmatched_frames": [
+                {"frame": frame, "samples": samples}
+                for frame, samples in summary["unmatched"].items()
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    print(f"\n{profile_summary(graph, summary)}")
+    if not functions:
+        print("\n没有样本落在调用图中的函数上，请检查剖析数据是否包含符号")
+        return
+
+    print("\n热点函数（包含样本数 / 自身样本数）:\n")
+    for node_id in functions:
+        extras = graph.nodes[node_id]["extras"]
+        print(
+            f"  {extras['samples']:8d} {extras['samples'] / total * 100:5.1f}%  "
+            f"{extras['self_samples']:8d}  {_node_display(graph, node_id)}"
+        )
+
+    print("\n热路径中的调用（样本数）:\n")
+    for edge in edges:
+        print(
+            f"  {edge['samples']:8d} {edge['samples'] / total * 100:5.1f}%  "
+            f"{graph.qualified_name(edge['caller_id'])} -> "
+            f"{graph.qualified_name(edge['callee_id'])}"
+        )
+    print(f"\n{cold_edges} 个静态调用没有出现在剖析数据中")
+
+    unseen = summary["unseen_edges"]
+    if unseen:
+        print(
+            f"\n观察到但静态调用图中没有的调用: {len(unseen)} 个"
+            "（可能是动态分发、回调或解析遗漏）\n"
+        )
+        for (caller_id, callee_id), samples in unseen.items():
+            print(
+                f"  {samples:8d}  {graph.qualified_name(caller_id)} -> "
+                f"{graph.qualified_name(callee_id)}"
+            )
+
+    unmatched = summary["unmatched"]
+    if unmatched and args.verbose:
+        print(f"\n无法对应到项目函数的帧: {len(unmatched)} 个\n")
+        for frame, samples in list(unmatched.items())[: args.top or None]:
+            print(f"  {samples:8d}  {frame}")
+    elif unmatched:
+        print(f"\n{len(unmatched)} 个帧无法对应到项目函数（库函数等，-v 列出）")
+
+
+def cmd_test_reach(args):
+    """测试可达性命令：每个生产代码函数可以被哪些测试到达（静态覆盖的近似）"""
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    tests = [
+        node_id
+        for node_id in graph.nodes
+        if entry_point_reason(graph, node_id) == "test"
+    ]
+    reaching = tests_reaching(graph)
+    order = sorted(
+        reaching,
+        key=lambda n: (graph.nodes[n]["file"], graph.nodes[n].get("start_line") or 0),
+    )
+    unreached = [node_id for node_id in order if not reaching[node_id]]
+    reached = len(order) - len(unreached)
+    percent = 100.0 * reached / len(order) if order else 100.0
+
+    if args.format == "json":
+        document = {
+            "summary": {
+                "tests": len(tests),
+                "functions": len(order),
+                "reached": reached,
+                "percent": round(percent, 2),
+            },
+            "functions": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "tests": [graph.qualified_name(t) for t in reaching[node_id]],
+                }
+                for node_id in order
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+    else:
+        print(
+            f"\n{len(tests)} 个测试函数，{len(order)} 个生产代码函数，"
+            f"其中 {reached} 个可以被测试到达（{percent:.1f}%）"
+        )
+        if args.verbose:
+            print("\n可以被测试到达的函数:\n")
+            for node_id in order:
+                if reaching[node_id]:
+                    names = [graph.nodes[t]["name"] for t in reaching[node_id]]
+                    shown = ", ".join(names[:TEST_NAMES_SHOWN])
+                    if len(names) > TEST_NAMES_SHOWN:
+                        shown += f" 等 {len(names)} 个"
+                    print(f"  {_node_display(graph, node_id)}  <- {shown}")
+
+        if unreached:
+            print(f"\n没有任何测试可以到达的函数: {len(unreached)} 个\n")
+            current_file = None
+            for node_id in unreached:
+                node = graph.nodes[node_id]
+                if node["file"] != current_file:
+                    current_file = node["file"]
+                    print(f"{current_file}:")
+                print(f"  {node['start_line']:5d}  {node['name']}")
+        else:
+            print("\n所有生产代码函数都可以被测试到达")
+
+    if args.fail_under is not None and percent < args.fail_under:
+        print(
+            f"错误: 测试可达的函数比例 {percent:.1f}% 低于 {args.fail_under}%",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+
+
+def cmd_impact(args):
+    """
+    变更影响分析命令：git diff 中修改的函数、它们的所有传递调用者和能到达
+    它们的测试
+    """
+    try:
+        changes = changed_lines(args.repo, args.diff)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    result = impact(graph, changed_functions(graph, changes))
+
+    if args.list == "tests":
+        for node_id in result["tests"]:
+            print(graph.qualified_name(node_id))
+        return
+    if args.list == "test-files":
+        for path in result["test_files"]:
+            print(path)
+        return
+
+    if args.format == "json":
+        document = {
+            "changed_files": sorted(changes),
+            **{
+                key: [
+                    {
+                        "id": node_id,
+                        "qualified_name": graph.qualified_name(node_id),
+                        "file": graph.nodes[node_id]["file"],
+                        "line": graph.nodes[node_id].get("start_line"),
+                    }
+                    for node_id in result[key]
+                ]
+                for key in ("changed", "callers", "tests")
+            },
+            "test_files": result["test_files"],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    print(
+        f"\n{args.diff}: {len(changes)} 个文件有修改，"
+        f"涉及 {len(result['changed'])} 个函数"
+    )
+    sections = [
+        ("修改的函数", result["changed"]),
+        ("受影响的调用者", result["callers"]),
+        ("需要运行的测试", result["tests"]),
+    ]
+    for title, node_ids in sections:
+        print(f"\n{title}: {len(node_ids)} 个")
+        for node_id in node_ids:
+            print(f"  {_node_display(graph, node_id)}")
+    if result["test_files"]:
+        print(f"\n测试文件: {len(result['test_files'])} 个")
+        for path in result["test_files"]:
+            print(f"  {path}")
+
+
+def _reach_sources(graph: CallGraph, names: Optional[List[str]]) -> List[str]:
+    """--from 指定的入口函数；没有指定时使用默认入口点（main、测试函数等）"""
+    if not names:
+        return sorted(find_entry_points(graph))
+    sources = []
+    for name in names:
+        matches = graph.find(name)
+        if not matches:
+            print(f"警告: 没有找到入口函数 '{name}'", file=sys.stderr)
+        sources.extend(matches)
+    return sources
+
+
+def _unsafe_text(node: dict) -> str:
+    """unsafe fn 和 unsafe 块数量的说明"""
+    extras = node.get("extras") or {}
+    parts = []
+    if extras.get("unsafe"):
+        parts.append("unsafe fn")
+    if extras.get("unsafe_blocks"):
+        parts.append(f"{extras['unsafe_blocks']} 个 unsafe 块")
+    return "，".join(parts)
+
+
+def cmd_unsafe_reach(args):
+    """unsafe 可达性命令：列出从入口函数到包含 unsafe 代码的函数的调用路径"""
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    sources = _reach_sources(graph, args.source)
+    if not sources:
+        print("\n没有找到任何入口点，请使用 --from 指定", file=sys.stderr)
+        sys.exit(1)
+
+    unsafe = [node_id for node_id in graph.nodes if _unsafe_text(graph.nodes[node_id])]
+    reachable = reachable_from(graph, sources)
+    reached = sorted(
+        (node_id for node_id in unsafe if node_id in reachable),
+        key=graph.qualified_name,
+    )
+    limit = args.limit if args.limit > 0 else None
+    paths = {
+        node_id: find_call_paths(graph, sources, [node_id], limit, args.max_depth)
+        for node_id in reached
+    }
+
+    if args.format == "json":
+        document = {
+            "sources": [graph.qualified_name(n) for n in sources],
+            "unsafe_functions": len(unsafe),
+            "reachable": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "unsafe_fn": bool(graph.nodes[node_id]["extras"].get("unsafe")),
+                    "unsafe_blocks": graph.nodes[node_id]["extras"].get(
+                        "unsafe_blocks", 0
+                    ),
+                    "paths": [
+                        [graph.qualified_name(n) for n in path]
+                        for path in paths[node_id]
+                    ],
+                }
+                for node_id in reached
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    print(
+        f"\n{len(unsafe)} 个函数包含 unsafe 代码，"
+        f"其中 {len(reached)} 个可以从 {len(sources)} 个入口函数到达"
+    )
+    for node_id in reached:
+        node = graph.nodes[node_id]
+        print(f"\n{_node_display(graph, node_id)}  [{_unsafe_text(node)}]")
+        for i, path in enumerate(paths[node_id], 1):
+            names = [graph.nodes[n]["name"] for n in path]
+            print(f"  {i}. {' -> '.join(names)}")
+        if limit is not None and len(paths[node_id]) == limit:
+            print(f"  （已达到上限 {limit} 条，可用 --limit 调整）")
+
+
+def _public_api(graph: CallGraph) -> List[str]:
+    """公开 API：public 可见性的非测试函数（不含模块级代码）"""
+    return [
+        node_id
+        for node_id in graph.nodes
+        if graph.visibility(node_id) == "public"
+        and not graph.nodes[node_id]["extras"].get("module_body")
+        and not is_test_code(graph, node_id)
+    ]
+
+
+def _panic_text(panics: dict) -> str:
+    """panic 位置的说明：unwrap ×2（第 14 行）, index（第 20 行）"""
+    parts = []
+    for kind in PANIC_KINDS:
+        if kind in panics:
+            count = panics[kind]["count"]
+            times = f" ×{count}" if count > 1 else ""
+            parts.append(f"{kind}{times}（第 {panics[kind]['line']} 行）")
+    return ", ".join(parts)
+
+
+def cmd_panic_reach(args):
+    """panic 路径命令：列出可以直接或间接到达 panic 的公开 API 函数及最短示例路径"""
+    ignored = {kind for value in args.ignore or [] for kind in value.split(",")}
+    unknown = sorted(ignored - set(PANIC_KINDS))
+    if unknown:
+        print(
+            f"错误: 未知的 panic 类别: {', '.join(unknown)}"
+            f"（可选: {', '.join(PANIC_KINDS)}）",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    sources = _reach_sources(graph, args.source) if args.source else _public_api(graph)
+
+    panics = {}
+    for node_id, node in graph.nodes.items():
+        sites = {
+            kind: site
+            for kind, site in (node["extras"].get("panics") or {}).items()
+            if kind not in ignored
+        }
+        if sites:
+            panics[node_id] = sites
+
+    paths = shortest_paths_to(graph, sources, panics)
+    order = sorted(paths, key=graph.qualified_name)
+
+    if args.format in FINDING_FORMATS:
+        _print_findings(panic_results(graph, paths, panics, order), args.format)
+        return
+
+    if args.format == "json":
+        document = {
+            "functions": len(sources),
+            "panicking": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "path": [graph.qualified_name(n) for n in paths[node_id]],
+                    "panic_file": graph.nodes[paths[node_id][-1]]["file"],
+                    "panics": panics[paths[node_id][-1]],
+                }
+                for node_id in order
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    kind = "入口函数" if args.source else "公开函数"
+    print(f"\n{len(sources)} 个{kind}中有 {len(order)} 个可能直接或间接 panic")
+    for node_id in order:
+        path = paths[node_id]
+        print(f"\n{_node_display(graph, node_id)}")
+        if len(path) > 1:
+            names = [graph.nodes[n]["name"] for n in path]
+            print(f"  路径: {' -> '.join(names)}")
+        print(
+            f"  {_node_display(graph, path[-1])}: {_panic_text(panics[path[-1]])}"
+        )
+
+
+def _std_call_text(sites: List[dict]) -> str:
+    """标准库调用的说明：std::fs::read（第 14 行）, alloc::vec::Vec::new（第 20 行）"""
+    return ", ".join(f"{site['callee']}（第 {site['line']} 行）" for site in sites)
+
+
+def cmd_no_std(args):
+    """
+    no_std 兼容性审计命令：列出可以直接或间接调用 std 专有函数（或未被允许的
+    alloc 函数）的公开 API 函数及最短示例路径
+    """
+    allowed = args.allow or list(DEFAULT_ALLOWED)
+
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    sources = _reach_sources(graph, args.source) if args.source else _public_api(graph)
+    calls = std_calls(graph, allowed)
+    paths = shortest_paths_to(graph, sources, calls)
+    order = sorted(paths, key=graph.qualified_name)
+
+    if args.format == "json":
+        document = {
+            "allowed": sorted(set(allowed) | {"core"}, key=STD_CRATES.index),
+            "functions": len(sources),
+            "std_functions": len(calls),
+            "reaching": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "path": [graph.qualified_name(n) for n in paths[node_id]],
+                    "std_file": graph.nodes[paths[node_id][-1]]["file"],
+                    "std_calls": calls[paths[node_id][-1]],
+                }
+                for node_id in order
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+    else:
+        kind = "入口函数" if args.source else "公开函数"
+        names = ", ".join(sorted(set(allowed) | {"core"}, key=STD_CRATES.index))
+        print(
+            f"\n{len(sources)} 个{kind}中有 {len(order)} 个在 no_std（允许: {names}）"
+            "下不可用"
+        )
+        for node_id in order:
+            path = paths[node_id]
+            print(f"\n{_node_display(graph, node_id)}")
+            if len(path) > 1:
+                print(f"  路径: {' -> '.join(graph.nodes[n]['name'] for n in path)}")
+            print(
+                f"  {_node_display(graph, path[-1])}: {_std_call_text(calls[path[-1]])}"
+            )
+
+    if args.check and order:
+        sys.exit(1)
+
+
+def _handling_text(entry: dict) -> str:
+    """调用者对错误的处理方式和调用点行号：转发（第 12 行）, unwrap（第 20 行）"""
+    parts = []
+    for kind in entry["handling"]:
+        lines = sorted(
+            {
+                site.get("call_site_line")
+                for site in entry["sites"]
+                if site_handling(site) == kind and site.get("call_site_line")
+            }
+        )
+        where = f"（第 {'、'.join(str(n) for n in lines)} 行）" if lines else ""
+        parts.append(f"{HANDLING_LABELS[kind]}{where}")
+    return ", ".join(parts)
+
+
+def _print_error_tree(graph: CallGraph, tree: dict, charset: str) -> Tuple[set, set]:
+    """
+    以树形打印错误传播树（子节点是调用父节点的函数）
+
+    Returns:
+        (转发错误的调用者, 只在本地处理错误的调用者)
+    """
+    middle, last_branch, pipe, blank = TREE_CHARSETS[charset]
+    forwarding, handling = set(), set()
+
+    def walk(entries: List[dict], prefix: str):
+        for i, entry in enumerate(entries):
+            last = i == len(entries) - 1
+            caller_id = entry["caller_id"]
+            if "propagate" in entry["handling"]:
+                forwarding.add(caller_id)
+            else:
+                handling.add(caller_id)
+            marker = ""
+            if entry["recursive"]:
+                marker = " (递归)"
+            elif entry["truncated"]:
+                marker = " ..."
+            branch = last_branch if last else middle
+            print(
+                f"{prefix}{branch}{_node_display(graph, caller_id)}  "
+                f"{_handling_text(entry)}{marker}"
+            )
+            walk(entry["callers"], prefix + (blank if last else pipe))
+
+    walk(tree["callers"], "")
+    return forwarding, handling - forwarding
+
+
+def _error_tree_json(graph: CallGraph, entries: List[dict]) -> List[dict]:
+    return [
+        {
+            "caller": graph.qualified_name(entry["caller_id"]),
+            "caller_id": entry["caller_id"],
+            "handling": entry["handling"],
+            "lines": [site.get("call_site_line") for site in entry["sites"]],
+            "recursive": entry["recursive"],
+            "truncated": entry["truncated"],
+            "callers": _error_tree_json(graph, entry["callers"]),
+        }
+        for entry in entries
+    ]
+
+
+def cmd_errors(args):
+    """
+    错误传播命令：从返回 Result / Option 的函数出发，沿调用者列出转发错误（?）
+    和在本地处理错误（match、unwrap、ok() 等）的调用者；不指定函数时统计每个
+    可能失败的函数的调用点处理方式
+    """
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    if args.function:
+        roots = sorted(graph.resolve(args.function), key=graph.qualified_name)
+        if not roots:
+            print(
+                f"错误: 没有找到函数: {args.function}"
+                f"{suggestion(graph, args.function)}",
+                file=sys.stderr,
+            )
+            sys.exit(1)
+        trees = [propagation_tree(graph, root_id, args.depth) for root_id in roots]
+        if args.format == "json":
+            document = [
+                {
+                    "id": tree["id"],
+                    "qualified_name": graph.qualified_name(tree["id"]),
+                    "fallible": fallible_type(graph, tree["id"]),
+                    "callers": _error_tree_json(graph, tree["callers"]),
+                }
+                for tree in trees
+            ]
+            print(json.dumps(document, ensure_ascii=False, indent=2))
+            return
+        for i, tree in enumerate(trees):
+            if i:
+                print()
+            fallible = fallible_type(graph, tree["id"])
+            suffix = f"  -> {fallible}" if fallible else "  （返回类型不是 Result / Option）"
+            print(f"{_node_display(graph, tree['id'])}{suffix}")
+            forwarding, handling = _print_error_tree(graph, tree, args.charset)
+            print(
+                f"\n{len(forwarding)} 个调用者转发错误，"
+                f"{len(handling)} 个调用者在本地处理"
+            )
+        return
+
+    summary = error_summary(graph)
+    limit = args.limit if args.limit > 0 else None
+    if args.format == "json":
+        document = {
+            "functions": [
+                {
+                    "id": entry["id"],
+                    "qualified_name": graph.qualified_name(entry["id"]),
+                    "file": graph.nodes[entry["id"]]["file"],
+                    "line": graph.nodes[entry["id"]].get("start_line"),
+                    "fallible": entry["fallible"],
+                    "call_sites": entry["counts"],
+                }
+                for entry in summary[:limit]
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    if not summary:
+        print("\n没有找到返回 Result / Option 的函数（只支持 Rust）")
+        return
+    totals = {kind: sum(e["counts"][kind] for e in summary) for kind in HANDLING_LABELS}
+    kinds = [fallible_type(graph, e["id"]) for e in summary]
+    print(
+        f"\n{len(summary)} 个函数返回 Result / Option"
+        f"（Result {kinds.count('Result')} 个，Option {kinds.count('Option')} 个）"
+    )
+    print(
+        "调用点: "
+        + ", ".join(f"{HANDLING_LABELS[k]} {n}" for k, n in totals.items() if n)
+    )
+    print()
+    for entry in summary[:limit]:
+        counts = ", ".join(
+            f"{HANDLING_LABELS[k]} {n}" for k, n in entry["counts"].items() if n
+        )
+        print(
+            f"  {_node_display(graph, entry['id'])}  {entry['fallible']}  "
+            f"{counts or '没有调用者'}"
+        )
+    if limit is not None and len(summary) > limit:
+        print(f"  ... 共 {len(summary)} 个（可用 --limit 调整）")
+
+
+# trait 覆盖矩阵中 impl 方法状态的标记（见 traits.IMPL_STATES）
+TRAIT_MARKERS = {"override": "+", "default": "-", "missing": "!"}
+
+
+def cmd_traits(args):
+    """
+    trait 实现覆盖命令：列出项目中定义的 trait、实现它们的类型，以及每个方法
+    被哪些类型覆盖和被调用的次数，标出从未被覆盖的默认方法和从未被调用的方法
+    """
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+        items = db.get_trait_items()
+    finally:
+        db.close()
+
+    report = trait_coverage(graph, items, args.trait)
+    if args.trait and not report:
+        print(f"错误: 没有找到 trait: {args.trait}", file=sys.stderr)
+        sys.exit(1)
+    methods = [m for t in report for m in t["methods"]]
+    summary = {
+        "traits": len(report),
+        "methods": len(methods),
+        "never_overridden": sum(m["never_overridden"] for m in methods),
+        "never_called": sum(m["never_called"] for m in methods),
+    }
+    if args.unused:
+        report = [
+            {
+                **t,
+                "methods": [
+                    m
+                    for m in t["methods"]
+                    if m["never_overridden"] or m["never_called"]
+                ],
+            }
+            for t in report
+        ]
+        report = [t for t in report if t["methods"]]
+
+    if args.format == "json":
+        document = {"traits": report, "summary": summary}
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    if not summary["traits"]:
+        print("\n没有找到 trait 定义（只支持 Rust；旧数据库需要重新分析）")
+        return
+    for t in report:
+        types = [impl["type"] for impl in t["impls"]]
+        types = list(dict.fromkeys(types))
+        print(
+            f"\ntrait {t['name']}（{t['file']}:{t['line']}，"
+            f"{len(t['methods'])} 个方法，{len(types)} 个实现类型）"
+        )
+        if not t["methods"]:
+            continue
+        width = max(len(m["name"]) for m in t["methods"])
+        print("  " + " " * width + "".join(f"  {name}" for name in types) + "  调用")
+        for m in t["methods"]:
+            cells = "".join(
+                f"  {TRAIT_MARKERS[m['impls'][name]]:<{len(name)}}" for name in types
+            )
+            notes = ["默认实现"] if m["default"] else []
+            if m["never_overridden"]:
+                notes.append("从未被覆盖")
+            if m["never_called"]:
+                notes.append("从未被调用")
+            line = f"  {m['name']:<{width}}{cells}  {m['calls']:<4}"
+            print(line + f"  [{'，'.join(notes)}]" if notes else line.rstrip())
+    print("\n+ 覆盖  - 使用默认实现  ! 缺失（必需方法没有实现）")
+    print(
+        f"\n{summary['traits']} 个 trait，{summary['methods']} 个方法；"
+        f"{summary['never_overridden']} 个默认方法从未被覆盖，"
+        f"{summary['never_called']} 个方法从未被调用"
+    )
+
+
+# 方法组的类别（见 segregation.method_kind）
+METHOD_KIND_LABELS = {"read": "读取方法", "write": "写入方法"}
+
+
+def cmd_interfaces(args):
+    """
+    接口隔离建议命令：按调用者所在的模块把类型的方法聚为几组，列出可以拆分为
+    更小的 trait / 结构体的类型，以及只使用了其中一部分方法的调用者模块
+    """
+    if not 0 < args.similarity <= 1:
+        print("错误: --similarity 必须在 0 到 1 之间", file=sys.stderr)
+        sys.exit(1)
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    suggestions = segregation_suggestions(
+        graph, args.similarity, args.min_methods, args.type
+    )
+    if args.type and not suggestions:
+        print(f"错误: 没有找到被外部调用的 {args.type} 的方法", file=sys.stderr)
+        sys.exit(1)
+    limit = args.limit if args.limit > 0 else None
+
+    def names(node_ids):
+        return [graph.qualified_name(n) for n in node_ids]
+
+    if args.format == "json":
+        document = [
+            {
+                **s,
+                "methods": names(s["methods"]),
+                "groups": [{**g, "methods": names(g["methods"])} for g in s["groups"]],
+                "partial": [
+                    {**p, "methods": names(p["methods"])} for p in s["partial"]
+                ],
+            }
+            for s in suggestions[:limit]
+        ]
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    if not suggestions:
+        print(
+            f"\n没有找到可以按调用者拆分的类型（至少 {args.min_methods} 个被外部调用的"
+            "方法，可用 --min-methods 调整）"
+        )
+        return
+
+    def method_list(node_ids):
+        return ", ".join(graph.nodes[n]["name"] for n in node_ids)
+
+    for s in suggestions[:limit]:
+        total = len(s["methods"])
+        verdict = (
+            f"建议拆分为 {len(s['groups'])} 组"
+            if len(s["groups"]) > 1
+            else "各方法被同一批模块使用，不需要拆分"
+        )
+        print(
+            f"\n{s['type']}（{s['file']}，{total} 个方法，"
+            f"{len(s['modules'])} 个调用者模块）：{verdict}"
+        )
+        for i, group in enumerate(s["groups"], 1):
+            label = METHOD_KIND_LABELS.get(group["kind"])
+            suffix = f"（{label}）" if label else ""
+            print(f"  {i}. {method_list(group['methods'])}{suffix}")
+            print(f"     使用者: {', '.join(group['modules'])}")
+        for p in s["partial"]:
+            label = METHOD_KIND_LABELS.get(p["kind"])
+            used = f"{len(p['methods'])}/{total}"
+            what = f"{label}（{used}）" if label else f"{used} 个方法"
+            print(f"  {p['module']} 只使用了{what}: {method_list(p['methods'])}")
+    if limit is not None and len(suggestions) > limit:
+        print(f"\n... 共 {len(suggestions)} 个类型（可用 --limit 调整）")
+    elif not args.type:
+        print(f"\n{len(suggestions)} 个类型可以按调用者拆分")
+
+
+def cmd_layers(args):
+    """
+    分层架构推断命令：把模块依赖图排成层（底层不依赖其他模块），列出逆着层次
+    方向的反向依赖和循环依赖；可以输出为 DOT / Mermaid 分层图
+    """
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    result = infer_layers(graph)
+    if args.format == "json":
+        print(json.dumps(result, ensure_ascii=False, indent=2))
+    elif args.format == "dot":
+        print(layers_to_dot(result), end="")
+    elif args.format == "mermaid":
+        print(layers_to_mermaid(result), end="")
+    elif not result["modules"]:
+        print("\n没有找到模块")
+    else:
+        print(
+            f"\n{len(result['modules'])} 个模块分为 {len(result['layers'])} 层"
+            f"（模块依赖 {len(result['dependencies'])} 条）\n"
+        )
+        for layer in range(len(result["layers"]) - 1, -1, -1):
+            print(f"第 {layer} 层: {', '.join(result['layers'][layer])}")
+        back_edges = result["back_edges"]
+        if back_edges:
+            total = sum(edge["calls"] for edge in back_edges)
+            print(f"\n反向依赖（违反分层）: {len(back_edges)} 条，共 {total} 个调用点")
+            for edge in back_edges:
+                print(
+                    f"  {edge['from']} -> {edge['to']}（第 {edge['from_layer']} 层 -> "
+                    f"第 {edge['to_layer']} 层，{edge['calls']} 个调用点）"
+                )
+                shown = edge["sites"][: args.sites] if args.sites > 0 else edge["sites"]
+                for site in shown:
+                    print(
+                        f"    {site['caller']} -> {site['callee']}  "
+                        f"{site['file']}:{site['line']}"
+                    )
+                if len(shown) < len(edge["sites"]):
+                    print(f"    ... 共 {len(edge['sites'])} 个（可用 --sites 调整）")
+        else:
+            print("\n没有反向依赖：模块依赖已经是严格分层的")
+        for members in result["cycles"]:
+            print(f"循环依赖: {', '.join(members)}")
+
+    if args.check and result["back_edges"]:
+        sys.exit(1)
+
+
+def cmd_duplicates(args):
+    """
+    重复函数检测命令：按函数体的规范化词法单元哈希和被调用函数的多重集合，
+    列出可疑的相似函数分组
+    """
+    if not 0 < args.similarity <= 1:
+        print("错误: --similarity 必须在 0 到 1 之间", file=sys.stderr)
+        sys.exit(1)
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    clusters = find_duplicates(
+        graph, args.min_tokens, args.min_calls, args.similarity, args.tests
+    )
+    limit = args.limit if args.limit > 0 else None
+    if args.format == "json":
+        document = [
+            {
+                **cluster,
+                "members": [
+                    {
+                        "id": node_id,
+                        "qualified_name": graph.qualified_name(node_id),
+                        "file": graph.nodes[node_id]["file"],
+                        "line": graph.nodes[node_id].get("start_line"),
+                        "tokens": graph.nodes[node_id]["extras"]["tokens"],
+                    }
+                    for node_id in cluster["members"]
+                ],
+            }
+            for cluster in clusters[:limit]
+        ]
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    if not clusters:
+        fingerprinted = any(
+            "body_hash" in (node.get("extras") or {}) for node in graph.nodes.values()
+        )
+        print(
+            "\n没有找到相似的函数"
+            + ("" if fingerprinted else "（旧数据库没有函数体指纹，需要重新分析）")
+        )
+        return
+    functions = sum(len(cluster["members"]) for cluster in clusters)
+    print(f"\n{len(clusters)} 组相似函数（{functions} 个函数）")
+    for i, cluster in enumerate(clusters[:limit], 1):
+        if cluster["same_body"]:
+            reason = f"函数体相同（忽略标识符和字面量，{cluster['tokens']} 个词法单元）"
+        else:
+            reason = f"调用的函数相似（相似度 {cluster['similarity']:.2f}）"
+        print(f"\n{i}. {reason}")
+        for node_id in cluster["members"]:
+            print(f"   {_node_display(graph, node_id)}")
+        if cluster["shared_callees"]:
+            shared = cluster["shared_callees"]
+            more = f" 等 {len(shared)} 个" if len(shared) > 5 else ""
+            print(f"   共同调用: {', '.join(shared[:5])}{more}")
+    if limit is not None and len(clusters) > limit:
+        print(f"\n... 共 {len(clusters)} 组（可用 --limit 调整）")
+
+
+def cmd_touches(args):
+    """
+    入口函数影响范围命令：为每个公开函数（或 --from 指定的入口）汇总可达的模块、
+    副作用标签、unsafe 代码和最长调用链深度，输出为文档用的表格
+    """
+    try:
+        effect_set = EffectSet.from_specs(args.effect or [])
+        if args.config:
+            effect_set = EffectSet.from_config(args.config).merge(effect_set)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    sources = _reach_sources(graph, args.source) if args.source else _public_api(graph)
+    rows = touches_rows(graph, touches_summary(graph, sources, effect_set))
+
+    if args.format == "json":
+        print(json.dumps(rows, ensure_ascii=False, indent=2))
+        return
+    if args.format == "csv":
+        print(touches_to_csv(rows), end="")
+        return
+    if args.format == "markdown":
+        print(touches_to_markdown(rows, bool(effect_set)), end="")
+        return
+
+    if not rows:
+        print("\n没有找到公开函数，请使用 --from 指定入口函数")
+        return
+    for row in rows:
+        depth = row["max_depth"]
+        if row["recursive"]:
+            depth = f"{depth}+（调用链上有递归）"
+        print(f"\n{row['function']}（{row['file']}:{row['line']}）")
+        print(f"  可达函数 {row['functions']} 个，最大调用深度 {depth}")
+        print(f"  模块: {', '.join(row['modules'])}")
+        if effect_set:
+            print(f"  副作用: {', '.join(row['effects']) or '无'}")
+        if row["unsafe_functions"]:
+            print(
+                f"  unsafe: {row['unsafe_functions']} 个函数"
+                f"（{row['unsafe_blocks']} 个 unsafe 块）"
+            )
+    kind = "入口函数" if args.source else "公开函数"
+    print(f"\n共 {len(rows)} 个{kind}")
+    if not effect_set:
+        print("未声明副作用标签（配置文件的 [effects] 表或 --effect），不列出副作用")
+
+
+def _reach_root_set(graph: CallGraph, value: str) -> List[str]:
+    """
+    --roots 的一组入口：逗号分隔的函数名或二进制目标名（Cargo 的 [[bin]] 目标，
+    对应该目标的 main 函数）
+    """
+    roots = []
+    for name in filter(None, (part.strip() for part in value.split(","))):
+        matches = [
+            node_id
+            for node_id, node in graph.nodes.items()
+            if (node.get("extras") or {}).get("bin") == name
+        ] or graph.find(name)
+        if not matches:
+            print(f"警告: 没有找到入口函数或二进制目标 '{name}'", file=sys.stderr)
+        roots.extend(node_id for node_id in matches if node_id not in roots)
+    return roots
+
+
+def cmd_reach(args):
+    """
+    多入口可达性命令：列出从每组入口函数出发可达的函数；--compare 时对比各组，
+    列出只从一组入口可达的函数、部分组和所有组共同可达的函数
+    """
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    root_sets = {}
+    for value in args.roots:
+        roots = _reach_root_set(graph, value)
+        if not roots:
+            print(f"错误: 入口组 '{value}' 中没有找到任何函数", file=sys.stderr)
+            sys.exit(1)
+        root_sets[value] = roots
+    if args.compare and len(root_sets) < 2:
+        print("错误: --compare 至少需要两组不同的 --roots", file=sys.stderr)
+        sys.exit(1)
+
+    result = compare_reach(graph, root_sets)
+    labels = list(root_sets)
+    reachable = {
+        label: sorted(
+            result["shared"]
+            + result["only"][label]
+            + [p["id"] for p in result["partial"] if label in p["labels"]],
+            key=graph.qualified_name,
+        )
+        for label in labels
+    }
+    limit = args.limit if args.limit > 0 else None
+
+    def function(node_id):
+        return {
+            "id": node_id,
+            "qualified_name": graph.qualified_name(node_id),
+            "file": graph.nodes[node_id]["file"],
+            "line": graph.nodes[node_id].get("start_line"),
+        }
+
+    if args.format == "json":
+        document = {
+            "roots": [
+                {**entry, "roots": [function(n) for n in entry["roots"]]}
+                for entry in result["roots"]
+            ],
+            "modules": result["modules"],
+        }
+        if args.compare:
+            document["only"] = {
+                label: [function(n) for n in node_ids]
+                for label, node_ids in result["only"].items()
+            }
+            document["partial"] = [
+                {**function(entry["id"]), "labels": entry["labels"]}
+                for entry in result["partial"]
+            ]
+            document["shared"] = [function(n) for n in result["shared"]]
+        else:
+            for entry in document["roots"]:
+                entry["functions"] = [function(n) for n in reachable[entry["label"]]]
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    def print_functions(title, node_ids, labels_of=None):
+        print(f"\n{title}: {len(node_ids)} 个函数")
+        if args.modules:
+            return
+        for node_id in node_ids[:limit]:
+            node = graph.nodes[node_id]
+            name = graph.qualified_name(node_id)
+            text = f"{name}（{node['file']}:{node['start_line']}）"
+            if labels_of:
+                text += f"  [{', '.join(labels_of[node_id])}]"
+            print(f"  {text}")
+        if limit is not None and len(node_ids) > limit:
+            print(f"  ... 共 {len(node_ids)} 个（可用 --limit 调整）")
+
+    print("\n入口组:")
+    for entry in result["roots"]:
+        names = []
+        for node_id in entry["roots"]:
+            detail = _root_detail(graph, node_id)
+            name = graph.qualified_name(node_id)
+            names.append(f"{name}（{detail}）" if detail else name)
+        print(f"  [{entry['label']}] {', '.join(names)}")
+
+    if not args.compare:
+        for label in labels:
+            print_functions(f"从 [{label}] 可达", reachable[label])
+    else:
+        for label in labels:
+            print_functions(f"只从 [{label}] 可达", result["only"][label])
+        if result["partial"]:
+            labels_of = {entry["id"]: entry["labels"] for entry in result["partial"]}
+            print_functions("部分入口组可达", list(labels_of), labels_of)
+        print_functions("所有入口组共同可达", result["shared"])
+
+    if args.modules:
+        modules = result["modules"]
+        print(f"\n模块: {len(modules)} 个")
+        for entry in modules[:limit]:
+            counts = ", ".join(
+                f"[{label}] {count}" for label, count in entry["functions"].items()
+            )
+            note = ""
+            if args.compare and len(entry["labels"]) == 1:
+                note = f"（只有 [{entry['labels'][0]}] 需要）"
+            print(f"  {entry['module']}: {counts}{note}")
+        if limit is not None and len(modules) > limit:
+            print(f"  ... 共 {len(modules)} 个（可用 --limit 调整）")
+
+    if args.compare:
+        exclusive = sum(len(node_ids) for node_ids in result["only"].values())
+        print(
+            f"\n{exclusive} 个函数只被一个入口组需要，"
+            f"{len(result['shared'])} 个函数被所有入口组共享"
+        )
+
+
+def _deprecation_text(info: dict) -> str:
+    """弃用信息的说明：自 1.2 起弃用: use save instead"""
+    text = f"自 {info['since']} 起弃用" if info["since"] else "已弃用"
+    return f"{text}: {info['note']}" if info["note"] else text
+
+
+def cmd_deprecated(args):
+    """
+    弃用 API 审计命令：列出每个调用已弃用函数（#[deprecated]、@Deprecated、
+    @deprecated）的调用点、调用者和弃用说明
+    """
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    deprecated = {}
+    for node_id in graph.nodes:
+        info = graph.deprecation(node_id)
+        if info is not None:
+            deprecated[node_id] = info
+
+    # 已弃用函数中的调用默认不列出（编译器同样不对其报告警告）
+    usages = {}
+    for node_id in deprecated:
+        sites = []
+        for caller_id in graph.predecessors(node_id):
+            if caller_id in deprecated and not args.all:
+                continue
+            for site in graph.call_sites(caller_id, node_id):
+                sites.append((caller_id, site))
+        sites.sort(
+            key=lambda item: (
+                item[1].get("caller_file") or "",
+                item[1].get("call_site_line") or 0,
+                item[1].get("call_site_column") or 0,
+            )
+        )
+        usages[node_id] = sites
+    order = sorted(deprecated, key=lambda n: (graph.qualified_name(n), n))
+    used = [node_id for node_id in order if usages[node_id]]
+    total = sum(len(usages[node_id]) for node_id in order)
+    callers = {caller_id for sites in usages.values() for caller_id, _ in sites}
+
+    if args.format == "json":
+        document = {
+            "deprecated_functions": len(deprecated),
+            "call_sites": total,
+            "callers": len(callers),
+            "items": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "since": deprecated[node_id]["since"],
+                    "note": deprecated[node_id]["note"],
+                    "call_sites": [
+                        {
+                            "caller": graph.qualified_name(caller_id),
+                            "caller_id": caller_id,
+                            "file": site.get("caller_file"),
+                            "line": site.get("call_site_line"),
+                            "column": site.get("call_site_column"),
+                        }
+                        for caller_id, site in usages[node_id]
+                    ],
+                }
+                for node_id in order
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+    elif not deprecated:
+        print("\n没有找到已弃用的函数")
+    else:
+        print(
+            f"\n{len(deprecated)} 个已弃用函数中有 {len(used)} 个仍被调用，"
+            f"共 {total} 个调用点（{len(callers)} 个调用者）"
+        )
+        for node_id in used:
+            info = _deprecation_text(deprecated[node_id])
+            print(f"\n{_node_display(graph, node_id)}  {info}")
+            for caller_id, site in usages[node_id]:
+                print(
+                    f"  {_call_site_text(site, True)}  "
+                    f"<- {graph.qualified_name(caller_id)}"
+                )
+        unused = [node_id for node_id in order if not usages[node_id]]
+        if unused:
+            print(f"\n没有调用者的已弃用函数（可以考虑删除）: {len(unused)} 个")
+            for node_id in unused:
+                print(f"  {_node_display(graph, node_id)}")
+
+    if args.check and total:
+        sys.exit(1)
+
+
+def cmd_const_eval(args):
+    """
+    编译期求值命令：列出 const fn 组成的可在编译期执行的子图，以及 const fn
+    调用非 const 函数的违规调用
+    """
+    output_format = None
+    if args.export:
+        output_format = args.export_format or export_format_for(args.export)
+        if not output_format:
+            print(
+                f"错误: 无法根据扩展名推断导出格式: {args.export}，"
+                "请使用 --export-format",
+                file=sys.stderr,
+            )
+            sys.exit(1)
+
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    const = sorted(
+        (node_id for node_id in graph.nodes if graph.is_const(node_id)),
+        key=graph.qualified_name,
+    )
+    violations = const_violations(graph)
+    subgraph = const_subgraph(graph)
+    const_edges = [
+        edge
+        for edge in subgraph.edges()
+        if graph.is_const(edge["caller_id"]) and graph.is_const(edge["callee_id"])
+    ]
+    total = sum(len(v["sites"]) for v in violations)
+    callers = {v["caller_id"] for v in violations}
+
+    if args.export:
+        options = {"output": args.export} if output_format in FILE_EXPORTERS else {}
+        try:
+            with contextlib.redirect_stdout(io.StringIO()):
+                content = export_graph(subgraph, output_format, **options)
+        except ValueError as e:
+            print(f"错误: 导出失败: {e}", file=sys.stderr)
+            sys.exit(1)
+        if output_format not in FILE_EXPORTERS:
+            with open(args.export, "w", encoding="utf-8") as f:
+                f.write(content)
+        print(
+            f"已导出编译期子图（{output_format} 格式）到: {args.export}",
+            file=sys.stderr,
+        )
+
+    if args.format == "json":
+        document = {
+            "const_functions": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "callees": sorted(
+                        graph.qualified_name(n)
+                        for n in graph.successors(node_id)
+                        if n in graph.nodes and graph.is_const(n)
+                    ),
+                }
+                for node_id in const
+            ],
+            "const_edges": len(const_edges),
+            "violations": [
+                {
+                    "caller": graph.qualified_name(v["caller_id"]),
+                    "caller_id": v["caller_id"],
+                    "callee": graph.qualified_name(v["callee_id"]),
+                    "callee_id": v["callee_id"],
+                    "callee_file": graph.nodes[v["callee_id"]]["file"],
+                    "callee_line": graph.nodes[v["callee_id"]].get("start_line"),
+                    "call_sites": [
+                        {
+                            "file": site.get("caller_file"),
+                            "line": site.get("call_site_line"),
+                            "column": site.get("call_site_column"),
+                        }
+                        for site in v["sites"]
+                    ],
+                }
+                for v in violations
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+    elif not const:
+        print("\n没有找到 const fn")
+    else:
+        print(
+            f"\n{len(const)} 个 const fn，之间有 {len(const_edges)} 条调用边；"
+            f"其中 {len(callers)} 个调用了非 const 函数（{total} 个调用点）"
+        )
+        if args.all:
+            print("\n编译期子图:")
+            for node_id in const:
+                callees = sorted(
+                    graph.nodes[n]["name"]
+                    for n in graph.successors(node_id)
+                    if n in graph.nodes and graph.is_const(n)
+                )
+                suffix = f"  -> {', '.join(callees)}" if callees else ""
+                print(f"  {_node_display(graph, node_id)}{suffix}")
+        current = None
+        for violation in violations:
+            caller_id, callee_id = violation["caller_id"], violation["callee_id"]
+            if caller_id != current:
+                current = caller_id
+                print(f"\n{_node_display(graph, caller_id)}  const fn")
+            print(f"  -> {_node_display(graph, callee_id)}  非 const")
+            for site in violation["sites"]:
+                print(f"     {_call_site_text(site, True)}")
+
+    if args.check and violations:
+        sys.exit(1)
+
+
+def _effect_source_text(path: List[str], site: dict, graph: CallGraph) -> str:
+    """副作用的来源：调用路径和最终触发副作用的调用，如 load -> std::fs::read（第 14 行）"""
+    names = [graph.nodes[n]["name"] for n in path]
+    if site["callee"] is None:
+        return f"{' -> '.join(names)}（自身匹配）"
+    times = f" ×{site['count']}" if site["count"] > 1 else ""
+    return f"{' -> '.join(names + [site['callee']])}{times}（第 {site['line']} 行）"
+
+
+def cmd_effects(args):
+    """副作用命令：按 [effects] 表中的模式标记函数，并沿调用图传播到所有调用者"""
+    try:
+        effect_set = EffectSet.from_specs(args.effect or [])
+        if args.config:
+            effect_set = EffectSet.from_config(args.config).merge(effect_set)
+        scope = [Pattern(text) for text in args.scope or []]
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    if not effect_set:
+        print(
+            "错误: 没有副作用标签，请在配置文件的 [effects] 表中声明或使用 --effect",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+    selected = [name for value in args.only or [] for name in value.split(",")]
+    unknown = sorted(set(selected) - set(effect_set.names()))
+    if unknown:
+        print(
+            f"错误: 未定义的副作用标签: {', '.join(unknown)}"
+            f"（已定义: {', '.join(effect_set.names())}）",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+    names = [name for name in effect_set.names() if not selected or name in selected]
+
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    direct = effect_set.direct_effects(graph)
+    effects = effect_set.propagate(graph, direct)
+    functions = sorted(
+        (
+            node_id
+            for node_id in graph.nodes
+            if not scope
+            or any(p.matches_node(graph, node_id) for p in scope)
+        ),
+        key=graph.qualified_name,
+    )
+
+    def tags(node_id: str) -> List[str]:
+        return [name for name in names if name in effects.get(node_id, {})]
+
+    affected = [node_id for node_id in functions if tags(node_id)]
+
+    if args.format == "json":
+        document = {
+            "effects": {
+                name: [p.text for p in effect_set.patterns[name]] for name in names
+            },
+            "functions": [
+                {
+                    "id": node_id,
+                    "qualified_name": graph.qualified_name(node_id),
+                    "file": graph.nodes[node_id]["file"],
+                    "line": graph.nodes[node_id].get("start_line"),
+                    "effects": {
+                        name: {
+                            "path": [
+                                graph.qualified_name(n) for n in effects[node_id][name]
+                            ],
+                            **direct[effects[node_id][name][-1]][name],
+                        }
+                        for name in tags(node_id)
+                    },
+                }
+                for node_id in functions
+            ],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+    else:
+        print(
+            "副作用标签: "
+            + ", ".join(
+                f"{name}（{len(effect_set.patterns[name])} 个模式）" for name in names
+            )
+        )
+        print(f"\n{len(functions)} 个函数中有 {len(affected)} 个可能触发副作用")
+        for node_id in affected:
+            print(f"  {_node_display(graph, node_id)}  [{', '.join(tags(node_id))}]")
+            if args.explain:
+                for name in tags(node_id):
+                    path = effects[node_id][name]
+                    source = _effect_source_text(path, direct[path[-1]][name], graph)
+                    print(f"    {name}: {source}")
+
+    if args.check and affected:
+        sys.exit(1)
+
+
+def cmd_paths(args):
+    """调用路径命令：列出从一个函数到另一个函数的所有调用链"""
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+
+        sources = graph.resolve(args.source)
+        targets = graph.resolve(args.target)
+        for name, matches in ((args.source, sources), (args.target, targets)):
+            if not matches:
+                print(f"\n没有找到函数 '{name}'{suggestion(graph, name)}")
+                return
+
+        limit = args.limit if args.limit > 0 else None
+        paths = find_call_paths(graph, sources, targets, limit, args.max_depth)
+        if not paths:
+            depth = f"（最大深度 {args.max_depth}）" if args.max_depth else ""
+            print(f"\n没有找到从 {args.source} 到 {args.target} 的调用路径{depth}")
+            return
+
+        print(f"\n从 {args.source} 到 {args.target} 的调用路径: {len(paths)} 条")
+        if limit is not None and len(paths) == limit:
+            print(f"（已达到上限 {limit} 条，可能还有更多路径，可用 --limit 调整）")
+
+        for i, path in enumerate(paths, 1):
+            print(f"\n路径 {i}（{len(path) - 1} 跳）:")
+            print(f"  {_node_display(graph, path[0])}")
+            for depth, (caller_id, callee_id) in enumerate(zip(path, path[1:]), 1):
+                sites = graph.call_sites(caller_id, callee_id)
+                site = sites[0]
+                location = f"{site['caller_file']}:{site['call_site_line']}"
+                if len(sites) > 1:
+                    location += f" 等 {len(sites)} 处"
+                kind = site.get("edge_kind") or "static"
+                marker = "" if kind == "static" else f" [{kind}]"
+                print(
+                    f"  {'  ' * depth}-> {_node_display(graph, callee_id)}"
+                    f"{marker}  调用点: {location}"
+                )
+
+    finally:
+        db.close()
+
+
+def cmd_metrics(args):
+    """函数指标命令：扇入、扇出、传递被调用者数量、入口深度和圈复杂度"""
+    # 指定 --centrality 时默认按中心度排序
+    sort = args.sort or ("centrality" if args.centrality else "fan_in")
+    if sort == "centrality" and not args.centrality:
+        print("错误: 按 centrality 排序需要 --centrality", file=sys.stderr)
+        sys.exit(1)
+
+    db = CallGraphDB(args.database)
+
+    try:
+        graph = _load_graph(db, args)
+        roots = _entry_roots(graph, args)
+        metrics = compute_metrics(graph, roots, args.centrality)
+    finally:
+        db.close()
+
+    limit = args.limit
+    if limit is None:
+        limit = 20 if args.format == "text" else 0
+    metrics = sort_metrics(metrics, sort, limit if limit > 0 else None)
+
+    if args.format == "text":
+        if not metrics:
+            print("\n没有函数")
+            return
+        print(f"\n函数指标（共 {len(graph.nodes)} 个函数，按 {sort} 排序）:\n")
+        # 中文字符占两列宽度
+        header = f"{'扇入':>4} {'扇出':>4} {'传递':>4} {'深度':>4} {'复杂度':>3}"
+        if args.centrality:
+            header += f" {'中心度':>5}"
+        print(f"{header}  函数")
+        for metric in metrics:
+            values = [
+                metric[key]
+                for key in (
+                    "fan_in",
+                    "fan_out",
+                    "transitive_callees",
+                    "depth",
+                    "complexity",
+                )
+            ]
+            columns = " ".join(f"{'-' if v is None else v:>6}" for v in values)
+            if args.centrality:
+                columns += f" {metric['centrality']:>8.4f}"
+            print(f"{columns}  {_node_display(graph, metric['id'])}")
+        if not roots:
+            print("\n没有找到任何入口点，深度为空（可使用 --root 指定）")
+        return
+
+    if args.format == "csv":
+        content = metrics_to_csv(metrics)
+    else:
+        content = metrics_to_json(metrics)
+    if args.output:
+        with open(args.output, "w", encoding="utf-8") as f:
+            f.write(content)
+        print(f"已导出 {len(metrics)} 个函数的指标到: {args.output}", file=sys.stderr)
+    else:
+        sys.stdout.write(content if content.endswith("\n") else content + "\n")
+
+
+def _load_neo4j(analyzer, args):
+    """export --neo4j-uri：不生成 Cypher 文本，直接写入 Neo4j"""
+    if args.format != "cypher":
+        print("错误: --neo4j-uri 需要与 --format cypher 一起使用", file=sys.stderr)
+        sys.exit(1)
+    try:
+        graph = analyzer.export_view(
+            focus=args.focus,
+            depth=args.depth,
+            granularity=args.granularity,
+            symbol_filter=_symbol_filter(args),
+            monomorphize=args.monomorphize,
+            condense=args.condense,
+            max_nodes=args.max_nodes,
+            prune_by=args.prune_by,
+            stable_ids=args.stable_ids,
+            public_only=args.public_only,
+        )
+        print(f"写入 Neo4j: {args.neo4j_uri}")
+        load_into_neo4j(
+            graph,
+            args.neo4j_uri,
+            user=args.neo4j_user,
+            password=os.environ.get(PASSWORD_ENV),
+            database=args.neo4j_database,
+            clear=args.neo4j_clear,
+        )
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
+
+def cmd_export(args):
+    """导出命令"""
+    analyzer = CallGraphAnalyzer(args.database)
+
+    # 输出到标准输出时，进度信息改写到标准错误，保证管道中的内容可直接解析
+    log = sys.stdout if args.output else sys.stderr
+
+    try:
+        print(f"导出调用图为 {args.format} 格式...", file=log)
+
+        # 模块级、类型级视图的边是调用的汇总，始终标注调用次数
+        edge_labels = args.edge_labels or args.granularity != "function"
+        options = {}
+        if args.format in ("dot", "svg", "png"):
+            options = {
+                "color_by_module": args.color_by_module,
+                "edge_labels": edge_labels,
+                "rankdir": args.rankdir,
+            }
+            if args.format == "dot":
+                options["shape_by_kind"] = args.shape_by_kind
+                options["cluster_depth"] = args.cluster_depth
+        elif args.format == "mermaid":
+            options = {
+                "group_by_module": args.group_by_module,
+                "edge_labels": edge_labels,
+                "direction": args.direction,
+            }
+        elif args.format == "d2":
+            options = {
+                "shape_by_kind": args.shape_by_kind,
+                "edge_labels": edge_labels,
+                "rankdir": args.rankdir,
+            }
+        elif args.format == "plantuml":
+            options = {
+                "style": args.plantuml_style,
+                "edge_labels": edge_labels,
+                "rankdir": args.rankdir,
+            }
+        elif args.format == "html" and args.title:
+            options = {"title": args.title}
+        elif args.format == "csv":
+            options = {"neo4j_headers": args.neo4j_headers}
+        if args.format in FILE_EXPORTERS:
+            if not args.output:
+                print(f"错误: {args.format} 格式需要 --output 指定输出文件", file=sys.stderr)
+                sys.exit(1)
+            options["output"] = args.output
+
+        if args.focus:
+            print(
+                f"只导出 {', '.join(args.focus)} 周围 {args.depth} 跳以内的函数",
+                file=log,
+            )
+
+        if (args.profile or args.coverage) and args.format not in ("dot", "json"):
+            print("错误: --profile 和 --coverage 只支持 dot 和 json 格式", file=sys.stderr)
+            sys.exit(1)
+
+        if args.neo4j_uri:
+            _load_neo4j(analyzer, args)
+            return
+
+        try:
+            with contextlib.redirect_stdout(log):
+                content = analyzer.export_graph(
+                    args.format,
+                    focus=args.focus,
+                    depth=args.depth,
+                    granularity=args.granularity,
+                    symbol_filter=_symbol_filter(args),
+                    monomorphize=args.monomorphize,
+                    condense=args.condense,
+                    profile=_load_profile_arg(args),
+                    coverage=_load_coverage_arg(args),
+                    max_nodes=args.max_nodes,
+                    prune_by=args.prune_by,
+                    stable_ids=args.stable_ids,
+                    public_only=args.public_only,
+                    **options,
+                )
+        except ValueError as e:
+            print(f"错误: {e}", file=sys.stderr)
+            sys.exit(1)
+
+        if args.format in FILE_EXPORTERS:
+            print(f"已保存到: {args.output}")
+        elif args.output:
+            with open(args.output, "w", encoding="utf-8") as f:
+                f.write(content)
+            print(f"已保存到: {args.output}")
+        else:
+            print(content)
+
+    finally:
+        analyzer.close()
+
+
+def _analyze_version(args, revision, directory, workdir) -> CallGraph:
+    """分析 diff 的一侧：git 修订版本、目录，或（都未指定时）当前工作区"""
+    if directory:
+        project = directory
+    elif revision:
+        project = export_revision(args.repo, revision, workdir)
+    else:
+        project = args.repo
+    print(f"分析 {revision or directory or '工作区'}: {project}", file=sys.stderr)
+    exclude_dirs = args.exclude_dirs.split(",") if args.exclude_dirs else None
+    return _symbol_filter(args).apply(analyze(project, exclude_dirs=exclude_dirs))
+
+
+def cmd_diff(args):
+    """调用图差异命令：比较两个 git 修订版本或两个目录的调用图"""
+    if not (args.base or args.base_dir):
+        print("错误: 需要 --base 或 --base-dir", file=sys.stderr)
+        sys.exit(1)
+
+    with tempfile.TemporaryDirectory() as workdir:
+        try:
+            base_dir = os.path.join(workdir, "base")
+            head_dir = os.path.join(workdir, "head")
+            os.makedirs(base_dir)
+            os.makedirs(head_dir)
+            base = _analyze_version(args, args.base, args.base_dir, base_dir)
+            head = _analyze_version(args, args.head, args.head_dir, head_dir)
+        except ValueError as e:
+            print(f"错误: {e}", file=sys.stderr)
+            sys.exit(1)
+
+    result = diff_graphs(base, head)
+
+    if args.format == "json":
+        print(json.dumps(result, indent=2, ensure_ascii=False))
+    else:
+        base_name = args.base or args.base_dir
+        head_name = args.head or args.head_dir or "工作区"
+        print(f"\n调用图差异: {base_name} -> {head_name}")
+        sections = [
+            ("新增函数", "+", result["added_functions"]),
+            ("删除函数", "-", result["removed_functions"]),
+            ("新增调用", "+", result["added_edges"]),
+            ("删除调用", "-", result["removed_edges"]),
+            ("新增模块依赖", "+", result["added_dependencies"]),
+            ("删除模块依赖", "-", result["removed_dependencies"]),
+        ]
+        for title, sign, items in sections:
+            print(f"\n{title}: {len(items)} 个")
+            for item in items:
+                text = item if isinstance(item, str) else " -> ".join(item)
+                print(f"  {sign} {text}")
+
+    if args.fail_on_new_deps and result["added_dependencies"]:
+        sys.exit(1)
+
+
+def cmd_history(args):
+    """
+    历史趋势命令：按时间间隔分析 git 历史中的快照，输出每个快照的指标
+    （CSV 或 JSON）
+    """
+    exclude_dirs = args.exclude_dirs.split(",") if args.exclude_dirs else None
+    symbol_filter = _symbol_filter(args)
+
+    def analyze_project(project: str) -> CallGraph:
+        graph = analyze(project, exclude_dirs=exclude_dirs, quiet=True)
+        return symbol_filter.apply(graph)
+
+    def progress(index: int, snapshot: dict):
+        print(
+            f"分析快照 {index}/{len(snapshots)}: "
+            f"{snapshot['revision'][:12]} ({snapshot['date'][:10]})",
+            file=sys.stderr,
+        )
+
+    try:
+        snapshots = history_revisions(args.repo, args.since, args.until, args.step)
+        rows = analyze_history(args.repo, snapshots, analyze_project, progress)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+
+    if args.format == "json":
+        content = json.dumps({"snapshots": rows}, ensure_ascii=False, indent=2)
+    else:
+        content = history_to_csv(rows)
+    if args.output:
+        with open(args.output, "w", encoding="utf-8") as f:
+            f.write(content)
+        print(f"已导出 {len(rows)} 个快照的指标到: {args.output}", file=sys.stderr)
+    else:
+        sys.stdout.write(content if content.endswith("\n") else content + "\n")
+
+
+def cmd_check(args):
+    """架构规则检查命令：存在违反 deny 规则的调用时以非零退出码结束"""
+    try:
+        rules = RuleSet(args.rule, args.allow)
+        if args.config:
+            rules = RuleSet.from_config(args.config).merge(rules)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    if not rules:
+        print(
+            "错误: 没有规则，请在配置文件的 [rules] 表中声明 deny 规则或使用 --rule",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+        violations = rules.check(graph)
+    finally:
+        db.close()
+
+    if args.format in FINDING_FORMATS:
+        _print_findings(violation_results(graph, violations), args.format)
+        if violations:
+            sys.exit(1)
+        return
+
+    print(f"检查 {len(rules.deny)} 条规则（{len(rules.allow)} 条例外）")
+    if not violations:
+        print("\n没有发现违反规则的调用")
+        return
+
+    current_rule = None
+    for violation in violations:
+        if violation["rule"] != current_rule:
+            current_rule = violation["rule"]
+            count = sum(1 for v in violations if v["rule"] == current_rule)
+            print(f"\n违反规则 {current_rule}（{count} 条调用）:")
+        print(
+            f"  {_node_display(graph, violation['caller_id'])} -> "
+            f"{_node_display(graph, violation['callee_id'])}"
+        )
+        for site in violation["sites"]:
+            print(f"    调用点: {site['caller_file']}:{site['call_site_line']}")
+
+    print(f"\n发现 {len(violations)} 条违反规则的调用")
+    sys.exit(1)
+
+
+def cmd_baseline(args):
+    """
+    模块依赖基线命令：record 把当前的模块依赖记录为已批准的基线，
+    check 在出现基线之外的新依赖时以退出码 1 结束
+    """
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    if args.baseline_command == "record":
+        baseline = save_baseline(args.file, graph)
+        print(f"已记录 {len(baseline['dependencies'])} 个模块依赖到基线: {args.file}")
+        return
+
+    try:
+        approved = load_baseline(args.file)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    result = check_baseline(graph, approved)
+
+    if args.format == "json":
+        print(json.dumps(result, ensure_ascii=False, indent=2))
+    else:
+        print(f"基线: {args.file}（{len(approved)} 个已批准的模块依赖）")
+        if result["new"]:
+            print(f"\n不在基线中的新模块依赖: {len(result['new'])} 个")
+            for dependency in result["new"]:
+                print(
+                    f"  + {dependency['from']} -> {dependency['to']}"
+                    f"（{dependency['calls']} 个调用点）"
+                )
+                for example in dependency["examples"]:
+                    print(
+                        f"      {example['caller']} -> {example['callee']}"
+                        f"  ({example['file']}:{example['line']})"
+                    )
+        else:
+            print("\n没有新的模块依赖")
+        if result["stale"]:
+            print(
+                f"\n基线中已不存在的依赖: {len(result['stale'])} 个"
+                "（可以运行 baseline record 收紧基线）"
+            )
+            for dependency in result["stale"]:
+                print(f"  - {dependency['from']} -> {dependency['to']}")
+
+    if result["new"]:
+        sys.exit(1)
+
+
+def _owner_edge(graph: CallGraph, edge: dict) -> dict:
+    """跨团队调用边的 JSON 表示"""
+    return {
+        "from": edge["from"],
+        "to": edge["to"],
+        "caller": graph.qualified_name(edge["caller_id"]),
+        "callee": graph.qualified_name(edge["callee_id"]),
+        "sites": [
+            {"file": site["caller_file"], "line": site["call_site_line"]}
+            for site in edge["sites"]
+        ],
+    }
+
+
+def cmd_owners(args):
+    """
+    代码所有者命令：按 CODEOWNERS 把函数归属到团队，列出团队之间的依赖
+    和跨团队的调用
+    """
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+
+    try:
+        if args.codeowners:
+            codeowners = CodeOwners(args.codeowners)
+        else:
+            # 先从项目根目录向上查找，再从当前目录查找
+            codeowners = CodeOwners.find(graph.project_root() or ".")
+            codeowners = codeowners or CodeOwners.find(".")
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    if codeowners is None:
+        print(
+            f"错误: 找不到 CODEOWNERS 文件（{'、'.join(CODEOWNERS_LOCATIONS)}），"
+            "请使用 --codeowners 指定",
+            file=sys.stderr,
+        )
+        sys.exit(1)
+
+    result = team_dependencies(graph, codeowners)
+    if args.team:
+        result["edges"] = [
+            e for e in result["edges"] if args.team in (e["from"], e["to"])
+        ]
+        result["dependencies"] = [
+            d for d in result["dependencies"] if args.team in (d["from"], d["to"])
+        ]
+
+    if args.format == "json":
+        document = {
+            "codeowners": str(codeowners.path),
+            "teams": result["teams"],
+            "dependencies": [
+                {
+                    **{k: v for k, v in d.items() if k != "examples"},
+                    "examples": [_owner_edge(graph, e) for e in d["examples"]],
+                }
+                for d in result["dependencies"]
+            ],
+            "edges": [_owner_edge(graph, e) for e in result["edges"]],
+        }
+        print(json.dumps(document, ensure_ascii=False, indent=2))
+        return
+
+    print(f"CODEOWNERS: {codeowners.path}（{len(codeowners.rules)} 条规则）")
+    teams = sorted(result["teams"].items(), key=lambda item: (-item[1], item[0]))
+    summary = ", ".join(f"{team} {count} 个" for team, count in teams)
+    print(f"团队: {len(teams)} 个（函数数量: {summary}）")
+
+    if not result["dependencies"]:
+        print("\n没有跨团队的调用")
+        return
+    print(f"\n团队之间的依赖: {len(result['dependencies'])} 个")
+    for dependency in result["dependencies"]:
+        print(
+            f"  {dependency['from']} -> {dependency['to']}"
+            f"（{dependency['edges']} 条调用边，{dependency['calls']} 个调用点）"
+        )
+        if not args.edges:
+            for edge in dependency["examples"]:
+                print(
+                    f"      {graph.qualified_name(edge['caller_id'])} -> "
+                    f"{graph.qualified_name(edge['callee_id'])}"
+                )
+
+    if args.edges:
+        print(f"\n跨团队的调用: {len(result['edges'])} 条")
+        for edge in result["edges"]:
+            print(
+                f"  [{edge['from']} -> {edge['to']}] "
+                f"{_node_display(graph, edge['caller_id'])} -> "
+                f"{_node_display(graph, edge['callee_id'])}"
+            )
+            for site in edge["sites"]:
+                print(f"    调用点: {site['caller_file']}:{site['call_site_line']}")
+
+
+def cmd_lsp(args):
+    """
+    语言服务器命令：在标准输入/输出上提供调用层次
+
+    标准输出只用于协议消息，提示信息写到标准错误；分析数据库更新后（如同时
+    运行 analyze --watch）在下一个请求前重新加载调用图
+    """
+    if not os.path.exists(args.database):
+        print(
+            f"警告: 分析数据库不存在: {args.database}，请先运行 analyze",
+            file=sys.stderr,
+        )
+
+    def load_graph() -> CallGraph:
+        db = CallGraphDB(args.database)
+        try:
+            graph = _load_graph(db, args)
+        finally:
+            db.close()
+        print(f"已加载调用图: {len(graph.nodes)} 个函数", file=sys.stderr)
+        return graph
+
+    def version():
+        # WAL 模式下的写入先落在 -wal 文件中
+        stamps = []
+        for path in (args.database, args.database + "-wal"):
+            try:
+                stamps.append(os.stat(path).st_mtime_ns)
+            except OSError:
+                stamps.append(None)
+        return tuple(stamps)
+
+    print("call-graph 语言服务器已启动（标准输入/输出）", file=sys.stderr)
+    sys.exit(serve(CallHierarchyServer(load_graph, version)))
+
+
+def cmd_tui(args):
+    """终端浏览器命令：交互式浏览函数的调用者和被调用者"""
+    if not sys.stdin.isatty() or not sys.stdout.isatty():
+        print("错误: tui 需要在交互式终端中运行", file=sys.stderr)
+        sys.exit(1)
+    db = CallGraphDB(args.database)
+    try:
+        graph = _load_graph(db, args)
+    finally:
+        db.close()
+    if not graph.nodes:
+        print("错误: 调用图中没有函数，请先运行 analyze", file=sys.stderr)
+        sys.exit(1)
+
+    start = None
+    if args.function:
+        matches = graph.resolve(args.function)
+        if not matches:
+            print(
+                f"错误: 没有找到函数: {args.function}"
+                f"{suggestion(graph, args.function)}",
+                file=sys.stderr,
+            )
+            sys.exit(1)
+        start = matches[0]
+    try:
+        run_tui(graph, query=args.search or "", start=start)
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    except KeyboardInterrupt:
+        pass
+
+
+def _apply_project_config(parser, subparsers, args, argv):
+    """
+    读取 --config 指定的或自动找到的 call_graph.toml，把其中的选项作为默认值
+    重新解析命令行；返回新的参数
+    """
+    config_path = args.config
+    if not config_path and not args.no_config:
+        start = getattr(args, "project_path", None) or os.getcwd()
+        found = find_config(start)
+        if not found:
+            return args
+        config_path = str(found)
+        print(f"使用配置文件: {config_path}", file=sys.stderr)
+    if not config_path:
+        return args
+    try:
+        apply_config(
+            load_config(config_path),
+            config_path,
+            parser,
+            args.command,
+            subparsers.choices.get(args.command),
+        )
+    except ValueError as e:
+        print(f"错误: {e}", file=sys.stderr)
+        sys.exit(1)
+    args = parser.parse_args(argv)
+    args.config = config_path
+    return args
+
+
+def main(argv: Optional[List[str]] = None):
+    """主函数（argv 默认为 sys.argv[1:]）"""
+    parser = argparse.ArgumentParser(
+        description="多语言函数调用关系分析工具",
+        formatter_class=argparse.RawDescriptionHelpFormatter,
+        epilog="""
+使用示例:
+
+  # 分析项目（清空旧数据）
+  python call-graph.py --database myproject.db analyze /path/to/project --clear
+  
+  # 分析项目（使用性能优化模式，适合大型项目）⚡
+  python call-graph.py --database myproject.db analyze /path/to/project --clear --fast
+  
+  # 性能优化模式（自定义参数）
+  python call-graph.py --database myproject.db analyze /path/to/project --clear --fast --workers 8 --batch-size 200
+  
+  # 增量分析（按文件内容哈希缓存解析结果，只重新解析变化的文件）
+  python call-graph.py --database myproject.db analyze /path/to/project --cache
+
+  # 超大项目：低内存模式，调用关系写入临时文件后归并入库
+  python call-graph.py --database myproject.db analyze /path/to/project --fast --low-memory
+
+  # 输出 JSON 结构化日志（各阶段的文件数、函数数和耗时）到 analyze.log
+  python call-graph.py --log-format json --database myproject.db analyze /path/to/project 2> analyze.log
+
+  # 按 Linux 平台和启用的 feature 求值 #[cfg(...)]，排除不参与编译的代码
+  python call-graph.py --database myproject.db analyze /path/to/crate --cfg 'feature="serde"' --cfg 'target_os="linux"'
+
+  # 不跳过 .gitignore / .ignore / .callgraphignore 中忽略的文件
+  python call-graph.py --database myproject.db analyze /path/to/project --no-ignore
+
+  # 只分析 Rust 源文件；不使用自动找到的 call_graph.toml
+  python call-graph.py --no-config --database myproject.db analyze /path/to/project --languages rust
+
+  # 从 debug 构建的二进制文件提取单态化之后的调用图（v0 符号保留泛型实参）
+  RUSTFLAGS="-C symbol-mangling-version=v0" cargo build
+  python call-graph.py --database mybinary.db analyze-binary target/debug/my_app --clear
+
+  # WebAssembly 模块：函数、导入的宿主函数和间接调用目标
+  python call-graph.py --database app.db analyze-binary target/wasm32-wasip1/release/app.wasm --clear
+
+  # 展开 Rust 宏（需要 cargo expand 或 nightly 工具链），宏生成的调用定位到宏调用处
+  python call-graph.py --database myproject.db analyze /path/to/crate --expand-macros --macro-call-sites
+
+  # 加入运算符重载和 Deref 对应的 trait 方法调用
+  python call-graph.py --database myproject.db analyze /path/to/crate --operator-calls
+
+  # 用编译器的 MIR 得到精确的 Rust 调用关系（需要能编译项目）
+  python call-graph.py --database myproject.db analyze /path/to/crate --mir
+
+  # 加入值离开作用域时的 Drop::drop 调用（守卫、连接池等 RAII 类型）
+  python call-graph.py --database myproject.db analyze /path/to/crate --drop-glue
+
+  # 加入调用到的依赖 crate 函数（如 serde_json::to_string），沿调用链最多深入 2 层
+  python call-graph.py --database myproject.db analyze /path/to/crate --include-deps --deps-depth 2
+
+  # 分析项目（排除特定目录）
+  python call-graph.py --database myproject.db analyze /path/to/project --exclude "node_modules,build"
+  
+  # 查看统计信息
+  python call-graph.py --database myproject.db stats
+  
+  # 分析一次后保存为快照，在别处恢复后直接查询（不重新解析代码）
+  python call-graph.py --database myproject.db save graph.cg
+  python call-graph.py --database restored.db load graph.cg
+  
+  # 查询谁调用了某个函数
+  python call-graph.py --database myproject.db query main --callers
+  
+  # 查询某个函数调用了哪些函数
+  python call-graph.py --database myproject.db query process_data --callees
+
+  # 查询表达式：main 可达的函数中直接调用 db::write 的函数
+  python call-graph.py --database myproject.db query -x "callers(db::write) & reachable_from(main)"
+  
+  # 查询调用者树（所有直接和间接调用者，用于重构前的影响分析）
+  python call-graph.py --database myproject.db query validate_input --reverse --depth 5
+
+  # 以树形打印 main 的传递被调用者（类似 cargo tree），-i 反向打印调用者
+  python call-graph.py --database myproject.db tree main --depth 4
+  python call-graph.py --database myproject.db tree validate_input -i --charset ascii
+
+  # 列出库的公开 API 及其用到的内部函数
+  python call-graph.py --database myproject.db api --crate mylib --depth 2
+
+  # 查询支配树（哪些函数控制着整个子系统的入口）
+  python call-graph.py --database myproject.db query main --dominators --depth 3
+
+  # 查询调用链（向下）
+  python call-graph.py --database myproject.db query main --chain --depth 3
+  
+  # 查询完整调用路径（向上+向下）
+  python call-graph.py --database myproject.db query process_data --fullpath
+  
+  # 查询完整路径并显示详细信息
+  python call-graph.py --database myproject.db query validate_input --fullpath --verbose
+  
+  # 搜索函数（模糊匹配）
+  python call-graph.py --database myproject.db search "process"
+  
+  # 搜索函数（显示详细信息）
+  python call-graph.py --database myproject.db search "calculate" --verbose
+
+  # 按限定名模糊查找函数，交互选择一个作为其他命令的参数
+  python call-graph.py --database myproject.db find user_valid
+  python call-graph.py --database myproject.db tree "$(python call-graph.py --database myproject.db find user_valid --select)"
+  
+  # 检测递归环（直接递归和相互递归）
+  python call-graph.py --database myproject.db cycles --verbose
+
+  # 嵌入式目标：按 DWARF 调用帧信息估计递归的栈使用，标出 8 KiB 栈上可能的溢出
+  python call-graph.py --database myproject.db cycles --binary target/thumbv7em-none-eabihf/release/app --stack-limit 8192
+
+  # 列出自动发现的入口点（main、测试、extern 导出、二进制目标、build.rs）
+  python call-graph.py --database myproject.db roots --kind ffi --kind build
+
+  # 检测死代码（从 main、测试函数、#[no_mangle] 函数及指定入口不可达的函数）
+  python call-graph.py --database myproject.db deadcode --root handle_request
+
+  # 查询从 main 到 write_record 的所有调用链
+  python call-graph.py --database myproject.db paths --from main --to write_record --max-depth 6
+
+  # 导出调用图为 DOT 格式
+  python call-graph.py --database myproject.db export --output graph.dot
+  
+  # 导出带样式的 DOT（按模块着色、按类别区分形状、标注调用次数）
+  python call-graph.py --database myproject.db export --color-by-module --shape-by-kind --edge-labels -o graph.dot
+
+  # 按 crate 和顶层模块分组（两层 subgraph cluster）
+  python call-graph.py --database myproject.db export --cluster-depth 2 -o graph.dot
+
+  # 导出 Mermaid 流程图（可直接粘贴到 Markdown 文档）
+  python call-graph.py --database myproject.db export --format mermaid --group-by-module -o graph.mmd
+
+  # 导出 JSON（供其他工具处理，格式见 README）
+  python call-graph.py --database myproject.db export --format json -o graph.json
+
+  # 只导出某个函数 3 跳以内的调用者和被调用者（适合放进设计文档）
+  python call-graph.py --database myproject.db export --focus my_crate::db::save --depth 3 -o save.dot
+
+  # 导出交互式 HTML 页面（缩放、搜索、折叠模块、高亮调用路径，可离线打开）
+  python call-graph.py --database myproject.db export --format html -o graph.html
+
+  # 导出为 GraphML / GEXF，在 yEd、Gephi 中浏览
+  python call-graph.py --database myproject.db export --format gexf -o graph.gexf
+
+  # 导出为可直接用 SQL 查询的 SQLite 数据库
+  python call-graph.py --database myproject.db export --format sqlite -o graph.sqlite
+
+  # 导出节点表和边表（graph/nodes.csv、graph/edges.csv），供电子表格、pandas 或 Neo4j 导入
+  python call-graph.py --database myproject.db export --format csv -o graph
+  python call-graph.py --database myproject.db export --format csv --neo4j-headers -o neo4j
+
+  # 导出为 Cypher 语句，或直接写入 Neo4j（需要 pip install neo4j）
+  python call-graph.py --database myproject.db export --format cypher -o graph.cypher
+  NEO4J_PASSWORD=secret python call-graph.py --database myproject.db export --format cypher --neo4j-uri bolt://localhost:7687
+
+  # 导出 PlantUML 组件图（按模块分组）或类图（每个模块一个类）
+  python call-graph.py --database myproject.db export --format plantuml -o graph.puml
+  python call-graph.py --database myproject.db export --format plantuml --plantuml-style class
+
+  # 导出 D2 图（每个 crate / 模块一个嵌套容器），用 d2 渲染
+  python call-graph.py --database myproject.db export --format d2 -o graph.d2
+
+  # 不安装 Graphviz 直接生成图片（内置分层布局）
+  python call-graph.py --database myproject.db export --format svg -o graph.svg
+  python call-graph.py --database myproject.db export --format png -o graph.png
+
+  # 导出模块级依赖图（每个模块一个节点，边上标注模块间的调用次数）
+  python call-graph.py --database myproject.db export --granularity module -o modules.dot
+
+  # 类型级依赖图：类型的方法之间的调用和签名中使用的类型
+  python call-graph.py --database myproject.db export --granularity type -f mermaid -o types.mmd
+
+  # 把相互递归的函数组缩成一个节点，导出无环图
+  python call-graph.py --database myproject.db export --condense -o dag.dot
+
+  # 大项目只导出最重要的 80 个节点，其余合并为“… 还有 k 个”占位节点
+  python call-graph.py --database myproject.db export --max-nodes 80 -o overview.dot
+
+  # 排除测试代码和 Display/Debug 实现（也可以写在配置文件的 [filters] 表中）
+  python call-graph.py --database myproject.db export --exclude "tests::*" --exclude "*::fmt" -o graph.dot
+
+  # 只分析确定的调用边（去掉动态分发候选和按名称猜测的目标）
+  python call-graph.py --database myproject.db deadcode --min-confidence certain
+
+  # 只看公开 API 之间的调用（私有辅助函数合并到边上），用于文档
+  python call-graph.py --database myproject.db export --public-only -o public.dot
+
+  # 加载第三方语言前端插件（模块中调用 register_language 注册语言）
+  python call-graph.py --plugin my_plugins.zig --database myproject.db analyze /path/to/project
+
+  # 检查架构规则（配置文件 [rules] 表中的 deny），有违规时退出码为 1
+  python call-graph.py --database myproject.db --config callgraph.toml check
+  python call-graph.py --database myproject.db check --rule "ui::* -> db::*"
+
+  # 输出 SARIF 日志，上传到 GitHub code scanning 后以代码注释显示
+  python call-graph.py --database myproject.db check --format sarif > check.sarif
+  python call-graph.py --database myproject.db deadcode --format sarif > deadcode.sarif
+
+  # 在 GitHub Actions 中直接输出注释，违规调用显示在 Pull Request 的差异中
+  python call-graph.py --database myproject.db check --format github
+
+  # 作为语言服务器运行，在编辑器中浏览调用层次（Call Hierarchy）
+  python call-graph.py --database /path/to/myproject.db lsp
+
+  # 在终端中交互式浏览调用层次（/ 搜索，Enter 跳转，Backspace 返回）
+  python call-graph.py --database myproject.db tui my_crate::db::save
+
+  # 监视模式：源文件变化时增量重新分析，并重新导出 HTML
+  python call-graph.py --database myproject.db analyze /path/to/project --watch --export graph.html
+
+  # 比较 main 分支和 HEAD 的调用图，出现新的模块依赖时 CI 失败
+  python call-graph.py diff --base main --head HEAD --fail-on-new-deps
+
+  # 从 v1.0 起每月一个快照，输出指标的趋势 CSV（观察架构的劣化）
+  python call-graph.py history --since v1.0 --step monthly -o trend.csv
+
+  # 记录已批准的模块依赖基线，之后在 CI 中禁止新增基线之外的模块依赖
+  python call-graph.py --database myproject.db baseline record
+  python call-graph.py --database myproject.db baseline check
+
+  # 按 CODEOWNERS 统计团队之间的依赖，列出某个团队的所有跨团队调用
+  python call-graph.py --database myproject.db owners
+  python call-graph.py --database myproject.db owners --team @org/payments --edges
+
+  # 函数指标：按扇出排序找出“上帝函数”，或导出为 CSV
+  python call-graph.py --database myproject.db metrics --sort fan_out --limit 10
+  python call-graph.py --database myproject.db metrics --format csv -o metrics.csv
+
+  # 按介数中心度找出位于最多调用路径上的瓶颈函数
+  python call-graph.py --database myproject.db metrics --centrality
+
+  # 列出从 main 到 unsafe 代码的所有调用路径（安全审查）
+  python call-graph.py --database myproject.db unsafe-reach --from main
+
+  # 列出可能 panic 的公开 API 函数及最短示例路径（忽略下标访问）
+  python call-graph.py --database myproject.db panic-reach --ignore index
+
+  # 错误传播：read_config 返回的错误经过哪些调用者转发（?），在哪里被处理
+  python call-graph.py --database myproject.db errors read_config
+
+  # no_std 兼容性审计：列出能到达 std 专有函数（std::fs、std::thread 等）的公开 API
+  python call-graph.py --database myproject.db no-std
+  python call-graph.py --database myproject.db no-std --allow core --check
+
+  # trait 实现覆盖：从未被覆盖的默认方法和从未被调用的 trait 方法
+  python call-graph.py --database myproject.db traits --unused
+
+  # 接口隔离建议：哪些模块只使用了 UserManager 的读取方法
+  python call-graph.py --database myproject.db interfaces --type UserManager
+
+  # 分层架构推断：生成分层图，列出违反分层的反向依赖
+  python call-graph.py --database myproject.db layers --format dot > layers.dot
+
+  # 重复函数检测：函数体相同或调用了几乎相同的一组函数的函数
+  python call-graph.py --database myproject.db duplicates --min-tokens 50
+
+  # 生成 API 影响范围表格（模块、副作用、unsafe、最大调用深度），放进文档
+  python call-graph.py --database myproject.db touches --format markdown > docs/api.md
+
+  # 对比两个二进制目标各自需要的代码（按模块汇总），用于拆分单体程序
+  python call-graph.py --database myproject.db reach --roots main --roots admin_main --compare --modules
+
+  # 弃用 API 审计：列出调用 #[deprecated] 函数的调用点和弃用说明
+  python call-graph.py --database myproject.db deprecated
+
+  # 把 API 迁移为 const fn：列出 const fn 中对非 const 函数的调用
+  python call-graph.py --database myproject.db const-eval --check
+
+  # 副作用标记：检查 irq 模块中的函数是否可能间接分配内存
+  python call-graph.py --database myproject.db --config callgraph.toml effects --only alloc --in "irq::*" --check
+
+  # 在各个 feature 组合下求值 #[cfg]，找出只在部分 feature 下存在、却没有调用者的函数
+  python call-graph.py --database myproject.db features
+
+  # 叠加 cargo llvm-cov 的覆盖率：列出可以从 main 到达却没有被执行的函数
+  cargo llvm-cov --lcov --output-path lcov.info
+  python call-graph.py --database myproject.db coverage lcov.info -v
+
+  # 叠加 perf 采样数据：列出热点函数和热路径，并导出按样本数着色的 DOT
+  perf script > perf.txt
+  python call-graph.py --database myproject.db profile perf.txt
+  python call-graph.py --database myproject.db export --profile perf.txt -o hot.dot
+
+  # 测试可达性：列出没有任何测试能到达的函数，比例低于 80% 时 CI 失败
+  python call-graph.py --database myproject.db test-reach --fail-under 80
+
+  # 变更影响分析：上一个提交以来修改的函数、它们的调用者和需要运行的测试
+  python call-graph.py --database myproject.db impact --diff HEAD~1
+  python call-graph.py --database myproject.db impact --diff origin/main --list test-files
+
+  # 使用 Graphviz 生成可视化图片
+  dot -Tpng graph.dot -o graph.png
+
+安装依赖:
+  pip install -e .
+
+注意：
+  1. --database 参数必须放在子命令之前
+  2. 首次使用需要先安装依赖（见上方）
+  3. 首次分析建议使用 --clear 清空旧数据
+  4. 默认排除 node_modules, .git, __pycache__ 等目录
+  
+其他运行方式：
+  # 使用 Python 模块方式
+  python -m call_graph --database myproject.db stats
+  
+  # 如果已安装到系统（推荐日常使用）
+  call-graph --database myproject.db stats
+        """,
+    )
+
+    parser.add_argument(
+        "--database",
+        "-d",
+        default="call_graph.db",
+        help="数据库文件路径 (默认: call_graph.db)",
+    )
+
+    parser.add_argument(
+        "--config",
+        help=f"配置文件路径（TOML；默认从被分析的目录或当前目录向上查找 "
+        f"{CONFIG_FILE_NAME}；与子命令同名的表为该命令的选项默认值，"
+        "[filters] 表中的 include/exclude/phase/edge_kinds 与命令行合并，"
+        "[rules] 表为 check 命令的规则，"
+        "[effects] 表为 effects 命令的副作用标签，[roots] 表为额外的入口点）",
+    )
+
+    parser.add_argument(
+        "--no-config",
+        action="store_true",
+        help=f"不自动查找 {CONFIG_FILE_NAME}",
+    )
+
+    parser.add_argument(
+        "--plugin",
+        action="append",
+        default=[],
+        metavar="MODULE",
+        help="加载语言前端插件模块（可多次指定；已安装包的 call_graph.languages "
+        "入口点会自动加载）",
+    )
+
+    parser.add_argument(
+        "--verbose",
+        dest="log_verbose",
+        action="store_true",
+        help="输出诊断日志到标准错误（包括每个文件的解析结果）",
+    )
+
+    parser.add_argument(
+        "--log-format",
+        choices=LOG_FORMATS,
+        default="text",
+        help="日志格式：text（默认，只在 --verbose 时输出）或 json"
+        "（每行一个 JSON 事件，包括各阶段的文件数、函数数和耗时）",
+    )
+
+    subparsers = parser.add_subparsers(dest="command", help="子命令")
+
+    # 基于调用图的命令共用的包含/排除过滤选项
+    filter_parser = argparse.ArgumentParser(add_help=False)
+    filter_parser.add_argument(
+        "--include",
+        action="append",
+        metavar="PATTERN",
+        help="只保留匹配的函数：glob（如 my_crate::*、src/core/*）或 re:正则，"
+        "匹配限定名和文件路径，可多次指定",
+    )
+    filter_parser.add_argument(
+        "--exclude",
+        action="append",
+        metavar="PATTERN",
+        help="排除匹配的函数（如 tests::*、*::fmt、re:_test$），可多次指定",
+    )
+    filter_parser.add_argument(
+        "--phase",
+        choices=PHASES,
+        help="只保留运行时（runtime）或编译期（compile-time：build.rs 和过程宏 "
+        "crate）的函数，避免生成代码的依赖混入程序的调用路径",
+    )
+    filter_parser.add_argument(
+        "--edge-kinds",
+        action="append",
+        metavar="KIND",
+        help="只保留这些类型的调用边（逗号分隔，可多次指定）："
+        f"{', '.join(EDGE_KINDS)}",
+    )
+    filter_parser.add_argument(
+        "--min-confidence",
+        choices=CONFIDENCE_LEVELS,
+        help="只保留置信度不低于该级别的调用边：certain（确定的调用）、"
+        "probable（加上 trait 对象、函数指针等可能的目标）、"
+        "heuristic（加上按名称猜测的目标，默认）",
+    )
+    filter_parser.add_argument(
+        "--public-only",
+        action="store_true",
+        help="只保留 public 函数，经过私有函数的调用链合并为公开函数之间的边"
+        "（标注经过的私有函数数量），用于记录 crate 的对外行为",
+    )
+
+    # analyze命令
+    analyze_parser = subparsers.add_parser("analyze", help="分析项目")
+    analyze_parser.add_argument("project_path", help="项目路径")
+    analyze_parser.add_argument("--exclude", "-e", help="要排除的目录，用逗号分隔")
+    analyze_parser.add_argument(
+        "--languages",
+        "-l",
+        help="只分析这些语言的源文件，用逗号分隔（如 rust,python；默认：所有语言）",
+    )
+    analyze_parser.add_argument(
+        "--no-ignore",
+        action="store_true",
+        help="不遵循 .gitignore、.ignore 和 .callgraphignore（默认跳过其中忽略的路径）",
+    )
+    analyze_parser.add_argument(
+        "--cfg",
+        action="append",
+        metavar="SPEC",
+        help='生效的 Rust cfg（同 rustc --cfg，如 unix、feature="serde"、'
+        'target_os="linux"，可多次指定）；指定时按它们求值 #[cfg(...)]',
+    )
+    analyze_parser.add_argument(
+        "--cfg-mode",
+        choices=CFG_MODES,
+        default="filter",
+        help="与 --cfg 一起使用：filter（默认）排除条件不成立的函数和调用；"
+        "tag 全部保留，只在函数上标记 cfg_active",
+    )
+    analyze_parser.add_argument(
+        "--clear", "-c", action="store_true", help="清空现有数据"
+    )
+    analyze_parser.add_argument(
+        "--fast", "-f", action="store_true", help="使用性能优化模式（多进程+批量操作）"
+    )
+    analyze_parser.add_argument(
+        "--workers",
+        "-w",
+        type=int,
+        default=None,
+        help="工作进程数（默认：CPU核心数-1）",
+    )
+    analyze_parser.add_argument(
+        "--batch-size",
+        "-b",
+        type=int,
+        default=100,
+        help="批量插入数据库的大小（默认：100）",
+    )
+    analyze_parser.add_argument(
+        "--low-memory",
+        action="store_true",
+        help="低内存模式：调用引用和调用关系写入临时文件，分段排序后归并入库"
+        "（适合数万个函数的超大项目）",
+    )
+    analyze_parser.add_argument(
+        "--spill-dir",
+        default=None,
+        metavar="DIR",
+        help="与 --low-memory 一起使用：临时文件目录（默认：系统临时目录）",
+    )
+    analyze_parser.add_argument(
+        "--cache",
+        action="store_true",
+        help="启用增量分析缓存：只重新解析内容变化的文件",
+    )
+    analyze_parser.add_argument(
+        "--cache-dir",
+        default=None,
+        help="缓存目录（默认：<项目路径>/.call_graph_cache）",
+    )
+    analyze_parser.add_argument(
+        "--expand-macros",
+        action="store_true",
+        help="通过 cargo expand 展开 Rust 宏，补充宏生成的调用（需要能编译项目）",
+    )
+    analyze_parser.add_argument(
+        "--macro-call-sites",
+        action="store_true",
+        help="将宏生成的调用定位到对应的宏调用位置（默认定位到调用者的定义处）",
+    )
+    analyze_parser.add_argument(
+        "--mir",
+        action="store_true",
+        help="Rust: 编译 crate 获取 MIR，用编译器解析的调用（方法、闭包、泛型实例化）"
+        "替换语法分析推断的调用关系（需要能编译项目）",
+    )
+    analyze_parser.add_argument(
+        "--operator-calls",
+        action="store_true",
+        help="Rust: 加入运算符重载（a + b、a[i]）和解引用（*a、自动解引用）"
+        "对应的 trait 实现方法的调用",
+    )
+    analyze_parser.add_argument(
+        "--drop-glue",
+        action="store_true",
+        help="Rust: 加入从函数到其中被销毁的值的 Drop::drop 实现的调用",
+    )
+    analyze_parser.add_argument(
+        "--include-deps",
+        action="store_true",
+        help="加入 Rust 代码调用到的依赖 crate 中的函数（源码来自 cargo 缓存）",
+    )
+    analyze_parser.add_argument(
+        "--deps-depth",
+        type=int,
+        default=DEFAULT_DEPENDENCY_DEPTH,
+        metavar="N",
+        help=f"与 --include-deps 一起使用：沿调用链最多深入依赖的层数"
+        f"（默认：{DEFAULT_DEPENDENCY_DEPTH}）",
+    )
+    analyze_parser.add_argument(
+        "--watch",
+        action="store_true",
+        help="分析完成后监视源文件变化，自动增量重新分析（隐含 --cache）",
+    )
+    analyze_parser.add_argument(
+        "--export",
+        metavar="FILE",
+        help="每次分析后导出调用图到该文件（格式按扩展名：.html/.dot/.json/.mmd 等）",
+    )
+    analyze_parser.add_argument(
+        "--export-format",
+        choices=[
+            "dot",
+            "mermaid",
+            "json",
+            "html",
+            "graphml",
+            "gexf",
+            "plantuml",
+            "d2",
+            "svg",
+            "png",
+            "sqlite",
+            "csv",
+            "cypher",
+        ],
+        help="与 --export 一起使用：指定导出格式（默认按扩展名推断）",
+    )
+
+    # analyze-binary命令
+    binary_parser = subparsers.add_parser(
+        "analyze-binary",
+        help="从带调试信息的二进制文件（需要 objdump）或 WebAssembly 模块提取调用图",
+    )
+    binary_parser.add_argument(
+        "binary", help="二进制文件（可执行文件、.so、.a 中的目标文件）或 .wasm 模块"
+    )
+    binary_parser.add_argument(
+        "--source-root",
+        metavar="DIR",
+        help="只保留源码位于该目录下的函数（默认：二进制文件所在的 target/ 的上级"
+        "目录，不在 target/ 中时为当前目录）",
+    )
+    binary_parser.add_argument(
+        "--all-symbols",
+        action="store_true",
+        help="保留所有有调试信息的函数（包括标准库和依赖）",
+    )
+    binary_parser.add_argument(
+        "--objdump",
+        default="objdump",
+        metavar="PATH",
+        help="反汇编工具 (默认: objdump，也可以使用 llvm-objdump)",
+    )
+    binary_parser.add_argument(
+        "--clear", "-c", action="store_true", help="清空现有数据"
+    )
+
+    # query命令
+    query_parser = subparsers.add_parser("query", help="查询调用关系")
+    query_parser.add_argument("function", help="函数名称（--expr 时为查询表达式）")
+    query_parser.add_argument(
+        "--expr",
+        "-x",
+        action="store_true",
+        help="把参数作为查询表达式求值，列出匹配的函数，如 "
+        "'callers(db::write) & reachable_from(main)'、'fanin(>20)'；"
+        "查询函数: callers、callees、reachable_from、reaches、fanin、fanout、"
+        "match、kind、roots、all，运算符: & | - !",
+    )
+    query_parser.add_argument(
+        "--callers", action="store_true", help="查询谁调用了这个函数"
+    )
+    query_parser.add_argument(
+        "--callees", action="store_true", help="查询这个函数调用了谁"
+    )
+    query_parser.add_argument(
+        "--reverse",
+        action="store_true",
+        help="查询调用者树（向上追溯所有直接和间接调用者，用于影响分析）",
+    )
+    query_parser.add_argument(
+        "--dominators",
+        action="store_true",
+        help="查询从该函数出发的支配树（子节点的所有调用路径都经过父节点）",
+    )
+    query_parser.add_argument("--chain", action="store_true", help="查询调用链（向下）")
+    query_parser.add_argument(
+        "--fullpath",
+        action="store_true",
+        help="查询完整调用路径（向上追溯到入口，向下追溯到叶子）",
+    )
+    query_parser.add_argument(
+        "--depth", type=int, default=10, help="最大搜索深度 (默认: 10)"
+    )
+    query_parser.add_argument(
+        "--verbose", "-v", action="store_true", help="显示详细信息（包括完整路径）"
+    )
+    query_parser.add_argument(
+        "--show-call-sites",
+        action="store_true",
+        help="与 --callers/--callees 一起使用：列出每个调用点（文件:行号:列号和源码片段）",
+    )
+    query_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式，json 只支持 --expr/--callers/--callees/--dominators "
+        "(默认: text)",
+    )
+    query_parser.add_argument(
+        "--snapshot",
+        metavar="FILE",
+        help="查询快照文件（save 保存的 .cg）而不是 --database",
+    )
+
+    # search命令
+    search_parser = subparsers.add_parser("search", help="搜索符号")
+    search_parser.add_argument("pattern", help="搜索模式")
+    search_parser.add_argument(
+        "--verbose", "-v", action="store_true", help="显示详细信息"
+    )
+
+    # find命令
+    find_parser = subparsers.add_parser(
+        "find",
+        parents=[filter_parser],
+        help="按限定名模糊查找函数（子串、单词、子序列匹配），列出候选及其位置",
+    )
+    find_parser.add_argument(
+        "pattern", help="查找文本（如 user_valid、db::save、valid user）"
+    )
+    find_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        default=20,
+        help="最多列出多少个候选，0 表示不限制 (默认: 20)",
+    )
+    find_parser.add_argument(
+        "--select",
+        "-s",
+        action="store_true",
+        help="交互选择一个候选，把它的限定名输出到标准输出"
+        "（只有一个候选时直接输出）",
+    )
+    find_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # stats命令
+    subparsers.add_parser("stats", help="显示统计信息")
+
+    # save / load命令
+    save_parser = subparsers.add_parser(
+        "save", help="把分析数据库保存为紧凑的二进制快照（如 graph.cg）"
+    )
+    save_parser.add_argument("output", help="快照文件路径")
+    load_parser = subparsers.add_parser(
+        "load", help="从快照恢复分析数据库（替换数据库中已有的数据）"
+    )
+    load_parser.add_argument("input", help="快照文件路径")
+
+    # tree命令
+    tree_parser = subparsers.add_parser(
+        "tree",
+        parents=[filter_parser],
+        help="以树形打印函数的传递被调用者（类似 cargo tree）",
+    )
+    tree_parser.add_argument("function", help="根函数（函数名或限定名）")
+    tree_parser.add_argument(
+        "--depth", type=int, default=10, help="最多展开的层数 (默认: 10)"
+    )
+    tree_parser.add_argument(
+        "--invert",
+        "-i",
+        action="store_true",
+        help="反向：打印传递调用者（子节点是调用父节点的函数）",
+    )
+    tree_parser.add_argument(
+        "--charset",
+        default="utf8",
+        choices=list(TREE_CHARSETS),
+        help="连接线使用的字符集 (默认: utf8)",
+    )
+    tree_parser.add_argument(
+        "--no-dedupe",
+        action="store_true",
+        help="重复展开已经显示过的函数（默认只标注 (见上文)）",
+    )
+
+    # api命令
+    api_parser = subparsers.add_parser(
+        "api",
+        parents=[filter_parser],
+        help="列出库 crate 的公开 API（从 crate 根可访问的 pub 函数）及其调用的内部函数",
+    )
+    api_parser.add_argument(
+        "--crate", action="append", help="只列出这个 crate 的公开 API（可多次指定）"
+    )
+    api_parser.add_argument(
+        "--depth",
+        type=int,
+        default=3,
+        help="每个公开函数的被调用者树展开的层数，0 表示不打印树 (默认: 3)",
+    )
+    api_parser.add_argument(
+        "--charset",
+        default="utf8",
+        choices=list(TREE_CHARSETS),
+        help="连接线使用的字符集 (默认: utf8)",
+    )
+    api_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # cycles命令
+    cycles_parser = subparsers.add_parser(
+        "cycles",
+        parents=[filter_parser],
+        help="检测递归环（直接递归和相互递归）"
+    )
+    cycles_parser.add_argument(
+        "--verbose", "-v", action="store_true", help="显示环上每条边的调用点"
+    )
+    cycles_parser.add_argument(
+        "--binary",
+        metavar="FILE",
+        help="从二进制文件的 DWARF 调用帧信息估计每个函数的栈帧大小（需要 readelf、nm）",
+    )
+    cycles_parser.add_argument(
+        "--stack-limit",
+        type=int,
+        metavar="BYTES",
+        help="栈大小（字节），与 --binary 一起计算最大递归深度，标出可能的栈溢出",
+    )
+    cycles_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json", *FINDING_FORMATS],
+        help="输出格式 (默认: text)，sarif 用于 GitHub code scanning 等 CI 平台，"
+        "github 输出 GitHub Actions 注释",
+    )
+
+    # deadcode命令
+    deadcode_parser = subparsers.add_parser(
+        "deadcode",
+        parents=[filter_parser],
+        help="检测从入口点不可达的函数（死代码）"
+    )
+    deadcode_parser.add_argument(
+        "--root",
+        "-r",
+        action="append",
+        metavar="FUNCTION",
+        help="额外的入口函数名（可多次指定）",
+    )
+    deadcode_parser.add_argument(
+        "--no-default-roots",
+        action="store_true",
+        help="不使用默认入口点（main、测试函数、#[no_mangle]、extern 导出、build.rs 等），"
+        "只用 --root",
+    )
+    deadcode_parser.add_argument(
+        "--verbose", "-v", action="store_true", help="列出所有入口点"
+    )
+    deadcode_parser.add_argument(
+        "--base",
+        metavar="REV",
+        help="基准 git 修订版本（如 main）：只报告相对基准版本新变为不可达的函数，"
+        "sarif / github 格式中级别为 warning",
+    )
+    deadcode_parser.add_argument(
+        "--repo", default=".", help="--base 的 git 仓库或其中的项目子目录（默认：当前目录）"
+    )
+    deadcode_parser.add_argument(
+        "--exclude-dirs", help="分析基准版本时要排除的目录，用逗号分隔"
+    )
+    deadcode_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", *FINDING_FORMATS],
+        help="输出格式 (默认: text)，sarif 用于 GitHub code scanning 等 CI 平台，"
+        "github 输出 GitHub Actions 注释",
+    )
+
+    # roots命令
+    roots_parser = subparsers.add_parser(
+        "roots",
+        parents=[filter_parser],
+        help="列出自动发现的入口点（deadcode、metrics 等可达性分析的默认起点）",
+    )
+    roots_parser.add_argument(
+        "--kind",
+        "-k",
+        action="append",
+        choices=list(ENTRY_REASONS),
+        help="只列出这类入口点（可多次指定）",
+    )
+    roots_parser.add_argument(
+        "--root",
+        "-r",
+        action="append",
+        metavar="FUNCTION",
+        help="额外的入口函数名（可多次指定）",
+    )
+    roots_parser.add_argument(
+        "--no-default-roots",
+        action="store_true",
+        help="不使用自动发现的入口点，只列出 --root",
+    )
+    roots_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # features命令
+    features_parser = subparsers.add_parser(
+        "features",
+        parents=[filter_parser],
+        help="feature 组合分析：在 Cargo.toml 的各个 feature 组合下求值 #[cfg]，"
+        "找出只在部分组合下存在的函数和调用",
+    )
+    features_parser.add_argument(
+        "--package",
+        "-p",
+        action="append",
+        metavar="CRATE",
+        help="只分析这些 crate（可多次指定，默认为所有声明了 feature 的 crate）",
+    )
+    features_parser.add_argument(
+        "--set",
+        action="append",
+        metavar="FEATURES",
+        help="要分析的组合，逗号分隔的 feature（可多次指定，\"\" 表示不启用任何 "
+        "feature）；默认按 Cargo.toml 生成",
+    )
+    features_parser.add_argument(
+        "--max-features",
+        type=int,
+        default=DEFAULT_MAX_FEATURES,
+        metavar="N",
+        help="feature 不超过 N 个时分析所有组合，否则只分析无 feature、默认、"
+        f"每个 feature 单独启用和全部启用 (默认: {DEFAULT_MAX_FEATURES})",
+    )
+    features_parser.add_argument(
+        "--cfg",
+        action="append",
+        metavar="SPEC",
+        help='各个组合共同生效的其他 cfg（如 unix、target_os="linux"，可多次指定）',
+    )
+    features_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+    features_parser.add_argument(
+        "--verbose", "-v", action="store_true", help="同时列出只在部分组合下存在的调用"
+    )
+
+    # coverage命令
+    coverage_parser = subparsers.add_parser(
+        "coverage",
+        parents=[filter_parser],
+        help="覆盖率：把 lcov / Cobertura 覆盖率报告对应到调用图，列出可以从 main "
+        "到达却没有被执行的函数",
+    )
+    coverage_parser.add_argument(
+        "coverage",
+        metavar="FILE",
+        help="覆盖率报告（如 cargo llvm-cov --lcov --output-path lcov.info 的输出）",
+    )
+    coverage_parser.add_argument(
+        "--coverage-format",
+        default="auto",
+        choices=COVERAGE_FORMATS,
+        help="覆盖率报告的格式 (默认: auto，按内容识别)",
+    )
+    coverage_parser.add_argument(
+        "--from",
+        dest="source",
+        action="append",
+        metavar="FUNCTION",
+        help="入口函数名，可多次指定（默认：main 函数）",
+    )
+    coverage_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+    coverage_parser.add_argument(
+        "--verbose", "-v", action="store_true", help="同时列出从入口函数到达的一条调用路径"
+    )
+
+    # profile命令
+    profile_parser = subparsers.add_parser(
+        "profile",
+        parents=[filter_parser],
+        help="运行时剖析数据：把采样的调用栈对应到调用图，列出热点函数、热路径和"
+        "静态分析没有发现的调用",
+    )
+    profile_parser.add_argument(
+        "profile",
+        metavar="FILE",
+        help="剖析数据文件（perf script 输出、pprof 的 profile.pb.gz 或折叠栈）",
+    )
+    profile_parser.add_argument(
+        "--profile-format",
+        default="auto",
+        choices=PROFILE_FORMATS,
+        help="剖析数据的格式 (默认: auto，按内容识别)",
+    )
+    profile_parser.add_argument(
+        "--top",
+        type=int,
+        default=20,
+        metavar="N",
+        help="列出样本数最多的 N 个函数和调用，0 表示全部 (默认: 20)",
+    )
+    profile_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+    profile_parser.add_argument(
+        "--verbose", "-v", action="store_true", help="同时列出无法对应到项目函数的帧"
+    )
+
+    # test-reach命令
+    test_reach_parser = subparsers.add_parser(
+        "test-reach",
+        parents=[filter_parser],
+        help="测试可达性：每个生产代码函数可以被哪些测试到达，列出没有测试到达的函数",
+    )
+    test_reach_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+    test_reach_parser.add_argument(
+        "--fail-under",
+        type=float,
+        metavar="PERCENT",
+        help="测试可达的函数比例低于该百分比时以退出码 1 结束（用于 CI）",
+    )
+    test_reach_parser.add_argument(
+        "--verbose",
+        "-v",
+        action="store_true",
+        help="同时列出可以被测试到达的函数及到达它们的测试",
+    )
+
+    # impact命令
+    impact_parser = subparsers.add_parser(
+        "impact",
+        parents=[filter_parser],
+        help="变更影响分析：git diff 中修改的函数、它们的传递调用者和需要运行的测试",
+    )
+    impact_parser.add_argument(
+        "--diff",
+        required=True,
+        metavar="REV",
+        help="git diff 的修订版本：如 HEAD~1（与当前工作区比较）或 main..HEAD",
+    )
+    impact_parser.add_argument(
+        "--repo", default=".", help="git 仓库或其中的目录（默认：当前目录）"
+    )
+    impact_parser.add_argument(
+        "--list",
+        choices=["tests", "test-files"],
+        help="只输出需要运行的测试（限定名）或测试文件，每行一个，便于 CI 使用",
+    )
+    impact_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # unsafe-reach命令
+    unsafe_parser = subparsers.add_parser(
+        "unsafe-reach",
+        parents=[filter_parser],
+        help="列出从入口函数到包含 unsafe 代码（unsafe fn、unsafe 块）的函数的调用路径",
+    )
+    unsafe_parser.add_argument(
+        "--from",
+        dest="source",
+        action="append",
+        metavar="FUNCTION",
+        help="入口函数名，可多次指定（默认：main、测试函数等默认入口点）",
+    )
+    unsafe_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        default=5,
+        help="每个 unsafe 函数最多显示的路径条数，按长度从短到长 (默认: 5，0 表示不限制)",
+    )
+    unsafe_parser.add_argument(
+        "--max-depth", type=int, help="路径的最大跳数（默认不限制）"
+    )
+    unsafe_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # panic-reach命令
+    panic_parser = subparsers.add_parser(
+        "panic-reach",
+        parents=[filter_parser],
+        help="列出可能直接或间接 panic 的公开 API 函数及最短示例路径（Rust）",
+    )
+    panic_parser.add_argument(
+        "--from",
+        dest="source",
+        action="append",
+        metavar="FUNCTION",
+        help="只检查指定的函数，可多次指定（默认：所有公开的非测试函数）",
+    )
+    panic_parser.add_argument(
+        "--ignore",
+        action="append",
+        metavar="KIND",
+        help=f"忽略的 panic 类别（逗号分隔，可多次指定）：{', '.join(PANIC_KINDS)}",
+    )
+    panic_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json", *FINDING_FORMATS],
+        help="输出格式 (默认: text)，sarif 用于 GitHub code scanning 等 CI 平台，"
+        "github 输出 GitHub Actions 注释",
+    )
+
+    # no-std命令
+    no_std_parser = subparsers.add_parser(
+        "no-std",
+        parents=[filter_parser],
+        help="no_std 兼容性审计：列出能到达 std 专有函数的公开 API 函数及最短示例路径（Rust）",
+    )
+    no_std_parser.add_argument(
+        "--allow",
+        action="append",
+        choices=DEFAULT_ALLOWED,
+        help="允许使用的标准库 crate，可多次指定（默认：core 和 alloc；"
+        "只指定 core 时对 alloc 的调用也算作违规）",
+    )
+    no_std_parser.add_argument(
+        "--from",
+        dest="source",
+        action="append",
+        metavar="FUNCTION",
+        help="只检查指定的函数，可多次指定（默认：所有公开的非测试函数）",
+    )
+    no_std_parser.add_argument(
+        "--check",
+        action="store_true",
+        help="有函数在 no_std 下不可用时退出码为 1（CI 使用）",
+    )
+    no_std_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # errors命令
+    errors_parser = subparsers.add_parser(
+        "errors",
+        parents=[filter_parser],
+        help="错误传播：从返回 Result / Option 的函数沿调用者列出转发（?）和处理错误的位置（Rust）",
+    )
+    errors_parser.add_argument(
+        "function",
+        nargs="?",
+        help="可能失败的函数名（支持限定名）；省略时统计所有返回 Result / Option 的函数",
+    )
+    errors_parser.add_argument(
+        "--depth",
+        type=int,
+        default=DEFAULT_ERROR_DEPTH,
+        help=f"错误传播树展开的最大层数 (默认: {DEFAULT_ERROR_DEPTH})",
+    )
+    errors_parser.add_argument(
+        "--charset",
+        default="utf8",
+        choices=list(TREE_CHARSETS),
+        help="连接线使用的字符集 (默认: utf8)",
+    )
+    errors_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        default=20,
+        help="不指定函数时最多列出的函数数量，按调用点数量降序 (默认: 20，0 表示不限制)",
+    )
+    errors_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # interfaces命令
+    interfaces_parser = subparsers.add_parser(
+        "interfaces",
+        parents=[filter_parser],
+        help="接口隔离建议：按调用者模块把类型的方法聚为几组，建议拆分 trait / 结构体",
+    )
+    interfaces_parser.add_argument(
+        "--type", help="只分析这个类型（也列出不需要拆分的情况）"
+    )
+    interfaces_parser.add_argument(
+        "--similarity",
+        type=float,
+        default=DEFAULT_SIMILARITY,
+        help="使用两组方法的模块集合的相似度（Jaccard）不低于该值时合并为一组，"
+        f"越高拆分得越细 (默认: {DEFAULT_SIMILARITY})",
+    )
+    interfaces_parser.add_argument(
+        "--min-methods",
+        type=int,
+        default=DEFAULT_MIN_METHODS,
+        help=f"只分析至少有这么多被外部调用的方法的类型 (默认: {DEFAULT_MIN_METHODS})",
+    )
+    interfaces_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        default=20,
+        help="最多列出的类型数量 (默认: 20，0 表示不限制)",
+    )
+    interfaces_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # layers命令
+    layers_parser = subparsers.add_parser(
+        "layers",
+        parents=[filter_parser],
+        help="分层架构推断：把模块依赖图排成层，列出违反分层的反向依赖",
+    )
+    layers_parser.add_argument(
+        "--sites",
+        type=int,
+        default=3,
+        help="每条反向依赖最多列出的调用点数量 (默认: 3，0 表示不限制)",
+    )
+    layers_parser.add_argument(
+        "--check",
+        action="store_true",
+        help="有反向依赖时退出码为 1（CI 使用）",
+    )
+    layers_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json", "dot", "mermaid"],
+        help="输出格式：text、json，或 DOT / Mermaid 分层图 (默认: text)",
+    )
+
+    # duplicates命令
+    duplicates_parser = subparsers.add_parser(
+        "duplicates",
+        parents=[filter_parser],
+        help="重复函数检测：按函数体指纹和被调用函数列出可疑的相似函数",
+    )
+    duplicates_parser.add_argument(
+        "--min-tokens",
+        type=int,
+        default=DEFAULT_MIN_TOKENS,
+        help=f"只比较至少有这么多词法单元的函数 (默认: {DEFAULT_MIN_TOKENS})",
+    )
+    duplicates_parser.add_argument(
+        "--min-calls",
+        type=int,
+        default=DEFAULT_MIN_CALLS,
+        help=f"按调用比较时函数至少要有这么多调用点 (默认: {DEFAULT_MIN_CALLS})",
+    )
+    duplicates_parser.add_argument(
+        "--similarity",
+        type=float,
+        default=DEFAULT_CALLEE_SIMILARITY,
+        help="被调用函数多重集合的相似度（加权 Jaccard）不低于该值时算作相似 "
+        f"(默认: {DEFAULT_CALLEE_SIMILARITY})",
+    )
+    duplicates_parser.add_argument(
+        "--tests",
+        action="store_true",
+        help="也比较测试代码中的函数（默认跳过）",
+    )
+    duplicates_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        default=20,
+        help="最多列出的分组数量 (默认: 20，0 表示不限制)",
+    )
+    duplicates_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # touches命令
+    touches_parser = subparsers.add_parser(
+        "touches",
+        parents=[filter_parser],
+        help="入口函数影响范围：每个公开函数可达的模块、副作用、unsafe 代码和最大调用深度",
+    )
+    touches_parser.add_argument(
+        "--from",
+        dest="source",
+        action="append",
+        metavar="FUNCTION",
+        help="只汇总指定的入口函数，可多次指定（默认：所有公开的非测试函数）",
+    )
+    touches_parser.add_argument(
+        "--effect",
+        action="append",
+        metavar="NAME=PATTERN",
+        help='副作用标签及模式（如 "io=std::fs::*"），与配置文件 [effects] 表合并，'
+        "可多次指定",
+    )
+    touches_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "markdown", "csv", "json"],
+        help="输出格式：text、Markdown 表格（用于文档）、csv 或 json (默认: text)",
+    )
+
+    # reach命令
+    reach_parser = subparsers.add_parser(
+        "reach",
+        parents=[filter_parser],
+        help="多入口可达性：对比从几组入口函数（如各二进制目标）出发可达的函数",
+    )
+    reach_parser.add_argument(
+        "--roots",
+        action="append",
+        required=True,
+        metavar="FUNCTIONS",
+        help="一组入口：逗号分隔的函数名或二进制目标名（对应其 main 函数），"
+        "可多次指定，每次为一组",
+    )
+    reach_parser.add_argument(
+        "--compare",
+        action="store_true",
+        help="对比各组：列出只从一组入口可达、部分组和所有组共同可达的函数",
+    )
+    reach_parser.add_argument(
+        "--modules",
+        action="store_true",
+        help="按模块汇总每组可达的函数数量，不逐个列出函数",
+    )
+    reach_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        default=20,
+        help="每一部分最多列出的函数或模块数量 (默认: 20，0 表示不限制)",
+    )
+    reach_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # traits命令
+    traits_parser = subparsers.add_parser(
+        "traits",
+        parents=[filter_parser],
+        help="trait 实现覆盖：每个 trait 方法被哪些类型覆盖、被调用多少次（Rust）",
+    )
+    traits_parser.add_argument("--trait", help="只列出这个 trait（按名称）")
+    traits_parser.add_argument(
+        "--unused",
+        action="store_true",
+        help="只列出从未被覆盖的默认方法和从未被调用的方法",
+    )
+    traits_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # deprecated命令
+    deprecated_parser = subparsers.add_parser(
+        "deprecated",
+        parents=[filter_parser],
+        help="弃用 API 审计：列出调用已弃用函数的每个调用点、调用者和弃用说明",
+    )
+    deprecated_parser.add_argument(
+        "--all",
+        action="store_true",
+        help="也列出本身已弃用的函数中的调用（默认不列出，编译器同样不对其警告）",
+    )
+    deprecated_parser.add_argument(
+        "--check",
+        action="store_true",
+        help="有调用已弃用函数的调用点时退出码为 1（CI 使用）",
+    )
+    deprecated_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # const-eval命令
+    const_parser = subparsers.add_parser(
+        "const-eval",
+        parents=[filter_parser],
+        help="列出可在编译期执行的 const fn 子图，以及 const fn 对非 const 函数的调用（Rust）",
+    )
+    const_parser.add_argument(
+        "--all",
+        action="store_true",
+        help="也列出所有 const fn 及其调用的 const fn（默认只列出违规调用）",
+    )
+    const_parser.add_argument(
+        "--check",
+        action="store_true",
+        help="有 const fn 调用非 const 函数时退出码为 1（CI 使用）",
+    )
+    const_parser.add_argument(
+        "--export",
+        metavar="FILE",
+        help="把编译期子图（const fn 及它们调用的非 const 函数）导出到该文件"
+        "（格式按扩展名：.dot/.json/.mmd/.html 等）",
+    )
+    const_parser.add_argument(
+        "--export-format",
+        choices=[
+            "dot",
+            "mermaid",
+            "json",
+            "html",
+            "graphml",
+            "gexf",
+            "plantuml",
+            "d2",
+            "svg",
+            "png",
+            "sqlite",
+            "csv",
+            "cypher",
+        ],
+        help="与 --export 一起使用：指定导出格式（默认按扩展名推断）",
+    )
+    const_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # effects命令
+    effects_parser = subparsers.add_parser(
+        "effects",
+        parents=[filter_parser],
+        help="按模式标记 IO、内存分配等副作用，并传播到所有可能间接触发它们的函数",
+    )
+    effects_parser.add_argument(
+        "--effect",
+        action="append",
+        metavar="NAME=PATTERN",
+        help='副作用标签及模式（如 "io=std::fs::*"），与配置文件 [effects] 表合并，'
+        "可多次指定",
+    )
+    effects_parser.add_argument(
+        "--only",
+        action="append",
+        metavar="NAME",
+        help="只报告指定的标签（逗号分隔，可多次指定）",
+    )
+    effects_parser.add_argument(
+        "--in",
+        dest="scope",
+        action="append",
+        metavar="PATTERN",
+        help="只报告匹配模式的函数（规则同 --include），可多次指定",
+    )
+    effects_parser.add_argument(
+        "--explain",
+        action="store_true",
+        help="显示每个副作用的来源：到直接触发函数的最短调用路径",
+    )
+    effects_parser.add_argument(
+        "--check",
+        action="store_true",
+        help="报告的函数中有任何副作用时以退出码 1 结束（检查无分配、无 IO 区域）",
+    )
+    effects_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # paths命令
+    paths_parser = subparsers.add_parser(
+        "paths",
+        parents=[filter_parser],
+        help="查询两个函数之间的所有调用路径"
+    )
+    paths_parser.add_argument(
+        "--from", dest="source", required=True, metavar="FUNCTION", help="起点函数名"
+    )
+    paths_parser.add_argument(
+        "--to", dest="target", required=True, metavar="FUNCTION", help="终点函数名"
+    )
+    paths_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        default=20,
+        help="最多显示的路径条数，按长度从短到长 (默认: 20，0 表示不限制)",
+    )
+    paths_parser.add_argument(
+        "--max-depth", type=int, help="路径的最大跳数（默认不限制）"
+    )
+
+    # metrics命令
+    metrics_parser = subparsers.add_parser(
+        "metrics",
+        parents=[filter_parser],
+        help="函数指标：扇入、扇出、传递被调用者数量、入口深度、圈复杂度",
+    )
+    metrics_parser.add_argument(
+        "--sort",
+        "-s",
+        choices=SORT_KEYS,
+        help="排序指标，name 升序，其余降序"
+        "（默认: fan_in，指定 --centrality 时为 centrality）",
+    )
+    metrics_parser.add_argument(
+        "--centrality",
+        nargs="?",
+        const="betweenness",
+        choices=CENTRALITY_METHODS,
+        help="计算中心度：betweenness（介数，经过该函数的最短调用路径比例，默认）"
+        "或 pagerank",
+    )
+    metrics_parser.add_argument(
+        "--limit",
+        "-n",
+        type=int,
+        help="最多输出的函数数量（默认: text 为 20，csv/json 不限制；0 表示不限制）",
+    )
+    metrics_parser.add_argument(
+        "--format",
+        "-f",
+        default="text",
+        choices=["text", "csv", "json"],
+        help="输出格式 (默认: text)",
+    )
+    metrics_parser.add_argument(
+        "--output", "-o", help="csv/json 输出文件路径（默认输出到标准输出）"
+    )
+    metrics_parser.add_argument(
+        "--root",
+        "-r",
+        action="append",
+        metavar="FUNCTION",
+        help="计算深度时额外的入口函数名（可多次指定）",
+    )
+    metrics_parser.add_argument(
+        "--no-default-roots",
+        action="store_true",
+        help="不使用默认入口点（main、测试函数、#[no_mangle]、extern 导出、build.rs 等），"
+        "只用 --root",
+    )
+
+    # export命令
+    export_parser = subparsers.add_parser(
+        "export", parents=[filter_parser], help="导出调用图"
+    )
+    export_parser.add_argument(
+        "--format",
+        "-f",
+        default="dot",
+        choices=[
+            "dot",
+            "mermaid",
+            "json",
+            "html",
+            "graphml",
+            "gexf",
+            "plantuml",
+            "d2",
+            "svg",
+            "png",
+            "sqlite",
+            "csv",
+            "cypher",
+        ],
+        help="导出格式 (默认: dot)",
+    )
+    export_parser.add_argument(
+        "--output", "-o", help="输出文件路径（csv 格式为输出目录）"
+    )
+    export_parser.add_argument(
+        "--focus",
+        action="append",
+        metavar="FUNCTION",
+        help="只导出该函数周围的子图，可用函数名或限定名（如 my_crate::db::save），"
+        "可多次指定",
+    )
+    export_parser.add_argument(
+        "--depth",
+        type=int,
+        default=2,
+        help="与 --focus 一起使用：包含向上和向下多少跳以内的函数 (默认: 2)",
+    )
+    export_parser.add_argument(
+        "--granularity",
+        default="function",
+        choices=["function", "module", "type"],
+        help="节点粒度：function（每个函数一个节点）、module（每个模块一个节点，"
+        "模块间的调用合并为带调用次数的边）或 type（每个类型一个节点，方法间的"
+        "调用和签名中的类型依赖合并为边）(默认: function)",
+    )
+    export_parser.add_argument(
+        "--monomorphize",
+        action="store_true",
+        help="按调用点的类型实参把泛型函数拆分为单态化节点（如 push<User>）",
+    )
+    export_parser.add_argument(
+        "--condense",
+        action="store_true",
+        help="把每个强连通分量（相互递归的函数组）缩成一个节点，输出无环图；"
+        "与 --granularity module 一起使用时缩并模块间的循环依赖",
+    )
+    export_parser.add_argument(
+        "--max-nodes",
+        type=int,
+        metavar="N",
+        help="节点超过 N 个时只保留中心度最高的函数，被裁掉的调用者/被调用者合并为"
+        "“… 还有 k 个”占位节点（共不超过 N 个节点）",
+    )
+    export_parser.add_argument(
+        "--prune-by",
+        default=DEFAULT_PRUNE_CENTRALITY,
+        choices=CENTRALITY_METHODS,
+        help=f"--max-nodes 排名使用的中心度 (默认: {DEFAULT_PRUNE_CENTRALITY}；"
+        "betweenness 在大图上较慢)",
+    )
+    export_parser.add_argument(
+        "--stable-ids",
+        action="store_true",
+        help="用稳定 ID（限定名和签名的哈希，与函数所在的行号无关）作为节点 ID，"
+        "便于外部工具和多次导出的结果对比",
+    )
+    export_parser.add_argument(
+        "--profile",
+        metavar="FILE",
+        help="DOT/JSON: 叠加运行时剖析数据（perf script 输出、pprof 或折叠栈），"
+        "按样本数为函数着色、加粗热路径上的边",
+    )
+    export_parser.add_argument(
+        "--profile-format",
+        default="auto",
+        choices=PROFILE_FORMATS,
+        help="剖析数据的格式 (默认: auto，按内容识别)",
+    )
+    export_parser.add_argument(
+        "--coverage",
+        metavar="FILE",
+        help="DOT/JSON: 叠加覆盖率报告（lcov 或 Cobertura XML，如 cargo llvm-cov "
+        "--lcov 的输出），被执行过的函数为绿色、没有被执行的为红色",
+    )
+    export_parser.add_argument(
+        "--coverage-format",
+        default="auto",
+        choices=COVERAGE_FORMATS,
+        help="覆盖率报告的格式 (默认: auto，按内容识别)",
+    )
+    export_parser.add_argument(
+        "--color-by-module", action="store_true", help="DOT/SVG/PNG: 按模块为节点着色"
+    )
+    export_parser.add_argument(
+        "--shape-by-kind",
+        action="store_true",
+        help="DOT/D2: 按类别设置节点形状（函数=方框，方法=椭圆，闭包=菱形）",
+    )
+    export_parser.add_argument(
+        "--cluster-depth",
+        type=int,
+        metavar="N",
+        help="DOT: 按 crate → 模块 → 类型（impl、类）嵌套子图的层数，0 表示不分组，"
+        "负数表示完整嵌套（默认：超过 200 个节点时完整嵌套，否则不分组）",
+    )
+    export_parser.add_argument(
+        "--edge-labels",
+        action="store_true",
+        help="DOT/D2/SVG/PNG/Mermaid/PlantUML: 在边上标注调用次数",
+    )
+    export_parser.add_argument(
+        "--rankdir",
+        default="LR",
+        choices=["LR", "TB", "RL", "BT"],
+        help="DOT/D2/SVG/PNG/PlantUML: 布局方向 (默认: LR)",
+    )
+    export_parser.add_argument(
+        "--plantuml-style",
+        default="component",
+        choices=PLANTUML_STYLES,
+        help="PlantUML: 图的类型，component 为函数组件图，class 为每个模块一个类 "
+        "(默认: component)",
+    )
+    export_parser.add_argument(
+        "--neo4j-headers",
+        action="store_true",
+        help="CSV: 表头使用 Neo4j 批量导入格式（id:ID、:START_ID、:END_ID、:TYPE）",
+    )
+    export_parser.add_argument(
+        "--neo4j-uri",
+        metavar="URI",
+        help="Cypher: 不输出语句，通过 bolt 直接写入 Neo4j（如 bolt://localhost:7687，"
+        f"需要安装 neo4j，密码取自环境变量 {PASSWORD_ENV}）",
+    )
+    export_parser.add_argument(
+        "--neo4j-user", default="neo4j", help="与 --neo4j-uri 一起使用：用户名 (默认: neo4j)"
+    )
+    export_parser.add_argument(
+        "--neo4j-database", help="与 --neo4j-uri 一起使用：数据库名（默认为服务器的默认数据库）"
+    )
+    export_parser.add_argument(
+        "--neo4j-clear",
+        action="store_true",
+        help="与 --neo4j-uri 一起使用：写入前删除已有的调用图节点（Symbol/Module/Crate）",
+    )
+    export_parser.add_argument(
+        "--group-by-module",
+        action="store_true",
+        help="Mermaid: 按模块分组为 subgraph",
+    )
+    export_parser.add_argument(
+        "--direction",
+        default="TD",
+        choices=["TD", "LR", "BT", "RL"],
+        help="Mermaid: 布局方向 (默认: TD)",
+    )
+    export_parser.add_argument("--title", help="HTML: 页面标题 (默认: 调用图)")
+
+    # diff命令
+    diff_parser = subparsers.add_parser(
+        "diff",
+        parents=[filter_parser],
+        help="比较两个版本的调用图（新增/删除的函数、调用和模块依赖）",
+    )
+    diff_parser.add_argument("--base", metavar="REV", help="基准 git 修订版本（如 main）")
+    diff_parser.add_argument(
+        "--head", metavar="REV", help="对比的 git 修订版本（默认：当前工作区）"
+    )
+    diff_parser.add_argument(
+        "--repo", default=".", help="git 仓库或其中的项目子目录（默认：当前目录）"
+    )
+    diff_parser.add_argument(
+        "--base-dir", metavar="DIR", help="基准目录（代替 --base，比较两个目录）"
+    )
+    diff_parser.add_argument(
+        "--head-dir", metavar="DIR", help="对比目录（代替 --head）"
+    )
+    diff_parser.add_argument(
+        "--exclude-dirs", help="分析时要排除的目录，用逗号分隔"
+    )
+    diff_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+    diff_parser.add_argument(
+        "--fail-on-new-deps",
+        action="store_true",
+        help="出现新增的模块依赖时以退出码 1 结束（用于 CI）",
+    )
+
+    # history命令
+    history_parser = subparsers.add_parser(
+        "history",
+        parents=[filter_parser],
+        help="分析 git 历史中的快照，输出指标的趋势（函数数、调用边数、最大扇入、"
+        "递归环数、模块依赖数）",
+    )
+    history_parser.add_argument(
+        "--since",
+        required=True,
+        metavar="REV",
+        help="起始 git 修订版本（如 v1.0），作为第一个快照",
+    )
+    history_parser.add_argument(
+        "--until", default="HEAD", metavar="REV", help="结束修订版本 (默认: HEAD)"
+    )
+    history_parser.add_argument(
+        "--step",
+        default="monthly",
+        choices=HISTORY_STEPS,
+        help="快照间隔：每个时间段取最后一个提交 (默认: monthly)",
+    )
+    history_parser.add_argument(
+        "--repo", default=".", help="git 仓库或其中的项目子目录（默认：当前目录）"
+    )
+    history_parser.add_argument(
+        "--exclude-dirs", help="分析时要排除的目录，用逗号分隔"
+    )
+    history_parser.add_argument(
+        "--format",
+        default="csv",
+        choices=["csv", "json"],
+        help="输出格式 (默认: csv)",
+    )
+    history_parser.add_argument(
+        "--output", "-o", help="输出文件（默认：标准输出）"
+    )
+
+    # check命令
+    check_parser = subparsers.add_parser(
+        "check",
+        parents=[filter_parser],
+        help="检查架构规则：存在禁止的调用时以退出码 1 结束",
+    )
+    check_parser.add_argument(
+        "--rule",
+        action="append",
+        metavar="RULE",
+        help='禁止的调用（如 "ui::* -> db::*"），与配置文件 [rules] 表中的 deny 合并，'
+        "可多次指定",
+    )
+    check_parser.add_argument(
+        "--allow",
+        action="append",
+        metavar="RULE",
+        help="规则的例外（格式同 --rule），可多次指定",
+    )
+    check_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", *FINDING_FORMATS],
+        help="输出格式 (默认: text)，sarif 用于 GitHub code scanning 等 CI 平台，"
+        "github 输出 GitHub Actions 注释",
+    )
+
+    # baseline命令
+    baseline_parser = subparsers.add_parser(
+        "baseline",
+        help="模块依赖基线：记录已批准的模块依赖，出现新的依赖时 CI 失败",
+    )
+    baseline_subparsers = baseline_parser.add_subparsers(
+        dest="baseline_command", required=True, help="基线操作"
+    )
+    for name, description in (
+        ("record", "把当前的模块依赖记录为已批准的基线（覆盖已有的基线文件）"),
+        ("check", "检查是否有不在基线中的新模块依赖，有时退出码为 1"),
+    ):
+        action_parser = baseline_subparsers.add_parser(
+            name, parents=[filter_parser], help=description
+        )
+        action_parser.add_argument(
+            "--file",
+            default=DEFAULT_BASELINE_FILE,
+            help=f"基线文件 (默认: {DEFAULT_BASELINE_FILE})",
+        )
+        if name == "check":
+            action_parser.add_argument(
+                "--format",
+                default="text",
+                choices=["text", "json"],
+                help="输出格式 (默认: text)",
+            )
+
+    # owners命令
+    owners_parser = subparsers.add_parser(
+        "owners",
+        parents=[filter_parser],
+        help="按 CODEOWNERS 把函数归属到团队，列出团队之间的依赖和跨团队的调用",
+    )
+    owners_parser.add_argument(
+        "--codeowners",
+        metavar="FILE",
+        help="CODEOWNERS 文件（默认：从项目根目录向上查找 "
+        f"{'、'.join(CODEOWNERS_LOCATIONS)}）",
+    )
+    owners_parser.add_argument(
+        "--team",
+        metavar="TEAM",
+        help="只列出涉及这个团队的依赖（如 @org/payments，多个所有者以空格连接）",
+    )
+    owners_parser.add_argument(
+        "--edges", action="store_true", help="列出每条跨团队的调用边和调用点"
+    )
+    owners_parser.add_argument(
+        "--format",
+        default="text",
+        choices=["text", "json"],
+        help="输出格式 (默认: text)",
+    )
+
+    # lsp命令
+    subparsers.add_parser(
+        "lsp",
+        parents=[filter_parser],
+        help="运行语言服务器（标准输入/输出），为编辑器提供调用层次"
+        "（incomingCalls/outgoingCalls）",
+    )
+
+    # tui命令
+    tui_parser = subparsers.add_parser(
+        "tui",
+        parents=[filter_parser],
+        help="终端浏览器：可搜索的函数列表和调用者/被调用者窗格",
+    )
+    tui_parser.add_argument(
+        "function", nargs="?", help="初始显示的函数（函数名或限定名）"
+    )
+    tui_parser.add_argument("--search", "-s", help="函数列表的初始搜索词")
+
+    args = parser.parse_args(argv)
+
+    if not args.command:
         parser.print_help()
         sys.exit(1)
 
+    args = _apply_project_config(parser, subparsers, args, argv)
+    configure_logging(args.log_verbose, args.log_format)
+
+    try:
+        languages = load_plugins(args.plugin)
+    except Exception as e:
+        print(f"错误: 加载语言插件失败: {e}", file=sys.stderr)
+        sys.exit(1)
+    # 写到标准错误，避免混入导出到标准输出的内容
+    if languages:
+        print(f"已加载语言插件: {', '.join(languages)}", file=sys.stderr)
+
     # 执行对应的命令
     if args.command == "analyze":
         cmd_analyze(args)
+    elif args.command == "analyze-binary":
+        cmd_analyze_binary(args)
     elif args.command == "query":
         cmd_query(args)
     elif args.command == "search":
         cmd_search(args)
+    elif args.command == "find":
+        cmd_find(args)
     elif args.command == "stats":
         cmd_stats(args)
+    elif args.command == "save":
+        cmd_save(args)
+    elif args.command == "load":
+        cmd_load(args)
+    elif args.command == "tree":
+        cmd_tree(args)
+    elif args.command == "api":
+        cmd_api(args)
+    elif args.command == "cycles":
+        cmd_cycles(args)
+    elif args.command == "roots":
+        cmd_roots(args)
+    elif args.command == "deadcode":
+        cmd_deadcode(args)
+    elif args.command == "features":
+        cmd_features(args)
+    elif args.command == "coverage":
+        cmd_coverage(args)
+    elif args.command == "profile":
+        cmd_profile(args)
+    elif args.command == "test-reach":
+        cmd_test_reach(args)
+    elif args.command == "impact":
+        cmd_impact(args)
+    elif args.command == "unsafe-reach":
+        cmd_unsafe_reach(args)
+    elif args.command == "panic-reach":
+        cmd_panic_reach(args)
+    elif args.command == "no-std":
+        cmd_no_std(args)
+    elif args.command == "errors":
+        cmd_errors(args)
+    elif args.command == "traits":
+        cmd_traits(args)
+    elif args.command == "interfaces":
+        cmd_interfaces(args)
+    elif args.command == "layers":
+        cmd_layers(args)
+    elif args.command == "duplicates":
+        cmd_duplicates(args)
+    elif args.command == "touches":
+        cmd_touches(args)
+    elif args.command == "reach":
+        cmd_reach(args)
+    elif args.command == "deprecated":
+        cmd_deprecated(args)
+    elif args.command == "const-eval":
+        cmd_const_eval(args)
+    elif args.command == "effects":
+        cmd_effects(args)
+    elif args.command == "paths":
+        cmd_paths(args)
+    elif args.command == "metrics":
+        cmd_metrics(args)
     elif args.command == "export":
         cmd_export(args)
+    elif args.command == "diff":
+        cmd_diff(args)
+    elif args.command == "history":
+        cmd_history(args)
+    elif args.command == "check":
+        cmd_check(args)
+    elif args.command == "baseline":
+        cmd_baseline(args)
+    elif args.command == "owners":
+        cmd_owners(args)
+    elif args.command == "lsp":
+        cmd_lsp(args)
+    elif args.command == "tui":
+        cmd_tui(args)
 
 
 if __name__ == "__main__":
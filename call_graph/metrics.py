@@ -0,0 +1,262 @@
+"""
+函数指标
+每个函数的扇入、扇出、传递被调用者数量、距入口点的深度和近似圈复杂度，
+用于找出“上帝函数”和热点函数；可选的中心度（介数或 PageRank）用于找出
+位于大量调用路径上的架构瓶颈
+"""
+
+import csv
+import io
+import json
+from collections import deque
+from typing import Any, Dict, Iterable, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import strongly_connected_components
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import strongly_connected_components
+    from graph import CallGraph
+
+# 指标列（CSV 表头和 JSON 字段，按输出顺序）
+METRIC_FIELDS = [
+    "id",
+    "name",
+    "qualified_name",
+    "file",
+    "line",
+    "fan_in",
+    "fan_out",
+    "transitive_callees",
+    "depth",
+    "complexity",
+]
+
+# 可用于排序的指标
+SORT_KEYS = [
+    "fan_in",
+    "fan_out",
+    "transitive_callees",
+    "depth",
+    "complexity",
+    "centrality",
+    "name",
+]
+
+# 中心度算法
+CENTRALITY_METHODS = ["betweenness", "pagerank"]
+
+# PageRank 的阻尼系数、最大迭代次数和收敛阈值
+PAGERANK_DAMPING = 0.85
+PAGERANK_ITERATIONS = 100
+PAGERANK_TOLERANCE = 1e-10
+
+
+def transitive_callee_counts(graph: CallGraph) -> Dict[str, int]:
+    """
+    每个函数直接或间接调用的不同函数数量（不含自身）
+
+    同一强连通分量中的函数可达集合相同，按逆拓扑序合并各分量的可达集合
+    （以整数位集表示），避免对每个函数单独搜索
+    """
+    bit = {node_id: 1 << index for index, node_id in enumerate(graph.nodes)}
+    component_of: Dict[str, int] = {}
+    reach: List[int] = []
+    # Tarjan 算法按逆拓扑序给出分量：被调用者所在的分量先于调用者
+    for index, component in enumerate(strongly_connected_components(graph)):
+        bits = 0
+        for node_id in component:
+            component_of[node_id] = index
+        for node_id in component:
+            bits |= bit[node_id]
+            for succ in graph.successors(node_id):
+                if component_of[succ] != index:
+                    bits |= reach[component_of[succ]]
+        reach.append(bits)
+
+    counts = {}
+    for node_id in graph.nodes:
+        bits = reach[component_of[node_id]]
+        # 可达集合包含分量自身的成员；只有处于递归环中的函数才真正调用自身
+        counts[node_id] = bits.bit_count() - 1
+    return counts
+
+
+def entry_depths(graph: CallGraph, roots: Iterable[str]) -> Dict[str, int]:
+    """从入口点出发到每个可达函数的最少调用层数（入口点为 0）"""
+    depth = {root: 0 for root in roots if root in graph.nodes}
+    queue = deque(depth)
+    while queue:
+        node = queue.popleft()
+        for succ in graph.successors(node):
+            if succ not in depth:
+                depth[succ] = depth[node] + 1
+                queue.append(succ)
+    return depth
+
+
+def betweenness_centrality(graph: CallGraph) -> Dict[str, float]:
+    """
+    介数中心度（Brandes 算法）：经过该函数的最短调用路径所占比例之和，
+    按有向图的 (n-1)(n-2) 对归一化到 0~1
+    """
+    centrality = {node_id: 0.0 for node_id in graph.nodes}
+    for source in graph.nodes:
+        # 从 source 出发的 BFS：最短路径条数和前驱
+        order = []
+        predecessors: Dict[str, List[str]] = {source: []}
+        paths = {source: 1}
+        distance = {source: 0}
+        queue = deque([source])
+        while queue:
+            node = queue.popleft()
+            order.append(node)
+            for succ in graph.successors(node):
+                if succ not in distance:
+                    distance[succ] = distance[node] + 1
+                    paths[succ] = 0
+                    predecessors[succ] = []
+                    queue.append(succ)
+                if distance[succ] == distance[node] + 1:
+                    paths[succ] += paths[node]
+                    predecessors[succ].append(node)
+
+        # 按距离从远到近累计依赖值
+        dependency = dict.fromkeys(order, 0.0)
+        for node in reversed(order):
+            for pred in predecessors[node]:
+                dependency[pred] += paths[pred] / paths[node] * (1 + dependency[node])
+            if node != source:
+                centrality[node] += dependency[node]
+
+    n = len(graph.nodes)
+    if n > 2:
+        scale = 1 / ((n - 1) * (n - 2))
+        centrality = {node_id: value * scale for node_id, value in centrality.items()}
+    return centrality
+
+
+def pagerank(graph: CallGraph) -> Dict[str, float]:
+    """
+    PageRank（幂迭代）：被重要函数调用的函数更重要，所有函数之和为 1
+
+    不调用任何函数的节点把权重平均分给所有函数
+    """
+    nodes = list(graph.nodes)
+    n = len(nodes)
+    if not n:
+        return {}
+    rank = dict.fromkeys(nodes, 1 / n)
+    out_degree = {node_id: len(graph.successors(node_id)) for node_id in nodes}
+    for _ in range(PAGERANK_ITERATIONS):
+        dangling = sum(rank[node_id] for node_id in nodes if not out_degree[node_id])
+        base = (1 - PAGERANK_DAMPING) / n + PAGERANK_DAMPING * dangling / n
+        updated = dict.fromkeys(nodes, base)
+        for node_id in nodes:
+            if out_degree[node_id]:
+                share = PAGERANK_DAMPING * rank[node_id] / out_degree[node_id]
+                for succ in graph.successors(node_id):
+                    updated[succ] += share
+        delta = sum(abs(updated[node_id] - rank[node_id]) for node_id in nodes)
+        rank = updated
+        if delta < PAGERANK_TOLERANCE:
+            break
+    return rank
+
+
+def compute_metrics(
+    graph: CallGraph, roots: Iterable[str], centrality: Optional[str] = None
+) -> List[Dict[str, Any]]:
+    """
+    计算所有函数的指标
+
+    fan_in / fan_out 为不同调用者 / 被调用者的数量（不含自调用）；
+    depth 为从任一入口点出发的最少调用层数，不可达时为 None；
+    complexity 为解析时记录的近似圈复杂度，没有记录时为 None
+
+    Args:
+        centrality: 中心度算法（betweenness / pagerank），指定时每项
+            额外包含 centrality 字段
+
+    Returns:
+        指标列表（字段见 METRIC_FIELDS），按文件和行号排序
+    """
+    transitive = transitive_callee_counts(graph)
+    depth = entry_depths(graph, roots)
+    scores = None
+    if centrality == "betweenness":
+        scores = betweenness_centrality(graph)
+    elif centrality == "pagerank":
+        scores = pagerank(graph)
+    elif centrality is not None:
+        raise ValueError(f"未知的中心度算法: {centrality}")
+    metrics = []
+    for node_id, node in graph.nodes.items():
+        metrics.append(
+            {
+                "id": node_id,
+                "name": node["name"],
+                "qualified_name": graph.qualified_name(node_id),
+                "file": node.get("file"),
+                "line": node.get("start_line"),
+                "fan_in": sum(1 for n in graph.predecessors(node_id) if n != node_id),
+                "fan_out": sum(1 for n in graph.successors(node_id) if n != node_id),
+                "transitive_callees": transitive[node_id],
+                "depth": depth.get(node_id),
+                "complexity": (node.get("extras") or {}).get("complexity"),
+            }
+        )
+        if scores is not None:
+            metrics[-1]["centrality"] = scores[node_id]
+    metrics.sort(key=lambda m: (m["file"] or "", m["line"] or 0))
+    return metrics
+
+
+def sort_metrics(
+    metrics: List[Dict[str, Any]], key: str, limit: Optional[int] = None
+) -> List[Dict[str, Any]]:
+    """
+    按指标排序：name 升序，其余降序（没有值的排在最后）；
+    limit 为最多保留的条数，None 表示不限制
+    """
+    if key == "name":
+        ordered = sorted(metrics, key=lambda m: (m["qualified_name"], m["file"] or ""))
+    else:
+        ordered = sorted(
+            metrics,
+            key=lambda m: (
+                m.get(key) is None,
+                -(m.get(key) or 0),
+                m["qualified_name"],
+            ),
+        )
+    return ordered if limit is None else ordered[:limit]
+
+
+def metric_fields(metrics: List[Dict[str, Any]]) -> List[str]:
+    """输出的字段：METRIC_FIELDS，计算了中心度时再加上 centrality"""
+    if any("centrality" in metric for metric in metrics):
+        return METRIC_FIELDS + ["centrality"]
+    return METRIC_FIELDS
+
+
+def metrics_to_csv(metrics: List[Dict[str, Any]]) -> str:
+    """CSV 格式（带表头，没有值的单元格为空）"""
+    fields = metric_fields(metrics)
+    output = io.StringIO()
+    writer = csv.DictWriter(output, fieldnames=fields, lineterminator="\n")
+    writer.writeheader()
+    for metric in metrics:
+        writer.writerow({name: metric[name] for name in fields})
+    return output.getvalue()
+
+
+def metrics_to_json(metrics: List[Dict[str, Any]]) -> str:
+    """JSON 格式：{"functions": [...]}"""
+    fields = metric_fields(metrics)
+    return json.dumps(
+        {"functions": [{name: m[name] for name in fields} for m in metrics]},
+        ensure_ascii=False,
+        indent=2,
+    )
@@ -0,0 +1,667 @@
+"""
+Rust MIR 后端
+通过 cargo rustc -- --emit=mir 获取编译器生成的 MIR，从中提取调用关系，
+替换语法前端对这些函数推断出的调用
+
+MIR 中的调用已经由编译器完成名称解析和方法查找：方法调用写成
+<Type as Trait>::method 或 Type::method，泛型调用带有实际的类型实参，
+闭包调用指向闭包定义的位置，宏展开后的调用也都可见。只有 trait 对象上的
+调用（<dyn Trait as Trait>::method）和泛型约束上的调用在 MIR 中仍然没有
+确定的目标，按语法前端的方式连接到所有实现（dynamic 边）。
+
+MIR 函数按其中第一个源码位置对应到语法前端解析出的函数（包括闭包）；
+没有编译到的代码（#[cfg(test)]、未启用的 feature）保留语法前端的调用关系
+"""
+
+import os
+import re
+import subprocess
+import tempfile
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .binary import _split_path, _strip_generics
+    from .cargo import function_path
+    from .macros import _file_module_path, crate_targets, find_crates
+    from .parsers import RustParser, get_parser
+except ImportError:
+    from binary import _split_path, _strip_generics
+    from cargo import function_path
+    from macros import _file_module_path, crate_targets, find_crates
+    from parsers import RustParser, get_parser
+
+
+# 编译单个 crate 目标的超时时间（秒），需要编译依赖
+MIR_TIMEOUT = 600
+
+# 源码位置：src/main.rs:13:20: 13:30
+SPAN = re.compile(
+    r"(?P<file>[^\s:{@]+):(?P<line>\d+):(?P<column>\d+): "
+    r"(?P<end_line>\d+):(?P<end_column>\d+)"
+)
+
+# 语句末尾的位置注释：// scope 1 at src/main.rs:13:20: 13:30
+SCOPE_COMMENT = re.compile(r"\s+// scope \d+ at (?P<span>\S+ \S+)$")
+
+# 闭包类型：{closure@src/main.rs:16:34: 16:37}
+CLOSURE_TYPE = re.compile(r"\{closure@(?P<file>[^\s:]+):(?P<line>\d+):(?P<column>\d+)")
+
+# 调用终结符中的被调用者之后的部分：-> [return: bb1, ...] 或 -> unwind ...（发散函数）
+CALL_TARGETS = re.compile(r"^\s*->\s*(?:\[return:|unwind\b)")
+
+# 形如调用的其他终结符
+NON_CALL_TERMINATORS = {"drop", "switchInt", "assert", "falseEdge", "falseUnwind"}
+
+# 通过局部变量（函数指针）的调用：move _3(...)
+LOCAL_OPERAND = re.compile(r"^(?:move |copy )?_\d+$")
+
+
+def run_mir(crate_dir: Path, target_args: List[str]) -> Optional[str]:
+    """
+    编译 crate 目标，返回 MIR 文本；失败时返回 None
+
+    -Zmir-include-spans 在 MIR 中加入源码位置，-Ztrim-diagnostic-paths=no
+    输出完整的路径（-Z 选项通过 RUSTC_BOOTSTRAP=1 在稳定版上启用）
+    """
+    with tempfile.TemporaryDirectory(prefix="call_graph_mir_") as out_dir:
+        output = Path(out_dir) / "crate.mir"
+        command = [
+            "cargo",
+            "rustc",
+            *target_args,
+            "--",
+            f"--emit=mir={output}",
+            "-Zmir-include-spans=yes",
+            "-Ztrim-diagnostic-paths=no",
+        ]
+        try:
+            completed = subprocess.run(
+                command,
+                cwd=crate_dir,
+                env={**os.environ, "RUSTC_BOOTSTRAP": "1"},
+                capture_output=True,
+                text=True,
+                timeout=MIR_TIMEOUT,
+            )
+        except FileNotFoundError:
+            print("警告: 未找到 cargo，跳过 MIR 分析")
+            return None
+        except subprocess.TimeoutExpired:
+            error = f"{' '.join(command)} 超时"
+        else:
+            if completed.returncode == 0 and output.exists():
+                return output.read_text(encoding="utf-8", errors="replace")
+            lines = completed.stderr.strip().splitlines()
+            error = lines[-1] if lines else f"退出码 {completed.returncode}"
+
+    print(f"警告: 生成 MIR 失败 {crate_dir} {' '.join(target_args)}: {error}")
+    return None
+
+
+def _parse_span(text: str) -> Optional[Dict[str, Any]]:
+    match = SPAN.search(text)
+    if not match:
+        return None
+    return {
+        "file": match.group("file"),
+        "line": int(match.group("line")),
+        "column": int(match.group("column")),
+        "end_line": int(match.group("end_line")),
+        "end_column": int(match.group("end_column")),
+    }
+
+
+def _split_call(statement: str) -> Optional[Tuple[str, str]]:
+    """
+    拆分调用终结符 [_N = ]callee(args) -> ...
+
+    Returns:
+        (被调用者, 参数)，不是调用时返回 None
+    """
+    statement = statement.strip()
+    assign = re.match(r"_\d+ = ", statement)
+    if assign:
+        statement = statement[assign.end() :]
+    depth = 0
+    for index, char in enumerate(statement):
+        if char in "<{[":
+            depth += 1
+        elif char in ">}]" and not statement.startswith("->", index - 1):
+            depth -= 1
+        elif char == "(" and depth == 0:
+            callee = statement[:index]
+            close = _matching_paren(statement, index)
+            if close is None or not CALL_TARGETS.match(statement[close + 1 :]):
+                return None
+            return callee, statement[index + 1 : close]
+    return None
+
+
+def _matching_paren(text: str, start: int) -> Optional[int]:
+    depth = 0
+    quoted = False
+    for index in range(start, len(text)):
+        char = text[index]
+        if char == '"' and text[index - 1] != "\\":
+            quoted = not quoted
+        elif quoted:
+            continue
+        elif char == "(":
+            depth += 1
+        elif char == ")":
+            depth -= 1
+            if depth == 0:
+                return index
+    return None
+
+
+def parse_mir(text: str) -> List[Dict[str, Any]]:
+    """
+    解析 MIR 文本中的函数和调用
+
+    Returns:
+        [{"path": MIR 中的函数路径, "span": 函数体中的第一个源码位置,
+          "calls": [(被调用者, 参数, 调用位置)], "closures": [(文件, 行, 列)],
+          "spawned": 作为类型实参传给 thread::spawn 等调用的闭包 {(文件, 行, 列)}}]
+    """
+    functions = []
+    current: Optional[Dict[str, Any]] = None
+    for line in text.splitlines():
+        if line.startswith("fn "):
+            current = {
+                "path": line[3:].split("(", 1)[0],
+                "span": None,
+                "calls": [],
+                "closures": [],
+                "spawned": set(),
+            }
+            functions.append(current)
+            continue
+        if current is None:
+            continue
+        if line.startswith("}"):
+            current = None
+            continue
+        for match in CLOSURE_TYPE.finditer(line):
+            closure = (match.group("file"), int(match.group("line")))
+            current["closures"].append(closure + (int(match.group("column")),))
+        statement, span = line, None
+        comment = SCOPE_COMMENT.search(line)
+        if comment:
+            statement = line[: comment.start()]
+            span = _parse_span(comment.group("span"))
+        elif "//" in line:
+            span = _parse_span(line.rsplit("//", 1)[1])
+        if current["span"] is None and span and not os.path.isabs(span["file"]):
+            current["span"] = span
+        if not comment:
+            continue
+        call = _split_call(statement.rstrip(";"))
+        if call is None:
+            continue
+        callee, args = call
+        if callee in NON_CALL_TERMINATORS or LOCAL_OPERAND.match(callee):
+            continue
+        current["calls"].append((callee, args, span))
+        # 交给新线程/异步任务运行的闭包：std::thread::spawn::<{closure@..}, ()>
+        if "{closure@" in callee and (
+            callee_path(callee)["name"] in RustParser.SPAWN_CALLS
+        ):
+            for match in CLOSURE_TYPE.finditer(callee):
+                closure = (match.group("file"), int(match.group("line")))
+                current["spawned"].add(closure + (int(match.group("column")),))
+    return functions
+
+
+def callee_path(callee: str) -> Dict[str, Any]:
+    """
+    MIR 中的被调用者拆分为路径
+
+    Returns:
+        segments（去掉泛型参数的路径段）、self_type（<Type as Trait> 中的类型）、
+        trait（trait 路径的最后一段）、name、type_args（泛型实参）
+    """
+    parts = _split_path(callee)
+    self_type = trait = None
+    if parts and parts[0].startswith("<") and parts[0].endswith(">"):
+        inner = parts[0][1:-1]
+        self_type, _, trait_path = inner.partition(" as ")
+        trait = _strip_generics(_split_path(trait_path)[-1])[0] if trait_path else None
+        parts = parts[1:]
+    segments = []
+    type_args = []
+    for part in parts:
+        if part.startswith("<") and part.endswith(">"):
+            type_args.extend(
+                arg for arg in _split_args(part[1:-1]) if not arg.startswith("'")
+            )
+            continue
+        base, args = _strip_generics(part)
+        if args:
+            type_args.extend(
+                arg for arg in _split_args(args) if not arg.startswith("'")
+            )
+        segments.append(base)
+    return {
+        "segments": segments,
+        "self_type": self_type,
+        "trait": trait,
+        "name": segments[-1] if segments else callee,
+        "type_args": type_args,
+    }
+
+
+def _split_args(text: str) -> List[str]:
+    """按顶层的逗号分割泛型实参"""
+    args = []
+    depth = 0
+    current = ""
+    for char in text:
+        if char in "<([{":
+            depth += 1
+        elif char in ">)]}":
+            depth -= 1
+        if char == "," and depth == 0:
+            args.append(current.strip())
+            current = ""
+            continue
+        current += char
+    if current.strip():
+        args.append(current.strip())
+    return args
+
+
+class _SourceFile:
+    """源文件中行列位置到字节位置的换算，以及位于该文件的函数"""
+
+    def __init__(self, path: str):
+        self.path = path
+        self.functions: List[Dict[str, Any]] = []
+        try:
+            self.lines = Path(path).read_bytes().splitlines(keepends=True)
+        except OSError:
+            self.lines = []
+        self.offsets = [0]
+        for line in self.lines:
+            self.offsets.append(self.offsets[-1] + len(line))
+
+    def position(self, line: int, column: int) -> Optional[int]:
+        """1 起始的行、列（字符）对应的字节位置"""
+        if not 1 <= line <= len(self.lines):
+            return None
+        text = self.lines[line - 1].decode("utf-8", errors="replace")
+        return self.offsets[line - 1] + len(text[: column - 1].encode("utf-8"))
+
+    def innermost(self, line: int, column: int) -> Optional[Dict[str, Any]]:
+        """包含该位置的最内层函数（包括闭包）"""
+        position = self.position(line, column)
+        if position is None:
+            return None
+        best = None
+        for func in self.functions:
+            if func["start_byte"] <= position < func["end_byte"]:
+                if best is None or (
+                    func["end_byte"] - func["start_byte"]
+                    < best["end_byte"] - best["start_byte"]
+                ):
+                    best = func
+        return best
+
+    def snippet(self, span: Dict[str, Any]) -> Optional[str]:
+        """单行位置范围的源码片段"""
+        if span["line"] != span["end_line"] or span["line"] > len(self.lines):
+            return None
+        text = self.lines[span["line"] - 1].decode("utf-8", errors="replace")
+        return text[span["column"] - 1 : span["end_column"] - 1] or None
+
+
+class MirCallGraph:
+    """
+    把一个 crate 目标的 MIR 函数对应到源码函数，并解析其中的调用
+
+    被调用者按路径解析：相对 crate 根的路径（MIR 中本 crate 的路径不带
+    crate 名）与函数的模块路径匹配，其他 crate 的路径按 cargo 标注的
+    绝对路径匹配；<Type as Trait>::method 按所属类型、trait 和方法名匹配；
+    找不到的 extern 块中声明的函数交给语法前端连接到 C/C++ 定义（ffi 边）
+    """
+
+    def __init__(
+        self,
+        crate_dir: Path,
+        target_root: Path,
+        sources: Dict[str, _SourceFile],
+        paths: Dict[str, List[Dict[str, Any]]],
+        rust_functions: List[Dict[str, Any]],
+        parser,
+        foreign: Optional[Set[str]] = None,
+    ):
+        self.crate_dir = crate_dir
+        self.sources = sources
+        self.parser = parser
+        self.rust_functions = rust_functions
+        self.foreign = foreign or set()
+        self.paths = dict(paths)
+        for resolved, source in sources.items():
+            module_path = _file_module_path(Path(resolved), target_root)
+            if module_path is None:
+                continue
+            for func in source.functions:
+                extras = func.get("extras") or {}
+                if extras.get("closure"):
+                    continue
+                parts = [*module_path, *(extras.get("inline_modules") or [])]
+                if extras.get("owner"):
+                    parts.append(extras["owner"])
+                parts.append(func["name"])
+                key = "::".join(parts)
+                self.paths[key] = [func, *self.paths.get(key, [])]
+        self._files: Dict[str, Optional[_SourceFile]] = {}
+
+    def source_file(self, file_path: str) -> Optional[_SourceFile]:
+        """
+        MIR 中的相对路径对应的源文件：相对 cargo 执行 rustc 的目录，
+        即 crate 目录或上级的工作区目录
+        """
+        if file_path not in self._files:
+            found = None
+            for base in [self.crate_dir, *self.crate_dir.parents]:
+                candidate = str((base / file_path).resolve())
+                if candidate in self.sources:
+                    found = self.sources[candidate]
+                    break
+            self._files[file_path] = found
+        return self._files[file_path]
+
+    def function_at(self, file_path: str, line: int, column: int) -> Optional[Dict]:
+        source = self.source_file(file_path)
+        return source.innermost(line, column) if source else None
+
+    def build(
+        self, mir_functions: List[Dict[str, Any]]
+    ) -> Tuple[List[Dict[str, Any]], List[Dict[str, Any]], Set[str], int]:
+        """
+        Returns:
+            (直接解析的调用关系, 交给语法前端解析的调用引用（动态分发、闭包）,
+             对应到的源码函数 ID, 没有对应到源码函数的 MIR 函数数量)
+        """
+        relations: List[Dict[str, Any]] = []
+        refs: List[Dict[str, Any]] = []
+        callers: Set[str] = set()
+        unmapped = 0
+        for mir_func in mir_functions:
+            span = mir_func["span"]
+            caller = (
+                self.function_at(span["file"], span["line"], span["column"])
+                if span
+                else None
+            )
+            if caller is None:
+                unmapped += 1
+                continue
+            callers.add(caller["id"])
+            for callee, args, call_span in mir_func["calls"]:
+                site = self._call_site(caller, call_span)
+                relation, ref = self._resolve(caller, callee, site)
+                if relation:
+                    relations.append(relation)
+                if ref:
+                    refs.append(ref)
+            refs.extend(
+                self._closure_refs(caller, mir_func["closures"], mir_func["spawned"])
+            )
+        return relations, refs, callers, unmapped
+
+    def _call_site(
+        self, caller: Dict[str, Any], span: Optional[Dict[str, Any]]
+    ) -> Dict[str, Any]:
+        """
+        调用位置；位于宏定义（如标准库的 println!）中的调用没有调用者
+        文件中的位置，使用调用者的定义位置
+        """
+        source = self.source_file(span["file"]) if span else None
+        if source is None or source.path != caller["file"]:
+            return {"line": caller["start_line"], "column": 0, "snippet": None}
+        return {
+            "line": span["line"],
+            "column": span["column"] - 1,
+            "snippet": source.snippet(span),
+        }
+
+    def _resolve(
+        self, caller: Dict[str, Any], callee: str, site: Dict[str, Any]
+    ) -> Tuple[Optional[Dict[str, Any]], Optional[Dict[str, Any]]]:
+        path = callee_path(callee)
+        name = path["name"]
+        self_type = path["self_type"]
+        ref = {
+            "caller_id": caller["id"],
+            "caller_name": caller["name"],
+            "caller_file": caller["file"],
+            "call_name": name,
+            "call_site_line": site["line"],
+            "call_site_column": site["column"],
+            "call_site_snippet": site["snippet"],
+            "language": "rust",
+            "hints": {},
+        }
+
+        if self_type and self_type.startswith("{closure@"):
+            match = CLOSURE_TYPE.match(self_type)
+            closure = self.function_at(
+                match.group("file"),
+                int(match.group("line")),
+                int(match.group("column")),
+            )
+            if closure and (closure.get("extras") or {}).get("closure"):
+                ref["call_name"] = closure["name"]
+                ref["hints"]["target"] = {"id": closure["id"]}
+                return None, ref
+            return None, None
+
+        type_params = (caller.get("extras") or {}).get("type_params") or []
+        if self_type and path["trait"]:
+            base = _strip_generics(self_type)[0]
+            if base.startswith("dyn ") or base in type_params:
+                # trait 对象 / 泛型约束：连接到 trait 的所有实现
+                ref["hints"]["traits"] = [path["trait"]]
+                return None, ref
+            owner = _split_path(base)[-1] if _split_path(base) else base
+            func = self._trait_method(caller, owner, path["trait"], name)
+            full_path = "::".join([*_split_path(base), name])
+        else:
+            full_path = "::".join(path["segments"])
+            func = self._by_path(caller, full_path)
+
+        if func is None and not self_type and name in self.foreign:
+            ref["hints"]["ffi"] = True
+            return None, ref
+        if func is None:
+            return self._relation(caller, None, name, site, path, full_path), None
+        return self._relation(caller, func, name, site, path, None), None
+
+    def _trait_method(
+        self, caller: Dict[str, Any], owner: str, trait: str, name: str
+    ) -> Optional[Dict[str, Any]]:
+        """类型的 trait 实现方法；没有覆盖时为 trait 的默认方法"""
+        implementations = []
+        default = None
+        for func in self.rust_functions:
+            extras = func.get("extras") or {}
+            if func["name"] != name:
+                continue
+            if extras.get("trait") == trait and extras.get("owner") == owner:
+                implementations.append(func)
+            elif extras.get("in_trait") and extras.get("owner") == trait:
+                default = func
+        return self._prefer_crate(caller, implementations) or default
+
+    def _by_path(self, caller: Dict[str, Any], path: str) -> Optional[Dict[str, Any]]:
+        return self._prefer_crate(caller, self.paths.get(path, []))
+
+    @staticmethod
+    def _prefer_crate(
+        caller: Dict[str, Any], candidates: List[Dict[str, Any]]
+    ) -> Optional[Dict[str, Any]]:
+        """有多个候选（如不同 crate 中的同名类型）时优先调用者所在的 crate"""
+        crate = (caller.get("extras") or {}).get("crate")
+        for func in candidates:
+            if (func.get("extras") or {}).get("crate") == crate:
+                return func
+        return candidates[0] if candidates else None
+
+    def _relation(
+        self,
+        caller: Dict[str, Any],
+        func: Optional[Dict[str, Any]],
+        name: str,
+        site: Dict[str, Any],
+        path: Dict[str, Any],
+        external_path: Optional[str],
+    ) -> Dict[str, Any]:
+        """
+        调用关系；被调用者不在项目中时使用与语法前端相同的外部 ID，
+        并记录完整路径（用于副作用标记）
+        """
+        type_args = None
+        if func is None or (func.get("extras") or {}).get("type_params"):
+            type_args = ", ".join(path["type_args"]) or None
+        return {
+            "caller_id": caller["id"],
+            "callee_id": (
+                func["id"] if func else self.parser.generate_id("external", name, 0)
+            ),
+            "caller_name": caller["name"],
+            "callee_name": func["name"] if func else name,
+            "caller_file": caller["file"],
+            "callee_file": func["file"] if func else None,
+            "call_site_line": site["line"],
+            "call_site_column": site["column"],
+            "call_site_snippet": site["snippet"],
+            "language": "rust",
+            "edge_kind": "static",
+            "confidence": "certain",
+            "type_args": type_args,
+            "callee_path": external_path,
+            "cfg": None,
+        }
+
+    def _closure_refs(
+        self,
+        caller: Dict[str, Any],
+        closures: List[Tuple[str, int, int]],
+        spawned: Set[Tuple[str, int, int]],
+    ) -> List[Dict[str, Any]]:
+        """
+        函数中定义的闭包（与语法前端相同的 closure 边，交给 thread::spawn 等
+        运行的闭包为 spawn 边）
+        """
+        refs = []
+        seen = set()
+        for file_path, line, column in closures:
+            closure = self.function_at(file_path, line, column)
+            if (
+                closure is None
+                or closure["id"] in seen
+                or (closure.get("extras") or {}).get("parent") != caller["id"]
+            ):
+                continue
+            seen.add(closure["id"])
+            hints = {"target": {"id": closure["id"], "edge_kind": "closure"}}
+            if (file_path, line, column) in spawned:
+                hints["spawn"] = True
+            refs.append(
+                {
+                    "caller_id": caller["id"],
+                    "caller_name": caller["name"],
+                    "caller_file": caller["file"],
+                    "call_name": closure["name"],
+                    "call_site_line": line,
+                    "call_site_column": column - 1,
+                    "call_site_snippet": None,
+                    "language": "rust",
+                    "hints": hints,
+                }
+            )
+        return refs
+
+
+def mir_call_edges(
+    project_path: Path,
+    rust_files: List[str],
+    all_functions: List[Dict[str, Any]],
+    exclude_dirs: List[str],
+    foreign: Optional[Set[str]] = None,
+) -> Dict[str, Any]:
+    """
+    从 MIR 提取 Rust 调用关系
+
+    Args:
+        project_path: 项目路径
+        rust_files: 已分析的 Rust 源文件
+        all_functions: 全局函数表（用于对应 MIR 函数和解析被调用者）
+        exclude_dirs: 查找 crate 时排除的目录
+        foreign: extern 块中声明的函数名（调用这些函数的边类型为 ffi）
+
+    Returns:
+        {"calls": 调用关系, "callers": 调用关系被 MIR 替换的函数 ID,
+         "crates": 成功编译的目标数, "unmapped": 没有对应到源码函数的 MIR 函数数}
+    """
+    parser = get_parser("rust")
+    rust_functions = [func for func in all_functions if func.get("language") == "rust"]
+    sources: Dict[str, _SourceFile] = {}
+    for path in sorted(set(rust_files)):
+        sources[str(Path(path).resolve())] = _SourceFile(path)
+    for func in rust_functions:
+        source = sources.get(str(Path(func["file"]).resolve()))
+        if source is not None and func.get("start_byte") is not None:
+            source.functions.append(func)
+
+    # 按 cargo 标注的绝对路径（含 crate 名）索引，用于跨 crate 的调用
+    paths: Dict[str, List[Dict[str, Any]]] = {}
+    for func in rust_functions:
+        extras = func.get("extras") or {}
+        path = function_path(func)
+        if path:
+            paths.setdefault(path, []).append(func)
+        if extras.get("owner_type") and not extras.get("closure"):
+            paths.setdefault(f"{extras['owner_type']}::{func['name']}", []).append(func)
+
+    relations: List[Dict[str, Any]] = []
+    refs: List[Dict[str, Any]] = []
+    callers: Set[str] = set()
+    compiled = 0
+    unmapped = 0
+    for crate_dir in find_crates(project_path, exclude_dirs):
+        for target_args, target_root in crate_targets(crate_dir):
+            text = run_mir(crate_dir, target_args)
+            if text is None:
+                continue
+            compiled += 1
+            builder = MirCallGraph(
+                crate_dir,
+                target_root,
+                sources,
+                paths,
+                rust_functions,
+                parser,
+                foreign,
+            )
+            target_relations, target_refs, target_callers, missing = builder.build(
+                parse_mir(text)
+            )
+            # 同一个函数可能出现在多个目标中（如 lib 和 bin 共用的模块），只保留一次
+            fresh = target_callers - callers
+            relations.extend(r for r in target_relations if r["caller_id"] in fresh)
+            refs.extend(r for r in target_refs if r["caller_id"] in fresh)
+            callers |= fresh
+            unmapped += missing
+
+    calls = relations + parser.resolve_call_refs(refs, all_functions)
+    return {
+        "calls": calls,
+        "callers": callers,
+        "crates": compiled,
+        "unmapped": unmapped,
+    }
@@ -0,0 +1,150 @@
+"""
+直接导入 Neo4j
+通过 bolt 协议把调用图写入 Neo4j 数据库，节点、标签和关系与 Cypher 导出相同；
+需要安装可选依赖 neo4j（pip install "call-graph[neo4j]"）
+"""
+
+from typing import Any, Dict, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .exporters import CYPHER_NODE_LABEL, cypher_graph_data
+    from .graph import GROUP_KINDS, CallGraph
+except ImportError:
+    from exporters import CYPHER_NODE_LABEL, cypher_graph_data
+    from graph import GROUP_KINDS, CallGraph
+
+try:
+    import neo4j
+except ImportError:
+    neo4j = None
+
+# 每个事务写入的行数
+BATCH_SIZE = 1000
+
+# 密码所在的环境变量（避免出现在命令行历史中）
+PASSWORD_ENV = "NEO4J_PASSWORD"
+
+
+def _batches(rows: List[Dict[str, Any]]):
+    for start in range(0, len(rows), BATCH_SIZE):
+        yield rows[start : start + BATCH_SIZE]
+
+
+def load_into_neo4j(
+    graph: CallGraph,
+    uri: str,
+    user: str = "neo4j",
+    password: Optional[str] = None,
+    database: Optional[str] = None,
+    clear: bool = False,
+) -> Dict[str, int]:
+    """
+    把调用图写入 Neo4j
+
+    按标签组合分组，用 UNWIND 参数批量创建节点和关系，不拼接语句文本
+
+    Args:
+        uri: bolt 地址（如 bolt://localhost:7687 或 neo4j://host:7687）
+        database: 数据库名，None 表示服务器的默认数据库
+        clear: 写入前删除数据库中已有的调用图节点（Symbol/Module/Crate）及其关系
+
+    Returns:
+        {"nodes": 节点数, "edges": 边数}
+    """
+    if neo4j is None:
+        raise ValueError('直接导入 Neo4j 需要安装 neo4j: pip install "call-graph[neo4j]"')
+
+    data = cypher_graph_data(graph)
+    label = CYPHER_NODE_LABEL
+    by_labels: Dict[str, List[Dict[str, Any]]] = {}
+    for node in data["nodes"]:
+        properties = {k: v for k, v in node["properties"].items() if v is not None}
+        by_labels.setdefault(":".join(node["labels"]), []).append(properties)
+    memberships = [
+        {"id": node["properties"]["id"], "module": node["properties"]["module"]}
+        for node in data["nodes"]
+        if node["properties"]["kind"] not in GROUP_KINDS
+    ]
+    module_crates = [
+        {"id": node["properties"]["id"], "crate": node["properties"]["crate"]}
+        for node in data["nodes"]
+        if node["properties"]["kind"] in GROUP_KINDS
+        and node["properties"]["crate"]
+    ]
+    edges = [
+        {"caller": e["caller_id"], "callee": e["callee_id"], **e["properties"]}
+        for e in data["edges"]
+    ]
+
+    def write(tx, query: str, rows: List[Dict[str, Any]]):
+        for batch in _batches(rows):
+            tx.run(query, rows=batch)
+
+    try:
+        driver = neo4j.GraphDatabase.driver(uri, auth=(user, password or ""))
+    except Exception as e:
+        raise ValueError(f"无法连接 Neo4j: {e}")
+    try:
+        with driver.session(database=database) as session:
+            if clear:
+                session.run(
+                    f"MATCH (n) WHERE n:{label} OR n:Module OR n:Crate "
+                    "DETACH DELETE n"
+                )
+            session.run(
+                f"CREATE CONSTRAINT symbol_id IF NOT EXISTS FOR (n:{label}) "
+                "REQUIRE n.id IS UNIQUE"
+            )
+            session.run(
+                "CREATE INDEX module_name IF NOT EXISTS FOR (m:Module) ON (m.name)"
+            )
+            session.execute_write(
+                write,
+                "UNWIND $rows AS row CREATE (:Crate {name: row.name})",
+                data["crates"],
+            )
+            session.execute_write(
+                write,
+                "UNWIND $rows AS row CREATE (m:Module {name: row.name}) "
+                "SET m.crate = row.crate",
+                data["modules"],
+            )
+            for labels, rows in sorted(by_labels.items()):
+                session.execute_write(
+                    write, f"UNWIND $rows AS row CREATE (n:{labels}) SET n = row", rows
+                )
+            session.execute_write(
+                write,
+                f"UNWIND $rows AS row MATCH (n:{label} {{id: row.id}}), "
+                "(m:Module {name: row.module}) CREATE (n)-[:IN_MODULE]->(m)",
+                memberships,
+            )
+            session.execute_write(
+                write,
+                f"UNWIND $rows AS row MATCH (n:{label} {{id: row.id}}), "
+                "(c:Crate {name: row.crate}) CREATE (n)-[:IN_CRATE]->(c)",
+                module_crates,
+            )
+            session.execute_write(
+                write,
+                "UNWIND $rows AS row MATCH (m:Module {name: row.name}), "
+                "(c:Crate {name: row.crate}) CREATE (m)-[:IN_CRATE]->(c)",
+                [module for module in data["modules"] if module["crate"]],
+            )
+            session.execute_write(
+                write,
+                f"UNWIND $rows AS row MATCH (a:{label} {{id: row.caller}}), "
+                f"(b:{label} {{id: row.callee}}) "
+                "CREATE (a)-[:CALLS {kind: row.kind, count: row.count}]->(b)",
+                edges,
+            )
+    except neo4j.exceptions.Neo4jError as e:
+        raise ValueError(f"写入 Neo4j 失败: {e}")
+    except neo4j.exceptions.DriverError as e:
+        raise ValueError(f"无法连接 Neo4j: {e}")
+    finally:
+        driver.close()
+
+    print(f"导入成功: {len(data['nodes'])} 个节点, {len(edges)} 条边")
+    return {"nodes": len(data["nodes"]), "edges": len(edges)}
@@ -0,0 +1,137 @@
+"""
+no_std 兼容性审计
+按调用路径把项目外的调用归入标准库的 core、alloc 或 std，找出在 no_std 下不可用
+（需要未被允许的 crate）的调用，以及能到达这些调用的公开 API 函数
+"""
+
+from typing import Any, Dict, Iterable, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+# 标准库的三个层次，no_std 的 crate 默认允许使用 core 和 alloc
+STD_CRATES = ("core", "alloc", "std")
+DEFAULT_ALLOWED = ("core", "alloc")
+
+# 只在 std 中提供的模块（std::fs、std::io 等），其余 std 模块大多是 core 的
+# 重新导出
+STD_ONLY_MODULES = (
+    "backtrace",
+    "env",
+    "fs",
+    "io",
+    "net",
+    "os",
+    "path",
+    "process",
+    "thread",
+)
+
+# 所在模块来自 core/alloc，但本身只在 std 中提供的项
+STD_ONLY_ITEMS = (
+    "std::collections::HashMap",
+    "std::collections::HashSet",
+    "std::collections::hash_map",
+    "std::collections::hash_set",
+    "std::ffi::OsStr",
+    "std::ffi::OsString",
+    "std::hash::DefaultHasher",
+    "std::hash::RandomState",
+    "std::panic::catch_unwind",
+    "std::panic::resume_unwind",
+    "std::panic::set_hook",
+    "std::panic::take_hook",
+    "std::sync::Barrier",
+    "std::sync::Condvar",
+    "std::sync::LazyLock",
+    "std::sync::Mutex",
+    "std::sync::Once",
+    "std::sync::OnceLock",
+    "std::sync::RwLock",
+    "std::sync::mpsc",
+    "std::time::Instant",
+    "std::time::SystemTime",
+)
+
+# std 中从 alloc 重新导出的模块和项（需要堆分配）
+ALLOC_MODULES = ("boxed", "collections", "rc", "string", "vec")
+ALLOC_ITEMS = (
+    "std::alloc::alloc",
+    "std::alloc::alloc_zeroed",
+    "std::alloc::dealloc",
+    "std::alloc::handle_alloc_error",
+    "std::alloc::realloc",
+    "std::borrow::Cow",
+    "std::borrow::ToOwned",
+    "std::ffi::CString",
+    "std::fmt::format",
+    "std::sync::Arc",
+    "std::sync::Weak",
+)
+
+# std 的 prelude 中来自 alloc 的名称，不带路径调用（如 Vec::new）时按 alloc 计
+PRELUDE_ALLOC = ("Box", "String", "ToOwned", "ToString", "Vec")
+
+
+def _under(path: str, items: Iterable[str]) -> bool:
+    return any(path == item or path.startswith(item + "::") for item in items)
+
+
+def required_crate(path: str) -> Optional[str]:
+    """
+    调用路径（如 std::fs::read）需要的标准库 crate（见 STD_CRATES），
+    不属于标准库的路径为 None
+    """
+    segments = path.split("::")
+    root = segments[0]
+    if root in ("core", "alloc"):
+        return root
+    if root in PRELUDE_ALLOC and len(segments) > 1:
+        return "alloc"
+    if root != "std" or len(segments) < 2:
+        return None
+    if segments[1] in STD_ONLY_MODULES or _under(path, STD_ONLY_ITEMS):
+        return "std"
+    if segments[1] in ALLOC_MODULES or _under(path, ALLOC_ITEMS):
+        return "alloc"
+    return "core"
+
+
+def std_calls(
+    graph: CallGraph, allowed: Iterable[str] = DEFAULT_ALLOWED
+) -> Dict[str, List[Dict[str, Any]]]:
+    """
+    直接调用了未被允许的标准库函数的项目函数
+
+    Args:
+        allowed: 允许使用的 crate（core 总是允许）
+
+    Returns:
+        {函数ID: [{"callee", "requires", "line", "column"}]}，调用点按行号排序；
+        被调用函数按 use 声明展开后的路径判断（见 parsers 中的 callee_path），
+        无法确定路径的调用不计入
+    """
+    allowed = set(allowed) | {"core"}
+    calls: Dict[str, List[Dict[str, Any]]] = {}
+    for relation in graph.relations:
+        caller_id = relation["caller_id"]
+        if caller_id not in graph.nodes or relation["callee_id"] in graph.nodes:
+            continue
+        callee = relation.get("callee_path") or relation["callee_name"]
+        requires = required_crate(callee)
+        if requires is None or requires in allowed:
+            continue
+        calls.setdefault(caller_id, []).append(
+            {
+                "callee": callee,
+                "requires": requires,
+                "line": relation.get("call_site_line"),
+                "column": relation.get("call_site_column"),
+            }
+        )
+    for sites in calls.values():
+        sites.sort(key=lambda s: (s["line"] or 0, s["column"] or 0, s["callee"]))
+    return calls
@@ -0,0 +1,179 @@
+"""
+代码所有者
+读取 CODEOWNERS 文件（GitHub / GitLab 格式），把每个函数归属到所在文件的所有者
+（团队），统计团队之间的调用依赖：哪些团队的代码依赖哪些团队
+"""
+
+import os
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+    from .ignore import IgnorePattern
+except ImportError:
+    from graph import CallGraph
+    from ignore import IgnorePattern
+
+# 查找 CODEOWNERS 的位置（相对于仓库根目录，按 GitHub 的优先级）
+CODEOWNERS_LOCATIONS = (".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS")
+
+# 没有匹配任何规则（或匹配的规则没有所有者）的文件所属的团队
+UNOWNED = "(无所有者)"
+
+# 每个团队依赖列出的示例调用数量
+OWNER_EXAMPLES = 3
+
+
+class OwnerRule:
+    """
+    CODEOWNERS 中的一条规则：路径模式和所有者
+
+    模式的语法同 .gitignore（见 ignore.IgnorePattern），但不支持 ! 规则；
+    匹配目录的规则作用于目录下的所有文件，末段为 *（如 docs/*）时只匹配
+    目录中直接包含的文件
+    """
+
+    def __init__(self, pattern: str, owners: List[str]):
+        self.pattern = pattern
+        self.owners = tuple(owners)
+        self._matcher = IgnorePattern(pattern)
+        last = pattern.rstrip("/").rsplit("/", 1)[-1]
+        self._recursive = "*" not in last or last == "**"
+
+    def matches(self, relative_path: str) -> bool:
+        if self._matcher.matches(relative_path, False):
+            return True
+        if not self._recursive:
+            return False
+        parts = relative_path.split("/")
+        return any(
+            self._matcher.matches("/".join(parts[:index]), True)
+            for index in range(1, len(parts))
+        )
+
+
+class CodeOwners:
+    """一个 CODEOWNERS 文件：同一文件中靠后的规则优先"""
+
+    def __init__(self, path: str):
+        self.path = Path(path).resolve()
+        # 规则相对于仓库根目录：.github/ 和 docs/ 中的文件为上一级目录
+        self.root = self.path.parent
+        if self.root.name in (".github", "docs"):
+            self.root = self.root.parent
+        self.rules: List[OwnerRule] = []
+        try:
+            text = self.path.read_text(encoding="utf-8", errors="replace")
+        except OSError as e:
+            raise ValueError(f"无法读取 CODEOWNERS 文件 {path}: {e}") from e
+        for line in text.splitlines():
+            line = line.split(" #", 1)[0].strip()
+            # GitLab 的 [Section] 标题不影响匹配
+            if not line or line.startswith("#") or line.startswith("["):
+                continue
+            pattern, *owners = line.split()
+            try:
+                self.rules.append(OwnerRule(pattern, owners))
+            except ValueError:
+                continue
+
+    @classmethod
+    def find(cls, directory: str) -> Optional["CodeOwners"]:
+        """
+        从 directory 向上查找 CODEOWNERS（每级目录按 CODEOWNERS_LOCATIONS
+        的顺序），到 git 仓库根目录为止；找不到时为 None
+        """
+        current = Path(directory).resolve()
+        for candidate in (current, *current.parents):
+            for location in CODEOWNERS_LOCATIONS:
+                if (candidate / location).is_file():
+                    return cls(str(candidate / location))
+            if (candidate / ".git").exists():
+                break
+        return None
+
+    def owners(self, file_path: str) -> Tuple[str, ...]:
+        """文件的所有者（没有匹配的规则或不在仓库中时为空）"""
+        try:
+            relative = Path(os.path.abspath(file_path)).relative_to(self.root)
+        except ValueError:
+            return ()
+        relative_path = relative.as_posix()
+        for rule in reversed(self.rules):
+            if rule.matches(relative_path):
+                return rule.owners
+        return ()
+
+    def team(self, file_path: str) -> str:
+        """文件所属的团队：所有者以空格连接，没有所有者时为 UNOWNED"""
+        return " ".join(self.owners(file_path)) or UNOWNED
+
+
+def node_teams(graph: CallGraph, codeowners: CodeOwners) -> Dict[str, str]:
+    """每个函数所属的团队 {节点ID: 团队}"""
+    by_file: Dict[str, str] = {}
+    teams = {}
+    for node_id, node in graph.nodes.items():
+        file_path = node.get("file") or ""
+        if file_path not in by_file:
+            by_file[file_path] = codeowners.team(file_path)
+        teams[node_id] = by_file[file_path]
+    return teams
+
+
+def team_dependencies(graph: CallGraph, codeowners: CodeOwners) -> Dict[str, Any]:
+    """
+    团队之间的调用依赖
+
+    Returns:
+        teams: {团队: 函数数量}；
+        edges: 跨团队的调用边 [{"caller_id", "callee_id", "from", "to", "sites"}]，
+        按团队和限定名排序；
+        dependencies: 团队依赖 [{"from", "to", "edges", "calls", "examples"}]，
+        calls 为调用点数量，examples 为前 OWNER_EXAMPLES 条调用边，
+        按调用点数量降序排列
+    """
+    teams = node_teams(graph, codeowners)
+    counts: Dict[str, int] = {}
+    for team in teams.values():
+        counts[team] = counts.get(team, 0) + 1
+
+    edges = []
+    for edge in graph.edges():
+        caller_id, callee_id = edge["caller_id"], edge["callee_id"]
+        if teams[caller_id] == teams[callee_id]:
+            continue
+        edges.append(
+            {
+                "caller_id": caller_id,
+                "callee_id": callee_id,
+                "from": teams[caller_id],
+                "to": teams[callee_id],
+                "sites": graph.call_sites(caller_id, callee_id),
+            }
+        )
+    edges.sort(
+        key=lambda e: (
+            e["from"],
+            e["to"],
+            graph.qualified_name(e["caller_id"]),
+            graph.qualified_name(e["callee_id"]),
+        )
+    )
+
+    summary: Dict[Tuple[str, str], Dict[str, Any]] = {}
+    for edge in edges:
+        key = (edge["from"], edge["to"])
+        entry = summary.setdefault(
+            key, {"from": key[0], "to": key[1], "edges": 0, "calls": 0, "examples": []}
+        )
+        entry["edges"] += 1
+        entry["calls"] += len(edge["sites"])
+        if len(entry["examples"]) < OWNER_EXAMPLES:
+            entry["examples"].append(edge)
+    dependencies = sorted(
+        summary.values(), key=lambda d: (-d["calls"], d["from"], d["to"])
+    )
+    return {"teams": counts, "edges": edges, "dependencies": dependencies}
@@ -4,11 +4,25 @@
 """
 
 import hashlib
+import os
+import re
 from pathlib import Path
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, List, Optional, Set, Tuple
 
 from tree_sitter import Language, Node, Parser
 
+# 支持相对导入和直接运行
+try:
+    from .cargo import function_path
+    from .cfg import cfg_predicates, combine_cfg
+    from .directives import DIRECTIVE_PATTERN, collect_directives
+    from .graph import EdgeKind
+except ImportError:
+    from cargo import function_path
+    from cfg import cfg_predicates, combine_cfg
+    from directives import DIRECTIVE_PATTERN, collect_directives
+    from graph import EdgeKind
+
 # 语言配置
 LANGUAGE_CONFIG = {
     "python": {
@@ -16,33 +30,90 @@ LANGUAGE_CONFIG = {
         "module": "tree_sitter_python",
         "function_types": ["function_definition", "async_function_definition"],
         "call_types": ["call"],
+        "class_types": ["class_definition"],
+        "decision_types": [
+            "if_statement",
+            "elif_clause",
+            "for_statement",
+            "while_statement",
+            "except_clause",
+            "case_clause",
+            "conditional_expression",
+            "boolean_operator",
+            "if_clause",
+        ],
     },
     "c": {
         "extensions": [".c", ".h"],
         "module": "tree_sitter_c",
         "function_types": ["function_definition"],
         "call_types": ["call_expression"],
+        "class_types": [],
+        "decision_types": [
+            "if_statement",
+            "for_statement",
+            "while_statement",
+            "do_statement",
+            "case_statement",
+            "conditional_expression",
+        ],
     },
     "cpp": {
         "extensions": [".cpp", ".cc", ".cxx", ".hpp", ".hxx", ".h"],
         "module": "tree_sitter_cpp",
         "function_types": ["function_definition", "function_declarator"],
         "call_types": ["call_expression"],
+        "class_types": ["class_specifier", "struct_specifier"],
+        "decision_types": [
+            "if_statement",
+            "for_statement",
+            "for_range_loop",
+            "while_statement",
+            "do_statement",
+            "case_statement",
+            "catch_clause",
+            "conditional_expression",
+        ],
     },
     "java": {
         "extensions": [".java"],
         "module": "tree_sitter_java",
         "function_types": ["method_declaration", "constructor_declaration"],
-        "call_types": ["method_invocation"],
+        "call_types": ["method_invocation", "object_creation_expression"],
+        "class_types": [
+            "class_declaration",
+            "interface_declaration",
+            "enum_declaration",
+            "record_declaration",
+        ],
+        "decision_types": [
+            "if_statement",
+            "for_statement",
+            "enhanced_for_statement",
+            "while_statement",
+            "do_statement",
+            "switch_label",
+            "catch_clause",
+            "ternary_expression",
+        ],
     },
     "rust": {
         "extensions": [".rs"],
         "module": "tree_sitter_rust",
         "function_types": ["function_item"],
         "call_types": ["call_expression"],
+        "class_types": ["impl_item", "trait_item"],
+        "closure_types": ["closure_expression"],
+        "decision_types": [
+            "if_expression",
+            "while_expression",
+            "for_expression",
+            "match_arm",
+            "try_expression",
+        ],
     },
     "javascript": {
-        "extensions": [".js", ".jsx", ".mjs"],
+        "extensions": [".js", ".jsx", ".mjs", ".cjs"],
         "module": "tree_sitter_javascript",
         "function_types": [
             "function_declaration",
@@ -51,9 +122,20 @@ LANGUAGE_CONFIG = {
             "method_definition",
         ],
         "call_types": ["call_expression"],
+        "class_types": ["class_declaration", "class"],
+        "decision_types": [
+            "if_statement",
+            "for_statement",
+            "for_in_statement",
+            "while_statement",
+            "do_statement",
+            "switch_case",
+            "catch_clause",
+            "ternary_expression",
+        ],
     },
     "typescript": {
-        "extensions": [".ts", ".tsx"],
+        "extensions": [".ts", ".tsx", ".mts", ".cts"],
         "module": "tree_sitter_typescript",
         "submodule": "typescript",
         "function_types": [
@@ -64,21 +146,416 @@ LANGUAGE_CONFIG = {
             "method_signature",
         ],
         "call_types": ["call_expression"],
+        "class_types": [
+            "class_declaration",
+            "abstract_class_declaration",
+            "class",
+            "interface_declaration",
+        ],
+        "decision_types": [
+            "if_statement",
+            "for_statement",
+            "for_in_statement",
+            "while_statement",
+            "do_statement",
+            "switch_case",
+            "catch_clause",
+            "ternary_expression",
+        ],
     },
     "go": {
         "extensions": [".go"],
         "module": "tree_sitter_go",
         "function_types": ["function_declaration", "method_declaration"],
         "call_types": ["call_expression"],
+        "class_types": [],
+        "decision_types": [
+            "if_statement",
+            "for_statement",
+            "expression_case",
+            "type_case",
+            "communication_case",
+        ],
     },
 }
 
+# 计入圈复杂度的短路逻辑运算符（binary_expression 的运算符子节点）
+LOGICAL_OPERATORS = {"&&", "||", "??"}
+
+# 调用点源码片段的最大长度（字符）
+CALL_SNIPPET_LENGTH = 120
+
+# 计算函数体指纹时替换为占位符的词法单元：标识符（节点类型含 identifier）和
+# 字面量（节点类型含以下片段），使只重命名了变量或修改了常量的函数指纹相同
+FINGERPRINT_LITERALS = ("literal", "string", "number", "integer", "float", "char")
+
+# 文档摘要（文档注释的第一行）的最大长度（字符）
+DOC_SUMMARY_LENGTH = 200
+
+# 各语言语法树中的注释节点类型
+COMMENT_TYPES = ("comment", "line_comment", "block_comment")
+
+# 编译器/工具指令注释（//go:noinline、//nolint:errcheck），不是文档
+TOOL_DIRECTIVE_COMMENT = re.compile(r"^//\w+:")
+
+# 定义外层的包装节点：文档注释写在它们之前（export function f()、
+# template <..> T f()、const f = () => ...）
+DOC_WRAPPER_TYPES = (
+    "export_statement",
+    "template_declaration",
+    "variable_declarator",
+    "lexical_declaration",
+    "variable_declaration",
+)
+
+# 相对当前位置的路径前缀，不能作为项目外函数的路径
+RELATIVE_PATH_PREFIXES = ("self", "super", "crate", "Self")
+
+# 解析器没有给出置信度（confidence）时按边类型取的默认值：动态分发和间接调用的
+# 目标是多个候选之一，为 probable；其他为 certain
+EDGE_KIND_CONFIDENCE = {
+    EdgeKind.DYNAMIC.value: "probable",
+    EdgeKind.INDIRECT.value: "probable",
+}
+
+# 跨语言（FFI）边界上的调用：Rust 调用 extern 块中声明的函数，C/C++ 调用 Rust 中
+# #[no_mangle] 导出的函数
+FFI_EDGE_KIND = EdgeKind.FFI.value
+
+
+class LanguageAnalyzer:
+    """
+    语言前端接口
+
+    前端把源文件解析为函数定义和未解析的调用引用（parse_file_symbols），
+    再在全局符号表中解析调用引用（resolve_call_refs，默认按名称匹配）。
+    内置语言基于 tree-sitter（LanguageParser）；第三方前端只需继承本类，
+    通过 register_language 注册即可，不要求使用 tree-sitter。
+
+    函数定义是包含 id, file, name, kind, start_line, end_line, start_byte,
+    end_byte, container, signature, language, is_exported, extras 的字典；
+    调用引用包含 caller_id, caller_name, caller_file, call_name,
+    call_site_line, call_site_column, language, hints，以及可选的
+    call_site_snippet（调用表达式的源码片段）
+
+    hints 中的 value 表示函数作为值被引用（map(f)、cb: f），不是调用；
+    indirect 表示通过函数指针/回调的间接调用，目标由 indirect_targets 推断；
+    spawn 表示函数（或闭包、调用）交给新线程/异步任务运行，边类型为 spawn；
+    error_handling 为调用点对返回的错误的处理方式（见 ERROR_HANDLING）；
+    type_args 为调用点的泛型类型实参，被调用函数的类型参数记录在
+    extras.type_params 中
+    """
+
+    # 语言名称和文件扩展名（第三方前端在子类中声明）
+    language_name = ""
+    extensions: List[str] = []
+
+    def __init__(self, language_name: Optional[str] = None):
+        self.language_name = language_name or type(self).language_name
+        # 被作为值引用的函数、所有函数 ID 和泛型函数 ID，函数表变化时重建
+        self._address_taken: List[Dict[str, Any]] = []
+        self._function_ids: Set[str] = set()
+        self._generic_ids: Set[str] = set()
+        self._function_index_key: Optional[tuple] = None
+
+    def generate_id(self, file_path: str, name: str, start_line: int) -> str:
+        """生成唯一ID"""
+        unique_str = f"{file_path}:{name}:{start_line}"
+        return hashlib.md5(unique_str.encode()).hexdigest()
+
+    def parse_file_symbols(self, file_path: str) -> Dict[str, Any]:
+        """
+        解析文件，返回 {"functions": 函数定义列表, "call_refs": 调用引用列表}
+        （需要子类实现）
+        """
+        raise NotImplementedError
+
+    def extract_functions(self, file_path: str) -> List[Dict[str, Any]]:
+        """提取文件中的所有函数定义"""
+        return self.parse_file_symbols(file_path)["functions"]
+
+    def extract_calls(
+        self, file_path: str, functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """提取函数调用关系"""
+        return self.resolve_call_refs(
+            self.extract_call_refs(file_path, functions), functions
+        )
+
+    def extract_call_refs(
+        self, file_path: str, functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """提取文件中未解析的调用引用"""
+        return self.parse_file_symbols(file_path)["call_refs"]
+
+    def resolve_call_refs(
+        self, call_refs: List[Dict[str, Any]], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        将调用引用解析为调用关系（一个引用可能对应多个被调用函数）
+
+        间接调用点连接到所有可能的目标；找不到候选目标时按普通调用解析。
+        函数值引用只在传给项目外的函数时生成调用关系（见 callback_targets）
+        """
+        calls = []
+        self._function_index(functions)
+        for ref in call_refs:
+            hints = ref.get("hints") or {}
+            if hints.get("value"):
+                targets = self.callback_targets(ref, functions)
+            elif hints.get("indirect") and not hints.get("target"):
+                targets = self.indirect_targets(ref, functions)
+                targets = targets or self.resolve_callees(ref, functions)
+            else:
+                targets = self.resolve_callees(ref, functions)
+            for target in targets:
+                edge_kind = target.get("edge_kind", EdgeKind.STATIC.value)
+                calls.append(
+                    {
+                        "caller_id": ref["caller_id"],
+                        "callee_id": target["callee_id"],
+                        "caller_name": ref["caller_name"],
+                        "callee_name": target["callee_name"],
+                        "caller_file": ref["caller_file"],
+                        "callee_file": target.get("callee_file"),
+                        "call_site_line": ref["call_site_line"],
+                        "call_site_column": ref["call_site_column"],
+                        "call_site_snippet": ref.get("call_site_snippet"),
+                        "language": ref["language"],
+                        "edge_kind": EdgeKind.SPAWN.value
+                        if hints.get("spawn")
+                        else edge_kind,
+                        "confidence": target.get("confidence")
+                        or EDGE_KIND_CONFIDENCE.get(edge_kind, "certain"),
+                        "type_args": self._type_args(hints, target["callee_id"]),
+                        "callee_path": self._callee_path(hints, target["callee_id"]),
+                        "cfg": ref.get("cfg"),
+                        "error_handling": hints.get("error_handling"),
+                    }
+                )
+        return calls
+
+    def _type_args(self, hints: Dict[str, Any], callee_id: str) -> Optional[str]:
+        """
+        调用点实例化泛型函数使用的类型实参（如 "User"、"String, u32"）；
+        被调用者是项目中不带类型参数的函数时忽略
+        """
+        type_args = hints.get("type_args")
+        if not type_args:
+            return None
+        if callee_id in self._function_ids and callee_id not in self._generic_ids:
+            return None
+        return ", ".join(type_args)
+
+    def _callee_path(self, hints: Dict[str, Any], callee_id: str) -> Optional[str]:
+        """
+        项目外的被调用函数按 use 声明展开后的路径（如 std::fs::read），用于
+        副作用标记；相对当前模块的路径和没有导入的单个名称无法确定，为 None
+        """
+        if callee_id in self._function_ids:
+            return None
+        paths = hints.get("paths") or []
+        if len(paths) != 1 or paths[0].split("::")[0] in RELATIVE_PATH_PREFIXES:
+            return None
+        return paths[0]
+
+    def callable_languages(self) -> Tuple[str, ...]:
+        """间接调用可以指向的函数的语言"""
+        return (self.language_name,)
+
+    def _function_index(self, functions: List[Dict[str, Any]]):
+        """
+        被作为值引用的函数、所有函数 ID 和带类型参数的泛型函数 ID
+        （按 functions 列表缓存）
+        """
+        key = (id(functions), len(functions))
+        if self._function_index_key != key:
+            languages = self.callable_languages()
+            self._address_taken = [
+                func
+                for func in functions
+                if func.get("address_taken") and func.get("language") in languages
+            ]
+            self._function_ids = {func["id"] for func in functions}
+            self._generic_ids = {
+                func["id"]
+                for func in functions
+                if (func.get("extras") or {}).get("type_params")
+            }
+            self._function_index_key = key
+
+    def indirect_targets(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        间接调用（调用函数指针、回调参数或存入字段的函数值）的可能目标
+
+        没有类型信息，候选为全程序中作为值被引用过的函数（address_taken，
+        由 mark_address_taken 标记），按参数个数（arity）筛选
+        """
+        arity = (ref.get("hints") or {}).get("arity")
+        self._function_index(functions)
+        targets = []
+        for func in self._address_taken:
+            extras = func.get("extras") or {}
+            expected = extras.get("arity")
+            if (
+                arity is not None
+                and expected is not None
+                and not extras.get("varargs")
+                and arity != expected
+            ):
+                continue
+            targets.append(
+                {
+                    "callee_id": func["id"],
+                    "callee_name": func["name"],
+                    "callee_file": func["file"],
+                    "edge_kind": "indirect",
+                }
+            )
+        return targets
 
-class LanguageParser:
-    """多语言解析器基类"""
+    def callback_targets(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        作为参数传给项目外函数（map(f)、qsort(..., cmp)、thread::spawn(f)）的
+        函数值由外部代码回调：生成从传递处所在函数到该函数的间接调用
+
+        传给项目内的函数（或另一个函数指针）时，由其中的间接调用点连接，
+        这里不生成调用关系
+        """
+        hints = ref.get("hints") or {}
+        passed_to = hints.get("passed_to")
+        if not passed_to or not ref.get("caller_id"):
+            return []
+        if passed_to["hints"].get("indirect"):
+            return []
+        self._function_index(functions)
+        outer = {
+            **ref,
+            "call_name": passed_to["call_name"],
+            "hints": passed_to["hints"],
+        }
+        if any(
+            target["callee_id"] in self._function_ids
+            for target in self.resolve_callees(outer, functions)
+        ):
+            return []
+        return [
+            {**target, "edge_kind": "indirect"}
+            for target in self.resolve_callees(ref, functions)
+            if target["callee_id"] in self._function_ids
+        ]
+
+    def resolve_callees(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        解析调用引用对应的被调用函数
+
+        解析阶段已确定目标（hints 中的 target，如闭包）时直接使用；
+        否则按名称匹配同名函数（静态调用，有多个时优先取调用者所在
+        文件中的）。子类可覆盖以支持动态分发等需要返回多个目标的情况。
+
+        按名称匹配到的目标的置信度：项目中唯一的同名函数在调用者所在
+        文件中为 certain、在其他文件中为 probable；存在多个同名函数时，
+        调用者所在文件中恰好有一个为 probable，否则为 heuristic
+
+        Returns:
+            目标列表，每项包含 callee_id, callee_name, edge_kind，
+            以及可选的 confidence（certain / probable / heuristic）
+        """
+        call_name = ref["call_name"]
+        target = (ref.get("hints") or {}).get("target")
+        if target:
+            return [
+                {
+                    "callee_id": target["id"],
+                    "callee_name": call_name,
+                    "callee_file": ref["caller_file"],
+                    "edge_kind": target.get("edge_kind", "static"),
+                }
+            ]
+
+        # 依赖 crate 中的函数（--include-deps）只按路径解析，不参与按名称匹配
+        matches = [
+            func
+            for func in functions
+            if func["name"] == call_name
+            and not (func.get("extras") or {}).get("dependency")
+        ]
+
+        if not matches:
+            # 如果找不到定义，创建一个临时ID
+            callee_id = self.generate_id("external", call_name, 0)
+            return [
+                {
+                    "callee_id": callee_id,
+                    "callee_name": call_name,
+                    "edge_kind": "static",
+                }
+            ]
+
+        local = [func for func in matches if func["file"] == ref["caller_file"]]
+        if len(matches) == 1:
+            match = matches[0]
+            confidence = "certain" if local else "probable"
+        else:
+            # 多个同名函数：优先取调用者所在文件中的，但仍只是按名称猜测
+            match = local[0] if local else matches[0]
+            confidence = "probable" if len(local) == 1 else "heuristic"
+        return [
+            {
+                "callee_id": match["id"],
+                "callee_name": call_name,
+                "edge_kind": "static",
+                "confidence": confidence,
+            }
+        ]
+
+
+def _doc_summary(comments: List[str]) -> Optional[str]:
+    """
+    文档注释（按出现顺序）中的第一行非空文本：去掉 ///、//、/** */ 和每行
+    开头的 *；跳过工具指令和 callgraph: 注释指令
+    """
+    for text in comments:
+        if TOOL_DIRECTIVE_COMMENT.match(text) or DIRECTIVE_PATTERN.search(text):
+            continue
+        if text.startswith("/*"):
+            body = text[2:-2] if text.endswith("*/") else text[2:]
+            lines = body.lstrip("*").splitlines()
+            lines = [line.strip().lstrip("*") for line in lines]
+        else:
+            lines = [text.lstrip("/").lstrip("!")]
+        for line in lines:
+            if line.strip():
+                return line.strip()[:DOC_SUMMARY_LENGTH]
+    return None
+
+
+class LanguageParser(LanguageAnalyzer):
+    """基于 tree-sitter 的多语言解析器基类"""
+
+    # 函数作为值出现的位置：父节点类型 -> 值所在的字段（None 表示任一子节点），
+    # 如参数列表、结构体字段初始化、赋值右侧，子类按语法设置
+    FUNCTION_VALUE_CONTEXTS: Dict[str, Optional[str]] = {}
+    # 可以作为函数值的节点类型
+    FUNCTION_VALUE_TYPES: Tuple[str, ...] = ("identifier",)
+    # 把函数交给新线程/异步任务运行的调用（按名称或路径的最后一段匹配，如
+    # thread::spawn、Thread），作为参数传给它们的函数值和闭包是 spawn 边
+    SPAWN_CALLS: Tuple[str, ...] = ()
+    # 其中的调用在新线程/协程中运行的语句类型（如 Go 的 go 语句）
+    SPAWN_STATEMENTS: Tuple[str, ...] = ()
+    # 文档注释的前缀（/// 行注释、/** */ 块注释），子类按语言设置
+    DOC_COMMENT_PREFIXES: Tuple[str, ...] = ("///", "/**")
+    # 文档注释与定义之间可以出现的节点类型（如 Rust 的属性）
+    DOC_ATTRIBUTE_TYPES: Tuple[str, ...] = ()
 
     def __init__(self, language_name: str):
-        self.language_name = language_name
+        super().__init__(language_name)
         self.config = LANGUAGE_CONFIG[language_name]
         self.language = self._load_language()
         # tree-sitter >= 0.21 使用 Language 包装器
@@ -122,27 +599,160 @@ class LanguageParser:
             "utf-8", errors="ignore"
         )
 
-    def generate_id(self, file_path: str, name: str, start_line: int) -> str:
-        """生成唯一ID"""
-        unique_str = f"{file_path}:{name}:{start_line}"
-        return hashlib.md5(unique_str.encode()).hexdigest()
+    def call_snippet(self, node: Node, source_code: bytes) -> str:
+        """
+        调用点的表达式文本：只取第一行并合并空白，多行或过长时以 … 结尾
+        （最多 CALL_SNIPPET_LENGTH 个字符）
+        """
+        lines = self.get_node_text(node, source_code).strip().splitlines() or [""]
+        snippet = " ".join(lines[0].split())
+        if len(snippet) > CALL_SNIPPET_LENGTH:
+            return snippet[: CALL_SNIPPET_LENGTH - 1] + "…"
+        if len(lines) > 1:
+            return snippet + " …"
+        return snippet
+
+    def complexity(self, node: Node) -> int:
+        """
+        近似的圈复杂度：1 + 分支节点数（语言配置的 decision_types，
+        以及 &&、||、?? 短路运算），不计嵌套函数和闭包中的分支
+        """
+        decision_types = set(self.config.get("decision_types", []))
+        nested_types = set(self.config["function_types"]) | set(
+            self.config.get("closure_types", [])
+        )
+        count = 1
+        stack = list(node.children)
+        while stack:
+            current = stack.pop()
+            if current.type in nested_types:
+                continue
+            if current.type in decision_types:
+                count += 1
+            elif current.type == "binary_expression" and any(
+                child.type in LOGICAL_OPERATORS for child in current.children
+            ):
+                count += 1
+            stack.extend(current.children)
+        return count
+
+    def body_fingerprint(self, node: Node, source_code: bytes) -> Tuple[int, str]:
+        """
+        函数体的词法单元数量和规范化后的哈希
+
+        按顺序取语法树的叶子节点（不含注释），标识符和字面量替换为占位符（见
+        FINGERPRINT_LITERALS），其余保留原文；有函数体字段时只取函数体，不受
+        函数名和签名的影响
+        """
+        tokens = []
+        stack = [node.child_by_field_name("body") or node]
+        while stack:
+            current = stack.pop()
+            if "comment" in current.type:
+                continue
+            if "identifier" in current.type:
+                tokens.append("$id")
+            elif any(kind in current.type for kind in FINGERPRINT_LITERALS):
+                tokens.append("$lit")
+            elif current.children:
+                stack.extend(reversed(current.children))
+            else:
+                tokens.append(self.get_node_text(current, source_code))
+        digest = hashlib.sha1("\x00".join(tokens).encode()).hexdigest()[:16]
+        return len(tokens), digest
 
     def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
         """提取函数名称（需要子类实现）"""
         raise NotImplementedError
 
-    def extract_functions(self, file_path: str) -> List[Dict[str, Any]]:
-        """提取文件中的所有函数定义"""
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """提取函数的附加信息（存入 extras_json，子类可覆盖）"""
+        owner = self.find_owner(node, source_code)
+        return {"owner": owner} if owner else {}
+
+    def extract_attributes(self, node: Node, source_code: bytes) -> List[str]:
+        """
+        提取函数上的属性/注解/装饰器（不含 #[] 或 @ 符号），
+        如 Rust 的 test、no_mangle，Java 的 Test，子类可覆盖
+        """
+        return []
+
+    def extract_doc(self, node: Node, source_code: bytes) -> Optional[str]:
+        """
+        文档摘要：紧接在定义之前（中间可以有属性）、以 DOC_COMMENT_PREFIXES
+        开头的注释中的第一行非空文本，没有时为 None；子类可覆盖（如 Python
+        的 docstring）
+        """
+        while node.parent is not None and node.parent.type in DOC_WRAPPER_TYPES:
+            node = node.parent
+        comments = []
+        line = node.start_point[0]
+        sibling = node.prev_named_sibling
+        while sibling is not None and sibling.end_point[0] >= line - 1:
+            if sibling.type in COMMENT_TYPES:
+                text = self.get_node_text(sibling, source_code).strip()
+                if not text.startswith(self.DOC_COMMENT_PREFIXES):
+                    break
+                comments.insert(0, text)
+            elif sibling.type not in self.DOC_ATTRIBUTE_TYPES:
+                break
+            line = sibling.start_point[0]
+            sibling = sibling.prev_named_sibling
+        return _doc_summary(comments)
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """
+        提取函数的可见性：public / private，以及语言特有的
+        crate、restricted（Rust pub(in path)）、protected、package，子类可覆盖
+        """
+        return "public"
+
+    def find_owner(self, node: Node, source_code: bytes) -> Optional[str]:
+        """查找函数所属的类型（类、结构体等），用于区分方法和普通函数"""
+        parent = node.parent
+        while parent:
+            if parent.type in self.config["function_types"]:
+                # 嵌套在其他函数中的函数不算方法
+                return None
+            if parent.type in self.config["class_types"]:
+                name_node = parent.child_by_field_name("name")
+                if name_node:
+                    return self.get_node_text(name_node, source_code)
+                return None
+            parent = parent.parent
+        return None
+
+    def read_and_parse(self, file_path: str):
+        """读取并解析文件，返回 (根节点, 源代码)；失败时根节点为 None"""
         root = self.parse_file(file_path)
         if not root:
-            return []
-
+            return None, b""
         with open(file_path, "rb") as f:
             source_code = f.read()
+        return root, source_code
+
+    def extract_functions(self, file_path: str) -> List[Dict[str, Any]]:
+        """提取文件中的所有函数定义"""
+        root, source_code = self.read_and_parse(file_path)
+        if not root:
+            return []
+        return self.collect_functions(file_path, root, source_code)
 
+    def collect_functions(
+        self, file_path: str, root: Node, source_code: bytes
+    ) -> List[Dict[str, Any]]:
+        """从语法树中收集函数定义（闭包作为所在函数的子节点一并收集）"""
         functions = []
+        # 每个函数中已出现的闭包数量，用于闭包编号
+        closure_counts: Dict[str, int] = {}
 
-        def visit_node(node: Node, container: Optional[str] = None):
+        def visit_node(
+            node: Node,
+            container: Optional[str] = None,
+            parent: Optional[Dict[str, Any]] = None,
+        ):
             if node.type in self.config["function_types"]:
                 func_name = self.extract_function_name(node, source_code)
                 if func_name:
@@ -151,95 +761,274 @@ class LanguageParser:
                     )
 
                     # 提取函数签名
-                    signature = self.get_node_text(node, source_code).split("\n")[0]
-                    if len(signature) > 200:
-                        signature = signature[:200] + "..."
+                    signature = self.extract_signature(node, source_code)
 
-                    functions.append(
-                        {
-                            "id": func_id,
-                            "file": file_path,
-                            "name": func_name,
-                            "kind": "function",
-                            "start_line": node.start_point[0] + 1,
-                            "end_line": node.end_point[0] + 1,
-                            "start_byte": node.start_byte,
-                            "end_byte": node.end_byte,
-                            "container": container,
-                            "signature": signature,
-                            "language": self.language_name,
-                            "is_exported": 1,
-                        }
+                    extras = self.extract_extras(node, source_code)
+                    attributes = self.extract_attributes(node, source_code)
+                    if attributes:
+                        extras["attributes"] = attributes
+                    doc = self.extract_doc(node, source_code)
+                    if doc:
+                        extras["doc"] = doc
+                    visibility = self.extract_visibility(node, source_code, func_name)
+                    extras["visibility"] = visibility
+                    extras["complexity"] = self.complexity(node)
+                    extras["tokens"], extras["body_hash"] = self.body_fingerprint(
+                        node, source_code
                     )
 
+                    func = {
+                        "id": func_id,
+                        "file": file_path,
+                        "name": func_name,
+                        "kind": "function",
+                        "start_line": node.start_point[0] + 1,
+                        "end_line": node.end_point[0] + 1,
+                        "start_byte": node.start_byte,
+                        "end_byte": node.end_byte,
+                        "container": container,
+                        "signature": signature,
+                        "language": self.language_name,
+                        "is_exported": 1 if visibility == "public" else 0,
+                        "extras": extras,
+                    }
+                    functions.append(func)
+
                     # 更新容器名称
                     new_container = func_name
                     for child in node.children:
-                        visit_node(child, new_container)
+                        visit_node(child, new_container, func)
                     return
 
+            if node.type in self.config.get("closure_types", []) and parent:
+                closure = self.build_closure(
+                    file_path, node, source_code, parent, closure_counts
+                )
+                functions.append(closure)
+                for child in node.children:
+                    visit_node(child, closure["name"], closure)
+                return
+
             for child in node.children:
-                visit_node(child, container)
+                visit_node(child, container, parent)
 
         visit_node(root)
         return functions
 
-    def extract_calls(
+    def extract_signature(self, node: Node, source_code: bytes) -> str:
+        """取定义的第一行作为签名，过长时截断"""
+        signature = self.get_node_text(node, source_code).split("\n")[0]
+        if len(signature) > 200:
+            signature = signature[:200] + "..."
+        return signature
+
+    def build_closure(
+        self,
+        file_path: str,
+        node: Node,
+        source_code: bytes,
+        parent: Dict[str, Any],
+        closure_counts: Dict[str, int],
+    ) -> Dict[str, Any]:
+        """
+        构建闭包节点：作为所在函数（或外层闭包）的子节点，
+        按出现顺序命名为 parent::{closure#N}
+        """
+        index = closure_counts.get(parent["id"], 0)
+        closure_counts[parent["id"]] = index + 1
+        name = f"{parent['name']}::{{closure#{index}}}"
+
+        extras = {
+            "closure": True,
+            "parent": parent["id"],
+            "complexity": self.complexity(node),
+        }
+        extras.update(self.extract_closure_extras(node, source_code))
+
+        return {
+            "id": self.generate_id(file_path, name, node.start_point[0]),
+            "file": file_path,
+            "name": name,
+            "kind": "function",
+            "start_line": node.start_point[0] + 1,
+            "end_line": node.end_point[0] + 1,
+            "start_byte": node.start_byte,
+            "end_byte": node.end_byte,
+            "container": parent["name"],
+            "signature": self.extract_signature(node, source_code),
+            "language": self.language_name,
+            "is_exported": 0,
+            "extras": extras,
+        }
+
+    def extract_closure_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """提取闭包的附加信息（如捕获的变量），子类可覆盖"""
+        return {}
+
+    def parse_file_symbols(self, file_path: str) -> Dict[str, Any]:
+        """
+        只解析一次文件，同时提取函数定义和未解析的调用引用
+
+        调用引用只依赖本文件的函数（用于确定调用者），可以按文件缓存；
+        被调用者的解析依赖全局符号表，由 resolve_call_refs 完成。
+        """
+        root, source_code = self.read_and_parse(file_path)
+        if not root:
+            return {"functions": [], "call_refs": []}
+        functions = self.collect_functions(file_path, root, source_code)
+        call_refs = self.collect_call_refs(file_path, root, source_code, functions)
+        result = {"functions": functions, "call_refs": call_refs}
+        result.update(self.collect_module_items(root, source_code))
+        directives = collect_directives(
+            self.collect_comments(root, source_code), functions
+        )
+        if directives:
+            result["directives"] = directives
+        return result
+
+    def collect_comments(
+        self, root: Node, source_code: bytes
+    ) -> List[Tuple[str, int, int, bool]]:
+        """
+        包含注释指令标记（callgraph:）的注释：(文本, 起始行, 结束行,
+        是否在同一行的代码之后)
+        """
+        if b"callgraph:" not in source_code:
+            return []
+        comments = []
+        stack = [root]
+        while stack:
+            node = stack.pop()
+            if "comment" not in node.type:
+                stack.extend(node.children)
+                continue
+            text = self.get_node_text(node, source_code)
+            if "callgraph:" not in text:
+                continue
+            line_start = source_code.rfind(b"\n", 0, node.start_byte) + 1
+            trailing = bool(source_code[line_start : node.start_byte].strip())
+            comments.append(
+                (text, node.start_point[0] + 1, node.end_point[0] + 1, trailing)
+            )
+        comments.sort(key=lambda comment: comment[1])
+        return comments
+
+    def collect_module_items(self, root: Node, source_code: bytes) -> Dict[str, Any]:
+        """文件级的模块项（如 Rust 的 mod 声明和 pub use），合并到解析结果中"""
+        return {}
+
+    def extract_call_refs(
         self, file_path: str, functions: List[Dict[str, Any]]
     ) -> List[Dict[str, Any]]:
-        """提取函数调用关系"""
-        root = self.parse_file(file_path)
+        """提取文件中未解析的调用引用"""
+        root, source_code = self.read_and_parse(file_path)
         if not root:
             return []
+        return self.collect_call_refs(file_path, root, source_code, functions)
 
-        with open(file_path, "rb") as f:
-            source_code = f.read()
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        """
+        从语法树中收集调用引用
 
-        # 构建函数位置映射
-        func_map = {}
-        for func in functions:
-            if func["file"] == file_path:
-                func_map[(func["start_line"], func["end_line"])] = func
+        每个引用包含调用者、被调用名称、调用位置以及解析提示（hints），
+        尚未确定被调用函数的 ID。闭包定义处额外生成一条从所在函数指向
+        闭包的引用（edge_kind 为 closure）。
+        """
+        # 本文件的函数按范围从小到大排序，查找时优先命中最内层的闭包/嵌套函数
+        local_functions = sorted(
+            (func for func in functions if func["file"] == file_path),
+            key=lambda func: func["end_byte"] - func["start_byte"],
+        )
+        closures = {
+            func["start_byte"]: func
+            for func in local_functions
+            if (func.get("extras") or {}).get("closure")
+        }
+        by_id = {func["id"]: func for func in local_functions}
 
-        calls = []
+        refs = []
 
-        def find_containing_function(line: int) -> Optional[Dict[str, Any]]:
-            """查找包含给定行的函数"""
-            for (start, end), func in func_map.items():
-                if start <= line <= end:
+        def find_containing_function(position: int) -> Optional[Dict[str, Any]]:
+            """查找包含给定字节位置的最内层函数"""
+            for func in local_functions:
+                if func["start_byte"] <= position < func["end_byte"]:
                     return func
             return None
 
         def visit_node(node: Node):
+            closure = closures.get(node.start_byte)
+            if (
+                closure
+                and node.type in self.config.get("closure_types", [])
+                and closure["extras"]["parent"] in by_id
+            ):
+                parent = by_id[closure["extras"]["parent"]]
+                hints = {"target": {"id": closure["id"], "edge_kind": "closure"}}
+                if self.spawned_argument(node, source_code):
+                    hints["spawn"] = True
+                refs.append(
+                    {
+                        "caller_id": parent["id"],
+                        "caller_name": parent["name"],
+                        "caller_file": file_path,
+                        "call_name": closure["name"],
+                        "call_site_line": node.start_point[0] + 1,
+                        "call_site_column": node.start_point[1],
+                        "call_site_snippet": self.call_snippet(node, source_code),
+                        "language": self.language_name,
+                        "hints": hints,
+                    }
+                )
+
+            if self.is_function_value(node):
+                ref = self.function_value_ref(node, source_code)
+                if ref:
+                    caller = find_containing_function(node.start_byte)
+                    ref.update(
+                        {
+                            "caller_id": caller["id"] if caller else None,
+                            "caller_name": caller["name"] if caller else None,
+                            "caller_file": file_path,
+                            "call_site_snippet": self.call_snippet(
+                                self.enclosing_call(node, source_code) or node,
+                                source_code,
+                            ),
+                        }
+                    )
+                    refs.append(ref)
+
             if node.type in self.config["call_types"]:
                 call_name = self.extract_call_name(node, source_code)
                 if call_name:
                     line = node.start_point[0] + 1
-                    caller = find_containing_function(line)
+                    caller = find_containing_function(node.start_byte)
 
                     if caller:
-                        # 尝试匹配被调用的函数
-                        callee_id = None
-                        for func in functions:
-                            if func["name"] == call_name:
-                                callee_id = func["id"]
-                                break
-
-                        if not callee_id:
-                            # 如果找不到定义，创建一个临时ID
-                            callee_id = self.generate_id("external", call_name, 0)
-
-                        calls.append(
+                        hints = self.call_hints(node, source_code)
+                        if (
+                            node.parent is not None
+                            and node.parent.type in self.SPAWN_STATEMENTS
+                        ):
+                            hints["spawn"] = True
+                        refs.append(
                             {
                                 "caller_id": caller["id"],
-                                "callee_id": callee_id,
                                 "caller_name": caller["name"],
-                                "callee_name": call_name,
                                 "caller_file": file_path,
-                                "callee_file": None,
+                                "call_name": call_name,
                                 "call_site_line": line,
                                 "call_site_column": node.start_point[1],
+                                "call_site_snippet": self.call_snippet(
+                                    node, source_code
+                                ),
                                 "language": self.language_name,
+                                "hints": hints,
                             }
                         )
 
@@ -247,18 +1036,144 @@ class LanguageParser:
                 visit_node(child)
 
         visit_node(root)
-        return calls
+        return refs
 
     def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
         """提取被调用函数的名称（需要子类实现）"""
         raise NotImplementedError
 
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """提取调用点的解析提示（如动态分发的候选 trait），子类可覆盖"""
+        return {}
+
+    def is_function_value(self, node: Node) -> bool:
+        """节点是否处于函数值的位置（见 FUNCTION_VALUE_CONTEXTS）"""
+        if node.type not in self.FUNCTION_VALUE_TYPES or node.parent is None:
+            return False
+        parent = node.parent
+        if parent.type not in self.FUNCTION_VALUE_CONTEXTS:
+            return False
+        field = self.FUNCTION_VALUE_CONTEXTS[parent.type]
+        if field is None:
+            return True
+        child = parent.child_by_field_name(field)
+        return (
+            child is not None
+            and child.start_byte == node.start_byte
+            and child.end_byte == node.end_byte
+        )
+
+    def function_value_ref(
+        self, node: Node, source_code: bytes
+    ) -> Optional[Dict[str, Any]]:
+        """
+        函数值引用（hints.value）：被引用的名称、位置和所在调用的信息，
+        名称是局部变量或参数时不是函数，返回 None
+
+        作为调用参数时，hints.passed_to 记录接收它的调用（名称和解析提示），
+        用于判断函数值是否交给了项目外的代码回调；传给 SPAWN_CALLS 中的调用时
+        标记 hints.spawn
+        """
+        name = self.function_value_name(node, source_code)
+        if not name or name in self.local_names(node, source_code):
+            return None
+        hints = {"value": True, **self.function_value_hints(node, source_code)}
+        call = self.enclosing_call(node, source_code)
+        if call is not None:
+            call_name = self.extract_call_name(call, source_code)
+            if call_name:
+                hints["passed_to"] = {
+                    "call_name": call_name,
+                    "hints": self.call_hints(call, source_code),
+                }
+                if self.is_spawn_call(call_name):
+                    hints["spawn"] = True
+        return {
+            "call_name": name,
+            "call_site_line": node.start_point[0] + 1,
+            "call_site_column": node.start_point[1],
+            "language": self.language_name,
+            "hints": hints,
+        }
+
+    def enclosing_call(self, node: Node, source_code: bytes) -> Optional[Node]:
+        """函数值作为参数（可能带 &、关键字）时所在的调用节点"""
+        parent = node.parent
+        for _ in range(3):
+            if parent is None:
+                return None
+            if parent.type in self.config["call_types"]:
+                return parent
+            parent = parent.parent
+        return None
+
+    def is_spawn_call(self, call_name: str) -> bool:
+        """调用名（或路径、方法名的最后一段）是否在 SPAWN_CALLS 中"""
+        name = re.split(r"::|\.|->", call_name)[-1]
+        return name in self.SPAWN_CALLS
+
+    def spawned_argument(self, node: Node, source_code: bytes) -> bool:
+        """节点（闭包等）是否作为参数传给了 SPAWN_CALLS 中的调用"""
+        if not self.SPAWN_CALLS:
+            return False
+        call = self.enclosing_call(node, source_code)
+        if call is None:
+            return False
+        call_name = self.extract_call_name(call, source_code)
+        return bool(call_name) and self.is_spawn_call(call_name)
+
+    def function_value_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        """函数值引用的函数名，子类可覆盖（如取路径的最后一段）"""
+        return self.get_node_text(node, source_code)
+
+    def function_value_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """函数值引用的解析提示（如限定路径），子类可覆盖"""
+        return {}
+
+    def local_names(self, node: Node, source_code: bytes) -> Set[str]:
+        """节点所在函数中的参数和局部变量名，子类可覆盖"""
+        return set()
+
 
 class PythonParser(LanguageParser):
     """Python语言解析器"""
 
+    # 模块顶层代码（不在任何函数中的语句）对应的伪函数名
+    MODULE_BODY = "<module>"
+
+    # 函数名作为值：map(f, xs)、Thread(target=f)、cb = f、[f, g]、{"k": f}
+    FUNCTION_VALUE_CONTEXTS = {
+        "argument_list": None,
+        "keyword_argument": "value",
+        "assignment": "right",
+        "list": None,
+        "tuple": None,
+        "pair": "value",
+        "return_statement": None,
+    }
+    # threading.Thread(target=f)、Timer(1, f)、executor.submit(f)、
+    # loop.run_in_executor(None, f)、asyncio.to_thread(f)
+    SPAWN_CALLS = (
+        "Thread",
+        "Process",
+        "Timer",
+        "submit",
+        "apply_async",
+        "start_new_thread",
+        "run_in_executor",
+        "to_thread",
+    )
+
     def __init__(self):
         super().__init__("python")
+        self._imports: Dict[str, Any] = {"modules": {}, "names": {}}
+        self._type_cache: Dict[int, Dict[str, str]] = {}
+        # 函数内参数和局部变量名缓存（以函数起始字节为键），每个文件重置
+        self._locals_cache: Dict[int, Set[str]] = {}
+        self._index: Dict[str, List[Dict[str, Any]]] = {}
+        self._classes: Set[str] = set()
+        self._bases: Dict[str, List[str]] = {}
+        self._index_key = None
 
     def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
         for child in node.children:
@@ -280,157 +1195,3674 @@ class PythonParser(LanguageParser):
                         return self.get_node_text(subchild, source_code)
         return None
 
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        方法额外记录所在类的基类，用于沿继承链解析方法调用；
+        没有默认值和 *args/**kwargs 时记录参数个数
+        """
+        extras = super().extract_extras(node, source_code)
+        class_node = self._enclosing_class(node)
+        if extras.get("owner") and class_node:
+            bases = self._class_bases(class_node, source_code)
+            if bases:
+                extras["bases"] = bases
+        parameters = node.child_by_field_name("parameters")
+        if parameters is not None and all(
+            param.type in ("identifier", "typed_parameter")
+            for param in parameters.named_children
+        ):
+            extras["arity"] = len(parameters.named_children)
+        return extras
 
-class CParser(LanguageParser):
-    """C语言解析器"""
+    def extract_doc(self, node: Node, source_code: bytes) -> Optional[str]:
+        """docstring（函数体中第一条语句为字符串时）的第一行非空文本"""
+        body = node.child_by_field_name("body")
+        if body is None or not body.named_children:
+            return None
+        statement = body.named_children[0]
+        if statement.type != "expression_statement" or not statement.named_children:
+            return None
+        string = statement.named_children[0]
+        if string.type != "string":
+            return None
+        text = self.get_node_text(string, source_code).lstrip("rRuUbB")
+        quote = text[:3] if text[:3] in ('"""', "'''") else text[:1]
+        text = text[len(quote) :]
+        if text.endswith(quote):
+            text = text[: -len(quote)]
+        for line in text.splitlines():
+            if line.strip():
+                return line.strip()[:DOC_SUMMARY_LENGTH]
+        return None
 
-    def __init__(self):
-        super().__init__("c")
+    def extract_attributes(self, node: Node, source_code: bytes) -> List[str]:
+        """装饰器（@pytest.fixture 等）位于外层的 decorated_definition 中"""
+        parent = node.parent
+        if not parent or parent.type != "decorated_definition":
+            return []
+        return [
+            self.get_node_text(child, source_code).lstrip("@").strip()
+            for child in parent.children
+            if child.type == "decorator"
+        ]
 
-    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        declarator = node.child_by_field_name("declarator")
-        if declarator:
-            if declarator.type == "function_declarator":
-                name_node = declarator.child_by_field_name("declarator")
-                if name_node:
-                    return self.get_node_text(name_node, source_code)
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """按约定，单下划线开头（非 __dunder__）的函数为私有"""
+        if func_name.startswith("_") and not (
+            func_name.startswith("__") and func_name.endswith("__")
+        ):
+            return "private"
+        return "public"
+
+    def collect_functions(
+        self, file_path: str, root: Node, source_code: bytes
+    ) -> List[Dict[str, Any]]:
+        """
+        模块顶层有调用时（如 if __name__ == "__main__": main()），
+        额外生成一个覆盖整个文件的 <module> 伪函数作为这些调用的调用者
+        """
+        functions = super().collect_functions(file_path, root, source_code)
+        if self._has_top_level_call(root):
+            functions.append(
+                {
+                    "id": self.generate_id(file_path, self.MODULE_BODY, 0),
+                    "file": file_path,
+                    "name": self.MODULE_BODY,
+                    "kind": "function",
+                    "start_line": 1,
+                    "end_line": root.end_point[0] + 1,
+                    "start_byte": root.start_byte,
+                    "end_byte": root.end_byte,
+                    "container": None,
+                    "signature": "",
+                    "language": self.language_name,
+                    "is_exported": 1,
+                    "extras": {"module_body": True, "visibility": "public"},
+                }
+            )
+        return functions
+
+    def _has_top_level_call(self, root: Node) -> bool:
+        """是否存在不在任何函数中的调用"""
+        stack = [root]
+        while stack:
+            node = stack.pop()
+            if node.type in self.config["function_types"]:
+                continue
+            if node.type in self.config["call_types"]:
+                return True
+            stack.extend(node.children)
+        return False
+
+    def _enclosing_class(self, node: Node) -> Optional[Node]:
+        """方法所在的类定义（嵌套在函数中的函数不算方法）"""
+        parent = node.parent
+        while parent:
+            if parent.type in self.config["class_types"]:
+                return parent
+            if parent.type in self.config["function_types"]:
+                return None
+            parent = parent.parent
         return None
 
-    def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        function_node = node.child_by_field_name("function")
-        if function_node:
-            return self.get_node_text(function_node, source_code)
+    def _enclosing_function(self, node: Node) -> Optional[Node]:
+        parent = node.parent
+        while parent:
+            if parent.type in self.config["function_types"]:
+                return parent
+            parent = parent.parent
         return None
 
+    def _class_bases(self, class_node: Node, source_code: bytes) -> List[str]:
+        """类的基类名（只取最后一段：module.Base -> Base）"""
+        superclasses = class_node.child_by_field_name("superclasses")
+        if not superclasses:
+            return []
+        bases = []
+        for child in superclasses.named_children:
+            if child.type in ("identifier", "attribute"):
+                bases.append(self.get_node_text(child, source_code).split(".")[-1])
+        return bases
 
-class CppParser(LanguageParser):
-    """C++语言解析器"""
+    def _type_name(self, text: str) -> Optional[str]:
+        """
+        从类型注解中取出类名：Foo、mod.Foo、"Foo"、Optional[Foo]、Foo | None；
+        容器类型（List[Foo] 等）不推断
+        """
+        text = text.strip().strip("'\"")
+        if text.startswith("Optional[") and text.endswith("]"):
+            text = text[len("Optional[") : -1].strip()
+        if "|" in text:
+            parts = [part.strip() for part in text.split("|")]
+            parts = [part for part in parts if part != "None"]
+            if len(parts) != 1:
+                return None
+            text = parts[0]
+        name = text.split(".")[-1]
+        return name if name.isidentifier() else None
 
-    def __init__(self):
-        super().__init__("cpp")
+    def _constructed_type(
+        self, node: Optional[Node], source_code: bytes
+    ) -> Optional[str]:
+        """右值为 Foo(...) 或 mod.Foo(...)（首字母大写，按约定为类）时返回类名"""
+        if not node or node.type != "call":
+            return None
+        function_node = node.child_by_field_name("function")
+        if not function_node or function_node.type not in ("identifier", "attribute"):
+            return None
+        name = self.get_node_text(function_node, source_code).split(".")[-1]
+        return name if name[:1].isupper() else None
 
-    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        declarator = node.child_by_field_name("declarator")
-        if declarator:
-            # 处理各种声明形式
-            if declarator.type == "function_declarator":
-                name_node = declarator.child_by_field_name("declarator")
-                if name_node:
-                    if name_node.type == "qualified_identifier":
-                        return self.get_node_text(name_node, source_code)
-                    else:
-                        return self.get_node_text(name_node, source_code)
-        return None
+    def _assigned_types(
+        self, scope: Node, source_code: bytes, target_prefix: str = ""
+    ) -> Dict[str, str]:
+        """
+        作用域内（不进入嵌套函数和类）赋值语句推断的变量类型：
+        x = Foo()、x: Foo = ...；target_prefix 为 self. 时收集实例属性
+        """
+        types: Dict[str, str] = {}
+        stack = list(scope.children)
+        while stack:
+            node = stack.pop()
+            if node.type in self.config["function_types"] or node.type in (
+                "class_definition",
+                "lambda",
+            ):
+                continue
+            if node.type == "assignment":
+                left = node.child_by_field_name("left")
+                type_node = node.child_by_field_name("type")
+                if left:
+                    name = self.get_node_text(left, source_code)
+                    if target_prefix and name.startswith(target_prefix):
+                        name = name[len(target_prefix) :]
+                    elif target_prefix or left.type != "identifier":
+                        name = None
+                    if name and name.isidentifier():
+                        inferred = (
+                            self._type_name(self.get_node_text(type_node, source_code))
+                            if type_node
+                            else self._constructed_type(
+                                node.child_by_field_name("right"), source_code
+                            )
+                        )
+                        if inferred:
+                            types[name] = inferred
+            stack.extend(node.children)
+        return types
 
-    def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        function_node = node.child_by_field_name("function")
-        if function_node:
-            if function_node.type == "field_expression":
-                field = function_node.child_by_field_name("field")
-                if field:
-                    return self.get_node_text(field, source_code)
-            return self.get_node_text(function_node, source_code)
-        return None
+    def _local_types(self, func_node: Node, source_code: bytes) -> Dict[str, str]:
+        """函数中带类型注解的参数和赋值推断出的局部变量类型（按函数缓存）"""
+        cached = self._type_cache.get(func_node.start_byte)
+        if cached is not None:
+            return cached
 
+        types: Dict[str, str] = {}
+        parameters = func_node.child_by_field_name("parameters")
+        for param in parameters.named_children if parameters else []:
+            if param.type not in ("typed_parameter", "typed_default_parameter"):
+                continue
+            name_node = param.child_by_field_name("name")
+            if not name_node:
+                name_node = next(
+                    (c for c in param.named_children if c.type == "identifier"), None
+                )
+            type_node = param.child_by_field_name("type")
+            if name_node and type_node:
+                inferred = self._type_name(self.get_node_text(type_node, source_code))
+                if inferred:
+                    types[self.get_node_text(name_node, source_code)] = inferred
 
-class JavaParser(LanguageParser):
-    """Java语言解析器"""
+        body = func_node.child_by_field_name("body")
+        if body:
+            types.update(self._assigned_types(body, source_code))
+        self._type_cache[func_node.start_byte] = types
+        return types
 
-    def __init__(self):
-        super().__init__("java")
+    def _attribute_types(self, class_node: Node, source_code: bytes) -> Dict[str, str]:
+        """类中 self.x = Foo() 赋值和类级注解 x: Foo 推断的实例属性类型"""
+        cached = self._type_cache.get(class_node.start_byte)
+        if cached is not None:
+            return cached
 
-    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        name_node = node.child_by_field_name("name")
-        if name_node:
-            return self.get_node_text(name_node, source_code)
+        types: Dict[str, str] = {}
+        body = class_node.child_by_field_name("body")
+        for child in body.named_children if body else []:
+            method = child
+            if method.type == "decorated_definition":
+                method = method.child_by_field_name("definition") or method
+            if method.type in self.config["function_types"]:
+                method_body = method.child_by_field_name("body")
+                if method_body:
+                    types.update(
+                        self._assigned_types(method_body, source_code, "self.")
+                    )
+            elif child.type == "expression_statement":
+                types.update(self._assigned_types(child, source_code))
+        self._type_cache[class_node.start_byte] = types
+        return types
+
+    def _receiver_type(self, call_node: Node, receiver: str, source_code: bytes):
+        """推断 receiver.method() 中接收者的类名"""
+        func_node = self._enclosing_function(call_node)
+        class_node = self._enclosing_class(func_node) if func_node else None
+        class_name = None
+        if class_node:
+            name_node = class_node.child_by_field_name("name")
+            class_name = name_node and self.get_node_text(name_node, source_code)
+
+        if receiver in ("self", "cls") and class_name:
+            return class_name
+        if receiver.startswith("self.") and class_node:
+            return self._attribute_types(class_node, source_code).get(receiver[5:])
+        if func_node:
+            inferred = self._local_types(func_node, source_code).get(receiver)
+            if inferred:
+                return inferred
+        # Foo.create() 形式的类方法 / 静态方法调用
+        if receiver.isidentifier() and receiver[:1].isupper():
+            return receiver
         return None
 
-    def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        name_node = node.child_by_field_name("name")
-        if name_node:
-            return self.get_node_text(name_node, source_code)
+    def _python_imports(self, root: Node, source_code: bytes, file_path: str):
+        """
+        收集 import 语句
 
-        # 处理方法链调用
-        object_node = node.child_by_field_name("object")
-        if object_node and object_node.type == "method_invocation":
-            return self.extract_call_name(object_node, source_code)
+        Returns:
+            {"modules": {本地名称: [模块路径]}, "names": {本地名称: (模块路径, 原名)}}；
+            模块路径为去掉扩展名的文件路径后缀（a/b），相对导入为绝对路径
+        """
+        modules: Dict[str, List[str]] = {}
+        names: Dict[str, Any] = {}
 
-        return None
+        def module_path(node: Node) -> str:
+            text = self.get_node_text(node, source_code)
+            if node.type != "relative_import":
+                return text.replace(".", "/")
+            level = len(text) - len(text.lstrip("."))
+            base = Path(file_path).parent
+            for _ in range(level - 1):
+                base = base.parent
+            rest = text.lstrip(".")
+            return str(base / rest.replace(".", "/")) if rest else str(base)
 
+        stack = [root]
+        while stack:
+            node = stack.pop()
+            if node.type == "import_statement":
+                for child in node.named_children:
+                    if child.type == "dotted_name":
+                        # import a.b.c 绑定的名称是 a
+                        text = self.get_node_text(child, source_code)
+                        top = text.split(".")[0]
+                        modules[top] = [top]
+                    elif child.type == "aliased_import":
+                        name_node = child.child_by_field_name("name")
+                        alias_node = child.child_by_field_name("alias")
+                        if name_node and alias_node:
+                            alias = self.get_node_text(alias_node, source_code)
+                            modules[alias] = [module_path(name_node)]
+                continue
+            if node.type == "import_from_statement":
+                module_node = node.child_by_field_name("module_name")
+                if not module_node:
+                    continue
+                base = module_path(module_node)
+                for child in node.named_children:
+                    if child is module_node:
+                        continue
+                    name_node, alias_node = child, None
+                    if child.type == "aliased_import":
+                        name_node = child.child_by_field_name("name")
+                        alias_node = child.child_by_field_name("alias")
+                    if not name_node or name_node.type != "dotted_name":
+                        continue
+                    original = self.get_node_text(name_node, source_code)
+                    local = (
+                        self.get_node_text(alias_node, source_code)
+                        if alias_node
+                        else original
+                    )
+                    # from a import b：b 可能是函数/类，也可能是子模块
+                    names[local] = (base, original)
+                    modules[local] = [f"{base}/{original.replace('.', '/')}"]
+                continue
+            stack.extend(node.children)
+        return {"modules": modules, "names": names}
 
-class RustParser(LanguageParser):
-    """Rust语言解析器"""
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        self._type_cache = {}
+        self._locals_cache = {}
+        self._imports = self._python_imports(root, source_code, file_path)
+        return super().collect_call_refs(file_path, root, source_code, functions)
 
-    def __init__(self):
-        super().__init__("rust")
+    def local_names(self, node: Node, source_code: bytes) -> Set[str]:
+        func_node = self._enclosing_function(node)
+        if func_node is None:
+            return set()
+        cached = self._locals_cache.get(func_node.start_byte)
+        if cached is not None:
+            return cached
 
-    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        name_node = node.child_by_field_name("name")
-        if name_node:
-            return self.get_node_text(name_node, source_code)
-        return None
+        names = set()
+        parameters = func_node.child_by_field_name("parameters")
+        for param in parameters.named_children if parameters else []:
+            name_node = param
+            if param.type != "identifier":
+                name_node = param.child_by_field_name("name") or next(
+                    (c for c in param.named_children if c.type == "identifier"), None
+                )
+            if name_node is not None:
+                names.add(self.get_node_text(name_node, source_code))
+        stack = [func_node.child_by_field_name("body")]
+        while stack:
+            current = stack.pop()
+            if current is None:
+                continue
+            if current.type in self.config["function_types"] or current.type in (
+                "class_definition",
+                "lambda",
+            ):
+                continue
+            if current.type in ("assignment", "for_statement"):
+                left = current.child_by_field_name("left")
+                if left is not None and left.type == "identifier":
+                    names.add(self.get_node_text(left, source_code))
+            stack.extend(current.children)
 
-    def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        self._locals_cache[func_node.start_byte] = names
+        return names
+
+    def is_function_value(self, node: Node) -> bool:
+        """类定义的基类列表也是 argument_list，其中的名称不是函数值"""
+        if not super().is_function_value(node):
+            return False
+        parent = node.parent
+        return parent.type != "argument_list" or (
+            parent.parent is not None and parent.parent.type == "call"
+        )
+
+    def function_value_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """from mod import f 导入的函数作为值"""
+        imported = self._imports["names"].get(self.get_node_text(node, source_code))
+        if imported:
+            return {"modules": [imported[0]], "imported_name": imported[1]}
+        return {}
+
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        记录接收者、推断出的接收者类型（receiver_type）和导入的模块路径（modules）
+        """
+        hints: Dict[str, Any] = {}
         function_node = node.child_by_field_name("function")
-        if function_node:
-            if function_node.type == "field_expression":
-                field = function_node.child_by_field_name("field")
-                if field:
-                    return self.get_node_text(field, source_code)
-            return self.get_node_text(function_node, source_code)
-        return None
+        if not function_node:
+            return hints
 
+        if function_node.type == "attribute":
+            object_node = function_node.child_by_field_name("object")
+            if not object_node:
+                return hints
+            receiver = self.get_node_text(object_node, source_code)
+            hints["receiver"] = receiver
+            # 接收者以导入的模块名开头：mod.f()、pkg.sub.f()
+            first, _, rest = receiver.partition(".")
+            modules = self._imports["modules"].get(first)
+            if modules:
+                suffix = rest.replace(".", "/")
+                hints["modules"] = [
+                    f"{module}/{suffix}" if suffix else module for module in modules
+                ]
+            else:
+                receiver_type = self._receiver_type(node, receiver, source_code)
+                if receiver_type:
+                    hints["receiver_type"] = receiver_type
+        elif function_node.type == "identifier":
+            hints.update(self.function_value_hints(function_node, source_code))
+            # 调用作为参数传入、或赋值给局部变量的函数：callback(x)
+            if self.get_node_text(function_node, source_code) in self.local_names(
+                function_node, source_code
+            ):
+                hints["indirect"] = True
+                arguments = node.child_by_field_name("arguments")
+                if arguments is not None and arguments.type == "argument_list" and all(
+                    child.type not in ("list_splat", "dictionary_splat")
+                    for child in arguments.named_children
+                ):
+                    hints["arity"] = len(arguments.named_children)
+        return hints
 
-class JavaScriptParser(LanguageParser):
-    """JavaScript语言解析器"""
+    @staticmethod
+    def _module_matches(file_path: str, module: str) -> bool:
+        """文件是否为给定模块路径（a/b 对应 a/b.py 或 a/b/__init__.py）"""
+        key = str(Path(file_path).with_suffix("")).replace("\\", "/")
+        if key.endswith("/__init__"):
+            key = key[: -len("/__init__")]
+        module = module.replace("\\", "/")
+        return key == module or key.endswith(f"/{module}")
 
-    def __init__(self):
-        super().__init__("javascript")
+    def _python_index(self, functions: List[Dict[str, Any]]):
+        """
+        Python 函数按名称建立索引，同时记录项目中的类及其基类
+        （按 functions 列表缓存）
+        """
+        key = (id(functions), len(functions))
+        if self._index_key != key:
+            self._index = {}
+            self._classes = set()
+            self._bases = {}
+            for func in functions:
+                if func.get("language") != "python":
+                    continue
+                self._index.setdefault(func["name"], []).append(func)
+                extras = func.get("extras") or {}
+                if extras.get("owner"):
+                    self._classes.add(extras["owner"])
+                if extras.get("owner") and extras.get("bases"):
+                    self._bases.setdefault(extras["owner"], extras["bases"])
+            self._index_key = key
+        return self._index
 
-    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
-        name_node = node.child_by_field_name("name")
-        if name_node:
-            return self.get_node_text(name_node, source_code)
+    def _find_method(
+        self,
+        index: Dict[str, List[Dict[str, Any]]],
+        class_name: str,
+        method: str,
+        caller_file: str,
+        seen: Optional[Set[str]] = None,
+    ) -> Optional[Dict[str, Any]]:
+        """在类及其基类中查找方法（同名类优先取调用者所在文件中的）"""
+        seen = seen or set()
+        if class_name in seen:
+            return None
+        seen.add(class_name)
 
-        # 匿名函数或箭头函数
-        if node.type in ["arrow_function", "function_expression"]:
-            parent = node.parent
-            if parent and parent.type == "variable_declarator":
-                name_node = parent.child_by_field_name("name")
-                if name_node:
-                    return self.get_node_text(name_node, source_code)
+        methods = [
+            func
+            for func in index.get(method, [])
+            if (func.get("extras") or {}).get("owner") == class_name
+        ]
+        if methods:
+            return next(
+                (func for func in methods if func["file"] == caller_file), methods[0]
+            )
 
+        for base in self._bases.get(class_name, []):
+            found = self._find_method(index, base, method, caller_file, seen)
+            if found:
+                return found
         return None
 
+    def resolve_callees(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        按以下顺序解析 Python 调用（只在 Python 函数中查找）：
+
+        1. 推断出接收者类型时，在该类及其基类中查找方法
+        2. 导入的模块中的函数（import mod / from mod import f）或类的 __init__；
+           模块不在项目中时视为外部函数
+        3. 普通调用 f()：同文件的函数、其他文件的函数，最后是类 f 的 __init__
+        4. 接收者类型未知（或不是项目中的类）的 obj.m()：任意同名函数（优先方法）
+
+        3 中其他文件的函数（没有导入）和 4 是按名称猜测的目标（置信度 heuristic）
+        """
+        hints = ref.get("hints") or {}
+        if hints.get("target"):
+            return super().resolve_callees(ref, functions)
+
+        index = self._python_index(functions)
+        call_name = ref["call_name"]
+        name = hints.get("imported_name", call_name)
+        caller_file = ref["caller_file"]
+        candidates = index.get(name, [])
+
+        def is_method(func: Dict[str, Any]) -> bool:
+            return bool((func.get("extras") or {}).get("owner"))
+
+        target = None
+        confidence = "certain"
+        if hints.get("receiver_type"):
+            target = self._find_method(
+                index, hints["receiver_type"], name, caller_file
+            )
+        if not target and hints.get("modules"):
+            target = next(
+                (
+                    func
+                    for func in candidates
+                    if not is_method(func)
+                    and any(
+                        self._module_matches(func["file"], module)
+                        for module in hints["modules"]
+                    )
+                ),
+                None,
+            )
+            if not target:
+                # from mod import Foo; Foo() 调用构造函数
+                target = self._find_method(index, name, "__init__", caller_file)
+        # 接收者是项目中的类但找不到该方法（如继承自外部基类）时不再按名称猜测
+        known_class = hints.get("receiver_type") in self._classes
+        imported = bool(hints.get("modules")) or known_class
+        if not target and not imported and "receiver" not in hints:
+            free = [func for func in candidates if not is_method(func)]
+            target = next(
+                (func for func in free if func["file"] == caller_file),
+                free[0] if free else None,
+            )
+            if not target and name[:1].isupper():
+                target = self._find_method(index, name, "__init__", caller_file)
+            if target and target["file"] != caller_file:
+                confidence = "heuristic"
+        if not target and not imported and "receiver" in hints:
+            methods = [func for func in candidates if is_method(func)]
+            target = (methods or candidates or [None])[0]
+            confidence = "heuristic"
+
+        if not target:
+            return [
+                {
+                    "callee_id": self.generate_id("external", call_name, 0),
+                    "callee_name": call_name,
+                    "edge_kind": "static",
+                }
+            ]
+        return [
+            {
+                "callee_id": target["id"],
+                "callee_name": call_name,
+                "callee_file": target["file"],
+                "edge_kind": "static",
+                "confidence": confidence,
+            }
+        ]
+
+
+# C/C++ 中包裹函数声明符的声明符（返回指针/引用的函数、带属性的声明等）
+C_WRAPPER_DECLARATORS = (
+    "pointer_declarator",
+    "reference_declarator",
+    "parenthesized_declarator",
+    "attributed_declarator",
+)
+
+# 参与 C/C++ 调用解析的语言
+C_FAMILY_LANGUAGES = ("c", "cpp")
+
+
+def _is_static_function(node: Node, source_code: bytes) -> bool:
+    """C/C++ 函数是否带 static 存储类（仅在本编译单元可见）"""
+    for child in node.children:
+        if child.type == "storage_class_specifier":
+            text = source_code[child.start_byte : child.end_byte]
+            if text.strip() == b"static":
+                return True
+    return False
+
+
+def _unwrap_declarator(declarator: Optional[Node]) -> Optional[Node]:
+    """去掉指针/引用/括号等外层声明符（int *f(void) 中的 *，Foo &r 中的 &）"""
+    while declarator is not None and declarator.type in C_WRAPPER_DECLARATORS:
+        inner = declarator.child_by_field_name("declarator")
+        if inner is None:
+            inner = next(
+                (
+                    child
+                    for child in declarator.named_children
+                    if child.type.endswith("declarator") or child.type == "identifier"
+                ),
+                None,
+            )
+        declarator = inner
+    return declarator
+
+
+def _declared_name(declarator: Optional[Node]) -> Optional[Node]:
+    """
+    声明符中声明的名称：int *p、int a[4]、int x = 1、
+    函数指针 void (*cb)(int) 中的 cb
+    """
+    while declarator is not None and declarator.type != "identifier":
+        if declarator.type in C_WRAPPER_DECLARATORS:
+            declarator = _unwrap_declarator(declarator)
+        elif declarator.type in (
+            "init_declarator",
+            "function_declarator",
+            "array_declarator",
+        ):
+            declarator = declarator.child_by_field_name("declarator")
+        else:
+            return None
+    return declarator
+
+
+def _strip_template_args(text: str) -> str:
+    """去掉模板实参：std::vector<int>::push_back -> std::vector::push_back"""
+    result = []
+    depth = 0
+    for ch in text:
+        if ch == "<":
+            depth += 1
+        elif ch == ">" and depth:
+            depth -= 1
+        elif not depth:
+            result.append(ch)
+    return "".join(result).replace(" ", "")
+
+
+class CFamilyParser(LanguageParser):
+    """
+    C/C++ 解析器的公共部分
+
+    函数名取自函数声明符（支持返回指针、引用的函数）；调用只在 C/C++ 函数中
+    解析，static 函数只在所在文件中可见。通过参数、局部变量中的函数指针
+    进行的调用（cb(x)、(*fp)(x)）是间接调用
+    """
+
+    # 函数名作为值：qsort(..., cmp)、fp = f、&f、{ .read = f }、return f
+    FUNCTION_VALUE_CONTEXTS = {
+        "argument_list": None,
+        "init_declarator": "value",
+        "assignment_expression": "right",
+        "initializer_list": None,
+        "initializer_pair": "value",
+        "pointer_expression": "argument",
+        "return_statement": None,
+    }
+    # pthread_create(&t, NULL, worker, arg)、thrd_create、CreateThread，
+    # C++ 的 std::thread(f)、std::jthread(f)、std::async(f)
+    SPAWN_CALLS = (
+        "pthread_create",
+        "thrd_create",
+        "CreateThread",
+        "_beginthreadex",
+        "thread",
+        "jthread",
+        "async",
+    )
+
+    def __init__(self, language_name: str):
+        super().__init__(language_name)
+        # 函数内参数和局部变量名缓存（以函数起始字节为键），每个文件重置
+        self._locals_cache: Dict[int, Set[str]] = {}
+        # 按短名称（A::b -> b）索引的 C/C++ 函数表，函数表变化时重建
+        self._index: Dict[str, List[Dict[str, Any]]] = {}
+        self._by_id: Dict[str, Dict[str, Any]] = {}
+        # Rust 中以 #[no_mangle] 导出、可被 C 代码回调的函数
+        self._exports: Dict[str, Dict[str, Any]] = {}
+        self._index_key: Optional[tuple] = None
+
+    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        declarator = _unwrap_declarator(node.child_by_field_name("declarator"))
+        if declarator is not None and declarator.type == "function_declarator":
+            name_node = declarator.child_by_field_name("declarator")
+            if name_node:
+                return self.get_node_text(name_node, source_code)
+        return None
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        return "private" if _is_static_function(node, source_code) else "public"
+
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """记录参数个数（f(void) 为 0；可变参数函数另外标注 varargs）"""
+        extras = super().extract_extras(node, source_code)
+        declarator = _unwrap_declarator(node.child_by_field_name("declarator"))
+        parameters = (
+            declarator.child_by_field_name("parameters")
+            if declarator is not None and declarator.type == "function_declarator"
+            else None
+        )
+        if parameters is not None:
+            params = [
+                child
+                for child in parameters.named_children
+                if child.type.endswith("parameter_declaration")
+            ]
+            # f(void) 表示没有参数
+            if [self.get_node_text(param, source_code) for param in params] == [
+                "void"
+            ]:
+                params = []
+            extras["arity"] = len(params)
+            if any(child.type == "variadic_parameter" for child in parameters.children):
+                extras["varargs"] = True
+        return extras
+
+    def callable_languages(self) -> Tuple[str, ...]:
+        return C_FAMILY_LANGUAGES
+
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        self._locals_cache = {}
+        return super().collect_call_refs(file_path, root, source_code, functions)
+
+    def local_names(self, node: Node, source_code: bytes) -> Set[str]:
+        func_node = node.parent
+        while func_node is not None and func_node.type != "function_definition":
+            func_node = func_node.parent
+        if func_node is None:
+            return set()
+        cached = self._locals_cache.get(func_node.start_byte)
+        if cached is not None:
+            return cached
+
+        names = set()
+        declarator = _unwrap_declarator(func_node.child_by_field_name("declarator"))
+        parameters = (
+            declarator.child_by_field_name("parameters")
+            if declarator is not None and declarator.type == "function_declarator"
+            else None
+        )
+        declarators = []
+        if parameters is not None:
+            for parameter in parameters.named_children:
+                declarators.extend(parameter.children_by_field_name("declarator"))
+        stack = [func_node.child_by_field_name("body")]
+        while stack:
+            current = stack.pop()
+            if current is None:
+                continue
+            if current.type == "declaration":
+                declarators.extend(current.children_by_field_name("declarator"))
+            # lambda 和局部类中的声明不属于本函数
+            if current.type not in ("lambda_expression", "class_specifier"):
+                stack.extend(current.children)
+        for declarator in declarators:
+            name_node = _declared_name(declarator)
+            if name_node is not None:
+                names.add(self.get_node_text(name_node, source_code))
+
+        self._locals_cache[func_node.start_byte] = names
+        return names
+
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """通过参数/局部变量中的函数指针调用：cb(x)、(*fp)(x)、(obj->cb)(x)"""
+        function_node = node.child_by_field_name("function")
+        if function_node is None:
+            return {}
+        if function_node.type == "parenthesized_expression" or (
+            function_node.type == "identifier"
+            and self.get_node_text(function_node, source_code)
+            in self.local_names(function_node, source_code)
+        ):
+            return self._indirect_hints(node)
+        return {}
+
+    @staticmethod
+    def _indirect_hints(node: Node) -> Dict[str, Any]:
+        hints: Dict[str, Any] = {"indirect": True}
+        arguments = node.child_by_field_name("arguments")
+        if arguments is not None:
+            hints["arity"] = sum(
+                child.type != "comment" for child in arguments.named_children
+            )
+        return hints
+
+    def _c_index(self, functions: List[Dict[str, Any]]):
+        """C/C++ 函数按短名称建立索引（按 functions 列表缓存）"""
+        key = (id(functions), len(functions))
+        if self._index_key != key:
+            self._index = {}
+            self._by_id = {}
+            self._exports = {}
+            for func in functions:
+                language = func.get("language")
+                if language == "rust":
+                    attributes = (func.get("extras") or {}).get("attributes") or []
+                    if any("no_mangle" in attribute for attribute in attributes):
+                        self._exports.setdefault(func["name"], func)
+                    continue
+                if language not in C_FAMILY_LANGUAGES:
+                    continue
+                self._by_id[func["id"]] = func
+                short_name = func["name"].rsplit("::", 1)[-1]
+                self._index.setdefault(short_name, []).append(func)
+            self._index_key = key
+        return self._index
+
+    def resolve_callees(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        按以下顺序解析 C/C++ 调用（只在 C/C++ 函数中查找）：
+
+        1. 已知所属类型的调用（A::m()、this->m()、推断出类型的 obj.m() / p->m()）
+           以及方法内未限定的 m()：该类型的方法
+        2. 普通调用 f()：本文件的函数，其次是其他文件中非 static 的函数
+        3. 接收者类型未知（或类型中找不到该方法）的 obj.m()：任意同名方法；
+           接收者类型不是项目中的类（如 std::string）时视为外部函数
+        4. Rust 中以 #[no_mangle] 导出的同名函数（C 回调 Rust，边类型为 ffi）
+
+        3 中接收者类型未知时是按名称猜测的目标（置信度 heuristic）
+        """
+        hints = ref.get("hints") or {}
+        if hints.get("target"):
+            return super().resolve_callees(ref, functions)
+
+        index = self._c_index(functions)
+        call_name = ref["call_name"]
+        name = call_name.rsplit("::", 1)[-1]
+        caller_file = ref["caller_file"]
+        candidates = index.get(name, [])
+
+        def owner_of(func: Dict[str, Any]) -> Optional[str]:
+            owner = (func.get("extras") or {}).get("owner")
+            return owner.rsplit("::", 1)[-1] if owner else None
+
+        def prefer_local(funcs: List[Dict[str, Any]]) -> Optional[Dict[str, Any]]:
+            return next(
+                (func for func in funcs if func["file"] == caller_file),
+                funcs[0] if funcs else None,
+            )
+
+        def methods_of(owner: str) -> List[Dict[str, Any]]:
+            return [func for func in candidates if owner_of(func) == owner]
+
+        caller = self._by_id.get(ref["caller_id"])
+        caller_owner = owner_of(caller) if caller else None
+        owner = caller_owner if hints.get("this") else hints.get("owner")
+
+        target = None
+        confidence = "certain"
+        edge_kind = "static"
+        if owner:
+            target = prefer_local(methods_of(owner))
+        if not target and "receiver" not in hints:
+            if caller_owner and not owner:
+                target = prefer_local(methods_of(caller_owner))
+            if not target:
+                visible = [
+                    func
+                    for func in candidates
+                    if not owner_of(func)
+                    and (func["file"] == caller_file or func.get("is_exported"))
+                ]
+                target = prefer_local(visible)
+            if not target:
+                target = self._exports.get(name)
+                if target:
+                    edge_kind = FFI_EDGE_KIND
+        if not target and "receiver" in hints:
+            known_class = owner and any(
+                owner_of(func) == owner for funcs in index.values() for func in funcs
+            )
+            if not owner or known_class:
+                target = prefer_local([func for func in candidates if owner_of(func)])
+                if not owner:
+                    confidence = "heuristic"
+
+        if not target:
+            return [
+                {
+                    "callee_id": self.generate_id("external", call_name, 0),
+                    "callee_name": call_name,
+                    "edge_kind": "static",
+                }
+            ]
+        return [
+            {
+                "callee_id": target["id"],
+                "callee_name": target["name"],
+                "callee_file": target["file"],
+                "edge_kind": edge_kind,
+                "confidence": confidence,
+            }
+        ]
+
+
+class CParser(CFamilyParser):
+    """C语言解析器"""
+
+    def __init__(self):
+        super().__init__("c")
+
     def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
         function_node = node.child_by_field_name("function")
         if function_node:
-            if function_node.type == "member_expression":
-                property_node = function_node.child_by_field_name("property")
-                if property_node:
-                    return self.get_node_text(property_node, source_code)
             return self.get_node_text(function_node, source_code)
         return None
 
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """C 中没有方法，ops->read(x) 形式的调用都是通过字段中的函数指针"""
+        function_node = node.child_by_field_name("function")
+        if function_node is not None and function_node.type == "field_expression":
+            return self._indirect_hints(node)
+        return super().call_hints(node, source_code)
+
+
+class CppParser(CFamilyParser):
+    """C++语言解析器"""
+
+    def __init__(self):
+        super().__init__("cpp")
+        # 函数内局部变量的类型缓存（以函数起始字节为键），每个文件重置
+        self._type_cache: Dict[int, Dict[str, str]] = {}
+
+    def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        function_node = node.child_by_field_name("function")
+        if function_node:
+            if function_node.type == "field_expression":
+                field = function_node.child_by_field_name("field")
+                if field:
+                    return _strip_template_args(self.get_node_text(field, source_code))
+            if function_node.type == "template_function":
+                name_node = function_node.child_by_field_name("name")
+                if name_node:
+                    return self.get_node_text(name_node, source_code)
+            return _strip_template_args(self.get_node_text(function_node, source_code))
+        return None
+
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        extras = super().extract_extras(node, source_code)
+        if "owner" not in extras:
+            # 类外定义的成员函数：Type::method
+            name = self.extract_function_name(node, source_code)
+            if name and "::" in name:
+                extras["owner"] = name.rsplit("::", 1)[0]
+        type_params = self._template_params(node, source_code)
+        if type_params:
+            extras["type_params"] = type_params
+        return extras
+
+    def _template_params(self, node: Node, source_code: bytes) -> List[str]:
+        """函数模板（及所在类模板）的模板参数名"""
+        names: List[str] = []
+        parent = node.parent
+        while parent is not None:
+            if parent.type == "template_declaration":
+                params = parent.child_by_field_name("parameters")
+                found = []
+                for param in params.named_children if params else []:
+                    name_node = param.child_by_field_name("name")
+                    if name_node is None and param.type == "parameter_declaration":
+                        name_node = _declared_name(
+                            param.child_by_field_name("declarator")
+                        )
+                    if name_node is None:
+                        name_node = next(
+                            (
+                                child
+                                for child in param.named_children
+                                if child.type == "type_identifier"
+                            ),
+                            None,
+                        )
+                    if name_node is not None:
+                        found.append(self.get_node_text(name_node, source_code))
+                names = found + names
+            elif parent.type == "function_definition":
+                break
+            parent = parent.parent
+        return names
+
+    def _call_type_args(self, function_node: Node, source_code: bytes) -> List[str]:
+        """调用点显式指定的模板实参：max<int>(a, b)、std::get<0>(t)、obj.as<T>()"""
+        if function_node.type == "qualified_identifier":
+            function_node = function_node.child_by_field_name("name")
+        elif function_node.type == "field_expression":
+            function_node = function_node.child_by_field_name("field")
+        if function_node is None or function_node.type not in (
+            "template_function",
+            "template_method",
+        ):
+            return []
+        arguments = function_node.child_by_field_name("arguments")
+        return [
+            " ".join(self.get_node_text(child, source_code).split())
+            for child in (arguments.named_children if arguments else [])
+            if child.type != "comment"
+        ]
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """
+        类内定义的成员函数取最近的访问说明符（public: 等），
+        class 默认 private，struct 默认 public；类外的 static 函数为 private
+        """
+        parent = node.parent
+        if parent and parent.type == "field_declaration_list":
+            sibling = node.prev_sibling
+            while sibling:
+                if sibling.type == "access_specifier":
+                    return self.get_node_text(sibling, source_code).strip()
+                sibling = sibling.prev_sibling
+            owner = parent.parent
+            if owner and owner.type == "class_specifier":
+                return "private"
+            return "public"
+        return super().extract_visibility(node, source_code, func_name)
+
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        self._type_cache = {}
+        return super().collect_call_refs(file_path, root, source_code, functions)
+
+    def _type_name(self, node: Optional[Node], source_code: bytes) -> Optional[str]:
+        """类型节点的类名：ns::Foo<T> -> Foo，内置类型和 auto 返回 None"""
+        if node is None:
+            return None
+        if node.type == "template_type":
+            return self._type_name(node.child_by_field_name("name"), source_code)
+        if node.type in ("type_identifier", "qualified_identifier"):
+            text = _strip_template_args(self.get_node_text(node, source_code))
+            return text.rsplit("::", 1)[-1] or None
+        return None
+
+    def _declared_types(
+        self, node: Node, source_code: bytes, types: Dict[str, str]
+    ):
+        """记录声明（Foo x; Foo *p = ...; auto q = new Foo()）中变量的类型"""
+        type_name = self._type_name(node.child_by_field_name("type"), source_code)
+        for declarator in node.children_by_field_name("declarator"):
+            value = None
+            if declarator.type == "init_declarator":
+                value = declarator.child_by_field_name("value")
+                declarator = declarator.child_by_field_name("declarator")
+            declarator = _unwrap_declarator(declarator)
+            if declarator is None or declarator.type != "identifier":
+                continue
+            variable_type = type_name
+            if not variable_type and value is not None:
+                # auto p = new Foo(...); auto x = Foo(...);
+                if value.type == "new_expression":
+                    variable_type = self._type_name(
+                        value.child_by_field_name("type"), source_code
+                    )
+                elif value.type == "call_expression":
+                    function_node = value.child_by_field_name("function")
+                    if function_node is not None and function_node.type in (
+                        "identifier",
+                        "qualified_identifier",
+                    ):
+                        text = self.get_node_text(function_node, source_code)
+                        if text.rsplit("::", 1)[-1][:1].isupper():
+                            variable_type = _strip_template_args(text).rsplit(
+                                "::", 1
+                            )[-1]
+            if variable_type:
+                types[self.get_node_text(declarator, source_code)] = variable_type
+
+    def _local_types(self, func_node: Node, source_code: bytes) -> Dict[str, str]:
+        """函数参数和函数体中局部变量的类型（后出现的声明覆盖先出现的）"""
+        cached = self._type_cache.get(func_node.start_byte)
+        if cached is not None:
+            return cached
+
+        types: Dict[str, str] = {}
+        declarator = _unwrap_declarator(func_node.child_by_field_name("declarator"))
+        parameters = (
+            declarator.child_by_field_name("parameters")
+            if declarator is not None and declarator.type == "function_declarator"
+            else None
+        )
+        if parameters is not None:
+            for parameter in parameters.named_children:
+                if parameter.type == "parameter_declaration":
+                    self._declared_types(parameter, source_code, types)
+
+        stack = [func_node.child_by_field_name("body")]
+        while stack:
+            current = stack.pop()
+            if current is None:
+                continue
+            if current.type == "declaration":
+                self._declared_types(current, source_code, types)
+            # lambda 和局部类中的声明不属于本函数
+            elif current.type not in ("lambda_expression", "class_specifier"):
+                stack.extend(reversed(current.children))
+
+        self._type_cache[func_node.start_byte] = types
+        return types
+
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        方法调用的解析提示：receiver（obj.m() / p->m()）、this（this->m()）、
+        owner（A::m() 的 A，或根据局部变量声明推断出的接收者类型）
+        """
+        hints = super().call_hints(node, source_code)
+        function_node = node.child_by_field_name("function")
+        if function_node is None:
+            return hints
+        type_args = self._call_type_args(function_node, source_code)
+        if type_args:
+            hints["type_args"] = type_args
+
+        if function_node.type == "field_expression":
+            hints["receiver"] = True
+            argument = function_node.child_by_field_name("argument")
+            if argument is None:
+                return hints
+            if argument.type == "this":
+                hints["this"] = True
+            elif argument.type == "identifier":
+                func_node = node.parent
+                while func_node is not None and func_node.type != "function_definition":
+                    func_node = func_node.parent
+                if func_node is not None:
+                    types = self._local_types(func_node, source_code)
+                    owner = types.get(self.get_node_text(argument, source_code))
+                    if owner:
+                        hints["owner"] = owner
+        elif function_node.type == "qualified_identifier":
+            scope = function_node.child_by_field_name("scope")
+            if scope is not None:
+                text = _strip_template_args(self.get_node_text(scope, source_code))
+                if text:
+                    hints["owner"] = text.rsplit("::", 1)[-1]
+        return hints
+
+
+class JavaParser(LanguageParser):
+    """
+    Java语言解析器
+
+    类作为命名空间：方法的限定名为 包名.外部类.类.方法；调用根据 import、
+    同包的类、局部变量/参数/字段的声明类型和参数个数解析到具体类的方法，
+    new Foo() 连接到 Foo 的构造方法
+    """
+
+    def __init__(self):
+        super().__init__("java")
+        # 当前文件的包名和导入，每个文件重置
+        self._package = ""
+        self._imports: Dict[str, Any] = {
+            "types": {},
+            "packages": [],
+            "static": {},
+            "static_classes": [],
+        }
+        # 变量类型缓存（以方法或类的起始字节为键），每个文件重置
+        self._type_cache: Dict[int, Dict[str, str]] = {}
+        # 按名称索引的 Java 方法表，以及项目中的类 {(包名, 类名): 基类列表}，
+        # 函数表变化时重建
+        self._index: Dict[str, List[Dict[str, Any]]] = {}
+        self._by_id: Dict[str, Dict[str, Any]] = {}
+        self._classes: Dict[tuple, List[str]] = {}
+        self._index_key: Optional[tuple] = None
+
+    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        name_node = node.child_by_field_name("name")
+        if name_node:
+            return self.get_node_text(name_node, source_code)
+        return None
+
+    def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        if node.type == "object_creation_expression":
+            # new Foo<>(...) 调用构造方法 Foo
+            return self._type_name(node.child_by_field_name("type"), source_code)
+
+        name_node = node.child_by_field_name("name")
+        if name_node:
+            return self.get_node_text(name_node, source_code)
+
+        # 处理方法链调用
+        object_node = node.child_by_field_name("object")
+        if object_node and object_node.type == "method_invocation":
+            return self.extract_call_name(object_node, source_code)
+
+        return None
+
+    def extract_attributes(self, node: Node, source_code: bytes) -> List[str]:
+        """方法修饰符中的注解（@Test、@Override 等）"""
+        attributes = []
+        for child in node.children:
+            if child.type != "modifiers":
+                continue
+            for modifier in child.children:
+                if modifier.type in ("marker_annotation", "annotation"):
+                    text = self.get_node_text(modifier, source_code)
+                    attributes.append(text.lstrip("@").strip())
+        return attributes
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """访问修饰符；未声明时接口方法为 public，其余为包内可见"""
+        for child in node.children:
+            if child.type != "modifiers":
+                continue
+            for modifier in child.children:
+                if modifier.type in ("public", "protected", "private"):
+                    return modifier.type
+        parent = node.parent
+        if parent and parent.parent and parent.parent.type == "interface_declaration":
+            return "public"
+        return "package"
+
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        所属的类（owner）、外部类（enclosing）、类的基类和接口（bases）、
+        包名（package）以及参数个数（arity，可变参数方法标记 varargs）
+        """
+        extras = super().extract_extras(node, source_code)
+
+        classes = []
+        parent = node.parent
+        while parent:
+            if parent.type in self.config["class_types"]:
+                classes.insert(0, parent)
+            parent = parent.parent
+        if classes and extras.get("owner"):
+            names = []
+            for class_node in classes[:-1]:
+                name_node = class_node.child_by_field_name("name")
+                if name_node:
+                    names.append(self.get_node_text(name_node, source_code))
+            if names:
+                extras["enclosing"] = names
+            bases = self._class_bases(classes[-1], source_code)
+            if bases:
+                extras["bases"] = bases
+
+        root = node
+        while root.parent:
+            root = root.parent
+        extras["package"] = self._package_name(root, source_code)
+
+        parameters = node.child_by_field_name("parameters")
+        if parameters is not None:
+            kinds = [child.type for child in parameters.named_children]
+            extras["arity"] = sum(
+                kind in ("formal_parameter", "spread_parameter") for kind in kinds
+            )
+            if "spread_parameter" in kinds:
+                extras["varargs"] = True
+        return extras
+
+    def _type_name(self, node: Optional[Node], source_code: bytes) -> Optional[str]:
+        """类型的简单类名：a.b.Foo<T> -> Foo，Foo[] -> Foo；基本类型返回 None"""
+        if node is None:
+            return None
+        if node.type == "type_identifier":
+            return self.get_node_text(node, source_code)
+        if node.type == "scoped_type_identifier":
+            return self.get_node_text(node, source_code).rsplit(".", 1)[-1].strip()
+        if node.type in ("generic_type", "array_type"):
+            inner = node.child_by_field_name("element") or next(
+                iter(node.named_children), None
+            )
+            return self._type_name(inner, source_code)
+        return None
+
+    def _class_bases(self, class_node: Node, source_code: bytes) -> List[str]:
+        """类的父类和实现（或继承）的接口"""
+        bases = []
+        for field in ("superclass", "interfaces"):
+            clause = class_node.child_by_field_name(field)
+            if clause is None:
+                continue
+            stack = list(clause.named_children)
+            while stack:
+                current = stack.pop(0)
+                if current.type == "type_list":
+                    stack[:0] = current.named_children
+                    continue
+                name = self._type_name(current, source_code)
+                if name:
+                    bases.append(name)
+        # interface A extends B, C
+        for child in class_node.named_children:
+            if child.type == "extends_interfaces":
+                for item in child.named_children:
+                    for type_node in [item] + list(item.named_children):
+                        name = self._type_name(type_node, source_code)
+                        if name and name not in bases:
+                            bases.append(name)
+        return bases
+
+    def _package_name(self, root: Node, source_code: bytes) -> str:
+        """文件的包名（package a.b;），默认包为空字符串"""
+        for child in root.named_children:
+            if child.type == "package_declaration":
+                for item in child.named_children:
+                    if item.type in ("scoped_identifier", "identifier"):
+                        return "".join(self.get_node_text(item, source_code).split())
+        return ""
+
+    def _java_imports(self, root: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        收集文件的导入
+
+        Returns:
+            {"types": {类名: 包名}, "packages": [import a.b.* 的包],
+             "static": {方法名: (包名, 类名)},
+             "static_classes": [import static a.b.C.* 的 (包名, 类名)]}
+        """
+        imports: Dict[str, Any] = {
+            "types": {},
+            "packages": [],
+            "static": {},
+            "static_classes": [],
+        }
+        for child in root.named_children:
+            if child.type != "import_declaration":
+                continue
+            path_node = next(
+                (
+                    item
+                    for item in child.named_children
+                    if item.type in ("scoped_identifier", "identifier")
+                ),
+                None,
+            )
+            if path_node is None:
+                continue
+            path = "".join(self.get_node_text(path_node, source_code).split())
+            is_static = any(item.type == "static" for item in child.children)
+            wildcard = any(item.type == "asterisk" for item in child.children)
+            prefix, _, last = path.rpartition(".")
+            if is_static and wildcard:
+                package, _, class_name = path.rpartition(".")
+                imports["static_classes"].append((package, class_name))
+            elif is_static:
+                package, _, class_name = prefix.rpartition(".")
+                imports["static"][last] = (package, class_name)
+            elif wildcard:
+                imports["packages"].append(path)
+            else:
+                imports["types"][last] = prefix
+        return imports
+
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        self._package = self._package_name(root, source_code)
+        self._imports = self._java_imports(root, source_code)
+        self._type_cache = {}
+        return super().collect_call_refs(file_path, root, source_code, functions)
+
+    def _declared_types(self, node: Node, source_code: bytes) -> Dict[str, str]:
+        """
+        节点内直接声明的变量类型：方法的参数和局部变量（var x = new Foo()
+        取构造的类型），或类体中的字段；不进入 lambda、匿名类、嵌套类和其他方法
+        """
+        cached = self._type_cache.get(node.start_byte)
+        if cached is not None:
+            return cached
+
+        types: Dict[str, str] = {}
+
+        def declare(declaration: Node):
+            type_name = self._type_name(
+                declaration.child_by_field_name("type"), source_code
+            )
+            declarators = declaration.children_by_field_name("declarator")
+            if not declarators:
+                # 参数：formal_parameter 的 name 字段
+                declarators = [declaration]
+            for declarator in declarators:
+                name_node = declarator.child_by_field_name("name")
+                if name_node is None:
+                    continue
+                variable_type = type_name
+                value = declarator.child_by_field_name("value")
+                if (
+                    variable_type in (None, "var")
+                    and value is not None
+                    and value.type == "object_creation_expression"
+                ):
+                    variable_type = self._type_name(
+                        value.child_by_field_name("type"), source_code
+                    )
+                if variable_type and variable_type != "var":
+                    types[self.get_node_text(name_node, source_code)] = variable_type
+
+        stack = list(node.named_children)
+        while stack:
+            current = stack.pop()
+            if current.type in (
+                "formal_parameter",
+                "spread_parameter",
+                "local_variable_declaration",
+                "field_declaration",
+            ):
+                declare(current)
+            elif current.type not in (
+                "lambda_expression",
+                "class_body",
+                "method_declaration",
+                "constructor_declaration",
+            ):
+                stack.extend(current.named_children)
+
+        self._type_cache[node.start_byte] = types
+        return types
+
+    def _variable_type(
+        self, node: Node, name: str, source_code: bytes
+    ) -> Optional[str]:
+        """调用点可见的变量类型：所在方法的参数/局部变量，其次是外层各类的字段"""
+        parent = node.parent
+        while parent:
+            if parent.type in self.config["function_types"]:
+                variable_type = self._declared_types(parent, source_code).get(name)
+                if variable_type:
+                    return variable_type
+            elif parent.type in self.config["class_types"]:
+                body = parent.child_by_field_name("body")
+                if body is not None:
+                    variable_type = self._declared_types(body, source_code).get(name)
+                    if variable_type:
+                        return variable_type
+            parent = parent.parent
+        return None
+
+    def _type_packages(self, type_name: str) -> List[str]:
+        """类名可能所在的包，按 Java 的查找顺序：单类型导入、本包、按需导入"""
+        if type_name in self._imports["types"]:
+            return [self._imports["types"][type_name]]
+        return [self._package] + self._imports["packages"]
+
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        解析提示：参数个数（arity）、接收者类别（this / super / 未限定调用
+        implicit / 类型未知的 receiver），以及接收者类型（receiver_type）和
+        该类型可能所在的包（packages）
+        """
+        arguments = node.child_by_field_name("arguments")
+        hints: Dict[str, Any] = {
+            "arity": len(arguments.named_children) if arguments is not None else 0
+        }
+
+        if node.type == "object_creation_expression":
+            type_name = self._type_name(node.child_by_field_name("type"), source_code)
+            if type_name:
+                hints["constructor"] = True
+                hints["receiver_type"] = type_name
+                hints["packages"] = self._type_packages(type_name)
+            return hints
+
+        obj = node.child_by_field_name("object")
+        if obj is None:
+            hints["implicit"] = True
+            name = self.extract_call_name(node, source_code)
+            static = []
+            if name in self._imports["static"]:
+                static.append(list(self._imports["static"][name]))
+            static.extend(list(item) for item in self._imports["static_classes"])
+            if static:
+                hints["static"] = static
+            return hints
+        if obj.type in ("this", "super"):
+            hints[obj.type] = True
+            return hints
+
+        type_name = None
+        if obj.type == "identifier":
+            name = self.get_node_text(obj, source_code)
+            type_name = self._variable_type(node, name, source_code)
+            if not type_name and name[:1].isupper():
+                # 静态方法调用 Util.max()
+                type_name = name
+        elif obj.type == "field_access":
+            # this.repo.save()
+            target = obj.child_by_field_name("object")
+            field = obj.child_by_field_name("field")
+            if target is not None and target.type == "this" and field is not None:
+                type_name = self._variable_type(
+                    node, self.get_node_text(field, source_code), source_code
+                )
+        if type_name:
+            hints["receiver_type"] = type_name
+            hints["packages"] = self._type_packages(type_name)
+        else:
+            hints["receiver"] = True
+        return hints
+
+    def _java_index(self, functions: List[Dict[str, Any]]):
+        """Java 方法按名称建立索引，同时记录项目中的类及其基类（按 functions 列表缓存）"""
+        key = (id(functions), len(functions))
+        if self._index_key != key:
+            self._index = {}
+            self._by_id = {}
+            self._classes = {}
+            for func in functions:
+                if func.get("language") != "java":
+                    continue
+                self._by_id[func["id"]] = func
+                self._index.setdefault(func["name"], []).append(func)
+                extras = func.get("extras") or {}
+                if extras.get("owner"):
+                    class_key = (extras.get("package") or "", extras["owner"])
+                    bases = self._classes.setdefault(class_key, [])
+                    for base in extras.get("bases") or []:
+                        if base not in bases:
+                            bases.append(base)
+            self._index_key = key
+        return self._index
+
+    def _find_class(self, type_name: str, packages: List[str]) -> Optional[tuple]:
+        """类名对应的项目中的类 (包名, 类名)；不在项目中时返回 None"""
+        for package in packages:
+            if (package, type_name) in self._classes:
+                return (package, type_name)
+        # 使用完全限定名或未能通过导入确定包时，取唯一的同名类
+        matches = [key for key in self._classes if key[1] == type_name]
+        return matches[0] if len(matches) == 1 else None
+
+    def _find_method(
+        self,
+        class_key: tuple,
+        name: str,
+        arity: Optional[int],
+        seen: Optional[Set[tuple]] = None,
+    ) -> Optional[Dict[str, Any]]:
+        """在类及其基类中查找方法，重载时优先参数个数匹配的方法"""
+        seen = seen or set()
+        if class_key in seen:
+            return None
+        seen.add(class_key)
+
+        methods = [
+            func
+            for func in self._index.get(name, [])
+            if (func["extras"].get("package") or "", func["extras"].get("owner"))
+            == class_key
+        ]
+        if methods:
+
+            def matches(func: Dict[str, Any]) -> bool:
+                extras = func["extras"]
+                expected = extras.get("arity")
+                if arity is None or expected is None:
+                    return True
+                if extras.get("varargs"):
+                    return arity >= expected - 1
+                return arity == expected
+
+            return next((func for func in methods if matches(func)), methods[0])
+
+        for base in self._classes.get(class_key, []):
+            # 基类优先在同一个包中查找
+            base_key = self._find_class(base, [class_key[0]])
+            if base_key:
+                found = self._find_method(base_key, name, arity, seen)
+                if found:
+                    return found
+        return None
+
+    def resolve_callees(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        按以下顺序解析 Java 调用（只在 Java 方法中查找）：
+
+        1. new Foo()：类 Foo 的构造方法（没有显式声明构造方法时不产生调用）
+        2. m() / this.m()：所在类（及外部类）和基类中的方法，其次是静态导入的方法；
+           super.m()：基类中的方法
+        3. 接收者类型已知（局部变量、参数、字段的声明类型，或 Util.max() 中的类名）：
+           该类及其基类中的方法；类型不在项目中（如 String）时视为外部函数
+        4. 接收者类型未知（如链式调用）：任意同名方法（置信度 heuristic）
+        """
+        hints = ref.get("hints") or {}
+        if hints.get("target"):
+            return super().resolve_callees(ref, functions)
+
+        index = self._java_index(functions)
+        call_name = ref["call_name"]
+        arity = hints.get("arity")
+        caller = self._by_id.get(ref["caller_id"])
+        caller_extras = (caller or {}).get("extras") or {}
+        caller_package = caller_extras.get("package") or ""
+
+        target = None
+        confidence = "certain"
+        if hints.get("receiver_type"):
+            class_key = self._find_class(
+                hints["receiver_type"], hints.get("packages") or []
+            )
+            if class_key:
+                target = self._find_method(class_key, call_name, arity)
+                if not target and hints.get("constructor"):
+                    # 项目中的类没有显式声明构造方法
+                    return []
+        elif hints.get("implicit") or hints.get("this") or hints.get("super"):
+            scopes = []
+            if caller_extras.get("owner"):
+                scopes.append((caller_package, caller_extras["owner"]))
+                # 内部类中可以直接调用外部类的方法
+                for outer in reversed(caller_extras.get("enclosing") or []):
+                    scopes.append((caller_package, outer))
+            if hints.get("super"):
+                # super.m() 从基类开始查找
+                bases = self._classes.get(scopes[0], []) if scopes else []
+                scopes = [self._find_class(base, [caller_package]) for base in bases]
+            elif hints.get("implicit"):
+                scopes.extend(tuple(item) for item in hints.get("static") or [])
+            for class_key in scopes:
+                if class_key:
+                    target = self._find_method(class_key, call_name, arity)
+                    if target:
+                        break
+        elif hints.get("receiver"):
+            candidates = [
+                func for func in index.get(call_name, []) if func["extras"].get("owner")
+            ]
+            target = candidates[0] if candidates else None
+            confidence = "heuristic"
+
+        if not target:
+            return [
+                {
+                    "callee_id": self.generate_id("external", call_name, 0),
+                    "callee_name": call_name,
+                    "edge_kind": "static",
+                }
+            ]
+        return [
+            {
+                "callee_id": target["id"],
+                "callee_name": target["name"],
+                "callee_file": target["file"],
+                "edge_kind": "static",
+                "confidence": confidence,
+            }
+        ]
+
+
+# 可重载的二元运算符 -> (trait, 方法)；!= 由 eq 实现，比较运算符由 partial_cmp 实现
+RUST_OPERATOR_TRAITS = {
+    "+": ("Add", "add"),
+    "-": ("Sub", "sub"),
+    "*": ("Mul", "mul"),
+    "/": ("Div", "div"),
+    "%": ("Rem", "rem"),
+    "&": ("BitAnd", "bitand"),
+    "|": ("BitOr", "bitor"),
+    "^": ("BitXor", "bitxor"),
+    "<<": ("Shl", "shl"),
+    ">>": ("Shr", "shr"),
+    "==": ("PartialEq", "eq"),
+    "!=": ("PartialEq", "eq"),
+    "<": ("PartialOrd", "partial_cmp"),
+    ">": ("PartialOrd", "partial_cmp"),
+    "<=": ("PartialOrd", "partial_cmp"),
+    ">=": ("PartialOrd", "partial_cmp"),
+    "+=": ("AddAssign", "add_assign"),
+    "-=": ("SubAssign", "sub_assign"),
+    "*=": ("MulAssign", "mul_assign"),
+    "/=": ("DivAssign", "div_assign"),
+    "%=": ("RemAssign", "rem_assign"),
+    "&=": ("BitAndAssign", "bitand_assign"),
+    "|=": ("BitOrAssign", "bitor_assign"),
+    "^=": ("BitXorAssign", "bitxor_assign"),
+    "<<=": ("ShlAssign", "shl_assign"),
+    ">>=": ("ShrAssign", "shr_assign"),
+}
+RUST_UNARY_OPERATOR_TRAITS = {
+    "-": ("Neg", "neg"),
+    "!": ("Not", "not"),
+    "*": ("Deref", "deref"),
+}
+RUST_LITERAL_TYPES = (
+    "integer_literal",
+    "float_literal",
+    "string_literal",
+    "raw_string_literal",
+    "char_literal",
+    "boolean_literal",
+)
+# 可能 panic 的宏 -> 类别（debug_assert 只在调试构建中检查，同样计入 assert）
+RUST_PANIC_MACROS = {
+    "panic": "panic",
+    "assert": "assert",
+    "assert_eq": "assert",
+    "assert_ne": "assert",
+    "debug_assert": "assert",
+    "debug_assert_eq": "assert",
+    "debug_assert_ne": "assert",
+    "todo": "todo",
+    "unimplemented": "unimplemented",
+    "unreachable": "unreachable",
+}
+# 可能 panic 的方法调用（Option / Result）
+RUST_PANIC_METHODS = ("unwrap", "expect")
+# 所有 panic 类别（index 为下标访问 a[i]）
+PANIC_KINDS = (
+    "panic",
+    "assert",
+    "todo",
+    "unimplemented",
+    "unreachable",
+    "unwrap",
+    "expect",
+    "index",
+)
+# 返回可能失败的结果的类型（包括 io::Result、fmt::Result 等别名）
+RUST_FALLIBLE_TYPES = ("Result", "Option")
+# 调用点对返回的 Result / Option 的处理方式：propagate（? 或直接返回，转发给
+# 调用者）、match（match / if let / while let）、unwrap（unwrap、expect，失败时
+# panic）、convert（ok()、unwrap_or() 等转换为默认值或布尔值）、ignore（丢弃）
+ERROR_HANDLING = ("propagate", "match", "unwrap", "convert", "ignore")
+# 不改变错误流向的适配方法：f().map_err(..)? 仍然是转发
+RUST_ERROR_ADAPTERS = (
+    "map_err",
+    "context",
+    "with_context",
+    "wrap_err",
+    "wrap_err_with",
+    "ok_or",
+    "ok_or_else",
+    "map",
+    "and_then",
+    "into",
+)
+# 失败时 panic 的方法和转换为默认值、布尔值的方法
+RUST_UNWRAP_METHODS = (
+    "unwrap",
+    "expect",
+    "unwrap_err",
+    "expect_err",
+    "unwrap_unchecked",
+)
+RUST_CONVERT_METHODS = (
+    "ok",
+    "err",
+    "unwrap_or",
+    "unwrap_or_else",
+    "unwrap_or_default",
+    "map_or",
+    "map_or_else",
+    "is_ok",
+    "is_err",
+    "is_some",
+    "is_none",
+    "is_ok_and",
+    "is_some_and",
+)
+
+
+class RustParser(LanguageParser):
+    """Rust语言解析器"""
+
+    # 函数名作为值：map(f)、Handler { cb: f }、let f = f、&f、[f, g]、return f
+    FUNCTION_VALUE_CONTEXTS = {
+        "arguments": None,
+        "field_initializer": "value",
+        "let_declaration": "value",
+        "assignment_expression": "right",
+        "array_expression": None,
+        "tuple_expression": None,
+        "reference_expression": "value",
+        "return_expression": None,
+    }
+    FUNCTION_VALUE_TYPES = ("identifier", "scoped_identifier")
+    # thread::spawn(f)、Builder::new().spawn(f)、scope 中的 s.spawn(|| ..)、
+    # tokio::task::spawn_blocking(f)、spawn_local
+    SPAWN_CALLS = ("spawn", "spawn_blocking", "spawn_local")
+    DOC_ATTRIBUTE_TYPES = ("attribute_item",)
+
+    def __init__(self):
+        super().__init__("rust")
+        # 函数内 trait 绑定缓存（以函数起始字节为键），每个文件重置
+        self._binding_cache: Dict[int, Dict[str, Any]] = {}
+        # 函数内绑定的变量名缓存（以函数起始字节为键），每个文件重置
+        self._locals_cache: Dict[int, Set[str]] = {}
+        # 当前文件的 use 声明，每个文件重置
+        self._imports: Dict[str, Any] = {"names": {}, "globs": []}
+        # 按绝对路径索引的函数表（crate::module::name，方法另外按类型定义处的
+        # 路径 crate::module::Type::name 索引），函数表变化时重建
+        self._path_index: Dict[str, Dict[str, Any]] = {}
+        self._path_index_key: Optional[tuple] = None
+        # 全项目的类型/impl 索引：{(类型名或类型定义处的路径, 方法名): [方法]}，
+        # 以及 {方法名: [方法]}，函数表变化时重建
+        self._method_index: Dict[tuple, List[Dict[str, Any]]] = {}
+        self._methods_by_name: Dict[str, List[Dict[str, Any]]] = {}
+        self._method_index_key: Optional[tuple] = None
+        # 当前文件 extern 块中声明的外部函数（FFI），每个文件重置
+        self._foreign: Set[str] = set()
+
+    def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        name_node = node.child_by_field_name("name")
+        if name_node:
+            return self.get_node_text(name_node, source_code)
+        return None
+
+    @staticmethod
+    def _callee_node(node: Node) -> Optional[Node]:
+        """调用的函数部分，去掉 turbofish（parse::<T> -> parse）"""
+        function_node = node.child_by_field_name("function")
+        if function_node is not None and function_node.type == "generic_function":
+            return function_node.child_by_field_name("function")
+        return function_node
+
+    def extract_call_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        function_node = self._callee_node(node)
+        if function_node:
+            if function_node.type == "field_expression":
+                field = function_node.child_by_field_name("field")
+                if field:
+                    return self.get_node_text(field, source_code)
+            return self.get_node_text(function_node, source_code)
+        return None
+
+    def extract_attributes(self, node: Node, source_code: bytes) -> List[str]:
+        """
+        函数前的外部属性（#[test]、#[no_mangle] 等）
+
+        属性在语法树中是函数的前置兄弟节点，中间可能夹着文档注释
+        """
+        attributes = []
+        sibling = node.prev_named_sibling
+        while sibling and sibling.type in (
+            "attribute_item",
+            "line_comment",
+            "block_comment",
+        ):
+            if sibling.type == "attribute_item":
+                text = self.get_node_text(sibling, source_code).strip()
+                # "#[...]" -> "..."
+                attributes.insert(0, text[2:-1].strip())
+            sibling = sibling.prev_named_sibling
+        return attributes
+
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        记录方法所属的 impl 类型（带路径的 impl a::Foo 另外记录 owner_path）、
+        实现的 trait 或所在的 trait 定义
+        """
+        extras = {}
+        parent = node.parent
+        while parent and parent.type != "function_item":
+            if parent.type == "impl_item":
+                type_node = parent.child_by_field_name("type")
+                if type_node:
+                    extras["owner"] = self._base_type_name(type_node, source_code)
+                    if type_node.type == "generic_type":
+                        type_node = type_node.child_by_field_name("type") or type_node
+                    if type_node.type == "scoped_type_identifier":
+                        extras["owner_path"] = self.get_node_text(
+                            type_node, source_code
+                        )
+                trait_node = parent.child_by_field_name("trait")
+                if trait_node:
+                    extras["trait"] = self._base_type_name(trait_node, source_code)
+                break
+            if parent.type == "trait_item":
+                name_node = parent.child_by_field_name("name")
+                if name_node:
+                    extras["owner"] = self.get_node_text(name_node, source_code)
+                    extras["in_trait"] = True
+                break
+            parent = parent.parent
+
+        # 文件内联模块（mod tests { ... }）
+        modules = []
+        parent = node.parent
+        while parent:
+            if parent.type == "mod_item":
+                name_node = parent.child_by_field_name("name")
+                if name_node:
+                    modules.insert(0, self.get_node_text(name_node, source_code))
+            parent = parent.parent
+        if modules:
+            extras["inline_modules"] = modules
+
+        parameters = node.child_by_field_name("parameters")
+        if parameters is not None:
+            extras["arity"] = sum(
+                child.type in ("parameter", "self_parameter")
+                for child in parameters.named_children
+            )
+
+        type_params = self._type_params(node, source_code)
+        if type_params:
+            extras["type_params"] = type_params
+
+        modifiers = next(
+            (child for child in node.children if child.type == "function_modifiers"),
+            None,
+        )
+        if modifiers is not None and any(
+            child.type == "unsafe" for child in modifiers.children
+        ):
+            extras["unsafe"] = True
+        # const fn：可以在编译期求值（常量、静态变量的初始化表达式等）
+        if modifiers is not None and any(
+            child.type == "const" for child in modifiers.children
+        ):
+            extras["const"] = True
+        # extern "C" fn：按外部 ABI 导出，可被其他语言调用；省略 ABI 时为 "C"
+        extern = next(
+            (
+                child
+                for child in (modifiers.children if modifiers is not None else [])
+                if child.type == "extern_modifier"
+            ),
+            None,
+        )
+        if extern is not None:
+            literal = next(
+                (c for c in extern.children if c.type == "string_literal"), None
+            )
+            abi = self.get_node_text(literal, source_code).strip('"') if literal else ""
+            extras["abi"] = abi or "C"
+        fallible = self._fallible_type(node, source_code)
+        if fallible:
+            extras["fallible"] = fallible
+        unsafe_blocks = self._unsafe_blocks(node)
+        if unsafe_blocks:
+            extras["unsafe_blocks"] = unsafe_blocks
+        panics = self._panic_sites(node, source_code)
+        if panics:
+            extras["panics"] = panics
+        return extras
+
+    def _fallible_type(self, node: Node, source_code: bytes) -> Optional[str]:
+        """返回类型为 Result 或 Option（见 RUST_FALLIBLE_TYPES）时为类型名"""
+        return_type = node.child_by_field_name("return_type")
+        if return_type is not None and return_type.type == "generic_type":
+            return_type = return_type.child_by_field_name("type")
+        if return_type is None:
+            return None
+        name = self.get_node_text(return_type, source_code).rsplit("::", 1)[-1]
+        return name if name in RUST_FALLIBLE_TYPES else None
+
+    def _error_handling(self, node: Node, source_code: bytes) -> Optional[str]:
+        """
+        调用点对返回结果的处理方式（见 ERROR_HANDLING）
+
+        经过 map_err、context 等适配方法和 .await 后按最终的用法判断；函数体
+        末尾的表达式（没有分号）视为直接返回；存入变量、作为参数传递等其他
+        用法无法跟踪，为 None
+        """
+
+        def same(a: Optional[Node], b: Node) -> bool:
+            return a is not None and (a.start_byte, a.end_byte) == (
+                b.start_byte,
+                b.end_byte,
+            )
+
+        current = node
+        while current.parent is not None:
+            parent = current.parent
+            if parent.type in ("try_expression", "return_expression"):
+                return "propagate"
+            if parent.type in ("await_expression", "parenthesized_expression"):
+                current = parent
+                continue
+            if parent.type == "field_expression":
+                call = parent.parent
+                field = parent.child_by_field_name("field")
+                if (
+                    not same(parent.child_by_field_name("value"), current)
+                    or field is None
+                    or call is None
+                    or call.type != "call_expression"
+                ):
+                    return None
+                method = self.get_node_text(field, source_code)
+                if method in RUST_ERROR_ADAPTERS:
+                    current = call
+                    continue
+                if method in RUST_UNWRAP_METHODS:
+                    return "unwrap"
+                if method in RUST_CONVERT_METHODS:
+                    return "convert"
+                return None
+            if parent.type in (
+                "match_expression",
+                "let_condition",
+                "if_let_expression",
+                "while_let_expression",
+            ):
+                value = parent.child_by_field_name("value")
+                return "match" if same(value, current) else None
+            if parent.type == "let_declaration":
+                pattern = parent.child_by_field_name("pattern")
+                if (
+                    pattern is not None
+                    and self.get_node_text(pattern, source_code) == "_"
+                    and same(parent.child_by_field_name("value"), current)
+                ):
+                    return "ignore"
+                return None
+            if parent.type == "expression_statement":
+                return "ignore"
+            if (
+                parent.type == "block"
+                and parent.parent is not None
+                and parent.parent.type == "function_item"
+                and same(parent.named_children[-1], current)
+            ):
+                return "propagate"
+            return None
+        return None
+
+    def _panic_sites(self, node: Node, source_code: bytes) -> Dict[str, Dict[str, int]]:
+        """
+        函数体中可能 panic 的位置：{类别: {"count": 次数, "line": 第一处的行号}}
+
+        类别见 PANIC_KINDS；不含嵌套函数和闭包中的位置（闭包单独记录）。
+        宏参数在语法树中只是记号序列，其中的 unwrap 等无法识别
+        """
+        sites: Dict[str, Dict[str, int]] = {}
+
+        def add(kind: str, site: Node):
+            line = site.start_point[0] + 1
+            entry = sites.setdefault(kind, {"count": 0, "line": line})
+            entry["count"] += 1
+            entry["line"] = min(entry["line"], line)
+
+        stack = list(node.children)
+        while stack:
+            current = stack.pop()
+            if current.type in ("function_item", "closure_expression"):
+                continue
+            if current.type == "macro_invocation":
+                macro = current.child_by_field_name("macro")
+                if macro is not None:
+                    name = self.get_node_text(macro, source_code).rsplit("::", 1)[-1]
+                    if name in RUST_PANIC_MACROS:
+                        add(RUST_PANIC_MACROS[name], current)
+            elif current.type == "call_expression":
+                function_node = current.child_by_field_name("function")
+                field = (
+                    function_node.child_by_field_name("field")
+                    if function_node is not None
+                    and function_node.type == "field_expression"
+                    else None
+                )
+                if field is not None:
+                    method = self.get_node_text(field, source_code)
+                    if method in RUST_PANIC_METHODS:
+                        add(method, current)
+            elif current.type == "index_expression":
+                add("index", current)
+            stack.extend(current.children)
+        return sites
+
+    def _unsafe_blocks(self, node: Node) -> int:
+        """函数体中 unsafe 块的数量，不含嵌套函数和闭包中的块（闭包单独记录）"""
+        count = 0
+        stack = list(node.children)
+        while stack:
+            current = stack.pop()
+            if current.type in ("function_item", "closure_expression"):
+                continue
+            if current.type == "unsafe_block":
+                count += 1
+            stack.extend(current.children)
+        return count
+
+    def _type_params(self, func_node: Node, source_code: bytes) -> List[str]:
+        """函数（及外层 impl）的类型参数名，不含生命周期参数"""
+        owners = [func_node]
+        parent = func_node.parent
+        while parent and parent.type != "function_item":
+            if parent.type == "impl_item":
+                owners.insert(0, parent)
+                break
+            parent = parent.parent
+
+        names = []
+        for owner in owners:
+            type_params = owner.child_by_field_name("type_parameters")
+            for param in type_params.named_children if type_params else []:
+                if param.type == "type_identifier":
+                    name_node = param
+                elif param.type == "constrained_type_parameter":
+                    name_node = param.child_by_field_name("left")
+                elif param.type in (
+                    "type_parameter",
+                    "optional_type_parameter",
+                    "const_parameter",
+                ):
+                    name_node = param.child_by_field_name("name")
+                else:
+                    continue
+                if name_node:
+                    names.append(self.get_node_text(name_node, source_code))
+        return names
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """
+        pub -> public，pub(crate) -> crate，pub(super)/pub(in path) -> restricted；
+        trait 中声明的方法和 trait 实现方法的可见性跟随 trait，视为 public
+        """
+        for child in node.children:
+            if child.type == "visibility_modifier":
+                text = "".join(self.get_node_text(child, source_code).split())
+                if text == "pub":
+                    return "public"
+                if text == "pub(crate)":
+                    return "crate"
+                return "restricted"
+
+        parent = node.parent
+        while parent and parent.type != "function_item":
+            if parent.type == "trait_item":
+                return "public"
+            if parent.type == "impl_item":
+                if parent.child_by_field_name("trait"):
+                    return "public"
+                break
+            parent = parent.parent
+        return "private"
+
+    def _base_type_name(self, node: Node, source_code: bytes) -> str:
+        """取类型的基本名称：去掉路径前缀和泛型参数（如 fmt::Display<T> -> Display）"""
+        if node.type == "generic_type":
+            inner = node.child_by_field_name("type")
+            if inner:
+                return self._base_type_name(inner, source_code)
+        if node.type in ("scoped_type_identifier", "scoped_identifier"):
+            name_node = node.child_by_field_name("name")
+            if name_node:
+                return self.get_node_text(name_node, source_code)
+        return self.get_node_text(node, source_code)
+
+    def _bound_names(self, node: Node, source_code: bytes) -> List[str]:
+        """从 trait 约束（如 Shape + Send）中提取所有 trait 名称"""
+        if node.type in (
+            "type_identifier",
+            "scoped_type_identifier",
+            "generic_type",
+        ):
+            return [self._base_type_name(node, source_code)]
+        if node.type in ("lifetime", "type_arguments", "removed_trait_bound"):
+            return []
+        names = []
+        for child in node.named_children:
+            names.extend(self._bound_names(child, source_code))
+        return names
+
+    def _generic_bounds(
+        self, func_node: Node, source_code: bytes
+    ) -> Dict[str, List[str]]:
+        """收集函数（及外层 impl）的泛型参数约束：{T: [Trait, ...]}"""
+        bounds: Dict[str, List[str]] = {}
+
+        def add_params(owner: Node):
+            type_params = owner.child_by_field_name("type_parameters")
+            if type_params:
+                for param in type_params.named_children:
+                    if param.type == "constrained_type_parameter":
+                        name_node = param.child_by_field_name("left")
+                    elif param.type == "type_parameter":
+                        name_node = param.child_by_field_name("name")
+                    else:
+                        continue
+                    bound_node = param.child_by_field_name("bounds")
+                    if name_node and bound_node:
+                        name = self.get_node_text(name_node, source_code)
+                        bounds.setdefault(name, []).extend(
+                            self._bound_names(bound_node, source_code)
+                        )
+            for child in owner.children:
+                if child.type != "where_clause":
+                    continue
+                for predicate in child.named_children:
+                    if predicate.type != "where_predicate":
+                        continue
+                    left = predicate.child_by_field_name("left")
+                    bound_node = predicate.child_by_field_name("bounds")
+                    if left and bound_node:
+                        name = self.get_node_text(left, source_code)
+                        bounds.setdefault(name, []).extend(
+                            self._bound_names(bound_node, source_code)
+                        )
+
+        add_params(func_node)
+        parent = func_node.parent
+        while parent:
+            if parent.type == "impl_item":
+                add_params(parent)
+                break
+            parent = parent.parent
+        return bounds
+
+    def _trait_types(
+        self, type_node: Node, source_code: bytes, generics: Dict[str, List[str]]
+    ) -> List[str]:
+        """找出类型中通过 dyn Trait / impl Trait / 泛型约束引用的 trait"""
+        traits = []
+        stack = [type_node]
+        while stack:
+            current = stack.pop()
+            if current.type in ("dynamic_type", "abstract_type"):
+                trait_node = current.child_by_field_name("trait")
+                if trait_node:
+                    traits.extend(self._bound_names(trait_node, source_code))
+                continue
+            if current.type == "type_identifier":
+                name = self.get_node_text(current, source_code)
+                traits.extend(generics.get(name, []))
+                continue
+            stack.extend(current.children)
+        return traits
+
+    def _trait_bindings(self, func_node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        收集函数内以 trait 形式使用的变量和泛型参数，以及变量声明的泛型类型实参
+
+        Returns:
+            {"vars": {变量名: [Trait...]}, "generics": {T: [Trait...]},
+             "type_args": {变量名: [类型实参...]}, "types": {变量名: 类型名},
+             "borrowed": {声明为引用的变量名}}
+        """
+        generics = self._generic_bounds(func_node, source_code)
+        variables: Dict[str, List[str]] = {}
+        type_args: Dict[str, List[str]] = {}
+        types: Dict[str, str] = {}
+        borrowed: Set[str] = set()
+
+        def bind(
+            pattern: Optional[Node],
+            type_node: Optional[Node],
+            value: Optional[Node] = None,
+        ):
+            if not pattern or pattern.type not in ("identifier", "mut_pattern"):
+                return
+            name = self.get_node_text(pattern, source_code)
+            name = name.replace("mut ", "").strip()
+            if not type_node:
+                # let x = Type::new(...) / let x = Type { ... }
+                value_type = self._expression_type(value, source_code)
+                if value_type:
+                    types[name] = value_type
+                return
+            traits = self._trait_types(type_node, source_code, generics)
+            if traits:
+                variables[name] = traits
+            args = self._declared_type_args(type_node, source_code)
+            if args:
+                type_args[name] = args
+            if type_node.type == "reference_type":
+                borrowed.add(name)
+                type_node = type_node.child_by_field_name("type")
+            if type_node is not None and type_node.type in (
+                "type_identifier",
+                "scoped_type_identifier",
+                "generic_type",
+            ):
+                types[name] = self._base_type_name(type_node, source_code)
+
+        params = func_node.child_by_field_name("parameters")
+        if params:
+            for param in params.named_children:
+                if param.type == "parameter":
+                    bind(
+                        param.child_by_field_name("pattern"),
+                        param.child_by_field_name("type"),
+                    )
+
+        body = func_node.child_by_field_name("body")
+        if body:
+            stack = [body]
+            while stack:
+                current = stack.pop()
+                if current.type == "let_declaration":
+                    bind(
+                        current.child_by_field_name("pattern"),
+                        current.child_by_field_name("type"),
+                        current.child_by_field_name("value"),
+                    )
+                # 嵌套函数有自己的作用域
+                if current.type != "function_item":
+                    stack.extend(current.children)
+
+        return {
+            "vars": variables,
+            "generics": generics,
+            "type_args": type_args,
+            "types": types,
+            "borrowed": borrowed,
+        }
+
+    def _expression_type(
+        self, node: Optional[Node], source_code: bytes
+    ) -> Optional[str]:
+        """构造表达式的类型：Type::new(...)、Type { ... }，无法判断时返回 None"""
+        if node is None:
+            return None
+        if node.type == "struct_expression":
+            name_node = node.child_by_field_name("name")
+            return self._base_type_name(name_node, source_code) if name_node else None
+        if node.type == "call_expression":
+            function_node = self._callee_node(node)
+            path = (
+                function_node.child_by_field_name("path")
+                if function_node is not None
+                and function_node.type == "scoped_identifier"
+                else None
+            )
+            if path is not None and path.type in (
+                "identifier",
+                "scoped_identifier",
+                "generic_type",
+            ):
+                name = self._base_type_name(path, source_code)
+                # 路径的最后一段是类型（首字母大写），而不是模块
+                if name[:1].isupper():
+                    return name
+        return None
+
+    def _type_arguments(self, node: Optional[Node], source_code: bytes) -> List[str]:
+        """type_arguments 节点中的类型实参（不含生命周期）"""
+        if node is None:
+            return []
+        return [
+            " ".join(self.get_node_text(child, source_code).split())
+            for child in node.named_children
+            if child.type not in ("lifetime", "line_comment", "block_comment")
+        ]
+
+    def _declared_type_args(self, type_node: Node, source_code: bytes) -> List[str]:
+        """声明类型的类型实参：Repo<User>、&mut Repo<User> -> [User]"""
+        while type_node is not None and type_node.type == "reference_type":
+            type_node = type_node.child_by_field_name("type")
+        if type_node is None or type_node.type != "generic_type":
+            return []
+        return self._type_arguments(
+            type_node.child_by_field_name("type_arguments"), source_code
+        )
+
+    def _bindings(self, node: Node, source_code: bytes) -> Optional[Dict[str, Any]]:
+        """节点所在函数的变量绑定（见 _trait_bindings，按函数缓存）"""
+        func_node = node.parent
+        while func_node and func_node.type != "function_item":
+            func_node = func_node.parent
+        if not func_node:
+            return None
+
+        cache_key = func_node.start_byte
+        if cache_key not in self._binding_cache:
+            self._binding_cache[cache_key] = self._trait_bindings(
+                func_node, source_code
+            )
+        return self._binding_cache[cache_key]
+
+    def _call_type_args(self, node: Node, source_code: bytes) -> List[str]:
+        """
+        调用点的泛型类型实参：parse::<User>(x)、Repo::<User>::new()、
+        x.collect::<Vec<_>>()，以及声明为 Repo<User> 的变量上的方法调用
+        """
+        function_node = node.child_by_field_name("function")
+        if function_node is None:
+            return []
+        if function_node.type == "generic_function":
+            return self._type_arguments(
+                function_node.child_by_field_name("type_arguments"), source_code
+            )
+        if function_node.type == "scoped_identifier":
+            path = function_node.child_by_field_name("path")
+            if path is not None and path.type == "generic_type":
+                return self._type_arguments(
+                    path.child_by_field_name("type_arguments"), source_code
+                )
+        if function_node.type == "field_expression":
+            value = function_node.child_by_field_name("value")
+            bindings = self._bindings(node, source_code)
+            if value is not None and value.type == "identifier" and bindings:
+                name = self.get_node_text(value, source_code)
+                return bindings["type_args"].get(name, [])
+        return []
+
+    def _dispatch_traits(self, node: Node, source_code: bytes) -> List[str]:
+        """判断调用是否经由 trait 对象或泛型约束分发，返回候选 trait"""
+        function_node = self._callee_node(node)
+        if not function_node:
+            return []
+
+        bindings = self._bindings(node, source_code)
+        if not bindings:
+            return []
+
+        if function_node.type == "field_expression":
+            # receiver.method() 形式：receiver 是 dyn Trait / impl Trait / T: Trait
+            value = function_node.child_by_field_name("value")
+            if value and value.type == "identifier":
+                name = self.get_node_text(value, source_code)
+                return bindings["vars"].get(name, [])
+        elif function_node.type == "scoped_identifier":
+            # T::method(x) 形式：T 是受 trait 约束的泛型参数
+            path = function_node.child_by_field_name("path")
+            if path:
+                name = self.get_node_text(path, source_code)
+                return bindings["generics"].get(name, [])
+        return []
+
+    def extract_closure_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """记录 move 闭包、绑定的变量名（let f = |x| ...）和捕获的外部变量"""
+        extras: Dict[str, Any] = {}
+        if any(child.type == "move" for child in node.children):
+            extras["move"] = True
+
+        parent = node.parent
+        if parent and parent.type == "let_declaration":
+            pattern = parent.child_by_field_name("pattern")
+            value = parent.child_by_field_name("value")
+            if (
+                pattern
+                and pattern.type == "identifier"
+                and value
+                and value.start_byte == node.start_byte
+            ):
+                extras["binding"] = self.get_node_text(pattern, source_code)
+
+        captures = self._closure_captures(node, source_code)
+        if captures:
+            extras["captures"] = captures
+
+        parameters = node.child_by_field_name("parameters")
+        if parameters is not None:
+            extras["arity"] = sum(
+                child.type != "attribute_item" for child in parameters.named_children
+            )
+
+        unsafe_blocks = self._unsafe_blocks(node)
+        if unsafe_blocks:
+            extras["unsafe_blocks"] = unsafe_blocks
+        panics = self._panic_sites(node, source_code)
+        if panics:
+            extras["panics"] = panics
+        return extras
+
+    def _pattern_names(self, pattern: Node, source_code: bytes) -> Set[str]:
+        """模式中绑定的变量名（跳过 Some(x)、Point { .. } 中的类型路径）"""
+        names = set()
+        stack = [pattern]
+        while stack:
+            current = stack.pop()
+            if current.type in ("identifier", "shorthand_field_identifier"):
+                names.add(self.get_node_text(current, source_code))
+                continue
+            if current.type == "self":
+                names.add("self")
+                continue
+            type_node = None
+            if current.type in ("tuple_struct_pattern", "struct_pattern"):
+                type_node = current.child_by_field_name("type")
+            for child in current.named_children:
+                if type_node is None or child.start_byte != type_node.start_byte:
+                    stack.append(child)
+        return names
+
+    def _scope_bindings(
+        self, scope: Node, source_code: bytes, exclude: Optional[Node] = None
+    ) -> Set[str]:
+        """作用域内（参数、let、for、if let、match 分支、闭包参数）绑定的变量名"""
+        names = set()
+        stack = [scope]
+        while stack:
+            current = stack.pop()
+            if exclude and current.start_byte == exclude.start_byte:
+                if current.type == exclude.type:
+                    continue
+            if current.type == "self_parameter":
+                names.add("self")
+            elif current.type in (
+                "parameter",
+                "let_declaration",
+                "for_expression",
+                "let_condition",
+                "match_arm",
+            ):
+                pattern = current.child_by_field_name("pattern")
+                if pattern:
+                    names |= self._pattern_names(pattern, source_code)
+            elif current.type == "closure_parameters":
+                for param in current.named_children:
+                    if param.type != "parameter":
+                        names |= self._pattern_names(param, source_code)
+            # 嵌套函数有自己的作用域
+            if current.type != "function_item" or current is scope:
+                stack.extend(current.children)
+        return names
+
+    def _closure_captures(self, node: Node, source_code: bytes) -> List[str]:
+        """闭包体中引用的、在外层函数中绑定的变量（包括 self）"""
+        func_node = node.parent
+        while func_node and func_node.type != "function_item":
+            func_node = func_node.parent
+        if not func_node:
+            return []
+
+        outer = self._scope_bindings(func_node, source_code, exclude=node)
+        inner = self._scope_bindings(node, source_code)
+
+        used = set()
+        stack = [node.child_by_field_name("body")]
+        while stack:
+            current = stack.pop()
+            if current is None:
+                continue
+            if current.type == "identifier":
+                used.add(self.get_node_text(current, source_code))
+            elif current.type == "self":
+                used.add("self")
+            # 路径（module::item）不是变量引用；嵌套函数不能捕获外部变量
+            elif current.type not in ("scoped_identifier", "function_item"):
+                stack.extend(current.children)
+
+        return sorted((used & outer) - inner)
+
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        self._binding_cache = {}
+        self._locals_cache = {}
+        self._imports = self._use_imports(root, source_code)
+        self._foreign = self._foreign_functions(root, source_code)
+        refs = super().collect_call_refs(file_path, root, source_code, functions)
+        refs.extend(self._operator_refs(file_path, root, source_code, functions))
+        refs.extend(self._drop_refs(file_path, root, source_code, functions))
+        self._set_call_cfg(refs, root, source_code, functions)
+
+        # 通过 let 绑定的变量调用闭包：f(x) 指向所在函数（或外层函数）中的闭包
+        local = {func["id"]: func for func in functions if func["file"] == file_path}
+        bound = {}
+        for func in local.values():
+            extras = func.get("extras") or {}
+            if extras.get("binding"):
+                bound[(extras["parent"], extras["binding"])] = func
+        if not bound:
+            return refs
+
+        for ref in refs:
+            if ref["hints"].get("target") or ref["hints"].get("implicit"):
+                continue
+            scope_id = ref["caller_id"]
+            while scope_id in local:
+                closure = bound.get((scope_id, ref["call_name"]))
+                if closure and closure["id"] != ref["caller_id"]:
+                    ref["hints"]["target"] = {"id": closure["id"]}
+                    break
+                scope_id = (local[scope_id].get("extras") or {}).get("parent")
+        return refs
+
+    def _operator_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        """
+        运算符重载和解引用对应的隐式 trait 方法调用（hints.implicit 为 operator）
+
+        a + b -> Add::add、a[i] -> Index::index、*a -> Deref::deref；
+        接收者类型已知且该类型没有被调用的方法时，x.m() 经由自动解引用调用 Deref::deref。
+        能推断出左操作数的类型时记录在 hints.owner 中，只连接到该类型的实现
+        """
+        local_functions = sorted(
+            (func for func in functions if func["file"] == file_path),
+            key=lambda func: func["end_byte"] - func["start_byte"],
+        )
+        refs = []
+        stack = [root]
+        while stack:
+            node = stack.pop()
+            stack.extend(reversed(node.children))
+            operator = self._operator_call(node, source_code)
+            if not operator:
+                continue
+            caller = next(
+                (
+                    func
+                    for func in local_functions
+                    if func["start_byte"] <= node.start_byte < func["end_byte"]
+                ),
+                None,
+            )
+            if not caller:
+                continue
+            trait, method, hints = operator
+            refs.append(
+                {
+                    "caller_id": caller["id"],
+                    "caller_name": caller["name"],
+                    "caller_file": file_path,
+                    "call_name": method,
+                    "call_site_line": node.start_point[0] + 1,
+                    "call_site_column": node.start_point[1],
+                    "call_site_snippet": self.call_snippet(node, source_code),
+                    "language": self.language_name,
+                    "hints": {"implicit": "operator", "traits": [trait], **hints},
+                }
+            )
+        return refs
+
+    def _operator_call(self, node: Node, source_code: bytes):
+        """节点对应的隐式调用：(trait, 方法, 额外的 hints)，不是时返回 None"""
+        if node.type in ("binary_expression", "compound_assignment_expr"):
+            operator_node = node.child_by_field_name("operator")
+            left = node.child_by_field_name("left")
+            if operator_node is None or left is None:
+                return None
+            operator = self.get_node_text(operator_node, source_code).strip()
+            if operator not in RUST_OPERATOR_TRAITS:
+                return None
+            # 字面量（1 + x）上的运算符是内置实现
+            if left.type in RUST_LITERAL_TYPES:
+                return None
+            trait, method = RUST_OPERATOR_TRAITS[operator]
+            owner = self._operand_type(left, source_code)
+            return trait, method, {"owner": owner} if owner else {}
+
+        if node.type == "index_expression" and node.named_children:
+            owner = self._operand_type(node.named_children[0], source_code)
+            return "Index", "index", {"owner": owner} if owner else {}
+
+        if node.type == "unary_expression" and node.named_children:
+            operator = self.get_node_text(node.children[0], source_code)
+            if operator not in RUST_UNARY_OPERATOR_TRAITS:
+                return None
+            trait, method = RUST_UNARY_OPERATOR_TRAITS[operator]
+            operand = node.named_children[-1]
+            owner = self._operand_type(operand, source_code)
+            if trait == "Deref":
+                # 解引用普通引用是内置操作，只考虑已知类型的值
+                if not owner or self._is_borrowed(operand, source_code):
+                    return None
+            return trait, method, {"owner": owner} if owner else {}
+
+        if node.type == "call_expression":
+            function_node = self._callee_node(node)
+            if function_node is None or function_node.type != "field_expression":
+                return None
+            receiver = function_node.child_by_field_name("value")
+            field = function_node.child_by_field_name("field")
+            if receiver is None or field is None:
+                return None
+            owner = self._operand_type(receiver, source_code)
+            if not owner or self._is_borrowed(receiver, source_code):
+                return None
+            return (
+                "Deref",
+                "deref",
+                {"owner": owner, "autoderef": self.get_node_text(field, source_code)},
+            )
+        return None
+
+    def _drop_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        """
+        值被销毁时隐式调用的 Drop::drop（hints.implicit 为 drop，hints.owner 为类型）
 
-class TypeScriptParser(LanguageParser):
-    """TypeScript语言解析器"""
+        类型已知（声明的类型或构造表达式）且不是引用的函数参数和 let 绑定，
+        没有被移走（作为参数传递、赋给其他变量、返回）时在所在块的末尾销毁，
+        drop(x) 则在调用处销毁
+        """
+        local_functions = sorted(
+            (func for func in functions if func["file"] == file_path),
+            key=lambda func: func["end_byte"] - func["start_byte"],
+        )
+        refs = []
+        stack = [root]
+        while stack:
+            node = stack.pop()
+            stack.extend(reversed(node.children))
+            if node.type == "let_declaration":
+                scope = node.parent
+                binding = self._owned_binding(
+                    node.child_by_field_name("pattern"),
+                    node.child_by_field_name("type"),
+                    node.child_by_field_name("value"),
+                    source_code,
+                )
+            elif (
+                node.type == "parameter"
+                and node.parent is not None
+                and node.parent.parent is not None
+                and node.parent.parent.type == "function_item"
+            ):
+                scope = node.parent.parent.child_by_field_name("body")
+                binding = self._owned_binding(
+                    node.child_by_field_name("pattern"),
+                    node.child_by_field_name("type"),
+                    None,
+                    source_code,
+                )
+            else:
+                continue
+            if not binding or scope is None:
+                continue
+            name, owner = binding
+            site = self._drop_site(name, node, scope, source_code)
+            caller = next(
+                (
+                    func
+                    for func in local_functions
+                    if func["start_byte"] <= node.start_byte < func["end_byte"]
+                ),
+                None,
+            )
+            if site is None or caller is None:
+                continue
+            refs.append(
+                {
+                    "caller_id": caller["id"],
+                    "caller_name": caller["name"],
+                    "caller_file": file_path,
+                    "call_name": "drop",
+                    "call_site_line": site[0] + 1,
+                    "call_site_column": site[1],
+                    "language": self.language_name,
+                    "hints": {"implicit": "drop", "traits": ["Drop"], "owner": owner},
+                }
+            )
+        return refs
 
-    def __init__(self):
-        super().__init__("typescript")
+    def _owned_binding(
+        self,
+        pattern: Optional[Node],
+        type_node: Optional[Node],
+        value: Optional[Node],
+        source_code: bytes,
+    ) -> Optional[Tuple[str, str]]:
+        """绑定了已知类型的值（而不是引用）的变量：(变量名, 类型名)"""
+        if pattern is not None and pattern.type == "mut_pattern":
+            pattern = pattern.named_children[-1] if pattern.named_children else None
+        if pattern is None or pattern.type != "identifier":
+            return None
+        if type_node is None:
+            owner = self._expression_type(value, source_code)
+        elif type_node.type in (
+            "type_identifier",
+            "scoped_type_identifier",
+            "generic_type",
+        ):
+            owner = self._base_type_name(type_node, source_code)
+        else:
+            owner = None
+        if not owner:
+            return None
+        return self.get_node_text(pattern, source_code), owner
+
+    def _drop_site(
+        self, name: str, declaration: Node, scope: Node, source_code: bytes
+    ) -> Optional[Tuple[int, int]]:
+        """
+        变量被销毁的位置（行号从 0 开始）：drop(x) 调用处或所在块的末尾；
+        变量被移走时返回 None
+        """
+        moved_from = {"let_declaration": "value", "assignment_expression": "right"}
+        stack = list(reversed(scope.children))
+        while stack:
+            current = stack.pop()
+            if current.type == "function_item":
+                continue
+            stack.extend(reversed(current.children))
+            if (
+                current.type != "identifier"
+                or current.start_byte < declaration.end_byte
+                or self.get_node_text(current, source_code) != name
+            ):
+                continue
+            parent = current.parent
+            if parent.type == "arguments":
+                call = parent.parent
+                callee = self._callee_node(call) if call is not None else None
+                if callee is not None and self.get_node_text(
+                    callee, source_code
+                ) in ("drop", "mem::drop", "std::mem::drop"):
+                    return call.start_point
+                return None
+            # let y = x、y = x、Type { field: x }、[x]、return x、块的值
+            if parent.type in moved_from:
+                target = parent.child_by_field_name(moved_from[parent.type])
+                if target is not None and target.start_byte == current.start_byte:
+                    return None
+            elif parent.type in (
+                "field_initializer",
+                "array_expression",
+                "return_expression",
+                "block",
+            ):
+                return None
+        return (scope.end_point[0], max(scope.end_point[1] - 1, 0))
+
+    def _operand_type(self, node: Node, source_code: bytes) -> Optional[str]:
+        """操作数的类型名：声明了类型或由构造表达式初始化的变量、self、构造表达式"""
+        while node.type in ("parenthesized_expression", "reference_expression"):
+            inner = node.named_children[-1] if node.named_children else None
+            if inner is None:
+                return None
+            node = inner
+        if node.type == "identifier":
+            bindings = self._bindings(node, source_code)
+            if bindings:
+                return bindings["types"].get(self.get_node_text(node, source_code))
+            return None
+        if node.type == "self":
+            parent = node.parent
+            while parent and parent.type != "impl_item":
+                parent = parent.parent
+            type_node = parent.child_by_field_name("type") if parent else None
+            if type_node is not None:
+                return self._base_type_name(type_node, source_code)
+            return None
+        return self._expression_type(node, source_code)
+
+    def _is_borrowed(self, node: Node, source_code: bytes) -> bool:
+        """操作数是否为声明成引用的变量（&self 也视为引用）"""
+        if node.type == "self":
+            return True
+        if node.type != "identifier":
+            return False
+        bindings = self._bindings(node, source_code)
+        return bool(bindings) and (
+            self.get_node_text(node, source_code) in bindings["borrowed"]
+        )
+
+    def _use_imports(self, root: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        收集文件中的 use 声明
+
+        Returns:
+            {"names": {本地名称: 完整路径}, "globs": [use a::* 的路径前缀]}
+        """
+        names: Dict[str, str] = {}
+        globs: List[str] = []
+        stack = [root]
+        while stack:
+            current = stack.pop()
+            if current.type == "use_declaration":
+                argument = current.child_by_field_name("argument")
+                if argument:
+                    tree_names, tree_globs = self._use_tree(argument, source_code)
+                    names.update(tree_names)
+                    globs.extend(tree_globs)
+                continue
+            stack.extend(current.children)
+        return {"names": names, "globs": globs}
+
+    def _use_tree(
+        self, argument: Node, source_code: bytes
+    ) -> Tuple[Dict[str, str], List[str]]:
+        """展开一条 use 声明：({本地名称: 完整路径}, [通配导入的路径前缀])"""
+        names: Dict[str, str] = {}
+        globs: List[str] = []
+
+        def join(prefix: str, path: str) -> str:
+            path = path.strip().lstrip(":")
+            return f"{prefix}::{path}" if prefix else path
+
+        def add(node: Node, prefix: str):
+            if node.type in (
+                "identifier",
+                "scoped_identifier",
+                "crate",
+                "self",
+                "super",
+            ):
+                path = join(prefix, self.get_node_text(node, source_code))
+                # use a::{self} 导入模块 a 本身
+                if path.endswith("::self"):
+                    path = path[: -len("::self")]
+                names[path.rsplit("::", 1)[-1]] = path
+            elif node.type == "use_as_clause":
+                path_node = node.child_by_field_name("path")
+                alias_node = node.child_by_field_name("alias")
+                if path_node and alias_node:
+                    alias = self.get_node_text(alias_node, source_code)
+                    if alias != "_":
+                        path = self.get_node_text(path_node, source_code)
+                        names[alias] = join(prefix, path)
+            elif node.type == "scoped_use_list":
+                path_node = node.child_by_field_name("path")
+                list_node = node.child_by_field_name("list")
+                if path_node:
+                    prefix = join(prefix, self.get_node_text(path_node, source_code))
+                if list_node:
+                    for child in list_node.named_children:
+                        add(child, prefix)
+            elif node.type == "use_list":
+                for child in node.named_children:
+                    add(child, prefix)
+            elif node.type == "use_wildcard":
+                inner = node.named_children[0] if node.named_children else None
+                if inner:
+                    globs.append(join(prefix, self.get_node_text(inner, source_code)))
+                elif prefix:
+                    globs.append(prefix)
+
+        add(argument, "")
+        return names, globs
+
+    def _doc_hidden(self, node: Node, source_code: bytes) -> bool:
+        return any(
+            "".join(attribute.split()) == "doc(hidden)"
+            for attribute in self.extract_attributes(node, source_code)
+        )
+
+    def _cfg_regions(self, root: Node, source_code: bytes) -> List[tuple]:
+        """
+        带 #[cfg(...)] 的语法节点：(起始字节, 结束字节, 起始位置, 结束位置, 条件)，
+        外层的在前
+
+        外部属性作用于其后的项、语句或表达式（match 分支上的属性作用于整个
+        分支）；内部属性 #![cfg(...)] 作用于所在的文件、内联模块或函数
+        """
+        regions = []
+        stack = [root]
+        while stack:
+            node = stack.pop()
+            stack.extend(node.children)
+            if node.type not in ("attribute_item", "inner_attribute_item"):
+                continue
+            inner = node.type == "inner_attribute_item"
+            text = self.get_node_text(node, source_code).strip()
+            predicates = cfg_predicates([text[3 if inner else 2 : -1]])
+            parent = node.parent
+            if not predicates or parent is None:
+                continue
+            if inner:
+                target = parent if parent.type == "source_file" else parent.parent
+            elif parent.type == "match_arm":
+                target = parent
+            else:
+                target = node.next_named_sibling
+                while target is not None and target.type in (
+                    "attribute_item",
+                    "line_comment",
+                    "block_comment",
+                ):
+                    target = target.next_named_sibling
+            if target is None:
+                continue
+            regions.append(
+                (
+                    target.start_byte,
+                    target.end_byte,
+                    tuple(target.start_point),
+                    tuple(target.end_point),
+                    predicates[0],
+                )
+            )
+        regions.sort(key=lambda region: (region[0], -region[1]))
+        return regions
+
+    def collect_functions(
+        self, file_path: str, root: Node, source_code: bytes
+    ) -> List[Dict[str, Any]]:
+        """在函数的 extras 中记录它所在的各层项上的 cfg 条件（cfg，同时成立）"""
+        functions = super().collect_functions(file_path, root, source_code)
+        regions = self._cfg_regions(root, source_code)
+        if regions:
+            for func in functions:
+                predicates = [
+                    region[4]
+                    for region in regions
+                    if region[0] <= func["start_byte"] < region[1]
+                ]
+                if predicates:
+                    func["extras"]["cfg"] = list(dict.fromkeys(predicates))
+        return functions
+
+    def _set_call_cfg(
+        self,
+        refs: List[Dict[str, Any]],
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ):
+        """
+        调用点所在的、调用者内部的 cfg 条件（如 #[cfg(unix)] 的语句或 match
+        分支）记录在引用的 cfg 中；调用者本身的条件记录在函数上
+        """
+        regions = self._cfg_regions(root, source_code)
+        if not regions:
+            return
+        starts = {func["id"]: func["start_byte"] for func in functions}
+        for ref in refs:
+            point = (ref["call_site_line"] - 1, ref["call_site_column"])
+            caller_start = starts.get(ref.get("caller_id"), -1)
+            condition = combine_cfg(
+                region[4]
+                for region in regions
+                if region[2] <= point < region[3]
+                and not region[0] <= caller_start < region[1]
+            )
+            if condition:
+                ref["cfg"] = condition
+
+    def collect_module_items(self, root: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        收集决定模块树、名称解析和公开 API 的模块项（函数体内的项不可从外部
+        访问，跳过）：
+
+        - modules: mod 声明，path 为相对本文件模块的路径（内联模块之内的
+          声明带上外层模块名）
+        - types: struct/enum/union/trait/type 定义，scope 为所在的内联模块
+        - uses: use 声明展开后的每一项，alias 为引入的名字（use a::b as c 中
+          的 c），通配导入（use a::*）的 alias 为 None
+        - foreign: extern 块中声明的函数（FFI 边界），abi 为块的 ABI（省略时
+          为 "C"）
+        - traits: trait 定义和它的方法，default 表示有默认实现
+        - impls: trait 的 impl 块（impl Trait for Type），methods 为其中定义
+          （覆盖）的方法名
+
+        除 impls 外每一项都带有 visibility 和 hidden（#[doc(hidden)]）
+        """
+        items: Dict[str, List[Dict[str, Any]]] = {
+            "modules": [],
+            "types": [],
+            "uses": [],
+            "foreign": [],
+            "traits": [],
+            "impls": [],
+        }
+        type_kinds = (
+            "struct_item",
+            "enum_item",
+            "union_item",
+            "trait_item",
+            "type_item",
+        )
+
+        def visit(node: Node, scope: List[str]):
+            for child in node.named_children:
+                if child.type == "mod_item":
+                    name_node = child.child_by_field_name("name")
+                    if name_node is None:
+                        continue
+                    name = self.get_node_text(name_node, source_code)
+                    decl = {
+                        "path": scope + [name],
+                        "visibility": self.extract_visibility(child, source_code, name),
+                        "hidden": self._doc_hidden(child, source_code),
+                    }
+                    predicates = cfg_predicates(
+                        self.extract_attributes(child, source_code)
+                    )
+                    if predicates:
+                        decl["cfg"] = predicates
+                    items["modules"].append(decl)
+                    body = child.child_by_field_name("body")
+                    if body is not None:
+                        visit(body, scope + [name])
+                elif child.type in type_kinds:
+                    name_node = child.child_by_field_name("name")
+                    if name_node is None:
+                        continue
+                    name = self.get_node_text(name_node, source_code)
+                    items["types"].append(
+                        {
+                            "scope": scope,
+                            "name": name,
+                            "visibility": self.extract_visibility(
+                                child, source_code, name
+                            ),
+                            "hidden": self._doc_hidden(child, source_code),
+                        }
+                    )
+                    if child.type == "trait_item":
+                        items["traits"].append(
+                            {
+                                **items["types"][-1],
+                                "line": child.start_point[0] + 1,
+                                "methods": [
+                                    {"name": method, "default": default}
+                                    for method, default in self._body_methods(
+                                        child, source_code
+                                    )
+                                ],
+                            }
+                        )
+                elif child.type == "impl_item":
+                    trait_node = child.child_by_field_name("trait")
+                    type_node = child.child_by_field_name("type")
+                    if trait_node is None or type_node is None:
+                        continue
+                    items["impls"].append(
+                        {
+                            "scope": scope,
+                            "trait": self._base_type_name(trait_node, source_code),
+                            "type": self._base_type_name(type_node, source_code),
+                            "line": child.start_point[0] + 1,
+                            "methods": [
+                                method
+                                for method, _ in self._body_methods(child, source_code)
+                            ],
+                        }
+                    )
+                elif child.type == "use_declaration":
+                    argument = child.child_by_field_name("argument")
+                    if argument is None:
+                        continue
+                    visibility = self.extract_visibility(child, source_code, "")
+                    hidden = self._doc_hidden(child, source_code)
+                    names, globs = self._use_tree(argument, source_code)
+                    imported = list(names.items()) + [(None, path) for path in globs]
+                    for alias, path in imported:
+                        items["uses"].append(
+                            {
+                                "scope": scope,
+                                "path": path,
+                                "alias": alias,
+                                "visibility": visibility,
+                                "hidden": hidden,
+                            }
+                        )
+                elif child.type == "foreign_mod_item":
+                    items["foreign"].extend(
+                        self._foreign_items(child, source_code, scope)
+                    )
+
+        visit(root, [])
+        if not any(items.values()):
+            return {}
+        return {"items": items}
+
+    def _body_methods(self, node: Node, source_code: bytes) -> List[Tuple[str, bool]]:
+        """trait 或 impl 块中的方法：(方法名, 是否有函数体)"""
+        body = node.child_by_field_name("body")
+        methods = []
+        for child in body.named_children if body is not None else []:
+            if child.type not in ("function_item", "function_signature_item"):
+                continue
+            name_node = child.child_by_field_name("name")
+            if name_node is not None:
+                methods.append(
+                    (
+                        self.get_node_text(name_node, source_code),
+                        child.type == "function_item",
+                    )
+                )
+        return methods
+
+    def _foreign_items(
+        self, node: Node, source_code: bytes, scope: List[str]
+    ) -> List[Dict[str, Any]]:
+        """extern 块（foreign_mod_item）中声明的函数"""
+        extern = next(
+            (child for child in node.children if child.type == "extern_modifier"),
+            None,
+        )
+        literal = (
+            next((c for c in extern.children if c.type == "string_literal"), None)
+            if extern is not None
+            else None
+        )
+        abi = self.get_node_text(literal, source_code).strip('"') if literal else ""
+        declarations = []
+        for item in node.children:
+            for child in [item] + list(item.children):
+                if child.type != "function_signature_item":
+                    continue
+                name_node = child.child_by_field_name("name")
+                if name_node is None:
+                    continue
+                name = self.get_node_text(name_node, source_code)
+                declarations.append(
+                    {
+                        "scope": scope,
+                        "name": name,
+                        "abi": abi or "C",
+                        "line": child.start_point[0] + 1,
+                        "visibility": self.extract_visibility(
+                            child, source_code, name
+                        ),
+                        "hidden": self._doc_hidden(child, source_code),
+                    }
+                )
+        return declarations
+
+    def _foreign_functions(self, root: Node, source_code: bytes) -> Set[str]:
+        """extern "C" { fn name(...); } 块中声明的函数名"""
+        names = set()
+        stack = [root]
+        while stack:
+            current = stack.pop()
+            if current.type == "foreign_mod_item":
+                for item in current.children:
+                    for child in [item] + list(item.children):
+                        if child.type == "function_signature_item":
+                            name_node = child.child_by_field_name("name")
+                            if name_node:
+                                names.add(self.get_node_text(name_node, source_code))
+                continue
+            stack.extend(current.children)
+        return names
+
+    def _call_paths(self, call_path: str) -> List[str]:
+        """
+        根据 use 声明展开调用路径，返回候选路径（尚未规范化为绝对路径，
+        crate::、self::、super:: 及依赖 crate 名由 cargo 模块处理）
+        """
+        if "<" in call_path:
+            return []
+        segments = call_path.lstrip(":").split("::")
+        first, rest = segments[0], segments[1:]
+        imported = self._imports["names"].get(first)
+        if imported:
+            return ["::".join([imported] + rest)]
+        if rest:
+            return [call_path.lstrip(":")]
+        # 单个名称：当前模块中的函数，或通配导入的函数
+        return [f"self::{first}"] + [
+            f"{prefix}::{first}" for prefix in self._imports["globs"]
+        ]
+
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        hints: Dict[str, Any] = {}
+        traits = self._dispatch_traits(node, source_code)
+        if traits:
+            hints["traits"] = traits
+        type_args = self._call_type_args(node, source_code)
+        if type_args:
+            hints["type_args"] = type_args
+        function_node = self._callee_node(node)
+        if function_node and function_node.type in ("identifier", "scoped_identifier"):
+            text = self.get_node_text(function_node, source_code)
+            paths = self._call_paths(text)
+            if paths:
+                hints["paths"] = paths
+            if text in self._foreign:
+                hints["ffi"] = True
+        error_handling = self._error_handling(node, source_code)
+        if error_handling:
+            hints["error_handling"] = error_handling
+        # receiver.method()：只能调用方法，能推断时记录接收者类型（receiver_type）
+        if function_node and function_node.type == "field_expression":
+            hints["method"] = True
+            value = function_node.child_by_field_name("value")
+            receiver_type = self._operand_type(value, source_code) if value else None
+            if receiver_type:
+                hints["receiver_type"] = receiver_type
+        # 调用参数或局部变量中的函数值 f(x)、字段中的函数值 (self.f)(x)
+        if function_node and (
+            function_node.type == "parenthesized_expression"
+            or (
+                function_node.type == "identifier"
+                and self.get_node_text(function_node, source_code)
+                in self.local_names(function_node, source_code)
+            )
+        ):
+            hints["indirect"] = True
+            arguments = node.child_by_field_name("arguments")
+            if arguments is not None:
+                hints["arity"] = sum(
+                    child.type not in ("line_comment", "block_comment")
+                    for child in arguments.named_children
+                )
+        return hints
+
+    def local_names(self, node: Node, source_code: bytes) -> Set[str]:
+        func_node = node.parent
+        while func_node and func_node.type != "function_item":
+            func_node = func_node.parent
+        if not func_node:
+            return set()
+        if func_node.start_byte not in self._locals_cache:
+            self._locals_cache[func_node.start_byte] = self._scope_bindings(
+                func_node, source_code
+            )
+        return self._locals_cache[func_node.start_byte]
+
+    def function_value_name(self, node: Node, source_code: bytes) -> Optional[str]:
+        if node.type == "scoped_identifier":
+            name_node = node.child_by_field_name("name")
+            return self.get_node_text(name_node, source_code) if name_node else None
+        return self.get_node_text(node, source_code)
+
+    def function_value_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        paths = self._call_paths(self.get_node_text(node, source_code))
+        return {"paths": paths} if paths else {}
+
+    def enclosing_call(self, node: Node, source_code: bytes) -> Optional[Node]:
+        """Some(f)、Handler(f) 等元组结构体/枚举变体构造只是存储函数值"""
+        call = super().enclosing_call(node, source_code)
+        function_node = call.child_by_field_name("function") if call else None
+        if function_node is not None and function_node.type in (
+            "identifier",
+            "scoped_identifier",
+        ):
+            name_node = function_node
+            if function_node.type == "scoped_identifier":
+                name_node = function_node.child_by_field_name("name")
+            if (
+                name_node is not None
+                and self.get_node_text(name_node, source_code)[:1].isupper()
+            ):
+                return None
+        return call
+
+    def _function_by_path(
+        self, paths: List[str], functions: List[Dict[str, Any]]
+    ) -> Optional[Dict[str, Any]]:
+        """按绝对路径查找函数（需要 cargo 模块标注 crate 和模块路径）"""
+        key = (id(functions), len(functions))
+        if self._path_index_key != key:
+            self._path_index = {}
+            methods = []
+            for func in functions:
+                if func.get("language") != "rust":
+                    continue
+                path = function_path(func)
+                if path:
+                    self._path_index.setdefault(path, func)
+                owner_type = (func.get("extras") or {}).get("owner_type")
+                if owner_type:
+                    methods.append((f"{owner_type}::{func['name']}", func))
+            # impl 块与类型定义不在同一个模块时，也可以按类型的路径找到方法
+            for path, func in methods:
+                self._path_index.setdefault(path, func)
+            self._path_index_key = key
+        for path in paths:
+            func = self._path_index.get(path)
+            if func:
+                return func
+        return None
+
+    @staticmethod
+    def _foreign_definition(
+        name: str, functions: List[Dict[str, Any]]
+    ) -> Optional[Dict[str, Any]]:
+        """extern 块中声明的函数在项目 C/C++ 代码中的定义（非 static 的普通函数）"""
+        for func in functions:
+            if (
+                func.get("language") in C_FAMILY_LANGUAGES
+                and func["name"] == name
+                and func.get("is_exported")
+                and not (func.get("extras") or {}).get("owner")
+            ):
+                return func
+        return None
+
+    def _build_method_index(self, functions: List[Dict[str, Any]]):
+        key = (id(functions), len(functions))
+        if self._method_index_key == key:
+            return
+        self._method_index = {}
+        self._methods_by_name = {}
+        for func in functions:
+            extras = func.get("extras") or {}
+            if func.get("language") != "rust" or not extras.get("owner"):
+                continue
+            if extras.get("closure") or extras.get("dependency"):
+                continue
+            keys = {(extras["owner"], func["name"])}
+            if extras.get("owner_type"):
+                keys.add((extras["owner_type"], func["name"]))
+            for index_key in keys:
+                self._method_index.setdefault(index_key, []).append(func)
+            self._methods_by_name.setdefault(func["name"], []).append(func)
+        self._method_index_key = key
+
+    def _method_by_receiver(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> Tuple[Optional[Dict[str, Any]], str]:
+        """
+        按全项目的类型/impl 索引解析 receiver.method()：impl 块可以分散在
+        与类型定义不同的文件中
+
+        接收者类型已知时在该类型（优先按类型定义处的路径 receiver_types，
+        再按类型名）的方法中查找；类型未知或该类型没有此方法（如经由
+        Deref 调用）时，在所有同名方法中选择；有多个候选时优先同一文件、
+        目录最接近的
+
+        Returns:
+            (方法, 置信度)：按接收者类型找到时为 certain，否则同名方法唯一时为
+            probable、有多个时为 heuristic；找不到时方法为 None
+        """
+        self._build_method_index(functions)
+        hints = ref.get("hints") or {}
+        name = ref["call_name"].split("::")[-1]
+        candidates: List[Dict[str, Any]] = []
+        for type_path in hints.get("receiver_types") or []:
+            candidates = self._method_index.get((type_path, name), [])
+            if candidates:
+                break
+        if not candidates and hints.get("receiver_type"):
+            candidates = self._method_index.get((hints["receiver_type"], name), [])
+        confidence = "certain"
+        if not candidates:
+            candidates = self._methods_by_name.get(name, [])
+            confidence = "probable" if len(candidates) == 1 else "heuristic"
+        if not candidates:
+            return None, confidence
+        caller_dir = os.path.dirname(ref["caller_file"])
+
+        def rank(func: Dict[str, Any]) -> tuple:
+            common = os.path.commonprefix([caller_dir, os.path.dirname(func["file"])])
+            return (func["file"] != ref["caller_file"], -len(common))
+
+        return min(candidates, key=rank), confidence
+
+    def resolve_callees(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        解析 trait 对象 / 泛型约束上的方法调用
+
+        将调用连接到项目内该 trait 的所有 impl 方法（以及未被覆盖时的
+        默认方法），并标记为 dynamic 边；找不到候选实现时退回静态解析。
+        receiver.method() 按全项目的类型/impl 索引解析（见 _method_by_receiver），
+        extern "C" 块中声明的函数连接到项目 C/C++ 代码中的同名定义（FFI）。
+        """
+        if ref.get("hints", {}).get("target"):
+            return super().resolve_callees(ref, functions)
+        if ref.get("hints", {}).get("implicit"):
+            return self._implicit_targets(ref, functions)
+
+        traits = ref.get("hints", {}).get("traits", [])
+        method_name = ref["call_name"].split("::")[-1]
+
+        targets = []
+        seen = set()
+        for trait in traits:
+            implementors = set()
+            overriding = set()
+            default_method = None
+            for func in functions:
+                if func.get("language") != "rust":
+                    continue
+                extras = func.get("extras") or {}
+                if extras.get("trait") == trait:
+                    implementors.add(extras.get("owner"))
+                    if func["name"] == method_name:
+                        overriding.add(extras.get("owner"))
+                        if func["id"] not in seen:
+                            seen.add(func["id"])
+                            targets.append(func)
+                elif (
+                    extras.get("in_trait")
+                    and extras.get("owner") == trait
+                    and func["name"] == method_name
+                ):
+                    default_method = func
+
+            # 存在未覆盖默认方法的实现者时，默认方法也是可达目标
+            if default_method and (
+                not implementors or implementors - overriding
+            ):
+                if default_method["id"] not in seen:
+                    seen.add(default_method["id"])
+                    targets.append(default_method)
+
+        if not targets:
+            # 按 use 路径解析（跨 crate 调用、同名函数消歧）
+            qualified = ref.get("hints", {}).get("qualified")
+            func = self._function_by_path(qualified, functions) if qualified else None
+            confidence = "certain"
+            if not func and ref.get("hints", {}).get("method"):
+                func, confidence = self._method_by_receiver(ref, functions)
+                if not func:
+                    # 方法调用语法不会调用同名的自由函数，是项目外类型的方法
+                    name = ref["call_name"]
+                    return [
+                        {
+                            "callee_id": self.generate_id("external", name, 0),
+                            "callee_name": name,
+                            "edge_kind": "static",
+                        }
+                    ]
+            if not func and ref.get("hints", {}).get("ffi"):
+                # extern 块中声明的函数：连接到项目 C/C++ 代码中的定义，没有定义
+                # （如系统库函数）时连接到外部函数，边类型都为 ffi
+                name = ref["call_name"]
+                definition = self._foreign_definition(name, functions)
+                if not definition:
+                    return [
+                        {
+                            "callee_id": self.generate_id("external", name, 0),
+                            "callee_name": name,
+                            "edge_kind": FFI_EDGE_KIND,
+                        }
+                    ]
+                return [
+                    {
+                        "callee_id": definition["id"],
+                        "callee_name": definition["name"],
+                        "callee_file": definition["file"],
+                        "edge_kind": FFI_EDGE_KIND,
+                        "confidence": "certain",
+                    }
+                ]
+            if func:
+                return [
+                    {
+                        "callee_id": func["id"],
+                        "callee_name": func["name"],
+                        "callee_file": func["file"],
+                        "edge_kind": "static",
+                        "confidence": confidence,
+                    }
+                ]
+            return super().resolve_callees(ref, functions)
+
+        return [
+            {
+                "callee_id": func["id"],
+                "callee_name": func["name"],
+                "callee_file": func["file"],
+                "edge_kind": "dynamic",
+            }
+            for func in targets
+        ]
+
+
+    def _implicit_targets(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        隐式调用（运算符、解引用、Drop）对应的 trait 实现方法，边类型即隐式调用的类别
+
+        已知操作数类型时只连接到该类型的实现；自动解引用只在该类型没有
+        被调用的方法时发生。操作数类型未知时连接到所有实现（置信度 probable）
+        """
+        hints = ref["hints"]
+        trait = hints["traits"][0]
+        owner = hints.get("owner")
+        rust_functions = [func for func in functions if func.get("language") == "rust"]
+        if hints.get("autoderef") and any(
+            (func.get("extras") or {}).get("owner") == owner
+            and func["name"] == hints["autoderef"]
+            for func in rust_functions
+        ):
+            return []
+        return [
+            {
+                "callee_id": func["id"],
+                "callee_name": func["name"],
+                "callee_file": func["file"],
+                "edge_kind": hints["implicit"],
+                "confidence": "certain" if owner else "probable",
+            }
+            for func in rust_functions
+            if func["name"] == ref["call_name"]
+            and (func.get("extras") or {}).get("trait") == trait
+            and (owner is None or func["extras"].get("owner") == owner)
+        ]
+
+
+# JavaScript/TypeScript 模块文件的扩展名，按此顺序尝试解析 import 路径
+ECMASCRIPT_EXTENSIONS = (".js", ".jsx", ".mjs", ".cjs", ".ts", ".tsx", ".mts", ".cts")
+
+# 参与 JavaScript/TypeScript 调用解析的语言（两者可以互相导入）
+ECMASCRIPT_LANGUAGES = ("javascript", "typescript")
+
+
+class ECMAScriptParser(LanguageParser):
+    """
+    JavaScript/TypeScript 解析器的公共部分
+
+    除函数声明和方法外，赋值给变量、对象属性、类字段和 exports.x 的
+    箭头函数/函数表达式使用被赋值的名称；根据 import/export（ES 模块）和
+    require/module.exports（CommonJS）在文件之间解析调用
+    """
+
+    def __init__(self, language_name: str):
+        super().__init__(language_name)
+        # 当前文件的导入，每个文件重置
+        self._imports: Dict[str, Any] = {"names": {}, "namespaces": {}}
+        # 按名称索引的 JavaScript/TypeScript 函数表，函数表变化时重建
+        self._index: Dict[str, List[Dict[str, Any]]] = {}
+        self._by_id: Dict[str, Dict[str, Any]] = {}
+        self._index_key: Optional[tuple] = None
 
     def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
         name_node = node.child_by_field_name("name")
         if name_node:
             return self.get_node_text(name_node, source_code)
 
-        # 匿名函数或箭头函数
+        # 匿名函数或箭头函数：取被赋值的名称
         if node.type in ["arrow_function", "function_expression"]:
             parent = node.parent
-            if parent and parent.type == "variable_declarator":
+            if parent is None:
+                return None
+            if parent.type == "variable_declarator":
                 name_node = parent.child_by_field_name("name")
-                if name_node:
-                    return self.get_node_text(name_node, source_code)
+            elif parent.type == "pair":
+                # { handler: () => {} }
+                name_node = parent.child_by_field_name("key")
+            elif parent.type in ("field_definition", "public_field_definition"):
+                # class A { handler = () => {} }
+                name_node = parent.child_by_field_name(
+                    "property"
+                ) or parent.child_by_field_name("name")
+            elif parent.type == "assignment_expression":
+                # exports.handler = function () {}
+                name_node = parent.child_by_field_name("left")
+                if name_node is not None and name_node.type == "member_expression":
+                    name_node = name_node.child_by_field_name("property")
+            if name_node is not None and name_node.type in (
+                "identifier",
+                "property_identifier",
+                "private_property_identifier",
+                "string",
+            ):
+                return self.get_node_text(name_node, source_code).strip("'\"")
 
         return None
 
@@ -444,35 +4876,491 @@ class TypeScriptParser(LanguageParser):
             return self.get_node_text(function_node, source_code)
         return None
 
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        extras = super().extract_extras(node, source_code)
+        parent = node.parent
+        if parent is not None and parent.type == "pair":
+            # 对象字面量中的函数，只能通过 obj.name() 调用
+            extras["property"] = True
+        return extras
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """#name 形式的私有方法"""
+        return "private" if func_name.startswith("#") else "public"
+
+    def collect_functions(
+        self, file_path: str, root: Node, source_code: bytes
+    ) -> List[Dict[str, Any]]:
+        """
+        在模块文件（包含 export 或 module.exports 的文件）中，顶层函数的
+        extras.exports 记录导出名称（默认导出为 default），未导出的顶层函数
+        可见性为 private
+        """
+        functions = super().collect_functions(file_path, root, source_code)
+        exports = self._module_exports(root, source_code)
+        if not exports:
+            return functions
+        for func in functions:
+            extras = func["extras"]
+            if func["container"] or extras.get("owner") or extras.get("property"):
+                continue
+            if func["name"] in exports:
+                extras["exports"] = exports[func["name"]]
+            elif extras["visibility"] == "public":
+                extras["visibility"] = "private"
+                func["is_exported"] = 0
+        return functions
+
+    def _string_value(self, node: Optional[Node], source_code: bytes) -> Optional[str]:
+        """字符串字面量的内容（去掉引号）"""
+        if node is None or node.type not in ("string", "template_string"):
+            return None
+        return self.get_node_text(node, source_code).strip("'\"`")
+
+    def _module_exports(self, root: Node, source_code: bytes) -> Dict[str, List[str]]:
+        """
+        收集文件的导出：{本地名称: [导出名称]}
+
+        支持 export function/const/class、export default、export { a as b }，
+        以及 module.exports = ...、module.exports.a = ...、exports.a = ...；
+        从其他模块重新导出（export { a } from './b'）的名称不在本文件中
+        """
+        exports: Dict[str, List[str]] = {}
+
+        def add(local: str, exported: str):
+            names = exports.setdefault(local, [])
+            if exported not in names:
+                names.append(exported)
+
+        for statement in root.named_children:
+            if statement.type == "export_statement":
+                self._es_export(statement, source_code, add)
+            elif statement.type == "expression_statement" and statement.named_children:
+                expression = statement.named_children[0]
+                if expression.type == "assignment_expression":
+                    self._commonjs_export(expression, source_code, add)
+        return exports
+
+    def _es_export(self, statement: Node, source_code: bytes, add):
+        """export 语句导出的本地名称"""
+        if statement.child_by_field_name("source") is not None:
+            return
+        is_default = any(child.type == "default" for child in statement.children)
+        declaration = statement.child_by_field_name("declaration")
+        value = statement.child_by_field_name("value")
+        if declaration is not None:
+            name_node = declaration.child_by_field_name("name")
+            if name_node is not None:
+                name = self.get_node_text(name_node, source_code)
+                add(name, "default" if is_default else name)
+            for declarator in declaration.named_children:
+                if declarator.type == "variable_declarator":
+                    name_node = declarator.child_by_field_name("name")
+                    if name_node is not None:
+                        name = self.get_node_text(name_node, source_code)
+                        add(name, name)
+        elif value is not None and value.type == "identifier":
+            add(self.get_node_text(value, source_code), "default")
+
+        for clause in statement.named_children:
+            if clause.type != "export_clause":
+                continue
+            for specifier in clause.named_children:
+                name_node = specifier.child_by_field_name("name")
+                alias_node = specifier.child_by_field_name("alias")
+                if name_node is not None:
+                    name = self.get_node_text(name_node, source_code)
+                    alias = alias_node or name_node
+                    add(name, self.get_node_text(alias, source_code))
+
+    def _commonjs_export(self, expression: Node, source_code: bytes, add):
+        """module.exports / exports.x 赋值导出的本地名称"""
+        left = expression.child_by_field_name("left")
+        right = expression.child_by_field_name("right")
+        if left is None or right is None:
+            return
+        target = self.get_node_text(left, source_code).replace(" ", "")
+        if target.startswith(("module.exports.", "exports.")):
+            exported = target.rsplit(".", 1)[-1]
+            if right.type == "identifier":
+                add(self.get_node_text(right, source_code), exported)
+            else:
+                # exports.f = function () {}：函数以 f 命名
+                add(exported, exported)
+        elif target == "module.exports" and right.type == "identifier":
+            add(self.get_node_text(right, source_code), "default")
+        elif target == "module.exports" and right.type == "object":
+            for prop in right.named_children:
+                if prop.type == "shorthand_property_identifier":
+                    name = self.get_node_text(prop, source_code)
+                    add(name, name)
+                elif prop.type == "pair":
+                    key = prop.child_by_field_name("key")
+                    value = prop.child_by_field_name("value")
+                    if key is None or value is None:
+                        continue
+                    exported = self.get_node_text(key, source_code).strip("'\"")
+                    if value.type == "identifier":
+                        add(self.get_node_text(value, source_code), exported)
+                    else:
+                        add(exported, exported)
+
+    def _resolve_module(self, file_path: str, specifier: str) -> Optional[str]:
+        """
+        相对导入路径对应的模块（去掉扩展名的路径）；
+        包名（react、lodash/fp 等）返回 None，表示外部模块
+        """
+        if not specifier.startswith("."):
+            return None
+        path = os.path.normpath(os.path.join(os.path.dirname(file_path), specifier))
+        stem, ext = os.path.splitext(path)
+        if ext in ECMASCRIPT_EXTENSIONS:
+            # TypeScript 中 import './a.js' 指向 a.ts
+            path = stem
+        return path.replace("\\", "/")
+
+    def _ecmascript_imports(
+        self, root: Node, source_code: bytes, file_path: str
+    ) -> Dict[str, Any]:
+        """
+        收集文件中的导入
+
+        Returns:
+            {"names": {本地名称: (模块, 导入名称)},
+             "namespaces": {本地名称: 模块}}，
+            模块为去掉扩展名的路径，外部包为 None；默认导入的导入名称为 default
+        """
+        names: Dict[str, Any] = {}
+        namespaces: Dict[str, Optional[str]] = {}
+
+        def text(node: Node) -> str:
+            return self.get_node_text(node, source_code)
+
+        def require_source(node: Optional[Node]) -> Optional[str]:
+            """require('x') 的参数，不是 require 调用时返回 None"""
+            if node is None or node.type != "call_expression":
+                return None
+            function_node = node.child_by_field_name("function")
+            arguments = node.child_by_field_name("arguments")
+            if function_node is None or text(function_node) != "require":
+                return None
+            if arguments is None or not arguments.named_children:
+                return None
+            return self._string_value(arguments.named_children[0], source_code)
+
+        stack = [root]
+        while stack:
+            current = stack.pop()
+            if current.type == "import_statement":
+                specifier = self._string_value(
+                    current.child_by_field_name("source"), source_code
+                )
+                if specifier is None:
+                    continue
+                module = self._resolve_module(file_path, specifier)
+                for clause in current.named_children:
+                    if clause.type != "import_clause":
+                        continue
+                    for item in clause.named_children:
+                        if item.type == "identifier":
+                            names[text(item)] = (module, "default")
+                        elif item.type == "namespace_import":
+                            for child in item.named_children:
+                                if child.type == "identifier":
+                                    namespaces[text(child)] = module
+                        elif item.type == "named_imports":
+                            for spec in item.named_children:
+                                name_node = spec.child_by_field_name("name")
+                                alias_node = spec.child_by_field_name("alias")
+                                if name_node is None:
+                                    continue
+                                local = text(alias_node or name_node)
+                                names[local] = (module, text(name_node))
+                continue
+            if current.type == "variable_declarator":
+                specifier = require_source(current.child_by_field_name("value"))
+                name_node = current.child_by_field_name("name")
+                if specifier is not None and name_node is not None:
+                    module = self._resolve_module(file_path, specifier)
+                    if name_node.type == "identifier":
+                        # const lib = require('./lib'); lib() / lib.f()
+                        names[text(name_node)] = (module, "default")
+                        namespaces[text(name_node)] = module
+                    elif name_node.type == "object_pattern":
+                        for prop in name_node.named_children:
+                            if prop.type == "shorthand_property_identifier_pattern":
+                                names[text(prop)] = (module, text(prop))
+                            elif prop.type == "pair_pattern":
+                                key = prop.child_by_field_name("key")
+                                value = prop.child_by_field_name("value")
+                                if key is not None and value is not None:
+                                    names[text(value)] = (module, text(key))
+                    continue
+            stack.extend(current.children)
+        return {"names": names, "namespaces": namespaces}
+
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        self._imports = self._ecmascript_imports(root, source_code, file_path)
+        return super().collect_call_refs(file_path, root, source_code, functions)
+
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        解析提示：导入的函数（module、imported_name，外部包的 module 为 None）、
+        this.m()（this）以及其他接收者上的方法调用（receiver）
+        """
+        hints: Dict[str, Any] = {}
+        function_node = node.child_by_field_name("function")
+        if function_node is None:
+            return hints
+        if function_node.type == "identifier":
+            imported = self._imports["names"].get(
+                self.get_node_text(function_node, source_code)
+            )
+            if imported:
+                hints["module"], hints["imported_name"] = imported
+        elif function_node.type == "member_expression":
+            obj = function_node.child_by_field_name("object")
+            property_node = function_node.child_by_field_name("property")
+            hints["receiver"] = True
+            if obj is None or property_node is None:
+                return hints
+            if obj.type == "this":
+                hints["this"] = True
+            elif obj.type == "identifier":
+                name = self.get_node_text(obj, source_code)
+                if name in self._imports["namespaces"]:
+                    hints["module"] = self._imports["namespaces"][name]
+                    hints["imported_name"] = self.get_node_text(
+                        property_node, source_code
+                    )
+        return hints
+
+    def _ecmascript_index(self, functions: List[Dict[str, Any]]):
+        """JavaScript/TypeScript 函数按名称建立索引（按 functions 列表缓存）"""
+        key = (id(functions), len(functions))
+        if self._index_key != key:
+            self._index = {}
+            self._by_id = {}
+            for func in functions:
+                if func.get("language") not in ECMASCRIPT_LANGUAGES:
+                    continue
+                self._by_id[func["id"]] = func
+                self._index.setdefault(func["name"], []).append(func)
+            self._index_key = key
+        return self._index
+
+    @staticmethod
+    def _module_matches(file_path: str, module: str) -> bool:
+        """文件是否为给定模块（a/b 对应 a/b.js、a/b.ts 或 a/b/index.js 等）"""
+        key = os.path.splitext(file_path)[0].replace("\\", "/")
+        return key == module or key == f"{module}/index"
+
+    def resolve_callees(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        按以下顺序解析 JavaScript/TypeScript 调用（只在这两种语言的函数中查找）：
+
+        1. 导入的函数（import / require）：导入模块中对应导出名称的函数；
+           模块是外部包或找不到导出时视为外部函数
+        2. this.m()：所在类的方法，找不到时任意同名方法（可能定义在父类中）
+        3. obj.m()：任意同名方法或对象字面量中的函数
+        4. 普通调用 f()：本文件的函数，其次是其他文件中未被模块隐藏的同名顶层函数
+           （脚本中的全局函数）
+
+        2 中找不到所在类的方法时和 3 是按名称猜测的目标（置信度 heuristic），
+        4 中其他文件的全局函数为 probable
+        """
+        hints = ref.get("hints") or {}
+        if hints.get("target"):
+            return super().resolve_callees(ref, functions)
+
+        index = self._ecmascript_index(functions)
+        call_name = ref["call_name"]
+        caller_file = ref["caller_file"]
+
+        def owner_of(func: Dict[str, Any]) -> Optional[str]:
+            return (func.get("extras") or {}).get("owner")
+
+        def prefer_local(funcs: List[Dict[str, Any]]) -> Optional[Dict[str, Any]]:
+            return next(
+                (func for func in funcs if func["file"] == caller_file),
+                funcs[0] if funcs else None,
+            )
+
+        target = None
+        confidence = "certain"
+        if "imported_name" in hints:
+            module = hints.get("module")
+            imported_name = hints["imported_name"]
+            if module:
+                in_module = [
+                    func
+                    for func in functions
+                    if func.get("language") in ECMASCRIPT_LANGUAGES
+                    and self._module_matches(func["file"], module)
+                    and not func["container"]
+                    and not owner_of(func)
+                ]
+                target = next(
+                    (
+                        func
+                        for func in in_module
+                        if imported_name in (func["extras"].get("exports") or [])
+                    ),
+                    None,
+                )
+                if not target and imported_name != "default":
+                    target = next(
+                        (func for func in in_module if func["name"] == imported_name),
+                        None,
+                    )
+        else:
+            candidates = index.get(call_name, [])
+            if hints.get("this"):
+                caller = self._by_id.get(ref["caller_id"])
+                owner = owner_of(caller) if caller else None
+                target = prefer_local(
+                    [func for func in candidates if owner and owner_of(func) == owner]
+                )
+            if not target and hints.get("receiver"):
+                target = prefer_local(
+                    [
+                        func
+                        for func in candidates
+                        if owner_of(func) or func["extras"].get("property")
+                    ]
+                )
+                confidence = "heuristic"
+            if not target and not hints.get("receiver"):
+                local = [
+                    func
+                    for func in candidates
+                    if func["file"] == caller_file and not owner_of(func)
+                ]
+                free = [
+                    func
+                    for func in candidates
+                    if not func["container"]
+                    and not owner_of(func)
+                    and not func["extras"].get("property")
+                    and func.get("is_exported")
+                ]
+                target = prefer_local(local) or prefer_local(free)
+                if target and target["file"] != caller_file:
+                    confidence = "probable"
+
+        if not target:
+            return [
+                {
+                    "callee_id": self.generate_id("external", call_name, 0),
+                    "callee_name": call_name,
+                    "edge_kind": "static",
+                }
+            ]
+        return [
+            {
+                "callee_id": target["id"],
+                "callee_name": target["name"],
+                "callee_file": target["file"],
+                "edge_kind": "static",
+                "confidence": confidence,
+            }
+        ]
+
+
+class JavaScriptParser(ECMAScriptParser):
+    """JavaScript语言解析器"""
+
+    def __init__(self):
+        super().__init__("javascript")
+
+
+class TypeScriptParser(ECMAScriptParser):
+    """TypeScript语言解析器"""
+
+    def __init__(self):
+        super().__init__("typescript")
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """方法的访问修饰符（private/protected/public）或 #name 私有方法"""
+        if func_name.startswith("#"):
+            return "private"
+        for child in node.children:
+            if child.type == "accessibility_modifier":
+                return self.get_node_text(child, source_code).strip()
+        return "public"
+
+
+def _go_import_alias(import_path: str) -> str:
+    """导入路径的默认包名：最后一段（example.com/x/v2 取 x）"""
+    segments = [segment for segment in import_path.split("/") if segment]
+    if len(segments) > 1 and re.fullmatch(r"v\d+", segments[-1]):
+        return segments[-2]
+    return segments[-1] if segments else import_path
+
 
 class GoParser(LanguageParser):
-    """Go语言解析器"""
+    """
+    Go语言解析器
+
+    包按目录划分：未限定的调用在同一目录（包）中查找，pkg.F() 根据 import 路径
+    和 go.mod 中的 module 路径定位到项目中的包目录；方法调用根据接收者、参数和
+    局部变量的类型（包括 NewT() 等构造函数的返回类型）连接到对应类型的方法
+    """
+
+    # go worker(x)、go s.run()：调用在新的 goroutine 中运行
+    SPAWN_STATEMENTS = ("go_statement",)
+    # 按 Go 的约定，紧接在声明之前的普通注释就是文档
+    DOC_COMMENT_PREFIXES = ("//", "/*")
 
     def __init__(self):
         super().__init__("go")
+        # 目录 -> (go.mod 所在目录, module 路径)，查找结果缓存
+        self._modules: Dict[str, Optional[tuple]] = {}
+        # 当前文件的导入 {包名: 包目录}，项目外的包为 None，每个文件重置
+        self._imports: Dict[str, Optional[str]] = {}
+        self._file_dir = ""
+        # 函数内变量的类型缓存（以函数起始字节为键），每个文件重置
+        self._type_cache: Dict[int, Dict[str, Dict[str, Any]]] = {}
+        # 按短名称（T.M -> M）索引的 (所在目录, Go 函数)，函数表变化时重建
+        self._index: Dict[str, List[tuple]] = {}
+        self._index_key: Optional[tuple] = None
+
+    def _receiver_type(self, node: Node, source_code: bytes) -> Optional[str]:
+        """方法接收者的类型名：(s *Stack[T]) -> Stack"""
+        receiver = node.child_by_field_name("receiver")
+        if receiver is None:
+            return None
+        for param in receiver.named_children:
+            if param.type == "parameter_declaration":
+                type_name = self._type_name(
+                    param.child_by_field_name("type"), source_code
+                )
+                if type_name and not type_name[0]:
+                    return type_name[1]
+        return None
 
     def extract_function_name(self, node: Node, source_code: bytes) -> Optional[str]:
         name_node = node.child_by_field_name("name")
         if name_node:
             func_name = self.get_node_text(name_node, source_code)
 
-            # 对于方法声明，获取接收者类型
+            # 对于方法声明，名称前加接收者类型
             if node.type == "method_declaration":
-                receiver = node.child_by_field_name("receiver")
-                if receiver:
-                    # 提取接收者类型
-                    for child in receiver.children:
-                        if child.type == "parameter_list":
-                            for param in child.children:
-                                if param.type == "parameter_declaration":
-                                    type_node = param.child_by_field_name("type")
-                                    if type_node:
-                                        type_name = self.get_node_text(
-                                            type_node, source_code
-                                        )
-                                        # 去除指针符号
-                                        type_name = type_name.lstrip("*")
-                                        return f"{type_name}.{func_name}"
+                type_name = self._receiver_type(node, source_code)
+                if type_name:
+                    return f"{type_name}.{func_name}"
 
             return func_name
         return None
@@ -489,6 +5377,367 @@ class GoParser(LanguageParser):
             return self.get_node_text(function_node, source_code)
         return None
 
+    def extract_extras(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """方法所属的接收者类型；返回单个命名类型的函数记录返回类型（returns）"""
+        extras = {}
+        if node.type == "method_declaration":
+            name = self.extract_function_name(node, source_code)
+            if name and "." in name:
+                extras["owner"] = name.split(".", 1)[0]
+        result = node.child_by_field_name("result")
+        if result is not None and result.type == "parameter_list":
+            # (*Server, error) 取第一个结果
+            declarations = [
+                child
+                for child in result.named_children
+                if child.type == "parameter_declaration"
+            ]
+            result = (
+                declarations[0].child_by_field_name("type") if declarations else None
+            )
+        type_name = self._type_name(result, source_code)
+        if type_name and not type_name[0]:
+            extras["returns"] = type_name[1]
+        return extras
+
+    def extract_visibility(
+        self, node: Node, source_code: bytes, func_name: str
+    ) -> str:
+        """首字母大写的标识符导出到包外"""
+        short_name = func_name.rsplit(".", 1)[-1]
+        return "public" if short_name[:1].isupper() else "private"
+
+    def _type_name(
+        self, node: Optional[Node], source_code: bytes
+    ) -> Optional[tuple]:
+        """
+        类型节点的 (包名, 类型名)：*T -> ("", "T")，pkg.T -> ("pkg", "T")，
+        T[int] -> ("", "T")；内置类型和复合类型返回 None
+        """
+        if node is None:
+            return None
+        if node.type == "type_identifier":
+            return ("", self.get_node_text(node, source_code))
+        if node.type in ("pointer_type", "generic_type", "parenthesized_type"):
+            inner = node.child_by_field_name("type")
+            if inner is None:
+                inner = next(iter(node.named_children), None)
+            return self._type_name(inner, source_code)
+        if node.type == "qualified_type":
+            package = node.child_by_field_name("package")
+            name = node.child_by_field_name("name")
+            if package is not None and name is not None:
+                return (
+                    self.get_node_text(package, source_code),
+                    self.get_node_text(name, source_code),
+                )
+        return None
+
+    def _go_module(self, directory: str) -> Optional[tuple]:
+        """向上查找 go.mod，返回 (go.mod 所在目录, module 路径)"""
+        if directory in self._modules:
+            return self._modules[directory]
+        module = None
+        go_mod = os.path.join(directory, "go.mod")
+        if os.path.isfile(go_mod):
+            try:
+                with open(go_mod, encoding="utf-8", errors="ignore") as f:
+                    for line in f:
+                        parts = line.split()
+                        if len(parts) >= 2 and parts[0] == "module":
+                            module = (directory, parts[1].strip('"'))
+                            break
+            except OSError:
+                pass
+        if module is None:
+            parent = os.path.dirname(directory)
+            if parent and parent != directory:
+                module = self._go_module(parent)
+        self._modules[directory] = module
+        return module
+
+    def _go_imports(self, root: Node, source_code: bytes) -> Dict[str, Optional[str]]:
+        """收集文件的导入：{包名: 项目中的包目录}，标准库和第三方包为 None"""
+        imports: Dict[str, Optional[str]] = {}
+        module = self._go_module(self._file_dir)
+        stack = [root]
+        while stack:
+            current = stack.pop()
+            if current.type != "import_spec":
+                # import 只出现在文件顶层
+                if current.type in (
+                    "source_file",
+                    "import_declaration",
+                    "import_spec_list",
+                ):
+                    stack.extend(current.children)
+                continue
+            path_node = current.child_by_field_name("path")
+            if path_node is None:
+                continue
+            import_path = self.get_node_text(path_node, source_code).strip('"`')
+            name_node = current.child_by_field_name("name")
+            alias = (
+                self.get_node_text(name_node, source_code)
+                if name_node is not None
+                else _go_import_alias(import_path)
+            )
+            if alias in ("_", "."):
+                continue
+            directory = None
+            if module and (
+                import_path == module[1] or import_path.startswith(module[1] + "/")
+            ):
+                relative = import_path[len(module[1]) :].lstrip("/")
+                directory = os.path.normpath(os.path.join(module[0], relative))
+            imports[alias] = directory
+        return imports
+
+    def collect_call_refs(
+        self,
+        file_path: str,
+        root: Node,
+        source_code: bytes,
+        functions: List[Dict[str, Any]],
+    ) -> List[Dict[str, Any]]:
+        self._file_dir = os.path.dirname(os.path.abspath(file_path))
+        self._type_cache = {}
+        self._imports = self._go_imports(root, source_code)
+        return super().collect_call_refs(file_path, root, source_code, functions)
+
+    def _value_type(
+        self, node: Node, source_code: bytes
+    ) -> Optional[Dict[str, Any]]:
+        """表达式的类型：T{}、&T{}、new(T)、NewT()、pkg.NewT()"""
+        if node.type == "unary_expression":
+            operand = node.child_by_field_name("operand")
+            if operand is None:
+                return None
+            return self._value_type(operand, source_code)
+        if node.type == "composite_literal":
+            type_name = self._type_name(node.child_by_field_name("type"), source_code)
+            return self._qualify(type_name, "type") if type_name else None
+        if node.type == "call_expression":
+            function_node = node.child_by_field_name("function")
+            if function_node is None:
+                return None
+            if function_node.type == "identifier":
+                name = self.get_node_text(function_node, source_code)
+                if name == "new":
+                    arguments = node.child_by_field_name("arguments")
+                    items = arguments.named_children if arguments else []
+                    if not items:
+                        return None
+                    type_name = self._type_name(items[0], source_code)
+                    return self._qualify(type_name, "type") if type_name else None
+                return self._qualify(("", name), "constructor")
+            if function_node.type == "selector_expression":
+                operand = function_node.child_by_field_name("operand")
+                field = function_node.child_by_field_name("field")
+                if (
+                    operand is not None
+                    and field is not None
+                    and operand.type == "identifier"
+                ):
+                    package = self.get_node_text(operand, source_code)
+                    if package in self._imports:
+                        name = self.get_node_text(field, source_code)
+                        return self._qualify((package, name), "constructor")
+        return None
+
+    def _qualify(self, type_name: tuple, key: str) -> Optional[Dict[str, Any]]:
+        """(包名, 名称) -> {key: 名称, "dir": 包目录}；项目外的包返回 dir 为 None"""
+        package, name = type_name
+        if not package:
+            return {key: name, "dir": self._file_dir}
+        if package not in self._imports:
+            return None
+        return {key: name, "dir": self._imports[package]}
+
+    def _local_types(
+        self, func_node: Node, source_code: bytes
+    ) -> Dict[str, Dict[str, Any]]:
+        """接收者、参数和函数体中变量的类型（var x T、x := &T{}、x := NewT()）"""
+        cached = self._type_cache.get(func_node.start_byte)
+        if cached is not None:
+            return cached
+
+        types: Dict[str, Dict[str, Any]] = {}
+
+        def declare(names: List[Node], type_info: Optional[Dict[str, Any]]):
+            if not type_info:
+                return
+            for name_node in names:
+                if name_node.type == "identifier":
+                    types[self.get_node_text(name_node, source_code)] = type_info
+
+        def declare_typed(node: Node):
+            type_name = self._type_name(node.child_by_field_name("type"), source_code)
+            if type_name:
+                names = node.children_by_field_name("name")
+                declare(names, self._qualify(type_name, "type"))
+
+        for field in ("receiver", "parameters"):
+            parameters = func_node.child_by_field_name(field)
+            if parameters is not None:
+                for param in parameters.named_children:
+                    if param.type == "parameter_declaration":
+                        declare_typed(param)
+
+        stack = [func_node.child_by_field_name("body")]
+        while stack:
+            current = stack.pop()
+            if current is None:
+                continue
+            if current.type == "var_spec":
+                declare_typed(current)
+                values = current.child_by_field_name("value")
+                if values is not None and current.child_by_field_name("type") is None:
+                    for name_node, value in zip(
+                        current.children_by_field_name("name"), values.named_children
+                    ):
+                        declare([name_node], self._value_type(value, source_code))
+            elif current.type == "short_var_declaration":
+                left = current.child_by_field_name("left")
+                right = current.child_by_field_name("right")
+                if left is not None and right is not None:
+                    values = right.named_children
+                    # s, err := NewServer() 中的 s 取第一个返回值
+                    for name_node, value in zip(left.named_children, values[:1]):
+                        declare([name_node], self._value_type(value, source_code))
+            # 函数字面量中的局部变量不属于本函数
+            if current.type != "func_literal":
+                stack.extend(current.children)
+
+        self._type_cache[func_node.start_byte] = types
+        return types
+
+    def call_hints(self, node: Node, source_code: bytes) -> Dict[str, Any]:
+        """
+        解析提示：包目录（dir，调用者所在包或 pkg.F() 中 pkg 对应的包，
+        项目外的包为 None），以及方法调用的接收者类型（type）或构造函数（constructor）
+        """
+        function_node = node.child_by_field_name("function")
+        if function_node is None:
+            return {}
+        if function_node.type != "selector_expression":
+            return {"dir": self._file_dir}
+
+        operand = function_node.child_by_field_name("operand")
+        hints: Dict[str, Any] = {"receiver": True}
+        if operand is None or operand.type != "identifier":
+            return hints
+        name = self.get_node_text(operand, source_code)
+
+        func_node = node.parent
+        while func_node is not None and func_node.type not in (
+            "function_declaration",
+            "method_declaration",
+        ):
+            func_node = func_node.parent
+        types = self._local_types(func_node, source_code) if func_node else {}
+        local = types.get(name)
+        if local:
+            hints.update(local)
+        elif name in self._imports:
+            # pkg.F()
+            return {"dir": self._imports[name]}
+        return hints
+
+    def _go_index(self, functions: List[Dict[str, Any]]):
+        """Go 函数按短名称建立索引，并记录所在目录（按 functions 列表缓存）"""
+        key = (id(functions), len(functions))
+        if self._index_key != key:
+            self._index = {}
+            for func in functions:
+                if func.get("language") != "go":
+                    continue
+                self._index.setdefault(func["name"].rsplit(".", 1)[-1], []).append(
+                    (os.path.dirname(os.path.abspath(func["file"])), func)
+                )
+            self._index_key = key
+        return self._index
+
+    def resolve_callees(
+        self, ref: Dict[str, Any], functions: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """
+        按以下顺序解析 Go 调用（只在 Go 函数中查找）：
+
+        1. F() / pkg.F()：对应包（目录）中的包级函数；包不在项目中时视为外部函数
+        2. x.M()：推断出 x 的类型（项目中的类型）时，该类型的方法；
+           找不到时（如嵌入字段的方法）或类型未知时，任意同名方法
+           （置信度 heuristic）
+        """
+        hints = ref.get("hints") or {}
+        if hints.get("target"):
+            return super().resolve_callees(ref, functions)
+
+        index = self._go_index(functions)
+        call_name = ref["call_name"]
+        entries = index.get(call_name, [])
+
+        def owner_of(func: Dict[str, Any]) -> Optional[str]:
+            return (func.get("extras") or {}).get("owner")
+
+        def in_package(directory: Optional[str], owner: Optional[str]):
+            return next(
+                (
+                    func
+                    for func_dir, func in entries
+                    if func_dir == directory and owner_of(func) == owner
+                ),
+                None,
+            )
+
+        target = None
+        confidence = "certain"
+        if not hints.get("receiver"):
+            if hints.get("dir"):
+                target = in_package(hints["dir"], None)
+        else:
+            type_name = hints.get("type")
+            type_dir = hints.get("dir")
+            if hints.get("constructor") and type_dir:
+                # x := NewServer()：构造函数的返回类型
+                constructor = next(
+                    (
+                        func
+                        for func_dir, func in index.get(hints["constructor"], [])
+                        if func_dir == type_dir and not owner_of(func)
+                    ),
+                    None,
+                )
+                if constructor:
+                    type_name = (constructor.get("extras") or {}).get("returns")
+            if type_name and type_dir:
+                target = in_package(type_dir, type_name)
+            # 类型在项目外的包中（如 strings.Builder）时不再按名称猜测
+            external_type = "dir" in hints and not type_dir
+            if not target and not external_type:
+                target = next(
+                    (func for _, func in entries if owner_of(func)), None
+                )
+                confidence = "heuristic"
+
+        if not target:
+            return [
+                {
+                    "callee_id": self.generate_id("external", call_name, 0),
+                    "callee_name": call_name,
+                    "edge_kind": "static",
+                }
+            ]
+        return [
+            {
+                "callee_id": target["id"],
+                "callee_name": target["name"],
+                "callee_file": target["file"],
+                "edge_kind": "static",
+                "confidence": confidence,
+            }
+        ]
+
 
 # 解析器工厂
 PARSER_CLASSES = {
@@ -503,7 +5752,40 @@ PARSER_CLASSES = {
 }
 
 
-def get_parser(language: str) -> LanguageParser:
+# 插件注册的扩展名 -> 语言，优先于内置语言
+PLUGIN_EXTENSIONS: Dict[str, str] = {}
+
+
+def register_language(
+    name: str,
+    analyzer_class: type,
+    extensions: Optional[List[str]] = None,
+    config: Optional[Dict[str, Any]] = None,
+):
+    """
+    注册语言前端（第三方插件或覆盖内置语言）
+
+    Args:
+        name: 语言名称（写入函数定义和调用引用的 language 字段）
+        analyzer_class: LanguageAnalyzer 子类，必须可以无参数构造
+        extensions: 文件扩展名（默认取 analyzer_class.extensions），
+            与内置语言冲突时使用插件
+        config: 基于 tree-sitter 的前端（LanguageParser 子类）的语言配置，
+            格式同 LANGUAGE_CONFIG
+    """
+    is_class = isinstance(analyzer_class, type)
+    if not is_class or not issubclass(analyzer_class, LanguageAnalyzer):
+        raise TypeError(f"语言前端必须是 LanguageAnalyzer 的子类: {analyzer_class!r}")
+    extensions = list(extensions or analyzer_class.extensions)
+    if not extensions:
+        raise ValueError(f"语言 {name} 没有声明文件扩展名")
+    LANGUAGE_CONFIG[name] = {**(config or {}), "extensions": extensions}
+    PARSER_CLASSES[name] = analyzer_class
+    for ext in extensions:
+        PLUGIN_EXTENSIONS[ext] = name
+
+
+def get_parser(language: str) -> LanguageAnalyzer:
     """获取指定语言的解析器"""
     parser_class = PARSER_CLASSES.get(language)
     if not parser_class:
@@ -511,9 +5793,77 @@ def get_parser(language: str) -> LanguageParser:
     return parser_class()
 
 
+def mark_address_taken(
+    file_results: Dict[str, Dict[str, Any]], functions: List[Dict[str, Any]]
+) -> int:
+    """
+    全程序收集间接调用的候选目标：解析所有函数值引用（hints.value），
+    在被引用的函数和所有闭包上设置 address_taken
+
+    需要在解析调用关系之前、对完整的函数表执行一次
+
+    Returns:
+        可能被间接调用的函数数量
+    """
+    marker = AddressTakenMarker(functions)
+    for result in file_results.values():
+        marker.mark(result)
+    return marker.count()
+
+
+class AddressTakenMarker:
+    """
+    逐个文件设置 address_taken（mark_address_taken 的分步形式，低内存模式下
+    调用引用逐个文件读回时使用）；创建时重置函数表中已有的标记
+    """
+
+    def __init__(self, functions: List[Dict[str, Any]]):
+        self.functions = functions
+        self._by_id = {func["id"]: func for func in functions}
+        for func in functions:
+            func.pop("address_taken", None)
+            if (func.get("extras") or {}).get("closure"):
+                func["address_taken"] = True
+
+    def mark(self, result: Dict[str, Any]):
+        """解析一个文件的函数值引用"""
+        refs = [
+            ref
+            for ref in result["call_refs"]
+            if (ref.get("hints") or {}).get("value")
+        ]
+        if not refs:
+            return
+        parser = get_parser(result["language"])
+        for ref in refs:
+            for target in parser.resolve_callees(ref, self.functions):
+                func = self._by_id.get(target["callee_id"])
+                if func:
+                    func["address_taken"] = True
+
+    def count(self) -> int:
+        return sum(1 for func in self.functions if func.get("address_taken"))
+
+
+def drop_implicit_refs(file_results: Dict[str, Dict[str, Any]], enabled: Set[str]):
+    """
+    去掉未启用类别的隐式调用引用（hints.implicit，如运算符重载 operator）
+
+    解析结果总是包含这些引用（缓存与选项无关），分析时按选项决定是否加入调用图
+    """
+    for result in file_results.values():
+        result["call_refs"] = [
+            ref
+            for ref in result["call_refs"]
+            if ref["hints"].get("implicit") in (None, *enabled)
+        ]
+
+
 def detect_language(file_path: str) -> Optional[str]:
     """根据文件扩展名检测语言"""
     ext = Path(file_path).suffix
+    if ext in PLUGIN_EXTENSIONS:
+        return PLUGIN_EXTENSIONS[ext]
     for lang, config in LANGUAGE_CONFIG.items():
         if ext in config["extensions"]:
             return lang
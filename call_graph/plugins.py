@@ -0,0 +1,74 @@
+"""
+语言前端插件
+加载第三方语言前端：通过 --plugin 指定的模块，以及已安装包中
+call_graph.languages 入口点声明的 LanguageAnalyzer 子类
+"""
+
+import importlib
+from importlib import metadata
+from typing import Iterable, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .parsers import PARSER_CLASSES, LanguageAnalyzer, register_language
+except ImportError:
+    from parsers import PARSER_CLASSES, LanguageAnalyzer, register_language
+
+__all__ = [
+    "ENTRY_POINT_GROUP",
+    "LanguageAnalyzer",
+    "load_plugins",
+    "loaded_plugins",
+    "register_language",
+]
+
+# 已安装包声明语言前端的入口点分组，例如 pyproject.toml 中：
+# [project.entry-points."call_graph.languages"]
+# zig = "call_graph_zig:ZigAnalyzer"
+ENTRY_POINT_GROUP = "call_graph.languages"
+
+# 已加载的插件模块（多进程分析时在工作进程中重新加载）
+_loaded_modules: List[str] = []
+
+
+def _register(obj, source: str):
+    """注册入口点或模块导出的前端类；函数形式的入口点直接调用"""
+    if isinstance(obj, type) and issubclass(obj, LanguageAnalyzer):
+        if not obj.language_name:
+            raise ValueError(f"{source}: 语言前端没有声明 language_name")
+        register_language(obj.language_name, obj)
+    elif callable(obj):
+        obj()
+    else:
+        raise TypeError(f"{source}: 不是 LanguageAnalyzer 子类或注册函数")
+
+
+def load_plugins(modules: Optional[Iterable[str]] = None) -> List[str]:
+    """
+    加载语言前端插件，返回新注册的语言名称
+
+    Args:
+        modules: 要导入的模块名；模块在导入时调用 register_language，
+            或定义 LANGUAGE_ANALYZERS（LanguageAnalyzer 子类列表）
+    """
+    before = set(PARSER_CLASSES)
+
+    for entry_point in metadata.entry_points(group=ENTRY_POINT_GROUP):
+        try:
+            _register(entry_point.load(), f"入口点 {entry_point.name}")
+        except Exception as e:
+            print(f"警告: 加载语言插件失败 {entry_point.name}: {e}")
+
+    for module_name in modules or []:
+        module = importlib.import_module(module_name)
+        for analyzer_class in getattr(module, "LANGUAGE_ANALYZERS", []):
+            _register(analyzer_class, module_name)
+        if module_name not in _loaded_modules:
+            _loaded_modules.append(module_name)
+
+    return sorted(set(PARSER_CLASSES) - before)
+
+
+def loaded_plugins() -> List[str]:
+    """已加载的插件模块名"""
+    return list(_loaded_modules)
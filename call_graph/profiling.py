@@ -0,0 +1,419 @@
+"""
+运行时剖析数据
+读取采样剖析器记录的调用栈（perf script 的输出、pprof 的 profile.proto、
+火焰图使用的折叠栈格式），把样本数叠加到静态调用图上：函数的包含/自身样本数
+和调用边的样本数，用于找出静态可能的调用路径中实际的热路径
+"""
+
+import gzip
+import re
+from collections import Counter
+from typing import Any, Dict, Iterator, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+PROFILE_FORMATS = ("auto", "collapsed", "perf", "pprof")
+
+# 调用栈：(从根到叶的帧名, 样本数)
+Stack = Tuple[List[str], int]
+
+# 折叠栈的一行：a;b;c 123
+COLLAPSED_LINE = re.compile(r"^(?P<stack>.*\S)\s+(?P<count>\d+)$")
+
+# perf script 中的一帧：地址 符号+偏移 (DSO)
+PERF_FRAME = re.compile(
+    r"^\s+(?:[0-9a-fA-F]+\s+)?(?P<symbol>.+?)(?:\s+\((?P<dso>[^()]*)\))?$"
+)
+
+# Rust 旧式符号修饰的哈希后缀（::h0123456789abcdef）
+RUST_HASH = re.compile(r"::h[0-9a-f]{16}$")
+
+# 符号偏移（+0x1a）
+SYMBOL_OFFSET = re.compile(r"\+0x[0-9a-fA-F]+$")
+
+# 标准库的 crate，其中的帧不按最后两段匹配项目函数
+STD_CRATES = {"std", "core", "alloc"}
+
+# py-spy 等工具的帧名：函数 (文件:行号)
+FRAME_LOCATION = re.compile(r"^(?P<name>.+?) \((?P<file>[^()]+?):(?P<line>\d+)\)$")
+
+
+def parse_collapsed(text: str) -> List[Stack]:
+    """
+    折叠栈格式（stackcollapse-*.pl、inferno、py-spy record -f raw 的输出）：
+    每行为分号分隔、从根到叶的帧和样本数
+
+    Raises:
+        ValueError: 不是折叠栈格式
+    """
+    stacks = []
+    for number, line in enumerate(text.splitlines(), 1):
+        if not line.strip() or line.startswith("#"):
+            continue
+        match = COLLAPSED_LINE.match(line)
+        if not match:
+            raise ValueError(f"第 {number} 行不是折叠栈格式: {line[:80]}")
+        frames = [frame for frame in match.group("stack").split(";") if frame]
+        stacks.append((frames, int(match.group("count"))))
+    return stacks
+
+
+def parse_perf_script(text: str) -> List[Stack]:
+    """
+    perf script 的输出（perf record -g）：每个样本为一行事件和缩进的调用栈
+    （从叶到根），样本之间以空行分隔；每个样本计为 1
+    """
+    stacks = []
+    frames: List[str] = []
+
+    def finish():
+        if frames:
+            stacks.append((list(reversed(frames)), 1))
+            frames.clear()
+
+    for line in text.splitlines():
+        if not line.strip():
+            finish()
+            continue
+        if not line[0].isspace():
+            # 事件行：开始新的样本
+            finish()
+            continue
+        match = PERF_FRAME.match(line)
+        if match:
+            symbol = SYMBOL_OFFSET.sub("", match.group("symbol").strip())
+            if symbol != "[unknown]":
+                frames.append(symbol)
+    finish()
+    return stacks
+
+
+def _varint(data: bytes, position: int) -> Tuple[int, int]:
+    result = 0
+    shift = 0
+    while True:
+        if position >= len(data):
+            raise ValueError("pprof 数据不完整")
+        byte = data[position]
+        position += 1
+        result |= (byte & 0x7F) << shift
+        if not byte & 0x80:
+            return result, position
+        shift += 7
+
+
+def _fields(data: bytes) -> Iterator[Tuple[int, int, Any]]:
+    """protobuf 消息的字段：(字段号, 类型, 值)，值为整数或字节串"""
+    position = 0
+    while position < len(data):
+        key, position = _varint(data, position)
+        number, wire_type = key >> 3, key & 7
+        if wire_type == 0:
+            value, position = _varint(data, position)
+        elif wire_type == 2:
+            length, position = _varint(data, position)
+            value = data[position : position + length]
+            position += length
+        elif wire_type == 1:
+            value = data[position : position + 8]
+            position += 8
+        elif wire_type == 5:
+            value = data[position : position + 4]
+            position += 4
+        else:
+            raise ValueError(f"无法解析的 pprof 数据（字段类型 {wire_type}）")
+        yield number, wire_type, value
+
+
+def _repeated(wire_type: int, value: Any) -> List[int]:
+    """repeated 整数字段（可能是 packed 编码）"""
+    if wire_type == 0:
+        return [value]
+    values = []
+    position = 0
+    while position < len(value):
+        item, position = _varint(value, position)
+        values.append(item)
+    return values
+
+
+def parse_pprof(data: bytes) -> List[Stack]:
+    """
+    pprof 的 profile.proto（可以是 gzip 压缩的），使用第一种样本值（通常为
+    样本数）；内联的函数展开为单独的帧
+    """
+    if data[:2] == b"\x1f\x8b":
+        data = gzip.decompress(data)
+    strings: List[str] = []
+    functions: Dict[int, int] = {}
+    locations: Dict[int, List[int]] = {}
+    samples: List[Tuple[List[int], int]] = []
+    for number, wire_type, value in _fields(data):
+        if number == 2 and wire_type == 2:
+            location_ids: List[int] = []
+            values: List[int] = []
+            for field, field_type, item in _fields(value):
+                if field == 1:
+                    location_ids.extend(_repeated(field_type, item))
+                elif field == 2:
+                    values.extend(_repeated(field_type, item))
+            samples.append((location_ids, values[0] if values else 1))
+        elif number == 4 and wire_type == 2:
+            location_id = 0
+            lines: List[int] = []
+            for field, field_type, item in _fields(value):
+                if field == 1:
+                    location_id = item
+                elif field == 4 and field_type == 2:
+                    for line_field, _, line_value in _fields(item):
+                        if line_field == 1:
+                            lines.append(line_value)
+            locations[location_id] = lines
+        elif number == 5 and wire_type == 2:
+            function_id = name = 0
+            for field, _, item in _fields(value):
+                if field == 1:
+                    function_id = item
+                elif field == 2:
+                    name = item
+            functions[function_id] = name
+        elif number == 6 and wire_type == 2:
+            strings.append(value.decode("utf-8", errors="replace"))
+
+    stacks = []
+    for location_ids, count in samples:
+        frames = []
+        # 第一个位置为叶；同一位置的多行中，最后一行为内联展开的调用者
+        for location_id in reversed(location_ids):
+            for function_id in reversed(locations.get(location_id, [])):
+                name_index = functions.get(function_id, 0)
+                if 0 < name_index < len(strings):
+                    frames.append(strings[name_index])
+        if frames and count:
+            stacks.append((frames, count))
+    return stacks
+
+
+def load_profile(path: str, profile_format: str = "auto") -> List[Stack]:
+    """
+    读取剖析数据；auto 时按内容识别格式（二进制或 gzip 为 pprof，每行以
+    样本数结尾为折叠栈，否则为 perf script 的输出）
+
+    Raises:
+        ValueError: 文件无法读取或格式错误
+    """
+    try:
+        with open(path, "rb") as f:
+            data = f.read()
+    except OSError as e:
+        raise ValueError(f"无法读取剖析数据 {path}: {e}") from e
+    if profile_format == "auto":
+        try:
+            text = data.decode("utf-8")
+        except UnicodeDecodeError:
+            text = None
+        if text is None or data[:2] == b"\x1f\x8b":
+            profile_format = "pprof"
+        else:
+            lines = [line for line in text.splitlines() if line.strip()]
+            collapsed = lines and all(
+                COLLAPSED_LINE.match(line) and not line[0].isspace()
+                for line in lines[:20]
+            )
+            profile_format = "collapsed" if collapsed else "perf"
+    if profile_format == "pprof":
+        return parse_pprof(data)
+    text = data.decode("utf-8", errors="replace")
+    if profile_format == "collapsed":
+        return parse_collapsed(text)
+    return parse_perf_script(text)
+
+
+def _strip_generics(name: str) -> str:
+    """去掉泛型参数（Vec<T>::push -> Vec::push），保留 <T as Trait> 形式的开头"""
+    out = []
+    depth = 0
+    for char in name:
+        if char == "<":
+            depth += 1
+        elif char == ">" and depth:
+            depth -= 1
+        elif not depth:
+            out.append(char)
+    return "".join(out)
+
+
+def normalize_frame(frame: str) -> Tuple[str, Optional[str], Optional[int]]:
+    """
+    把剖析器中的帧名规范化为调用图中的（限定）函数名：(名称, 文件, 行号)
+
+    - 去掉符号偏移、Rust 符号的哈希后缀和泛型参数，
+      <Type as Trait>::method 改写为 Type::method，闭包帧（{{closure}}）
+      归到所在的函数
+    - Go 的 pkg.(*Type).method 改写为 pkg.Type.method
+    - py-spy 等的 函数 (文件:行号) 拆出文件和行号
+    """
+    file_path = line = None
+    match = FRAME_LOCATION.match(frame)
+    if match:
+        frame, file_path = match.group("name"), match.group("file")
+        line = int(match.group("line"))
+    name = SYMBOL_OFFSET.sub("", frame.strip())
+    name = RUST_HASH.sub("", name)
+    if name.startswith("<"):
+        # <Type<T> as Trait<U>>::method
+        depth = 0
+        for index, char in enumerate(name):
+            depth += {"<": 1, ">": -1}.get(char, 0)
+            if not depth:
+                self_type = _strip_generics(name[1:index]).split(" as ")[0]
+                name = self_type.strip() + name[index + 1 :]
+                break
+    name = _strip_generics(name)
+    name = re.sub(r"\.\(\*?(\w+)\)\.", r".\1.", name)
+    # C/C++ 符号的参数列表
+    name = re.sub(r"\(.*\)(\s*const)?$", "", name).strip()
+    parts = [part for part in name.split("::") if part and not part.startswith("{")]
+    return "::".join(parts), file_path, line
+
+
+class ProfileOverlay:
+    """
+    把调用栈中的帧对应到调用图中的函数并统计样本数
+
+    - 帧名按限定名后缀（规则同 query）对应到函数，有文件和行号时只对应到
+      该位置的函数；对应到多个函数时不计入
+    - 包含样本数：栈中出现该函数的样本数（递归时每个样本只计一次）
+    - 自身样本数：栈中最深的、对应到项目函数的帧为该函数的样本数（包括它
+      调用的库函数中的时间）
+    - 调用边的样本数：去掉无法对应的帧（库函数等）后相邻的两个函数；调用图
+      中没有的边记为观察到但静态分析没有发现的调用
+    """
+
+    def __init__(self, graph: CallGraph):
+        self.graph = graph
+        self._frames: Dict[str, Optional[str]] = {}
+
+    def resolve_frame(self, frame: str) -> Optional[str]:
+        """帧对应的节点 ID（无法对应或有歧义时为 None）"""
+        if frame in self._frames:
+            return self._frames[frame]
+        name, file_path, line = normalize_frame(frame)
+        matches = self.graph.resolve(name) if name else []
+        if not matches and "::" in name and name.split("::")[0] not in STD_CRATES:
+            # crate 名或模块路径与调用图不同时按最后两段匹配
+            matches = self.graph.resolve("::".join(name.split("::")[-2:]))
+        if not matches and "." in name:
+            matches = self.graph.resolve(name.rsplit(".", 1)[-1])
+        if file_path and len(matches) > 1:
+            matches = [
+                node_id
+                for node_id in matches
+                if (self.graph.nodes[node_id].get("file") or "").endswith(file_path)
+                and (
+                    line is None
+                    or (self.graph.nodes[node_id].get("start_line") or 0)
+                    <= line
+                    <= (self.graph.nodes[node_id].get("end_line") or 0)
+                )
+            ]
+        node_id = matches[0] if len(matches) == 1 else None
+        self._frames[frame] = node_id
+        return node_id
+
+    def apply(self, stacks: List[Stack]) -> Dict[str, Any]:
+        """
+        统计样本数，写入调用图：节点 extras 中的 samples、self_samples，
+        调用点的 samples（同一对函数的所有调用点相同，剖析数据无法区分调用点）
+
+        Returns:
+            - total: 样本总数；matched: 栈中至少有一个项目函数的样本数
+            - unmatched: {无法对应的帧: 样本数}
+            - unseen_edges: {(调用者, 被调用者): 样本数}，调用图中没有的调用
+        """
+        inclusive: Counter = Counter()
+        exclusive: Counter = Counter()
+        edges: Counter = Counter()
+        unmatched: Counter = Counter()
+        total = matched = 0
+        for frames, count in stacks:
+            total += count
+            path = []
+            for frame in frames:
+                node_id = self.resolve_frame(frame)
+                if node_id is None:
+                    unmatched[frame] += count
+                elif not path or path[-1] != node_id:
+                    path.append(node_id)
+            if not path:
+                continue
+            matched += count
+            for node_id in set(path):
+                inclusive[node_id] += count
+            exclusive[path[-1]] += count
+            for edge in set(zip(path, path[1:])):
+                edges[edge] += count
+
+        for node in self.graph.nodes.values():
+            extras = node.setdefault("extras", {})
+            extras.pop("samples", None)
+            extras.pop("self_samples", None)
+        for relation in self.graph.relations:
+            relation.pop("samples", None)
+        for node_id, count in inclusive.items():
+            extras = self.graph.nodes[node_id]["extras"]
+            extras["samples"] = count
+            extras["self_samples"] = exclusive.get(node_id, 0)
+        unseen = {}
+        for (caller_id, callee_id), count in edges.items():
+            sites = self.graph.call_sites(caller_id, callee_id)
+            if not sites:
+                unseen[(caller_id, callee_id)] = count
+            for site in sites:
+                site["samples"] = count
+        return {
+            "total": total,
+            "matched": matched,
+            "unmatched": dict(unmatched.most_common()),
+            "unseen_edges": dict(
+                sorted(unseen.items(), key=lambda item: -item[1])
+            ),
+        }
+
+
+def apply_profile(graph: CallGraph, stacks: List[Stack]) -> Dict[str, Any]:
+    """把剖析数据叠加到调用图上（见 ProfileOverlay.apply）"""
+    return ProfileOverlay(graph).apply(stacks)
+
+
+def hot_functions(graph: CallGraph, limit: int = 0) -> List[str]:
+    """有样本的函数，按包含样本数降序"""
+    ranked = sorted(
+        (
+            node_id
+            for node_id, node in graph.nodes.items()
+            if (node.get("extras") or {}).get("samples")
+        ),
+        key=lambda node_id: (
+            -graph.nodes[node_id]["extras"]["samples"],
+            graph.qualified_name(node_id),
+        ),
+    )
+    return ranked[:limit] if limit else ranked
+
+
+def profile_summary(graph: CallGraph, summary: Dict[str, Any]) -> str:
+    """叠加结果的一行摘要"""
+    total = summary["total"]
+    percent = summary["matched"] / total * 100 if total else 0.0
+    text = (
+        f"剖析数据: {total} 个样本，{summary['matched']} 个（{percent:.1f}%）"
+        f"落在 {len(hot_functions(graph))} 个项目函数中"
+    )
+    if summary["unseen_edges"]:
+        text += f"，{len(summary['unseen_edges'])} 个观察到的调用不在静态调用图中"
+    return text
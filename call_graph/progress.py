@@ -0,0 +1,153 @@
+"""
+进度与日志
+分析各阶段（扫描文件、解析、解析调用关系、保存）的进度条，以及可供诊断的
+结构化日志（--verbose、--log-format json）
+"""
+
+import json
+import logging
+import sys
+import time
+from typing import Any, Optional, TextIO
+
+LOGGER_NAME = "call_graph"
+
+LOG_FORMATS = ("text", "json")
+
+logger = logging.getLogger(LOGGER_NAME)
+# 未调用 configure_logging 时（如作为库使用）不输出日志
+logger.addHandler(logging.NullHandler())
+
+
+class _TextFormatter(logging.Formatter):
+    """时间 级别 事件 消息 key=value ..."""
+
+    def format(self, record: logging.LogRecord) -> str:
+        fields = getattr(record, "fields", {})
+        parts = [
+            time.strftime("%H:%M:%S", time.localtime(record.created)),
+            record.levelname,
+            getattr(record, "event", ""),
+            record.getMessage(),
+        ]
+        parts.extend(f"{key}={value}" for key, value in fields.items())
+        return " ".join(part for part in parts if part)
+
+
+class _JsonFormatter(logging.Formatter):
+    """每条日志一行 JSON：time、level、event、message 及事件字段"""
+
+    def format(self, record: logging.LogRecord) -> str:
+        entry = {
+            "time": round(record.created, 3),
+            "level": record.levelname.lower(),
+            "event": getattr(record, "event", None),
+            "message": record.getMessage(),
+        }
+        entry.update(getattr(record, "fields", {}))
+        return json.dumps(entry, ensure_ascii=False, default=str)
+
+
+def configure_logging(
+    verbose: bool = False, log_format: str = "text", stream: Optional[TextIO] = None
+):
+    """
+    按命令行选项配置日志（输出到标准错误）
+
+    - text 格式只在 verbose 时输出（屏幕上已有进度和统计信息）
+    - json 格式输出 info 级别的阶段事件，verbose 时另外输出每个文件的 debug 事件
+    """
+    for handler in list(logger.handlers):
+        if not isinstance(handler, logging.NullHandler):
+            logger.removeHandler(handler)
+    logger.propagate = False
+    if log_format == "text" and not verbose:
+        logger.setLevel(logging.WARNING)
+        return
+    handler = logging.StreamHandler(stream or sys.stderr)
+    handler.setFormatter(_JsonFormatter() if log_format == "json" else _TextFormatter())
+    logger.addHandler(handler)
+    logger.setLevel(logging.DEBUG if verbose else logging.INFO)
+
+
+def log_event(event: str, message: str, level: int = logging.INFO, **fields: Any):
+    """
+    记录一个结构化事件
+
+    Args:
+        event: 事件名（如 parse.done），便于按事件过滤 JSON 日志
+        message: 可读的说明
+        fields: 事件字段（文件数、耗时等）
+    """
+    if logger.isEnabledFor(level):
+        logger.log(level, message, extra={"event": event, "fields": fields})
+
+
+class ProgressBar:
+    """
+    单行进度条（写到标准错误，不混入导出到标准输出的内容）
+
+    total 为 None 时（如扫描文件，总数未知）只显示已处理的数量；进度每前进
+    every 才重绘一次；enabled 为 False 或标准错误不是终端时不显示
+    """
+
+    BAR_LENGTH = 40
+
+    def __init__(
+        self,
+        task: str,
+        total: Optional[int] = None,
+        enabled: bool = True,
+        stream: Optional[TextIO] = None,
+        every: int = 1,
+    ):
+        self.task = task
+        self.total = total
+        self.stream = stream or sys.stderr
+        self.enabled = enabled and self.stream.isatty()
+        self.every = max(1, every)
+        self.current = 0
+        self.started = time.time()
+        self._drawn = 0
+        self._shown = False
+
+    def update(self, current: Optional[int] = None):
+        """更新进度（不指定 current 时加一）"""
+        self.current = self.current + 1 if current is None else current
+        if not self.enabled:
+            return
+        if self.current - self._drawn < self.every and self.current != self.total:
+            return
+        self._draw()
+
+    def _draw(self):
+        if self.total:
+            percent = self.current / self.total * 100
+            filled = int(self.BAR_LENGTH * self.current / self.total)
+            bar = "█" * filled + "░" * (self.BAR_LENGTH - filled)
+            text = f"{self.task}: [{bar}] {self.current}/{self.total} ({percent:.1f}%)"
+        else:
+            text = f"{self.task}: {self.current}"
+        self.stream.write(f"\r{text}")
+        self.stream.flush()
+        self._drawn = self.current
+        self._shown = True
+
+    @property
+    def elapsed(self) -> float:
+        return time.time() - self.started
+
+    def close(self):
+        """结束进度条（换行）"""
+        if self.enabled and self.current != self._drawn:
+            self._draw()
+        if self._shown:
+            self.stream.write("\n")
+            self.stream.flush()
+            self._shown = False
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, exc_type, exc_val, exc_tb):
+        self.close()
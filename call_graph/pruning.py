@@ -0,0 +1,126 @@
+"""
+大图裁剪
+导出的图节点过多时只保留中心度最高的函数，被裁掉的调用者/被调用者合并为
+“… 还有 k 个”占位节点，使图无论项目多大都保持可读
+"""
+
+from typing import Dict, List, Set
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+    from .metrics import CENTRALITY_METHODS, betweenness_centrality, pagerank
+except ImportError:
+    from graph import CallGraph
+    from metrics import CENTRALITY_METHODS, betweenness_centrality, pagerank
+
+# 裁剪时默认使用的中心度：PageRank 与边数成线性，介数中心度在大图上较慢
+DEFAULT_PRUNE_CENTRALITY = "pagerank"
+
+
+def _ranking(graph: CallGraph, centrality: str) -> List[str]:
+    """按中心度从高到低排列的节点，相同时按限定名"""
+    if centrality not in CENTRALITY_METHODS:
+        raise ValueError(
+            f"未知的中心度: {centrality}（可选: {', '.join(CENTRALITY_METHODS)}）"
+        )
+    if centrality == "betweenness":
+        scores = betweenness_centrality(graph)
+    else:
+        scores = pagerank(graph)
+    return sorted(
+        graph.nodes,
+        key=lambda n: (-scores[n], graph.qualified_name(n), n),
+    )
+
+
+def _elided_neighbors(
+    graph: CallGraph, keep: Set[str]
+) -> Dict[str, Dict[str, List[str]]]:
+    """每个保留的函数被裁掉的调用者和被调用者 {节点ID: {"callers", "callees"}}"""
+    elided = {}
+    for node_id in keep:
+        callers = [n for n in graph.predecessors(node_id) if n not in keep]
+        callees = [n for n in graph.successors(node_id) if n not in keep]
+        if callers or callees:
+            elided[node_id] = {"callers": sorted(callers), "callees": sorted(callees)}
+    return elided
+
+
+def _placeholder_count(elided: Dict[str, Dict[str, List[str]]]) -> int:
+    """需要的占位节点数量：每个函数的调用者和被调用者各一个"""
+    return sum(bool(s["callers"]) + bool(s["callees"]) for s in elided.values())
+
+
+def prune(
+    graph: CallGraph, max_nodes: int, centrality: str = DEFAULT_PRUNE_CENTRALITY
+) -> CallGraph:
+    """
+    把调用图裁剪到最多 max_nodes 个节点（含占位节点）
+
+    按中心度（见 metrics.CENTRALITY_METHODS）保留最重要的函数；每个保留的函数
+    被裁掉的被调用者合并为一个占位节点（kind 为 elided，ID 为
+    elided:<节点ID>:callees），被裁掉的调用者同样合并为 elided:<节点ID>:callers，
+    extras.members 为被合并的函数 ID，extras.functions 为数量。占位节点与所连的
+    函数位于同一模块；保留的函数之间的调用点都保留，到占位节点的边的计数为
+    被合并的调用点数量。节点数不超过 max_nodes 时原样返回
+    """
+    if max_nodes < 1:
+        raise ValueError(f"最大节点数必须大于 0: {max_nodes}")
+    if len(graph.nodes) <= max_nodes:
+        return graph
+    ranking = _ranking(graph, centrality)
+
+    # 保留的函数和占位节点一起不超过 max_nodes：按超出的数量逐步减少保留的函数
+    count = max_nodes
+    while True:
+        keep = set(ranking[:count])
+        elided = _elided_neighbors(graph, keep)
+        overflow = count + _placeholder_count(elided) - max_nodes
+        if overflow <= 0 or count == 1:
+            break
+        count = max(1, count - (overflow + 1) // 2)
+
+    pruned = graph.subgraph(ranking[:count])
+    placeholder = {}
+    for node_id, sides in sorted(elided.items()):
+        node = graph.nodes[node_id]
+        extras = node.get("extras") or {}
+        for side, members in sides.items():
+            if not members:
+                continue
+            placeholder_id = f"elided:{node_id}:{side}"
+            for member in members:
+                placeholder[(node_id, member, side)] = placeholder_id
+            pruned.add_node(
+                {
+                    "id": placeholder_id,
+                    "name": f"… 还有 {len(members)} 个",
+                    "kind": "elided",
+                    "file": node["file"],
+                    "start_line": None,
+                    "end_line": None,
+                    "language": node.get("language"),
+                    "is_exported": 0,
+                    "extras": {
+                        "members": members,
+                        "functions": len(members),
+                        "crate": extras.get("crate"),
+                        "module": extras.get("module"),
+                        "compile_time": extras.get("compile_time"),
+                    },
+                }
+            )
+    for relation in graph.relations:
+        caller_id, callee_id = relation["caller_id"], relation["callee_id"]
+        if caller_id in keep and (caller_id, callee_id, "callees") in placeholder:
+            target = placeholder[(caller_id, callee_id, "callees")]
+            pruned.add_relation({**relation, "callee_id": target})
+        if callee_id in keep and (callee_id, caller_id, "callers") in placeholder:
+            source = placeholder[(callee_id, caller_id, "callers")]
+            pruned.add_relation({**relation, "caller_id": source})
+    print(
+        f"节点过多，只保留中心度最高的 {count} 个函数"
+        f"（共 {len(graph.nodes)} 个，--max-nodes {max_nodes}）"
+    )
+    return pruned
@@ -0,0 +1,336 @@
+"""
+查询表达式
+在调用图上求值的小型集合表达式语言，如
+callers(db::write) & reachable_from(main)、fanin(>20) - kind(closure)，
+结果为函数（节点 ID）集合
+"""
+
+import operator
+import re
+from typing import Callable, Dict, List, Optional, Set, Tuple, Union
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import find_entry_points, reachable_from
+    from .filters import Pattern
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import find_entry_points, reachable_from
+    from filters import Pattern
+    from graph import CallGraph
+
+TOKEN_PATTERN = re.compile(
+    r"""\s*(?:
+        (?P<string>"[^"]*"|'[^']*')
+        |(?P<compare>>=|<=|==|!=|>|<|=)
+        |(?P<op>[&|!(),-])
+        |(?P<number>\d+\b)
+        |(?P<name>[^\s&|!(),"'<>=-]+)
+    )""",
+    re.VERBOSE,
+)
+
+COMPARISONS = {
+    ">": operator.gt,
+    ">=": operator.ge,
+    "<": operator.lt,
+    "<=": operator.le,
+    "=": operator.eq,
+    "==": operator.eq,
+    "!=": operator.ne,
+}
+
+# 语法树：("name", 函数名或模式) / ("number", 值) / ("compare", 运算符, 值) /
+# ("call", 查询函数, [参数]) / ("not", 子树) / (运算符 & | -, 左, 右)
+Expr = Tuple
+
+
+class QueryError(ValueError):
+    """查询表达式的语法错误或求值错误"""
+
+
+def _tokenize(text: str) -> List[Tuple[str, str, int]]:
+    """(类别, 文本, 位置)"""
+    tokens = []
+    position = 0
+    while position < len(text):
+        if text[position:].isspace():
+            break
+        match = TOKEN_PATTERN.match(text, position)
+        if not match or match.end() == position:
+            char = text[position:].strip()[0]
+            raise QueryError(f"无法识别的字符 '{char}'（位置 {position}）")
+        kind = match.lastgroup
+        value = match.group(kind)
+        if kind == "string":
+            value = value[1:-1]
+        tokens.append((kind, value, match.start(kind)))
+        position = match.end()
+    return tokens
+
+
+class _Parser:
+    """
+    递归下降解析，优先级从低到高：| 和 -（左结合）、&、! 前缀
+
+    expr := term (("|" | "-") term)*
+    term := unary ("&" unary)*
+    unary := "!" unary | "(" expr ")" | NAME "(" [arg ("," arg)*] ")" | NAME | STRING
+    arg := expr | NUMBER | COMPARE NUMBER
+    """
+
+    def __init__(self, text: str):
+        self.tokens = _tokenize(text)
+        self.index = 0
+
+    def peek(self) -> Optional[Tuple[str, str, int]]:
+        return self.tokens[self.index] if self.index < len(self.tokens) else None
+
+    def take(self, value: Optional[str] = None) -> Tuple[str, str, int]:
+        token = self.peek()
+        if token is None:
+            missing = f"，缺少 '{value}'" if value else ""
+            raise QueryError(f"表达式意外结束{missing}")
+        if value is not None and token[1] != value:
+            raise QueryError(
+                f"应为 '{value}'，实际为 '{token[1]}'（位置 {token[2]}）"
+            )
+        self.index += 1
+        return token
+
+    def at(self, *values: str) -> bool:
+        token = self.peek()
+        return token is not None and token[0] == "op" and token[1] in values
+
+    def parse(self) -> Expr:
+        if not self.tokens:
+            raise QueryError("查询表达式为空")
+        expr = self.expr()
+        token = self.peek()
+        if token is not None:
+            raise QueryError(f"多余的 '{token[1]}'（位置 {token[2]}）")
+        return expr
+
+    def expr(self) -> Expr:
+        left = self.term()
+        while self.at("|", "-"):
+            op = self.take()[1]
+            left = (op, left, self.term())
+        return left
+
+    def term(self) -> Expr:
+        left = self.unary()
+        while self.at("&"):
+            self.take()
+            left = ("&", left, self.unary())
+        return left
+
+    def unary(self) -> Expr:
+        if self.at("!"):
+            self.take()
+            return ("not", self.unary())
+        if self.at("("):
+            self.take()
+            expr = self.expr()
+            self.take(")")
+            return expr
+        kind, value, position = self.take()
+        if kind == "string":
+            return ("name", value)
+        if kind != "name":
+            raise QueryError(
+                f"应为函数名或查询函数，实际为 '{value}'（位置 {position}）"
+            )
+        if not self.at("("):
+            return ("name", value)
+        self.take("(")
+        args = []
+        if not self.at(")"):
+            args.append(self.arg())
+            while self.at(","):
+                self.take()
+                args.append(self.arg())
+        self.take(")")
+        return ("call", value, args)
+
+    def arg(self) -> Expr:
+        token = self.peek()
+        if token and token[0] == "number":
+            self.take()
+            return ("number", int(token[1]))
+        if token and token[0] == "compare":
+            self.take()
+            kind, value, position = self.take()
+            if kind != "number":
+                raise QueryError(f"比较运算符后应为数字（位置 {position}）")
+            return ("compare", token[1], int(value))
+        return self.expr()
+
+
+def parse_query(text: str) -> Expr:
+    """
+    解析查询表达式
+
+    Raises:
+        QueryError: 语法错误
+    """
+    return _Parser(text).parse()
+
+
+def _fan_in(graph: CallGraph, node_id: str) -> int:
+    return sum(1 for n in graph.predecessors(node_id) if n != node_id)
+
+
+def _fan_out(graph: CallGraph, node_id: str) -> int:
+    return sum(1 for n in graph.successors(node_id) if n != node_id)
+
+
+def _reaching(graph: CallGraph, targets: Set[str]) -> Set[str]:
+    """可以到达 targets 的所有节点（包括 targets 本身）"""
+    return graph.compact().reachable(targets, reverse=True)
+
+
+class QueryEvaluator:
+    """
+    在调用图上求值查询表达式
+
+    - 函数名（process、db::write）按名称或限定名后缀匹配节点；找不到时报错
+    - 含 * 或 ? 的名称和 re: 开头的名称按过滤模式匹配（同 --include），
+      包含特殊字符的名称可以加引号，如 "re:^app::(a|b)$"
+    - 查询函数见 functions（用法 -> 处理函数）
+    - 运算符：a & b 交集，a | b 并集，a - b 差集，!a 补集
+    """
+
+    def __init__(self, graph: CallGraph):
+        self.graph = graph
+        self._entry_points: Optional[Set[str]] = None
+        # {查询函数: (参数说明，无参数时为空, 处理函数)}
+        self.functions: Dict[str, Tuple[str, Callable[..., Set[str]]]] = {
+            "callers": ("X", self._callers),
+            "callees": ("X", self._callees),
+            "reachable_from": ("X", self._reachable_from),
+            "reaches": ("X", self._reaches),
+            "fanin": (">N", self._fanin),
+            "fanout": (">N", self._fanout),
+            "match": ("模式", self._match),
+            "kind": ("类别", self._kind),
+            "roots": ("", self._roots),
+            "all": ("", self._all),
+        }
+
+    def evaluate(self, query: Union[str, Expr]) -> Set[str]:
+        """
+        Raises:
+            QueryError: 语法错误、未知的查询函数、参数错误或找不到函数名
+        """
+        expr = parse_query(query) if isinstance(query, str) else query
+        return self._eval(expr)
+
+    def _eval(self, expr: Expr) -> Set[str]:
+        kind = expr[0]
+        if kind == "name":
+            return self._name(expr[1])
+        if kind == "not":
+            return set(self.graph.nodes) - self._eval(expr[1])
+        if kind == "&":
+            return self._eval(expr[1]) & self._eval(expr[2])
+        if kind == "|":
+            return self._eval(expr[1]) | self._eval(expr[2])
+        if kind == "-":
+            return self._eval(expr[1]) - self._eval(expr[2])
+        if kind == "call":
+            return self._call(expr[1], expr[2])
+        raise QueryError("数字和比较只能作为 fanin/fanout 的参数")
+
+    def _name(self, text: str) -> Set[str]:
+        if text.startswith("re:") or any(ch in text for ch in "*?["):
+            return self._match(("name", text))
+        matches = set(self.graph.resolve(text))
+        if not matches:
+            raise QueryError(f"找不到函数: {text}")
+        return matches
+
+    def _call(self, name: str, args: List[Expr]) -> Set[str]:
+        if name not in self.functions:
+            known = ", ".join(sorted(self.functions))
+            raise QueryError(f"未知的查询函数: {name}（可用: {known}）")
+        expected, handler = self.functions[name]
+        if bool(expected) != bool(args) or len(args) > 1:
+            usage = f"{name}({expected})" if expected else f"{name}()"
+            raise QueryError(f"参数错误，用法: {usage}")
+        return handler(*args)
+
+    def _text_arg(self, name: str, arg: Expr) -> str:
+        if arg[0] != "name":
+            raise QueryError(f"{name} 的参数应为名称或模式")
+        return arg[1]
+
+    def _callers(self, arg: Expr) -> Set[str]:
+        targets = self._eval(arg)
+        return {pred for node in targets for pred in self.graph.predecessors(node)}
+
+    def _callees(self, arg: Expr) -> Set[str]:
+        sources = self._eval(arg)
+        return {succ for node in sources for succ in self.graph.successors(node)}
+
+    def _reachable_from(self, arg: Expr) -> Set[str]:
+        return reachable_from(self.graph, self._eval(arg))
+
+    def _reaches(self, arg: Expr) -> Set[str]:
+        return _reaching(self.graph, self._eval(arg))
+
+    def _compare(self, name: str, arg: Expr, measure) -> Set[str]:
+        if arg[0] == "number":
+            compare, value = operator.eq, arg[1]
+        elif arg[0] == "compare":
+            compare, value = COMPARISONS[arg[1]], arg[2]
+        else:
+            raise QueryError(f"{name} 的参数应为比较，如 {name}(>20)")
+        return {
+            node_id
+            for node_id in self.graph.nodes
+            if compare(measure(self.graph, node_id), value)
+        }
+
+    def _fanin(self, arg: Expr) -> Set[str]:
+        return self._compare("fanin", arg, _fan_in)
+
+    def _fanout(self, arg: Expr) -> Set[str]:
+        return self._compare("fanout", arg, _fan_out)
+
+    def _match(self, arg: Expr) -> Set[str]:
+        try:
+            pattern = Pattern(self._text_arg("match", arg))
+        except ValueError as e:
+            raise QueryError(str(e)) from e
+        return {
+            node_id
+            for node_id in self.graph.nodes
+            if pattern.matches_node(self.graph, node_id)
+        }
+
+    def _kind(self, arg: Expr) -> Set[str]:
+        kind = self._text_arg("kind", arg)
+        return {
+            node_id
+            for node_id in self.graph.nodes
+            if self.graph.node_kind(node_id) == kind
+        }
+
+    def _roots(self) -> Set[str]:
+        if self._entry_points is None:
+            self._entry_points = set(find_entry_points(self.graph))
+        return set(self._entry_points)
+
+    def _all(self) -> Set[str]:
+        return set(self.graph.nodes)
+
+
+def evaluate_query(graph: CallGraph, query: str) -> Set[str]:
+    """
+    在调用图上求值查询表达式，返回匹配的节点 ID 集合
+
+    Raises:
+        QueryError: 语法错误或求值错误
+    """
+    return QueryEvaluator(graph).evaluate(query)
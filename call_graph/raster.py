@@ -0,0 +1,165 @@
+"""
+光栅绘制
+内置 PNG 渲染使用的最小画布：矩形、线段、三角形和 5x7 点阵字体，
+直接用 zlib 编码为 PNG，不依赖图像库
+"""
+
+import struct
+import zlib
+from typing import List, Optional, Tuple
+
+Color = Tuple[int, int, int]
+
+# 5x7 点阵字体，覆盖 ASCII 0x20~0x7e：每个字符 5 列，每列一个字节，最低位为最上一行
+FONT_FIRST = 0x20
+FONT_5X7 = bytes.fromhex(
+    "0000000000" "00005f0000" "0007000700" "147f147f14" "242a7f2a12"
+    "2313086462" "3649562050" "0005030000" "001c224100" "0041221c00"
+    "14083e0814" "08083e0808" "0050300000" "0808080808" "0060600000"
+    "2010080402" "3e5149453e" "00427f4000" "4261514946" "2141454b31"
+    "1814127f10" "2745454539" "3c4a494930" "0171090503" "3649494936"
+    "064949291e" "0036360000" "0056360000" "0814224100" "1414141414"
+    "0041221408" "0201510906" "324979413e" "7e1111117e" "7f49494936"
+    "3e41414122" "7f4141221c" "7f49494941" "7f09090901" "3e4149497a"
+    "7f0808087f" "00417f4100" "2040413f01" "7f08142241" "7f40404040"
+    "7f020c027f" "7f0408107f" "3e4141413e" "7f09090906" "3e4151215e"
+    "7f09192946" "4649494931" "01017f0101" "3f4040403f" "1f2040201f"
+    "3f4038403f" "6314081463" "0708700807" "6151494543" "007f414100"
+    "0204081020" "0041417f00" "0402010204" "4040404040" "0001020400"
+    "2054545478" "7f48444438" "3844444420" "384444487f" "3854545418"
+    "087e090102" "0c5252523e" "7f08040478" "00447d4000" "2040443d00"
+    "007f102844" "00417f4000" "7c04180478" "7c08040478" "3844444438"
+    "7c14141408" "081414187c" "7c08040408" "4854545420" "043f444020"
+    "3c4040207c" "1c2040201c" "3c4030403c" "4428102844" "0c5050503c"
+    "4464544c44" "0008364100" "00007f0000" "0041360800" "1008081008"
+)
+GLYPH_WIDTH = 5
+GLYPH_HEIGHT = 7
+
+
+def parse_color(text: str) -> Color:
+    """#rrggbb -> (r, g, b)"""
+    text = text.lstrip("#")
+    return int(text[0:2], 16), int(text[2:4], 16), int(text[4:6], 16)
+
+
+def text_width(text: str, scale: int) -> int:
+    """文本的像素宽度（字符之间留 1 列空白）"""
+    return len(text) * (GLYPH_WIDTH + 1) * scale
+
+
+class Canvas:
+    """RGB 画布，坐标超出范围的像素被忽略"""
+
+    def __init__(self, width: int, height: int, background: Color = (255, 255, 255)):
+        self.width = width
+        self.height = height
+        self.pixels = bytearray(bytes(background) * (width * height))
+
+    def pixel(self, x: int, y: int, color: Color):
+        if 0 <= x < self.width and 0 <= y < self.height:
+            offset = (y * self.width + x) * 3
+            self.pixels[offset : offset + 3] = bytes(color)
+
+    def fill_rect(self, x0: int, y0: int, x1: int, y1: int, color: Color):
+        x0, x1 = max(x0, 0), min(x1, self.width - 1)
+        if x0 > x1:
+            return
+        row = bytes(color) * (x1 - x0 + 1)
+        for y in range(max(y0, 0), min(y1, self.height - 1) + 1):
+            offset = (y * self.width + x0) * 3
+            self.pixels[offset : offset + len(row)] = row
+
+    def rect(self, x0: int, y0: int, x1: int, y1: int, color: Color):
+        """矩形边框"""
+        self.fill_rect(x0, y0, x1, y0, color)
+        self.fill_rect(x0, y1, x1, y1, color)
+        self.fill_rect(x0, y0, x0, y1, color)
+        self.fill_rect(x1, y0, x1, y1, color)
+
+    def line(
+        self,
+        x0: int,
+        y0: int,
+        x1: int,
+        y1: int,
+        color: Color,
+        dash: Optional[Tuple[int, int]] = None,
+    ):
+        """Bresenham 线段；dash 为（实线像素数, 空白像素数）"""
+        dx, dy = abs(x1 - x0), -abs(y1 - y0)
+        sx = 1 if x0 < x1 else -1
+        sy = 1 if y0 < y1 else -1
+        error = dx + dy
+        step = 0
+        while True:
+            if dash is None or step % (dash[0] + dash[1]) < dash[0]:
+                self.pixel(x0, y0, color)
+            if x0 == x1 and y0 == y1:
+                break
+            doubled = 2 * error
+            if doubled >= dy:
+                error += dy
+                x0 += sx
+            if doubled <= dx:
+                error += dx
+                y0 += sy
+            step += 1
+
+    def triangle(self, points: List[Tuple[float, float]], color: Color):
+        """填充三角形（逐行扫描）"""
+        top = int(min(y for _, y in points))
+        bottom = int(max(y for _, y in points)) + 1
+        edges = list(zip(points, points[1:] + points[:1]))
+        for y in range(top, bottom + 1):
+            center = y + 0.5
+            xs = []
+            for (xa, ya), (xb, yb) in edges:
+                if (ya <= center < yb) or (yb <= center < ya):
+                    xs.append(xa + (center - ya) * (xb - xa) / (yb - ya))
+            if len(xs) >= 2:
+                self.fill_rect(round(min(xs)), y, round(max(xs)), y, color)
+
+    def text(self, x: int, y: int, text: str, color: Color, scale: int = 1):
+        """
+        从左上角 (x, y) 开始绘制文本
+
+        字体只覆盖 ASCII，其他字符（如中文）绘制为空心方框
+        """
+        advance = (GLYPH_WIDTH + 1) * scale
+        for char in text:
+            code = ord(char)
+            if FONT_FIRST <= code < FONT_FIRST + len(FONT_5X7) // GLYPH_WIDTH:
+                offset = (code - FONT_FIRST) * GLYPH_WIDTH
+                for column, bits in enumerate(FONT_5X7[offset : offset + GLYPH_WIDTH]):
+                    for row in range(GLYPH_HEIGHT):
+                        if bits >> row & 1:
+                            px = x + column * scale
+                            py = y + row * scale
+                            self.fill_rect(
+                                px, py, px + scale - 1, py + scale - 1, color
+                            )
+            else:
+                right = x + GLYPH_WIDTH * scale - 1
+                self.rect(x, y, right, y + GLYPH_HEIGHT * scale - 1, color)
+            x += advance
+
+    def to_png(self) -> bytes:
+        """编码为 8 位 RGB PNG"""
+        stride = self.width * 3
+        raw = bytearray()
+        for y in range(self.height):
+            raw.append(0)  # 过滤类型 None
+            raw.extend(self.pixels[y * stride : (y + 1) * stride])
+
+        def chunk(kind: bytes, data: bytes) -> bytes:
+            crc = zlib.crc32(kind + data) & 0xFFFFFFFF
+            return struct.pack(">I", len(data)) + kind + data + struct.pack(">I", crc)
+
+        header = struct.pack(">IIBBBBB", self.width, self.height, 8, 2, 0, 0, 0)
+        return (
+            b"\x89PNG\r\n\x1a\n"
+            + chunk(b"IHDR", header)
+            + chunk(b"IDAT", zlib.compress(bytes(raw), 9))
+            + chunk(b"IEND", b"")
+        )
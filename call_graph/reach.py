@@ -0,0 +1,83 @@
+"""
+多入口可达性对比
+分别计算从几组入口函数（如各个二进制目标的 main）出发可达的函数，列出只能从
+其中一组到达的函数、各组共同需要的函数，并按模块汇总，用于把单体程序拆分为
+多个二进制目标（或 crate）前确认每个目标实际需要哪些代码
+"""
+
+from typing import Any, Dict, List
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+
+def compare_reach(
+    graph: CallGraph, root_sets: Dict[str, List[str]]
+) -> Dict[str, Any]:
+    """
+    对比从每组入口函数出发可达的函数
+
+    Args:
+        root_sets: {名称: [入口函数ID]}，按给定的顺序对比
+
+    Returns:
+        roots: [{"label", "roots", "reachable"}]，reachable 为可达函数数量
+        （含入口本身）；
+        shared: 从每组入口都可达的函数ID；
+        only: {名称: [只从这组入口可达的函数ID]}；
+        partial: 从多组（但不是所有组）入口可达的函数 [{"id", "labels"}]，
+        只有两组以上时才会出现；
+        modules: [{"module", "labels", "functions": {名称: 可达函数数量}}]，
+        labels 为需要这个模块的组；
+        以上函数均按限定名排序，模块按名称排序
+    """
+    compact = graph.compact()
+    reachable = {label: compact.reachable(roots) for label, roots in root_sets.items()}
+    labels = list(root_sets)
+
+    reached_by: Dict[str, List[str]] = {}
+    for label in labels:
+        for node_id in reachable[label]:
+            reached_by.setdefault(node_id, []).append(label)
+
+    shared, partial = [], []
+    only: Dict[str, List[str]] = {label: [] for label in labels}
+    modules: Dict[str, Dict[str, int]] = {}
+    for node_id in sorted(reached_by, key=graph.qualified_name):
+        by = reached_by[node_id]
+        if len(by) == len(labels):
+            shared.append(node_id)
+        elif len(by) == 1:
+            only[by[0]].append(node_id)
+        else:
+            partial.append({"id": node_id, "labels": by})
+        counts = modules.setdefault(graph.module_name(node_id), {})
+        for label in by:
+            counts[label] = counts.get(label, 0) + 1
+
+    return {
+        "roots": [
+            {
+                "label": label,
+                "roots": sorted(root_sets[label], key=graph.qualified_name),
+                "reachable": len(reachable[label]),
+            }
+            for label in labels
+        ],
+        "shared": shared,
+        "only": only,
+        "partial": partial,
+        "modules": [
+            {
+                "module": module,
+                "labels": [label for label in labels if label in counts],
+                "functions": {
+                    label: counts[label] for label in labels if label in counts
+                },
+            }
+            for module, counts in sorted(modules.items())
+        ],
+    }
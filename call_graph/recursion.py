@@ -0,0 +1,384 @@
+"""
+递归深度估计
+对每个递归环启发式地判断递归是否有界（递归调用传入了明显递减的参数，如
+n - 1、xs[1..]、node.left），并可结合从二进制文件的 DWARF 调用帧信息
+（.eh_frame / .debug_frame）估计的栈帧大小，计算给定栈大小下的最大递归深度，
+标出嵌入式目标上可能的栈溢出
+"""
+
+import re
+import subprocess
+from typing import Any, Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .binary import symbol_path
+    from .graph import CallGraph
+except ImportError:
+    from binary import symbol_path
+    from graph import CallGraph
+
+READELF_TIMEOUT = 600
+
+# 参数中不是参数名的关键字和接收者
+PARAMETER_KEYWORDS = {"mut", "ref", "const", "final", "self", "cls", "this"}
+
+# 结构递归：参数的这些字段是更小的子结构（树的子节点、链表的下一个节点等）
+STRUCTURAL_FIELDS = (
+    "left",
+    "right",
+    "next",
+    "prev",
+    "child",
+    "children",
+    "tail",
+    "rest",
+    "lhs",
+    "rhs",
+    "inner",
+    "body",
+    "operand",
+    "subtree",
+)
+
+# 返回参数的一部分的方法（切片、子串）
+SLICING_METHODS = (
+    "split_at",
+    "split_first",
+    "split_last",
+    "subList",
+    "substring",
+    "slice",
+)
+
+# readelf --debug-dump=frames-interp 的输出
+FDE_HEADER = re.compile(r"\bFDE cie=([0-9a-f]+) pc=([0-9a-f]+)\.\.([0-9a-f]+)")
+CIE_HEADER = re.compile(r"^([0-9a-f]+) [0-9a-f]+ [0-9a-f]+ CIE\b")
+CFA_ROW = re.compile(r"^[0-9a-f]+ +([a-z]\w*)\+(\d+)\b")
+
+# 栈指针寄存器（x86、AArch64 / RISC-V、ARM 的 CFA 规则中的名称）
+STACK_POINTERS = {"rsp", "esp", "sp", "r13"}
+
+# nm 输出的函数符号：地址 类型 名称
+NM_LINE = re.compile(r"^([0-9a-fA-F]+) [tTwW] (.+)$")
+
+
+def parameter_names(signature: Optional[str], name: str, language: str) -> List[str]:
+    """
+    从函数签名中取出参数名（不含 self 等接收者）
+
+    Rust / Python 等 "名称: 类型" 取冒号前的部分，Go 取第一个标识符，Java、C
+    等 "类型 名称" 取最后一个标识符；默认值被忽略
+    """
+    if not signature:
+        return []
+    start = signature.find(name)
+    start = start + len(name) if start >= 0 else 0
+    # 跳过泛型参数和 Go 方法的接收者，取函数名之后的第一个圆括号
+    depth = 0
+    for index in range(start, len(signature)):
+        char = signature[index]
+        if char == "<":
+            depth += 1
+        elif char == ">" and depth:
+            depth -= 1
+        elif char == "(" and not depth:
+            start = index
+            break
+    else:
+        return []
+    names = []
+    for parameter in _split_arguments(_balanced(signature, start)):
+        parameter = parameter.split("=", 1)[0].strip().lstrip("*&")
+        if ":" in parameter and language != "go":
+            parameter = parameter.split(":", 1)[0]
+        words = [
+            w
+            for w in re.findall(r"[A-Za-z_]\w*", parameter)
+            if w not in PARAMETER_KEYWORDS
+        ]
+        if not words:
+            continue
+        names.append(words[0] if language == "go" else words[-1])
+    return names
+
+
+def _balanced(text: str, start: int) -> str:
+    """text[start] 处的括号中的内容（没有闭合时取到末尾）"""
+    pairs = {"(": ")", "[": "]", "{": "}"}
+    stack = []
+    for index in range(start, len(text)):
+        char = text[index]
+        if char in pairs:
+            stack.append(pairs[char])
+        elif stack and char == stack[-1]:
+            stack.pop()
+            if not stack:
+                return text[start + 1 : index]
+    return text[start + 1 :].rstrip(" …")
+
+
+def _split_arguments(text: str) -> List[str]:
+    """按顶层的逗号拆分参数列表"""
+    parts, depth, current = [], 0, []
+    for char in text:
+        if char in "([{<":
+            depth += 1
+        elif char in ")]}>" and depth:
+            depth -= 1
+        if char == "," and not depth:
+            parts.append("".join(current).strip())
+            current = []
+        else:
+            current.append(char)
+    if "".join(current).strip():
+        parts.append("".join(current).strip())
+    return parts
+
+
+def call_arguments(snippet: Optional[str], callee: str) -> List[str]:
+    """调用表达式片段中传给 callee 的参数（找不到调用时为空列表）"""
+    if not snippet:
+        return []
+    match = re.search(rf"\b{re.escape(callee)}\s*(?:::<[^>]*>)?\s*\(", snippet)
+    if not match:
+        return []
+    return _split_arguments(_balanced(snippet, match.end() - 1))
+
+
+def decreasing_argument(argument: str, parameters: List[str]) -> Optional[Dict]:
+    """
+    参数是否明显地由调用者的参数递减而来
+
+    Returns:
+        {"parameter", "argument", "kind"}（kind 为 decreasing：n - 1、n / 2、
+        n >> 1、切片等；structural：node.left 等子结构），否则为 None
+    """
+    text = argument.strip().lstrip("&*").strip()
+    if text.startswith("mut "):
+        text = text[4:].strip()
+    if text.startswith("(") and text.endswith(")"):
+        text = text[1:-1].strip()
+    fields = "|".join(STRUCTURAL_FIELDS)
+    methods = "|".join(SLICING_METHODS)
+    for parameter in parameters:
+        p = re.escape(parameter)
+        if (
+            re.match(rf"^{p}\s*(?:-|/|>>)\s*[\w.]+", text)
+            or re.match(rf"^{p}\s*\[[^\]]*(?:\.\.|:)[^\]]*\]", text)
+            or re.match(rf"^{p}\.(?:saturating_sub|checked_sub)\(", text)
+            or re.match(rf"^{p}\.(?:{methods})\(", text)
+        ):
+            kind = "decreasing"
+        elif re.match(rf"^{p}\s*(?:\.|->)\s*(?:{fields})\b", text):
+            kind = "structural"
+        else:
+            continue
+        return {"parameter": parameter, "argument": argument.strip(), "kind": kind}
+    return None
+
+
+def recursion_bound(graph: CallGraph, cycle: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    启发式地判断递归环是否有界
+
+    直接递归要求每个自调用点都传入了递减的参数；相互递归检查示例环路上的
+    调用边，只要有一条边的所有调用点都传入了递减的参数（每绕环一圈参数都
+    减小）就认为有界。只检查调用点的源码片段（第一行），判断为无界只表示
+    没有找到明显的递减参数
+
+    Returns:
+        bounded 和 evidence（递减参数所在的调用点：caller、callee、file、line、
+        parameter、argument、kind）
+    """
+    path = cycle["cycle"] or [cycle["members"][0]] * 2
+    edges = list(zip(path, path[1:]))
+    checked = []
+    for caller_id, callee_id in edges:
+        parameters = parameter_names(
+            graph.nodes[caller_id].get("signature"),
+            graph.nodes[caller_id]["name"],
+            graph.nodes[caller_id].get("language") or "",
+        )
+        evidence = []
+        sites = graph.call_sites(caller_id, callee_id)
+        for site in sites:
+            found = None
+            for argument in call_arguments(
+                site.get("call_site_snippet"), graph.nodes[callee_id]["name"]
+            ):
+                found = decreasing_argument(argument, parameters)
+                if found:
+                    break
+            if not found:
+                evidence = []
+                break
+            evidence.append(
+                {
+                    "caller": caller_id,
+                    "callee": callee_id,
+                    "file": site.get("caller_file"),
+                    "line": site.get("call_site_line"),
+                    **found,
+                }
+            )
+        checked.append(evidence)
+    if cycle["kind"] == "direct":
+        bounded = bool(checked) and all(checked)
+    else:
+        bounded = any(checked)
+    return {
+        "bounded": bounded,
+        "evidence": [item for evidence in checked for item in evidence],
+    }
+
+
+def _run(command: List[str]) -> str:
+    try:
+        result = subprocess.run(
+            command, capture_output=True, text=True, timeout=READELF_TIMEOUT
+        )
+    except FileNotFoundError as e:
+        raise ValueError(f"没有找到 {command[0]}（需要 binutils）") from e
+    except subprocess.TimeoutExpired as e:
+        raise ValueError(f"{' '.join(command)} 超时") from e
+    if result.returncode != 0:
+        lines = result.stderr.strip().splitlines()
+        raise ValueError(f"{command[0]} 失败: {lines[-1] if lines else command[-1]}")
+    return result.stdout
+
+
+def parse_frames(text: str) -> Dict[int, Tuple[int, bool]]:
+    """
+    解析 readelf --debug-dump=frames-interp 的输出
+
+    Returns:
+        {函数起始地址: (栈帧字节数, 是否准确)}：栈帧为 CFA 相对于栈指针的
+        最大偏移（包括返回地址）；使用帧指针的函数（CFA 规则基于 rbp、x29 等）
+        的栈帧从帧信息中只能得到下界，标记为不准确
+    """
+    cie_sizes: Dict[str, int] = {}
+    frames: Dict[int, Tuple[int, bool]] = {}
+    current_cie = None
+    current_fde = None
+    size, exact = 0, True
+    for line in text.splitlines() + [""]:
+        cie = CIE_HEADER.match(line)
+        fde = FDE_HEADER.search(line)
+        if cie or fde or not line.strip():
+            if current_fde is not None:
+                frames[current_fde] = (size, exact)
+            if current_cie is not None:
+                cie_sizes[current_cie] = size
+            current_cie = current_fde = None
+        if cie:
+            current_cie, size, exact = cie.group(1), 0, True
+        elif fde:
+            current_fde = int(fde.group(2), 16)
+            size, exact = cie_sizes.get(fde.group(1), 0), True
+        elif current_cie is not None or current_fde is not None:
+            row = CFA_ROW.match(line.strip())
+            if row:
+                offset = int(row.group(2))
+                if row.group(1) not in STACK_POINTERS:
+                    exact = False
+                size = max(size, offset)
+    return frames
+
+
+def frame_sizes(
+    binary: str, readelf: str = "readelf", nm: str = "nm"
+) -> Dict[str, Tuple[int, bool]]:
+    """
+    二进制文件中每个函数符号（还原后的名称）的栈帧估计：{符号: (字节数, 是否准确)}
+
+    Raises:
+        ValueError: 没有找到 readelf / nm 或读取失败
+    """
+    frames = parse_frames(_run([readelf, "--debug-dump=frames-interp", binary]))
+    sizes: Dict[str, Tuple[int, bool]] = {}
+    for line in _run([nm, "-C", "--defined-only", binary]).splitlines():
+        match = NM_LINE.match(line.strip())
+        if match and int(match.group(1), 16) in frames:
+            sizes[match.group(2)] = frames[int(match.group(1), 16)]
+    return sizes
+
+
+def node_frame_sizes(
+    graph: CallGraph, sizes: Dict[str, Tuple[int, bool]]
+) -> Dict[str, Dict[str, Any]]:
+    """
+    把符号的栈帧对应到调用图中的函数：{节点ID: {"bytes", "exact"}}
+
+    Rust / C++ 符号按还原后的路径（去掉哈希和泛型参数）与限定名对应，
+    同一函数的多个单态化实例取最大值；C 等没有路径的符号按函数名对应
+    （只在名称唯一时）
+    """
+    by_path: Dict[str, Tuple[int, bool]] = {}
+    for symbol, frame in sizes.items():
+        parts = symbol_path(symbol)
+        key = "::".join(
+            p for p in (parts.get(k) for k in ("crate", "module", "owner", "name")) if p
+        )
+        if key not in by_path or frame[0] > by_path[key][0]:
+            by_path[key] = frame
+    names: Dict[str, List[str]] = {}
+    for node_id, node in graph.nodes.items():
+        names.setdefault(node["name"], []).append(node_id)
+
+    result = {}
+    for node_id, node in graph.nodes.items():
+        frame = by_path.get(graph.qualified_name(node_id))
+        if frame is None and len(names[node["name"]]) == 1:
+            frame = by_path.get(node["name"])
+        if frame is not None:
+            result[node_id] = {"bytes": frame[0], "exact": frame[1]}
+    return result
+
+
+def cycle_stack(
+    cycle: Dict[str, Any], frames: Dict[str, Dict[str, Any]]
+) -> Optional[Dict[str, Any]]:
+    """
+    递归每绕环一圈（直接递归为每层）使用的栈：示例环路上各函数栈帧之和
+    {"bytes", "exact"}；有函数没有栈帧估计时为 None
+    """
+    path = cycle["cycle"] or cycle["members"][:1]
+    members = list(dict.fromkeys(path))
+    if not all(node_id in frames for node_id in members):
+        return None
+    return {
+        "bytes": sum(frames[n]["bytes"] for n in members),
+        "exact": all(frames[n]["exact"] for n in members),
+    }
+
+
+def analyze_recursion(
+    graph: CallGraph,
+    cycles: List[Dict[str, Any]],
+    frames: Optional[Dict[str, Dict[str, Any]]] = None,
+    stack_limit: Optional[int] = None,
+) -> List[Dict[str, Any]]:
+    """
+    每个递归环（与 cycles 一一对应）的分析结果
+
+    bounded / evidence 见 recursion_bound；stack 见 cycle_stack（没有 frames
+    时为 None）；max_depth 为 stack_limit 字节的栈最多容纳的递归层数（绕环
+    的圈数）；overflow 为没有找到递减参数的递归在给定栈大小下可能溢出
+    """
+    results = []
+    for cycle in cycles:
+        result = recursion_bound(graph, cycle)
+        stack = cycle_stack(cycle, frames) if frames is not None else None
+        max_depth = None
+        if stack and stack["bytes"] and stack_limit:
+            max_depth = stack_limit // stack["bytes"]
+        result.update(
+            {
+                "stack": stack,
+                "max_depth": max_depth,
+                "overflow": max_depth is not None and not result["bounded"],
+            }
+        )
+        results.append(result)
+    return results
@@ -0,0 +1,124 @@
+"""
+架构规则检查
+在配置文件中声明禁止的调用边（如 ui::* -> db::*），找出调用图中违反规则的调用
+"""
+
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .cargo import load_toml
+    from .filters import Pattern
+    from .graph import CallGraph
+except ImportError:
+    from cargo import load_toml
+    from filters import Pattern
+    from graph import CallGraph
+
+# 规则中分隔调用方和被调用方模式的箭头
+RULE_ARROW = "->"
+
+
+class Rule:
+    """
+    单条边规则：调用方模式 -> 被调用方模式
+
+    两侧的模式规则同 --include/--exclude（glob 或 re:正则，匹配限定名和文件路径）
+    """
+
+    def __init__(self, text: str):
+        self.text = text.strip()
+        if self.text.count(RULE_ARROW) != 1:
+            raise ValueError(f"无效的规则 '{text}'：格式应为 <调用方> -> <被调用方>")
+        source, target = (part.strip() for part in self.text.split(RULE_ARROW))
+        if not source or not target:
+            raise ValueError(f"无效的规则 '{text}'：调用方和被调用方都不能为空")
+        self.source = Pattern(source)
+        self.target = Pattern(target)
+
+    def matches(self, graph: CallGraph, caller_id: str, callee_id: str) -> bool:
+        return self.source.matches_node(graph, caller_id) and (
+            self.target.matches_node(graph, callee_id)
+        )
+
+    def __repr__(self) -> str:
+        return f"Rule({self.text!r})"
+
+
+class RuleSet:
+    """
+    禁止规则和例外
+
+    匹配任一 deny 规则、且不匹配任何 allow 规则的调用边视为违规
+    """
+
+    def __init__(
+        self,
+        deny: Optional[List[str]] = None,
+        allow: Optional[List[str]] = None,
+    ):
+        self.deny = [Rule(text) for text in deny or []]
+        self.allow = [Rule(text) for text in allow or []]
+
+    def __bool__(self) -> bool:
+        return bool(self.deny)
+
+    @classmethod
+    def from_config(cls, config_path: str) -> "RuleSet":
+        """
+        从 TOML 配置文件的 [rules] 表读取规则::
+
+            [rules]
+            deny = ["ui::* -> db::*", "core::* -> app::*"]
+            allow = ["ui::health::* -> db::ping"]
+        """
+        path = Path(config_path)
+        if not path.exists():
+            raise ValueError(f"配置文件不存在: {config_path}")
+        rules = load_toml(path).get("rules") or {}
+        return cls(rules.get("deny"), rules.get("allow"))
+
+    def merge(self, other: "RuleSet") -> "RuleSet":
+        """合并两组规则（如配置文件和命令行）"""
+        merged = RuleSet()
+        merged.deny = self.deny + other.deny
+        merged.allow = self.allow + other.allow
+        return merged
+
+    def check(self, graph: CallGraph) -> List[Dict[str, Any]]:
+        """
+        检查调用图中的所有边
+
+        Returns:
+            违规列表，每项包含 rule（违反的规则文本）、caller_id、callee_id
+            和 sites（调用点），按规则、调用方、被调用方排序
+        """
+        violations = []
+        for edge in graph.edges():
+            caller_id, callee_id = edge["caller_id"], edge["callee_id"]
+            rule = next(
+                (r for r in self.deny if r.matches(graph, caller_id, callee_id)),
+                None,
+            )
+            if not rule or any(
+                r.matches(graph, caller_id, callee_id) for r in self.allow
+            ):
+                continue
+            violations.append(
+                {
+                    "rule": rule.text,
+                    "caller_id": caller_id,
+                    "callee_id": callee_id,
+                    "sites": graph.call_sites(caller_id, callee_id),
+                }
+            )
+        order = {rule.text: index for index, rule in enumerate(self.deny)}
+        violations.sort(
+            key=lambda v: (
+                order[v["rule"]],
+                graph.qualified_name(v["caller_id"]),
+                graph.qualified_name(v["callee_id"]),
+            )
+        )
+        return violations
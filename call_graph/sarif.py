@@ -0,0 +1,259 @@
+"""
+SARIF 输出
+把死代码、panic 路径、架构规则违规和递归环等分析结果转换为 SARIF 2.1.0 日志，
+上传到 GitHub code scanning 等 CI 平台后以代码注释的形式显示；同样的结果也可以
+输出为 GitHub Actions 的工作流命令（::warning file=...::），不需要额外的上传步骤
+"""
+
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Optional
+from urllib.parse import unquote, urlparse
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+SARIF_VERSION = "2.1.0"
+SARIF_SCHEMA = "https://json.schemastore.org/sarif-2.1.0.json"
+
+# 工具名称（与命令行程序同名）和 SARIF 中相对路径的基准目录
+SARIF_TOOL = "call-graph"
+SARIF_SOURCE_ROOT = "%SRCROOT%"
+
+# 输出分析结果的格式（--format）：SARIF 日志和 GitHub Actions 注释
+FINDING_FORMATS = ("sarif", "github")
+
+# SARIF 级别对应的 GitHub Actions 注释命令
+GITHUB_COMMANDS = {"error": "error", "warning": "warning", "note": "notice"}
+
+# 各分析对应的 SARIF 规则：{规则ID: (说明, 默认级别)}
+SARIF_RULES = {
+    "dead-code": ("从任何入口点都不可达的函数（疑似死代码）", "note"),
+    "recursion-cycle": ("直接递归或相互递归的函数", "note"),
+    "panic-path": ("可能直接或间接 panic 的公开 API 函数", "warning"),
+    "rule-violation": ("违反架构规则（deny）的调用", "error"),
+}
+
+
+def artifact_uri(file: str) -> Dict[str, str]:
+    """
+    文件的 artifactLocation：当前目录下的文件使用相对路径（相对于 %SRCROOT%，
+    即在仓库根目录运行时的仓库路径），其他文件使用 file:// URI
+    """
+    path = Path(file)
+    if path.is_absolute():
+        try:
+            path = path.relative_to(Path.cwd())
+        except ValueError:
+            return {"uri": path.as_uri()}
+    return {"uri": path.as_posix(), "uriBaseId": SARIF_SOURCE_ROOT}
+
+
+def location(
+    file: str, line: Optional[int], message: Optional[str] = None
+) -> Dict[str, Any]:
+    """SARIF 位置（行号未知时只包含文件）"""
+    physical: Dict[str, Any] = {"artifactLocation": artifact_uri(file)}
+    if line:
+        physical["region"] = {"startLine": line}
+    entry: Dict[str, Any] = {"physicalLocation": physical}
+    if message:
+        entry["message"] = {"text": message}
+    return entry
+
+
+def node_location(
+    graph: CallGraph, node_id: str, message: Optional[str] = None
+) -> Dict[str, Any]:
+    """函数定义的 SARIF 位置，带限定名作为逻辑位置"""
+    node = graph.nodes[node_id]
+    entry = location(node["file"], node.get("start_line"), message)
+    entry["logicalLocations"] = [
+        {"fullyQualifiedName": graph.qualified_name(node_id), "kind": "function"}
+    ]
+    return entry
+
+
+def result(
+    rule_id: str,
+    message: str,
+    locations: List[Dict[str, Any]],
+    related: Iterable[Dict[str, Any]] = (),
+    level: Optional[str] = None,
+) -> Dict[str, Any]:
+    """一条 SARIF 结果，related 为相关位置（按顺序编号），level 默认为规则的级别"""
+    entry: Dict[str, Any] = {
+        "ruleId": rule_id,
+        "level": level or SARIF_RULES[rule_id][1],
+        "message": {"text": message},
+        "locations": locations,
+    }
+    numbered = [{"id": index, **loc} for index, loc in enumerate(related, 1)]
+    if numbered:
+        entry["relatedLocations"] = numbered
+    return entry
+
+
+def dead_code_results(
+    graph: CallGraph, unreachable: List[str], new: bool = False
+) -> List[Dict]:
+    """
+    死代码的结果：每个不可达函数一条，位于函数定义处；new 表示只包含相对基准
+    版本新变为不可达的函数，级别提升为 warning
+    """
+    return [
+        result(
+            "dead-code",
+            f"函数 {graph.qualified_name(node_id)} 从任何入口点都不可达",
+            [node_location(graph, node_id)],
+            level="warning" if new else None,
+        )
+        for node_id in unreachable
+    ]
+
+
+def cycle_results(graph: CallGraph, cycles: List[Dict[str, Any]]) -> List[Dict]:
+    """递归环的结果：每个环一条，位于第一个函数，其他函数作为相关位置"""
+    results = []
+    for cycle in cycles:
+        members = cycle["members"]
+        if cycle["kind"] == "direct":
+            message = f"函数 {graph.qualified_name(members[0])} 直接递归调用自身"
+        else:
+            path = cycle["cycle"] or members
+            names = " -> ".join(graph.nodes[n]["name"] for n in path)
+            message = f"{len(members)} 个函数相互递归: {names}"
+        results.append(
+            result(
+                "recursion-cycle",
+                message,
+                [node_location(graph, members[0])],
+                [
+                    node_location(graph, n, graph.nodes[n]["name"])
+                    for n in members[1:]
+                ],
+            )
+        )
+    return results
+
+
+def panic_results(
+    graph: CallGraph,
+    paths: Dict[str, List[str]],
+    panics: Dict[str, Dict[str, Dict[str, Any]]],
+    order: List[str],
+) -> List[Dict]:
+    """
+    panic 路径的结果：每个可能 panic 的函数一条，位于函数定义处，最短路径上的
+    函数和最终的 panic 位置作为相关位置
+    """
+    results = []
+    for node_id in order:
+        path = paths[node_id]
+        target = path[-1]
+        kinds = ", ".join(sorted(panics[target]))
+        message = f"函数 {graph.qualified_name(node_id)} 可能 panic（{kinds}）"
+        if len(path) > 1:
+            names = " -> ".join(graph.nodes[n]["name"] for n in path)
+            message += f"，路径: {names}"
+        related = [
+            node_location(graph, n, graph.nodes[n]["name"]) for n in path[1:]
+        ]
+        for kind, site in sorted(panics[target].items()):
+            related.append(
+                location(graph.nodes[target]["file"], site.get("line"), kind)
+            )
+        results.append(
+            result("panic-path", message, [node_location(graph, node_id)], related)
+        )
+    return results
+
+
+def violation_results(
+    graph: CallGraph, violations: List[Dict[str, Any]]
+) -> List[Dict]:
+    """架构规则违规的结果：每个违规的调用点一条，被调用的函数作为相关位置"""
+    results = []
+    for violation in violations:
+        caller_id, callee_id = violation["caller_id"], violation["callee_id"]
+        message = (
+            f"{graph.qualified_name(caller_id)} 调用 "
+            f"{graph.qualified_name(callee_id)} 违反规则 {violation['rule']}"
+        )
+        callee = node_location(graph, callee_id, graph.nodes[callee_id]["name"])
+        for site in violation["sites"]:
+            results.append(
+                result(
+                    "rule-violation",
+                    message,
+                    [location(site["caller_file"], site.get("call_site_line"))],
+                    [callee],
+                )
+            )
+    return results
+
+
+def sarif_log(results: List[Dict[str, Any]]) -> Dict[str, Any]:
+    """包含一次运行的 SARIF 日志，rules 只列出结果中用到的规则"""
+    used = {entry["ruleId"] for entry in results}
+    rules = [
+        {
+            "id": rule_id,
+            "shortDescription": {"text": description},
+            "defaultConfiguration": {"level": level},
+        }
+        for rule_id, (description, level) in SARIF_RULES.items()
+        if rule_id in used
+    ]
+    return {
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [
+            {
+                "tool": {"driver": {"name": SARIF_TOOL, "rules": rules}},
+                "originalUriBaseIds": {
+                    SARIF_SOURCE_ROOT: {"uri": Path.cwd().as_uri() + "/"}
+                },
+                "results": results,
+            }
+        ],
+    }
+
+
+def _escape_github(text: str, property_value: bool = False) -> str:
+    """转义工作流命令中的特殊字符（属性值中还需要转义 : 和 ,）"""
+    text = text.replace("%", "%25").replace("\r", "%0D").replace("\n", "%0A")
+    if property_value:
+        text = text.replace(":", "%3A").replace(",", "%2C")
+    return text
+
+
+def github_annotations(results: List[Dict[str, Any]]) -> List[str]:
+    """
+    把 SARIF 结果转换为 GitHub Actions 注释命令，如
+    ::warning file=src/db.rs,line=14,title=call-graph panic-path::函数 ... 可能 panic
+
+    在 Actions 中输出后，结果直接显示在 Pull Request 的文件差异中；文件为结果的
+    第一个位置（相对路径，同 artifact_uri），级别按 GITHUB_COMMANDS 对应
+    """
+    lines = []
+    for entry in results:
+        properties = []
+        if entry["locations"]:
+            physical = entry["locations"][0]["physicalLocation"]
+            artifact = physical["artifactLocation"]
+            path = artifact["uri"]
+            if "uriBaseId" not in artifact:
+                path = unquote(urlparse(path).path)
+            properties.append(f"file={_escape_github(path, True)}")
+            line = physical.get("region", {}).get("startLine")
+            if line:
+                properties.append(f"line={line}")
+        title = f"{SARIF_TOOL} {entry['ruleId']}"
+        properties.append(f"title={_escape_github(title, True)}")
+        command = GITHUB_COMMANDS[entry["level"]]
+        message = _escape_github(entry["message"]["text"])
+        lines.append(f"::{command} {','.join(properties)}::{message}")
+    return lines
@@ -0,0 +1,212 @@
+"""
+接口隔离建议
+按调用者所在的模块统计每个类型的方法被哪些模块使用，把被同一批模块使用的
+方法聚为一组（按使用它们的模块集合的 Jaccard 相似度合并），各组之间很少
+共同使用的类型可以按组拆分为更小的 trait / 结构体；也列出只使用了一小部分
+方法（如只用读取方法）的调用者模块
+"""
+
+import re
+from typing import Any, Dict, List, Optional, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+# 方法组合并的默认相似度阈值（使用两组方法的模块集合的 Jaccard 相似度）
+DEFAULT_SIMILARITY = 0.5
+
+# 参与分析的类型至少要有这么多被外部调用的方法
+DEFAULT_MIN_METHODS = 4
+
+# 按方法名的第一个单词判断读取 / 写入方法
+READ_VERBS = (
+    "as",
+    "contains",
+    "count",
+    "describe",
+    "fetch",
+    "find",
+    "get",
+    "has",
+    "is",
+    "iter",
+    "len",
+    "list",
+    "load",
+    "lookup",
+    "query",
+    "read",
+    "search",
+    "to",
+)
+WRITE_VERBS = (
+    "add",
+    "append",
+    "clear",
+    "create",
+    "delete",
+    "insert",
+    "modify",
+    "pop",
+    "push",
+    "put",
+    "register",
+    "remove",
+    "reset",
+    "save",
+    "set",
+    "store",
+    "unregister",
+    "update",
+    "write",
+)
+
+METHOD_VERB = re.compile(r"_*([A-Za-z][a-z0-9]*)")
+
+
+def method_kind(name: str) -> Optional[str]:
+    """按方法名判断读取（"read"）或写入（"write"）方法，无法判断时为 None"""
+    match = METHOD_VERB.match(name.rsplit("::", 1)[-1].rsplit(".", 1)[-1])
+    verb = match.group(1).lower() if match else ""
+    if verb in READ_VERBS:
+        return "read"
+    if verb in WRITE_VERBS:
+        return "write"
+    return None
+
+
+def _short_name(graph: CallGraph, node_id: str) -> str:
+    """方法名（去掉 C++ 类外定义和 Go 方法名称中的所属类型）"""
+    return graph.nodes[node_id]["name"].rsplit("::", 1)[-1].rsplit(".", 1)[-1]
+
+
+def _jaccard(a: Set[str], b: Set[str]) -> float:
+    return len(a & b) / len(a | b) if a | b else 0.0
+
+
+def method_usage(graph: CallGraph) -> Dict[Tuple[str, str], Dict[str, Set[str]]]:
+    """
+    每个类型的方法被哪些模块的调用者使用
+
+    Returns:
+        {(语言, 类型名): {方法ID: {调用者模块}}}：只包括固有方法（不含 trait
+        的实现和默认方法，它们已经是独立的接口），调用者为同一类型的方法时
+        不计入（内部调用）；没有外部调用者的方法不列出
+    """
+    usage: Dict[Tuple[str, str], Dict[str, Set[str]]] = {}
+    for node_id, node in graph.nodes.items():
+        extras = node.get("extras") or {}
+        owner = extras.get("owner")
+        if not owner or extras.get("trait") or extras.get("in_trait"):
+            continue
+        key = (node.get("language") or "", owner)
+        modules = set()
+        for caller_id in graph.predecessors(node_id):
+            caller = graph.nodes[caller_id]
+            caller_owner = (caller.get("extras") or {}).get("owner")
+            if (caller.get("language") or "", caller_owner) == key:
+                continue
+            modules.add(graph.module_name(caller_id))
+        if modules:
+            usage.setdefault(key, {})[node_id] = modules
+    return usage
+
+
+def _cluster(methods: Dict[str, Set[str]], similarity: float) -> List[Dict]:
+    """按使用模块集合的相似度合并方法（单链接），相似度最高的一对先合并"""
+    clusters = [
+        {"methods": [node_id], "modules": set(modules)}
+        for node_id, modules in methods.items()
+    ]
+    while len(clusters) > 1:
+        best, pair = -1.0, None
+        for i in range(len(clusters)):
+            for j in range(i + 1, len(clusters)):
+                score = _jaccard(clusters[i]["modules"], clusters[j]["modules"])
+                if score > best:
+                    best, pair = score, (i, j)
+        if best < similarity:
+            break
+        i, j = pair
+        clusters[i]["methods"] += clusters[j]["methods"]
+        clusters[i]["modules"] |= clusters.pop(j)["modules"]
+    return clusters
+
+
+def segregation_suggestions(
+    graph: CallGraph,
+    similarity: float = DEFAULT_SIMILARITY,
+    min_methods: int = DEFAULT_MIN_METHODS,
+    type_name: Optional[str] = None,
+) -> List[Dict[str, Any]]:
+    """
+    接口拆分建议
+
+    Args:
+        similarity: 方法组合并的相似度阈值，越高拆分得越细
+        min_methods: 类型至少要有这么多被外部调用的方法
+        type_name: 只分析这个类型（此时不要求 min_methods，也列出不需要拆分的
+            类型）
+
+    Returns:
+        [{"type", "language", "file", "methods", "modules", "groups":
+        [{"methods", "modules", "kind"}], "partial": [{"module", "methods",
+        "kind"}]}]：methods 为方法ID（按名称排序）；groups 为建议拆分出的方法
+        组，kind 为组内的方法都是读取或写入方法时的 "read" / "write"；partial
+        为只使用了类型一部分方法的调用者模块。默认只返回能拆分为至少两组的
+        类型，按组数和方法数降序排列
+    """
+    suggestions = []
+    for (language, owner), methods in method_usage(graph).items():
+        if type_name is not None:
+            if owner != type_name:
+                continue
+        elif len(methods) < min_methods:
+            continue
+
+        def by_name(node_ids):
+            return sorted(node_ids, key=lambda n: (_short_name(graph, n), n))
+
+        def kind_of(node_ids):
+            kinds = {method_kind(_short_name(graph, n)) for n in node_ids}
+            return kinds.pop() if len(kinds) == 1 else None
+
+        groups = [
+            {
+                "methods": by_name(cluster["methods"]),
+                "modules": sorted(cluster["modules"]),
+                "kind": kind_of(cluster["methods"]),
+            }
+            for cluster in _cluster(methods, similarity)
+        ]
+        if len(groups) < 2 and type_name is None:
+            continue
+        groups.sort(key=lambda g: (-len(g["methods"]), g["modules"]))
+
+        modules = sorted({m for used_by in methods.values() for m in used_by})
+        partial = []
+        for module in modules:
+            used = by_name(n for n, used_by in methods.items() if module in used_by)
+            if len(used) < len(methods):
+                partial.append(
+                    {"module": module, "methods": used, "kind": kind_of(used)}
+                )
+        partial.sort(key=lambda p: (len(p["methods"]), p["module"]))
+
+        first = by_name(methods)[0]
+        suggestions.append(
+            {
+                "type": owner,
+                "language": language,
+                "file": graph.nodes[first].get("file"),
+                "methods": by_name(methods),
+                "modules": modules,
+                "groups": groups,
+                "partial": partial,
+            }
+        )
+    suggestions.sort(key=lambda s: (-len(s["groups"]), -len(s["methods"]), s["type"]))
+    return suggestions
@@ -0,0 +1,217 @@
+"""
+调用图快照
+把分析数据库（符号、调用关系和文件哈希）保存为紧凑的二进制文件（.cg），
+分析一次后可以在其他机器或 CI 的后续步骤中恢复，直接查询而不必重新解析代码
+"""
+
+import struct
+import zlib
+from typing import Any, BinaryIO, Dict, List, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .database import CallGraphDB
+except ImportError:
+    from database import CallGraphDB
+
+# 文件头：魔数 + 格式版本（改变编码方式时递增）
+MAGIC = b"CGSNAP"
+FORMAT_VERSION = 1
+HEADER = struct.Struct(">6sH")
+
+# 保存的表（按此顺序写入和恢复）
+TABLES = ("symbols", "call_relations", "files")
+
+# 值的类型标记
+TAG_NULL = 0
+TAG_INT = 1
+TAG_STRING = 2
+TAG_FLOAT = 3
+
+
+def _write_varint(out: bytearray, value: int):
+    """无符号变长整数（每字节 7 位，最高位表示后面还有字节）"""
+    while value >= 0x80:
+        out.append(value & 0x7F | 0x80)
+        value >>= 7
+    out.append(value)
+
+
+def _read_varint(data: bytes, offset: int) -> Tuple[int, int]:
+    value = 0
+    shift = 0
+    while True:
+        if offset >= len(data):
+            raise ValueError("快照数据不完整")
+        byte = data[offset]
+        offset += 1
+        value |= (byte & 0x7F) << shift
+        if byte < 0x80:
+            return value, offset
+        shift += 7
+
+
+class _Encoder:
+    """按列名编码各表的行；字符串放入字符串表，重复的路径、名称只保存一次"""
+
+    def __init__(self):
+        self.strings: List[str] = []
+        self._string_ids: Dict[str, int] = {}
+        self.body = bytearray()
+
+    def string(self, text: str) -> int:
+        index = self._string_ids.get(text)
+        if index is None:
+            index = self._string_ids[text] = len(self.strings)
+            self.strings.append(text)
+        return index
+
+    def value(self, value: Any):
+        body = self.body
+        if value is None:
+            body.append(TAG_NULL)
+        elif isinstance(value, int):
+            body.append(TAG_INT)
+            # zigzag 编码，负数也是短的变长整数
+            _write_varint(body, value << 1 if value >= 0 else (-value << 1) - 1)
+        elif isinstance(value, float):
+            body.append(TAG_FLOAT)
+            body.extend(struct.pack(">d", value))
+        else:
+            body.append(TAG_STRING)
+            _write_varint(body, self.string(str(value)))
+
+    def table(self, name: str, columns: List[str], rows: List[Tuple[Any, ...]]):
+        _write_varint(self.body, self.string(name))
+        _write_varint(self.body, len(columns))
+        for column in columns:
+            _write_varint(self.body, self.string(column))
+        _write_varint(self.body, len(rows))
+        for row in rows:
+            for value in row:
+                self.value(value)
+
+    def payload(self) -> bytes:
+        head = bytearray()
+        _write_varint(head, len(self.strings))
+        for text in self.strings:
+            encoded = text.encode("utf-8")
+            _write_varint(head, len(encoded))
+            head.extend(encoded)
+        return bytes(head + self.body)
+
+
+def _decode(data: bytes) -> Dict[str, Tuple[List[str], List[Tuple[Any, ...]]]]:
+    """解码负载，返回 {表名: (列名, 行)}"""
+    count, offset = _read_varint(data, 0)
+    strings = []
+    for _ in range(count):
+        length, offset = _read_varint(data, offset)
+        strings.append(data[offset : offset + length].decode("utf-8"))
+        offset += length
+
+    tables = {}
+    while offset < len(data):
+        name, offset = _read_varint(data, offset)
+        width, offset = _read_varint(data, offset)
+        columns = []
+        for _ in range(width):
+            index, offset = _read_varint(data, offset)
+            columns.append(strings[index])
+        row_count, offset = _read_varint(data, offset)
+        rows = []
+        for _ in range(row_count):
+            row = []
+            for _ in range(width):
+                tag = data[offset]
+                offset += 1
+                if tag == TAG_NULL:
+                    row.append(None)
+                elif tag == TAG_INT:
+                    value, offset = _read_varint(data, offset)
+                    row.append(value >> 1 if not value & 1 else -((value + 1) >> 1))
+                elif tag == TAG_FLOAT:
+                    row.append(struct.unpack_from(">d", data, offset)[0])
+                    offset += 8
+                elif tag == TAG_STRING:
+                    value, offset = _read_varint(data, offset)
+                    row.append(strings[value])
+                else:
+                    raise ValueError(f"快照中有未知的值类型: {tag}")
+            rows.append(tuple(row))
+        tables[strings[name]] = (columns, rows)
+    return tables
+
+
+def save_snapshot(db: CallGraphDB, stream: BinaryIO) -> Dict[str, int]:
+    """
+    把数据库保存为快照
+
+    Returns:
+        {表名: 行数}
+    """
+    encoder = _Encoder()
+    counts = {}
+    cursor = db.conn.cursor()
+    for table in TABLES:
+        # 调用关系的自增 id 不保存，恢复时重新分配
+        cursor.execute(f"SELECT * FROM {table} LIMIT 0")
+        columns = [d[0] for d in cursor.description]
+        if table == "call_relations":
+            columns.remove("id")
+        cursor.execute(f"SELECT {', '.join(columns)} FROM {table} ORDER BY rowid")
+        rows = [tuple(row) for row in cursor.fetchall()]
+        encoder.table(table, columns, rows)
+        counts[table] = len(rows)
+    stream.write(HEADER.pack(MAGIC, FORMAT_VERSION))
+    stream.write(zlib.compress(encoder.payload(), 9))
+    return counts
+
+
+def load_snapshot(db: CallGraphDB, stream: BinaryIO) -> Dict[str, int]:
+    """
+    从快照恢复数据库，替换数据库中已有的数据
+
+    快照中有而数据库中没有的列（更新版本保存的快照）被忽略，数据库中有而
+    快照中没有的列取默认值
+
+    Returns:
+        {表名: 行数}
+
+    Raises:
+        ValueError: 不是快照文件、格式版本不支持或数据损坏
+    """
+    header = stream.read(HEADER.size)
+    if len(header) != HEADER.size or header[: len(MAGIC)] != MAGIC:
+        raise ValueError("不是调用图快照文件")
+    _, version = HEADER.unpack(header)
+    if version != FORMAT_VERSION:
+        raise ValueError(f"不支持的快照格式版本: {version}（支持 {FORMAT_VERSION}）")
+    try:
+        tables = _decode(zlib.decompress(stream.read()))
+    except (zlib.error, IndexError, UnicodeDecodeError, struct.error) as e:
+        raise ValueError(f"快照数据损坏: {e}")
+
+    counts = {}
+    cursor = db.conn.cursor()
+    try:
+        for table in TABLES:
+            cursor.execute(f"DELETE FROM {table}")
+        for table in TABLES:
+            columns, rows = tables.get(table, ([], []))
+            cursor.execute(f"PRAGMA table_info({table})")
+            existing = {row["name"] for row in cursor.fetchall()}
+            keep = [i for i, column in enumerate(columns) if column in existing]
+            if keep:
+                names = ", ".join(columns[i] for i in keep)
+                marks = ", ".join("?" * len(keep))
+                cursor.executemany(
+                    f"INSERT INTO {table} ({names}) VALUES ({marks})",
+                    ([row[i] for i in keep] for row in rows),
+                )
+            counts[table] = len(rows)
+        db.conn.commit()
+    except Exception:
+        db.conn.rollback()
+        raise
+    return counts
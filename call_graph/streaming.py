@@ -0,0 +1,233 @@
+"""
+低内存分析
+超大项目（数万个函数）的调用引用和调用关系不在内存中整体保存：每个文件解析
+后立即把调用引用写入临时目录，需要调用引用的跨文件处理（路径规范化、cfg、
+间接调用目标、注释指令）和第二遍扫描都逐个文件读回；解析出的调用关系中的
+字符串经过驻留，以整数元组缓存，攒满一批后排序写成磁盘上的有序段，最后
+多路归并，按顺序批量写入数据库
+"""
+
+import heapq
+import os
+import pickle
+import shutil
+import sys
+import tempfile
+import weakref
+from typing import Any, Callable, Dict, Iterable, Iterator, List, Optional, Tuple
+
+# 每个有序段最多缓存的调用关系数量
+DEFAULT_RUN_SIZE = 100000
+
+# 有序段文件中每次序列化的记录数
+RUN_CHUNK_SIZE = 4096
+
+# 调用关系按这些字段排序（调用者文件、调用位置），其余字段随后保存
+RELATION_FIELDS = (
+    "caller_file",
+    "call_site_line",
+    "call_site_column",
+    "caller_id",
+    "callee_id",
+    "caller_name",
+    "callee_name",
+    "callee_file",
+    "call_site_snippet",
+    "language",
+    "edge_kind",
+    "type_args",
+    "callee_path",
+    "confidence",
+    "cfg",
+    "error_handling",
+)
+
+# 按整数原样保存的字段，其余字段保存为字符串表中的 ID
+POSITION_FIELDS = {"call_site_line", "call_site_column"}
+
+# 符号中驻留的字符串字段（同一文件的函数共享文件路径等）
+SYMBOL_STRING_FIELDS = ("id", "name", "file", "language", "kind")
+
+
+class StringTable:
+    """字符串驻留：相同的字符串只保存一份，用整数 ID 代替（None 为 -1）"""
+
+    def __init__(self):
+        self.strings: List[str] = []
+        self._ids: Dict[str, int] = {}
+
+    def intern(self, text: Optional[str]) -> int:
+        if text is None:
+            return -1
+        index = self._ids.get(text)
+        if index is None:
+            index = self._ids[text] = len(self.strings)
+            self.strings.append(text)
+        return index
+
+    def text(self, index: int) -> Optional[str]:
+        return None if index < 0 else self.strings[index]
+
+    def __len__(self) -> int:
+        return len(self.strings)
+
+
+def intern_symbols(functions: List[Dict[str, Any]]):
+    """
+    驻留函数表中的字符串字段
+
+    工作进程返回的结果各自反序列化，同一文件路径、语言等字符串在每个函数中
+    都是单独的副本；驻留后共享同一个对象
+    """
+    for func in functions:
+        for name in SYMBOL_STRING_FIELDS:
+            if isinstance(func.get(name), str):
+                func[name] = sys.intern(func[name])
+        extras = func.get("extras")
+        if extras:
+            for name in ("crate", "module", "owner"):
+                if isinstance(extras.get(name), str):
+                    extras[name] = sys.intern(extras[name])
+
+
+def load_call_refs(spill_path: str) -> List[Dict[str, Any]]:
+    """读回写入临时文件的调用引用（可在工作进程中调用）"""
+    with open(spill_path, "rb") as f:
+        return pickle.load(f)
+
+
+def _read_run(path: str) -> Iterator[Tuple[int, ...]]:
+    with open(path, "rb") as f:
+        while True:
+            try:
+                chunk = pickle.load(f)
+            except EOFError:
+                return
+            yield from chunk
+
+
+class StreamingStore:
+    """
+    低内存分析的临时存储（临时目录在 close 时删除，没有关闭时在对象回收或
+    解释器退出时删除）
+
+    - spill_file: 把文件的调用引用写入临时文件并从内存中释放，
+      call_refs_path 返回文件对应的临时文件
+    - rewrite_call_refs: 逐个文件读回调用引用，处理后重新写入
+    - order_files: 按文件顺序驻留文件路径，决定调用关系的排序
+    - add_relations: 缓存解析出的调用关系，满 run_size 条时排序写成有序段
+    - relations: 多路归并所有有序段，按调用者文件和调用位置依次返回调用关系
+    """
+
+    def __init__(
+        self, temp_dir: Optional[str] = None, run_size: int = DEFAULT_RUN_SIZE
+    ):
+        if temp_dir:
+            os.makedirs(temp_dir, exist_ok=True)
+        self.directory = tempfile.mkdtemp(prefix="call_graph_", dir=temp_dir)
+        self.run_size = max(1, run_size)
+        self.strings = StringTable()
+        self.relation_count = 0
+        self._spilled: Dict[str, str] = {}
+        self._buffer: List[Tuple[int, ...]] = []
+        self._runs: List[str] = []
+        self._cleanup = weakref.finalize(
+            self, shutil.rmtree, self.directory, ignore_errors=True
+        )
+
+    @property
+    def run_count(self) -> int:
+        return len(self._runs)
+
+    def spill_file(self, file_path: str, result: Dict[str, Any]):
+        """写入（或覆盖）文件的调用引用，并清空 result 中的 call_refs"""
+        spill_path = self._spilled.get(file_path)
+        if spill_path is None:
+            spill_path = os.path.join(self.directory, f"refs_{len(self._spilled)}")
+            self._spilled[file_path] = spill_path
+        with open(spill_path, "wb") as f:
+            pickle.dump(result["call_refs"], f, pickle.HIGHEST_PROTOCOL)
+        result["call_refs"] = []
+
+    def rewrite_call_refs(
+        self,
+        file_results: Dict[str, Dict[str, Any]],
+        transform: Callable[[str, Dict[str, Any]], None],
+    ):
+        """
+        逐个文件读回调用引用放入 result["call_refs"]，调用 transform(文件, result)
+        后重新写入；同一时刻只有一个文件的调用引用在内存中
+        """
+        for file_path, result in file_results.items():
+            result["call_refs"] = load_call_refs(self._spilled[file_path])
+            transform(file_path, result)
+            self.spill_file(file_path, result)
+
+    def order_files(self, file_paths: Iterable[str]):
+        """先按文件顺序驻留文件路径，排序结果与文件解析完成的先后无关"""
+        for file_path in file_paths:
+            self.strings.intern(file_path)
+
+    def call_refs_path(self, file_path: str) -> str:
+        return self._spilled[file_path]
+
+    def add_relations(self, relations: Iterable[Dict[str, Any]]):
+        for relation in relations:
+            self._buffer.append(self._encode(relation))
+            self.relation_count += 1
+            if len(self._buffer) >= self.run_size:
+                self._flush()
+
+    def _flush(self):
+        if not self._buffer:
+            return
+        self._buffer.sort()
+        path = os.path.join(self.directory, f"run_{len(self._runs)}")
+        with open(path, "wb") as f:
+            for i in range(0, len(self._buffer), RUN_CHUNK_SIZE):
+                pickle.dump(
+                    self._buffer[i : i + RUN_CHUNK_SIZE], f, pickle.HIGHEST_PROTOCOL
+                )
+        self._runs.append(path)
+        self._buffer = []
+
+    def _encode(self, relation: Dict[str, Any]) -> Tuple[int, ...]:
+        record = []
+        for name in RELATION_FIELDS:
+            value = relation.get(name)
+            if name in POSITION_FIELDS:
+                record.append(-1 if value is None else value)
+            else:
+                record.append(self.strings.intern(value))
+        return tuple(record)
+
+    def _decode(self, record: Tuple[int, ...]) -> Dict[str, Any]:
+        """还原调用关系；缺少的字段不写入，插入数据库时取默认值"""
+        relation = {}
+        for name, value in zip(RELATION_FIELDS, record):
+            if value < 0:
+                continue
+            if name not in POSITION_FIELDS:
+                value = self.strings.text(value)
+            relation[name] = value
+        return relation
+
+    def relations(self) -> Iterator[Dict[str, Any]]:
+        """按顺序返回所有调用关系（没有写出有序段时直接在内存中排序）"""
+        if self._runs:
+            self._flush()
+            records = heapq.merge(*(_read_run(path) for path in self._runs))
+        else:
+            self._buffer.sort()
+            records = iter(self._buffer)
+        for record in records:
+            yield self._decode(record)
+
+    def close(self):
+        self._cleanup()
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, exc_type, exc_val, exc_tb):
+        self.close()
@@ -0,0 +1,176 @@
+"""
+入口函数影响范围摘要
+对每个公开的入口函数汇总它（直接或间接）会用到的东西：经过的模块、可能触发的
+副作用标签（见 effects 模块）、能到达的 unsafe 代码，以及最长的调用链深度，
+输出为可以放进文档的表格，帮助新成员了解一个服务的各个 API 做了什么
+"""
+
+import csv
+import io
+from typing import Any, Dict, Iterable, List, Optional
+
+# 支持相对导入和直接运行
+try:
+    from .algorithms import condensation
+    from .effects import EffectSet
+    from .graph import CallGraph
+except ImportError:
+    from algorithms import condensation
+    from effects import EffectSet
+    from graph import CallGraph
+
+# 表格的列（CSV 表头与 JSON 字段同名）
+TOUCHES_FIELDS = [
+    "function",
+    "file",
+    "line",
+    "functions",
+    "max_depth",
+    "recursive",
+    "modules",
+    "effects",
+    "unsafe_functions",
+    "unsafe_blocks",
+]
+
+
+def call_heights(graph: CallGraph) -> Dict[str, Dict[str, Any]]:
+    """
+    从每个函数出发的最长调用链
+
+    Returns:
+        {函数ID: {"depth", "recursive"}}：depth 为最长调用链的调用层数（调用链
+        经过相互递归的函数组或直接递归的函数时，组内的调用只计一层），recursive
+        为调用链上有递归
+    """
+    condensed = condensation(graph)
+    component_of = {}
+    for node_id, node in condensed.nodes.items():
+        for member in (node.get("extras") or {}).get("members") or [node_id]:
+            component_of[member] = node_id
+
+    heights: Dict[str, Dict[str, Any]] = {}
+    # 缩点图按调用者在前的拓扑序加入节点，倒序处理时被调用者先得到结果
+    for node_id in reversed(list(condensed.nodes)):
+        successors = [heights[succ] for succ in condensed.successors(node_id)]
+        cyclic = condensed.node_kind(node_id) == "component" or bool(
+            graph.call_sites(node_id, node_id)
+        )
+        # 递归组内的调用（含自调用）计一层，组内的入口函数深度不为 0
+        depth = max((s["depth"] + 1 for s in successors), default=0)
+        heights[node_id] = {
+            "depth": depth + 1 if cyclic else depth,
+            "recursive": cyclic or any(s["recursive"] for s in successors),
+        }
+    return {node_id: heights[component_of[node_id]] for node_id in graph.nodes}
+
+
+def touches_summary(
+    graph: CallGraph,
+    entries: Iterable[str],
+    effect_set: Optional[EffectSet] = None,
+) -> List[Dict[str, Any]]:
+    """
+    每个入口函数的影响范围
+
+    Args:
+        entries: 入口函数 ID
+        effect_set: 副作用标签，省略或为空时 effects 为空列表
+
+    Returns:
+        [{"id", "functions", "max_depth", "recursive", "modules", "effects",
+        "unsafe"}]，按限定名排序：functions 为可达的函数数量（含入口本身），
+        modules 为这些函数所在的模块，effects 为可能触发的副作用标签，
+        unsafe 为可达的包含 unsafe 代码的函数 ID
+    """
+    heights = call_heights(graph)
+    effects = effect_set.propagate(graph) if effect_set else {}
+    compact = graph.compact()
+    summary = []
+    for entry in sorted(set(entries), key=graph.qualified_name):
+        reachable = compact.reachable([entry])
+        unsafe = [
+            node_id
+            for node_id in reachable
+            if graph.nodes[node_id]["extras"].get("unsafe")
+            or graph.nodes[node_id]["extras"].get("unsafe_blocks")
+        ]
+        summary.append(
+            {
+                "id": entry,
+                "functions": len(reachable),
+                "max_depth": heights[entry]["depth"],
+                "recursive": heights[entry]["recursive"],
+                "modules": sorted({graph.module_name(n) for n in reachable}),
+                "effects": sorted(effects.get(entry, {})),
+                "unsafe": sorted(unsafe, key=graph.qualified_name),
+            }
+        )
+    return summary
+
+
+def touches_rows(
+    graph: CallGraph, summary: List[Dict[str, Any]]
+) -> List[Dict[str, Any]]:
+    """表格的行（列见 TOUCHES_FIELDS）"""
+    return [
+        {
+            "function": graph.qualified_name(entry["id"]),
+            "file": graph.nodes[entry["id"]]["file"],
+            "line": graph.nodes[entry["id"]].get("start_line"),
+            "functions": entry["functions"],
+            "max_depth": entry["max_depth"],
+            "recursive": entry["recursive"],
+            "modules": entry["modules"],
+            "effects": entry["effects"],
+            "unsafe_functions": len(entry["unsafe"]),
+            "unsafe_blocks": sum(
+                graph.nodes[n]["extras"].get("unsafe_blocks") or 0
+                for n in entry["unsafe"]
+            ),
+        }
+        for entry in summary
+    ]
+
+
+def touches_to_csv(rows: List[Dict[str, Any]]) -> str:
+    """CSV（带表头），模块和副作用标签用分号分隔"""
+    output = io.StringIO()
+    writer = csv.DictWriter(output, fieldnames=TOUCHES_FIELDS, lineterminator="\n")
+    writer.writeheader()
+    for row in rows:
+        writer.writerow(
+            {
+                **row,
+                "modules": ";".join(row["modules"]),
+                "effects": ";".join(row["effects"]),
+            }
+        )
+    return output.getvalue()
+
+
+def touches_to_markdown(rows: List[Dict[str, Any]], effects: bool = True) -> str:
+    """Markdown 表格；effects 为 False 时省略副作用列（没有声明副作用标签）"""
+    header = ["函数", "模块", "unsafe", "最大调用深度", "可达函数"]
+    if effects:
+        header.insert(2, "副作用")
+    lines = ["| " + " | ".join(header) + " |", "|" + "---|" * len(header)]
+    for row in rows:
+        unsafe = (
+            f"{row['unsafe_functions']} 个函数（{row['unsafe_blocks']} 个 unsafe 块）"
+            if row["unsafe_functions"]
+            else "-"
+        )
+        depth = f"{row['max_depth']}+（递归）" if row["recursive"] else row["max_depth"]
+        cells = [
+            f"`{row['function']}`",
+            ", ".join(f"`{m}`" for m in row["modules"]),
+            unsafe,
+            str(depth),
+            str(row["functions"]),
+        ]
+        if effects:
+            cells.insert(2, ", ".join(row["effects"]) or "-")
+        cells = [cell.replace("|", "\\|") for cell in cells]
+        lines.append("| " + " | ".join(cells) + " |")
+    return "\n".join(lines) + "\n"
@@ -0,0 +1,119 @@
+"""
+trait 实现覆盖
+把项目中定义的 trait、它们的 impl 块（来自解析结果中的模块项，见
+RustParser.collect_module_items）和调用图结合起来：每个 trait 方法被哪些类型
+覆盖、哪些类型使用默认实现，以及方法在项目中被调用的次数，找出从未被覆盖的
+默认方法和从未被调用的 trait 方法
+"""
+
+from typing import Any, Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+# impl 中方法的状态：覆盖、使用默认实现、缺失（必需方法没有实现，通常是
+# 条件编译或解析不完整）
+IMPL_STATES = ("override", "default", "missing")
+
+
+def _method_nodes(graph: CallGraph) -> Dict[Tuple[str, str], List[str]]:
+    """{(trait 名, 方法名): [函数ID]}：impl 中的实现和 trait 中的默认实现"""
+    index: Dict[Tuple[str, str], List[str]] = {}
+    for node_id, node in graph.nodes.items():
+        extras = node.get("extras") or {}
+        trait = extras.get("trait") or (
+            extras.get("owner") if extras.get("in_trait") else None
+        )
+        if trait:
+            index.setdefault((trait, node["name"]), []).append(node_id)
+    return index
+
+
+def _call_count(graph: CallGraph, node_ids: List[str]) -> Tuple[int, int]:
+    """调用这些函数的 (调用点数量, 调用者数量)，调用者之间去重"""
+    sites, callers = 0, set()
+    for node_id in node_ids:
+        for caller_id in graph.predecessors(node_id):
+            sites += len(graph.call_sites(caller_id, node_id)) or 1
+            callers.add(caller_id)
+    return sites, len(callers)
+
+
+def trait_coverage(
+    graph: CallGraph,
+    items: Dict[str, List[Dict[str, Any]]],
+    trait: Optional[str] = None,
+) -> List[Dict[str, Any]]:
+    """
+    每个 trait 的实现覆盖情况
+
+    Args:
+        items: trait 定义和 impl 块（CallGraphDB.get_trait_items）
+        trait: 只统计这个 trait（按名称）
+
+    Returns:
+        [{"name", "file", "line", "visibility", "impls": [{"type", "file",
+        "line"}], "methods": [{"name", "default", "impls": {类型: IMPL_STATES
+        之一}, "calls", "callers", "never_overridden", "never_called"}]}]，
+        按名称和文件排序。impl 按 trait 名称对应（同名的 trait 共享 impl）；
+        calls 为调用任一实现或默认实现的调用点数量，never_overridden 为有默认
+        实现但没有任何 impl 覆盖的方法
+    """
+    impls_by_trait: Dict[str, List[Dict[str, Any]]] = {}
+    for impl in items.get("impls") or []:
+        impls_by_trait.setdefault(impl["trait"], []).append(impl)
+    method_nodes = _method_nodes(graph)
+
+    report = []
+    for definition in items.get("traits") or []:
+        name = definition["name"]
+        if trait and name != trait:
+            continue
+        impls = sorted(
+            impls_by_trait.get(name, []),
+            key=lambda i: (i["type"], i.get("file") or "", i.get("line") or 0),
+        )
+        methods = []
+        for method in definition.get("methods") or []:
+            states = {}
+            for impl in impls:
+                if method["name"] in impl["methods"]:
+                    state = "override"
+                else:
+                    state = "default" if method["default"] else "missing"
+                # 同一类型的多个 impl（不同的泛型参数）中有一个覆盖即算覆盖
+                if states.get(impl["type"]) != "override":
+                    states[impl["type"]] = state
+            calls, callers = _call_count(
+                graph, method_nodes.get((name, method["name"]), [])
+            )
+            methods.append(
+                {
+                    "name": method["name"],
+                    "default": method["default"],
+                    "impls": states,
+                    "calls": calls,
+                    "callers": callers,
+                    "never_overridden": method["default"]
+                    and "override" not in states.values(),
+                    "never_called": calls == 0,
+                }
+            )
+        report.append(
+            {
+                "name": name,
+                "file": definition.get("file"),
+                "line": definition.get("line"),
+                "visibility": definition.get("visibility"),
+                "impls": [
+                    {"type": i["type"], "file": i.get("file"), "line": i.get("line")}
+                    for i in impls
+                ],
+                "methods": methods,
+            }
+        )
+    report.sort(key=lambda t: (t["name"], t["file"] or ""))
+    return report
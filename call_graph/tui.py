@@ -0,0 +1,300 @@
+"""
+终端浏览器
+基于 curses 的交互式调用层次浏览：左侧为可搜索的函数列表，右侧为当前函数的
+调用者和被调用者，用键盘沿调用图上下跳转，不需要编辑器集成
+"""
+
+import unicodedata
+from typing import Dict, List, Optional, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .graph import CallGraph
+except ImportError:
+    from graph import CallGraph
+
+try:
+    import curses
+except ImportError:  # Windows 需要安装 windows-curses
+    curses = None
+
+# 窗格：函数列表、调用者、被调用者
+PANES = ["functions", "callers", "callees"]
+
+PANE_TITLES = {"functions": "函数", "callers": "调用者", "callees": "被调用者"}
+
+HELP_TEXT = (
+    "↑↓/jk 移动  Tab 切换窗格  Enter 跳转  ←/Backspace 返回  / 搜索  q 退出"
+)
+
+
+class Explorer:
+    """
+    浏览状态（与终端绘制无关）
+
+    - current: 当前函数；在函数列表中移动时跟随选中项，在调用者/被调用者
+      窗格中按 Enter 时跳转到选中的函数，并记录到历史中，可用 back 返回
+    - query: 函数列表的搜索词，按函数名或限定名做不区分大小写的子串匹配
+    """
+
+    def __init__(self, graph: CallGraph, query: str = ""):
+        self.graph = graph
+        self._all = sorted(
+            graph.nodes,
+            key=lambda n: (graph.qualified_name(n), graph.nodes[n].get("file") or ""),
+        )
+        self._search_text = {
+            node_id: f"{graph.nodes[node_id]['name']}\0"
+            f"{graph.qualified_name(node_id)}".lower()
+            for node_id in self._all
+        }
+        self.focus = "functions"
+        self.selected: Dict[str, int] = dict.fromkeys(PANES, 0)
+        self.history: List[str] = []
+        self.current: Optional[str] = None
+        self.matches: List[str] = []
+        self.set_query(query)
+
+    def set_query(self, query: str):
+        """更新搜索词，当前函数改为第一个匹配"""
+        self.query = query
+        needle = query.lower()
+        self.matches = [n for n in self._all if needle in self._search_text[n]]
+        self.selected["functions"] = 0
+        self._follow_list()
+
+    def _follow_list(self):
+        if self.matches:
+            self.current = self.matches[self.selected["functions"]]
+        else:
+            self.current = None
+        self.selected["callers"] = self.selected["callees"] = 0
+
+    def _neighbors(self, pane: str) -> List[Tuple[str, int]]:
+        """(函数ID, 调用点数量)，按限定名排序"""
+        if self.current is None:
+            return []
+        if pane == "callers":
+            ids = self.graph.predecessors(self.current)
+            counts = [len(self.graph.call_sites(n, self.current)) for n in ids]
+        else:
+            ids = self.graph.successors(self.current)
+            counts = [len(self.graph.call_sites(self.current, n)) for n in ids]
+        entries = list(zip(ids, counts))
+        entries.sort(key=lambda e: self.graph.qualified_name(e[0]))
+        return entries
+
+    def items(self, pane: str) -> List[str]:
+        """窗格中的函数 ID 列表"""
+        if pane == "functions":
+            return self.matches
+        return [node_id for node_id, _ in self._neighbors(pane)]
+
+    def lines(self, pane: str) -> List[str]:
+        """窗格中每一项的显示文本"""
+        graph = self.graph
+        if pane == "functions":
+            return [graph.qualified_name(n) for n in self.matches]
+        lines = []
+        for node_id, count in self._neighbors(pane):
+            text = graph.qualified_name(node_id)
+            if node_id == self.current:
+                text += "（递归）"
+            if count > 1:
+                text += f" ×{count}"
+            lines.append(text)
+        return lines
+
+    def move(self, delta: int):
+        """在当前窗格中移动选中项"""
+        count = len(self.items(self.focus))
+        if not count:
+            return
+        index = max(0, min(self.selected[self.focus] + delta, count - 1))
+        self.selected[self.focus] = index
+        if self.focus == "functions":
+            self._follow_list()
+
+    def cycle_focus(self, step: int = 1):
+        self.focus = PANES[(PANES.index(self.focus) + step) % len(PANES)]
+
+    def jump(self):
+        """
+        Enter：在函数列表中进入被调用者窗格；在调用者/被调用者窗格中跳转到
+        选中的函数
+        """
+        if self.focus == "functions":
+            if self.current is not None:
+                self.focus = "callees"
+            return
+        items = self.items(self.focus)
+        if not items:
+            return
+        self.goto(items[self.selected[self.focus]])
+
+    def goto(self, node_id: str):
+        """跳转到函数，记录历史；函数在搜索结果中时同步列表的选中项"""
+        if self.current is not None and node_id != self.current:
+            self.history.append(self.current)
+        self._show(node_id)
+
+    def back(self) -> bool:
+        """返回上一个函数，没有历史时返回 False"""
+        if not self.history:
+            return False
+        self._show(self.history.pop())
+        return True
+
+    def _show(self, node_id: str):
+        self.current = node_id
+        if node_id in self.matches:
+            self.selected["functions"] = self.matches.index(node_id)
+        self.selected["callers"] = self.selected["callees"] = 0
+
+    def status(self) -> str:
+        """当前函数的位置和调用者/被调用者数量"""
+        if self.current is None:
+            return "没有匹配的函数"
+        node = self.graph.nodes[self.current]
+        return (
+            f"{self.graph.qualified_name(self.current)}  "
+            f"{node.get('file')}:{node.get('start_line')}  "
+            f"调用者 {len(self.graph.predecessors(self.current))}  "
+            f"被调用者 {len(self.graph.successors(self.current))}"
+        )
+
+
+def _cells(char: str) -> int:
+    """字符占用的终端列数（中文等全角字符占两列）"""
+    return 2 if unicodedata.east_asian_width(char) in ("W", "F") else 1
+
+
+def _clip(text: str, width: int) -> str:
+    """按显示宽度截断并补齐到 width 列"""
+    result = []
+    used = 0
+    for char in text:
+        cells = _cells(char)
+        if used + cells > width:
+            break
+        result.append(char)
+        used += cells
+    return "".join(result) + " " * (width - used)
+
+
+def _put(window, row: int, column: int, text: str, width: int, attr: int = 0):
+    """写入一行；写到窗口右下角时 curses 会报错，忽略即可"""
+    try:
+        window.addstr(row, column, _clip(text, width), attr)
+    except curses.error:
+        pass
+
+
+def _draw_pane(window, explorer: Explorer, pane: str, top, left, height, width):
+    """绘制一个带标题的窗格，保证选中项可见"""
+    lines = explorer.lines(pane)
+    title = f" {PANE_TITLES[pane]} ({len(lines)}) "
+    if pane == "functions" and explorer.query:
+        title += f"/{explorer.query} "
+    attr = curses.A_BOLD | (curses.A_REVERSE if explorer.focus == pane else 0)
+    _put(window, top, left, title, width, attr)
+    rows = height - 1
+    if rows <= 0:
+        return
+    selected = explorer.selected[pane] if lines else -1
+    first = max(0, selected - rows + 1)
+    for row, index in enumerate(range(first, min(first + rows, len(lines)))):
+        style = curses.A_REVERSE if index == selected and explorer.focus == pane else 0
+        if index == selected and explorer.focus != pane:
+            style = curses.A_UNDERLINE
+        _put(window, top + 1 + row, left, lines[index], width, style)
+
+
+def _draw(window, explorer: Explorer, searching: bool):
+    window.erase()
+    height, width = window.getmaxyx()
+    if height < 6 or width < 20:
+        _put(window, 0, 0, "终端窗口太小", width)
+        window.refresh()
+        return
+    body = height - 2
+    list_width = width // 2
+    right = list_width + 1
+    right_width = width - right
+    upper = body // 2
+
+    _draw_pane(window, explorer, "functions", 0, 0, body, list_width)
+    for row in range(body):
+        window.addch(row, list_width, curses.ACS_VLINE)
+    _draw_pane(window, explorer, "callers", 0, right, upper, right_width)
+    _draw_pane(window, explorer, "callees", upper, right, body - upper, right_width)
+
+    status = f"搜索: {explorer.query}" if searching else explorer.status()
+    _put(window, height - 2, 0, status, width, curses.A_REVERSE)
+    _put(window, height - 1, 0, HELP_TEXT, width - 1)
+    try:
+        curses.curs_set(1 if searching else 0)
+    except curses.error:
+        pass  # 终端不支持隐藏光标
+    if searching:
+        cursor = sum(_cells(char) for char in status)
+        window.move(height - 2, min(cursor, width - 1))
+    window.refresh()
+
+
+def _main_loop(window, explorer: Explorer):
+    searching = False
+    while True:
+        _draw(window, explorer, searching)
+        try:
+            key = window.get_wch()
+        except curses.error:
+            continue
+        if searching:
+            if key in ("\n", "\r", "\x1b", curses.KEY_ENTER):
+                searching = False
+            elif key in ("\b", "\x7f", curses.KEY_BACKSPACE):
+                explorer.set_query(explorer.query[:-1])
+            elif isinstance(key, str) and key.isprintable():
+                explorer.set_query(explorer.query + key)
+            continue
+        if key in ("q", "Q"):
+            return
+        if key == "/":
+            searching = True
+            explorer.focus = "functions"
+        elif key in ("j", curses.KEY_DOWN):
+            explorer.move(1)
+        elif key in ("k", curses.KEY_UP):
+            explorer.move(-1)
+        elif key == curses.KEY_NPAGE:
+            explorer.move(max(window.getmaxyx()[0] - 4, 1))
+        elif key == curses.KEY_PPAGE:
+            explorer.move(-max(window.getmaxyx()[0] - 4, 1))
+        elif key == "\t":
+            explorer.cycle_focus()
+        elif key == curses.KEY_BTAB:
+            explorer.cycle_focus(-1)
+        elif key in ("\n", "\r", "l", curses.KEY_ENTER, curses.KEY_RIGHT):
+            explorer.jump()
+        elif key in ("h", "\b", "\x7f", curses.KEY_BACKSPACE, curses.KEY_LEFT):
+            explorer.back()
+
+
+def run_tui(graph: CallGraph, query: str = "", start: Optional[str] = None):
+    """
+    运行终端浏览器，直到按 q 退出
+
+    Args:
+        query: 函数列表的初始搜索词
+        start: 初始的当前函数 ID
+    """
+    if curses is None:
+        raise ValueError(
+            "当前 Python 不支持 curses（Windows 上需要 pip install windows-curses）"
+        )
+    explorer = Explorer(graph, query)
+    if start is not None:
+        explorer.goto(start)
+        explorer.history.clear()
+    curses.wrapper(_main_loop, explorer)
@@ -0,0 +1,197 @@
+"""
+监视模式
+等待项目源文件变化：安装了 watchdog 时使用文件系统通知，否则轮询修改时间
+"""
+
+import os
+import queue
+import time
+from pathlib import Path
+from typing import Dict, List, Optional, Set, Tuple
+
+# 支持相对导入和直接运行
+try:
+    from .ignore import IgnoreRules
+    from .parsers import LANGUAGE_CONFIG
+except ImportError:
+    from ignore import IgnoreRules
+    from parsers import LANGUAGE_CONFIG
+
+try:
+    from watchdog.events import FileSystemEventHandler
+    from watchdog.observers import Observer
+except ImportError:
+    FileSystemEventHandler = object
+    Observer = None
+
+# 导出文件扩展名 -> 导出格式
+EXPORT_EXTENSIONS = {
+    ".dot": "dot",
+    ".gv": "dot",
+    ".mmd": "mermaid",
+    ".json": "json",
+    ".html": "html",
+    ".graphml": "graphml",
+    ".gexf": "gexf",
+    ".puml": "plantuml",
+    ".d2": "d2",
+    ".cypher": "cypher",
+    ".cql": "cypher",
+    ".plantuml": "plantuml",
+    ".svg": "svg",
+    ".png": "png",
+    ".sqlite": "sqlite",
+    ".db": "sqlite",
+}
+
+
+def export_format_for(path: str) -> Optional[str]:
+    """根据输出文件扩展名推断导出格式"""
+    return EXPORT_EXTENSIONS.get(Path(path).suffix.lower())
+
+
+def _supported_extensions() -> Tuple[str, ...]:
+    extensions = set()
+    for config in LANGUAGE_CONFIG.values():
+        extensions.update(config["extensions"])
+    return tuple(extensions)
+
+
+class _EventHandler(FileSystemEventHandler):
+    """把源文件的变化放入队列"""
+
+    def __init__(self, watcher: "ChangeWatcher"):
+        super().__init__()
+        self.watcher = watcher
+
+    def on_any_event(self, event):
+        if event.is_directory:
+            return
+        for path in (event.src_path, getattr(event, "dest_path", None)):
+            if path and self.watcher.is_source(path):
+                self.watcher.events.put(os.fsdecode(path))
+
+
+class ChangeWatcher:
+    """
+    等待项目中源文件的新增、修改和删除
+
+    与分析器使用相同的规则决定哪些文件属于项目：支持的扩展名，
+    排除 exclude_dirs 中的目录、以 . 开头的目录和忽略文件中的路径
+    """
+
+    def __init__(
+        self,
+        project_path: str,
+        exclude_dirs: List[str],
+        interval: float = 0.5,
+        debounce: float = 0.2,
+        ignore_files: bool = True,
+    ):
+        self.project_path = Path(project_path).resolve()
+        self.exclude_dirs = set(exclude_dirs)
+        self.ignore = IgnoreRules(self.project_path) if ignore_files else None
+        self.interval = interval
+        self.debounce = debounce
+        self.extensions = _supported_extensions()
+        self.events: "queue.Queue[str]" = queue.Queue()
+        self.observer = None
+        self._snapshot: Dict[str, int] = {}
+
+        if Observer is not None:
+            self.observer = Observer()
+            self.observer.schedule(
+                _EventHandler(self), str(self.project_path), recursive=True
+            )
+            self.observer.start()
+        else:
+            self._snapshot = self._scan()
+
+    @property
+    def mode(self) -> str:
+        return "文件系统通知" if self.observer else f"轮询（每 {self.interval} 秒）"
+
+    def is_source(self, path) -> bool:
+        """文件是否属于项目源文件"""
+        path = os.fsdecode(path)
+        if not path.endswith(self.extensions):
+            return False
+        try:
+            parts = Path(path).resolve().relative_to(self.project_path).parts
+        except ValueError:
+            return False
+        for part in parts[:-1]:
+            if part in self.exclude_dirs or part.startswith("."):
+                return False
+        return not (self.ignore and self.ignore.excludes(path))
+
+    def _ignored(self, path: str, is_dir: bool) -> bool:
+        return self.ignore is not None and self.ignore.is_ignored(path, is_dir)
+
+    def _scan(self) -> Dict[str, int]:
+        """{源文件: 修改时间}"""
+        snapshot = {}
+        for root, dirs, files in os.walk(self.project_path):
+            dirs[:] = [
+                d
+                for d in dirs
+                if d not in self.exclude_dirs
+                and not d.startswith(".")
+                and not self._ignored(os.path.join(root, d), True)
+            ]
+            for file in files:
+                if file.endswith(self.extensions):
+                    path = os.path.join(root, file)
+                    if self._ignored(path, False):
+                        continue
+                    try:
+                        snapshot[path] = os.stat(path).st_mtime_ns
+                    except OSError:
+                        continue
+        return snapshot
+
+    def _poll(self) -> Set[str]:
+        snapshot = self._scan()
+        changed = {
+            path
+            for path in set(snapshot) | set(self._snapshot)
+            if snapshot.get(path) != self._snapshot.get(path)
+        }
+        self._snapshot = snapshot
+        return changed
+
+    def wait(self) -> List[str]:
+        """
+        阻塞直到有源文件变化，返回变化的文件列表
+
+        收到第一个变化后再等待 debounce 秒，把编辑器一次保存产生的多个事件合并
+        """
+        if not self.observer:
+            while True:
+                time.sleep(self.interval)
+                changed = self._poll()
+                if changed:
+                    return sorted(changed)
+
+        # 带超时地等待，保证 Ctrl+C 可以及时中断
+        while True:
+            try:
+                changed = {self.events.get(timeout=self.interval)}
+                break
+            except queue.Empty:
+                continue
+        deadline = time.monotonic() + self.debounce
+        while True:
+            remaining = deadline - time.monotonic()
+            if remaining <= 0:
+                break
+            try:
+                changed.add(self.events.get(timeout=remaining))
+            except queue.Empty:
+                break
+        return sorted(changed)
+
+    def close(self):
+        if self.observer:
+            self.observer.stop()
+            self.observer.join()
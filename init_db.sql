@@ -27,11 +27,36 @@ CREATE TABLE IF NOT EXISTS call_relations(
     callee_file TEXT,
     call_site_line INTEGER,
     call_site_column INTEGER,
+    -- 调用点的源码片段（调用表达式的第一行）
+    call_site_snippet TEXT,
     language TEXT,
+    -- 边类型：static（静态调用）/ dynamic（经由 trait 对象或泛型约束分发）
+    edge_kind TEXT DEFAULT 'static',
+    -- 置信度：certain（确定）/ probable（可能的目标之一）/ heuristic（按名称猜测）
+    confidence TEXT DEFAULT 'certain',
+    -- 调用点实例化泛型函数时使用的具体类型实参（如 "User"），逗号分隔
+    type_args TEXT,
+    -- 项目外的被调用函数按 use 声明展开后的路径（如 std::fs::read）
+    callee_path TEXT,
+    -- 调用点在调用者内部的条件编译条件（如 Rust 的 #[cfg(unix)] 语句），
+    -- 调用者本身的条件记录在符号的 extras 中
+    cfg TEXT,
+    -- 调用点对返回的 Result / Option 的处理方式：propagate（? 或直接返回）/
+    -- match / unwrap / convert（ok()、unwrap_or() 等）/ ignore，无法跟踪时为 NULL
+    error_handling TEXT,
     FOREIGN KEY (caller_id) REFERENCES symbols(id),
     FOREIGN KEY (callee_id) REFERENCES symbols(id)
 );
 
+-- 文件表：记录已分析文件的内容哈希，用于增量分析
+CREATE TABLE IF NOT EXISTS files(
+    path TEXT PRIMARY KEY,
+    hash TEXT,
+    language TEXT,
+    -- trait 定义和 impl 块（JSON: {"traits": [...], "impls": [...]}）
+    traits_json TEXT
+);
+
 -- 为调用关系创建索引，加速查询
 CREATE INDEX IF NOT EXISTS idx_caller ON call_relations(caller_id);
 CREATE INDEX IF NOT EXISTS idx_callee ON call_relations(callee_id);